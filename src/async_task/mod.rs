@@ -0,0 +1,46 @@
+// ============================================================================
+// File: packages/cylo/src/async_task/mod.rs
+// ----------------------------------------------------------------------------
+// AsyncTask - simple wrapper around tokio for backend compatibility, and
+// bounded event streams for the streaming and watch APIs.
+// ============================================================================
+
+mod stream;
+
+pub use stream::{
+    DEFAULT_STREAM_CAPACITY, EventSender, EventStream, RecvError, SendError, StreamStats,
+    TryRecvError, event_stream,
+};
+
+/// AsyncTask is a type alias for tokio::task::JoinHandle
+pub type AsyncTask<T> = tokio::task::JoinHandle<T>;
+
+/// Simple AsyncTaskBuilder for fluent construction
+pub struct AsyncTaskBuilder<F> {
+    future: F,
+}
+
+impl<F, T> AsyncTaskBuilder<F>
+where
+    F: std::future::Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    /// Create a new AsyncTaskBuilder
+    pub fn new(future: F) -> Self {
+        Self { future }
+    }
+
+    /// Spawn the task and return the AsyncTask handle
+    pub fn spawn(self) -> AsyncTask<T> {
+        tokio::spawn(self.future)
+    }
+}
+
+/// Convenience function to spawn an async task
+pub fn spawn_async<F, T>(future: F) -> AsyncTask<T>
+where
+    F: std::future::Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::spawn(future)
+}
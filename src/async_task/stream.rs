@@ -0,0 +1,361 @@
+// ============================================================================
+// File: packages/cylo/src/async_task/stream.rs
+// ----------------------------------------------------------------------------
+// Bounded, backpressure-aware event streams.
+//
+// Execution events, log lines and resource samples are produced by backends
+// at their own pace and consumed by callers at theirs. Every stream holds at
+// most `capacity` undelivered events, so a slow or stalled consumer costs a
+// fixed amount of memory instead of an ever-growing queue. Producers choose
+// per event: `send` never waits and drops the oldest buffered event when
+// the buffer is full, which suits progress and samples where the latest
+// value matters most; `send_wait` waits for room, for events that must not
+// be lost. Dropped events are counted, and the consumer learns how many it
+// missed through `RecvError::Lagged` before it receives the next event.
+// ============================================================================
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::Notify;
+
+/// Events a stream buffers when created with `event_stream(None)`
+pub const DEFAULT_STREAM_CAPACITY: usize = 1024;
+
+/// Counters of one stream since it was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamStats {
+    /// Most events buffered at once
+    pub capacity: usize,
+    /// Events buffered now, not yet received
+    pub buffered: usize,
+    /// Events accepted from producers
+    pub sent: u64,
+    /// Events handed to the consumer
+    pub delivered: u64,
+    /// Events dropped because the consumer fell behind
+    pub dropped: u64,
+}
+
+/// Why `EventStream::recv` returned no event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum RecvError {
+    /// This many events were dropped since the last receive; the next
+    /// receive returns the oldest event still buffered
+    #[error("Consumer lagged behind; {0} events were dropped")]
+    Lagged(u64),
+
+    /// Every sender is gone and the buffer is drained
+    #[error("Event stream closed")]
+    Closed,
+}
+
+/// Why `EventStream::try_recv` returned no event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum TryRecvError {
+    /// No event is buffered right now
+    #[error("No event is buffered")]
+    Empty,
+
+    /// This many events were dropped since the last receive
+    #[error("Consumer lagged behind; {0} events were dropped")]
+    Lagged(u64),
+
+    /// Every sender is gone and the buffer is drained
+    #[error("Event stream closed")]
+    Closed,
+}
+
+/// The consumer is gone; carries back the event that was not sent
+#[derive(Clone, PartialEq, Eq, Error)]
+#[error("Event stream receiver was dropped")]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SendError(..)")
+    }
+}
+
+#[derive(Debug)]
+struct State<T> {
+    buffer: VecDeque<T>,
+    capacity: usize,
+    /// Dropped since the consumer last heard about it
+    lagged: u64,
+    sent: u64,
+    delivered: u64,
+    dropped: u64,
+    senders: usize,
+    receiver_alive: bool,
+}
+
+impl<T> State<T> {
+    fn stats(&self) -> StreamStats {
+        StreamStats {
+            capacity: self.capacity,
+            buffered: self.buffer.len(),
+            sent: self.sent,
+            delivered: self.delivered,
+            dropped: self.dropped,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    /// Wakes the consumer when an event arrives or the last sender leaves
+    event_ready: Notify,
+    /// Wakes producers in `send_wait` when room frees up or the consumer
+    /// leaves
+    space_ready: Notify,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Create a bounded event stream
+///
+/// # Arguments
+/// * `capacity` - Most events buffered at once; `None` uses
+///   `DEFAULT_STREAM_CAPACITY`, and zero is raised to one
+///
+/// # Returns
+/// The producing half, which can be cloned, and the consuming half
+pub fn event_stream<T>(capacity: Option<usize>) -> (EventSender<T>, EventStream<T>) {
+    let capacity = capacity.unwrap_or(DEFAULT_STREAM_CAPACITY).max(1);
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            buffer: VecDeque::with_capacity(capacity.min(DEFAULT_STREAM_CAPACITY)),
+            capacity,
+            lagged: 0,
+            sent: 0,
+            delivered: 0,
+            dropped: 0,
+            senders: 1,
+            receiver_alive: true,
+        }),
+        event_ready: Notify::new(),
+        space_ready: Notify::new(),
+    });
+    (
+        EventSender {
+            shared: Arc::clone(&shared),
+        },
+        EventStream { shared },
+    )
+}
+
+/// Producing half of an event stream
+pub struct EventSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> EventSender<T> {
+    /// Send an event without waiting
+    ///
+    /// When the buffer is full the oldest buffered event is dropped to
+    /// make room, and the consumer is told it lagged.
+    ///
+    /// # Returns
+    /// Ok(()) once buffered, or the event back if the consumer is gone
+    pub fn send(&self, event: T) -> Result<(), SendError<T>> {
+        {
+            let mut state = self.shared.lock();
+            if !state.receiver_alive {
+                return Err(SendError(event));
+            }
+            if state.buffer.len() >= state.capacity {
+                state.buffer.pop_front();
+                state.dropped += 1;
+                state.lagged += 1;
+            }
+            state.buffer.push_back(event);
+            state.sent += 1;
+        }
+        self.shared.event_ready.notify_one();
+        Ok(())
+    }
+
+    /// Send an event, waiting for room when the buffer is full
+    ///
+    /// Nothing is dropped; a slow consumer slows this producer down instead.
+    ///
+    /// # Returns
+    /// Ok(()) once buffered, or the event back if the consumer is gone
+    pub async fn send_wait(&self, event: T) -> Result<(), SendError<T>> {
+        loop {
+            let space_ready = self.shared.space_ready.notified();
+            tokio::pin!(space_ready);
+            // Registered before checking, so a receive in between wakes us
+            space_ready.as_mut().enable();
+            {
+                let mut state = self.shared.lock();
+                if !state.receiver_alive {
+                    return Err(SendError(event));
+                }
+                if state.buffer.len() < state.capacity {
+                    state.buffer.push_back(event);
+                    state.sent += 1;
+                    drop(state);
+                    self.shared.event_ready.notify_one();
+                    return Ok(());
+                }
+            }
+            space_ready.await;
+        }
+    }
+
+    /// Whether the consumer is gone, so further events would be discarded
+    pub fn is_closed(&self) -> bool {
+        !self.shared.lock().receiver_alive
+    }
+
+    /// Counters of this stream
+    pub fn stats(&self) -> StreamStats {
+        self.shared.lock().stats()
+    }
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for EventSender<T> {
+    fn drop(&mut self) {
+        let last = {
+            let mut state = self.shared.lock();
+            state.senders -= 1;
+            state.senders == 0
+        };
+        if last {
+            self.shared.event_ready.notify_one();
+        }
+    }
+}
+
+impl<T> fmt::Debug for EventSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventSender").field("stats", &self.stats()).finish()
+    }
+}
+
+/// Consuming half of an event stream
+pub struct EventStream<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> EventStream<T> {
+    /// Receive the next event, waiting until one arrives
+    ///
+    /// # Returns
+    /// The oldest buffered event; `RecvError::Lagged` once after events
+    /// were dropped; `RecvError::Closed` when every sender is gone and the
+    /// buffer is drained
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(event) => return Ok(event),
+                Err(TryRecvError::Lagged(missed)) => return Err(RecvError::Lagged(missed)),
+                Err(TryRecvError::Closed) => return Err(RecvError::Closed),
+                Err(TryRecvError::Empty) => self.shared.event_ready.notified().await,
+            }
+        }
+    }
+
+    /// Receive the next event if one is buffered, without waiting
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let event = {
+            let mut state = self.shared.lock();
+            if state.lagged > 0 {
+                return Err(TryRecvError::Lagged(std::mem::take(&mut state.lagged)));
+            }
+            match state.buffer.pop_front() {
+                Some(event) => {
+                    state.delivered += 1;
+                    event
+                }
+                None if state.senders == 0 => return Err(TryRecvError::Closed),
+                None => return Err(TryRecvError::Empty),
+            }
+        };
+        self.shared.space_ready.notify_waiters();
+        Ok(event)
+    }
+
+    /// Counters of this stream
+    pub fn stats(&self) -> StreamStats {
+        self.shared.lock().stats()
+    }
+}
+
+impl<T> Drop for EventStream<T> {
+    fn drop(&mut self) {
+        {
+            let mut state = self.shared.lock();
+            state.receiver_alive = false;
+            state.buffer.clear();
+        }
+        self.shared.space_ready.notify_waiters();
+    }
+}
+
+impl<T> fmt::Debug for EventStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStream").field("stats", &self.stats()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn slow_consumers_lag_instead_of_growing_the_buffer() {
+        let (tx, mut rx) = event_stream(Some(2));
+        for n in 0..5 {
+            tx.send(n).unwrap();
+        }
+        assert_eq!(rx.stats().buffered, 2);
+        assert_eq!(rx.recv().await, Err(RecvError::Lagged(3)));
+        assert_eq!(rx.recv().await, Ok(3));
+        assert_eq!(rx.try_recv(), Ok(4));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        // Waiting producers are released as the consumer catches up
+        tx.send(5).unwrap();
+        tx.send(6).unwrap();
+        let waiting = tx.clone();
+        let producer = tokio::spawn(async move { waiting.send_wait(7).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!producer.is_finished());
+        assert_eq!(rx.recv().await, Ok(5));
+        producer.await.unwrap().unwrap();
+        assert_eq!(rx.recv().await, Ok(6));
+        assert_eq!(rx.recv().await, Ok(7));
+
+        let stats = tx.stats();
+        assert_eq!((stats.sent, stats.delivered, stats.dropped), (8, 5, 3));
+
+        drop(tx);
+        assert_eq!(rx.recv().await, Err(RecvError::Closed));
+
+        let (tx, rx) = event_stream::<u8>(None);
+        drop(rx);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(1).unwrap_err().0, 1);
+    }
+}
@@ -0,0 +1,603 @@
+// ============================================================================
+// File: packages/cylo/src/backend_conformance.rs
+// ----------------------------------------------------------------------------
+// Conformance suite for ExecutionBackend implementations.
+//
+// The checks here hold for any backend, built-in or third-party: timeouts
+// terminate the program and are reported as such, memory limits are
+// enforced, unicode and binary output survive the trip back, environment
+// variables reach the program unchanged and every execution starts from a
+// fresh workspace that is gone once it finishes. Environment handling is
+// checked property-style, with values drawn from a seeded generator so a
+// failure reproduces from the seed in the report. A backend's own test
+// suite runs it as:
+//
+//     let report = backend_conformance::run(&backend, &ConformanceConfig::default()).await;
+//     assert!(report.is_conformant(), "{}", report.summary());
+// ============================================================================
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{ExecutionBackend, ExecutionRequest, ExecutionResult, ResourceLimits};
+
+/// Environment variable the env checks pass their values through
+pub const ENV_PROBE_VAR: &str = "CYLO_CONFORMANCE_VALUE";
+
+/// Text the unicode check expects back, covering several scripts, a
+/// combining mark and characters outside the basic multilingual plane
+pub const UNICODE_SAMPLE: &str = "Grüße, 世界! Здравствуй مرحبا e\u{301} 🚀";
+
+/// File the cleanup check leaves behind in its first execution's workspace
+const MARKER_FILE: &str = "cylo_conformance_marker";
+
+/// Memory the limit check allocates, well above the limit it sets
+const ALLOCATION_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Memory limit set by the limit check
+const MEMORY_LIMIT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Time a backend may take beyond a request's timeout to report it,
+/// sandbox provisioning and teardown included
+const TIMEOUT_SLACK: Duration = Duration::from_secs(30);
+
+/// Programs the checks run, in one language
+#[derive(Debug, Clone, Copy)]
+struct Probes {
+    language: &'static str,
+    sleep: &'static str,
+    allocate: &'static str,
+    unicode: &'static str,
+    binary: &'static str,
+    echo_env: &'static str,
+    write_marker: &'static str,
+    find_marker: &'static str,
+}
+
+const PROBES: &[Probes] = &[
+    Probes {
+        language: "python",
+        sleep: "import time\ntime.sleep(3600)\n",
+        allocate: "data = bytearray(256 * 1024 * 1024)\n\
+                   for i in range(0, len(data), 4096):\n    data[i] = 1\nprint(len(data))\n",
+        unicode: "print('Grüße, 世界! Здравствуй مرحبا e\u{301} 🚀')\n",
+        binary: "import sys\nsys.stdout.buffer.write(b'start\\x00\\xff\\xfe\\x80end\\n')\n",
+        echo_env: "import os, sys\n\
+                   sys.stdout.write(os.environ.get('CYLO_CONFORMANCE_VALUE', ''))\n",
+        write_marker: "import os\nopen('cylo_conformance_marker', 'w').write('x')\n\
+                       print(os.getcwd())\n",
+        find_marker: "import os\nprint('present' if os.path.exists('cylo_conformance_marker') \
+                      else 'absent')\n",
+    },
+    Probes {
+        language: "bash",
+        sleep: "sleep 3600\n",
+        allocate: "data=$(head -c 268435456 /dev/zero | tr '\\0' a)\necho ${#data}\n",
+        unicode: "printf '%s\\n' 'Grüße, 世界! Здравствуй مرحبا e\u{301} 🚀'\n",
+        binary: "printf 'start\\000\\377\\376\\200end\\n'\n",
+        echo_env: "printf '%s' \"$CYLO_CONFORMANCE_VALUE\"\n",
+        write_marker: "echo x > cylo_conformance_marker && pwd\n",
+        find_marker: "if [ -e cylo_conformance_marker ]; then echo present; else echo absent; fi\n",
+    },
+];
+
+/// One property of the `ExecutionBackend` contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Check {
+    /// A program outliving its timeout is terminated and reported as
+    /// timed out, soon after the timeout
+    Timeout,
+    /// A program allocating past its memory limit does not succeed
+    MemoryLimit,
+    /// Non-ASCII output comes back exactly as written
+    UnicodeOutput,
+    /// Output that is not valid UTF-8 neither fails the execution nor
+    /// loses the valid text around it
+    BinaryOutput,
+    /// Environment variable values reach the program unchanged
+    EnvVars,
+    /// An execution cannot see files left by the previous one, and a host
+    /// workspace is removed once its execution finishes
+    WorkspaceCleanup,
+}
+
+impl Check {
+    /// Every check, in the order `run` performs them
+    pub const ALL: [Check; 6] = [
+        Check::Timeout,
+        Check::MemoryLimit,
+        Check::UnicodeOutput,
+        Check::BinaryOutput,
+        Check::EnvVars,
+        Check::WorkspaceCleanup,
+    ];
+
+    /// Short name of the check
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::Timeout => "timeout",
+            Check::MemoryLimit => "memory_limit",
+            Check::UnicodeOutput => "unicode_output",
+            Check::BinaryOutput => "binary_output",
+            Check::EnvVars => "env_vars",
+            Check::WorkspaceCleanup => "workspace_cleanup",
+        }
+    }
+}
+
+/// How a check ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckStatus {
+    /// The backend meets the property
+    Passed,
+    /// The backend violates the property
+    Failed,
+    /// The check could not run, e.g. no probe language is supported
+    Skipped,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckResult {
+    /// Check performed
+    pub check: Check,
+    /// How it ended
+    pub status: CheckStatus,
+    /// What was observed, for failed and skipped checks
+    pub details: Option<String>,
+    /// How long the check took
+    pub duration: Duration,
+}
+
+/// Result of running the suite against one backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformanceReport {
+    /// Backend type, as reported by the backend
+    pub backend: String,
+    /// Language the probe programs were written in, if any was supported
+    pub language: Option<String>,
+    /// Seed the env check drew its values from
+    pub seed: u64,
+    /// Outcome per check, in the order performed
+    pub checks: Vec<CheckResult>,
+}
+
+impl ConformanceReport {
+    /// Whether no check failed
+    ///
+    /// Skipped checks do not count against a backend, but a report in
+    /// which every check was skipped proves nothing and is not conformant.
+    pub fn is_conformant(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Passed)
+            && self.failures().is_empty()
+    }
+
+    /// Checks that failed
+    pub fn failures(&self) -> Vec<&CheckResult> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Failed)
+            .collect()
+    }
+
+    /// One line per check, for assertion messages
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} ({}, seed {}):",
+            self.backend,
+            self.language.as_deref().unwrap_or("no probe language"),
+            self.seed
+        );
+        for check in &self.checks {
+            summary.push_str(&format!("\n  {:<18} {:?}", check.check.name(), check.status));
+            if let Some(details) = &check.details {
+                summary.push_str(&format!(": {details}"));
+            }
+        }
+        summary
+    }
+}
+
+/// Which checks to run, and how
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceConfig {
+    /// Language of the probe programs; the first of python and bash the
+    /// backend supports when unset
+    pub language: Option<String>,
+    /// Checks to perform
+    pub checks: Vec<Check>,
+    /// Random values the env check passes through
+    pub env_cases: u32,
+    /// Seed of the env check's value generator
+    pub seed: u64,
+    /// Timeout the timeout check sets
+    pub timeout: Duration,
+}
+
+impl Default for ConformanceConfig {
+    fn default() -> Self {
+        Self {
+            language: None,
+            checks: Check::ALL.to_vec(),
+            env_cases: 16,
+            seed: 0x00c7_10c0_5eed,
+            timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+impl ConformanceConfig {
+    /// Write the probe programs in this language
+    pub fn with_language<L: Into<String>>(mut self, language: L) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Perform only these checks
+    pub fn with_checks<I: IntoIterator<Item = Check>>(mut self, checks: I) -> Self {
+        self.checks = checks.into_iter().collect();
+        self
+    }
+
+    /// Pass this many random values through the environment
+    pub fn with_env_cases(mut self, cases: u32) -> Self {
+        self.env_cases = cases;
+        self
+    }
+
+    /// Draw the env check's values from this seed, e.g. to reproduce a
+    /// failure
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Run the suite against a backend
+///
+/// Checks run one after another and leave nothing running behind, but they
+/// do execute code, so the backend must be usable for real executions.
+///
+/// # Arguments
+/// * `backend` - Backend under test
+/// * `config` - Checks to perform
+///
+/// # Returns
+/// Outcome per check
+pub async fn run<B: ExecutionBackend + ?Sized>(
+    backend: &B,
+    config: &ConformanceConfig,
+) -> ConformanceReport {
+    let probes = PROBES.iter().find(|probes| match &config.language {
+        Some(language) => probes.language == language.as_str(),
+        None => backend.supports_language(probes.language),
+    });
+
+    let mut checks = Vec::with_capacity(config.checks.len());
+    for &check in &config.checks {
+        let started = Instant::now();
+        let (status, details) = match probes {
+            Some(probes) => match perform(backend, check, probes, config).await {
+                Ok(()) => (CheckStatus::Passed, None),
+                Err(details) => (CheckStatus::Failed, Some(details)),
+            },
+            None => (
+                CheckStatus::Skipped,
+                Some(match &config.language {
+                    Some(language) => format!("no probe programs in '{language}'"),
+                    None => "backend supports neither python nor bash".to_string(),
+                }),
+            ),
+        };
+        checks.push(CheckResult {
+            check,
+            status,
+            details,
+            duration: started.elapsed(),
+        });
+    }
+
+    ConformanceReport {
+        backend: backend.backend_type().to_string(),
+        language: probes.map(|probes| probes.language.to_string()),
+        seed: config.seed,
+        checks,
+    }
+}
+
+/// Perform one check
+///
+/// # Returns
+/// Ok(()) if it passed, or what was observed
+async fn perform<B: ExecutionBackend + ?Sized>(
+    backend: &B,
+    check: Check,
+    probes: &Probes,
+    config: &ConformanceConfig,
+) -> Result<(), String> {
+    let language = probes.language;
+    match check {
+        Check::Timeout => {
+            let started = Instant::now();
+            let request =
+                ExecutionRequest::new(probes.sleep, language).with_timeout(config.timeout);
+            execute(backend, request).await.and_then(|result| {
+                let elapsed = started.elapsed();
+                if !result.is_timed_out() {
+                    Err(format!("not reported as timed out: {}", describe(&result)))
+                } else if elapsed > config.timeout + TIMEOUT_SLACK {
+                    Err(format!(
+                        "took {}s to time out after {}s",
+                        elapsed.as_secs(),
+                        config.timeout.as_secs()
+                    ))
+                } else {
+                    Ok(())
+                }
+            })
+        }
+        Check::MemoryLimit => {
+            let limits = ResourceLimits {
+                max_memory: Some(MEMORY_LIMIT_BYTES),
+                ..ResourceLimits::default()
+            };
+            let request = ExecutionRequest::new(probes.allocate, language).with_limits(limits);
+            execute(backend, request).await.and_then(|result| {
+                if result.is_success() && result.stdout.trim() == ALLOCATION_BYTES.to_string() {
+                    Err(format!(
+                        "allocated {ALLOCATION_BYTES} bytes under a {MEMORY_LIMIT_BYTES} byte limit"
+                    ))
+                } else {
+                    Ok(())
+                }
+            })
+        }
+        Check::UnicodeOutput => {
+            let request = ExecutionRequest::new(probes.unicode, language);
+            execute(backend, request).await.and_then(|result| {
+                if result.is_success() && result.stdout.trim_end() == UNICODE_SAMPLE {
+                    Ok(())
+                } else {
+                    Err(format!("expected {UNICODE_SAMPLE:?}, got {}", describe(&result)))
+                }
+            })
+        }
+        Check::BinaryOutput => {
+            let request = ExecutionRequest::new(probes.binary, language);
+            execute(backend, request).await.and_then(|result| {
+                let stdout = result.stdout.trim_end();
+                if result.is_success() && stdout.starts_with("start") && stdout.ends_with("end") {
+                    Ok(())
+                } else {
+                    Err(format!("expected start…end, got {}", describe(&result)))
+                }
+            })
+        }
+        Check::EnvVars => {
+            let mut values = ValueGenerator::new(config.seed);
+            let mut outcome = Ok(());
+            for case in 0..config.env_cases {
+                let value = values.next_value();
+                let request = ExecutionRequest::new(probes.echo_env, language)
+                    .with_env(ENV_PROBE_VAR, value.as_str());
+                outcome = execute(backend, request).await.and_then(|result| {
+                    if result.is_success() && result.stdout == value {
+                        Ok(())
+                    } else {
+                        Err(format!("case {case}: sent {value:?}, got {}", describe(&result)))
+                    }
+                });
+                if outcome.is_err() {
+                    break;
+                }
+            }
+            outcome
+        }
+        Check::WorkspaceCleanup => workspace_cleanup(backend, probes).await,
+    }
+}
+
+async fn workspace_cleanup<B: ExecutionBackend + ?Sized>(
+    backend: &B,
+    probes: &Probes,
+) -> Result<(), String> {
+    let written = execute(backend, ExecutionRequest::new(probes.write_marker, probes.language))
+        .await?;
+    if !written.is_success() {
+        return Err(format!("writing the marker failed: {}", describe(&written)));
+    }
+    // The reported directory is a guest path for VM and container
+    // backends; it is only meaningful when it exists on this host
+    let workspace = Path::new(written.stdout.trim());
+    if workspace.is_absolute() && workspace.join(MARKER_FILE).exists() {
+        return Err(format!("workspace {} outlived its execution", workspace.display()));
+    }
+
+    let found = execute(backend, ExecutionRequest::new(probes.find_marker, probes.language))
+        .await?;
+    match found.stdout.trim() {
+        "absent" if found.is_success() => Ok(()),
+        "present" => Err("marker from the previous execution is visible".to_string()),
+        _ => Err(format!("looking for the marker failed: {}", describe(&found))),
+    }
+}
+
+async fn execute<B: ExecutionBackend + ?Sized>(
+    backend: &B,
+    request: ExecutionRequest,
+) -> Result<ExecutionResult, String> {
+    backend
+        .execute_code(request)
+        .await
+        .map_err(|e| format!("backend task failed: {e}"))
+}
+
+fn describe(result: &ExecutionResult) -> String {
+    format!(
+        "exit {} ({:?}), stdout {:?}, stderr {:?}",
+        result.exit_code,
+        result.outcome,
+        truncate(&result.stdout),
+        truncate(result.stderr.trim())
+    )
+}
+
+fn truncate(text: &str) -> &str {
+    match text.char_indices().nth(200) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// Seeded xorshift generator of environment variable values
+///
+/// Values mix ASCII, including quotes, spaces and shell metacharacters,
+/// with Latin, CJK, combining and astral characters. They never contain
+/// NUL, which no environment can carry, or other control characters.
+struct ValueGenerator(u64);
+
+impl ValueGenerator {
+    fn new(seed: u64) -> Self {
+        // xorshift never leaves zero
+        Self(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn below(&mut self, bound: u32) -> u32 {
+        (self.next_u64() % u64::from(bound)) as u32
+    }
+
+    fn next_value(&mut self) -> String {
+        const RANGES: [(u32, u32); 5] = [
+            (0x20, 0x7e),       // printable ASCII
+            (0xa1, 0x17f),      // Latin-1 and Latin Extended-A
+            (0x300, 0x36f),     // combining marks
+            (0x4e00, 0x9fff),   // CJK
+            (0x1f300, 0x1f64f), // emoji
+        ];
+        let length = 1 + self.below(32);
+        (0..length)
+            .filter_map(|_| {
+                let (low, high) = RANGES[self.below(RANGES.len() as u32) as usize];
+                char::from_u32(low + self.below(high - low + 1))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::{AsyncTask, BackendConfig, HealthCheckLevel, HealthStatus};
+    use crate::execution_env::CyloResult;
+
+    /// Backend answering the python probes the way a conformant backend
+    /// would, optionally dropping environment variables
+    #[derive(Debug)]
+    struct ScriptedBackend {
+        config: BackendConfig,
+        drops_env: bool,
+    }
+
+    impl ExecutionBackend for ScriptedBackend {
+        fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
+            let code = request.code.as_str();
+            let result = if code.contains("sleep") {
+                ExecutionResult::timed_out("", "", request.timeout)
+            } else if code.contains("bytearray") {
+                ExecutionResult::failure(137, "MemoryError")
+            } else if code.contains("Grüße") {
+                ExecutionResult::success(format!("{UNICODE_SAMPLE}\n"))
+            } else if code.contains("buffer") {
+                ExecutionResult::success(String::from_utf8_lossy(b"start\0\xff\xfe\x80end\n"))
+            } else if code.contains("environ") {
+                let value = request.env_vars.get(ENV_PROBE_VAR).filter(|_| !self.drops_env);
+                ExecutionResult::success(value.cloned().unwrap_or_default())
+            } else if code.contains("open(") {
+                ExecutionResult::success("/sandbox/gone\n")
+            } else {
+                ExecutionResult::success("absent\n")
+            };
+            tokio::spawn(async move { result })
+        }
+
+        fn health_check(&self, _level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
+            tokio::spawn(async { HealthStatus::healthy("scripted") })
+        }
+
+        fn cleanup(&self) -> AsyncTask<CyloResult<()>> {
+            tokio::spawn(async { Ok(()) })
+        }
+
+        fn get_config(&self) -> &BackendConfig {
+            &self.config
+        }
+
+        fn backend_type(&self) -> &'static str {
+            "Scripted"
+        }
+
+        fn supports_language(&self, language: &str) -> bool {
+            language == "python"
+        }
+
+        fn supported_languages(&self) -> &[&'static str] {
+            &["python"]
+        }
+    }
+
+    #[tokio::test]
+    async fn suite_passes_conformant_backends_and_pins_down_violations() {
+        let mut backend = ScriptedBackend {
+            config: BackendConfig::new("scripted"),
+            drops_env: false,
+        };
+        let report = run(&backend, &ConformanceConfig::default()).await;
+        assert_eq!(report.language.as_deref(), Some("python"));
+        assert!(report.is_conformant(), "{}", report.summary());
+
+        backend.drops_env = true;
+        let report = run(&backend, &ConformanceConfig::default()).await;
+        let failures = report.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].check, Check::EnvVars);
+
+        let config = ConformanceConfig::default().with_language("cobol");
+        let report = run(&backend, &config).await;
+        assert!(report.checks.iter().all(|c| c.status == CheckStatus::Skipped));
+        assert!(!report.is_conformant());
+
+        let mut values = ValueGenerator::new(7);
+        let value = values.next_value();
+        assert_eq!(ValueGenerator::new(7).next_value(), value);
+        assert!(!value.is_empty() && !value.chars().any(char::is_control));
+    }
+
+    #[tokio::test]
+    async fn backends_without_live_output_stream_it_once_finished() {
+        use crate::backends::OutputStream;
+
+        let backend = ScriptedBackend {
+            config: BackendConfig::new("scripted"),
+            drops_env: false,
+        };
+        assert!(!backend.capabilities().streaming);
+        let request = ExecutionRequest::new("print('absent')", "python");
+        let (mut chunks, execution) = backend.execute_code_streaming(request);
+
+        let chunk = chunks.recv().await.unwrap();
+        assert_eq!((chunk.stream, chunk.text().as_ref()), (OutputStream::Stdout, "absent\n"));
+        assert!(chunks.recv().await.is_err(), "empty stderr sends nothing");
+        assert_eq!(execution.await.unwrap().stdout, "absent\n");
+    }
+}
@@ -5,13 +5,31 @@
 // ============================================================================
 
 use std::collections::HashMap;
+use std::fs;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::AsyncTaskBuilder;
+use crate::backends::blob_store::write_blob_files;
+use crate::backends::input_stream;
+use crate::backends::live::LiveSet;
+use crate::backends::paths::{ExposedPath, exposed_paths, relative_inside, write_files};
+use crate::backends::process::{self, CapturedOutput, OutputCapture, WaitOutcome};
 use crate::backends::{
-    AsyncTask, BackendError, BackendResult, ExecutionRequest, ExecutionResult,
+    ARCH_METADATA, AsyncTask, BackendError, BackendResult, CLOCK_METADATA, CORE_DUMP_METADATA,
+    CompilerOptions, CrashReport, DnsPolicy, DnsResolvers, ExecutionOutcome,
+    ExecutionRequest, ExecutionResult, IsolationLevel, SIGNAL_METADATA, SecurityReport,
+    WATCHDOG_METADATA,
 };
+use crate::backends::{
+    SqlEngine, SqlOptions, archive, clock, compile_phase, compiler, git_checkout, go_cache,
+    language, r_library, retention, sql,
+};
+use crate::hardening::{Helper, HelperCommand};
+use crate::ids;
+use crate::reaper::{DEADLINE_GRACE, ResourceKind, global_reaper};
 
 use super::resource_stats;
 
@@ -19,30 +37,67 @@ use super::resource_stats;
 ///
 /// # Arguments
 /// * `image` - Container image specification
+/// * `owner_id` - Executor identity embedded in the container name
+/// * `live_containers` - Registry the running container is tracked in
 /// * `request` - Execution request with code and configuration
+/// * `retention` - How long the mounted source directory is kept if the
+///   execution fails
 ///
 /// # Returns
 /// AsyncTask that resolves to execution result
 pub(super) fn execute_in_container(
     image: String,
+    owner_id: String,
+    live_containers: LiveSet<String>,
     request: ExecutionRequest,
+    retention: Duration,
 ) -> AsyncTask<BackendResult<ExecutionResult>> {
     AsyncTaskBuilder::new(async move {
         let start_time = Instant::now();
 
         // Create unique container name
-        let container_name = format!(
-            "cylo-{}-{}",
-            uuid::Uuid::new_v4().simple(),
-            std::process::id()
-        );
+        let container_name =
+            ids::artifact_name(ids::ID_PREFIX, &owner_id, &ids::language_tag(&request.language));
 
-        // Prepare execution command based on language
-        let exec_cmd = prepare_execution_command(&request.language, &request.code)?;
+        // Prepare execution command based on language; the command only
+        // names the mounted source file and never embeds the code
+        let (source_file, mut exec_cmd) = prepare_execution_command(
+            &request.language,
+            request.compiler.as_ref(),
+            request.sql.as_ref(),
+        )?;
+        let source_dir = SourceDir::create(&owner_id, &source_file, &request)?;
+        if let Some(repo) = &request.git_repo {
+            git_checkout::checkout(repo, source_dir.path(), &request).await?;
+        }
+
+        // The umask is applied, static and blocked names go into the
+        // container's /etc/hosts, and a virtual clock preloads libfaketime
+        // from the image before the code starts
+        let mut setup = format!("umask {:03o}\n", request.environment.umask);
+        if let Some(dns) = &request.dns
+            && (!dns.static_hosts.is_empty() || !dns.blocked_domains.is_empty())
+        {
+            source_dir.write(HOSTS_FILE, &dns.hosts())?;
+            setup.push_str(&format!("cat {SOURCE_MOUNT}/{HOSTS_FILE} > /etc/hosts || exit 1\n"));
+        }
+        if let Some(clock) = &request.clock {
+            setup.push_str(&clock::faketime_shell_setup(clock));
+        }
+        exec_cmd = with_setup(&setup, exec_cmd);
 
         // Build container run command
         let mut cmd = Command::new("container");
         cmd.args(["run", "--rm", "--name", &container_name]);
+        cmd.args([
+            "--volume",
+            &format!("{}:{}", source_dir.path().display(), SOURCE_MOUNT),
+        ]);
+
+        // Host paths the request exposes, mounted at the same location
+        for path in exposed_paths(&request)? {
+            cmd.args(["--volume", &volume_spec(&path)?]);
+        }
 
         // Add resource limits
         if let Some(memory) = request.limits.max_memory {
@@ -53,19 +108,47 @@ pub(super) fn execute_in_container(
             cmd.args(["--cpus", &format!("{cpu_time}")]);
         }
 
+        if let Some(dns) = &request.dns {
+            cmd.args(dns_args(dns));
+        }
+
+        // Normalized environment, Go caches and R library first, so request
+        // variables win; HOME and the caches are directories next to the
+        // source file
+        source_dir.create_subdir(HOME_DIR)?;
+        let home = format!("{SOURCE_MOUNT}/{HOME_DIR}");
+        for (key, value) in request.environment.variables(&home) {
+            cmd.args(["-e", &format!("{key}={value}")]);
+        }
+        let cache_root = format!("{SOURCE_MOUNT}/{CACHE_DIR}");
+        for (key, value) in go_cache::go_env(&request, &cache_root) {
+            cmd.args(["-e", &format!("{key}={value}")]);
+        }
+        for (key, value) in r_library::r_env(&request, &cache_root) {
+            cmd.args(["-e", &format!("{key}={value}")]);
+        }
+
         // Add environment variables
         for (key, value) in &request.env_vars {
             cmd.args(["-e", &format!("{key}={value}")]);
         }
 
-        // Set working directory if specified
+        // Set working directory if specified; `..` escapes are rejected and
+        // the path is always absolute inside the container
         if let Some(workdir) = &request.working_dir {
-            cmd.args(["-w", workdir]);
+            let relative = relative_inside(workdir)?;
+            cmd.args(["-w", &format!("/{}", relative.display())]);
         }
 
         // Add timeout handling
         cmd.args(["--timeout", &format!("{}s", request.timeout.as_secs())]);
 
+        // Multi-arch images provide the requested variant; x86_64 ones run
+        // on Apple silicon through Rosetta
+        if let Some(arch) = request.arch {
+            cmd.args(["--arch", arch.oci_name()]);
+        }
+
         // Specify image and command
         cmd.arg(&image);
         cmd.args(&exec_cmd);
@@ -75,10 +158,29 @@ pub(super) fn execute_in_container(
         cmd.stderr(Stdio::piped());
         cmd.stdin(Stdio::piped());
 
+        // Lead a new process group so a forced kill reaches the whole client tree
+        cmd.process_group(0);
+
         // Execute the container
-        let mut child = cmd.spawn().map_err(|e| BackendError::ProcessFailed {
-            details: format!("Failed to spawn container: {e}"),
-        })?;
+        let mut child = cmd
+            .helper_spawn(Helper::ContainerCli)
+            .map_err(|e| BackendError::ProcessFailed {
+                details: format!("Failed to spawn container: {e}"),
+            })?;
+        let reaper_guard = global_reaper().track(
+            ResourceKind::Container {
+                runtime: "container".to_string(),
+                name: container_name.clone(),
+            },
+            "Apple",
+            request.execution_id.as_deref(),
+            Some(request.timeout + DEADLINE_GRACE),
+        );
+        let live_guard = live_containers.track(container_name.clone());
+
+        // Capture output incrementally so it survives a forced kill, and
+        // stream it to the request's sink as it arrives
+        let capture = OutputCapture::streaming(&mut child, request.output_sink.clone());
 
         // Write input if provided
         if let Some(input) = &request.input
@@ -92,123 +194,434 @@ pub(super) fn execute_in_container(
                     details: format!("Failed to write to container stdin: {e}"),
                 })?;
         }
+        let _input_pump = input_stream::pipe(&mut child, &request)?;
 
-        // Wait for completion with timeout
+        // Wait for completion; on timeout ask the container to stop with
+        // SIGTERM, allow the grace period, then SIGKILL it and the CLI client
         let timeout_duration = request.timeout;
+        let client_pid = child.id();
+        let mut usage_at_timeout = None;
+        let outcome = process::wait_with_grace(
+            &mut child,
+            timeout_duration,
+            request.termination_grace,
+            || {
+                // Snapshot stats while the container still exists; --rm
+                // removes it as soon as it stops
+                usage_at_timeout = resource_stats::sample_resource_usage(&container_name);
+                signal_container(&container_name, "SIGTERM");
+            },
+            || {
+                signal_container(&container_name, "SIGKILL");
+                process::kill_group(client_pid);
+            },
+        )
+        .await
+        .map_err(|e| BackendError::ProcessFailed {
+            details: format!("Container execution failed: {e}"),
+        })?;
+        // A container that exited on its own has been removed by --rm; one
+        // that was stopped on timeout is left for the sweep to make sure of,
+        // and sampled as the instance's until then
+        if matches!(outcome, WaitOutcome::Exited(_)) {
+            reaper_guard.release();
+        } else {
+            live_guard.linger();
+        }
 
-        // Use a different approach - spawn a task that can kill the process
-        let child_handle = tokio::spawn(async move { child.wait_with_output() });
+        let duration = start_time.elapsed();
+        let CapturedOutput { stdout, stderr, raw } = capture.collect(process::OUTPUT_DRAIN).await;
 
-        let output = match tokio::time::timeout(timeout_duration, child_handle).await {
-            Ok(Ok(Ok(output))) => output,
-            Ok(Ok(Err(e))) => {
-                return Err(BackendError::ProcessFailed {
-                    details: format!("Container execution failed: {e}"),
-                });
+        let mut result = match outcome {
+            WaitOutcome::Exited(status) => ExecutionResult {
+                exit_code: status.code().unwrap_or(-1),
+                stdout,
+                stderr,
+                duration,
+                // Parse resource usage from container stats (if available)
+                resource_usage: resource_stats::parse_resource_usage(&container_name)
+                    .await
+                    .unwrap_or_default(),
+                metadata: HashMap::new(),
+                verdict: None,
+                outcome: ExecutionOutcome::Completed,
+                security: None,
+                cost: None,
+                crash: None,
+                result_sets: None,
+                workspace_archive: None,
+                blobs: HashMap::new(),
+                processed: None,
+                raw_output: None,
+                compilation: None,
+            },
+            // No watchdog runs here, so a stall can only be a timeout
+            WaitOutcome::TimedOut { graceful } | WaitOutcome::Stalled { graceful } => {
+                let mut result = ExecutionResult::timed_out(stdout, stderr, timeout_duration);
+                result.duration = duration;
+                result.resource_usage = usage_at_timeout.unwrap_or_default();
+                result.metadata.insert(
+                    "termination".to_string(),
+                    if graceful { "graceful" } else { "forced" }.to_string(),
+                );
+                result
             }
-            Ok(Err(_)) => {
-                return Err(BackendError::ProcessFailed {
-                    details: "Container process task failed".to_string(),
-                });
+        };
+
+        result.raw_output = raw;
+        result.security = Some(SecurityReport {
+            isolation: IsolationLevel::Container,
+            network_disabled: false,
+            filesystem_read_only: false,
+            memory_limit_bytes: request.limits.max_memory,
+            // Every container runs in its own lightweight VM
+            ipc_isolated: true,
+            ui_restricted: true,
+        });
+        result
+            .metadata
+            .insert("backend".to_string(), "Apple".to_string());
+        result.metadata.insert("image".to_string(), image);
+        if let Some(arch) = request.arch {
+            result.metadata.insert(ARCH_METADATA.to_string(), arch.to_string());
+        }
+        if let Some(dns) = &request.dns {
+            dns.record(&mut result);
+        }
+        if let Some(clock) = &request.clock {
+            result
+                .metadata
+                .insert(CLOCK_METADATA.to_string(), clock.mode().to_string());
+        }
+        if request.stall_timeout.is_some() {
+            // Liveness needs the sandbox's CPU use, which only the container sees
+            result.metadata.insert(
+                WATCHDOG_METADATA.to_string(),
+                "not enforced: container CPU use is not observable from the host".to_string(),
+            );
+        }
+
+        // The runtime reports a signal death as 128 + N; the core, if any,
+        // stays inside the container's VM
+        if !result.is_timed_out() {
+            result.crash = CrashReport::from_exit_code(result.exit_code);
+        }
+        if let Some(crash) = &result.crash {
+            result
+                .metadata
+                .insert(SIGNAL_METADATA.to_string(), crash.signal_name.clone());
+            if request.core_dump_limit.is_some() && crash.dumps_core() {
+                result.metadata.insert(
+                    CORE_DUMP_METADATA.to_string(),
+                    "not captured: core dumps stay inside the container".to_string(),
+                );
             }
-            Err(_) => {
-                // Timeout occurred - the process is still running but we can't kill it
-                // from here since it's been moved into the task
-                return Err(BackendError::ExecutionTimeout {
-                    seconds: timeout_duration.as_secs(),
-                });
+        }
+        result
+            .metadata
+            .insert("container_name".to_string(), container_name);
+        archive::attach_output(&request, source_dir.path(), &mut result);
+        let workspace = vec![source_dir.path().to_path_buf()];
+        if retention::retain_on_failure(retention, workspace, "Apple", &mut result) {
+            source_dir.retain();
+        }
+
+        Ok(result)
+    })
+    .spawn()
+}
+
+/// Send a signal to a running container
+fn signal_container(container_name: &str, signal: &str) {
+    let _ = Command::new("container")
+        .args(["kill", "--signal", signal, container_name])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .helper_status(Helper::ContainerCli);
+}
+
+/// Container path the host source directory is mounted at
+const SOURCE_MOUNT: &str = "/cylo-src";
+
+/// Name of the rendered hosts file inside the source directory
+const HOSTS_FILE: &str = "hosts";
+
+/// Name of the home directory inside the source directory
+const HOME_DIR: &str = "home";
+
+/// Name of the Go cache directory inside the source directory
+const CACHE_DIR: &str = ".cache";
+
+/// `container run` flags selecting the resolvers of a DNS policy
+///
+/// `--no-dns` leaves the container without a resolv.conf, so lookups go
+/// to the container's own loopback where nothing answers. Connections to
+/// addresses are untouched; the container's network decides those.
+fn dns_args(policy: &DnsPolicy) -> Vec<String> {
+    let mut args = Vec::new();
+    match &policy.resolvers {
+        DnsResolvers::Host => {}
+        DnsResolvers::Fixed(servers) => {
+            for server in servers {
+                args.extend(["--dns".to_string(), server.to_string()]);
             }
+        }
+        DnsResolvers::None => args.push("--no-dns".to_string()),
+    }
+    for domain in &policy.search_domains {
+        args.extend(["--dns-search".to_string(), domain.clone()]);
+    }
+    args
+}
+
+/// `--volume` argument mounting an exposed host path
+///
+/// The volume syntax is colon-separated, so paths containing a colon
+/// cannot be expressed and are rejected rather than mis-mounted.
+fn volume_spec(path: &ExposedPath) -> BackendResult<String> {
+    let (source, target) = (path.source.display(), path.target.display());
+    if source.to_string().contains(':') || target.to_string().contains(':') {
+        return Err(BackendError::InvalidConfig {
+            backend: "Apple",
+            details: format!("Cannot mount {target}: paths containing ':' are not supported"),
+        });
+    }
+    let mode = if path.writable { "" } else { ":ro" };
+    Ok(format!("{source}:{target}{mode}"))
+}
+
+/// Wrap a command so shell `setup` lines run before it
+fn with_setup(setup: &str, command: Vec<String>) -> Vec<String> {
+    let script = format!("{setup}exec \"$@\"");
+    ["sh".to_string(), "-c".to_string(), script, "sh".to_string()]
+        .into_iter()
+        .chain(command)
+        .collect()
+}
+
+/// Host directory holding the source file mounted into the container
+///
+/// Removed when dropped, on success and on every error path, unless it
+/// was retained.
+struct SourceDir {
+    path: PathBuf,
+    retained: bool,
+}
+
+impl SourceDir {
+    /// Create the directory and write the request's workspace archive,
+    /// additional files and blobs, and code into it, in that order
+    fn create(owner_id: &str, file_name: &str, request: &ExecutionRequest) -> BackendResult<Self> {
+        // `cylo_<owner>_apple_<random>` so startup recovery finds leftovers
+        let path = std::env::temp_dir().join(ids::workspace_dir_name(owner_id, "apple"));
+        fs::create_dir_all(&path).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to create source directory: {e}"),
+        })?;
+
+        // Dropping the guard removes the directory if a write fails
+        let dir = Self {
+            path,
+            retained: false,
         };
+        archive::unpack_workspace(request, &dir.path, "Apple")?;
+        write_files(&dir.path, &request.files)?;
+        write_blob_files(&dir.path, &request.blob_files)?;
+        fs::write(dir.path.join(file_name), &request.code).map_err(|e| {
+            BackendError::FileSystemFailed {
+                details: format!("Failed to write source file: {e}"),
+            }
+        })?;
+        Ok(dir)
+    }
 
-        let duration = start_time.elapsed();
+    /// Write an additional file next to the source file
+    fn write(&self, file_name: &str, content: &str) -> BackendResult<()> {
+        fs::write(self.path.join(file_name), content).map_err(|e| {
+            BackendError::FileSystemFailed {
+                details: format!("Failed to write {file_name}: {e}"),
+            }
+        })
+    }
 
-        // Parse resource usage from container stats (if available)
-        let resource_usage = resource_stats::parse_resource_usage(&container_name)
-            .await
-            .unwrap_or_default();
-
-        Ok(ExecutionResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-            duration,
-            resource_usage,
-            metadata: {
-                let mut meta = HashMap::new();
-                meta.insert("backend".to_string(), "Apple".to_string());
-                meta.insert("image".to_string(), image);
-                meta.insert("container_name".to_string(), container_name);
-                meta
-            },
+    /// Create an empty directory next to the source file
+    fn create_subdir(&self, name: &str) -> BackendResult<()> {
+        fs::create_dir_all(self.path.join(name)).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to create {name} directory: {e}"),
         })
-    })
-    .spawn()
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Leave the directory in place when dropped; the reaper removes it
+    /// once its retention period is over
+    fn retain(mut self) {
+        self.retained = true;
+    }
+}
+
+impl Drop for SourceDir {
+    fn drop(&mut self) {
+        if !self.retained {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
 }
 
 /// Prepare execution command for specific language
 ///
 /// # Arguments
 /// * `language` - Programming language
-/// * `code` - Source code to execute
+/// * `compiler` - Compiler options for compiled languages
+/// * `sql` - Engine and seeds of a SQL request
 ///
 /// # Returns
-/// Command arguments for container execution
-pub(super) fn prepare_execution_command(language: &str, code: &str) -> BackendResult<Vec<String>> {
-    match language.to_lowercase().as_str() {
-        "python" | "python3" => Ok(vec![
-            "python3".to_string(),
-            "-c".to_string(),
-            code.to_string(),
-        ]),
+/// Source file name to write under the mount and the command arguments
+/// for container execution
+pub(super) fn prepare_execution_command(
+    language: &str,
+    compiler: Option<&CompilerOptions>,
+    sql: Option<&SqlOptions>,
+) -> BackendResult<(String, Vec<String>)> {
+    // The pinned version picked the image (`ImageReference::for_runtime`)
+    let (name, _) = language::split_version(language);
+    let options = compiler.cloned().unwrap_or_default();
+    let shell = |script: String| vec!["sh".to_string(), "-c".to_string(), script];
+    let (source_file, command) = match name.to_lowercase().as_str() {
+        "python" | "python3" => ("main.py", vec!["python3".into(), "/cylo-src/main.py".into()]),
         "javascript" | "js" | "node" => {
-            Ok(vec!["node".to_string(), "-e".to_string(), code.to_string()])
-        }
-        "rust" => {
-            // For Rust, we need to create a temporary file and compile
-            Ok(vec![
-                "sh".to_string(),
-                "-c".to_string(),
-                format!(
-                    "echo '{}' > /tmp/main.rs && cd /tmp && rustc main.rs && ./main",
-                    code.replace('\'', "'\"'\"'")
+            ("main.js", vec!["node".into(), "/cylo-src/main.js".into()])
+        }
+        "rust" => (
+            "main.rs",
+            shell(compile_phase::compile_then_run(
+                &format!(
+                    "rustc {}/cylo-src/main.rs -o /tmp/main",
+                    compiler::shell_args(&options.rustc_args())
                 ),
-            ])
-        }
-        "bash" | "sh" => Ok(vec!["sh".to_string(), "-c".to_string(), code.to_string()]),
-        "go" => Ok(vec![
-            "sh".to_string(),
-            "-c".to_string(),
-            format!(
-                "echo '{}' > /tmp/main.go && cd /tmp && go run main.go",
-                code.replace('\'', "'\"'\"'")
-            ),
-        ]),
-        _ => Err(BackendError::UnsupportedLanguage {
-            backend: "Apple",
-            language: language.to_string(),
-        }),
-    }
+                "exec /tmp/main",
+            )),
+        ),
+        "bash" | "sh" => ("main.sh", vec!["sh".into(), "/cylo-src/main.sh".into()]),
+        "go" => (
+            "main.go",
+            shell(compile_phase::compile_then_run(
+                &format!(
+                    "cd /tmp && go build -o /tmp/main {}/cylo-src/main.go",
+                    compiler::shell_args(&options.go_args())
+                ),
+                "exec /tmp/main",
+            )),
+        ),
+        "r" | "rscript" => (
+            "main.R",
+            shell(r_library::rscript_command("Rscript", "/cylo-src/main.R")),
+        ),
+        "sql" | "sqlite" | "sqlite3" | "duckdb" => {
+            let engine = SqlEngine::of(language, sql);
+            let args = sql::shell_args(engine, sql, "/cylo-src/main.sql").map_err(|details| {
+                BackendError::InvalidConfig {
+                    backend: "Apple",
+                    details,
+                }
+            })?;
+            let mut command = vec![engine.program().to_string()];
+            command.extend(args);
+            ("main.sql", command)
+        }
+        _ => {
+            return Err(BackendError::UnsupportedLanguage {
+                backend: "Apple",
+                language: language.to_string(),
+            });
+        }
+    };
+
+    Ok((source_file.to_string(), command))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backends::OptLevel;
 
     #[test]
     fn execution_command_preparation() {
-        let python_cmd = prepare_execution_command("python", "print('hello')")
+        let (file, python_cmd) = prepare_execution_command("python", None, None)
             .expect("test should successfully prepare python execution command");
-        assert_eq!(python_cmd, vec!["python3", "-c", "print('hello')"]);
+        assert_eq!(file, "main.py");
+        assert_eq!(python_cmd, vec!["python3", "/cylo-src/main.py"]);
 
-        let js_cmd = prepare_execution_command("javascript", "console.log('hello')")
+        let (file, js_cmd) = prepare_execution_command("javascript", None, None)
             .expect("test should successfully prepare javascript execution command");
-        assert_eq!(js_cmd, vec!["node", "-e", "console.log('hello')"]);
+        assert_eq!(file, "main.js");
+        assert_eq!(js_cmd, vec!["node", "/cylo-src/main.js"]);
 
-        let bash_cmd = prepare_execution_command("bash", "echo hello")
+        let (file, bash_cmd) = prepare_execution_command("bash", None, None)
             .expect("test should successfully prepare bash execution command");
-        assert_eq!(bash_cmd, vec!["sh", "-c", "echo hello"]);
+        assert_eq!(file, "main.sh");
+        assert_eq!(bash_cmd, vec!["sh", "/cylo-src/main.sh"]);
+
+        let (file, r_cmd) = prepare_execution_command("R", None, None)
+            .expect("test should successfully prepare R execution command");
+        assert_eq!(file, "main.R");
+        assert!(r_cmd[2].ends_with("exec Rscript /cylo-src/main.R"), "{}", r_cmd[2]);
 
-        let unsupported = prepare_execution_command("cobol", "some code");
+        let unsupported = prepare_execution_command("cobol", None, None);
         assert!(unsupported.is_err());
+
+        let debug = CompilerOptions::default().with_opt_level(OptLevel::None);
+        let (file, go_cmd) = prepare_execution_command("go", Some(&debug), None)
+            .expect("test should successfully prepare go execution command");
+        assert_eq!(file, "main.go");
+        assert_eq!(
+            go_cmd[2],
+            "cd /tmp && exec go run '-gcflags=all=-N -l' /cylo-src/main.go"
+        );
+    }
+
+    #[test]
+    fn source_dir_holds_code_verbatim_and_is_removed() {
+        let code = "print('it''s $(whoami)')";
+        let request = ExecutionRequest::new(code, "python")
+            .with_file("main.py", "shadowed")
+            .with_file("lib/util.py", "X = 1");
+        let dir = SourceDir::create("test", "main.py", &request).unwrap();
+        let path = dir.path().to_path_buf();
+        assert_eq!(fs::read_to_string(path.join("main.py")).unwrap(), code);
+        assert_eq!(fs::read_to_string(path.join("lib/util.py")).unwrap(), "X = 1");
+
+        drop(dir);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn exposed_paths_mount_read_only_unless_writable() {
+        let mut path = ExposedPath {
+            source: PathBuf::from("/Users/dev/data"),
+            target: PathBuf::from("/data"),
+            writable: false,
+        };
+        assert_eq!(volume_spec(&path).unwrap(), "/Users/dev/data:/data:ro");
+        path.writable = true;
+        assert_eq!(volume_spec(&path).unwrap(), "/Users/dev/data:/data");
+        path.target = PathBuf::from("/data:rw");
+        assert!(volume_spec(&path).is_err());
+    }
+
+    #[test]
+    fn dns_policy_maps_to_run_flags_and_setup_wrapper() {
+        let fixed = DnsPolicy::fixed(["1.1.1.1".parse().unwrap()]).with_search_domain("corp");
+        assert_eq!(dns_args(&fixed), ["--dns", "1.1.1.1", "--dns-search", "corp"]);
+        let allow_list = DnsPolicy::only([("api.internal", "10.0.0.1".parse().unwrap())]);
+        assert_eq!(dns_args(&allow_list), ["--no-dns"]);
+        assert!(dns_args(&DnsPolicy::default()).is_empty());
+
+        let command = vec!["python3".to_string(), "/cylo-src/main.py".to_string()];
+        let wrapped = with_setup("cat /cylo-src/hosts > /etc/hosts || exit 1\n", command);
+        assert_eq!(&wrapped[..2], ["sh", "-c"]);
+        assert_eq!(wrapped[2], "cat /cylo-src/hosts > /etc/hosts || exit 1\nexec \"$@\"");
+        assert_eq!(&wrapped[3..], ["sh", "python3", "/cylo-src/main.py"]);
     }
 }
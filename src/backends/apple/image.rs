@@ -4,10 +4,26 @@
 // Container image management for Apple containerization backend.
 // ============================================================================
 
-use std::process::{Command, Stdio};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
 
 use crate::AsyncTaskBuilder;
-use crate::backends::{AsyncTask, BackendError, BackendResult};
+use crate::backends::image_ref::is_valid_digest;
+use crate::backends::progress::{self, ProgressReporter, ProvisioningStage};
+use crate::backends::provisioning::{self, StageBudget};
+use crate::backends::{
+    AsyncTask, BackendError, BackendResult, ImageReference, ImageStore, ProvisioningLimits,
+    RegistryCredentials, RegistryLogin, StoredImage,
+};
+use crate::hardening::{Helper, HelperCommand};
+
+/// Interval at which a CLI process is checked against its budget
+const WAIT_POLL: Duration = Duration::from_millis(50);
 
 /// Check if Apple containerization CLI is available
 ///
@@ -19,7 +35,7 @@ pub(super) fn check_cli_availability() -> AsyncTask<bool> {
             .arg("--version")
             .stdout(Stdio::null())
             .stderr(Stdio::null())
-            .status();
+            .helper_status(Helper::ContainerCli);
 
         match result {
             Ok(status) => status.success(),
@@ -31,19 +47,39 @@ pub(super) fn check_cli_availability() -> AsyncTask<bool> {
 
 /// Pull container image if not already available
 ///
+/// Images held by the offline image store are loaded from it without
+/// contacting a registry. Otherwise the image's registry is logged in to
+/// first when credentials are configured for it. Pull progress printed by
+/// the CLI is forwarded to `progress` as `PullingImage` events. A pull or
+/// store load running past `limits.pull_timeout`, or a pull whose size
+/// exceeds `limits.max_download_bytes`, is killed.
+///
 /// # Arguments
 /// * `image` - Image to pull
+/// * `store` - Offline image store from the backend config, if any
+/// * `credentials` - Registry credentials from the backend config
+/// * `limits` - Provisioning budgets from the backend config
+/// * `progress` - Reporter of the request being provisioned, if any
 ///
 /// # Returns
 /// AsyncTask that resolves when image is available
-pub(super) fn ensure_image_available(image: String) -> AsyncTask<BackendResult<()>> {
+pub(super) fn ensure_image_available(
+    image: String,
+    store: Option<ImageStore>,
+    credentials: RegistryCredentials,
+    limits: ProvisioningLimits,
+    progress: Option<ProgressReporter>,
+) -> AsyncTask<BackendResult<()>> {
     AsyncTaskBuilder::new(async move {
+        let stage = ProvisioningStage::PullingImage;
+        let budget = StageBudget::start("Apple", stage, limits.pull_timeout);
+
         // Check if image exists locally first
         let check_result = Command::new("container")
             .args(["image", "exists", &image])
             .stdout(Stdio::null())
             .stderr(Stdio::null())
-            .status();
+            .helper_status(Helper::ContainerCli);
 
         match check_result {
             Ok(status) if status.success() => {
@@ -55,25 +91,295 @@ pub(super) fn ensure_image_available(image: String) -> AsyncTask<BackendResult<(
             }
         }
 
-        // Pull the image
-        let pull_result = Command::new("container")
+        let reference = ImageReference::parse(&image).ok();
+        if let (Some(store), Some(reference)) = (&store, &reference)
+            && let Some(stored) = store.lookup(reference)
+        {
+            if let Some(progress) = &progress {
+                progress.report(
+                    ProvisioningStage::PullingImage,
+                    None,
+                    format!("Loading {image} from the image store"),
+                );
+            }
+            return load_from_store(store, &stored, &budget);
+        }
+
+        if let Some(reference) = &reference
+            && let Some(login) = credentials.resolve(reference.registry())?
+        {
+            registry_login(&login)?;
+        }
+
+        let report = |percent: Option<u8>, size: Option<u64>| {
+            if let Some(progress) = &progress {
+                let message = match size {
+                    Some(bytes) => format!("Pulling {image} ({:.1} MB)", bytes as f64 / 1e6),
+                    None => format!("Pulling {image}"),
+                };
+                progress.report(ProvisioningStage::PullingImage, percent, message);
+            }
+        };
+        report(Some(0), None);
+
+        // Pull the image, following the progress lines the CLI rewrites
+        // with carriage returns
+        let mut child = Command::new("container")
             .args(["pull", &image])
-            .stdout(Stdio::piped())
+            .stdout(Stdio::null())
             .stderr(Stdio::piped())
-            .output();
-
-        match pull_result {
-            Ok(output) if output.status.success() => Ok(()),
-            Ok(output) => {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                Err(BackendError::ContainerFailed {
-                    details: format!("Failed to pull image {image}: {stderr}"),
-                })
-            }
-            Err(e) => Err(BackendError::ContainerFailed {
+            .helper_spawn(Helper::ContainerCli)
+            .map_err(|e| BackendError::ContainerFailed {
                 details: format!("Failed to execute container pull: {e}"),
-            }),
+            })?;
+
+        // Lines are read on their own thread so the budget is enforced
+        // even while the CLI prints nothing
+        let (lines, received) = mpsc::channel::<Vec<u8>>();
+        if let Some(stderr) = child.stderr.take() {
+            thread::spawn(move || {
+                for chunk in BufReader::new(stderr).split(b'\n') {
+                    let Ok(chunk) = chunk else { break };
+                    if lines.send(chunk).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        let mut output = Vec::new();
+        let mut last_percent = None;
+        let mut size = None;
+        loop {
+            let chunk = match budget.remaining() {
+                Some(remaining) => received.recv_timeout(remaining),
+                None => received.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => {
+                    kill(&mut child);
+                    return Err(budget.exceeded());
+                }
+            };
+            for line in chunk.split(|b| *b == b'\r') {
+                let line = String::from_utf8_lossy(line);
+                if let Some(bytes) = provisioning::parse_bytes(&line) {
+                    if let Some(max) = limits.max_download_bytes
+                        && bytes > max
+                    {
+                        kill(&mut child);
+                        return Err(BackendError::ResourceLimitExceeded {
+                            resource: format!("download size of image {image}"),
+                            limit: format!("{max} bytes"),
+                        });
+                    }
+                    size = Some(bytes);
+                }
+                if let Some(percent) = progress::parse_percent(&line)
+                    && last_percent != Some(percent)
+                {
+                    last_percent = Some(percent);
+                    report(Some(percent), size);
+                }
+            }
+            output.extend_from_slice(&chunk);
+            output.push(b'\n');
+        }
+
+        if wait_within(&mut child, &budget)?.success() {
+            report(Some(100), size);
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output);
+            Err(BackendError::ContainerFailed {
+                details: format!("Failed to pull image {image}: {stderr}"),
+            })
         }
     })
     .spawn()
 }
+
+/// Load an image from the offline store into the container CLI
+///
+/// Exporting and loading share the pull's budget.
+fn load_from_store(
+    store: &ImageStore,
+    stored: &StoredImage,
+    budget: &StageBudget,
+) -> BackendResult<()> {
+    let failed = |details: String| BackendError::ContainerFailed { details };
+
+    let staging = tempfile::tempdir()
+        .map_err(|e| failed(format!("Failed to create image staging dir: {e}")))?;
+    let archive = staging.path().join("image.tar");
+    store
+        .export(stored, &archive)
+        .map_err(|e| failed(format!("Failed to export {}: {e}", stored.reference)))?;
+    if budget.remaining().is_some_and(|remaining| remaining.is_zero()) {
+        return Err(budget.exceeded());
+    }
+
+    // Errors go to a file so a chatty load cannot block on a full pipe
+    // while it is being waited on
+    let log_path = staging.path().join("load.log");
+    let log = File::create(&log_path)
+        .map_err(|e| failed(format!("Failed to create image load log: {e}")))?;
+    let mut child = Command::new("container")
+        .args(["image", "load", "--input"])
+        .arg(&archive)
+        .stdout(Stdio::null())
+        .stderr(log)
+        .helper_spawn(Helper::ContainerCli)
+        .map_err(|e| failed(format!("Failed to execute container image load: {e}")))?;
+    if wait_within(&mut child, budget)?.success() {
+        Ok(())
+    } else {
+        Err(failed(format!(
+            "Failed to load {} from the image store: {}",
+            stored.reference,
+            std::fs::read_to_string(&log_path).unwrap_or_default().trim()
+        )))
+    }
+}
+
+/// Wait for a CLI process, killing it once `budget` is spent
+fn wait_within(child: &mut Child, budget: &StageBudget) -> BackendResult<ExitStatus> {
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Ok(status),
+            Ok(None) => {}
+            Err(e) => {
+                return Err(BackendError::ContainerFailed {
+                    details: format!("Failed to wait for container CLI: {e}"),
+                });
+            }
+        }
+        if budget.remaining().is_some_and(|remaining| remaining.is_zero()) {
+            kill(child);
+            return Err(budget.exceeded());
+        }
+        thread::sleep(WAIT_POLL);
+    }
+}
+
+/// Kill and reap a CLI process abandoned mid-step
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Log the container CLI in to a registry
+///
+/// The secret is passed on stdin so it never appears in the process list.
+fn registry_login(login: &RegistryLogin) -> BackendResult<()> {
+    let failed = |details: String| BackendError::ContainerFailed { details };
+
+    let mut child = Command::new("container")
+        .args(["registry", "login", "--username", &login.username, "--password-stdin"])
+        .arg(&login.registry)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .helper_spawn(Helper::ContainerCli)
+        .map_err(|e| failed(format!("Failed to execute container registry login: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(login.secret.as_bytes())
+            .map_err(|e| failed(format!("Failed to pass registry secret: {e}")))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failed(format!("Failed to execute container registry login: {e}")))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(failed(format!(
+            "Login to {} failed: {}",
+            login.registry,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Look up the content digest of a local image
+///
+/// # Arguments
+/// * `image` - Image reference as pulled
+///
+/// # Returns
+/// AsyncTask that resolves to the image's `sha256:` digest
+pub(super) fn resolve_image_digest(image: String) -> AsyncTask<BackendResult<String>> {
+    AsyncTaskBuilder::new(async move {
+        let output = Command::new("container")
+            .args(["image", "inspect", &image])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .helper_output(Helper::ContainerCli)
+            .map_err(|e| BackendError::ContainerFailed {
+                details: format!("Failed to execute container image inspect: {e}"),
+            })?;
+        if !output.status.success() {
+            return Err(BackendError::ContainerFailed {
+                details: format!(
+                    "Failed to inspect image {image}: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+            });
+        }
+
+        serde_json::from_slice::<serde_json::Value>(&output.stdout)
+            .ok()
+            .and_then(|inspect| find_digest(&inspect))
+            .ok_or_else(|| BackendError::ContainerFailed {
+                details: format!("No digest reported for image {image}"),
+            })
+    })
+    .spawn()
+}
+
+/// Find the outermost `digest` field holding a sha256 digest
+///
+/// The index digest sits above the per-platform manifest digests, so the
+/// search is breadth-first.
+fn find_digest(inspect: &serde_json::Value) -> Option<String> {
+    let mut queue = VecDeque::from([inspect]);
+    while let Some(value) = queue.pop_front() {
+        match value {
+            serde_json::Value::Object(fields) => {
+                for (key, field) in fields {
+                    if key.eq_ignore_ascii_case("digest")
+                        && let Some(digest) = field.as_str()
+                        && is_valid_digest(digest)
+                    {
+                        return Some(digest.to_ascii_lowercase());
+                    }
+                }
+                queue.extend(fields.values());
+            }
+            serde_json::Value::Array(items) => queue.extend(items),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_digest_is_preferred_over_manifest_digests() {
+        let index = format!("sha256:{}", "a".repeat(64));
+        let manifest = format!("sha256:{}", "b".repeat(64));
+        let inspect = serde_json::json!([{
+            "name": "python:3.12",
+            "variants": [{ "descriptor": { "digest": manifest } }],
+            "index": { "digest": index, "mediaType": "application/vnd.oci.image.index.v1+json" }
+        }]);
+        assert_eq!(find_digest(&inspect), Some(index));
+        assert_eq!(find_digest(&serde_json::json!({ "digest": "latest" })), None);
+    }
+}
@@ -23,10 +23,14 @@ use std::process::Command;
 use std::time::Duration;
 
 use crate::AsyncTaskBuilder;
+use crate::backends::{arch, cpuset, desktop, io_throttle, language, python_env};
 use crate::backends::{
     AsyncTask, BackendConfig, BackendError, BackendResult, ExecutionBackend, ExecutionRequest,
-    ExecutionResult, HealthStatus,
+    ExecutionResult, HealthCheckLevel, HealthStatus, ImageReference, ImageStore, InstanceMetrics,
+    ProvisioningStage,
 };
+use crate::backends::live::LiveSet;
+use crate::hardening::{Helper, HelperCommand};
 
 /// Apple containerization backend
 ///
@@ -38,8 +42,14 @@ pub struct AppleBackend {
     /// Container image specification (e.g., "python:alpine3.20")
     image: String,
 
+    /// Parsed image specification, carrying any pinned digest
+    reference: ImageReference,
+
     /// Backend configuration
     config: BackendConfig,
+
+    /// Names of the containers currently running executions
+    live_containers: LiveSet<String>,
 }
 
 impl AppleBackend {
@@ -64,22 +74,58 @@ impl AppleBackend {
         if !validation::is_valid_image_format(&image) {
             return Err(BackendError::InvalidConfig {
                 backend: "Apple",
-                details: format!("Invalid image format: {image}. Expected format: 'name:tag'"),
+                details: format!(
+                    "Invalid image format: {image}. Expected format: 'name:tag' or \
+                     'name@sha256:<digest>'"
+                ),
             });
         }
+        let reference = ImageReference::parse(&image)
+            .map_err(|details| BackendError::InvalidConfig { backend: "Apple", details })?;
 
-        Ok(Self { image, config })
+        Ok(Self {
+            image,
+            reference,
+            config,
+            live_containers: LiveSet::default(),
+        })
     }
 }
 
 impl ExecutionBackend for AppleBackend {
     fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
         let image = self.image.clone();
+        let configured = self.reference.clone();
+        let credentials = self.config.registry_credentials.clone();
+        let limits = self.config.provisioning.clone();
+        let store = ImageStore::from_backend_config(&self.config);
+        let owner_id = self.config.owner_id.clone();
         let backend_name = self.backend_type();
+        let live_containers = self.live_containers.clone();
+        let retention = self.config.retain_workspace_on_failure;
 
         AsyncTaskBuilder::new(async move {
+            if let Err(e) = python_env::reject_in_guest(&request, backend_name)
+                .and_then(|()| desktop::reject_in_guest(&request, backend_name))
+                .and_then(|()| cpuset::reject_unpinnable(&request, backend_name))
+                .and_then(|()| io_throttle::reject_unthrottled(&request, backend_name))
+                .and_then(|()| arch::check(&request, backend_name))
+            {
+                return ExecutionResult::failure(-1, e.to_string());
+            }
+
+            // A pinned runtime version runs the image tagged with it
+            let reference = match configured.for_runtime(&request.language, backend_name) {
+                Ok(runtime_reference) => runtime_reference,
+                Err(e) => return ExecutionResult::failure(-1, e.to_string()),
+            };
+            let image = if reference == configured { image } else { reference.to_string() };
+
             // Ensure image is available
-            match image::ensure_image_available(image.clone()).await {
+            let progress = request.progress.clone();
+            let pulled =
+                image::ensure_image_available(image.clone(), store, credentials, limits, progress);
+            match pulled.await {
                 Ok(Ok(())) => {}
                 Ok(Err(e)) => {
                     return ExecutionResult::failure(-1, format!("Failed to prepare image: {e}"));
@@ -91,10 +137,33 @@ impl ExecutionBackend for AppleBackend {
                     );
                 }
             }
+            // Tags can be repointed; run only the pinned content, and record
+            // what actually ran either way
+            let digest = match image::resolve_image_digest(image.clone()).await {
+                Ok(Ok(digest)) => digest,
+                Ok(Err(e)) => return ExecutionResult::failure(-1, e.to_string()),
+                Err(e) => {
+                    return ExecutionResult::failure(-1, format!("Image inspect task failed: {e}"));
+                }
+            };
+            if let Err(e) = reference.verify(&digest) {
+                return ExecutionResult::failure(-1, e);
+            }
+            request.report_progress(ProvisioningStage::Ready, None, "Starting container");
 
             // Execute in container
-            match execution::execute_in_container(image, request).await {
-                Ok(Ok(result)) => result,
+            let execution = execution::execute_in_container(
+                image,
+                owner_id,
+                live_containers,
+                request,
+                retention,
+            );
+            match execution.await {
+                Ok(Ok(mut result)) => {
+                    reference.record(&mut result, digest);
+                    result
+                }
                 Ok(Err(e)) => {
                     ExecutionResult::failure(-1, format!("{backend_name} execution failed: {e}"))
                 }
@@ -107,8 +176,23 @@ impl ExecutionBackend for AppleBackend {
         .spawn()
     }
 
-    fn health_check(&self) -> AsyncTask<HealthStatus> {
+    fn instance_metrics(&self) -> AsyncTask<Option<InstanceMetrics>> {
+        let live_containers = self.live_containers.clone();
+
+        AsyncTaskBuilder::new(async move {
+            let samples = live_containers.sample(|name| {
+                resource_stats::sample_resource_usage(name)
+                    .map(|usage| (usage.peak_memory, usage.cpu_time_ms))
+            });
+            Some(InstanceMetrics::from_samples(samples))
+        })
+        .spawn()
+    }
+
+    fn health_check(&self, level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
         let image = self.image.clone();
+        let owner_id = self.config.owner_id.clone();
+        let live_containers = self.live_containers.clone();
 
         AsyncTaskBuilder::new(async move {
             // Check CLI availability
@@ -124,11 +208,25 @@ impl ExecutionBackend for AppleBackend {
                     .with_metric("platform_supported", "false");
             }
 
+            if level == HealthCheckLevel::Liveness {
+                return HealthStatus::healthy("Apple containerization CLI available")
+                    .with_metric("cli_available", "true")
+                    .with_metric("platform_supported", "true");
+            }
+
             // Test container execution with simple command
             let test_request = ExecutionRequest::new("echo 'health check'", "bash")
                 .with_timeout(Duration::from_secs(10));
 
-            match execution::execute_in_container(image.clone(), test_request).await {
+            // A failed probe is reported here; its workspace is not kept
+            let probe = execution::execute_in_container(
+                image.clone(),
+                owner_id,
+                live_containers,
+                test_request,
+                Duration::ZERO,
+            );
+            match probe.await {
                 Ok(Ok(result)) if result.is_success() => {
                     HealthStatus::healthy("Apple containerization backend operational")
                         .with_metric("cli_available", "true")
@@ -151,18 +249,20 @@ impl ExecutionBackend for AppleBackend {
     }
 
     fn cleanup(&self) -> AsyncTask<crate::execution_env::CyloResult<()>> {
+        let name_filter = format!("name=cylo-{}-", self.config.owner_id);
+
         AsyncTaskBuilder::new(async move {
-            // Clean up any dangling containers with our prefix
+            // Clean up dangling containers created by this executor
             let cleanup_result = Command::new("container")
                 .args([
                     "ps",
                     "-a",
                     "--filter",
-                    "name=cylo-",
+                    &name_filter,
                     "--format",
                     "{{.Names}}",
                 ])
-                .output();
+                .helper_output(Helper::ContainerCli);
 
             if let Ok(output) = cleanup_result
                 && output.status.success()
@@ -172,7 +272,7 @@ impl ExecutionBackend for AppleBackend {
                     if !name.trim().is_empty() {
                         let _ = Command::new("container")
                             .args(["rm", "-f", name.trim()])
-                            .status();
+                            .helper_status(Helper::ContainerCli);
                     }
                 }
             }
@@ -191,7 +291,8 @@ impl ExecutionBackend for AppleBackend {
     }
 
     fn supports_language(&self, language: &str) -> bool {
-        self.supported_languages().contains(&language)
+        let (name, _) = language::split_version(language);
+        self.supported_languages().contains(&name)
     }
 
     fn supported_languages(&self) -> &[&'static str] {
@@ -205,6 +306,13 @@ impl ExecutionBackend for AppleBackend {
             "bash",
             "sh",
             "go",
+            "r",
+            "R",
+            "rscript",
+            "sql",
+            "sqlite",
+            "sqlite3",
+            "duckdb",
         ]
     }
 }
@@ -7,6 +7,7 @@
 use std::process::Command;
 
 use crate::backends::ResourceUsage;
+use crate::hardening::{Helper, HelperCommand};
 
 /// Parse resource usage from container stats
 ///
@@ -16,9 +17,17 @@ use crate::backends::ResourceUsage;
 /// # Returns
 /// Resource usage statistics or None if unavailable
 pub(super) async fn parse_resource_usage(container_name: &str) -> Option<ResourceUsage> {
+    sample_resource_usage(container_name)
+}
+
+/// Synchronously sample container stats
+///
+/// Used where awaiting is not possible, e.g. to snapshot usage right before
+/// a timed-out container is stopped and removed.
+pub(super) fn sample_resource_usage(container_name: &str) -> Option<ResourceUsage> {
     let stats_result = Command::new("container")
         .args(["stats", "--no-stream", "--format", "json", container_name])
-        .output();
+        .helper_output(Helper::ContainerCli);
 
     match stats_result {
         Ok(output) if output.status.success() => {
@@ -4,6 +4,8 @@
 // Platform and image validation for Apple containerization backend.
 // ============================================================================
 
+use crate::backends::ImageReference;
+
 /// Check if platform supports Apple containerization
 ///
 /// # Returns
@@ -25,40 +27,9 @@ pub(super) fn is_platform_supported() -> bool {
 /// * `image` - Image specification to validate
 ///
 /// # Returns
-/// true if format is valid, false otherwise
+/// true for `name:tag`, `name@sha256:<hex>` or both combined
 pub(super) fn is_valid_image_format(image: &str) -> bool {
-    // Basic validation: must contain ':' for tag
-    if !image.contains(':') {
-        return false;
-    }
-
-    // Split into name and tag
-    let parts: Vec<&str> = image.splitn(2, ':').collect();
-    if parts.len() != 2 {
-        return false;
-    }
-
-    let (name, tag) = (parts[0], parts[1]);
-
-    // Name must not be empty and contain valid characters
-    if name.is_empty()
-        || !name
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '/' || c == '-' || c == '_' || c == '.')
-    {
-        return false;
-    }
-
-    // Tag must not be empty and contain valid characters
-    if tag.is_empty()
-        || !tag
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '.' || c == '-' || c == '_')
-    {
-        return false;
-    }
-
-    true
+    ImageReference::parse(image).is_ok()
 }
 
 #[cfg(test)]
@@ -77,5 +48,10 @@ mod tests {
         assert!(!is_valid_image_format(":tag"));
         assert!(!is_valid_image_format("image:"));
         assert!(!is_valid_image_format("image:tag:extra"));
+
+        let digest = format!("sha256:{}", "ab".repeat(32));
+        assert!(is_valid_image_format(&format!("python@{digest}")));
+        assert!(is_valid_image_format(&format!("python:3.12@{digest}")));
+        assert!(!is_valid_image_format("python@sha256:tooshort"));
     }
 }
@@ -0,0 +1,253 @@
+// ============================================================================
+// File: packages/cylo/src/backends/arch.rs
+// ----------------------------------------------------------------------------
+// Target CPU architecture of an execution.
+//
+// Every backend runs code built for the host's architecture. Some can also
+// run the other one: Apple containers run x86_64 images on Apple silicon
+// through Rosetta, and LandLock sandboxes run foreign Go and Rust binaries
+// through a qemu-user handler registered with binfmt_misc, cross-compiling
+// them first. `supports` answers whether a backend can run a request's
+// architecture on this host, so routing only offers capable backends and
+// backends refuse the rest with `BackendError::UnsupportedArchitecture`.
+// ============================================================================
+
+use std::fmt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{BackendError, BackendResult, ExecutionRequest, language, runtime};
+
+/// Result metadata key with the architecture the code ran as
+pub const ARCH_METADATA: &str = "arch";
+
+/// Rosetta's runtime, present once Rosetta is installed on Apple silicon
+const ROSETTA_RUNTIME: &str = "/Library/Apple/usr/libexec/oah/libRosettaRuntime";
+
+/// Where binfmt_misc lists its registered handlers
+const BINFMT_MISC_DIR: &str = "/proc/sys/fs/binfmt_misc";
+
+/// CPU architecture code is built for and run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Arch {
+    /// 64-bit x86, also called amd64
+    #[serde(alias = "amd64")]
+    X86_64,
+    /// 64-bit ARM, also called aarch64
+    #[serde(alias = "aarch64")]
+    Arm64,
+}
+
+impl Arch {
+    /// Architecture of this host, if it is one cylo can target
+    pub fn host() -> Option<Self> {
+        match std::env::consts::ARCH {
+            "x86_64" => Some(Self::X86_64),
+            "aarch64" => Some(Self::Arm64),
+            _ => None,
+        }
+    }
+
+    /// Whether this is the host's architecture
+    pub fn is_native(self) -> bool {
+        Self::host() == Some(self)
+    }
+
+    /// Name in OCI image platforms and container CLIs
+    pub fn oci_name(self) -> &'static str {
+        match self {
+            Self::X86_64 => "amd64",
+            Self::Arm64 => "arm64",
+        }
+    }
+
+    /// Value of `GOARCH` building for this architecture
+    pub fn go_arch(self) -> &'static str {
+        self.oci_name()
+    }
+
+    /// Rust target triple of Linux on this architecture
+    pub fn rust_target(self) -> &'static str {
+        match self {
+            Self::X86_64 => "x86_64-unknown-linux-gnu",
+            Self::Arm64 => "aarch64-unknown-linux-gnu",
+        }
+    }
+
+    /// GNU cross linker producing binaries for this architecture
+    pub fn cross_linker(self) -> &'static str {
+        match self {
+            Self::X86_64 => "x86_64-linux-gnu-gcc",
+            Self::Arm64 => "aarch64-linux-gnu-gcc",
+        }
+    }
+
+    /// Directory holding this architecture's libraries on a multiarch
+    /// Debian-style host, where qemu finds a foreign binary's loader
+    pub fn cross_sysroot(self) -> &'static str {
+        match self {
+            Self::X86_64 => "/usr/x86_64-linux-gnu",
+            Self::Arm64 => "/usr/aarch64-linux-gnu",
+        }
+    }
+
+    /// Name of the qemu-user handler binfmt_misc runs this
+    /// architecture's binaries with
+    fn binfmt_handler(self) -> &'static str {
+        match self {
+            Self::X86_64 => "qemu-x86_64",
+            Self::Arm64 => "qemu-aarch64",
+        }
+    }
+}
+
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::X86_64 => "x86_64",
+            Self::Arm64 => "arm64",
+        })
+    }
+}
+
+impl FromStr for Arch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "x86_64" | "amd64" => Ok(Self::X86_64),
+            "arm64" | "aarch64" => Ok(Self::Arm64),
+            other => Err(format!("unknown architecture '{other}'; use x86_64 or arm64")),
+        }
+    }
+}
+
+/// Whether a backend can run `language` code built for `arch` on this host
+///
+/// Native execution always works. Apple runs x86_64 on Apple silicon when
+/// Rosetta is installed; LandLock runs foreign Go and Rust when a
+/// qemu-user handler is registered, Rust also needing the cross linker and
+/// the target's standard library; Docker runs any foreign image through
+/// the same handler; K8sJob schedules onto cluster nodes of the requested
+/// architecture. Every other backend is native only.
+pub fn supports(backend: &str, arch: Arch, language: &str) -> bool {
+    if arch.is_native() {
+        return true;
+    }
+    match backend {
+        "Apple" => arch == Arch::X86_64 && Path::new(ROSETTA_RUNTIME).exists(),
+        "Docker" => binfmt_enabled(arch),
+        "K8sJob" => true,
+        "LandLock" => {
+            let language = language::resolve(language).map(|spec| spec.name);
+            binfmt_enabled(arch)
+                && match language {
+                    Some("go") => true,
+                    Some("rust") => rust_cross_ready(arch),
+                    _ => false,
+                }
+        }
+        _ => false,
+    }
+}
+
+/// Refuse a request whose architecture the backend cannot run here
+pub fn check(request: &ExecutionRequest, backend: &'static str) -> BackendResult<()> {
+    match request.arch {
+        Some(arch) if !supports(backend, arch, &request.language) => {
+            Err(BackendError::UnsupportedArchitecture {
+                backend,
+                arch,
+                reason: unsupported_reason(backend, arch),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Why `supports` said no, for error messages and routing rejections
+pub fn unsupported_reason(backend: &str, arch: Arch) -> String {
+    let host = Arch::host().map_or_else(|| std::env::consts::ARCH.to_string(), |a| a.to_string());
+    match backend {
+        "Apple" if arch == Arch::X86_64 => "Rosetta is not installed".to_string(),
+        "LandLock" | "Docker" if !binfmt_enabled(arch) => {
+            format!("no {} handler is registered with binfmt_misc", arch.binfmt_handler())
+        }
+        "LandLock" => format!(
+            "only Go, and Rust with {} and the {} target installed, run as {}",
+            arch.cross_linker(),
+            arch.rust_target(),
+            arch
+        ),
+        _ => format!("{backend} only runs {host} code here"),
+    }
+}
+
+fn binfmt_enabled(arch: Arch) -> bool {
+    std::fs::read_to_string(Path::new(BINFMT_MISC_DIR).join(arch.binfmt_handler()))
+        .is_ok_and(|entry| entry.lines().next() == Some("enabled"))
+}
+
+/// Whether rustc can cross-compile for `arch`; probed once per process
+fn rust_cross_ready(arch: Arch) -> bool {
+    static READY: OnceLock<[bool; 2]> = OnceLock::new();
+    let ready = READY.get_or_init(|| {
+        [Arch::X86_64, Arch::Arm64].map(|arch| {
+            let search_path = runtime::host_search_path();
+            let linker = search_path.iter().any(|dir| dir.join(arch.cross_linker()).is_file());
+            linker && rust_target_installed(arch)
+        })
+    });
+    ready[arch as usize]
+}
+
+fn rust_target_installed(arch: Arch) -> bool {
+    Command::new("rustc")
+        .args(["--print", "target-libdir", "--target", arch.rust_target()])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| {
+            Path::new(String::from_utf8_lossy(&output.stdout).trim()).is_dir()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_architecture_runs_everywhere() {
+        assert_eq!("amd64".parse::<Arch>(), Ok(Arch::X86_64));
+        assert_eq!("AArch64".parse::<Arch>(), Ok(Arch::Arm64));
+        assert!("riscv64".parse::<Arch>().is_err());
+        assert_eq!(serde_json::from_str::<Arch>("\"aarch64\"").unwrap(), Arch::Arm64);
+        assert_eq!(serde_json::to_string(&Arch::X86_64).unwrap(), "\"x86_64\"");
+
+        let Some(host) = Arch::host() else {
+            return;
+        };
+        let foreign = match host {
+            Arch::X86_64 => Arch::Arm64,
+            Arch::Arm64 => Arch::X86_64,
+        };
+        for backend in ["Apple", "LandLock", "FireCracker", "WindowsJob"] {
+            assert!(supports(backend, host, "python"));
+        }
+        assert!(!supports("FireCracker", foreign, "go"));
+        assert!(!supports("LandLock", foreign, "python"));
+
+        let request = ExecutionRequest::new("print(1)", "python").with_arch(foreign);
+        match check(&request, "WindowsJob") {
+            Err(BackendError::UnsupportedArchitecture { arch, .. }) => assert_eq!(arch, foreign),
+            other => panic!("expected UnsupportedArchitecture, got {other:?}"),
+        }
+    }
+}
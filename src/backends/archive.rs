@@ -0,0 +1,524 @@
+// ============================================================================
+// File: packages/cylo/src/backends/archive.rs
+// ----------------------------------------------------------------------------
+// Tar and zip archives of execution workspaces.
+//
+// A request can bring its whole initial workspace as one archive instead of
+// file by file, and ask for the final workspace (or a subtree of it) back as
+// an archive in the result. Unpacking is guarded against decompression
+// bombs and hostile entries: the entry count and the unpacked size are
+// capped and counted as the bytes are written, and entries that would land
+// outside the workspace, links and device nodes are refused. Packing skips
+// links, refuses a subtree that resolves outside the workspace and opens no
+// file through a link, so nothing outside the workspace ends up in the
+// result.
+// ============================================================================
+
+use std::fs;
+use std::io::{self, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+use crate::backends::paths::relative_inside;
+use crate::backends::{BackendError, BackendResult, ExecutionRequest, ExecutionResult};
+
+/// Result metadata key with the reason the requested workspace archive is
+/// missing from the result
+pub const ARCHIVE_ERROR_METADATA: &str = "workspace_archive.error";
+
+/// Unix file type bits of a symbolic link
+const SYMLINK_MODE: u32 = 0o120000;
+
+/// Container format of a workspace archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    /// Uncompressed tar
+    Tar,
+    /// Gzip-compressed tar
+    TarGz,
+    /// Zip with deflate compression
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Format of archive bytes, judged by their magic number
+    ///
+    /// Anything that is neither gzip nor zip is taken to be a plain tar.
+    pub fn detect(data: &[u8]) -> Self {
+        if data.starts_with(&[0x1f, 0x8b]) {
+            Self::TarGz
+        } else if data.starts_with(b"PK\x03\x04") || data.starts_with(b"PK\x05\x06") {
+            Self::Zip
+        } else {
+            Self::Tar
+        }
+    }
+}
+
+/// An archive of workspace contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceArchive {
+    /// Container format
+    pub format: ArchiveFormat,
+    /// Archive bytes
+    pub data: Vec<u8>,
+}
+
+impl WorkspaceArchive {
+    /// Wrap archive bytes of a known format
+    pub fn new(format: ArchiveFormat, data: Vec<u8>) -> Self {
+        Self { format, data }
+    }
+
+    /// Wrap archive bytes, detecting their format
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self::new(ArchiveFormat::detect(&data), data)
+    }
+}
+
+/// Caps on what an archive may unpack to or be packed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveLimits {
+    /// Most bytes of file contents
+    pub max_bytes: u64,
+    /// Most files and directories
+    pub max_entries: usize,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 1024 * 1024 * 1024, // 1GB
+            max_entries: 100_000,
+        }
+    }
+}
+
+/// Workspace archive an execution returns
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputArchive {
+    /// Container format
+    pub format: ArchiveFormat,
+    /// Workspace-relative directory to archive instead of the whole
+    /// workspace
+    #[serde(default)]
+    pub subtree: Option<String>,
+}
+
+impl OutputArchive {
+    /// Archive the whole workspace
+    pub fn new(format: ArchiveFormat) -> Self {
+        Self {
+            format,
+            subtree: None,
+        }
+    }
+
+    /// Archive only this workspace-relative directory
+    pub fn with_subtree<S: Into<String>>(mut self, subtree: S) -> Self {
+        self.subtree = Some(subtree.into());
+        self
+    }
+}
+
+/// Unpack an archive into a workspace directory
+///
+/// # Arguments
+/// * `archive` - Archive to unpack
+/// * `dir` - Host directory of the workspace
+/// * `limits` - Caps on the unpacked entries and bytes
+///
+/// # Returns
+/// Ok(()) or why the archive was refused; entries written before the
+/// refusal are left for the workspace cleanup
+pub fn unpack(
+    archive: &WorkspaceArchive,
+    dir: &Path,
+    limits: &ArchiveLimits,
+) -> Result<(), String> {
+    let mut unpacker = Unpacker {
+        root: dir,
+        limits,
+        entries: 0,
+        bytes: 0,
+    };
+    match archive.format {
+        ArchiveFormat::Tar => unpacker.tar(&archive.data[..]),
+        ArchiveFormat::TarGz => unpacker.tar(GzDecoder::new(&archive.data[..])),
+        ArchiveFormat::Zip => unpacker.zip(&archive.data),
+    }
+}
+
+/// Pack a workspace, or a directory in it, into an archive
+///
+/// # Arguments
+/// * `dir` - Host directory of the workspace
+/// * `output` - Format and subtree of the archive
+/// * `limits` - Caps on the packed entries and bytes
+///
+/// # Returns
+/// The archive, or why it could not be built
+pub fn pack(
+    dir: &Path,
+    output: &OutputArchive,
+    limits: &ArchiveLimits,
+) -> Result<WorkspaceArchive, String> {
+    let subtree = output.subtree.as_deref().unwrap_or(".");
+    let not_directory = || format!("'{subtree}' is not a directory in the workspace");
+    let workspace = fs::canonicalize(dir).map_err(|_| not_directory())?;
+    let relative = relative_inside(subtree)
+        .map_err(|_| format!("'{}' escapes the workspace", subtree.escape_debug()))?;
+    // The code may have linked any directory along the way elsewhere on
+    // the host, so the resolved path is what has to stay inside
+    let root = fs::canonicalize(workspace.join(relative)).map_err(|_| not_directory())?;
+    if !root.starts_with(&workspace) {
+        return Err(format!("'{}' escapes the workspace", subtree.escape_debug()));
+    }
+    if !fs::symlink_metadata(&root).is_ok_and(|metadata| metadata.is_dir()) {
+        return Err(not_directory());
+    }
+
+    let mut members = Vec::new();
+    collect(&root, "", limits, &mut members, &mut 0)?;
+    let data = match output.format {
+        ArchiveFormat::Tar => write_tar(Vec::new(), &members),
+        ArchiveFormat::TarGz => {
+            let encoder = GzEncoder::new(Vec::new(), Compression::default());
+            write_tar(encoder, &members).and_then(|encoder| encoder.finish())
+        }
+        ArchiveFormat::Zip => write_zip(&members),
+    }
+    .map_err(|e| format!("Failed to write {:?} archive: {e}", output.format))?;
+    Ok(WorkspaceArchive::new(output.format, data))
+}
+
+/// Unpack a request's workspace archive, if it has one, into its workspace
+///
+/// # Arguments
+/// * `request` - Execution request
+/// * `dir` - Host directory of the workspace, before any file is written
+/// * `backend` - Backend name reported with a refused archive
+pub fn unpack_workspace(
+    request: &ExecutionRequest,
+    dir: &Path,
+    backend: &'static str,
+) -> BackendResult<()> {
+    match &request.workspace_archive {
+        Some(archive) => unpack(archive, dir, &request.archive_limits)
+            .map_err(|details| BackendError::InvalidConfig { backend, details }),
+        None => Ok(()),
+    }
+}
+
+/// Pack the workspace into the result if the request asked for an archive
+///
+/// The code has run by now, so a workspace that cannot be packed is
+/// reported under `ARCHIVE_ERROR_METADATA` instead of failing the result.
+///
+/// # Arguments
+/// * `request` - Execution request
+/// * `dir` - Host directory of the workspace, before it is removed
+/// * `result` - Result of the execution
+pub fn attach_output(request: &ExecutionRequest, dir: &Path, result: &mut ExecutionResult) {
+    let Some(output) = &request.output_archive else {
+        return;
+    };
+    match pack(dir, output, &request.archive_limits) {
+        Ok(archive) => result.workspace_archive = Some(archive),
+        Err(reason) => {
+            result.metadata.insert(ARCHIVE_ERROR_METADATA.to_string(), reason);
+        }
+    }
+}
+
+/// Writes archive entries below a workspace while counting them
+struct Unpacker<'a> {
+    root: &'a Path,
+    limits: &'a ArchiveLimits,
+    entries: usize,
+    bytes: u64,
+}
+
+impl Unpacker<'_> {
+    fn tar<R: Read>(&mut self, reader: R) -> Result<(), String> {
+        let mut archive = tar::Archive::new(reader);
+        let entries = archive.entries().map_err(|e| format!("Unreadable tar archive: {e}"))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| format!("Unreadable tar entry: {e}"))?;
+            let name = entry
+                .path()
+                .map_err(|e| format!("Unreadable tar entry name: {e}"))?
+                .to_string_lossy()
+                .into_owned();
+            let mode = entry.header().mode().unwrap_or(0o644);
+            match entry.header().entry_type() {
+                tar::EntryType::Directory => self.directory(&name)?,
+                tar::EntryType::Regular | tar::EntryType::Continuous => {
+                    self.file(&name, mode, &mut entry)?
+                }
+                tar::EntryType::XGlobalHeader => {}
+                other => return Err(format!("entry '{name}' has unsupported type {other:?}")),
+            }
+        }
+        Ok(())
+    }
+
+    fn zip(&mut self, data: &[u8]) -> Result<(), String> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(data))
+            .map_err(|e| format!("Unreadable zip archive: {e}"))?;
+        for index in 0..archive.len() {
+            let mut entry = archive
+                .by_index(index)
+                .map_err(|e| format!("Unreadable zip entry: {e}"))?;
+            let name = entry.name().to_string();
+            let mode = entry.unix_mode();
+            if mode.is_some_and(|mode| mode & 0o170000 == SYMLINK_MODE) {
+                return Err(format!("entry '{name}' is a link"));
+            }
+            if entry.is_dir() {
+                self.directory(&name)?;
+            } else {
+                self.file(&name, mode.unwrap_or(0o644), &mut entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Host path of an entry, counting it against the entry cap
+    fn target(&mut self, name: &str) -> Result<Option<PathBuf>, String> {
+        self.entries += 1;
+        if self.entries > self.limits.max_entries {
+            return Err(format!("archive has more than {} entries", self.limits.max_entries));
+        }
+        let relative = relative_inside(name)
+            .map_err(|_| format!("entry '{}' escapes the workspace", name.escape_debug()))?;
+        // `./` names the workspace itself
+        Ok((!relative.as_os_str().is_empty()).then(|| self.root.join(relative)))
+    }
+
+    fn directory(&mut self, name: &str) -> Result<(), String> {
+        if let Some(target) = self.target(name)? {
+            fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create directory '{name}': {e}"))?;
+        }
+        Ok(())
+    }
+
+    fn file(&mut self, name: &str, mode: u32, contents: &mut dyn Read) -> Result<(), String> {
+        let target = self
+            .target(name)?
+            .ok_or_else(|| format!("file entry '{name}' names the workspace itself"))?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory for '{name}': {e}"))?;
+        }
+        let mut file =
+            fs::File::create(&target).map_err(|e| format!("Failed to create '{name}': {e}"))?;
+
+        // Declared sizes can lie, so the cap is enforced on what is written
+        let remaining = self.limits.max_bytes - self.bytes;
+        let written = io::copy(&mut contents.take(remaining.saturating_add(1)), &mut file)
+            .map_err(|e| format!("Failed to unpack '{name}': {e}"))?;
+        self.bytes += written;
+        if self.bytes > self.limits.max_bytes {
+            return Err(format!("archive unpacks to more than {} bytes", self.limits.max_bytes));
+        }
+        set_mode(&target, mode).map_err(|e| format!("Failed to set mode of '{name}': {e}"))
+    }
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    // No setuid, setgid or sticky bits from an archive
+    fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o777))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
+
+/// A file or directory to pack
+struct Member {
+    /// Name in the archive, `/`-separated
+    name: String,
+    path: PathBuf,
+    is_dir: bool,
+    mode: u32,
+    size: u64,
+}
+
+/// Gather the members below `dir`, sorted by name, skipping links and
+/// special files
+fn collect(
+    dir: &Path,
+    prefix: &str,
+    limits: &ArchiveLimits,
+    members: &mut Vec<Member>,
+    bytes: &mut u64,
+) -> Result<(), String> {
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read '{}': {e}", dir.display()))?
+        .flatten()
+        .collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+            continue;
+        };
+        if !metadata.is_dir() && !metadata.is_file() {
+            continue;
+        }
+        let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+        if members.len() >= limits.max_entries {
+            return Err(format!("workspace has more than {} entries", limits.max_entries));
+        }
+        if metadata.is_file() {
+            *bytes += metadata.len();
+            if *bytes > limits.max_bytes {
+                return Err(format!("workspace holds more than {} bytes", limits.max_bytes));
+            }
+        }
+
+        members.push(Member {
+            name: name.clone(),
+            path: entry.path(),
+            is_dir: metadata.is_dir(),
+            mode: file_mode(&metadata),
+            size: if metadata.is_file() { metadata.len() } else { 0 },
+        });
+        if metadata.is_dir() {
+            collect(&entry.path(), &format!("{name}/"), limits, members, bytes)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o777
+}
+
+#[cfg(not(unix))]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    if metadata.is_dir() { 0o755 } else { 0o644 }
+}
+
+/// Open a member file, refusing one replaced by a link since it was
+/// collected
+#[cfg(unix)]
+fn open_member(path: &Path) -> io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    fs::OpenOptions::new().read(true).custom_flags(libc::O_NOFOLLOW).open(path)
+}
+
+#[cfg(not(unix))]
+fn open_member(path: &Path) -> io::Result<fs::File> {
+    fs::File::open(path)
+}
+
+fn write_tar<W: Write>(inner: W, members: &[Member]) -> io::Result<W> {
+    let mut builder = tar::Builder::new(inner);
+    for member in members {
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(member.mode);
+        if member.is_dir {
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_size(0);
+            builder.append_data(&mut header, format!("{}/", member.name), io::empty())?;
+        } else {
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_size(member.size);
+            let file = open_member(&member.path)?;
+            builder.append_data(&mut header, &member.name, file.take(member.size))?;
+        }
+    }
+    builder.into_inner()
+}
+
+fn write_zip(members: &[Member]) -> io::Result<Vec<u8>> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for member in members {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .unix_permissions(member.mode);
+        if member.is_dir {
+            writer.add_directory(member.name.as_str(), options)?;
+        } else {
+            writer.start_file(member.name.as_str(), options)?;
+            io::copy(&mut open_member(&member.path)?, &mut writer)?;
+        }
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archives_round_trip_and_bombs_are_refused() {
+        let base = std::env::temp_dir().join(format!("cylo_archive_{}", uuid::Uuid::new_v4()));
+        let (source, copy) = (base.join("source"), base.join("copy"));
+        fs::create_dir_all(source.join("out/logs")).unwrap();
+        fs::write(source.join("main.py"), "print(1)").unwrap();
+        fs::write(source.join("out/logs/run.txt"), "done").unwrap();
+
+        let limits = ArchiveLimits::default();
+        for format in [ArchiveFormat::Tar, ArchiveFormat::TarGz, ArchiveFormat::Zip] {
+            let output = OutputArchive::new(format).with_subtree("out");
+            let archive = pack(&source, &output, &limits).unwrap();
+            assert_eq!(ArchiveFormat::detect(&archive.data), format);
+
+            let _ = fs::remove_dir_all(&copy);
+            unpack(&archive, &copy, &limits).unwrap();
+            assert_eq!(fs::read_to_string(copy.join("logs/run.txt")).unwrap(), "done");
+            assert!(!copy.join("main.py").exists());
+        }
+
+        let whole = pack(&source, &OutputArchive::new(ArchiveFormat::Zip), &limits).unwrap();
+        let tiny = ArchiveLimits {
+            max_bytes: 4,
+            ..limits
+        };
+        assert!(unpack(&whole, &base.join("tiny"), &tiny).unwrap_err().contains("bytes"));
+        let few = ArchiveLimits {
+            max_entries: 2,
+            ..limits
+        };
+        assert!(unpack(&whole, &base.join("few"), &few).unwrap_err().contains("entries"));
+        let escaping = OutputArchive::new(ArchiveFormat::Tar).with_subtree("../");
+        assert!(pack(&source, &escaping, &limits).is_err());
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, "escape", "/etc").unwrap();
+        let link = WorkspaceArchive::from_bytes(builder.into_inner().unwrap());
+        assert!(unpack(&link, &base.join("link"), &limits).is_err());
+
+        // A subtree reached through a link the code made is not packed
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("/", source.join("host")).unwrap();
+            let through = OutputArchive::new(ArchiveFormat::Tar).with_subtree("host/etc");
+            assert!(pack(&source, &through, &limits).unwrap_err().contains("escapes"));
+            let whole = pack(&source, &OutputArchive::new(ArchiveFormat::Tar), &limits).unwrap();
+            let _ = fs::remove_dir_all(&copy);
+            unpack(&whole, &copy, &limits).unwrap();
+            assert!(!copy.join("host").exists());
+        }
+
+        let _ = fs::remove_dir_all(&base);
+    }
+}
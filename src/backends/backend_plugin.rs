@@ -0,0 +1,699 @@
+// ============================================================================
+// File: packages/cylo/src/backends/backend_plugin.rs
+// ----------------------------------------------------------------------------
+// Backends loaded from plugins at runtime.
+//
+// A backend plugin is a WebAssembly module run through the same Extism
+// machinery as SweetMCP plugins, or, with the `dylib-plugins` feature, a
+// native shared library. Either way the host and the plugin only exchange
+// JSON over two calls, so the ABI stays stable across compilers and cylo
+// releases:
+//
+// - `cylo_negotiate` receives the host's `HostOffer` and answers with the
+//   plugin's `PluginManifest`: its name, ABI version, languages,
+//   capabilities, and the request features it requires or can use.
+// - `cylo_execute` receives a `PluginRequest` carrying only the negotiated
+//   features and answers with a `PluginResponse`.
+//
+// A request the plugin did not negotiate the means to honour is refused:
+// its timeout, limits, host paths and network policy must either be
+// negotiated or be enforced by the host itself, as it does through the
+// grants of a WASM plugin. Every call is also bounded by the request's
+// timeout on the host side.
+//
+// WASM plugins export both as Extism functions. Native plugins export
+// `cylo_plugin_call(name, input) -> output` and `cylo_plugin_free(output)`
+// over NUL-terminated UTF-8 strings; they run in the host process and are
+// trusted like any linked code. A loaded plugin is registered as a
+// third-party backend and routed to like any other.
+// ============================================================================
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use extism::Plugin;
+use serde::{Deserialize, Serialize};
+
+use crate::backends::paths::exposed_paths;
+use crate::backends::{blocking, cpuset, io_throttle};
+use crate::backends::registry::{self, BackendFactory};
+use crate::backends::sweetmcp_plugin::PluginGrants;
+use crate::backends::{
+    AsyncTask, BackendCapabilities, BackendConfig, BackendError, BackendResult, ExecutionBackend,
+    ExecutionRequest, ExecutionResult, HealthCheckLevel, HealthStatus,
+};
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Version of the plugin ABI this host speaks
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Request features the host can forward to a plugin
+pub const HOST_FEATURES: [&str; 7] =
+    ["input", "files", "env", "timeout", "limits", "paths", "network"];
+
+/// Call answering a `HostOffer` with a `PluginManifest`
+const NEGOTIATE_CALL: &str = "cylo_negotiate";
+
+/// Call answering a `PluginRequest` with a `PluginResponse`
+const EXECUTE_CALL: &str = "cylo_execute";
+
+/// Time a plugin call has beyond the request's timeout and grace before
+/// the host stops waiting for it
+const CALL_GRACE: Duration = Duration::from_secs(5);
+
+/// What the host offers a plugin during negotiation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostOffer {
+    /// ABI version the host speaks
+    pub abi_version: u32,
+    /// cylo release loading the plugin
+    pub host_version: String,
+    /// Request features the host can forward
+    pub features: Vec<String>,
+}
+
+impl HostOffer {
+    fn current() -> Self {
+        Self {
+            abi_version: PLUGIN_ABI_VERSION,
+            host_version: env!("CARGO_PKG_VERSION").to_string(),
+            features: HOST_FEATURES.iter().map(|feature| feature.to_string()).collect(),
+        }
+    }
+}
+
+/// What a plugin declares about itself during negotiation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    /// ABI version the plugin speaks
+    pub abi_version: u32,
+    /// Backend name the plugin registers under
+    pub name: String,
+    /// Plugin release
+    pub version: String,
+    /// Languages the backend runs
+    pub languages: Vec<String>,
+    /// What the backend offers executions
+    pub capabilities: BackendCapabilities,
+    /// Performance rating (0-100) routing weighs the backend by
+    #[serde(default)]
+    pub performance_rating: Option<u8>,
+    /// Request features the plugin cannot work without
+    #[serde(default)]
+    pub required_features: Vec<String>,
+    /// Request features the plugin uses when the host offers them
+    #[serde(default)]
+    pub optional_features: Vec<String>,
+}
+
+impl PluginManifest {
+    /// Settle on the features both sides support
+    ///
+    /// # Returns
+    /// The features requests may use, or why the plugin cannot be loaded
+    pub fn negotiate(&self, offer: &HostOffer) -> Result<Vec<String>, String> {
+        if self.abi_version != offer.abi_version {
+            return Err(format!(
+                "plugin '{}' speaks ABI version {}, the host speaks {}",
+                self.name, self.abi_version, offer.abi_version
+            ));
+        }
+        if self.languages.is_empty() {
+            return Err(format!("plugin '{}' declares no languages", self.name));
+        }
+        if let Some(missing) = self
+            .required_features
+            .iter()
+            .find(|feature| !offer.features.contains(feature))
+        {
+            return Err(format!(
+                "plugin '{}' requires feature '{}', which the host does not offer",
+                self.name, missing
+            ));
+        }
+
+        let mut features: Vec<String> = self
+            .required_features
+            .iter()
+            .chain(&self.optional_features)
+            .filter(|feature| offer.features.contains(feature))
+            .cloned()
+            .collect();
+        features.sort();
+        features.dedup();
+        Ok(features)
+    }
+}
+
+/// Request handed to a plugin's `cylo_execute`
+///
+/// Fields of features that were not negotiated are always empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginRequest {
+    /// ABI version of the request
+    pub abi_version: u32,
+    /// Source code to run
+    pub code: String,
+    /// Language of the code
+    pub language: String,
+    /// Standard input (`input` feature)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    /// Workspace files by relative path (`files` feature)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, String>,
+    /// Environment variables (`env` feature)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Timeout in milliseconds (`timeout` feature)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Memory limit in bytes (`limits` feature)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory: Option<u64>,
+    /// Process limit (`limits` feature)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<u32>,
+    /// CPU time limit in seconds (`limits` feature)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cpu_time: Option<u64>,
+    /// Largest file the program may write, in bytes (`limits` feature)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+    /// Host paths to expose to the program (`paths` feature)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<PluginPath>,
+    /// Whether the program must have no network access (`network` feature)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub network_disabled: bool,
+}
+
+/// Host path a plugin exposes to the program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginPath {
+    /// Canonical host path
+    pub source: PathBuf,
+    /// Path the program sees, as given in the request
+    pub target: PathBuf,
+    /// Whether the program may write through it
+    pub writable: bool,
+}
+
+impl PluginRequest {
+    /// Translate a request for a plugin with the negotiated features
+    ///
+    /// # Arguments
+    /// * `request` - Request to translate
+    /// * `features` - Features the plugin negotiated
+    /// * `confined` - Whether the host confines the plugin itself, as it
+    ///   does a WASM plugin's time, memory and network through its grants
+    ///
+    /// # Returns
+    /// The plugin request, or why the plugin cannot serve the request, e.g.
+    /// it uses a feature that was not negotiated
+    fn build(
+        request: &ExecutionRequest,
+        features: &[String],
+        confined: bool,
+    ) -> Result<Self, String> {
+        // Plugins are handed their input in one piece
+        if request.input_stream.is_some() {
+            return Err("the plugin cannot stream stdin".to_string());
+        }
+        if request.dns.is_some() {
+            return Err("the plugin cannot apply a DNS policy".to_string());
+        }
+        let limits = &request.limits;
+        let unconfined_limits = limits.max_cpu_time.is_some() || limits.max_file_size.is_some();
+        let confined_limits = limits.max_memory.is_some() || limits.max_processes.is_some();
+        let paths = exposed_paths(request).map_err(|e| e.to_string())?;
+
+        let enabled = |feature: &str| features.iter().any(|f| f == feature);
+        let used = [
+            ("input", request.input.is_some()),
+            ("files", !request.files.is_empty() || !request.blob_files.is_empty()),
+            ("env", !request.env_vars.is_empty()),
+            ("timeout", !confined),
+            ("limits", unconfined_limits || (confined_limits && !confined)),
+            ("paths", !paths.is_empty()),
+            ("network", !request.network_allowed() && !confined),
+        ];
+        let unsupported = used.into_iter().find(|(feature, used)| *used && !enabled(feature));
+        if let Some((feature, _)) = unsupported {
+            return Err(format!("the plugin does not support the '{feature}' feature"));
+        }
+        // Blobs are inlined; plugins see them as ordinary text files
+        let mut files = request.files.clone();
+        for (path, blob) in &request.blob_files {
+            let content = std::fs::read(&blob.source)
+                .map_err(|e| format!("Failed to read blob {}: {}", blob.digest, e))?;
+            let text = String::from_utf8(content).map_err(|_| {
+                format!("blob {} for {} is not UTF-8 text, which plugins take", blob.digest, path)
+            })?;
+            files.insert(path.clone(), text);
+        }
+
+        let with_limits = enabled("limits");
+        Ok(Self {
+            abi_version: PLUGIN_ABI_VERSION,
+            code: request.code.clone(),
+            language: request.language.clone(),
+            input: request.input.clone(),
+            files,
+            env: request.env_vars.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            timeout_ms: enabled("timeout")
+                .then(|| u64::try_from(request.timeout.as_millis()).unwrap_or(u64::MAX)),
+            max_memory: limits.max_memory.filter(|_| with_limits),
+            max_processes: limits.max_processes.filter(|_| with_limits),
+            max_cpu_time: limits.max_cpu_time.filter(|_| with_limits),
+            max_file_size: limits.max_file_size.filter(|_| with_limits),
+            paths: paths
+                .into_iter()
+                .map(|path| PluginPath {
+                    source: path.source,
+                    target: path.target,
+                    writable: path.writable,
+                })
+                .collect(),
+            network_disabled: enabled("network") && !request.network_allowed(),
+        })
+    }
+}
+
+/// Answer of a plugin's `cylo_execute`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginResponse {
+    /// Exit code of the program
+    pub exit_code: i32,
+    /// Standard output
+    #[serde(default)]
+    pub stdout: String,
+    /// Standard error
+    #[serde(default)]
+    pub stderr: String,
+    /// Whether the program was terminated on its timeout
+    #[serde(default)]
+    pub timed_out: bool,
+    /// Backend-specific details, copied into the result metadata
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// Code the calls are made into
+#[derive(Debug)]
+enum Runtime {
+    /// WebAssembly module, instantiated per call with that call's grants
+    Wasm(PathBuf),
+    /// Native shared library
+    #[cfg(feature = "dylib-plugins")]
+    Dylib(native::Library),
+}
+
+impl Runtime {
+    fn open(path: &Path) -> BackendResult<Self> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("wasm") => Ok(Self::Wasm(path.to_path_buf())),
+            #[cfg(feature = "dylib-plugins")]
+            Some("so" | "dylib" | "dll") => native::Library::open(path).map(Self::Dylib),
+            #[cfg(not(feature = "dylib-plugins"))]
+            Some("so" | "dylib" | "dll") => Err(BackendError::InvalidConfig {
+                backend: "plugin",
+                details: "native plugins need cylo's dylib-plugins feature".to_string(),
+            }),
+            _ => Err(BackendError::InvalidConfig {
+                backend: "plugin",
+                details: format!("{} is not a .wasm module or shared library", path.display()),
+            }),
+        }
+    }
+
+    fn call(&self, name: &str, input: String, grants: &PluginGrants) -> Result<String, String> {
+        match self {
+            Self::Wasm(path) => {
+                let mut plugin = Plugin::new(grants.manifest(path), [], true)
+                    .map_err(|e| format!("Failed to load plugin: {e}"))?;
+                plugin
+                    .call::<String, String>(name, input)
+                    .map_err(|e| format!("Plugin call {name} failed: {e}"))
+            }
+            #[cfg(feature = "dylib-plugins")]
+            Self::Dylib(library) => library.call(name, &input),
+        }
+    }
+}
+
+/// A negotiated plugin, shared by its factory and backend instances
+#[derive(Debug)]
+struct LoadedPlugin {
+    /// Backend name, as registered
+    name: &'static str,
+    manifest: PluginManifest,
+    /// Features requests may use
+    features: Vec<String>,
+    /// Languages, in the form `ExecutionBackend` hands them out
+    languages: Vec<&'static str>,
+    path: PathBuf,
+    runtime: Runtime,
+}
+
+/// Load a backend plugin and register it as a third-party backend
+///
+/// Plugins are meant to be loaded once per process: the backend name and
+/// languages live as long as the process does.
+///
+/// # Arguments
+/// * `path` - `.wasm` module, or `.so`/`.dylib`/`.dll` library with the
+///   `dylib-plugins` feature
+///
+/// # Returns
+/// The plugin's manifest, or an error if it cannot be loaded, speaks
+/// another ABI version, requires features the host lacks, or its name is
+/// taken
+pub fn load_backend_plugin(path: &Path) -> CyloResult<PluginManifest> {
+    let runtime = Runtime::open(path)?;
+    let offer = HostOffer::current();
+    let offer_json = serde_json::to_string(&offer)
+        .map_err(|e| CyloError::internal(format!("Failed to encode host offer: {e}")))?;
+    let answer = runtime
+        .call(NEGOTIATE_CALL, offer_json, &PluginGrants::default())
+        .map_err(|e| CyloError::validation(format!("{}: {}", path.display(), e)))?;
+    let manifest: PluginManifest = serde_json::from_str(&answer).map_err(|e| {
+        CyloError::validation(format!("{}: invalid plugin manifest: {}", path.display(), e))
+    })?;
+    let features = manifest.negotiate(&offer).map_err(CyloError::validation)?;
+
+    let leak = |text: &str| -> &'static str { Box::leak(text.to_string().into_boxed_str()) };
+    let plugin = LoadedPlugin {
+        name: leak(&manifest.name),
+        languages: manifest.languages.iter().map(|language| leak(language)).collect(),
+        manifest: manifest.clone(),
+        features,
+        path: path.to_path_buf(),
+        runtime,
+    };
+    registry::register_backend(plugin.name, Box::new(PluginFactory(Arc::new(plugin))))?;
+    Ok(manifest)
+}
+
+struct PluginFactory(Arc<LoadedPlugin>);
+
+impl BackendFactory for PluginFactory {
+    fn create(
+        &self,
+        _env_config: &str,
+        config: BackendConfig,
+    ) -> CyloResult<Box<dyn ExecutionBackend>> {
+        Ok(Box::new(PluginBackend {
+            plugin: Arc::clone(&self.0),
+            config,
+        }))
+    }
+
+    fn detect(&self) -> Result<(), String> {
+        if self.0.path.exists() {
+            Ok(())
+        } else {
+            Err(format!("Plugin file not found: {}", self.0.path.display()))
+        }
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        self.0.manifest.capabilities.clone()
+    }
+
+    fn performance_rating(&self) -> u8 {
+        self.0.manifest.performance_rating.unwrap_or(50).min(100)
+    }
+}
+
+/// Backend instance running executions through a loaded plugin
+#[derive(Debug)]
+struct PluginBackend {
+    plugin: Arc<LoadedPlugin>,
+    config: BackendConfig,
+}
+
+impl ExecutionBackend for PluginBackend {
+    fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
+        let plugin = Arc::clone(&self.plugin);
+        let config = self.config.clone();
+
+        // Plugin calls block, native ones for as long as the program runs
+        tokio::spawn(async move {
+            let name = plugin.name;
+            // WASM calls time out on their own first, with their output
+            let request_timeout = request.timeout;
+            let limit = request.timeout + request.termination_grace + CALL_GRACE;
+            let called = blocking::backend_io().run(move || {
+                let started = Instant::now();
+                let mut result = execute(&plugin, &config, &request);
+                result.duration = started.elapsed();
+                result
+                    .metadata
+                    .insert("plugin.version".to_string(), plugin.manifest.version.clone());
+                result
+            });
+            let mut result = match tokio::time::timeout(limit, called).await {
+                Ok(called) => called.unwrap_or_else(|e| {
+                    ExecutionResult::failure(-1, format!("Plugin call failed: {e}"))
+                }),
+                // A native call cannot be stopped; it is abandoned instead
+                Err(_) => {
+                    let mut result = ExecutionResult::timed_out("", "", request_timeout);
+                    result.metadata.insert(
+                        "termination".to_string(),
+                        "abandoned: the plugin call did not return".to_string(),
+                    );
+                    result
+                }
+            };
+            result
+                .metadata
+                .insert("plugin.name".to_string(), name.to_string());
+            result
+        })
+    }
+
+    fn health_check(&self, _level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
+        let plugin = Arc::clone(&self.plugin);
+        tokio::spawn(async move {
+            if plugin.path.exists() {
+                HealthStatus::healthy("Plugin loaded")
+                    .with_metric("plugin_version", plugin.manifest.version.as_str())
+            } else {
+                HealthStatus::unhealthy(format!(
+                    "Plugin file not found: {}",
+                    plugin.path.display()
+                ))
+            }
+        })
+    }
+
+    fn cleanup(&self) -> AsyncTask<CyloResult<()>> {
+        // Plugins hold nothing between calls that the host could release
+        tokio::spawn(async { Ok(()) })
+    }
+
+    fn get_config(&self) -> &BackendConfig {
+        &self.config
+    }
+
+    fn backend_type(&self) -> &'static str {
+        self.plugin.name
+    }
+
+    fn supports_language(&self, language: &str) -> bool {
+        self.plugin.languages.contains(&language)
+    }
+
+    fn supported_languages(&self) -> &[&'static str] {
+        &self.plugin.languages
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        self.plugin.manifest.capabilities.clone()
+    }
+}
+
+fn execute(
+    plugin: &LoadedPlugin,
+    config: &BackendConfig,
+    request: &ExecutionRequest,
+) -> ExecutionResult {
+    let confined = matches!(plugin.runtime, Runtime::Wasm(_));
+    let wire = io_throttle::reject_unthrottled(request, plugin.name)
+        .and_then(|()| cpuset::reject_unpinnable(request, plugin.name))
+        .map_err(|e| e.to_string())
+        .and_then(|()| PluginRequest::build(request, &plugin.features, confined));
+    let wire = match wire {
+        Ok(wire) => wire,
+        Err(e) => return ExecutionResult::failure(1, format!("Plugin '{}': {}", plugin.name, e)),
+    };
+    let grants = match &plugin.runtime {
+        Runtime::Wasm(_) => match PluginGrants::derive(config, request) {
+            Ok(grants) => grants,
+            Err(e) => return ExecutionResult::failure(1, format!("Invalid plugin grants: {e}")),
+        },
+        #[cfg(feature = "dylib-plugins")]
+        Runtime::Dylib(_) => PluginGrants::default(),
+    };
+
+    let response = serde_json::to_string(&wire)
+        .map_err(|e| format!("Request serialization failed: {e}"))
+        .and_then(|input| plugin.runtime.call(EXECUTE_CALL, input, &grants))
+        .and_then(|output| {
+            serde_json::from_str::<PluginResponse>(&output)
+                .map_err(|e| format!("Response parsing failed: {e}: {output}"))
+        });
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => return ExecutionResult::failure(1, e),
+    };
+
+    let mut result = if response.timed_out {
+        ExecutionResult::timed_out(response.stdout, response.stderr, request.timeout)
+    } else {
+        let mut result = ExecutionResult::failure(response.exit_code, response.stderr);
+        result.stdout = response.stdout;
+        result
+    };
+    result.metadata.extend(response.metadata);
+    // Native plugins run in the host process; only WASM ones are confined
+    // by their grants
+    if confined {
+        result.security = Some(grants.security_report());
+        grants.record(&mut result.metadata);
+    }
+    result
+}
+
+#[cfg(feature = "dylib-plugins")]
+mod native {
+    use std::ffi::{CStr, CString, c_char};
+    use std::path::Path;
+
+    use crate::backends::{BackendError, BackendResult};
+
+    type CallFn = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_char;
+    type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+    /// Native plugin library
+    #[derive(Debug)]
+    pub(super) struct Library(libloading::Library);
+
+    impl Library {
+        pub(super) fn open(path: &Path) -> BackendResult<Self> {
+            // SAFETY: loading runs the library's initialisers; native
+            // plugins are trusted code, like any linked dependency
+            let library = unsafe { libloading::Library::new(path) }.map_err(|e| {
+                BackendError::InvalidConfig {
+                    backend: "plugin",
+                    details: format!("Failed to load {}: {}", path.display(), e),
+                }
+            })?;
+            Ok(Self(library))
+        }
+
+        pub(super) fn call(&self, name: &str, input: &str) -> Result<String, String> {
+            let name = CString::new(name).map_err(|e| e.to_string())?;
+            let input = CString::new(input).map_err(|e| format!("Plugin input: {e}"))?;
+            // SAFETY: the symbols' signatures are fixed by the plugin ABI;
+            // the output is only read before it is handed back to the
+            // plugin's own free function
+            unsafe {
+                let call = self
+                    .0
+                    .get::<CallFn>(b"cylo_plugin_call\0")
+                    .map_err(|e| format!("Plugin lacks cylo_plugin_call: {e}"))?;
+                let free = self
+                    .0
+                    .get::<FreeFn>(b"cylo_plugin_free\0")
+                    .map_err(|e| format!("Plugin lacks cylo_plugin_free: {e}"))?;
+                let output = call(name.as_ptr(), input.as_ptr());
+                if output.is_null() {
+                    return Err(format!("Plugin call {} failed", name.to_string_lossy()));
+                }
+                let text = CStr::from_ptr(output).to_string_lossy().into_owned();
+                free(output);
+                Ok(text)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiation_settles_on_shared_features() {
+        let offer = HostOffer::current();
+        let manifest: PluginManifest = serde_json::from_value(serde_json::json!({
+            "abi_version": PLUGIN_ABI_VERSION,
+            "name": "Nomad",
+            "version": "0.3.0",
+            "languages": ["python"],
+            "capabilities": BackendCapabilities::of_backend("LandLock"),
+            "required_features": ["env"],
+            "optional_features": ["timeout", "gpu", "env"],
+        }))
+        .unwrap();
+        let features = manifest.negotiate(&offer).unwrap();
+        assert_eq!(features, ["env", "timeout"]);
+
+        let request = ExecutionRequest::new("print(1)", "python").with_env("A", "1");
+        let wire = PluginRequest::build(&request, &features, false).unwrap();
+        assert_eq!(wire.env["A"], "1");
+        assert!(wire.timeout_ms.is_some() && wire.max_memory.is_none());
+        let unsupported = PluginRequest::build(&request.clone().with_input("x"), &features, false);
+        assert!(unsupported.unwrap_err().contains("'input'"));
+
+        // What the plugin cannot honour is refused, unless the host
+        // confines the plugin itself
+        let mut limited = request.clone();
+        limited.limits.max_memory = Some(64 * 1024 * 1024);
+        limited.limits.max_network_bandwidth = Some(0);
+        let refused = PluginRequest::build(&limited, &features, false).unwrap_err();
+        assert!(refused.contains("'limits'"), "{refused}");
+        assert!(PluginRequest::build(&limited, &features, true).is_ok());
+        limited.limits.max_cpu_time = Some(1);
+        assert!(PluginRequest::build(&limited, &features, true).is_err());
+        let untimed = PluginRequest::build(&request, &["env".to_string()], false);
+        assert!(untimed.unwrap_err().contains("'timeout'"));
+        let dir = tempfile::tempdir().unwrap();
+        let exposed = request.clone().with_readable_path(dir.path());
+        assert!(PluginRequest::build(&exposed, &features, true).is_err());
+        let all: Vec<String> = HOST_FEATURES.iter().map(|f| f.to_string()).collect();
+        let wire = PluginRequest::build(&exposed, &all, false).unwrap();
+        assert!(!wire.paths[0].writable && !wire.network_disabled);
+
+        // Blobs are handed over as text only
+        let blob = dir.path().join("blob");
+        std::fs::write(&blob, [0xff, 0xfe]).unwrap();
+        let mut binary = request.clone();
+        binary.blob_files.insert(
+            "data.bin".to_string(),
+            crate::backends::BlobFile {
+                digest: "sha256:ff".to_string(),
+                source: blob,
+            },
+        );
+        let refused = PluginRequest::build(&binary, &all, false).unwrap_err();
+        assert!(refused.contains("not UTF-8"), "{refused}");
+
+        let newer = PluginManifest {
+            abi_version: PLUGIN_ABI_VERSION + 1,
+            ..manifest.clone()
+        };
+        assert!(newer.negotiate(&offer).is_err());
+        let demanding = PluginManifest {
+            required_features: vec!["gpu".to_string()],
+            ..manifest
+        };
+        assert!(demanding.negotiate(&offer).unwrap_err().contains("gpu"));
+
+        assert!(load_backend_plugin(Path::new("/tmp/backend.tar")).is_err());
+    }
+}
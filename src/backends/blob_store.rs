@@ -0,0 +1,422 @@
+// ============================================================================
+// File: packages/cylo/src/backends/blob_store.rs
+// ----------------------------------------------------------------------------
+// Content-addressed store for large execution inputs and outputs.
+//
+// Datasets, model weights and other large inputs are put into the store
+// once and requests reference them by their sha256 digest, so the same
+// megabytes are not embedded in every request and are kept on disk only
+// once however many executions, or backends, use them. Backends copy a
+// referenced blob into the workspace, so code can modify its copy without
+// corrupting the stored content. Requests can have large outputs stored
+// the same way, and their results then reference them by digest.
+// ============================================================================
+
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::backends::image_ref::is_valid_digest;
+use crate::backends::paths::relative_inside;
+use crate::backends::{BackendError, BackendResult, ExecutionResult};
+use crate::config::RamdiskConfig;
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Directory of the blobs, named by the hex part of their digest
+const BLOBS_DIR: &str = "sha256";
+
+/// Directory uploads are written to before they are moved into place
+const UPLOAD_DIR: &str = "uploads";
+
+/// A blob in a store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blob {
+    /// `sha256:` digest of the content
+    pub digest: String,
+    /// File holding the content
+    pub path: PathBuf,
+    /// Content size in bytes
+    pub size: u64,
+}
+
+/// A blob written into the workspace of an execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobFile {
+    /// `sha256:` digest of the content
+    pub digest: String,
+    /// Host file holding the content in the store
+    pub source: PathBuf,
+}
+
+/// Where an execution's large outputs are stored instead of being returned
+/// inline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputBlobs {
+    /// Root of the store the outputs are put into
+    pub root: PathBuf,
+    /// Outputs larger than this many bytes are stored
+    pub threshold_bytes: u64,
+}
+
+/// Content-addressed store of blobs below one directory
+#[derive(Debug, Clone)]
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+impl BlobStore {
+    /// Open a store rooted at `root`; it is created with the first blob
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Store inside a ramdisk, whose blobs vanish with it
+    pub fn in_ramdisk(config: &RamdiskConfig) -> Self {
+        Self::new(config.mount_point.join("blobs"))
+    }
+
+    /// Default store location: `$XDG_DATA_HOME/cylo/blobs`, falling back
+    /// to `~/.local/share/cylo/blobs`
+    pub fn default_root() -> PathBuf {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+            .unwrap_or_else(std::env::temp_dir)
+            .join("cylo")
+            .join("blobs")
+    }
+
+    /// Store root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Store bytes, or find them already stored
+    pub fn put(&self, content: &[u8]) -> CyloResult<Blob> {
+        self.put_reader(content)
+    }
+
+    /// Store a file's content without reading it into memory
+    pub fn put_file(&self, path: &Path) -> CyloResult<Blob> {
+        let file = File::open(path).map_err(|e| {
+            CyloError::validation(format!("Failed to open {}: {}", path.display(), e))
+        })?;
+        self.put_reader(file)
+    }
+
+    /// Look up a blob by digest
+    pub fn get(&self, digest: &str) -> CyloResult<Option<Blob>> {
+        let path = self.blob_path(digest)?;
+        match fs::metadata(&path) {
+            Ok(metadata) => Ok(Some(Blob {
+                digest: digest.to_string(),
+                path,
+                size: metadata.len(),
+            })),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(CyloError::internal(format!("Failed to stat blob {digest}: {e}"))),
+        }
+    }
+
+    /// Read a blob's content
+    pub fn read(&self, digest: &str) -> CyloResult<Vec<u8>> {
+        let blob = self
+            .get(digest)?
+            .ok_or_else(|| CyloError::validation(format!("Blob {digest} is not stored")))?;
+        fs::read(&blob.path)
+            .map_err(|e| CyloError::internal(format!("Failed to read blob {digest}: {e}")))
+    }
+
+    /// Every blob in the store, by digest
+    pub fn list(&self) -> CyloResult<Vec<Blob>> {
+        let entries = match fs::read_dir(self.root.join(BLOBS_DIR)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(CyloError::internal(format!("Failed to list blobs: {e}"))),
+        };
+
+        let mut blobs: Vec<Blob> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let digest = format!("sha256:{}", entry.file_name().to_string_lossy());
+                let size = entry.metadata().ok()?.len();
+                is_valid_digest(&digest).then(|| Blob {
+                    digest,
+                    path: entry.path(),
+                    size,
+                })
+            })
+            .collect();
+        blobs.sort_by(|a, b| a.digest.cmp(&b.digest));
+        Ok(blobs)
+    }
+
+    /// Delete a blob
+    ///
+    /// # Returns
+    /// false if the blob was not stored
+    pub fn remove(&self, digest: &str) -> CyloResult<bool> {
+        match fs::remove_file(self.blob_path(digest)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(CyloError::internal(format!("Failed to remove blob {digest}: {e}"))),
+        }
+    }
+
+    /// Delete blobs neither stored nor referenced by an execution for
+    /// `max_idle`
+    ///
+    /// # Returns
+    /// Digests of the removed blobs
+    pub fn prune(&self, max_idle: Duration) -> CyloResult<Vec<String>> {
+        let now = SystemTime::now();
+        let mut removed = Vec::new();
+        for blob in self.list()? {
+            let idle = fs::metadata(&blob.path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|used| now.duration_since(used).ok());
+            if idle.is_some_and(|idle| idle > max_idle) && self.remove(&blob.digest)? {
+                removed.push(blob.digest);
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Stream content into an upload file while hashing it, then move it
+    /// into place under its digest
+    fn put_reader<R: Read>(&self, mut reader: R) -> CyloResult<Blob> {
+        let uploads = self.root.join(UPLOAD_DIR);
+        fs::create_dir_all(&uploads)
+            .and_then(|()| fs::create_dir_all(self.root.join(BLOBS_DIR)))
+            .map_err(|e| CyloError::internal(format!("Failed to create blob store: {e}")))?;
+        let upload = uploads.join(uuid::Uuid::new_v4().simple().to_string());
+        let store_error = |e: io::Error| {
+            let _ = fs::remove_file(&upload);
+            CyloError::internal(format!("Failed to store blob: {e}"))
+        };
+
+        let mut file = File::create(&upload).map_err(store_error)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 1024 * 1024];
+        let mut size = 0u64;
+        loop {
+            let read = reader.read(&mut buffer).map_err(store_error)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            file.write_all(&buffer[..read]).map_err(store_error)?;
+            size += read as u64;
+        }
+        file.sync_all().map_err(store_error)?;
+        drop(file);
+
+        let hex: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        let digest = format!("sha256:{hex}");
+        let path = self.root.join(BLOBS_DIR).join(&hex);
+        if path.exists() {
+            // Already stored: keep the existing copy, which running
+            // executions may be reading
+            let _ = fs::remove_file(&upload);
+            touch(&path);
+        } else {
+            fs::rename(&upload, &path).map_err(store_error)?;
+        }
+        Ok(Blob { digest, path, size })
+    }
+
+    fn blob_path(&self, digest: &str) -> CyloResult<PathBuf> {
+        if !is_valid_digest(digest) {
+            return Err(CyloError::invalid_request(
+                "blob_files",
+                format!("'{digest}' is not a sha256 digest"),
+            ));
+        }
+        Ok(self.root.join(BLOBS_DIR).join(digest.trim_start_matches("sha256:")))
+    }
+}
+
+/// Check the blobs a request references before it runs
+///
+/// Each must still be in its store, under a name matching its digest.
+/// Referencing a blob counts as using it for `BlobStore::prune`.
+pub(crate) fn admit<'a>(blob_files: impl IntoIterator<Item = &'a BlobFile>) -> CyloResult<()> {
+    let blob_files: Vec<&BlobFile> = blob_files.into_iter().collect();
+    check(blob_files.iter().copied())?;
+    for blob in blob_files {
+        touch(&blob.source);
+    }
+    Ok(())
+}
+
+/// Check that the blobs a request references are still stored, without
+/// counting the reference as a use
+pub(crate) fn check<'a>(blob_files: impl IntoIterator<Item = &'a BlobFile>) -> CyloResult<()> {
+    for blob in blob_files {
+        let hex = blob.digest.trim_start_matches("sha256:");
+        if blob.source.file_name().is_none_or(|name| name != hex) {
+            return Err(CyloError::invalid_request(
+                "blob_files",
+                format!("{} does not hold blob {}", blob.source.display(), blob.digest),
+            ));
+        }
+        if !blob.source.is_file() {
+            return Err(CyloError::invalid_request(
+                "blob_files",
+                format!("blob {} is no longer stored", blob.digest),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Move an execution's outputs larger than the threshold into a store
+///
+/// stdout and stderr are stored as the exact bytes the program wrote and
+/// the workspace archive as packed. Each stored output is emptied in the
+/// result, which lists its blob under the field's name in `blobs`.
+///
+/// # Returns
+/// An error if an output could not be stored; outputs stored before it
+/// are already referenced
+pub(crate) fn offload(result: &mut ExecutionResult, outputs: &OutputBlobs) -> CyloResult<()> {
+    let store = BlobStore::new(&outputs.root);
+    let exceeds = |len: usize| len as u64 > outputs.threshold_bytes;
+
+    if exceeds(result.stdout.len()) {
+        let raw = result.raw_output.as_mut().map(|raw| &mut raw.stdout);
+        let blob = offload_stream(&store, &mut result.stdout, raw)?;
+        result.blobs.insert("stdout".to_string(), blob);
+    }
+    if exceeds(result.stderr.len()) {
+        let raw = result.raw_output.as_mut().map(|raw| &mut raw.stderr);
+        let blob = offload_stream(&store, &mut result.stderr, raw)?;
+        result.blobs.insert("stderr".to_string(), blob);
+    }
+    if let Some(archive) = &mut result.workspace_archive
+        && exceeds(archive.data.len())
+    {
+        let blob = store.put(&archive.data)?;
+        archive.data = Vec::new();
+        result.blobs.insert("workspace_archive".to_string(), blob);
+    }
+    Ok(())
+}
+
+/// Store one output stream, preferring its exact bytes to the decoded text
+fn offload_stream(
+    store: &BlobStore,
+    text: &mut String,
+    raw: Option<&mut bytes::Bytes>,
+) -> CyloResult<Blob> {
+    let blob = match raw.as_deref() {
+        Some(bytes) => store.put(bytes)?,
+        None => store.put(text.as_bytes())?,
+    };
+    *text = String::new();
+    if let Some(raw) = raw {
+        *raw = bytes::Bytes::new();
+    }
+    Ok(blob)
+}
+
+/// Copy a request's blobs into a workspace
+///
+/// # Arguments
+/// * `root` - Sandbox workspace directory
+/// * `blob_files` - Blobs by relative path
+pub fn write_blob_files<'a>(
+    root: &Path,
+    blob_files: impl IntoIterator<Item = (&'a String, &'a BlobFile)>,
+) -> BackendResult<()> {
+    for (name, blob) in blob_files {
+        let target = root.join(relative_inside(name)?);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| BackendError::FileSystemFailed {
+                details: format!("Failed to create directory for {}: {}", name, e),
+            })?;
+        }
+        fs::copy(&blob.source, &target).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to copy blob {} to {}: {}", blob.digest, name, e),
+        })?;
+    }
+    Ok(())
+}
+
+/// Mark a blob as used now
+fn touch(path: &Path) {
+    if let Ok(file) = File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blobs_are_deduplicated_and_copied_into_workspaces() {
+        let root = std::env::temp_dir().join(format!("cylo_blobs_{}", uuid::Uuid::new_v4()));
+        let store = BlobStore::new(&root);
+
+        let blob = store.put(b"weights").unwrap();
+        assert!(is_valid_digest(&blob.digest));
+        assert_eq!(store.put(b"weights").unwrap(), blob);
+        assert_eq!(store.list().unwrap(), vec![blob.clone()]);
+        assert_eq!(store.read(&blob.digest).unwrap(), b"weights");
+        assert!(store.get("sha256:nothex").is_err());
+
+        let file = BlobFile {
+            digest: blob.digest.clone(),
+            source: blob.path.clone(),
+        };
+        assert!(admit([&file]).is_ok());
+        let forged = BlobFile {
+            source: PathBuf::from("/etc/passwd"),
+            ..file.clone()
+        };
+        assert!(admit([&forged]).is_err());
+
+        let workspace = root.join("workspace");
+        let name = "data/model.bin".to_string();
+        write_blob_files(&workspace, [(&name, &file)]).unwrap();
+        assert_eq!(fs::read(workspace.join(&name)).unwrap(), b"weights");
+
+        assert!(store.prune(Duration::from_secs(3600)).unwrap().is_empty());
+        assert!(store.remove(&blob.digest).unwrap());
+        assert!(admit([&file]).is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn large_outputs_are_stored_as_blobs() {
+        let root = std::env::temp_dir().join(format!("cylo_blobs_{}", uuid::Uuid::new_v4()));
+        let outputs = OutputBlobs {
+            root: root.clone(),
+            threshold_bytes: 4,
+        };
+        let mut result = ExecutionResult::success("a large table");
+        result.stderr = "ok".to_string();
+        offload(&mut result, &outputs).unwrap();
+
+        assert!(result.stdout.is_empty());
+        assert_eq!(result.stderr, "ok");
+        let stdout = &result.blobs["stdout"];
+        assert_eq!(stdout.size, 13);
+        let store = BlobStore::new(&root);
+        assert_eq!(store.read(&stdout.digest).unwrap(), b"a large table");
+        assert!(!result.blobs.contains_key("stderr"));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
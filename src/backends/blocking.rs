@@ -0,0 +1,177 @@
+// ============================================================================
+// File: packages/cylo/src/backends/blocking.rs
+// ----------------------------------------------------------------------------
+// Bounded pool for blocking backend IO.
+//
+// Tokio's blocking pool grows to hundreds of threads on demand, so a burst
+// of executions doing synchronous SSH or filesystem work could tie up the
+// whole pool and starve everything else that needs it (DNS lookups, file
+// IO of the runtime itself). Backends run such work through a named pool
+// instead, which caps how much of it runs at once and queues the rest.
+// ============================================================================
+
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinError;
+
+/// Environment variable overriding the backend IO pool's size
+pub const BLOCKING_POOL_SIZE_ENV: &str = "CYLO_BLOCKING_POOL_SIZE";
+
+/// Default number of blocking backend IO jobs run at once
+const DEFAULT_POOL_SIZE: usize = 32;
+
+/// Point-in-time counters of a blocking pool
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockingPoolStats {
+    /// Pool name
+    pub name: &'static str,
+    /// Jobs allowed to run at once
+    pub limit: usize,
+    /// Jobs running now
+    pub running: usize,
+    /// Jobs waiting for a free slot
+    pub queued: usize,
+    /// Jobs finished since start
+    pub completed: u64,
+}
+
+/// Runs blocking jobs on tokio's blocking threads, at most `limit` at once
+#[derive(Debug)]
+pub(crate) struct BlockingPool {
+    name: &'static str,
+    limit: usize,
+    slots: Arc<Semaphore>,
+    counters: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    running: AtomicUsize,
+    queued: AtomicUsize,
+    completed: AtomicU64,
+}
+
+/// Decrements a counter when dropped, so cancelled jobs are not counted
+struct Counted<'a>(&'a AtomicUsize);
+
+impl<'a> Counted<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl BlockingPool {
+    /// Create a pool
+    ///
+    /// # Arguments
+    /// * `name` - Name the pool's counters are reported under
+    /// * `limit` - Jobs allowed to run at once, at least one
+    pub(crate) fn new(name: &'static str, limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            name,
+            limit,
+            slots: Arc::new(Semaphore::new(limit)),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Run a blocking job once a slot is free
+    ///
+    /// Dropping the returned future while the job waits for a slot
+    /// withdraws it; a job that started keeps its slot until it returns.
+    ///
+    /// # Returns
+    /// The job's output, or the join error if it panicked
+    pub(crate) async fn run<T, F>(&self, job: F) -> Result<T, JoinError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let slot = {
+            let _queued = Counted::new(&self.counters.queued);
+            // The semaphore is never closed
+            Arc::clone(&self.slots).acquire_owned().await.ok()
+        };
+
+        let counters = Arc::clone(&self.counters);
+        tokio::task::spawn_blocking(move || {
+            let _slot = slot;
+            let output = {
+                let _running = Counted::new(&counters.running);
+                job()
+            };
+            counters.completed.fetch_add(1, Ordering::Relaxed);
+            output
+        })
+        .await
+    }
+
+    /// Current counters
+    pub(crate) fn stats(&self) -> BlockingPoolStats {
+        BlockingPoolStats {
+            name: self.name,
+            limit: self.limit,
+            running: self.counters.running.load(Ordering::Relaxed),
+            queued: self.counters.queued.load(Ordering::Relaxed),
+            completed: self.counters.completed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Pool backends run blocking IO on: SSH sessions, file copies, host probes
+pub(crate) fn backend_io() -> &'static BlockingPool {
+    static POOL: OnceLock<BlockingPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let limit = std::env::var(BLOCKING_POOL_SIZE_ENV)
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .unwrap_or(DEFAULT_POOL_SIZE);
+        BlockingPool::new("backend-io", limit)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn pool_caps_running_jobs_and_queues_the_rest() {
+        let pool = Arc::new(BlockingPool::new("test", 2));
+        let (release, released) = std::sync::mpsc::channel::<()>();
+        let released = Arc::new(std::sync::Mutex::new(released));
+
+        let jobs: Vec<_> = (0..3)
+            .map(|_| {
+                let pool = Arc::clone(&pool);
+                let released = Arc::clone(&released);
+                tokio::spawn(async move {
+                    pool.run(move || released.lock().unwrap().recv().ok()).await
+                })
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let stats = pool.stats();
+        assert_eq!((stats.running, stats.queued), (2, 1));
+
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+        for job in jobs {
+            assert!(job.await.unwrap().is_ok());
+        }
+        let stats = pool.stats();
+        assert_eq!((stats.running, stats.queued, stats.completed), (0, 0, 3));
+    }
+}
@@ -0,0 +1,340 @@
+// ============================================================================
+// File: packages/cylo/src/backends/budget.rs
+// ----------------------------------------------------------------------------
+// Execution budgets pooled across a series of requests.
+//
+// An agent conversation may run dozens of executions, each within its own
+// limits yet together without bound. A budget caps the CPU time, wall time
+// and number of executions the whole series may use: every request the
+// budget is attached to draws on it as it is admitted and settles its
+// actual usage once it finishes. Drawing holds the most the execution may
+// use, so concurrent executions cannot jointly overspend the budget; a
+// request that finds the rest held rather than spent gets a retryable
+// error instead of being told the budget is exhausted.
+// ============================================================================
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{ExecutionRequest, ExecutionResult};
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Suggested wait before retrying a request whose budget is held by
+/// running executions
+const HELD_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Caps of an execution budget; an unset cap is unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetLimits {
+    /// CPU time all executions together may use
+    pub cpu_time: Option<Duration>,
+    /// Wall time all executions together may run for
+    pub wall_time: Option<Duration>,
+    /// Number of executions that may be admitted
+    pub executions: Option<u32>,
+}
+
+impl BudgetLimits {
+    /// Cap the CPU time all executions together may use
+    pub fn with_cpu_time(mut self, cpu_time: Duration) -> Self {
+        self.cpu_time = Some(cpu_time);
+        self
+    }
+
+    /// Cap the wall time all executions together may run for
+    pub fn with_wall_time(mut self, wall_time: Duration) -> Self {
+        self.wall_time = Some(wall_time);
+        self
+    }
+
+    /// Cap the number of executions that may be admitted
+    pub fn with_executions(mut self, executions: u32) -> Self {
+        self.executions = Some(executions);
+        self
+    }
+}
+
+/// Usage drawn from a budget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    /// CPU time used
+    pub cpu_time: Duration,
+    /// Wall time run
+    pub wall_time: Duration,
+    /// Executions admitted
+    pub executions: u32,
+}
+
+impl BudgetUsage {
+    fn accrue(&mut self, other: &BudgetUsage) {
+        self.cpu_time = self.cpu_time.saturating_add(other.cpu_time);
+        self.wall_time = self.wall_time.saturating_add(other.wall_time);
+        self.executions = self.executions.saturating_add(other.executions);
+    }
+
+    fn deduct(&mut self, other: &BudgetUsage) {
+        self.cpu_time = self.cpu_time.saturating_sub(other.cpu_time);
+        self.wall_time = self.wall_time.saturating_sub(other.wall_time);
+        self.executions = self.executions.saturating_sub(other.executions);
+    }
+}
+
+#[derive(Debug)]
+struct Ledger {
+    limits: BudgetLimits,
+    /// Usage of finished executions
+    spent: BudgetUsage,
+    /// Most the running executions may still use
+    held: BudgetUsage,
+}
+
+impl Ledger {
+    fn remaining(&self) -> BudgetLimits {
+        let mut used = self.spent;
+        used.accrue(&self.held);
+        self.left_after(&used)
+    }
+
+    fn left_after(&self, used: &BudgetUsage) -> BudgetLimits {
+        BudgetLimits {
+            cpu_time: self.limits.cpu_time.map(|cap| cap.saturating_sub(used.cpu_time)),
+            wall_time: self.limits.wall_time.map(|cap| cap.saturating_sub(used.wall_time)),
+            executions: self.limits.executions.map(|cap| cap.saturating_sub(used.executions)),
+        }
+    }
+
+    /// First cap with nothing left, and its limit
+    fn exhausted(&self, left: &BudgetLimits) -> Option<(&'static str, String)> {
+        if left.executions == Some(0) {
+            self.limits.executions.map(|cap| ("executions", cap.to_string()))
+        } else if left.cpu_time == Some(Duration::ZERO) {
+            self.limits.cpu_time.map(|cap| ("cpu_time", format!("{cap:?}")))
+        } else if left.wall_time == Some(Duration::ZERO) {
+            self.limits.wall_time.map(|cap| ("wall_time", format!("{cap:?}")))
+        } else {
+            None
+        }
+    }
+
+    fn check(&self) -> CyloResult<()> {
+        // Every admitted execution counts once it finishes, however it ends,
+        // so only held CPU and wall time may still come back
+        let mut committed = self.spent;
+        committed.executions = committed.executions.saturating_add(self.held.executions);
+        if let Some((resource, limit)) = self.exhausted(&self.left_after(&committed)) {
+            return Err(CyloError::budget_exhausted(resource, limit));
+        }
+        match self.exhausted(&self.remaining()) {
+            Some((resource, _)) => Err(CyloError::budget_held(resource, HELD_RETRY_AFTER)),
+            None => Ok(()),
+        }
+    }
+
+    /// Fit a request's limits into what is left, returning the most it
+    /// may then use
+    fn clamp(&self, request: &mut ExecutionRequest) -> BudgetUsage {
+        let remaining = self.remaining();
+        if let Some(wall_time) = remaining.wall_time {
+            request.timeout = request.timeout.min(wall_time);
+        }
+        // max_cpu_time is whole seconds, so the last execution may run
+        // over by less than a second
+        if let Some(cpu_time) = remaining.cpu_time {
+            let seconds = cpu_time.as_secs_f64().ceil() as u64;
+            let limit = request.limits.max_cpu_time.map_or(seconds, |own| own.min(seconds));
+            request.limits.max_cpu_time = Some(limit);
+        }
+
+        BudgetUsage {
+            cpu_time: match (remaining.cpu_time, request.limits.max_cpu_time) {
+                (Some(cpu_time), Some(limit)) => cpu_time.min(Duration::from_secs(limit)),
+                _ => Duration::ZERO,
+            },
+            wall_time: remaining.wall_time.map_or(Duration::ZERO, |_| request.timeout),
+            executions: 1,
+        }
+    }
+}
+
+/// Budget shared by a series of requests, such as one agent conversation
+///
+/// Clones share the same budget. Attach it with
+/// `ExecutionRequest::with_budget`; once a cap is spent, further requests
+/// fail with `BudgetExhausted` before reaching a backend. While running
+/// executions hold what is left, they fail with the retryable `BudgetHeld`
+/// instead.
+#[derive(Debug, Clone)]
+pub struct ExecutionBudget {
+    ledger: Arc<Mutex<Ledger>>,
+}
+
+impl ExecutionBudget {
+    /// Create a budget with nothing spent
+    pub fn new(limits: BudgetLimits) -> Self {
+        Self {
+            ledger: Arc::new(Mutex::new(Ledger {
+                limits,
+                spent: BudgetUsage::default(),
+                held: BudgetUsage::default(),
+            })),
+        }
+    }
+
+    /// Caps the budget was created with
+    pub fn limits(&self) -> BudgetLimits {
+        self.ledger().limits
+    }
+
+    /// Usage of the executions that have finished
+    pub fn spent(&self) -> BudgetUsage {
+        self.ledger().spent
+    }
+
+    /// What is left of each cap, less what running executions may still use
+    pub fn remaining(&self) -> BudgetLimits {
+        self.ledger().remaining()
+    }
+
+    /// Whether another execution may draw on the budget
+    ///
+    /// # Returns
+    /// Ok(()) while every cap has something left, BudgetExhausted once one
+    /// is spent, BudgetHeld while running executions hold the rest of one
+    pub fn check(&self) -> CyloResult<()> {
+        self.ledger().check()
+    }
+
+    /// Fit a request's timeout and CPU limit into what is left, without
+    /// drawing on the budget
+    pub(crate) fn clamp(&self, request: &mut ExecutionRequest) {
+        self.ledger().clamp(request);
+    }
+
+    /// Admit a request against the budget
+    ///
+    /// Fits the request's limits into what is left and holds that much
+    /// until the returned draw is settled or dropped.
+    ///
+    /// # Returns
+    /// The draw to settle with the execution's result, BudgetExhausted or
+    /// BudgetHeld
+    pub(crate) fn draw(&self, request: &mut ExecutionRequest) -> CyloResult<BudgetDraw> {
+        let mut ledger = self.ledger();
+        ledger.check()?;
+        let held = ledger.clamp(request);
+        ledger.held.accrue(&held);
+        Ok(BudgetDraw {
+            budget: self.clone(),
+            held,
+            started: Instant::now(),
+            used: None,
+        })
+    }
+
+    fn ledger(&self) -> std::sync::MutexGuard<'_, Ledger> {
+        self.ledger.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// One admitted execution's hold on a budget
+///
+/// Dropped without being settled, e.g. when routing fails or the execution
+/// is abandoned, it charges the wall time since it was drawn.
+#[derive(Debug)]
+pub(crate) struct BudgetDraw {
+    budget: ExecutionBudget,
+    held: BudgetUsage,
+    started: Instant,
+    used: Option<BudgetUsage>,
+}
+
+impl BudgetDraw {
+    /// Replace the hold with what the execution actually used
+    pub(crate) fn settle(mut self, result: Option<&ExecutionResult>) {
+        self.used = result.map(|result| BudgetUsage {
+            cpu_time: Duration::from_millis(result.resource_usage.cpu_time_ms),
+            wall_time: result.duration,
+            executions: 1,
+        });
+    }
+}
+
+impl Drop for BudgetDraw {
+    fn drop(&mut self) {
+        let used = self.used.unwrap_or(BudgetUsage {
+            cpu_time: Duration::ZERO,
+            wall_time: self.started.elapsed(),
+            executions: 1,
+        });
+        let mut ledger = self.budget.ledger();
+        ledger.held.deduct(&self.held);
+        ledger.spent.accrue(&used);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_hold_then_settle_until_spent() {
+        let budget = ExecutionBudget::new(
+            BudgetLimits::default()
+                .with_cpu_time(Duration::from_secs(10))
+                .with_wall_time(Duration::from_secs(60))
+                .with_executions(3),
+        );
+
+        // The first draw holds its whole timeout and CPU limit
+        let mut first = ExecutionRequest::new("print(1)", "python")
+            .with_timeout(Duration::from_secs(45));
+        let draw = budget.draw(&mut first).unwrap();
+        assert_eq!(first.limits.max_cpu_time, Some(10));
+        let remaining = budget.remaining();
+        assert_eq!(remaining.wall_time, Some(Duration::from_secs(15)));
+        assert_eq!(remaining.cpu_time, Some(Duration::ZERO));
+
+        // Concurrent requests cannot spend what the first one holds, but
+        // are told to retry since none of it is spent yet
+        let mut second = ExecutionRequest::new("print(2)", "python");
+        let err = budget.draw(&mut second).unwrap_err();
+        assert!(matches!(err, CyloError::BudgetHeld { resource: "cpu_time", .. }));
+        assert!(err.retry_after().is_some());
+
+        let mut result = ExecutionResult::success("1");
+        result.duration = Duration::from_secs(2);
+        result.resource_usage.cpu_time_ms = 1500;
+        draw.settle(Some(&result));
+        assert_eq!(budget.spent().cpu_time, Duration::from_millis(1500));
+        assert_eq!(budget.spent().wall_time, Duration::from_secs(2));
+
+        // What is left caps the next request
+        let mut third = ExecutionRequest::new("print(3)", "python")
+            .with_timeout(Duration::from_secs(300));
+        drop(budget.draw(&mut third).unwrap());
+        assert_eq!(third.timeout, Duration::from_secs(58));
+        assert_eq!(third.limits.max_cpu_time, Some(9));
+        assert_eq!(budget.spent().executions, 2);
+
+        drop(budget.draw(&mut ExecutionRequest::new("print(4)", "python")).unwrap());
+        let err = budget.check().unwrap_err();
+        assert!(matches!(err, CyloError::BudgetExhausted { resource: "executions", .. }));
+    }
+
+    #[test]
+    fn spent_time_is_exhausted_not_held() {
+        let budget =
+            ExecutionBudget::new(BudgetLimits::default().with_cpu_time(Duration::from_secs(1)));
+        let draw = budget.draw(&mut ExecutionRequest::new("loop()", "python")).unwrap();
+        let mut result = ExecutionResult::success("");
+        result.resource_usage.cpu_time_ms = 1200;
+        draw.settle(Some(&result));
+
+        let err = budget.check().unwrap_err();
+        assert!(matches!(err, CyloError::BudgetExhausted { resource: "cpu_time", .. }));
+        assert!(err.retry_after().is_none());
+    }
+}
@@ -0,0 +1,227 @@
+// ============================================================================
+// File: packages/cylo/src/backends/cgroup.rs
+// ----------------------------------------------------------------------------
+// cgroup v2 slices for executions sharing one backend instance.
+//
+// An instance owns a slice below an operator-delegated parent cgroup, and
+// each execution gets its own child slice carrying the request's memory,
+// process, CPU placement and disk bandwidth limits, so concurrent executions
+// cannot starve each other. Cleaning up the instance removes its slice along
+// with any execution slices left in it.
+// ============================================================================
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::os::fd::AsRawFd;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::backends::{ResourceLimits, io_throttle};
+use crate::logging::targets;
+
+/// Backend config key naming a delegated cgroup v2 directory under which
+/// instance and execution slices are created
+pub const CGROUP_PARENT_KEY: &str = "cgroup_parent";
+
+/// Controllers enabled for execution slices
+const CONTROLLERS: &str = "+memory +pids";
+
+/// Controller enabled as well for executions pinned to CPUs or NUMA nodes
+const CPUSET_CONTROLLER: &str = "+cpuset";
+
+/// Controller enabled as well for executions with throttled disk I/O
+const IO_CONTROLLER: &str = "+io";
+
+/// A cgroup removed again when dropped
+#[derive(Debug)]
+pub(crate) struct CgroupSlice {
+    path: PathBuf,
+    procs: File,
+}
+
+impl CgroupSlice {
+    /// Create an execution slice below an instance slice
+    ///
+    /// The instance slice is created on first use and delegates the memory
+    /// and pids controllers to its children, and the cpuset and io
+    /// controllers once an execution is pinned or throttled.
+    ///
+    /// # Arguments
+    /// * `parent` - Delegated cgroup directory from the backend config
+    /// * `instance` - Name of the instance slice
+    /// * `execution` - Name of the execution slice
+    /// * `limits` - Limits written to the execution slice
+    pub(crate) fn create(
+        parent: &Path,
+        instance: &str,
+        execution: &str,
+        limits: &ResourceLimits,
+    ) -> io::Result<Self> {
+        let instance_path = parent.join(instance);
+        if !instance_path.is_dir() {
+            fs::create_dir(&instance_path)?;
+        }
+        // Already enabled controllers are accepted again without error
+        let subtree_control = instance_path.join("cgroup.subtree_control");
+        fs::write(&subtree_control, CONTROLLERS)?;
+        if limits.cpuset.is_some() || limits.numa_nodes.is_some() {
+            fs::write(&subtree_control, CPUSET_CONTROLLER)?;
+        }
+        if limits.throttles_io() {
+            fs::write(&subtree_control, IO_CONTROLLER)?;
+        }
+
+        let path = instance_path.join(execution);
+        fs::create_dir(&path)?;
+        let procs = match OpenOptions::new().write(true).open(path.join("cgroup.procs")) {
+            Ok(procs) => procs,
+            Err(e) => {
+                let _ = fs::remove_dir(&path);
+                return Err(e);
+            }
+        };
+        let slice = Self { path, procs };
+
+        if let Some(bytes) = limits.max_memory {
+            fs::write(slice.path.join("memory.max"), bytes.to_string())?;
+            fs::write(slice.path.join("memory.swap.max"), "0").ok();
+        }
+        if let Some(processes) = limits.max_processes {
+            fs::write(slice.path.join("pids.max"), processes.to_string())?;
+        }
+        if let Some(cpus) = &limits.cpuset {
+            fs::write(slice.path.join("cpuset.cpus"), cpus.to_string())?;
+        }
+        if let Some(nodes) = &limits.numa_nodes {
+            fs::write(slice.path.join("cpuset.mems"), nodes.to_string())?;
+        }
+        if limits.throttles_io() {
+            slice.throttle_io(limits)?;
+        }
+        Ok(slice)
+    }
+
+    /// Cap the slice's bandwidth on every disk
+    ///
+    /// Disks the io controller does not manage, such as some virtual ones,
+    /// refuse the limit; it is an error only when every disk does.
+    fn throttle_io(&self, limits: &ResourceLimits) -> io::Result<()> {
+        let io_max = self.path.join("io.max");
+        let mut refused = None;
+        let mut applied = false;
+        for line in io_throttle::io_max_lines(limits) {
+            match fs::write(&io_max, &line) {
+                Ok(()) => applied = true,
+                Err(e) => refused = Some(e),
+            }
+        }
+        match refused {
+            Some(e) if !applied => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Start `cmd` inside this slice
+    ///
+    /// The child moves itself in between fork and exec, so nothing it
+    /// spawns can escape the slice's limits.
+    pub(crate) fn attach(&self, cmd: &mut Command) {
+        let fd = self.procs.as_raw_fd();
+        // SAFETY: the hook only calls write(2) on an inherited descriptor,
+        // which is async-signal-safe; "0" names the writing process
+        unsafe {
+            cmd.pre_exec(move || {
+                if libc::write(fd, b"0".as_ptr().cast(), 1) == 1 {
+                    Ok(())
+                } else {
+                    Err(io::Error::last_os_error())
+                }
+            });
+        }
+    }
+
+    /// Directory of the slice
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Remove an instance's slice and the execution slices left in it
+///
+/// Slices still holding processes are refused by the kernel and stay; the
+/// next cleanup of the instance tries again.
+///
+/// # Arguments
+/// * `parent` - Delegated cgroup directory from the backend config
+/// * `instance` - Name of the instance slice
+pub(crate) fn remove_instance(parent: &Path, instance: &str) {
+    let instance_path = parent.join(instance);
+    if let Ok(entries) = fs::read_dir(&instance_path) {
+        for entry in entries.filter_map(Result::ok) {
+            if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                remove(&entry.path());
+            }
+        }
+    }
+    if instance_path.is_dir() {
+        remove(&instance_path);
+    }
+}
+
+/// Remove an empty cgroup, logging why not if it cannot be
+fn remove(path: &Path) {
+    if let Err(e) = fs::remove_dir(path) {
+        tracing::debug!(
+            target: targets::BACKEND,
+            "Failed to remove cgroup {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+impl Drop for CgroupSlice {
+    fn drop(&mut self) {
+        // Fails while processes remain; the kernel then keeps the slice
+        // until the instance is cleaned up
+        remove(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_parent_is_an_error() {
+        let parent = std::env::temp_dir().join(format!("cylo-cgroup-{}", uuid::Uuid::new_v4()));
+        let result = CgroupSlice::create(
+            &parent,
+            "instance",
+            "exec",
+            &ResourceLimits::default(),
+        );
+        assert!(result.is_err());
+        assert!(!parent.exists());
+    }
+
+    #[test]
+    fn instance_cleanup_removes_idle_slices() {
+        let parent = tempfile::tempdir().unwrap();
+        let instance = parent.path().join("cylo-owner-instance");
+        fs::create_dir_all(instance.join("exec-a")).unwrap();
+        // A plain directory with a file stands in for a slice with processes
+        fs::create_dir_all(instance.join("exec-b")).unwrap();
+        fs::write(instance.join("exec-b").join("busy"), "").unwrap();
+
+        remove_instance(parent.path(), "cylo-owner-instance");
+        assert!(!instance.join("exec-a").exists());
+        assert!(instance.join("exec-b").exists());
+
+        fs::remove_file(instance.join("exec-b").join("busy")).unwrap();
+        remove_instance(parent.path(), "cylo-owner-instance");
+        assert!(!instance.exists());
+        remove_instance(parent.path(), "cylo-owner-instance");
+    }
+}
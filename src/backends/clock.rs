@@ -0,0 +1,180 @@
+// ============================================================================
+// File: packages/cylo/src/backends/clock.rs
+// ----------------------------------------------------------------------------
+// Virtualized clocks for sandboxed executions.
+//
+// Jails and containers preload libfaketime so the code sees a chosen time
+// instead of the host's; VMs get their guest clock set before the code
+// runs. Time-dependent code can then be tested deterministically, and
+// untrusted code reading the wall clock learns nothing about the host.
+//
+// libfaketime intercepts libc time calls. Statically linked binaries and
+// code issuing raw syscalls bypass it, so only the VM backend hides host
+// time from code that actively tries to read it.
+// ============================================================================
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Result metadata key naming the virtual clock mode an execution ran with
+pub const CLOCK_METADATA: &str = "clock.virtual";
+
+/// Locations distributions install libfaketime to
+pub(crate) const LIBFAKETIME_PATHS: &[&str] = &[
+    "/usr/lib/x86_64-linux-gnu/faketime/libfaketime.so.1",
+    "/usr/lib/aarch64-linux-gnu/faketime/libfaketime.so.1",
+    "/usr/lib64/faketime/libfaketime.so.1",
+    "/usr/lib/faketime/libfaketime.so.1",
+    "/usr/local/lib/faketime/libfaketime.so.1",
+];
+
+/// Clock the executed code observes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum VirtualClock {
+    /// Starts at `at` when the execution starts and advances in real time
+    StartAt {
+        /// Time reported at the start of the execution
+        at: DateTime<Utc>,
+    },
+    /// Reports `at` for the whole execution
+    Frozen {
+        /// Time reported throughout
+        at: DateTime<Utc>,
+    },
+    /// Host time shifted by a fixed number of seconds
+    Offset {
+        /// Seconds added to host time; negative values go back in time
+        seconds: i64,
+    },
+}
+
+impl VirtualClock {
+    /// Short name used in result metadata
+    pub fn mode(&self) -> &'static str {
+        match self {
+            Self::StartAt { .. } => "start_at",
+            Self::Frozen { .. } => "frozen",
+            Self::Offset { .. } => "offset",
+        }
+    }
+
+    /// Value of the `FAKETIME` variable selecting this clock
+    pub fn faketime_spec(&self) -> String {
+        const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+        match self {
+            Self::StartAt { at } => format!("@{}", at.format(FORMAT)),
+            Self::Frozen { at } => at.format(FORMAT).to_string(),
+            Self::Offset { seconds } => format!("{seconds:+}"),
+        }
+    }
+
+    /// Environment that makes libfaketime apply this clock
+    ///
+    /// # Arguments
+    /// * `library` - Path of libfaketime as seen by the sandboxed process
+    pub fn faketime_env(&self, library: &Path) -> Vec<(String, String)> {
+        vec![
+            ("LD_PRELOAD".to_string(), library.display().to_string()),
+            ("FAKETIME".to_string(), self.faketime_spec()),
+        ]
+    }
+
+    /// Time a guest clock must be set to when the execution starts
+    ///
+    /// # Arguments
+    /// * `now` - Current host time
+    pub fn start_time(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::StartAt { at } | Self::Frozen { at } => *at,
+            Self::Offset { seconds } => now + Duration::seconds(*seconds),
+        }
+    }
+}
+
+/// Find libfaketime on the host
+///
+/// # Returns
+/// Path of the library, or None if it is not installed
+pub(crate) fn find_libfaketime() -> Option<PathBuf> {
+    LIBFAKETIME_PATHS
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+}
+
+/// Shell lines that preload libfaketime from wherever the sandbox has it
+///
+/// Used where the sandbox's file layout is not known up front (container
+/// images, VM rootfs). The run fails instead of using the real clock when
+/// the library is missing.
+pub(crate) fn faketime_shell_setup(clock: &VirtualClock) -> String {
+    let candidates = LIBFAKETIME_PATHS.join(" ");
+    format!(
+        "for lib in {candidates}; do [ -f \"$lib\" ] && export LD_PRELOAD=\"$lib\" && break; \
+         done\n\
+         [ -n \"$LD_PRELOAD\" ] || {{ echo 'cylo: libfaketime is not installed' >&2; exit 125; }}\n\
+         export FAKETIME='{}'\n",
+        clock.faketime_spec()
+    )
+}
+
+/// Shell lines applying a clock inside a VM guest before the code runs
+///
+/// A guest clock can be set but not stopped, so frozen clocks fall back to
+/// libfaketime in the guest.
+///
+/// # Arguments
+/// * `clock` - Clock requested for the execution
+/// * `now` - Current host time
+pub(crate) fn guest_clock_setup(clock: &VirtualClock, now: DateTime<Utc>) -> String {
+    match clock {
+        VirtualClock::Frozen { .. } => faketime_shell_setup(clock),
+        _ => format!(
+            "date -u -s @{} > /dev/null || exit 1\n",
+            clock.start_time(now).timestamp()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instant() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-02-29T12:30:00Z")
+            .expect("timestamp")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn clocks_render_faketime_specs() {
+        let at = instant();
+        assert_eq!(VirtualClock::StartAt { at }.faketime_spec(), "@2024-02-29 12:30:00");
+        assert_eq!(VirtualClock::Frozen { at }.faketime_spec(), "2024-02-29 12:30:00");
+        assert_eq!(VirtualClock::Offset { seconds: -3600 }.faketime_spec(), "-3600");
+        assert_eq!(VirtualClock::Offset { seconds: 90 }.faketime_spec(), "+90");
+
+        let env = VirtualClock::Frozen { at }.faketime_env(Path::new("/lib/ft.so"));
+        assert!(env.contains(&("LD_PRELOAD".to_string(), "/lib/ft.so".to_string())));
+    }
+
+    #[test]
+    fn guest_start_time_follows_the_clock() {
+        let now = instant();
+        let offset = VirtualClock::Offset { seconds: 60 };
+        assert_eq!(offset.start_time(now), now + Duration::seconds(60));
+        assert_eq!(VirtualClock::StartAt { at: now }.start_time(Utc::now()), now);
+
+        assert_eq!(
+            guest_clock_setup(&VirtualClock::StartAt { at: now }, Utc::now()),
+            "date -u -s @1709209800 > /dev/null || exit 1\n"
+        );
+
+        let setup = guest_clock_setup(&VirtualClock::Frozen { at: now }, Utc::now());
+        assert!(setup.contains("export FAKETIME='2024-02-29 12:30:00'"), "{setup}");
+        assert!(setup.contains("exit 125"));
+    }
+}
@@ -0,0 +1,140 @@
+// ============================================================================
+// File: packages/cylo/src/backends/compile_phase.rs
+// ----------------------------------------------------------------------------
+// Separate compile and run phases of compiled languages.
+//
+// Compiled languages used to run as one `compile && run` command whose
+// output streams merged the compiler's diagnostics with the program's
+// output, so a caller could not tell code that does not compile from code
+// that crashed. The compile step now ends by printing a marker line with
+// its exit status and timing on both streams; the marker splits the output
+// into a `CompilationPhase` and the run's own output. Backends that invoke
+// the compiler themselves fill the phase in directly.
+// ============================================================================
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::ExecutionResult;
+
+/// Start of the line a compile step prints once the compiler has exited;
+/// the record separator keeps it from matching program output
+const MARKER: &str = "\u{1e}cylo-compiled";
+
+/// Output and outcome of compiling the code, before it ran
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilationPhase {
+    /// Compiler exit code (0 = compiled)
+    pub exit_code: i32,
+    /// Compiler standard output
+    pub stdout: String,
+    /// Compiler diagnostics
+    pub stderr: String,
+    /// Time spent compiling; zero where the sandbox cannot time it
+    pub duration: Duration,
+}
+
+impl CompilationPhase {
+    /// Whether the code compiled
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// Shell command compiling with `compile`, then running `run` if that
+/// succeeded
+///
+/// Timing comes from /proc/uptime, which every Linux sandbox has; the
+/// shell exits with the compiler's status if compiling failed.
+pub(crate) fn compile_then_run(compile: &str, run: &str) -> String {
+    format!(
+        "read -r __cylo_t0 _ 2>/dev/null </proc/uptime; {compile}; __cylo_rc=$?; \
+         read -r __cylo_t1 _ 2>/dev/null </proc/uptime; \
+         __cylo_mark=\"$(printf '\\036')cylo-compiled $__cylo_rc ${{__cylo_t0:-0}} \
+         ${{__cylo_t1:-0}}\"; \
+         echo \"$__cylo_mark\"; echo \"$__cylo_mark\" >&2; \
+         [ \"$__cylo_rc\" -eq 0 ] || exit \"$__cylo_rc\"; {run}"
+    )
+}
+
+/// Move compiler output in front of the marker into `result.compilation`
+///
+/// Results without the marker, e.g. of interpreted languages or cut off
+/// by an output limit, are left as they are.
+pub(crate) fn split_phases(result: &mut ExecutionResult) {
+    let (Some((compile_stdout, marker, run_stdout)), Some((compile_stderr, _, run_stderr))) =
+        (split_at_marker(&result.stdout), split_at_marker(&result.stderr))
+    else {
+        return;
+    };
+    let mut fields = marker.split_whitespace();
+    let exit_code = fields.next().and_then(|code| code.parse().ok()).unwrap_or(-1);
+    let mut uptime = || fields.next().and_then(|secs| secs.parse::<f64>().ok());
+    let duration = match (uptime(), uptime()) {
+        (Some(start), Some(end)) if end >= start => Duration::from_secs_f64(end - start),
+        _ => Duration::ZERO,
+    };
+
+    let phase = CompilationPhase {
+        exit_code,
+        stdout: compile_stdout,
+        stderr: compile_stderr,
+        duration,
+    };
+    result.stdout = run_stdout;
+    result.stderr = run_stderr;
+    result.compilation = Some(phase);
+}
+
+/// Output before the marker line, the marker's fields and output after it
+fn split_at_marker(output: &str) -> Option<(String, &str, String)> {
+    let start = output.find(MARKER)?;
+    let rest = &output[start + MARKER.len()..];
+    let (fields, after) = rest.split_once('\n').unwrap_or((rest, ""));
+    Some((output[..start].to_string(), fields, after.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiler_output_is_split_from_program_output() {
+        let mut compiled = ExecutionResult::success(
+            "\u{1e}cylo-compiled 0 100.25 101.50\nhello\n",
+        );
+        compiled.stderr = "warning: unused variable\n\u{1e}cylo-compiled 0 100.25 101.50\n\
+                           thread 'main' panicked\n"
+            .to_string();
+        split_phases(&mut compiled);
+        let phase = compiled.compilation.as_ref().unwrap();
+        assert!(phase.succeeded());
+        assert_eq!(phase.stderr, "warning: unused variable\n");
+        assert_eq!(phase.duration, Duration::from_millis(1250));
+        assert_eq!(compiled.stdout, "hello\n");
+        assert_eq!(compiled.stderr, "thread 'main' panicked\n");
+
+        let mut broken = ExecutionResult::failure(
+            1,
+            "error[E0425]: cannot find value `x`\n\u{1e}cylo-compiled 1 5 5\n",
+        );
+        broken.stdout = "\u{1e}cylo-compiled 1 5 5\n".to_string();
+        split_phases(&mut broken);
+        let phase = broken.compilation.as_ref().unwrap();
+        assert!(!phase.succeeded());
+        assert!(phase.stderr.starts_with("error[E0425]"));
+        assert!(broken.stderr.is_empty() && broken.stdout.is_empty());
+        assert!(broken.combined_output().contains("error[E0425]"));
+
+        // Interpreted languages print no marker
+        let mut script = ExecutionResult::success("1\n");
+        split_phases(&mut script);
+        assert!(script.compilation.is_none());
+        assert_eq!(script.stdout, "1\n");
+
+        let command = compile_then_run("rustc main.rs -o main", "exec ./main");
+        assert!(command.contains("; rustc main.rs -o main; __cylo_rc=$?;"), "{command}");
+        assert!(command.ends_with("|| exit \"$__cylo_rc\"; exec ./main"), "{command}");
+    }
+}
@@ -0,0 +1,321 @@
+// ============================================================================
+// File: packages/cylo/src/backends/compiler.rs
+// ----------------------------------------------------------------------------
+// Per-request compiler settings for compiled languages.
+//
+// Optimization level, edition and extra flags are typed and checked against
+// an allowlist before any backend sees them. Compilers accept flags that run
+// arbitrary programs or write outside the workspace (`-C linker=`,
+// `-C link-arg=`, `--out-dir`, `-toolexec`, ...), so only flags that change
+// how the code is compiled, never where or with what, get through. Every
+// allowed token is also safe to splice into a shell command unquoted.
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::language;
+
+/// `-C` options rustc may be given
+const RUST_CODEGEN_OPTIONS: &[&str] = &[
+    "opt-level",
+    "debuginfo",
+    "debug-assertions",
+    "overflow-checks",
+    "panic",
+    "codegen-units",
+    "lto",
+    "strip",
+    "target-cpu",
+];
+
+/// Optimization level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptLevel {
+    /// No optimizations
+    #[serde(rename = "0")]
+    None,
+    /// Basic optimizations
+    #[serde(rename = "1")]
+    Basic,
+    /// Full optimizations
+    #[serde(rename = "2")]
+    Full,
+    /// Full optimizations plus aggressive inlining and vectorization
+    #[serde(rename = "3")]
+    Aggressive,
+    /// Optimize for binary size
+    #[serde(rename = "s")]
+    Size,
+    /// Optimize for binary size, also turning off loop vectorization
+    #[serde(rename = "z")]
+    MinSize,
+}
+
+impl OptLevel {
+    /// Value as written in compiler flags
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "0",
+            Self::Basic => "1",
+            Self::Full => "2",
+            Self::Aggressive => "3",
+            Self::Size => "s",
+            Self::MinSize => "z",
+        }
+    }
+}
+
+/// Rust edition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RustEdition {
+    /// Rust 2015
+    #[serde(rename = "2015")]
+    E2015,
+    /// Rust 2018
+    #[serde(rename = "2018")]
+    E2018,
+    /// Rust 2021
+    #[serde(rename = "2021")]
+    E2021,
+    /// Rust 2024
+    #[serde(rename = "2024")]
+    E2024,
+}
+
+impl RustEdition {
+    /// Value as written in compiler flags
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::E2015 => "2015",
+            Self::E2018 => "2018",
+            Self::E2021 => "2021",
+            Self::E2024 => "2024",
+        }
+    }
+}
+
+/// How the request's code is compiled
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompilerOptions {
+    /// Optimization level; None keeps the compiler's default
+    pub opt_level: Option<OptLevel>,
+    /// Rust edition; None keeps rustc's default
+    pub edition: Option<RustEdition>,
+    /// Extra flags, one token per entry, e.g. `["-C", "panic=abort"]`
+    pub flags: Vec<String>,
+}
+
+impl CompilerOptions {
+    /// Set the optimization level
+    pub fn with_opt_level(mut self, level: OptLevel) -> Self {
+        self.opt_level = Some(level);
+        self
+    }
+
+    /// Set the Rust edition
+    pub fn with_edition(mut self, edition: RustEdition) -> Self {
+        self.edition = Some(edition);
+        self
+    }
+
+    /// Append an extra flag token
+    pub fn with_flag<F: Into<String>>(mut self, flag: F) -> Self {
+        self.flags.push(flag.into());
+        self
+    }
+
+    /// Check the options against what the language's compiler allows
+    ///
+    /// # Returns
+    /// Ok(()) if every setting applies to the language and every flag is
+    /// allowlisted, otherwise a description of the first problem
+    pub fn validate(&self, language: &str) -> Result<(), String> {
+        match language::resolve(language).map(|spec| spec.name) {
+            Some("rust") => check_rust_flags(&self.flags),
+            Some("go") => {
+                if self.edition.is_some() {
+                    return Err("editions only apply to rust".to_string());
+                }
+                if let Some(level) = self.opt_level
+                    && !matches!(level, OptLevel::None | OptLevel::Full)
+                {
+                    return Err(format!(
+                        "go supports opt levels 0 and 2 only, got {}",
+                        level.as_str()
+                    ));
+                }
+                check_go_flags(&self.flags)
+            }
+            _ => Err(format!("'{language}' is not a compiled language")),
+        }
+    }
+
+    /// rustc arguments applying the options
+    pub fn rustc_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(level) = self.opt_level {
+            args.extend(["-C".to_string(), format!("opt-level={}", level.as_str())]);
+        }
+        if let Some(edition) = self.edition {
+            args.extend(["--edition".to_string(), edition.as_str().to_string()]);
+        }
+        args.extend(self.flags.iter().cloned());
+        args
+    }
+
+    /// `go build`/`go run` arguments applying the options
+    pub fn go_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.opt_level == Some(OptLevel::None) {
+            args.push("-gcflags=all=-N -l".to_string());
+        }
+        args.extend(self.flags.iter().cloned());
+        args
+    }
+}
+
+/// Render compiler arguments for a shell command line
+///
+/// Each argument is followed by a space, so the result goes directly in
+/// front of the source file. Allowlisted flags never need quoting;
+/// arguments the options generate themselves (e.g. `-gcflags=all=-N -l`)
+/// are single-quoted.
+pub(crate) fn shell_args(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.chars().all(is_plain_char) {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            }
+        })
+        .map(|arg| format!("{arg} "))
+        .collect()
+}
+
+/// Check extra rustc flags against the allowlist
+///
+/// # Returns
+/// Ok(()) if every token is allowed, otherwise the first disallowed one
+pub(crate) fn check_rust_flags(flags: &[String]) -> Result<(), String> {
+    let mut tokens = flags.iter().map(String::as_str);
+    while let Some(flag) = tokens.next() {
+        let disallowed = || format!("rustc flag '{flag}' is not allowed");
+        if !flag.chars().all(is_plain_char) {
+            return Err(disallowed());
+        }
+
+        // Options taking a value accept it attached or as the next token
+        let mut value = |option: &str| match flag.strip_prefix(option) {
+            Some("") => tokens.next().filter(|value| value.chars().all(is_plain_char)),
+            Some(attached) => Some(attached.trim_start_matches('=')),
+            None => None,
+        };
+        let allowed = match flag {
+            "-O" | "-g" => true,
+            _ if flag.starts_with("-C") => value("-C").is_some_and(|option| {
+                option.split_once('=').is_some_and(|(key, value)| {
+                    RUST_CODEGEN_OPTIONS.contains(&key) && !value.is_empty()
+                })
+            }),
+            _ if flag.starts_with("--cfg") => value("--cfg").is_some_and(is_identifier),
+            _ if flag.starts_with("--cap-lints") => value("--cap-lints")
+                .is_some_and(|level| matches!(level, "allow" | "warn" | "deny" | "forbid")),
+            _ if ["-A", "-W", "-D"].iter().any(|lint| flag.starts_with(lint)) => {
+                value(&flag[..2]).is_some_and(|lint| lint.split("::").all(is_identifier))
+            }
+            _ => false,
+        };
+        if !allowed {
+            return Err(disallowed());
+        }
+    }
+    Ok(())
+}
+
+/// Check extra go flags against the allowlist
+fn check_go_flags(flags: &[String]) -> Result<(), String> {
+    for flag in flags {
+        let allowed = match flag.as_str() {
+            "-race" | "-trimpath" => true,
+            _ => flag.strip_prefix("-tags=").is_some_and(|tags| {
+                !tags.is_empty() && tags.split(',').all(is_identifier)
+            }),
+        };
+        if !allowed {
+            return Err(format!("go flag '{flag}' is not allowed"));
+        }
+    }
+    Ok(())
+}
+
+/// Characters allowlisted flags may consist of; none is special to a shell
+fn is_plain_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '=' | '.' | ',' | ':')
+}
+
+fn is_identifier(value: &str) -> bool {
+    !value.is_empty()
+        && !value.starts_with(|c: char| c.is_ascii_digit())
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(tokens: &[&str]) -> Vec<String> {
+        tokens.iter().map(|token| token.to_string()).collect()
+    }
+
+    #[test]
+    fn rust_options_render_rustc_args() {
+        let options = CompilerOptions::default()
+            .with_opt_level(OptLevel::Aggressive)
+            .with_edition(RustEdition::E2021)
+            .with_flag("-C")
+            .with_flag("panic=abort");
+        assert!(options.validate("rust").is_ok());
+        assert_eq!(
+            options.rustc_args(),
+            ["-C", "opt-level=3", "--edition", "2021", "-C", "panic=abort"]
+        );
+        assert_eq!(
+            shell_args(&options.rustc_args()),
+            "-C opt-level=3 --edition 2021 -C panic=abort "
+        );
+    }
+
+    #[test]
+    fn escaping_rust_flags_are_rejected() {
+        for escape in [
+            &["-C", "link-arg=-Wl,--wrap=main"][..],
+            &["-Clinker=/tmp/evil"],
+            &["-o", "/etc/passwd"],
+            &["--out-dir", "/tmp"],
+            &["-L", "/tmp"],
+            &["@/tmp/args"],
+            &["-C"],
+            &["--cfg", "x;rm"],
+        ] {
+            assert!(check_rust_flags(&flags(escape)).is_err(), "{escape:?}");
+        }
+        let lints = flags(&["-Cdebuginfo=2", "-A", "dead_code", "--cfg=test"]);
+        assert!(check_rust_flags(&lints).is_ok());
+        assert!(check_rust_flags(&flags(&["-W", "clippy::all", "--cap-lints", "warn"])).is_ok());
+    }
+
+    #[test]
+    fn go_options_are_limited_to_go_settings() {
+        let debug = CompilerOptions::default().with_opt_level(OptLevel::None).with_flag("-race");
+        assert!(debug.validate("golang").is_ok());
+        assert_eq!(shell_args(&debug.go_args()), "'-gcflags=all=-N -l' -race ");
+
+        let options = CompilerOptions::default;
+        assert!(options().with_edition(RustEdition::E2018).validate("go").is_err());
+        assert!(options().with_opt_level(OptLevel::Size).validate("go").is_err());
+        assert!(options().with_flag("-toolexec=sh").validate("go").is_err());
+        assert!(options().validate("python").is_err());
+    }
+}
@@ -5,10 +5,15 @@
 // ============================================================================
 
 use std::collections::HashMap;
+use std::sync::OnceLock;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+use crate::backends::cpuset::CpuSet;
+use crate::backends::provisioning::ProvisioningLimits;
+use crate::backends::registry_auth::RegistryCredentials;
+
 /// Backend configuration
 ///
 /// Common configuration options for all backends.
@@ -28,6 +33,53 @@ pub struct BackendConfig {
 
     /// Backend-specific configuration
     pub backend_specific: HashMap<String, String>,
+
+    /// Maximum number of executions allowed to run concurrently on this backend
+    #[serde(default)]
+    pub max_concurrent_executions: Option<u32>,
+
+    /// Maximum number of executions one registered instance runs at once;
+    /// None means one at a time
+    #[serde(default)]
+    pub max_parallel: Option<u32>,
+
+    /// Identity of the executor that owns files, containers, and VMs this
+    /// backend creates; woven into their names and used to scope cleanup
+    #[serde(default = "default_owner_id")]
+    pub owner_id: String,
+
+    /// Credentials for pulling images from private registries
+    #[serde(default)]
+    pub registry_credentials: RegistryCredentials,
+
+    /// How long the workspace of a failed execution (exec directory,
+    /// container mount, VM config and log) is kept for inspection; zero
+    /// removes it right away
+    #[serde(default)]
+    pub retain_workspace_on_failure: Duration,
+
+    /// Let sandboxed code reach the host's SysV IPC objects and POSIX
+    /// shared memory; off by default, giving every execution its own
+    #[serde(default)]
+    pub share_host_ipc: bool,
+
+    /// Time and size budgets of image pulls and root filesystem preparation
+    #[serde(default)]
+    pub provisioning: ProvisioningLimits,
+}
+
+/// Identity of the cylo executor running in this process
+///
+/// Generated once per process. Every temp directory, jail directory,
+/// container, and VM name created through a default-configured backend
+/// carries it, so cleanup in one process never touches another's resources.
+pub fn executor_identity() -> &'static str {
+    static IDENTITY: OnceLock<String> = OnceLock::new();
+    IDENTITY.get_or_init(|| uuid::Uuid::new_v4().simple().to_string()[..12].to_string())
+}
+
+fn default_owner_id() -> String {
+    executor_identity().to_string()
 }
 
 impl BackendConfig {
@@ -39,6 +91,13 @@ impl BackendConfig {
             default_timeout: Duration::from_secs(30),
             default_limits: ResourceLimits::default(),
             backend_specific: HashMap::new(),
+            max_concurrent_executions: None,
+            max_parallel: None,
+            owner_id: default_owner_id(),
+            registry_credentials: RegistryCredentials::default(),
+            retain_workspace_on_failure: Duration::ZERO,
+            share_host_ipc: false,
+            provisioning: ProvisioningLimits::default(),
         }
     }
 
@@ -60,6 +119,57 @@ impl BackendConfig {
         self
     }
 
+    /// Limit how many executions may run concurrently on this backend
+    pub fn with_max_concurrent_executions(mut self, max: u32) -> Self {
+        self.max_concurrent_executions = Some(max);
+        self
+    }
+
+    /// Let one registered instance run up to `max` executions at once, each
+    /// in its own workspace (and cgroup, where the backend supports one)
+    pub fn with_max_parallel(mut self, max: u32) -> Self {
+        self.max_parallel = Some(max);
+        self
+    }
+
+    /// Executions one registered instance may run at once
+    pub fn parallelism(&self) -> u32 {
+        self.max_parallel.unwrap_or(1).max(1)
+    }
+
+    /// Set the owner identity used to name and scope backend resources
+    pub fn with_owner_id<S: Into<String>>(mut self, owner_id: S) -> Self {
+        self.owner_id = owner_id.into();
+        self
+    }
+
+    /// Set the credentials used for image pulls from private registries
+    pub fn with_registry_credentials(mut self, credentials: RegistryCredentials) -> Self {
+        self.registry_credentials = credentials;
+        self
+    }
+
+    /// Keep the workspaces of failed executions for `retention` before
+    /// removing them; the paths are recorded in the result metadata
+    pub fn with_retain_workspace_on_failure(mut self, retention: Duration) -> Self {
+        self.retain_workspace_on_failure = retention;
+        self
+    }
+
+    /// Share the host's IPC namespace and shared memory with sandboxed code,
+    /// for workloads that talk to host services over SysV IPC; backends
+    /// without a shared IPC namespace (VMs, containers) ignore it
+    pub fn with_share_host_ipc(mut self, share: bool) -> Self {
+        self.share_host_ipc = share;
+        self
+    }
+
+    /// Set the budgets of image pulls and root filesystem preparation
+    pub fn with_provisioning_limits(mut self, limits: ProvisioningLimits) -> Self {
+        self.provisioning = limits;
+        self
+    }
+
     /// Add backend-specific configuration
     pub fn with_config<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
         self.backend_specific.insert(key.into(), value.into());
@@ -76,7 +186,7 @@ impl Default for BackendConfig {
 /// Resource limits for execution
 ///
 /// Defines constraints on resource usage during code execution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResourceLimits {
     /// Maximum memory usage in bytes
     pub max_memory: Option<u64>,
@@ -92,6 +202,23 @@ pub struct ResourceLimits {
 
     /// Maximum network bandwidth in bytes/sec
     pub max_network_bandwidth: Option<u64>,
+
+    /// Maximum disk read bandwidth in bytes/sec
+    #[serde(default)]
+    pub max_read_bps: Option<u64>,
+
+    /// Maximum disk write bandwidth in bytes/sec
+    #[serde(default)]
+    pub max_write_bps: Option<u64>,
+
+    /// CPUs the execution may run on; None allows all of them
+    #[serde(default)]
+    pub cpuset: Option<CpuSet>,
+
+    /// NUMA nodes the execution's memory is allocated from; None allows
+    /// all of them
+    #[serde(default)]
+    pub numa_nodes: Option<CpuSet>,
 }
 
 impl Default for ResourceLimits {
@@ -102,10 +229,21 @@ impl Default for ResourceLimits {
             max_processes: Some(10),                       // 10 processes
             max_file_size: Some(100 * 1024 * 1024),        // 100MB
             max_network_bandwidth: Some(10 * 1024 * 1024), // 10MB/s
+            max_read_bps: None,
+            max_write_bps: None,
+            cpuset: None,
+            numa_nodes: None,
         }
     }
 }
 
+impl ResourceLimits {
+    /// Whether disk bandwidth is capped
+    pub fn throttles_io(&self) -> bool {
+        self.max_read_bps.is_some() || self.max_write_bps.is_some()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,14 +253,24 @@ mod tests {
         let config = BackendConfig::new("test_backend")
             .with_enabled(true)
             .with_timeout(Duration::from_secs(120))
+            .with_max_concurrent_executions(2)
             .with_config("custom_option", "value");
 
         assert_eq!(config.name, "test_backend");
         assert!(config.enabled);
         assert_eq!(config.default_timeout, Duration::from_secs(120));
+        assert_eq!(config.max_concurrent_executions, Some(2));
+        assert_eq!(config.owner_id, executor_identity());
         assert_eq!(
             config.backend_specific.get("custom_option"),
             Some(&"value".to_string())
         );
     }
+
+    #[test]
+    fn instances_run_one_execution_unless_raised() {
+        assert_eq!(BackendConfig::new("a").parallelism(), 1);
+        assert_eq!(BackendConfig::new("a").with_max_parallel(0).parallelism(), 1);
+        assert_eq!(BackendConfig::new("a").with_max_parallel(4).parallelism(), 4);
+    }
 }
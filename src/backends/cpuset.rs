@@ -0,0 +1,223 @@
+// ============================================================================
+// File: packages/cylo/src/backends/cpuset.rs
+// ----------------------------------------------------------------------------
+// CPU and NUMA node pinning of executions.
+//
+// Hosts running latency-sensitive work next to sandboxes reserve cores for
+// it; `ResourceLimits::cpuset` keeps executions on the remaining ones, and
+// `ResourceLimits::numa_nodes` keeps their memory on chosen nodes. Lists use
+// the kernel's cpuset syntax ("0-3,8"). On Linux the sandbox's affinity and
+// memory policy are set before it starts and, with a delegated cgroup, the
+// cpuset controller enforces them so code cannot widen them again; on
+// Windows the execution's Job Object carries the affinity. Backends that
+// cannot pin refuse the request rather than run it unpinned.
+// ============================================================================
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{BackendError, BackendResult, ExecutionRequest};
+
+/// Highest CPU or node number accepted, matching the kernel's default
+/// `CPU_SETSIZE`
+pub const MAX_CPU_ID: u32 = 1023;
+
+/// A non-empty set of CPU or NUMA node numbers
+///
+/// Parsed from and displayed as a cpuset list such as "0-3,8".
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CpuSet(BTreeSet<u32>);
+
+impl CpuSet {
+    /// Set of the given numbers; None when empty or one exceeds
+    /// `MAX_CPU_ID`
+    pub fn new<I: IntoIterator<Item = u32>>(ids: I) -> Option<Self> {
+        let ids: BTreeSet<u32> = ids.into_iter().collect();
+        let in_range = ids.last().is_some_and(|&last| last <= MAX_CPU_ID);
+        in_range.then_some(Self(ids))
+    }
+
+    /// Numbers in ascending order
+    pub fn ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Whether `id` is in the set
+    pub fn contains(&self, id: u32) -> bool {
+        self.0.contains(&id)
+    }
+
+    /// Windows affinity mask; None when a CPU lies beyond the mask's width,
+    /// which needs processor groups
+    pub fn affinity_mask(&self) -> Option<usize> {
+        self.ids()
+            .try_fold(0usize, |mask, id| Some(mask | 1usize.checked_shl(id)?))
+    }
+}
+
+impl FromStr for CpuSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |part: &str| format!("invalid cpuset entry '{part}'; use a list like 0-3,8");
+        let mut ids = BTreeSet::new();
+        for part in s.split(',').map(str::trim) {
+            let number = |text: &str| text.trim().parse::<u32>().map_err(|_| invalid(part));
+            let (first, last) = match part.split_once('-') {
+                Some((first, last)) => (number(first)?, number(last)?),
+                None => (number(part)?, number(part)?),
+            };
+            if first > last || last > MAX_CPU_ID {
+                return Err(invalid(part));
+            }
+            ids.extend(first..=last);
+        }
+        Self::new(ids).ok_or_else(|| invalid(s))
+    }
+}
+
+impl fmt::Display for CpuSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ids = self.ids().peekable();
+        let mut separator = "";
+        while let Some(first) = ids.next() {
+            let mut last = first;
+            while ids.next_if_eq(&(last + 1)).is_some() {
+                last += 1;
+            }
+            if first == last {
+                write!(f, "{separator}{first}")?;
+            } else {
+                write!(f, "{separator}{first}-{last}")?;
+            }
+            separator = ",";
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for CpuSet {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<CpuSet> for String {
+    fn from(set: CpuSet) -> Self {
+        set.to_string()
+    }
+}
+
+/// Refuse pinning on a backend that cannot pin its executions
+pub fn reject_unpinnable(request: &ExecutionRequest, backend: &'static str) -> BackendResult<()> {
+    if request.limits.cpuset.is_some() || request.limits.numa_nodes.is_some() {
+        return Err(BackendError::NotAvailable {
+            backend,
+            reason: "executions cannot be pinned to CPUs or NUMA nodes".to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) use linux::pin;
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::io;
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    use super::{CpuSet, MAX_CPU_ID};
+
+    /// `MPOL_BIND` from <linux/mempolicy.h>
+    const MPOL_BIND: libc::c_int = 2;
+
+    /// Words of a mask covering every accepted CPU or node number
+    const MASK_WORDS: usize = (MAX_CPU_ID as usize + 1) / libc::c_ulong::BITS as usize;
+
+    /// Kernel bit mask of a set, laid out like `cpu_set_t` and `nodemask_t`
+    fn mask(set: &CpuSet) -> [libc::c_ulong; MASK_WORDS] {
+        let mut mask = [0; MASK_WORDS];
+        let bits = libc::c_ulong::BITS;
+        for id in set.ids() {
+            mask[(id / bits) as usize] |= 1 << (id % bits);
+        }
+        mask
+    }
+
+    /// Start `cmd` on `cpus` with its memory bound to `nodes`
+    ///
+    /// Both are inherited by everything the command spawns. They are set
+    /// between fork and exec, so a CPU or node that is not online fails the
+    /// spawn instead of leaving the command unpinned.
+    pub(crate) fn pin(cmd: &mut Command, cpus: Option<&CpuSet>, nodes: Option<&CpuSet>) {
+        if cpus.is_none() && nodes.is_none() {
+            return;
+        }
+        let affinity = cpus.map(mask);
+        let node_mask = nodes.map(mask);
+
+        // SAFETY: sched_setaffinity(2) and set_mempolicy(2) are plain
+        // syscalls reading masks prepared before the fork
+        unsafe {
+            cmd.pre_exec(move || {
+                if let Some(mask) = &affinity
+                    && libc::sched_setaffinity(0, std::mem::size_of_val(mask), mask.as_ptr().cast())
+                        != 0
+                {
+                    return Err(io::Error::last_os_error());
+                }
+                if let Some(mask) = &node_mask {
+                    // The kernel reads one bit fewer than maxnode
+                    let max_node = (MASK_WORDS * libc::c_ulong::BITS as usize + 1) as libc::c_ulong;
+                    if libc::syscall(libc::SYS_set_mempolicy, MPOL_BIND, mask.as_ptr(), max_node)
+                        != 0
+                    {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::ResourceLimits;
+
+    #[test]
+    fn cpuset_lists_round_trip() {
+        let set: CpuSet = "8, 0-3,2".parse().unwrap();
+        assert_eq!(set.ids().collect::<Vec<_>>(), [0, 1, 2, 3, 8]);
+        assert_eq!(set.to_string(), "0-3,8");
+        assert_eq!(set.affinity_mask(), Some(0b1_0000_1111));
+        assert!(set.contains(8) && !set.contains(4));
+
+        for invalid in ["", "3-1", "a", "0,,1", "2048"] {
+            assert!(invalid.parse::<CpuSet>().is_err(), "{invalid}");
+        }
+        let wide: CpuSet = "0,70".parse().unwrap();
+        assert_eq!(wide.affinity_mask(), None);
+
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, "\"0-3,8\"");
+        assert_eq!(serde_json::from_str::<CpuSet>(&json).unwrap(), set);
+
+        let pinned = ExecutionRequest::new("print(1)", "python").with_limits(ResourceLimits {
+            cpuset: Some(set),
+            ..ResourceLimits::default()
+        });
+        assert!(reject_unpinnable(&pinned, "Apple").is_err());
+        let free = ExecutionRequest::new("print(1)", "python");
+        assert!(reject_unpinnable(&free, "Apple").is_ok());
+    }
+}
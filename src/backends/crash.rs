@@ -0,0 +1,337 @@
+// ============================================================================
+// File: packages/cylo/src/backends/crash.rs
+// ----------------------------------------------------------------------------
+// Crash reports for executions terminated by a signal.
+//
+// A segfaulting program usually prints nothing, leaving only an exit code.
+// Results instead carry the signal that ended the program and, when the
+// request asked for it, the core dump the kernel wrote into the workspace,
+// moved out before the workspace is removed. Dumps hold the crashed
+// program's memory, so they are kept in a directory private to the user
+// and pruned by age and total size as new ones arrive.
+// ============================================================================
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::platform_utils;
+
+/// Result metadata key naming the signal that terminated the execution
+pub const SIGNAL_METADATA: &str = "crash.signal";
+
+/// Result metadata key explaining why a requested core dump is missing
+pub const CORE_DUMP_METADATA: &str = "crash.core_dump";
+
+/// Age after which captured core dumps are removed
+pub const CORE_DUMP_RETENTION: Duration = Duration::from_secs(3 * 24 * 60 * 60);
+
+/// Total size captured core dumps are pruned to, oldest first
+pub const CORE_DUMP_MAX_BYTES: u64 = 4 * 1024 * 1024 * 1024; // 4GB
+
+/// File extension of captured core dumps
+const CORE_EXTENSION: &str = "core";
+
+/// How a crashed execution ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashReport {
+    /// Signal number (Linux numbering)
+    pub signal: i32,
+    /// Signal name, e.g. "SIGSEGV"
+    pub signal_name: String,
+    /// Host path of the captured core dump
+    pub core_path: Option<PathBuf>,
+    /// Size of the captured core dump in bytes
+    pub core_bytes: Option<u64>,
+    /// The dump reached the size cap and is cut off
+    pub core_truncated: bool,
+}
+
+impl CrashReport {
+    /// Describe a termination by `signal`
+    pub fn new(signal: i32) -> Self {
+        Self {
+            signal,
+            signal_name: signal_name(signal).to_string(),
+            core_path: None,
+            core_bytes: None,
+            core_truncated: false,
+        }
+    }
+
+    /// Describe an exit code reported by a wrapper process
+    ///
+    /// Shells, bubblewrap and container runtimes report a child killed by
+    /// signal N as exit code 128 + N. A program that exits with such a
+    /// code on purpose is indistinguishable and is reported as a crash too.
+    ///
+    /// # Returns
+    /// The report, or None when the code does not encode a signal
+    pub fn from_exit_code(exit_code: i32) -> Option<Self> {
+        let signal = exit_code - 128;
+        (1..=64).contains(&signal).then(|| Self::new(signal))
+    }
+
+    /// Describe a process exit status
+    ///
+    /// # Returns
+    /// The report, or None when the process exited normally with a code
+    /// that does not encode a signal
+    #[cfg(unix)]
+    pub fn from_status(status: &std::process::ExitStatus) -> Option<Self> {
+        use std::os::unix::process::ExitStatusExt;
+        match status.signal() {
+            Some(signal) => Some(Self::new(signal)),
+            None => status.code().and_then(Self::from_exit_code),
+        }
+    }
+
+    /// Whether the signal is one the kernel writes a core dump for
+    pub fn dumps_core(&self) -> bool {
+        matches!(self.signal, 3..=8 | 11 | 24 | 25 | 31)
+    }
+}
+
+/// Name of a Linux signal number
+pub fn signal_name(signal: i32) -> &'static str {
+    match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        5 => "SIGTRAP",
+        6 => "SIGABRT",
+        7 => "SIGBUS",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        10 => "SIGUSR1",
+        11 => "SIGSEGV",
+        12 => "SIGUSR2",
+        13 => "SIGPIPE",
+        14 => "SIGALRM",
+        15 => "SIGTERM",
+        24 => "SIGXCPU",
+        25 => "SIGXFSZ",
+        31 => "SIGSYS",
+        _ => "unknown signal",
+    }
+}
+
+/// Directory private to the user that captured core dumps are kept in
+///
+/// Dumps older than `CORE_DUMP_RETENTION`, and the oldest beyond
+/// `CORE_DUMP_MAX_BYTES` in all, are removed as new ones are captured.
+pub fn core_dump_dir() -> PathBuf {
+    platform_utils::user_state_dir().join("cores")
+}
+
+/// Move a core dump out of a workspace
+///
+/// Only ELF core files count, so a file the code itself named "core" is
+/// never mistaken for one.
+///
+/// # Arguments
+/// * `workspace` - Directory the crashed process ran in
+/// * `execution` - Name the captured dump is stored under
+/// * `limit` - Size cap the dump was written under
+/// * `report` - Report the dump's location and size are recorded in
+///
+/// # Returns
+/// Whether a dump was found
+pub(crate) fn capture_core(
+    workspace: &Path,
+    execution: &str,
+    limit: u64,
+    report: &mut CrashReport,
+) -> bool {
+    let Some(core) = find_core(workspace, 2) else {
+        return false;
+    };
+    let bytes = fs::metadata(&core).map_or(0, |m| m.len());
+
+    let dir = core_dump_dir();
+    let target = dir.join(format!("{execution}.{CORE_EXTENSION}"));
+    let moved = platform_utils::create_private_dir(&dir).is_ok()
+        && (fs::rename(&core, &target).is_ok() || fs::copy(&core, &target).is_ok());
+    if !moved {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&target, fs::Permissions::from_mode(0o600));
+    }
+    prune_cores(&dir, &target, CORE_DUMP_RETENTION, CORE_DUMP_MAX_BYTES);
+
+    report.core_path = Some(target);
+    report.core_bytes = Some(bytes);
+    report.core_truncated = bytes >= limit;
+    true
+}
+
+/// Remove captured dumps past `retention`, then the oldest until those
+/// left take at most `max_bytes`; the dump just captured is always kept
+fn prune_cores(dir: &Path, keep: &Path, retention: Duration, max_bytes: u64) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    let mut cores: Vec<(SystemTime, u64, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path != keep)
+        .filter(|path| path.extension().is_some_and(|ext| ext == CORE_EXTENSION))
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            Some((metadata.modified().ok()?, metadata.len(), path))
+        })
+        .collect();
+    // Newest first, so the oldest are the ones past the size cap
+    cores.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut total = fs::metadata(keep).map_or(0, |m| m.len());
+    for (modified, bytes, path) in cores {
+        let expired = now.duration_since(modified).is_ok_and(|age| age > retention);
+        if expired || total + bytes > max_bytes {
+            let _ = fs::remove_file(&path);
+        } else {
+            total += bytes;
+        }
+    }
+}
+
+/// Explain why no core dump appeared despite being requested
+///
+/// Cores follow the host's `kernel.core_pattern`; only a relative pattern
+/// makes the kernel write them into the workspace.
+pub(crate) fn missing_core_reason() -> String {
+    match fs::read_to_string("/proc/sys/kernel/core_pattern") {
+        Ok(pattern) if pattern.starts_with('|') => format!(
+            "not captured: kernel.core_pattern pipes dumps to {}",
+            pattern.trim().trim_start_matches('|')
+        ),
+        Ok(pattern) if pattern.starts_with('/') => format!(
+            "not captured: kernel.core_pattern writes dumps to {}",
+            pattern.trim()
+        ),
+        _ => "not captured: the process wrote no core dump".to_string(),
+    }
+}
+
+/// Search a directory, `depth` levels deep, for an ELF core file
+fn find_core(dir: &Path, depth: usize) -> Option<PathBuf> {
+    let entries = fs::read_dir(dir).ok()?.filter_map(Result::ok).collect::<Vec<_>>();
+    let named_core = |name: &str| {
+        name == "core" || name.strip_prefix("core.").is_some_and(|pid| pid.parse::<u32>().is_ok())
+    };
+
+    let found = entries.iter().find(|entry| {
+        entry.file_type().is_ok_and(|kind| kind.is_file())
+            && entry.file_name().to_str().is_some_and(named_core)
+            && is_elf_core(&entry.path())
+    });
+    if let Some(entry) = found {
+        return Some(entry.path());
+    }
+    if depth == 0 {
+        return None;
+    }
+    entries
+        .iter()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .find_map(|entry| find_core(&entry.path(), depth - 1))
+}
+
+/// Check for the ELF magic and the ET_CORE file type
+fn is_elf_core(path: &Path) -> bool {
+    let mut header = [0u8; 18];
+    let read = fs::File::open(path).and_then(|mut file| file.read_exact(&mut header));
+    // e_type is at offset 16; ELFDATA2LSB (1) or ELFDATA2MSB (2) at offset 5
+    read.is_ok()
+        && header[..4] == *b"\x7fELF"
+        && match header[5] {
+            1 => u16::from_le_bytes([header[16], header[17]]) == 4,
+            2 => u16::from_be_bytes([header[16], header[17]]) == 4,
+            _ => false,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elf_core() -> Vec<u8> {
+        let mut header = b"\x7fELF\x02\x01\x01".to_vec();
+        header.resize(16, 0);
+        header.extend_from_slice(&4u16.to_le_bytes());
+        header.resize(64, 0);
+        header
+    }
+
+    #[test]
+    fn wrapper_exit_codes_decode_to_signals() {
+        let segv = CrashReport::from_exit_code(139).expect("SIGSEGV");
+        assert_eq!((segv.signal, segv.signal_name.as_str()), (11, "SIGSEGV"));
+        assert!(segv.dumps_core());
+        assert!(!CrashReport::new(9).dumps_core());
+        assert_eq!(CrashReport::from_exit_code(1), None);
+        assert_eq!(CrashReport::from_exit_code(128), None);
+    }
+
+    #[test]
+    fn only_elf_cores_are_captured() {
+        let workspace = tempfile::tempdir().expect("tempdir");
+        fs::write(workspace.path().join("core"), b"not a core dump").expect("write");
+        let mut report = CrashReport::new(11);
+        let execution = format!("test-{}", uuid::Uuid::new_v4().simple());
+        assert!(!capture_core(workspace.path(), &execution, 1 << 20, &mut report));
+
+        let nested = workspace.path().join("src");
+        fs::create_dir_all(&nested).expect("mkdir");
+        fs::write(nested.join("core.4242"), elf_core()).expect("write");
+        assert!(capture_core(workspace.path(), &execution, 64, &mut report));
+
+        let captured = report.core_path.clone().expect("core path");
+        assert_eq!(report.core_bytes, Some(64));
+        assert!(report.core_truncated);
+        assert!(captured.exists() && !nested.join("core.4242").exists());
+        let _ = fs::remove_file(captured);
+    }
+
+    #[test]
+    fn captured_cores_are_pruned_by_age_and_size() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let core = |name: &str, bytes: usize, age: u64| {
+            let path = dir.path().join(format!("{name}.core"));
+            fs::write(&path, vec![0u8; bytes]).expect("write");
+            let modified = SystemTime::now() - Duration::from_secs(age);
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(modified))
+                .expect("set mtime");
+            path
+        };
+        let older = core("older", 10, 60);
+        let old = core("old", 10, 30);
+        let new = core("new", 30, 0);
+        let unrelated = dir.path().join("notes.txt");
+        fs::write(&unrelated, vec![0u8; 100]).expect("write");
+
+        // The newest other dump fits under the cap next to the new one
+        prune_cores(dir.path(), &new, Duration::from_secs(3600), 45);
+        assert!(new.exists() && old.exists() && unrelated.exists());
+        assert!(!older.exists());
+
+        // Expired dumps go whatever their size
+        prune_cores(dir.path(), &new, Duration::from_secs(10), u64::MAX);
+        assert!(!old.exists());
+
+        // The dump just captured stays even past the cap
+        prune_cores(dir.path(), &new, Duration::from_secs(3600), 0);
+        assert!(new.exists());
+    }
+}
@@ -0,0 +1,170 @@
+// ============================================================================
+// File: packages/cylo/src/backends/desktop.rs
+// ----------------------------------------------------------------------------
+// Display, audio and session bus access of executed code.
+//
+// On a developer desktop the executor's environment names the X11 or
+// Wayland display, the PulseAudio or PipeWire server and the D-Bus session
+// bus, and host sandboxes inherit that environment. Code could then read
+// the clipboard, take screenshots, inject input, record the microphone or
+// drive desktop services. Executions get none of it unless the request
+// opts in to the parts GUI code needs; the variables are stripped and the
+// sockets and devices are not bound into the sandbox.
+// ============================================================================
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{BackendError, BackendResult, ExecutionRequest};
+
+/// Variables naming or authenticating to a display server
+const DISPLAY_VARS: &[&str] = &["DISPLAY", "WAYLAND_DISPLAY", "WAYLAND_SOCKET", "XAUTHORITY"];
+
+/// Variables naming or authenticating to a sound server
+const AUDIO_VARS: &[&str] = &[
+    "PULSE_SERVER",
+    "PULSE_COOKIE",
+    "PIPEWIRE_REMOTE",
+    "PIPEWIRE_RUNTIME_DIR",
+    "JACK_DEFAULT_SERVER",
+    "AUDIODEV",
+];
+
+/// Variables naming a D-Bus bus
+const SESSION_BUS_VARS: &[&str] = &["DBUS_SESSION_BUS_ADDRESS", "DBUS_SYSTEM_BUS_ADDRESS"];
+
+/// Desktop services executed code may use; none by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DesktopAccess {
+    /// X11 or Wayland display, with the GPU render nodes
+    pub display: bool,
+    /// PulseAudio or PipeWire server and ALSA devices
+    pub audio: bool,
+    /// D-Bus session and system buses
+    pub session_bus: bool,
+}
+
+impl DesktopAccess {
+    /// Display and audio, as GUI programs need; the session bus stays off
+    pub fn gui() -> Self {
+        Self {
+            display: true,
+            audio: true,
+            session_bus: false,
+        }
+    }
+
+    /// Grant or withhold the D-Bus buses
+    pub fn with_session_bus(mut self, session_bus: bool) -> Self {
+        self.session_bus = session_bus;
+        self
+    }
+
+    /// Whether anything is granted
+    pub fn any(&self) -> bool {
+        self.display || self.audio || self.session_bus
+    }
+
+    /// Host variables to remove from the executed code's environment
+    ///
+    /// `XDG_RUNTIME_DIR`, where the sockets live, goes unless something is
+    /// granted.
+    pub fn stripped_vars(&self) -> Vec<&'static str> {
+        let mut vars = Vec::new();
+        for (granted, group) in [
+            (self.display, DISPLAY_VARS),
+            (self.audio, AUDIO_VARS),
+            (self.session_bus, SESSION_BUS_VARS),
+        ] {
+            if !granted {
+                vars.extend_from_slice(group);
+            }
+        }
+        if !self.any() {
+            vars.push("XDG_RUNTIME_DIR");
+        }
+        vars
+    }
+
+    /// Host sockets, files and devices to expose at the same location,
+    /// with whether each is a device; only those that exist are returned
+    pub fn host_paths(&self) -> Vec<(PathBuf, bool)> {
+        let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+        let in_runtime_dir = |name: &str| runtime_dir.as_ref().map(|dir| dir.join(name));
+        let mut paths = Vec::new();
+
+        if self.display {
+            paths.push(Some(PathBuf::from("/tmp/.X11-unix")));
+            paths.push(std::env::var_os("XAUTHORITY").map(PathBuf::from));
+            let wayland = std::env::var("WAYLAND_DISPLAY").unwrap_or_else(|_| "wayland-0".into());
+            paths.push(in_runtime_dir(&wayland));
+        }
+        if self.audio {
+            paths.push(in_runtime_dir("pulse"));
+            paths.push(in_runtime_dir("pipewire-0"));
+        }
+        if self.session_bus {
+            paths.push(in_runtime_dir("bus"));
+            paths.push(Some(PathBuf::from("/run/dbus/system_bus_socket")));
+        }
+
+        let mut exposed: Vec<(PathBuf, bool)> = paths
+            .into_iter()
+            .flatten()
+            .filter(|path| path.exists())
+            .map(|path| (path, false))
+            .collect();
+        for (granted, device) in [(self.display, "/dev/dri"), (self.audio, "/dev/snd")] {
+            if granted && std::path::Path::new(device).exists() {
+                exposed.push((PathBuf::from(device), true));
+            }
+        }
+        exposed
+    }
+}
+
+/// Refuse desktop access on a backend whose code runs in a guest
+///
+/// A VM or container has no route to the host's display, sound server or
+/// buses, so GUI requests fail instead of running without them.
+pub fn reject_in_guest(request: &ExecutionRequest, backend: &'static str) -> BackendResult<()> {
+    if request.desktop.any() {
+        return Err(BackendError::NotAvailable {
+            backend,
+            reason: "the host display, audio and session bus cannot be shared with a guest"
+                .to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_is_stripped_unless_granted() {
+        let none = DesktopAccess::default();
+        assert!(!none.any());
+        assert!(none.host_paths().is_empty());
+        let stripped = none.stripped_vars();
+        for var in ["DISPLAY", "WAYLAND_DISPLAY", "PULSE_SERVER", "DBUS_SESSION_BUS_ADDRESS"] {
+            assert!(stripped.contains(&var), "{var}");
+        }
+        assert!(stripped.contains(&"XDG_RUNTIME_DIR"));
+
+        let gui = DesktopAccess::gui();
+        let stripped = gui.stripped_vars();
+        assert!(!stripped.contains(&"DISPLAY"));
+        assert!(!stripped.contains(&"PULSE_SERVER"));
+        assert!(!stripped.contains(&"XDG_RUNTIME_DIR"));
+        assert!(stripped.contains(&"DBUS_SESSION_BUS_ADDRESS"));
+
+        let request = ExecutionRequest::new("print(1)", "python").with_desktop_access(gui);
+        assert!(reject_in_guest(&request, "FireCracker").is_err());
+        let request = ExecutionRequest::new("print(1)", "python");
+        assert!(reject_in_guest(&request, "FireCracker").is_ok());
+    }
+}
@@ -0,0 +1,289 @@
+// ============================================================================
+// File: packages/cylo/src/backends/dns.rs
+// ----------------------------------------------------------------------------
+// Per-execution DNS policy.
+//
+// When an execution may use the network, the policy decides which resolvers
+// it talks to and which names it can resolve at all. Backends apply it by
+// injecting a generated resolv.conf and hosts file into the jail, container
+// or VM, so "only api.internal resolves" is expressed as a policy with no
+// resolvers and a single static host.
+//
+// This is best effort, not egress filtering: it shapes what libc's resolver
+// answers, nothing more. Code connecting to addresses directly, querying a
+// resolver or DNS-over-HTTPS endpoint of its own, or rewriting the files it
+// was given reaches whatever the network lets it reach. Results say so, and
+// restricting where an execution may connect takes disabling its network or
+// a network-level policy.
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::ExecutionResult;
+
+/// Result metadata key describing the DNS policy an execution ran with
+pub const DNS_METADATA: &str = "dns.resolvers";
+
+/// Result metadata key saying how far the DNS policy was enforced
+pub const DNS_ENFORCEMENT_METADATA: &str = "dns.enforcement";
+
+/// Enforcement recorded for every DNS policy
+const BEST_EFFORT: &str = "best effort: name lookups only; connections by address are not filtered";
+
+/// Loopback address nothing listens on
+///
+/// A resolv.conf without any nameserver makes libc fall back to 127.0.0.1,
+/// where a host-local resolver may well answer. Pointing at an unused
+/// loopback address instead makes lookups fail fast.
+const UNREACHABLE_RESOLVER: &str = "127.0.0.254";
+
+/// Which resolvers an execution may query
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "servers", rename_all = "snake_case")]
+pub enum DnsResolvers {
+    /// Whatever the backend provides by default (the host's resolvers)
+    #[default]
+    Host,
+    /// Only these resolvers
+    Fixed(Vec<IpAddr>),
+    /// No resolvers; only static hosts resolve
+    None,
+}
+
+impl DnsResolvers {
+    /// Short name used in result metadata
+    pub fn mode(&self) -> &'static str {
+        match self {
+            Self::Host => "host",
+            Self::Fixed(_) => "fixed",
+            Self::None => "none",
+        }
+    }
+}
+
+/// DNS policy of one execution
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DnsPolicy {
+    /// Resolvers the execution may query
+    pub resolvers: DnsResolvers,
+    /// Search domains appended to unqualified names
+    pub search_domains: Vec<String>,
+    /// Names that never resolve, regardless of the resolvers
+    ///
+    /// Their addresses stay reachable. Blocking is by exact name, so list
+    /// subdomains that matter explicitly.
+    pub blocked_domains: Vec<String>,
+    /// Names resolved locally to a fixed address
+    pub static_hosts: BTreeMap<String, IpAddr>,
+}
+
+impl DnsPolicy {
+    /// Use only the given resolvers
+    pub fn fixed<I: IntoIterator<Item = IpAddr>>(servers: I) -> Self {
+        Self {
+            resolvers: DnsResolvers::Fixed(servers.into_iter().collect()),
+            ..Self::default()
+        }
+    }
+
+    /// Resolve nothing except the given static hosts
+    ///
+    /// Other names fail to resolve; their addresses stay reachable.
+    pub fn only<N: Into<String>, I: IntoIterator<Item = (N, IpAddr)>>(hosts: I) -> Self {
+        Self {
+            resolvers: DnsResolvers::None,
+            static_hosts: hosts.into_iter().map(|(name, ip)| (name.into(), ip)).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Append a search domain
+    pub fn with_search_domain<D: Into<String>>(mut self, domain: D) -> Self {
+        self.search_domains.push(domain.into());
+        self
+    }
+
+    /// Block a name
+    pub fn with_blocked_domain<D: Into<String>>(mut self, domain: D) -> Self {
+        self.blocked_domains.push(domain.into());
+        self
+    }
+
+    /// Resolve `name` to `address` without asking any resolver
+    pub fn with_static_host<N: Into<String>>(mut self, name: N, address: IpAddr) -> Self {
+        self.static_hosts.insert(name.into(), address);
+        self
+    }
+
+    /// Record the policy an execution ran with, and that it was applied
+    /// only to name lookups
+    pub fn record(&self, result: &mut ExecutionResult) {
+        result
+            .metadata
+            .insert(DNS_METADATA.to_string(), self.resolvers.mode().to_string());
+        result
+            .metadata
+            .insert(DNS_ENFORCEMENT_METADATA.to_string(), BEST_EFFORT.to_string());
+    }
+
+    /// Whether the policy changes nothing about the backend's default DNS
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check the policy for values that cannot be written to resolv.conf
+    /// or hosts
+    ///
+    /// # Returns
+    /// Ok(()) if valid, otherwise a description of the first problem
+    pub fn validate(&self) -> Result<(), String> {
+        if let DnsResolvers::Fixed(servers) = &self.resolvers {
+            if servers.is_empty() {
+                return Err("fixed resolvers need at least one server".to_string());
+            }
+            // glibc reads at most three nameserver lines
+            if servers.len() > 3 {
+                return Err(format!("at most 3 resolvers are supported, got {}", servers.len()));
+            }
+        }
+
+        let names = self
+            .search_domains
+            .iter()
+            .chain(&self.blocked_domains)
+            .chain(self.static_hosts.keys());
+        for name in names {
+            if !is_valid_hostname(name) {
+                return Err(format!("invalid domain name '{name}'"));
+            }
+        }
+
+        if let Some(name) = self
+            .blocked_domains
+            .iter()
+            .find(|name| self.static_hosts.contains_key(*name))
+        {
+            return Err(format!("'{name}' is both blocked and a static host"));
+        }
+        Ok(())
+    }
+
+    /// Render the resolv.conf to inject
+    ///
+    /// # Returns
+    /// File content, or None when the backend's default resolvers apply and
+    /// no search domains are set
+    pub fn resolv_conf(&self) -> Option<String> {
+        let mut conf = String::from("# Generated by cylo from the execution's DNS policy\n");
+        match &self.resolvers {
+            DnsResolvers::Host if self.search_domains.is_empty() => return None,
+            DnsResolvers::Host => {
+                // Keep the host's servers, replace only its search list
+                let host = std::fs::read_to_string("/etc/resolv.conf").unwrap_or_default();
+                for line in host.lines() {
+                    let keyword = line.split_whitespace().next().unwrap_or("");
+                    if keyword != "search" && keyword != "domain" {
+                        conf.push_str(line);
+                        conf.push('\n');
+                    }
+                }
+            }
+            DnsResolvers::Fixed(servers) => {
+                for server in servers {
+                    let _ = writeln!(conf, "nameserver {server}");
+                }
+            }
+            DnsResolvers::None => {
+                let _ = writeln!(conf, "nameserver {UNREACHABLE_RESOLVER}");
+                conf.push_str("options timeout:1 attempts:1\n");
+            }
+        }
+        if !self.search_domains.is_empty() {
+            let _ = writeln!(conf, "search {}", self.search_domains.join(" "));
+        }
+        Some(conf)
+    }
+
+    /// Render the hosts file to inject
+    ///
+    /// Blocked names map to the unspecified address, so they never reach
+    /// whatever a resolver would have returned for them.
+    pub fn hosts(&self) -> String {
+        let mut hosts = String::from("127.0.0.1\tlocalhost\n::1\tlocalhost\n");
+        for (name, address) in &self.static_hosts {
+            let _ = writeln!(hosts, "{address}\t{name}");
+        }
+        for name in &self.blocked_domains {
+            let _ = writeln!(hosts, "0.0.0.0\t{name}");
+            let _ = writeln!(hosts, "::\t{name}");
+        }
+        hosts
+    }
+}
+
+fn is_valid_hostname(name: &str) -> bool {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    !name.is_empty()
+        && name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allow_list_policy_resolves_only_static_hosts() {
+        let internal: IpAddr = "10.0.4.7".parse().expect("ip");
+        let policy = DnsPolicy::only([("api.internal", internal)]);
+        assert!(policy.validate().is_ok());
+
+        let conf = policy.resolv_conf().expect("resolv.conf");
+        assert!(conf.contains(&format!("nameserver {UNREACHABLE_RESOLVER}")), "{conf}");
+        assert!(policy.hosts().contains("10.0.4.7\tapi.internal\n"));
+
+        // Results never claim more than the lookups were filtered
+        let mut result = ExecutionResult::success("");
+        policy.record(&mut result);
+        assert_eq!(result.metadata[DNS_METADATA], "none");
+        assert!(result.metadata[DNS_ENFORCEMENT_METADATA].starts_with("best effort"));
+    }
+
+    #[test]
+    fn fixed_resolvers_and_blocklist_render() {
+        let policy = DnsPolicy::fixed(["1.1.1.1".parse().expect("ip"), "::1".parse().expect("ip")])
+            .with_search_domain("corp.example")
+            .with_blocked_domain("telemetry.example.com");
+        assert!(policy.validate().is_ok());
+
+        let conf = policy.resolv_conf().expect("resolv.conf");
+        assert!(conf.contains("nameserver 1.1.1.1\nnameserver ::1\n"), "{conf}");
+        assert!(conf.contains("search corp.example\n"), "{conf}");
+        assert!(policy.hosts().contains("0.0.0.0\ttelemetry.example.com\n"));
+
+        assert_eq!(DnsPolicy::default().resolv_conf(), None);
+        assert!(DnsPolicy::default().is_default());
+    }
+
+    #[test]
+    fn invalid_policies_are_rejected() {
+        let ip: IpAddr = "10.0.0.1".parse().expect("ip");
+        assert!(DnsPolicy::fixed([]).validate().is_err());
+        assert!(DnsPolicy::fixed([ip; 4]).validate().is_err());
+        assert!(DnsPolicy::default().with_blocked_domain("bad name").validate().is_err());
+        assert!(DnsPolicy::default().with_search_domain("-x.example").validate().is_err());
+        let conflicting = DnsPolicy::only([("a.example", ip)]).with_blocked_domain("a.example");
+        assert!(conflicting.validate().is_err());
+    }
+}
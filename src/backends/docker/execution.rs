@@ -0,0 +1,662 @@
+// ============================================================================
+// File: packages/cylo/src/backends/docker/execution.rs
+// ----------------------------------------------------------------------------
+// Container execution logic for the Docker/Podman backend.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::AsyncTaskBuilder;
+use crate::backends::blob_store::write_blob_files;
+use crate::backends::input_stream;
+use crate::backends::live::LiveSet;
+use crate::backends::paths::{exposed_paths, relative_inside, write_files};
+use crate::backends::process::{self, CapturedOutput, OutputCapture, WaitOutcome};
+use crate::backends::{
+    ARCH_METADATA, AsyncTask, BackendError, BackendResult, CLOCK_METADATA, CORE_DUMP_METADATA,
+    CompilerOptions, CrashReport, DnsPolicy, DnsResolvers, ExecutionOutcome,
+    ExecutionRequest, ExecutionResult, IsolationLevel, SANDBOX_STATE_METADATA, SIGNAL_METADATA,
+    SecurityReport, WATCHDOG_METADATA,
+};
+use crate::backends::{
+    SqlEngine, SqlOptions, archive, clock, compile_phase, compiler, git_checkout, go_cache,
+    language, r_library, retention, sql,
+};
+use crate::hardening::{Helper, HelperCommand};
+use crate::ids;
+use crate::reaper::{DEADLINE_GRACE, ResourceKind, global_reaper};
+
+use super::{is_podman, resource_stats};
+
+/// Backend configuration an execution needs
+#[derive(Debug, Clone)]
+pub(super) struct ContainerOptions {
+    /// Executor identity embedded in the container name
+    pub owner_id: String,
+    /// Join the host's IPC namespace instead of a private one
+    pub share_host_ipc: bool,
+    /// How long the mounted source directory is kept if the execution fails
+    pub retention: Duration,
+}
+
+/// Execute code in a Docker or Podman container
+///
+/// # Arguments
+/// * `runtime` - Container CLI to drive
+/// * `image` - Container image specification
+/// * `options` - Owner, IPC and retention settings from the backend config
+/// * `live_containers` - Registry the running container is tracked in
+/// * `request` - Execution request with code and configuration
+///
+/// # Returns
+/// AsyncTask that resolves to execution result
+pub(super) fn execute_in_container(
+    runtime: String,
+    image: String,
+    options: ContainerOptions,
+    live_containers: LiveSet<String>,
+    request: ExecutionRequest,
+) -> AsyncTask<BackendResult<ExecutionResult>> {
+    AsyncTaskBuilder::new(async move {
+        let start_time = Instant::now();
+        let owner_id = &options.owner_id;
+
+        // Create unique container name
+        let container_name =
+            ids::artifact_name(ids::ID_PREFIX, owner_id, &ids::language_tag(&request.language));
+
+        // Prepare execution command based on language; the command only
+        // names the mounted source file and never embeds the code
+        let (source_file, mut exec_cmd) = prepare_execution_command(
+            &request.language,
+            request.compiler.as_ref(),
+            request.sql.as_ref(),
+        )?;
+        let source_dir = SourceDir::create(owner_id, &source_file, &request)?;
+        if let Some(repo) = &request.git_repo {
+            git_checkout::checkout(repo, source_dir.path(), &request).await?;
+        }
+
+        // The umask is applied, static and blocked names go into the
+        // container's /etc/hosts, and a virtual clock preloads libfaketime
+        // from the image before the code starts
+        let mut setup = format!("umask {:03o}\n", request.environment.umask);
+        if let Some(dns) = &request.dns
+            && (!dns.static_hosts.is_empty() || !dns.blocked_domains.is_empty())
+        {
+            source_dir.write(HOSTS_FILE, &dns.hosts())?;
+            setup.push_str(&format!("cat {SOURCE_MOUNT}/{HOSTS_FILE} > /etc/hosts || exit 1\n"));
+        }
+        if let Some(clock) = &request.clock {
+            setup.push_str(&clock::faketime_shell_setup(clock));
+        }
+        exec_cmd = with_setup(&setup, exec_cmd);
+
+        // Build container run command; --rm removes the container however
+        // it exits, and the reaper covers a CLI that dies first. A session's
+        // container is kept until it has been committed
+        let mut cmd = Command::new(&runtime);
+        cmd.arg("run");
+        if request.sandbox_state.is_none() {
+            cmd.arg("--rm");
+        }
+        cmd.args(["--name", &container_name]);
+        if request.input.is_some() || request.input_stream.is_some() {
+            cmd.arg("--interactive");
+        }
+        cmd.args(["--mount", &mount_spec(source_dir.path(), Path::new(SOURCE_MOUNT), true)?]);
+
+        // Host paths the request exposes, mounted at the same location
+        for path in exposed_paths(&request)? {
+            cmd.args(["--mount", &mount_spec(&path.source, &path.target, path.writable)?]);
+        }
+
+        // No privilege can be regained inside the container
+        cmd.args(["--cap-drop", "ALL", "--security-opt", "no-new-privileges"]);
+        cmd.args(["--ipc", if options.share_host_ipc { "host" } else { "private" }]);
+        cmd.args(limit_args(&request));
+
+        if !request.network_allowed() {
+            cmd.args(["--network", "none"]);
+        } else if let Some(dns) = &request.dns {
+            cmd.args(dns_args(&runtime, dns));
+        }
+
+        // Normalized environment, Go caches and R library first, so request
+        // variables win; HOME and the caches are directories next to the
+        // source file
+        source_dir.create_subdir(HOME_DIR)?;
+        let home = format!("{SOURCE_MOUNT}/{HOME_DIR}");
+        for (key, value) in request.environment.variables(&home) {
+            cmd.args(["-e", &format!("{key}={value}")]);
+        }
+        let cache_root = format!("{SOURCE_MOUNT}/{CACHE_DIR}");
+        for (key, value) in go_cache::go_env(&request, &cache_root) {
+            cmd.args(["-e", &format!("{key}={value}")]);
+        }
+        for (key, value) in r_library::r_env(&request, &cache_root) {
+            cmd.args(["-e", &format!("{key}={value}")]);
+        }
+
+        // Add environment variables
+        for (key, value) in &request.env_vars {
+            cmd.args(["-e", &format!("{key}={value}")]);
+        }
+
+        // Set working directory if specified; `..` escapes are rejected and
+        // the path is always absolute inside the container
+        if let Some(workdir) = &request.working_dir {
+            let relative = relative_inside(workdir)?;
+            cmd.args(["-w", &format!("/{}", relative.display())]);
+        }
+
+        // Multi-arch images provide the requested variant; foreign ones
+        // run through the host's binfmt emulation
+        if let Some(arch) = request.arch {
+            cmd.args(["--platform", &format!("linux/{}", arch.oci_name())]);
+        }
+
+        // Specify image and command
+        cmd.arg(&image);
+        cmd.args(&exec_cmd);
+
+        // Set up stdio
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        cmd.stdin(Stdio::piped());
+
+        // Lead a new process group so a forced kill reaches the whole client tree
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        // Execute the container
+        let mut child = cmd
+            .helper_spawn(Helper::ContainerCli)
+            .map_err(|e| BackendError::ProcessFailed {
+                details: format!("Failed to spawn {runtime}: {e}"),
+            })?;
+        let reaper_guard = global_reaper().track(
+            ResourceKind::Container {
+                runtime: runtime.clone(),
+                name: container_name.clone(),
+            },
+            "Docker",
+            request.execution_id.as_deref(),
+            Some(request.timeout + DEADLINE_GRACE),
+        );
+        let live_guard = live_containers.track(container_name.clone());
+
+        // Capture output incrementally so it survives a forced kill, and
+        // stream it to the request's sink as it arrives
+        let capture = OutputCapture::streaming(&mut child, request.output_sink.clone());
+
+        // Write input if provided
+        if let Some(input) = &request.input
+            && let Some(stdin) = child.stdin.take()
+        {
+            use std::io::Write;
+            let mut stdin = stdin;
+            stdin
+                .write_all(input.as_bytes())
+                .map_err(|e| BackendError::ProcessFailed {
+                    details: format!("Failed to write to container stdin: {e}"),
+                })?;
+        }
+        let _input_pump = input_stream::pipe(&mut child, &request)?;
+
+        // Wait for completion; on timeout ask the container to stop with
+        // SIGTERM, allow the grace period, then SIGKILL it and the CLI client
+        let timeout_duration = request.timeout;
+        #[cfg(unix)]
+        let client_pid = child.id();
+        let mut usage_at_timeout = None;
+        let outcome = process::wait_with_grace(
+            &mut child,
+            timeout_duration,
+            request.termination_grace,
+            || {
+                // Snapshot stats while the container still exists; --rm
+                // removes it as soon as it stops
+                usage_at_timeout = resource_stats::sample_resource_usage(&runtime, &container_name);
+                signal_container(&runtime, &container_name, "SIGTERM");
+            },
+            || {
+                signal_container(&runtime, &container_name, "SIGKILL");
+                #[cfg(unix)]
+                process::kill_group(client_pid);
+            },
+        )
+        .await
+        .map_err(|e| BackendError::ProcessFailed {
+            details: format!("Container execution failed: {e}"),
+        })?;
+        // A session's container that exited on its own is saved to the
+        // session and removed here, any other by --rm; one that was stopped
+        // on timeout is not saved and is left for the sweep to make sure of
+        let saved = match (&request.sandbox_state, &outcome) {
+            (Some(state), WaitOutcome::Exited(_)) => {
+                let committed = state.commit(&runtime, &container_name);
+                remove_container(&runtime, &container_name);
+                Some(committed)
+            }
+            (Some(_), _) => Some(Err("the execution did not exit on its own".to_string())),
+            (None, _) => None,
+        };
+        // A container not known to be removed is still sampled as the
+        // instance's
+        if matches!(outcome, WaitOutcome::Exited(_)) {
+            reaper_guard.release();
+        } else {
+            live_guard.linger();
+        }
+
+        let duration = start_time.elapsed();
+        let CapturedOutput { stdout, stderr, raw } = capture.collect(process::OUTPUT_DRAIN).await;
+
+        let mut result = match outcome {
+            WaitOutcome::Exited(status) => ExecutionResult {
+                exit_code: status.code().unwrap_or(-1),
+                stdout,
+                stderr,
+                duration,
+                // Parse resource usage from container stats (if available)
+                resource_usage: resource_stats::parse_resource_usage(&runtime, &container_name)
+                    .await
+                    .unwrap_or_default(),
+                metadata: HashMap::new(),
+                verdict: None,
+                outcome: ExecutionOutcome::Completed,
+                security: None,
+                cost: None,
+                crash: None,
+                result_sets: None,
+                workspace_archive: None,
+                blobs: HashMap::new(),
+                processed: None,
+                raw_output: None,
+                compilation: None,
+            },
+            // No watchdog runs here, so a stall can only be a timeout
+            WaitOutcome::TimedOut { graceful } | WaitOutcome::Stalled { graceful } => {
+                let mut result = ExecutionResult::timed_out(stdout, stderr, timeout_duration);
+                result.duration = duration;
+                result.resource_usage = usage_at_timeout.unwrap_or_default();
+                result.metadata.insert(
+                    "termination".to_string(),
+                    if graceful { "graceful" } else { "forced" }.to_string(),
+                );
+                result
+            }
+        };
+
+        result.raw_output = raw;
+        result.security = Some(SecurityReport {
+            isolation: IsolationLevel::Container,
+            network_disabled: !request.network_allowed(),
+            filesystem_read_only: false,
+            memory_limit_bytes: request.limits.max_memory,
+            ipc_isolated: !options.share_host_ipc,
+            ui_restricted: true,
+        });
+        result
+            .metadata
+            .insert("backend".to_string(), "Docker".to_string());
+        result.metadata.insert("runtime".to_string(), runtime.clone());
+        result.metadata.insert("image".to_string(), image);
+        if let Some(arch) = request.arch {
+            result.metadata.insert(ARCH_METADATA.to_string(), arch.to_string());
+        }
+        if let Some(dns) = &request.dns {
+            dns.record(&mut result);
+        }
+        if let Some(clock) = &request.clock {
+            result
+                .metadata
+                .insert(CLOCK_METADATA.to_string(), clock.mode().to_string());
+        }
+        if request.stall_timeout.is_some() {
+            // Liveness needs the sandbox's CPU use, which the CLI does not
+            // report cumulatively
+            result.metadata.insert(
+                WATCHDOG_METADATA.to_string(),
+                "not enforced: container CPU use is not observable from the host".to_string(),
+            );
+        }
+
+        // The engine reports a signal death as 128 + N; the core, if any,
+        // stays inside the container
+        if !result.is_timed_out() {
+            result.crash = CrashReport::from_exit_code(result.exit_code);
+        }
+        if let Some(crash) = &result.crash {
+            result
+                .metadata
+                .insert(SIGNAL_METADATA.to_string(), crash.signal_name.clone());
+            if request.core_dump_limit.is_some() && crash.dumps_core() {
+                result.metadata.insert(
+                    CORE_DUMP_METADATA.to_string(),
+                    "not captured: core dumps stay inside the container".to_string(),
+                );
+            }
+        }
+        if let Some(saved) = saved {
+            let saved = match saved {
+                Ok(()) => "saved".to_string(),
+                Err(e) => format!("not saved: {e}"),
+            };
+            result.metadata.insert(SANDBOX_STATE_METADATA.to_string(), saved);
+        }
+        result
+            .metadata
+            .insert("container_name".to_string(), container_name);
+        archive::attach_output(&request, source_dir.path(), &mut result);
+        let workspace = vec![source_dir.path().to_path_buf()];
+        if retention::retain_on_failure(options.retention, workspace, "Docker", &mut result) {
+            source_dir.retain();
+        }
+
+        Ok(result)
+    })
+    .spawn()
+}
+
+/// Send a signal to a running container
+fn signal_container(runtime: &str, container_name: &str, signal: &str) {
+    let _ = Command::new(runtime)
+        .args(["kill", "--signal", signal, container_name])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .helper_status(Helper::ContainerCli);
+}
+
+/// Remove a stopped container
+fn remove_container(runtime: &str, container_name: &str) {
+    let _ = Command::new(runtime)
+        .args(["rm", "-f", container_name])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .helper_status(Helper::ContainerCli);
+}
+
+/// Container path the host source directory is mounted at
+const SOURCE_MOUNT: &str = "/cylo-src";
+
+/// Name of the rendered hosts file inside the source directory
+const HOSTS_FILE: &str = "hosts";
+
+/// Name of the home directory inside the source directory
+const HOME_DIR: &str = "home";
+
+/// Name of the Go cache directory inside the source directory
+const CACHE_DIR: &str = ".cache";
+
+/// `run` flags enforcing the request's resource limits
+///
+/// Memory swap is capped at the memory limit so the limit cannot be
+/// stretched onto disk; CPU time and file size become rlimits inside the
+/// container.
+fn limit_args(request: &ExecutionRequest) -> Vec<String> {
+    let limits = &request.limits;
+    let mut args = Vec::new();
+    if let Some(memory) = limits.max_memory {
+        args.extend(["--memory".to_string(), format!("{memory}b")]);
+        args.extend(["--memory-swap".to_string(), format!("{memory}b")]);
+    }
+    if let Some(cpu_time) = limits.max_cpu_time {
+        args.extend(["--ulimit".to_string(), format!("cpu={cpu_time}")]);
+    }
+    if let Some(processes) = limits.max_processes {
+        args.extend(["--pids-limit".to_string(), processes.to_string()]);
+    }
+    if let Some(file_size) = limits.max_file_size {
+        args.extend(["--ulimit".to_string(), format!("fsize={file_size}")]);
+    }
+    if let Some(cpus) = &limits.cpuset {
+        args.extend(["--cpuset-cpus".to_string(), cpus.to_string()]);
+    }
+    if let Some(nodes) = &limits.numa_nodes {
+        args.extend(["--cpuset-mems".to_string(), nodes.to_string()]);
+    }
+    args
+}
+
+/// `run` flags selecting the resolvers of a DNS policy
+///
+/// Podman can leave the container without a resolv.conf; docker cannot,
+/// so it is pointed at the container's own loopback where nothing answers.
+fn dns_args(runtime: &str, policy: &DnsPolicy) -> Vec<String> {
+    let mut args = Vec::new();
+    match &policy.resolvers {
+        DnsResolvers::Host => {}
+        DnsResolvers::Fixed(servers) => {
+            for server in servers {
+                args.extend(["--dns".to_string(), server.to_string()]);
+            }
+        }
+        DnsResolvers::None if is_podman(runtime) => {
+            args.extend(["--dns".to_string(), "none".to_string()]);
+        }
+        DnsResolvers::None => args.extend(["--dns".to_string(), "127.0.0.1".to_string()]),
+    }
+    for domain in &policy.search_domains {
+        args.extend(["--dns-search".to_string(), domain.clone()]);
+    }
+    args
+}
+
+/// `--mount` argument bind-mounting a host path
+///
+/// The mount syntax is comma-separated key=value pairs, so paths containing
+/// a comma cannot be expressed and are rejected rather than mis-mounted.
+/// Colons are fine, which keeps Windows host paths mountable.
+fn mount_spec(source: &Path, target: &Path, writable: bool) -> BackendResult<String> {
+    let (source, target) = (source.display().to_string(), target.display().to_string());
+    if source.contains(',') || target.contains(',') {
+        return Err(BackendError::InvalidConfig {
+            backend: "Docker",
+            details: format!("Cannot mount {target}: paths containing ',' are not supported"),
+        });
+    }
+    let mode = if writable { "" } else { ",readonly" };
+    Ok(format!("type=bind,source={source},target={target}{mode}"))
+}
+
+/// Wrap a command so shell `setup` lines run before it
+fn with_setup(setup: &str, command: Vec<String>) -> Vec<String> {
+    let script = format!("{setup}exec \"$@\"");
+    ["sh".to_string(), "-c".to_string(), script, "sh".to_string()]
+        .into_iter()
+        .chain(command)
+        .collect()
+}
+
+/// Host directory holding the source file mounted into the container
+///
+/// Removed when dropped, on success and on every error path, unless it
+/// was retained.
+struct SourceDir {
+    path: PathBuf,
+    retained: bool,
+}
+
+impl SourceDir {
+    /// Create the directory and write the request's workspace archive,
+    /// additional files and blobs, and code into it, in that order
+    fn create(owner_id: &str, file_name: &str, request: &ExecutionRequest) -> BackendResult<Self> {
+        // `cylo_<owner>_docker_<random>` so startup recovery finds leftovers
+        let path = std::env::temp_dir().join(ids::workspace_dir_name(owner_id, "docker"));
+        fs::create_dir_all(&path).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to create source directory: {e}"),
+        })?;
+
+        // Dropping the guard removes the directory if a write fails
+        let dir = Self {
+            path,
+            retained: false,
+        };
+        archive::unpack_workspace(request, &dir.path, "Docker")?;
+        write_files(&dir.path, &request.files)?;
+        write_blob_files(&dir.path, &request.blob_files)?;
+        fs::write(dir.path.join(file_name), &request.code).map_err(|e| {
+            BackendError::FileSystemFailed {
+                details: format!("Failed to write source file: {e}"),
+            }
+        })?;
+        Ok(dir)
+    }
+
+    /// Write an additional file next to the source file
+    fn write(&self, file_name: &str, content: &str) -> BackendResult<()> {
+        fs::write(self.path.join(file_name), content).map_err(|e| {
+            BackendError::FileSystemFailed {
+                details: format!("Failed to write {file_name}: {e}"),
+            }
+        })
+    }
+
+    /// Create an empty directory next to the source file
+    fn create_subdir(&self, name: &str) -> BackendResult<()> {
+        fs::create_dir_all(self.path.join(name)).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to create {name} directory: {e}"),
+        })
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Leave the directory in place when dropped; the reaper removes it
+    /// once its retention period is over
+    fn retain(mut self) {
+        self.retained = true;
+    }
+}
+
+impl Drop for SourceDir {
+    fn drop(&mut self) {
+        if !self.retained {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// Prepare execution command for specific language
+///
+/// # Arguments
+/// * `language` - Programming language
+/// * `compiler` - Compiler options for compiled languages
+/// * `sql` - Engine and seeds of a SQL request
+///
+/// # Returns
+/// Source file name to write under the mount and the command arguments
+/// for container execution
+pub(super) fn prepare_execution_command(
+    language: &str,
+    compiler: Option<&CompilerOptions>,
+    sql: Option<&SqlOptions>,
+) -> BackendResult<(String, Vec<String>)> {
+    // The pinned version picked the image (`ImageReference::for_runtime`)
+    let (name, _) = language::split_version(language);
+    let options = compiler.cloned().unwrap_or_default();
+    let shell = |script: String| vec!["sh".to_string(), "-c".to_string(), script];
+    let (source_file, command) = match name.to_lowercase().as_str() {
+        "python" | "python3" => ("main.py", vec!["python3".into(), "/cylo-src/main.py".into()]),
+        "javascript" | "js" | "node" => {
+            ("main.js", vec!["node".into(), "/cylo-src/main.js".into()])
+        }
+        "rust" => (
+            "main.rs",
+            shell(compile_phase::compile_then_run(
+                &format!(
+                    "rustc {}/cylo-src/main.rs -o /tmp/main",
+                    compiler::shell_args(&options.rustc_args())
+                ),
+                "exec /tmp/main",
+            )),
+        ),
+        "bash" | "sh" => ("main.sh", vec!["sh".into(), "/cylo-src/main.sh".into()]),
+        "go" => (
+            "main.go",
+            shell(compile_phase::compile_then_run(
+                &format!(
+                    "cd /tmp && go build -o /tmp/main {}/cylo-src/main.go",
+                    compiler::shell_args(&options.go_args())
+                ),
+                "exec /tmp/main",
+            )),
+        ),
+        "r" | "rscript" => (
+            "main.R",
+            shell(r_library::rscript_command("Rscript", "/cylo-src/main.R")),
+        ),
+        "sql" | "sqlite" | "sqlite3" | "duckdb" => {
+            let engine = SqlEngine::of(language, sql);
+            let args = sql::shell_args(engine, sql, "/cylo-src/main.sql").map_err(|details| {
+                BackendError::InvalidConfig {
+                    backend: "Docker",
+                    details,
+                }
+            })?;
+            let mut command = vec![engine.program().to_string()];
+            command.extend(args);
+            ("main.sql", command)
+        }
+        _ => {
+            return Err(BackendError::UnsupportedLanguage {
+                backend: "Docker",
+                language: language.to_string(),
+            });
+        }
+    };
+
+    Ok((source_file.to_string(), command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::{CpuSet, ResourceLimits};
+
+    #[test]
+    fn limits_and_mounts_map_to_run_flags() {
+        let mut request = ExecutionRequest::new("print(1)", "python");
+        request.limits = ResourceLimits {
+            max_memory: Some(64 * 1024 * 1024),
+            max_processes: Some(32),
+            cpuset: CpuSet::new([0, 1, 2, 5]),
+            ..ResourceLimits::default()
+        };
+        let args = limit_args(&request);
+        let flag = |name: &str| {
+            let at = args.iter().position(|arg| arg == name)?;
+            args.get(at + 1).cloned()
+        };
+        assert_eq!(flag("--memory").as_deref(), Some("67108864b"));
+        assert_eq!(flag("--memory-swap").as_deref(), Some("67108864b"));
+        assert_eq!(flag("--pids-limit").as_deref(), Some("32"));
+        assert_eq!(flag("--cpuset-cpus").as_deref(), Some("0-2,5"));
+        assert!(flag("--ulimit").is_none());
+
+        let data = Path::new("C:\\Users\\dev\\data");
+        assert_eq!(
+            mount_spec(data, Path::new("/data"), false).unwrap(),
+            "type=bind,source=C:\\Users\\dev\\data,target=/data,readonly"
+        );
+        assert!(mount_spec(Path::new("/a,b"), Path::new("/data"), true).is_err());
+
+        let no_dns = DnsPolicy::only([("api.internal", "10.0.0.1".parse().unwrap())]);
+        assert_eq!(dns_args("docker", &no_dns), ["--dns", "127.0.0.1"]);
+        assert_eq!(dns_args("/usr/bin/podman", &no_dns), ["--dns", "none"]);
+
+        let (file, rust_cmd) = prepare_execution_command("rust", None, None).unwrap();
+        assert_eq!(file, "main.rs");
+        assert!(rust_cmd[2].ends_with("exec /tmp/main"), "{}", rust_cmd[2]);
+        assert!(prepare_execution_command("cobol", None, None).is_err());
+    }
+}
@@ -0,0 +1,450 @@
+// ============================================================================
+// File: packages/cylo/src/backends/docker/image.rs
+// ----------------------------------------------------------------------------
+// Container image management for the Docker/Podman backend.
+// ============================================================================
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::AsyncTaskBuilder;
+use crate::backends::image_ref::is_valid_digest;
+use crate::backends::progress::{ProgressReporter, ProvisioningStage};
+use crate::backends::provisioning::{self, StageBudget};
+use crate::backends::{
+    AsyncTask, BackendError, BackendResult, ImageReference, ImageStore, ProvisioningLimits,
+    RegistryCredentials, RegistryLogin, StoredImage,
+};
+use crate::hardening::{Helper, HelperCommand};
+
+/// Interval at which a CLI process is checked against its budget
+const WAIT_POLL: Duration = Duration::from_millis(50);
+
+/// Ask the engine behind `runtime` for its version
+///
+/// Fails when the CLI is missing or its engine is not running, e.g. a
+/// stopped docker daemon or an uninitialized podman machine.
+///
+/// # Returns
+/// AsyncTask that resolves to the engine's version
+pub(super) fn engine_version(runtime: String) -> AsyncTask<BackendResult<String>> {
+    AsyncTaskBuilder::new(async move {
+        let output = Command::new(&runtime)
+            .args(["info", "--format", "{{json .}}"])
+            .stdin(Stdio::null())
+            .helper_output(Helper::ContainerCli)
+            .map_err(|e| BackendError::ContainerFailed {
+                details: format!("Failed to execute {runtime} info: {e}"),
+            })?;
+        if !output.status.success() {
+            return Err(BackendError::ContainerFailed {
+                details: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+
+        // Docker reports ServerVersion, podman nests it under version
+        let info = serde_json::from_slice::<serde_json::Value>(&output.stdout)
+            .unwrap_or_default();
+        let version = info["ServerVersion"]
+            .as_str()
+            .or_else(|| info["version"]["Version"].as_str())
+            .unwrap_or("unknown");
+        Ok(version.to_string())
+    })
+    .spawn()
+}
+
+/// Pull container image if not already available
+///
+/// Images held by the offline image store are loaded from it without
+/// contacting a registry. Otherwise the image's registry is logged in to
+/// first when credentials are configured for it. The CLI reports layers
+/// rather than percentages when not writing to a terminal, so progress is
+/// the share of layers pulled, forwarded to `progress` as `PullingImage`
+/// events. A pull or store load running past `limits.pull_timeout`, or a
+/// pull whose reported size exceeds `limits.max_download_bytes`, is killed.
+///
+/// # Arguments
+/// * `runtime` - Container CLI to drive
+/// * `image` - Image to pull
+/// * `store` - Offline image store from the backend config, if any
+/// * `credentials` - Registry credentials from the backend config
+/// * `limits` - Provisioning budgets from the backend config
+/// * `progress` - Reporter of the request being provisioned, if any
+///
+/// # Returns
+/// AsyncTask that resolves when image is available
+pub(super) fn ensure_image_available(
+    runtime: String,
+    image: String,
+    store: Option<ImageStore>,
+    credentials: RegistryCredentials,
+    limits: ProvisioningLimits,
+    progress: Option<ProgressReporter>,
+) -> AsyncTask<BackendResult<()>> {
+    AsyncTaskBuilder::new(async move {
+        let stage = ProvisioningStage::PullingImage;
+        let budget = StageBudget::start("Docker", stage, limits.pull_timeout);
+
+        // Check if image exists locally first
+        let check_result = Command::new(&runtime)
+            .args(["image", "inspect", &image])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .helper_status(Helper::ContainerCli);
+        if check_result.is_ok_and(|status| status.success()) {
+            return Ok(());
+        }
+
+        let reference = ImageReference::parse(&image).ok();
+        if let (Some(store), Some(reference)) = (&store, &reference)
+            && let Some(stored) = store.lookup(reference)
+        {
+            if let Some(progress) = &progress {
+                progress.report(
+                    ProvisioningStage::PullingImage,
+                    None,
+                    format!("Loading {image} from the image store"),
+                );
+            }
+            return load_from_store(&runtime, store, &stored, &budget);
+        }
+
+        if let Some(reference) = &reference
+            && let Some(login) = credentials.resolve(reference.registry())?
+        {
+            registry_login(&runtime, &login)?;
+        }
+
+        let report = |percent: Option<u8>| {
+            if let Some(progress) = &progress {
+                let message = format!("Pulling {image}");
+                progress.report(ProvisioningStage::PullingImage, percent, message);
+            }
+        };
+        report(Some(0));
+
+        // Progress goes to stdout, errors to a file so a failing pull
+        // cannot block on a full pipe
+        let staging = tempfile::tempdir().map_err(|e| BackendError::ContainerFailed {
+            details: format!("Failed to create pull log dir: {e}"),
+        })?;
+        let log_path = staging.path().join("pull.log");
+        let log = File::create(&log_path).map_err(|e| BackendError::ContainerFailed {
+            details: format!("Failed to create pull log: {e}"),
+        })?;
+        let mut child = Command::new(&runtime)
+            .args(["pull", &image])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(log)
+            .helper_spawn(Helper::ContainerCli)
+            .map_err(|e| BackendError::ContainerFailed {
+                details: format!("Failed to execute {runtime} pull: {e}"),
+            })?;
+
+        // Lines are read on their own thread so the budget is enforced
+        // even while the CLI prints nothing
+        let (lines, received) = mpsc::channel::<String>();
+        if let Some(stdout) = child.stdout.take() {
+            thread::spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else { break };
+                    if lines.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        let mut layers = LayerProgress::default();
+        let mut last_percent = Some(0);
+        loop {
+            let line = match budget.remaining() {
+                Some(remaining) => received.recv_timeout(remaining),
+                None => received.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            let line = match line {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => {
+                    kill(&mut child);
+                    return Err(budget.exceeded());
+                }
+            };
+            if let (Some(bytes), Some(max)) =
+                (provisioning::parse_bytes(&line), limits.max_download_bytes)
+                && bytes > max
+            {
+                kill(&mut child);
+                return Err(BackendError::ResourceLimitExceeded {
+                    resource: format!("download size of image {image}"),
+                    limit: format!("{max} bytes"),
+                });
+            }
+            layers.observe(&line);
+            let percent = layers.percent();
+            if percent.is_some() && percent != last_percent {
+                last_percent = percent;
+                report(percent);
+            }
+        }
+
+        if wait_within(&mut child, &budget)?.success() {
+            report(Some(100));
+            Ok(())
+        } else {
+            let stderr = std::fs::read_to_string(&log_path).unwrap_or_default();
+            Err(BackendError::ContainerFailed {
+                details: format!("Failed to pull image {image}: {}", stderr.trim()),
+            })
+        }
+    })
+    .spawn()
+}
+
+/// Layers seen on `pull` output lines and those finished
+///
+/// Docker prints `<layer>: Pulling fs layer` and later
+/// `<layer>: Pull complete` (or `Already exists`); podman prints
+/// `Copying blob <layer>` and appends `done` once it has it.
+#[derive(Debug, Default)]
+struct LayerProgress {
+    seen: HashSet<String>,
+    done: HashSet<String>,
+}
+
+impl LayerProgress {
+    fn observe(&mut self, line: &str) {
+        let line = line.trim();
+        let (layer, status) = match line.strip_prefix("Copying blob ") {
+            Some(rest) => rest.split_once(' ').unwrap_or((rest, "")),
+            None => match line.split_once(": ") {
+                Some((layer, status)) if is_layer_id(layer) => (layer, status),
+                _ => return,
+            },
+        };
+        self.seen.insert(layer.to_string());
+        let status = status.trim();
+        let finished = matches!(status, "Pull complete" | "Already exists")
+            || status.starts_with("done")
+            || status.contains("skipped");
+        if finished {
+            self.done.insert(layer.to_string());
+        }
+    }
+
+    /// Share of seen layers pulled; None until a layer is seen
+    fn percent(&self) -> Option<u8> {
+        if self.seen.is_empty() {
+            return None;
+        }
+        Some((self.done.len() * 100 / self.seen.len()) as u8)
+    }
+}
+
+/// Whether `id` looks like a short layer ID as docker prints it
+fn is_layer_id(id: &str) -> bool {
+    id.len() >= 12 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Load an image from the offline store into the container engine
+///
+/// Exporting and loading share the pull's budget.
+fn load_from_store(
+    runtime: &str,
+    store: &ImageStore,
+    stored: &StoredImage,
+    budget: &StageBudget,
+) -> BackendResult<()> {
+    let failed = |details: String| BackendError::ContainerFailed { details };
+
+    let staging = tempfile::tempdir()
+        .map_err(|e| failed(format!("Failed to create image staging dir: {e}")))?;
+    let archive = staging.path().join("image.tar");
+    store
+        .export(stored, &archive)
+        .map_err(|e| failed(format!("Failed to export {}: {e}", stored.reference)))?;
+    if budget.remaining().is_some_and(|remaining| remaining.is_zero()) {
+        return Err(budget.exceeded());
+    }
+
+    // Errors go to a file so a chatty load cannot block on a full pipe
+    // while it is being waited on
+    let log_path = staging.path().join("load.log");
+    let log = File::create(&log_path)
+        .map_err(|e| failed(format!("Failed to create image load log: {e}")))?;
+    let mut child = Command::new(runtime)
+        .args(["load", "--input"])
+        .arg(&archive)
+        .stdout(Stdio::null())
+        .stderr(log)
+        .helper_spawn(Helper::ContainerCli)
+        .map_err(|e| failed(format!("Failed to execute {runtime} load: {e}")))?;
+    if wait_within(&mut child, budget)?.success() {
+        Ok(())
+    } else {
+        Err(failed(format!(
+            "Failed to load {} from the image store: {}",
+            stored.reference,
+            std::fs::read_to_string(&log_path).unwrap_or_default().trim()
+        )))
+    }
+}
+
+/// Wait for a CLI process, killing it once `budget` is spent
+fn wait_within(child: &mut Child, budget: &StageBudget) -> BackendResult<ExitStatus> {
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Ok(status),
+            Ok(None) => {}
+            Err(e) => {
+                return Err(BackendError::ContainerFailed {
+                    details: format!("Failed to wait for container CLI: {e}"),
+                });
+            }
+        }
+        if budget.remaining().is_some_and(|remaining| remaining.is_zero()) {
+            kill(child);
+            return Err(budget.exceeded());
+        }
+        thread::sleep(WAIT_POLL);
+    }
+}
+
+/// Kill and reap a CLI process abandoned mid-step
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Log the container CLI in to a registry
+///
+/// The secret is passed on stdin so it never appears in the process list.
+fn registry_login(runtime: &str, login: &RegistryLogin) -> BackendResult<()> {
+    let failed = |details: String| BackendError::ContainerFailed { details };
+
+    let mut child = Command::new(runtime)
+        .args(["login", "--username", &login.username, "--password-stdin"])
+        .arg(&login.registry)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .helper_spawn(Helper::ContainerCli)
+        .map_err(|e| failed(format!("Failed to execute {runtime} login: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(login.secret.as_bytes())
+            .map_err(|e| failed(format!("Failed to pass registry secret: {e}")))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failed(format!("Failed to execute {runtime} login: {e}")))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(failed(format!(
+            "Login to {} failed: {}",
+            login.registry,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Look up the content digest of a local image
+///
+/// # Arguments
+/// * `runtime` - Container CLI to drive
+/// * `image` - Image reference as pulled
+///
+/// # Returns
+/// AsyncTask that resolves to the image's `sha256:` digest
+pub(super) fn resolve_image_digest(
+    runtime: String,
+    image: String,
+) -> AsyncTask<BackendResult<String>> {
+    AsyncTaskBuilder::new(async move {
+        let output = Command::new(&runtime)
+            .args(["image", "inspect", &image])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .helper_output(Helper::ContainerCli)
+            .map_err(|e| BackendError::ContainerFailed {
+                details: format!("Failed to execute {runtime} image inspect: {e}"),
+            })?;
+        if !output.status.success() {
+            return Err(BackendError::ContainerFailed {
+                details: format!(
+                    "Failed to inspect image {image}: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+            });
+        }
+
+        serde_json::from_slice::<serde_json::Value>(&output.stdout)
+            .ok()
+            .and_then(|inspect| find_digest(&inspect))
+            .ok_or_else(|| BackendError::ContainerFailed {
+                details: format!("No digest reported for image {image}"),
+            })
+    })
+    .spawn()
+}
+
+/// Digest of the manifest the image was pulled by
+///
+/// Pulled images list it in `RepoDigests` as `name@sha256:<hex>`; images
+/// built or loaded locally have none, and their config digest (`Id`)
+/// stands in.
+fn find_digest(inspect: &serde_json::Value) -> Option<String> {
+    let image = inspect.get(0).unwrap_or(inspect);
+    let repo_digest = image["RepoDigests"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.as_str()?.rsplit_once('@'))
+        .map(|(_, digest)| digest)
+        .find(|digest| is_valid_digest(digest));
+    repo_digest
+        .or_else(|| image["Id"].as_str().filter(|id| is_valid_digest(id)))
+        .map(str::to_ascii_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_and_pull_progress_are_read_from_cli_output() {
+        let manifest = format!("sha256:{}", "a".repeat(64));
+        let config = format!("sha256:{}", "b".repeat(64));
+        let pulled = serde_json::json!([{
+            "Id": config,
+            "RepoDigests": [format!("python@{manifest}")]
+        }]);
+        assert_eq!(find_digest(&pulled), Some(manifest));
+        let built = serde_json::json!([{ "Id": config, "RepoDigests": [] }]);
+        assert_eq!(find_digest(&built), Some(config));
+        assert_eq!(find_digest(&serde_json::json!([{ "Id": "latest" }])), None);
+
+        let mut docker = LayerProgress::default();
+        assert_eq!(docker.percent(), None);
+        docker.observe("3.12-alpine: Pulling from library/python");
+        docker.observe("4abcf2066143: Pulling fs layer");
+        docker.observe("5ea7d4a5e6f1: Already exists");
+        docker.observe("8e2e3a6a5d1c: Pulling fs layer");
+        docker.observe("4abcf2066143: Pull complete");
+        assert_eq!(docker.percent(), Some(66));
+
+        let mut podman = LayerProgress::default();
+        podman.observe("Copying blob sha256:4abcf2066143");
+        podman.observe("Copying blob 5ea7d4a5e6f1 done");
+        assert_eq!(podman.percent(), Some(50));
+    }
+}
@@ -0,0 +1,401 @@
+// ============================================================================
+// File: packages/cylo/src/backends/docker/mod.rs
+// ----------------------------------------------------------------------------
+// Docker/Podman container backend for secure code execution.
+//
+// Implements ExecutionBackend trait by driving the docker or podman CLI.
+// Hosts without KVM or a Landlock-capable kernel often still run a
+// container engine; this backend gives them container isolation with:
+// - OCI images pulled within the provisioning budgets
+// - Memory, CPU, process and network limits enforced by the engine
+// - Per-execution containers removed on exit, timeout and cleanup
+// - Sessions continuing from the container they committed last
+// ============================================================================
+
+mod execution;
+mod image;
+mod resource_stats;
+
+#[cfg(test)]
+mod tests;
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::AsyncTaskBuilder;
+use crate::backends::{arch, desktop, io_throttle, language, python_env, runtime};
+use crate::backends::{
+    AsyncTask, BackendConfig, BackendError, BackendResult, ExecutionBackend, ExecutionRequest,
+    ExecutionResult, HealthCheckLevel, HealthStatus, ImageReference, ImageStore, InstanceMetrics,
+    ProvisioningStage,
+};
+use crate::backends::live::LiveSet;
+use crate::hardening::{Helper, HelperCommand};
+
+/// `backend_specific` key naming the container CLI to drive, e.g.
+/// "podman" or an absolute path; by default the first of docker and
+/// podman found on PATH
+pub const CONTAINER_RUNTIME_KEY: &str = "container_runtime";
+
+/// Container CLIs the backend can drive, in order of preference
+const RUNTIMES: [&str; 2] = ["docker", "podman"];
+
+/// First supported container CLI installed on the host PATH
+///
+/// Only looks for the binary, so it is cheap enough for platform
+/// detection; whether its engine is running is up to the health check.
+pub fn installed_runtime() -> Option<&'static str> {
+    let search_path = runtime::host_search_path();
+    RUNTIMES.into_iter().find(|name| {
+        search_path.iter().any(|dir| {
+            dir.join(name).is_file() || dir.join(format!("{name}.exe")).is_file()
+        })
+    })
+}
+
+/// Docker/Podman container backend
+///
+/// Runs every execution in a fresh container of the configured image,
+/// removed once it exits. Works with any engine speaking the docker CLI,
+/// rootless podman included.
+#[derive(Debug, Clone)]
+pub struct DockerBackend {
+    /// Container CLI driven, "docker" or "podman"
+    runtime: String,
+
+    /// Container image specification (e.g., "python:3.12-alpine")
+    image: String,
+
+    /// Parsed image specification, carrying any pinned digest
+    reference: ImageReference,
+
+    /// Backend configuration
+    config: BackendConfig,
+
+    /// Names of the containers currently running executions
+    live_containers: LiveSet<String>,
+}
+
+impl DockerBackend {
+    /// Create a new Docker backend instance
+    ///
+    /// # Arguments
+    /// * `image` - Container image specification
+    /// * `config` - Backend configuration; `CONTAINER_RUNTIME_KEY` picks
+    ///   the CLI
+    ///
+    /// # Returns
+    /// New Docker backend instance or error if no container CLI is installed
+    pub fn new(image: String, config: BackendConfig) -> BackendResult<Self> {
+        let reference =
+            ImageReference::parse(&image).map_err(|details| BackendError::InvalidConfig {
+                backend: "Docker",
+                details: format!(
+                    "Invalid image format: {image}. Expected format: 'name:tag' or \
+                     'name@sha256:<digest>': {details}"
+                ),
+            })?;
+
+        let runtime = match config.backend_specific.get(CONTAINER_RUNTIME_KEY) {
+            Some(runtime) => runtime.clone(),
+            None => installed_runtime()
+                .ok_or_else(|| BackendError::NotAvailable {
+                    backend: "Docker",
+                    reason: "neither docker nor podman is installed".to_string(),
+                })?
+                .to_string(),
+        };
+
+        Ok(Self {
+            runtime,
+            image,
+            reference,
+            config,
+            live_containers: LiveSet::default(),
+        })
+    }
+
+    /// Container CLI this backend drives
+    pub fn runtime(&self) -> &str {
+        &self.runtime
+    }
+}
+
+impl ExecutionBackend for DockerBackend {
+    fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
+        let runtime = self.runtime.clone();
+        let image = self.image.clone();
+        let configured = self.reference.clone();
+        let credentials = self.config.registry_credentials.clone();
+        let limits = self.config.provisioning.clone();
+        let store = ImageStore::from_backend_config(&self.config);
+        let options = execution::ContainerOptions {
+            owner_id: self.config.owner_id.clone(),
+            share_host_ipc: self.config.share_host_ipc,
+            retention: self.config.retain_workspace_on_failure,
+        };
+        let backend_name = self.backend_type();
+        let live_containers = self.live_containers.clone();
+
+        AsyncTaskBuilder::new(async move {
+            if let Err(e) = python_env::reject_in_guest(&request, backend_name)
+                .and_then(|()| desktop::reject_in_guest(&request, backend_name))
+                .and_then(|()| io_throttle::reject_unthrottled(&request, backend_name))
+                .and_then(|()| arch::check(&request, backend_name))
+            {
+                return ExecutionResult::failure(-1, e.to_string());
+            }
+
+            // A pinned runtime version runs the image tagged with it
+            let reference = match configured.for_runtime(&request.language, backend_name) {
+                Ok(runtime_reference) => runtime_reference,
+                Err(e) => return ExecutionResult::failure(-1, e.to_string()),
+            };
+            let image = if reference == configured { image } else { reference.to_string() };
+
+            // Ensure image is available
+            let progress = request.progress.clone();
+            let pulled = image::ensure_image_available(
+                runtime.clone(),
+                image.clone(),
+                store,
+                credentials,
+                limits,
+                progress,
+            );
+            match pulled.await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    return ExecutionResult::failure(-1, format!("Failed to prepare image: {e}"));
+                }
+                Err(e) => {
+                    return ExecutionResult::failure(
+                        -1,
+                        format!("Failed to prepare image task: {e}"),
+                    );
+                }
+            }
+            // Tags can be repointed; run only the pinned content, and record
+            // what actually ran either way
+            let digest = match image_digest(&runtime, &image).await {
+                Ok(digest) => digest,
+                Err(e) => return ExecutionResult::failure(-1, e),
+            };
+            if let Err(e) = reference.verify(&digest) {
+                return ExecutionResult::failure(-1, e);
+            }
+
+            // A session continues from the container it committed last, so
+            // that commit is what runs and what is recorded
+            let (image, reference, digest) = match &request.sandbox_state {
+                Some(state) if state.has_image(&runtime) => {
+                    let committed = state.image();
+                    let reference = match ImageReference::parse(&committed) {
+                        Ok(reference) => reference,
+                        Err(e) => return ExecutionResult::failure(-1, e),
+                    };
+                    match image_digest(&runtime, &committed).await {
+                        Ok(digest) => (committed, reference, digest),
+                        Err(e) => return ExecutionResult::failure(-1, e),
+                    }
+                }
+                _ => (image, reference, digest),
+            };
+            request.report_progress(ProvisioningStage::Ready, None, "Starting container");
+
+            // Execute in container
+            let execution = execution::execute_in_container(
+                runtime,
+                image,
+                options,
+                live_containers,
+                request,
+            );
+            match execution.await {
+                Ok(Ok(mut result)) => {
+                    reference.record(&mut result, digest);
+                    result
+                }
+                Ok(Err(e)) => {
+                    ExecutionResult::failure(-1, format!("{backend_name} execution failed: {e}"))
+                }
+                Err(e) => ExecutionResult::failure(
+                    -1,
+                    format!("{backend_name} execution task failed: {e}"),
+                ),
+            }
+        })
+        .spawn()
+    }
+
+    fn instance_metrics(&self) -> AsyncTask<Option<InstanceMetrics>> {
+        let runtime = self.runtime.clone();
+        let live_containers = self.live_containers.clone();
+
+        AsyncTaskBuilder::new(async move {
+            let samples = live_containers.sample(|name| {
+                resource_stats::sample_resource_usage(&runtime, name)
+                    .map(|usage| (usage.peak_memory, usage.cpu_time_ms))
+            });
+            Some(InstanceMetrics::from_samples(samples))
+        })
+        .spawn()
+    }
+
+    fn health_check(&self, level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
+        let runtime = self.runtime.clone();
+        let image = self.image.clone();
+        let options = execution::ContainerOptions {
+            owner_id: self.config.owner_id.clone(),
+            share_host_ipc: self.config.share_host_ipc,
+            retention: Duration::ZERO,
+        };
+        let live_containers = self.live_containers.clone();
+
+        AsyncTaskBuilder::new(async move {
+            // The CLI can be installed while its engine is stopped; `info`
+            // only succeeds once the engine answers
+            let engine = image::engine_version(runtime.clone()).await;
+            let version = match engine {
+                Ok(Ok(version)) => version,
+                Ok(Err(e)) => {
+                    return HealthStatus::unhealthy(format!("{runtime} engine not reachable: {e}"))
+                        .with_metric("runtime", &runtime)
+                        .with_metric("engine_available", "false");
+                }
+                Err(e) => {
+                    return HealthStatus::unhealthy(format!("Health check task error: {e}"))
+                        .with_metric("runtime", &runtime);
+                }
+            };
+
+            if level == HealthCheckLevel::Liveness {
+                return HealthStatus::healthy(format!("{runtime} engine available"))
+                    .with_metric("runtime", &runtime)
+                    .with_metric("engine_available", "true")
+                    .with_metric("engine_version", &version);
+            }
+
+            // Test container execution with simple command; an image that
+            // is not pulled yet fails the probe rather than pulling here
+            let test_request = ExecutionRequest::new("echo 'health check'", "bash")
+                .with_timeout(Duration::from_secs(10));
+            let probe = execution::execute_in_container(
+                runtime.clone(),
+                image.clone(),
+                options,
+                live_containers,
+                test_request,
+            );
+            match probe.await {
+                Ok(Ok(result)) if result.is_success() => {
+                    HealthStatus::healthy(format!("{runtime} backend operational"))
+                        .with_metric("runtime", &runtime)
+                        .with_metric("engine_available", "true")
+                        .with_metric("engine_version", &version)
+                        .with_metric("test_execution", "success")
+                        .with_metric("image", &image)
+                }
+                Ok(Ok(result)) => {
+                    HealthStatus::unhealthy(format!("Test execution failed: {}", result.stderr))
+                        .with_metric("test_execution", "failed")
+                        .with_metric("exit_code", result.exit_code.to_string())
+                }
+                Ok(Err(e)) => HealthStatus::unhealthy(format!("Health check execution error: {e}"))
+                    .with_metric("test_execution", "error"),
+                Err(e) => HealthStatus::unhealthy(format!("Health check task error: {e}"))
+                    .with_metric("test_execution", "task_error"),
+            }
+        })
+        .spawn()
+    }
+
+    fn cleanup(&self) -> AsyncTask<crate::execution_env::CyloResult<()>> {
+        let runtime = self.runtime.clone();
+        let name_filter = format!("name=cylo-{}-", self.config.owner_id);
+
+        AsyncTaskBuilder::new(async move {
+            // Clean up dangling containers created by this executor
+            let cleanup_result = Command::new(&runtime)
+                .args([
+                    "ps",
+                    "-a",
+                    "--filter",
+                    &name_filter,
+                    "--format",
+                    "{{.Names}}",
+                ])
+                .stderr(Stdio::null())
+                .helper_output(Helper::ContainerCli);
+
+            if let Ok(output) = cleanup_result
+                && output.status.success()
+            {
+                let container_names = String::from_utf8_lossy(&output.stdout);
+                for name in container_names.lines() {
+                    if !name.trim().is_empty() {
+                        let _ = Command::new(&runtime)
+                            .args(["rm", "-f", name.trim()])
+                            .stdout(Stdio::null())
+                            .stderr(Stdio::null())
+                            .helper_status(Helper::ContainerCli);
+                    }
+                }
+            }
+
+            Ok(())
+        })
+        .spawn()
+    }
+
+    fn get_config(&self) -> &BackendConfig {
+        &self.config
+    }
+
+    fn backend_type(&self) -> &'static str {
+        "Docker"
+    }
+
+    fn supports_language(&self, language: &str) -> bool {
+        let (name, _) = language::split_version(language);
+        self.supported_languages().contains(&name)
+    }
+
+    fn supported_languages(&self) -> &[&'static str] {
+        &[
+            "python",
+            "python3",
+            "javascript",
+            "js",
+            "node",
+            "rust",
+            "bash",
+            "sh",
+            "go",
+            "r",
+            "R",
+            "rscript",
+            "sql",
+            "sqlite",
+            "sqlite3",
+            "duckdb",
+        ]
+    }
+}
+
+/// Content digest of a local image
+async fn image_digest(runtime: &str, image: &str) -> Result<String, String> {
+    match image::resolve_image_digest(runtime.to_string(), image.to_string()).await {
+        Ok(digest) => digest.map_err(|e| e.to_string()),
+        Err(e) => Err(format!("Image inspect task failed: {e}")),
+    }
+}
+
+/// Whether `runtime` names podman, whose CLI differs from docker's in a
+/// few flags
+fn is_podman(runtime: &str) -> bool {
+    Path::new(runtime)
+        .file_stem()
+        .is_some_and(|stem| stem.to_string_lossy().starts_with("podman"))
+}
@@ -0,0 +1,90 @@
+// ============================================================================
+// File: packages/cylo/src/backends/docker/resource_stats.rs
+// ----------------------------------------------------------------------------
+// Resource usage statistics parsing for the Docker/Podman backend.
+// ============================================================================
+
+use std::process::{Command, Stdio};
+
+use crate::backends::ResourceUsage;
+use crate::backends::provisioning::parse_bytes;
+use crate::hardening::{Helper, HelperCommand};
+
+/// Parse resource usage from container stats
+///
+/// # Arguments
+/// * `runtime` - Container CLI to drive
+/// * `container_name` - Name of the container
+///
+/// # Returns
+/// Resource usage statistics or None if unavailable
+pub(super) async fn parse_resource_usage(
+    runtime: &str,
+    container_name: &str,
+) -> Option<ResourceUsage> {
+    sample_resource_usage(runtime, container_name)
+}
+
+/// Synchronously sample container stats
+///
+/// Used where awaiting is not possible, e.g. to snapshot usage right before
+/// a timed-out container is stopped and removed. The CLI reports current
+/// usage as human-readable sizes and no cumulative CPU time.
+pub(super) fn sample_resource_usage(runtime: &str, container_name: &str) -> Option<ResourceUsage> {
+    let output = Command::new(runtime)
+        .args(["stats", "--no-stream", "--format", "{{json .}}", container_name])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .helper_output(Helper::ContainerCli)
+        .ok()
+        .filter(|output| output.status.success())?;
+    let stats = serde_json::from_slice::<serde_json::Value>(&output.stdout).ok()?;
+    Some(usage_from_stats(&stats))
+}
+
+/// Usage from one line of `stats --format '{{json .}}'`
+///
+/// Pairs such as `"BlockIO": "1.2MB / 0B"` hold reads (or received
+/// bytes) first.
+fn usage_from_stats(stats: &serde_json::Value) -> ResourceUsage {
+    let pair = |key: &str| {
+        let (first, second) = stats[key].as_str()?.split_once('/')?;
+        Some((parse_bytes(first).unwrap_or(0), parse_bytes(second).unwrap_or(0)))
+    };
+    let (memory, _) = pair("MemUsage").unwrap_or_default();
+    let (block_read, block_written) = pair("BlockIO").unwrap_or_default();
+    let (net_received, net_sent) = pair("NetIO").unwrap_or_default();
+    let pids = stats["PIDs"].as_str().and_then(|pids| pids.trim().parse().ok());
+
+    ResourceUsage {
+        peak_memory: memory,
+        cpu_time_ms: 0,
+        process_count: pids.unwrap_or(0),
+        disk_bytes_written: block_written,
+        disk_bytes_read: block_read,
+        network_bytes_sent: net_sent,
+        network_bytes_received: net_received,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_line_sizes_are_parsed() {
+        let stats = serde_json::json!({
+            "BlockIO": "4MB / 0B",
+            "CPUPerc": "0.52%",
+            "MemUsage": "12.5MiB / 7.6GiB",
+            "NetIO": "1.5kB / 0B",
+            "PIDs": "3"
+        });
+        let usage = usage_from_stats(&stats);
+        assert_eq!(usage.peak_memory, (12.5 * (1 << 20) as f64) as u64);
+        assert_eq!(usage.disk_bytes_read, 4_000_000);
+        assert_eq!(usage.network_bytes_received, 1500);
+        assert_eq!(usage.process_count, 3);
+        assert_eq!(usage_from_stats(&serde_json::json!({})).peak_memory, 0);
+    }
+}
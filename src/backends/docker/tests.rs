@@ -0,0 +1,34 @@
+// ============================================================================
+// File: packages/cylo/src/backends/docker/tests.rs
+// ----------------------------------------------------------------------------
+// Tests for the Docker/Podman backend.
+// ============================================================================
+
+use std::time::Duration;
+
+use crate::backends::{BackendConfig, ExecutionBackend};
+
+use super::{CONTAINER_RUNTIME_KEY, DockerBackend, is_podman};
+
+#[test]
+fn backend_creation_picks_the_configured_runtime() {
+    let config = BackendConfig::new("test_docker")
+        .with_timeout(Duration::from_secs(60))
+        .with_config(CONTAINER_RUNTIME_KEY, "podman");
+
+    // The configured CLI is used as given, installed or not
+    let backend = DockerBackend::new("python:3.12-alpine".to_string(), config.clone())
+        .expect("a configured runtime needs no detection");
+    assert_eq!(backend.runtime(), "podman");
+    assert_eq!(backend.backend_type(), "Docker");
+    assert!(backend.supports_language("python@3.12"));
+    assert!(backend.supports_language("go"));
+    assert!(!backend.supports_language("cobol"));
+
+    // Invalid image should fail
+    assert!(DockerBackend::new("invalid".to_string(), config).is_err());
+
+    assert!(is_podman("podman"));
+    assert!(is_podman("/usr/local/bin/podman-remote"));
+    assert!(!is_podman("docker"));
+}
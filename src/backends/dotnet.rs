@@ -0,0 +1,116 @@
+// ============================================================================
+// File: packages/cylo/src/backends/dotnet.rs
+// ----------------------------------------------------------------------------
+// Generated project and isolated SDK state for C# executions.
+//
+// `dotnet run` builds a project rather than a lone source file, so every C#
+// execution gets a minimal console project written next to its code; with
+// top-level statements a plain script is a valid program. The SDK's
+// first-run state and NuGet package cache live inside the workspace, and
+// the banner and telemetry are off, so nothing outlives the execution.
+// ============================================================================
+
+use std::fs;
+use std::path::Path;
+
+use crate::backends::{BackendError, BackendResult, ExecutionRequest, language};
+
+/// Project file generated in the workspace of a C# execution
+pub const PROJECT_FILE: &str = "cylo.csproj";
+
+/// Target framework when the SDK version is unknown
+const DEFAULT_TARGET_FRAMEWORK: &str = "net8.0";
+
+/// Target framework moniker for an SDK version (`8.0.100` is `net8.0`)
+pub fn target_framework(sdk_version: Option<&str>) -> String {
+    sdk_version
+        .and_then(|version| version.split('.').next())
+        .and_then(|major| major.parse::<u32>().ok())
+        .filter(|major| *major >= 5)
+        .map(|major| format!("net{major}.0"))
+        .unwrap_or_else(|| DEFAULT_TARGET_FRAMEWORK.to_string())
+}
+
+/// Console project compiling every `.cs` file in its directory
+///
+/// Dot-directories such as the workspace cache are excluded by the SDK's
+/// default globs, so only the code and the request's own files are built.
+pub fn project(sdk_version: Option<&str>) -> String {
+    format!(
+        "<Project Sdk=\"Microsoft.NET.Sdk\">\n\
+         \x20 <PropertyGroup>\n\
+         \x20   <OutputType>Exe</OutputType>\n\
+         \x20   <TargetFramework>{}</TargetFramework>\n\
+         \x20   <ImplicitUsings>enable</ImplicitUsings>\n\
+         \x20   <AssemblyName>main</AssemblyName>\n\
+         \x20 </PropertyGroup>\n\
+         </Project>\n",
+        target_framework(sdk_version)
+    )
+}
+
+/// Write the generated project into a workspace
+///
+/// # Arguments
+/// * `dir` - Workspace directory holding the code
+/// * `sdk_version` - Version of the SDK that builds it, if known
+pub fn write_project(dir: &Path, sdk_version: Option<&str>) -> BackendResult<()> {
+    fs::write(dir.join(PROJECT_FILE), project(sdk_version)).map_err(|e| {
+        BackendError::FileSystemFailed {
+            details: format!("Failed to write C# project file: {}", e),
+        }
+    })
+}
+
+/// Arguments to `dotnet` building and running the generated project
+///
+/// # Arguments
+/// * `workspace` - Directory holding the project, as the process sees it
+pub fn run_args(workspace: &Path) -> Vec<String> {
+    vec![
+        "run".to_string(),
+        "--project".to_string(),
+        workspace.join(PROJECT_FILE).display().to_string(),
+    ]
+}
+
+/// Variables keeping a C# execution's SDK state in its workspace
+///
+/// Empty for any other language. Backends apply them after the environment
+/// profile and before the request's variables, which still win.
+///
+/// # Arguments
+/// * `request` - Execution request
+/// * `cache_root` - Writable directory inside the sandbox that is removed
+///   with the workspace
+pub fn dotnet_env(request: &ExecutionRequest, cache_root: &str) -> Vec<(&'static str, String)> {
+    if language::resolve(&request.language).map(|spec| spec.name) != Some("csharp") {
+        return Vec::new();
+    }
+    vec![
+        ("DOTNET_CLI_HOME", format!("{cache_root}/dotnet")),
+        ("NUGET_PACKAGES", format!("{cache_root}/nuget")),
+        ("DOTNET_NOLOGO", "1".to_string()),
+        ("DOTNET_CLI_TELEMETRY_OPTOUT", "1".to_string()),
+        ("DOTNET_SKIP_FIRST_TIME_EXPERIENCE", "1".to_string()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_targets_the_installed_sdk() {
+        assert_eq!(target_framework(Some("8.0.100")), "net8.0");
+        assert_eq!(target_framework(Some("9.0.101")), "net9.0");
+        assert_eq!(target_framework(Some("3.1.426")), DEFAULT_TARGET_FRAMEWORK);
+        assert_eq!(target_framework(None), DEFAULT_TARGET_FRAMEWORK);
+        assert!(project(Some("9.0.101")).contains("<TargetFramework>net9.0</TargetFramework>"));
+
+        let request = ExecutionRequest::new("Console.WriteLine(1);", "c#");
+        let env = dotnet_env(&request, "/workspace/.cache");
+        assert!(env.contains(&("NUGET_PACKAGES", "/workspace/.cache/nuget".to_string())));
+        assert!(dotnet_env(&ExecutionRequest::new("1", "zig"), "/tmp").is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+// ============================================================================
+// File: packages/cylo/src/backends/environment.rs
+// ----------------------------------------------------------------------------
+// Normalized process environment for executed code.
+//
+// Timezone, locale, umask and home directory otherwise leak in from the
+// host or the image, so the same code formats dates, sorts strings or
+// creates files differently depending on where it was routed. Every
+// backend applies one EnvironmentProfile instead; request env_vars are
+// applied afterwards and win.
+// ============================================================================
+
+use serde::{Deserialize, Serialize};
+
+/// Environment every backend gives executed code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentProfile {
+    /// Value of `TZ`, e.g. "UTC" or "Europe/Berlin"
+    pub timezone: String,
+    /// Value of `LANG` and `LC_ALL`, e.g. "C.UTF-8"
+    pub locale: String,
+    /// File mode creation mask (ignored on Windows, which has none)
+    pub umask: u32,
+}
+
+impl Default for EnvironmentProfile {
+    fn default() -> Self {
+        Self {
+            timezone: "UTC".to_string(),
+            locale: "C.UTF-8".to_string(),
+            umask: 0o022,
+        }
+    }
+}
+
+impl EnvironmentProfile {
+    /// Set the timezone
+    pub fn with_timezone<T: Into<String>>(mut self, timezone: T) -> Self {
+        self.timezone = timezone.into();
+        self
+    }
+
+    /// Set the locale
+    pub fn with_locale<L: Into<String>>(mut self, locale: L) -> Self {
+        self.locale = locale.into();
+        self
+    }
+
+    /// Set the umask
+    pub fn with_umask(mut self, umask: u32) -> Self {
+        self.umask = umask;
+        self
+    }
+
+    /// Variables the profile sets
+    ///
+    /// # Arguments
+    /// * `home` - Workspace path as seen by the executed code; `HOME` points
+    ///   there so nothing reads or writes the host user's dotfiles
+    pub fn variables(&self, home: &str) -> Vec<(&'static str, String)> {
+        vec![
+            ("TZ", self.timezone.clone()),
+            ("LANG", self.locale.clone()),
+            ("LC_ALL", self.locale.clone()),
+            ("HOME", home.to_string()),
+        ]
+    }
+
+    /// Shell lines applying the profile, for backends that start the code
+    /// through a shell
+    pub fn shell_setup(&self, home: &str) -> String {
+        let exports = self
+            .variables(home)
+            .into_iter()
+            .map(|(key, value)| format!("{key}='{}'", value.replace('\'', r"'\''")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("umask {:03o}\nexport {exports}\n", self.umask)
+    }
+
+    /// Check the profile for values no backend can apply
+    ///
+    /// # Returns
+    /// Ok(()) if valid, otherwise a description of the first problem
+    pub fn validate(&self) -> Result<(), String> {
+        if self.umask > 0o777 {
+            return Err(format!("umask {:o} is larger than 777", self.umask));
+        }
+        for (name, value) in [("timezone", &self.timezone), ("locale", &self.locale)] {
+            let valid = !value.is_empty()
+                && value.chars().all(|c| {
+                    c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '+' | '/' | ':' | '@')
+                });
+            if !valid {
+                return Err(format!("invalid {name} '{}'", value.escape_debug()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_profile_is_utc_c_utf8() {
+        let profile = EnvironmentProfile::default();
+        assert!(profile.validate().is_ok());
+        assert_eq!(
+            profile.variables("/workspace"),
+            [
+                ("TZ", "UTC".to_string()),
+                ("LANG", "C.UTF-8".to_string()),
+                ("LC_ALL", "C.UTF-8".to_string()),
+                ("HOME", "/workspace".to_string()),
+            ]
+        );
+        assert_eq!(
+            profile.shell_setup("/tmp/home"),
+            "umask 022\nexport TZ='UTC' LANG='C.UTF-8' LC_ALL='C.UTF-8' HOME='/tmp/home'\n"
+        );
+    }
+
+    #[test]
+    fn invalid_profiles_are_rejected() {
+        assert!(EnvironmentProfile::default().with_umask(0o1000).validate().is_err());
+        assert!(EnvironmentProfile::default().with_timezone("").validate().is_err());
+        assert!(EnvironmentProfile::default().with_locale("C; rm -rf /").validate().is_err());
+        let berlin = EnvironmentProfile::default().with_timezone("Europe/Berlin");
+        assert!(berlin.validate().is_ok());
+    }
+}
@@ -4,6 +4,10 @@
 // Backend-specific error types
 // ============================================================================
 
+use std::time::Duration;
+
+use crate::backends::{Arch, ProvisioningStage};
+use crate::error::ErrorCategory;
 use crate::execution_env::CyloError;
 
 /// Backend-specific error types
@@ -32,6 +36,26 @@ pub enum BackendError {
         language: String,
     },
 
+    /// Pinned runtime version is not installed for this backend
+    #[error(
+        "{language} {requested} is not available on {backend}; installed versions: {}",
+        list_installed(.installed)
+    )]
+    RuntimeVersionUnavailable {
+        backend: &'static str,
+        language: String,
+        requested: String,
+        installed: Vec<String>,
+    },
+
+    /// Requested architecture cannot run on this backend and host
+    #[error("{backend} cannot run {arch} code on this host: {reason}")]
+    UnsupportedArchitecture {
+        backend: &'static str,
+        arch: Arch,
+        reason: String,
+    },
+
     /// Resource limit exceeded during execution
     #[error("Resource limit exceeded: {resource} exceeded {limit}")]
     ResourceLimitExceeded { resource: String, limit: String },
@@ -40,6 +64,14 @@ pub enum BackendError {
     #[error("Execution timed out after {seconds} seconds")]
     ExecutionTimeout { seconds: u64 },
 
+    /// A provisioning step ran past its budget
+    #[error("{backend} gave up {stage} after {limit:?}")]
+    ProvisioningTimeout {
+        backend: &'static str,
+        stage: ProvisioningStage,
+        limit: Duration,
+    },
+
     /// Process execution failed
     #[error("Process execution failed: {details}")]
     ProcessFailed { details: String },
@@ -48,6 +80,23 @@ pub enum BackendError {
     #[error("Container creation failed: {details}")]
     ContainerFailed { details: String },
 
+    /// MicroVM did not boot; carries the tail of what it printed
+    #[error(
+        "{backend} VM failed to boot: {cause}. {remediation}{}",
+        boot_output(.console, .vmm_output)
+    )]
+    BootFailed {
+        backend: &'static str,
+        /// Classified reason the boot failed
+        cause: String,
+        /// What the operator can do about it
+        remediation: String,
+        /// Last lines of the guest's serial console
+        console: String,
+        /// Last lines of the VMM's stderr and log
+        vmm_output: String,
+    },
+
     /// Network operation failed
     #[error("Network operation failed: {details}")]
     NetworkFailed { details: String },
@@ -56,11 +105,49 @@ pub enum BackendError {
     #[error("File system operation failed: {details}")]
     FileSystemFailed { details: String },
 
+    /// Downloaded artifact does not match its pinned checksum
+    #[error("Checksum mismatch for {artifact}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        artifact: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// Request path resolves outside the sandbox workspace
+    #[error("Path '{path}' escapes the sandbox workspace")]
+    PathEscape { path: String },
+
     /// Internal backend error
     #[error("Internal backend error: {message}")]
     Internal { message: String },
 }
 
+impl BackendError {
+    /// What kind of failure this is
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            BackendError::NotAvailable { .. } | BackendError::RuntimeVersionUnavailable { .. } => {
+                ErrorCategory::Unavailable
+            }
+            BackendError::InvalidConfig { .. } => ErrorCategory::Configuration,
+            BackendError::UnsupportedLanguage { .. }
+            | BackendError::UnsupportedArchitecture { .. } => ErrorCategory::Unsupported,
+            BackendError::ResourceLimitExceeded { .. } => ErrorCategory::ResourceExhausted,
+            BackendError::ExecutionTimeout { .. } | BackendError::ProvisioningTimeout { .. } => {
+                ErrorCategory::Timeout
+            }
+            BackendError::ProcessFailed { .. }
+            | BackendError::ContainerFailed { .. }
+            | BackendError::BootFailed { .. } => ErrorCategory::ExecutionFailed,
+            BackendError::NetworkFailed { .. }
+            | BackendError::FileSystemFailed { .. }
+            | BackendError::ChecksumMismatch { .. } => ErrorCategory::Io,
+            BackendError::PathEscape { .. } => ErrorCategory::InvalidInput,
+            BackendError::Internal { .. } => ErrorCategory::Internal,
+        }
+    }
+}
+
 impl From<BackendError> for CyloError {
     fn from(err: BackendError) -> Self {
         match err {
@@ -78,6 +165,15 @@ impl From<BackendError> for CyloError {
                 backend: "unknown",
                 timeout_secs: seconds,
             },
+            BackendError::ProvisioningTimeout {
+                backend,
+                stage,
+                limit,
+            } => CyloError::ProvisioningTimeout {
+                backend,
+                stage: stage.to_string(),
+                timeout_secs: limit.as_secs(),
+            },
             BackendError::ResourceLimitExceeded { resource, limit } => {
                 CyloError::ResourceLimitExceeded {
                     backend: "unknown",
@@ -85,10 +181,38 @@ impl From<BackendError> for CyloError {
                     limit,
                 }
             }
+            BackendError::RuntimeVersionUnavailable { .. }
+            | BackendError::UnsupportedArchitecture { .. } => {
+                CyloError::requirements_unsatisfiable(vec![err.to_string()])
+            }
+            BackendError::PathEscape { .. } => {
+                CyloError::invalid_request("working_dir", err.to_string())
+            }
+            BackendError::BootFailed { backend, .. } => {
+                CyloError::execution_failed(backend, err.to_string())
+            }
             _ => CyloError::internal(err.to_string()),
         }
     }
 }
 
+fn boot_output(console: &str, vmm_output: &str) -> String {
+    let mut output = String::new();
+    for (source, text) in [("serial console", console), ("VMM", vmm_output)] {
+        if !text.trim().is_empty() {
+            output.push_str(&format!("\n--- {source} ---\n{}", text.trim_end()));
+        }
+    }
+    output
+}
+
+fn list_installed(installed: &[String]) -> String {
+    if installed.is_empty() {
+        "none".to_string()
+    } else {
+        installed.join(", ")
+    }
+}
+
 /// Result type for backend operations
 pub type BackendResult<T> = Result<T, BackendError>;
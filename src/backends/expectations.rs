@@ -0,0 +1,224 @@
+// ============================================================================
+// File: packages/cylo/src/backends/expectations.rs
+// ----------------------------------------------------------------------------
+// Exit-policy assertions evaluated against execution results.
+//
+// Callers attach Expectations to an ExecutionRequest; after execution the
+// executor evaluates them and stores a structured ExpectationVerdict on the
+// ExecutionResult so graders don't have to re-implement output checks.
+// ============================================================================
+
+use std::time::Duration;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::backends::types::ExecutionResult;
+
+/// Assertions that an execution result must satisfy
+///
+/// Every field is optional; only the configured checks are evaluated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Expectations {
+    /// Expected process exit code
+    pub exit_code: Option<i32>,
+
+    /// Regular expression that stdout must match
+    pub stdout_matches: Option<String>,
+
+    /// Regular expression that stderr must match
+    pub stderr_matches: Option<String>,
+
+    /// Maximum allowed wall-clock duration
+    pub max_duration: Option<Duration>,
+}
+
+impl Expectations {
+    /// Create an empty set of expectations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a specific exit code
+    pub fn with_exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = Some(exit_code);
+        self
+    }
+
+    /// Require stdout to match a regular expression
+    pub fn with_stdout_matching<P: Into<String>>(mut self, pattern: P) -> Self {
+        self.stdout_matches = Some(pattern.into());
+        self
+    }
+
+    /// Require stderr to match a regular expression
+    pub fn with_stderr_matching<P: Into<String>>(mut self, pattern: P) -> Self {
+        self.stderr_matches = Some(pattern.into());
+        self
+    }
+
+    /// Require execution to finish within the given duration
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Check whether no assertions are configured
+    pub fn is_empty(&self) -> bool {
+        self.exit_code.is_none()
+            && self.stdout_matches.is_none()
+            && self.stderr_matches.is_none()
+            && self.max_duration.is_none()
+    }
+
+    /// Evaluate all configured assertions against a result
+    ///
+    /// # Arguments
+    /// * `result` - Execution result to check
+    ///
+    /// # Returns
+    /// Verdict with one entry per configured assertion
+    pub fn evaluate(&self, result: &ExecutionResult) -> ExpectationVerdict {
+        let mut checks = Vec::new();
+
+        if let Some(expected) = self.exit_code {
+            checks.push(ExpectationCheck {
+                name: "exit_code".to_string(),
+                passed: result.exit_code == expected,
+                expected: expected.to_string(),
+                actual: result.exit_code.to_string(),
+            });
+        }
+
+        if let Some(pattern) = &self.stdout_matches {
+            checks.push(match_check("stdout", pattern, &result.stdout));
+        }
+
+        if let Some(pattern) = &self.stderr_matches {
+            checks.push(match_check("stderr", pattern, &result.stderr));
+        }
+
+        if let Some(max_duration) = self.max_duration {
+            checks.push(ExpectationCheck {
+                name: "max_duration".to_string(),
+                passed: result.duration <= max_duration,
+                expected: format!("<= {}ms", max_duration.as_millis()),
+                actual: format!("{}ms", result.duration.as_millis()),
+            });
+        }
+
+        ExpectationVerdict {
+            passed: checks.iter().all(|check| check.passed),
+            checks,
+        }
+    }
+}
+
+/// Evaluate a regex assertion against captured output
+fn match_check(stream: &str, pattern: &str, output: &str) -> ExpectationCheck {
+    let (passed, actual) = match Regex::new(pattern) {
+        Ok(regex) => {
+            let passed = regex.is_match(output);
+            let actual = if passed {
+                "matched".to_string()
+            } else {
+                format!("no match in {} bytes of output", output.len())
+            };
+            (passed, actual)
+        }
+        Err(e) => (false, format!("invalid pattern: {e}")),
+    };
+
+    ExpectationCheck {
+        name: format!("{stream}_matches"),
+        passed,
+        expected: pattern.to_string(),
+        actual,
+    }
+}
+
+/// Outcome of evaluating expectations against a result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectationVerdict {
+    /// Whether every assertion passed
+    pub passed: bool,
+
+    /// Individual assertion outcomes
+    pub checks: Vec<ExpectationCheck>,
+}
+
+impl ExpectationVerdict {
+    /// Get the assertions that failed
+    pub fn failures(&self) -> impl Iterator<Item = &ExpectationCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+}
+
+/// Outcome of a single assertion
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectationCheck {
+    /// Assertion name (exit_code, stdout_matches, stderr_matches, max_duration)
+    pub name: String,
+
+    /// Whether the assertion passed
+    pub passed: bool,
+
+    /// Expected value or pattern
+    pub expected: String,
+
+    /// Observed value
+    pub actual: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_expectations_pass() {
+        let verdict = Expectations::new().evaluate(&ExecutionResult::failure(3, "boom"));
+        assert!(verdict.passed);
+        assert!(verdict.checks.is_empty());
+    }
+
+    #[test]
+    fn all_assertions_pass() {
+        let mut result = ExecutionResult::success("answer = 42\n");
+        result.duration = Duration::from_millis(150);
+
+        let verdict = Expectations::new()
+            .with_exit_code(0)
+            .with_stdout_matching(r"answer = \d+")
+            .with_max_duration(Duration::from_secs(1))
+            .evaluate(&result);
+
+        assert!(verdict.passed);
+        assert_eq!(verdict.checks.len(), 3);
+    }
+
+    #[test]
+    fn failing_assertions_are_reported() {
+        let mut result = ExecutionResult::failure(1, "Traceback");
+        result.duration = Duration::from_secs(5);
+
+        let verdict = Expectations::new()
+            .with_exit_code(0)
+            .with_stderr_matching("Traceback")
+            .with_max_duration(Duration::from_secs(1))
+            .evaluate(&result);
+
+        assert!(!verdict.passed);
+        let failed: Vec<&str> = verdict.failures().map(|c| c.name.as_str()).collect();
+        assert_eq!(failed, vec!["exit_code", "max_duration"]);
+    }
+
+    #[test]
+    fn invalid_pattern_fails_check() {
+        let verdict = Expectations::new()
+            .with_stdout_matching("(unclosed")
+            .evaluate(&ExecutionResult::success("anything"));
+
+        assert!(!verdict.passed);
+        assert!(verdict.checks[0].actual.starts_with("invalid pattern"));
+    }
+}
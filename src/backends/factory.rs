@@ -5,6 +5,7 @@
 // ============================================================================
 
 use crate::backends::config::BackendConfig;
+use crate::backends::registry;
 use crate::backends::trait_def::ExecutionBackend;
 use crate::execution_env::{CyloError, CyloResult};
 
@@ -14,7 +15,7 @@ use crate::backends::AppleBackend;
 use crate::backends::{FireCrackerBackend, LandLockBackend};
 #[cfg(target_os = "windows")]
 use crate::backends::WindowsJobBackend;
-use crate::backends::SweetMcpPluginBackend;
+use crate::backends::{DockerBackend, K8sJobBackend, SweetMcpPluginBackend, WasmBackend};
 
 /// Create a backend instance from configuration
 ///
@@ -81,17 +82,39 @@ pub fn create_backend(
             "WindowsJob is only available on Windows",
         )),
 
+        crate::execution_env::Cylo::Docker(image) => {
+            let backend = DockerBackend::new(image.clone(), config)?;
+            Ok(Box::new(backend))
+        }
+
+        crate::execution_env::Cylo::Wasm(wasmtime) => {
+            let backend = WasmBackend::new(wasmtime.clone(), config)?;
+            Ok(Box::new(backend))
+        }
+
+        crate::execution_env::Cylo::K8sJob(image) => {
+            let backend = K8sJobBackend::new(image.clone(), config)?;
+            Ok(Box::new(backend))
+        }
+
         crate::execution_env::Cylo::SweetMcpPlugin(plugin_path) => {
             let backend = SweetMcpPluginBackend::new(plugin_path.clone().into(), config)?;
             Ok(Box::new(backend))
         }
+
+        crate::execution_env::Cylo::Custom { backend, config: env_config } => {
+            let (_, factory) = registry::factory(backend)
+                .ok_or_else(|| CyloError::unsupported_backend(backend.as_str()))?;
+            factory.create(env_config, config)
+        }
     }
 }
 
 /// Get all available backends for the current platform
 ///
 /// # Returns
-/// List of backend types available on this platform
+/// List of backend types available on this platform, registered
+/// third-party backends included
 pub fn available_backends() -> Vec<&'static str> {
     let mut backends = vec!["SweetMcpPlugin"];
 
@@ -107,6 +130,19 @@ pub fn available_backends() -> Vec<&'static str> {
     #[cfg(target_os = "windows")]
     backends.push("WindowsJob");
 
+    if crate::backends::docker::installed_runtime().is_some() {
+        backends.push("Docker");
+    }
+
+    if crate::backends::wasm::installed_runtime().is_some() {
+        backends.push("Wasm");
+    }
+
+    if crate::backends::kubernetes::installed_runtime().is_some() {
+        backends.push("K8sJob");
+    }
+
+    backends.extend(registry::registered_backends());
     backends
 }
 
@@ -4,30 +4,45 @@
 // FireCracker backend implementation of ExecutionBackend trait.
 // ============================================================================
 
-use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::time::Duration;
 
 use crate::async_task::AsyncTaskBuilder;
 use crate::backends::{
-    AsyncTask, BackendConfig, BackendError, BackendResult, ExecutionBackend, ExecutionRequest,
-    ExecutionResult, HealthStatus,
+    arch, blocking, desktop, input_stream, language, python_env, retention,
 };
+use crate::backends::{
+    AsyncTask, BackendCapabilities, BackendConfig, BackendError, BackendResult, ExecutionBackend,
+    ExecutionRequest, ExecutionResult, HealthCheckLevel, HealthStatus, ImageReference, ImageStore,
+    InstanceMetrics, IsolationLevel, ProvisioningStage, SANDBOX_STATE_METADATA,
+};
+use crate::backends::landlock::monitoring;
+use crate::backends::live::LiveSet;
+use crate::backends::provisioning::StageBudget;
+use crate::logging::targets;
+use crate::reaper::global_reaper;
 
 use super::config::FireCrackerConfig;
+use super::digest;
+use super::shared_paths;
 use super::vm_instance::VMInstance;
 
 /// FireCracker backend for secure code execution
 #[derive(Debug, Clone)]
 pub struct FireCrackerBackend {
-    /// Container image specification (e.g., "rust:alpine3.20")
-    _image: String,
+    /// Image specification (e.g., "rust:alpine3.20"); a pinned digest
+    /// must match the rootfs the VM boots from
+    image: ImageReference,
 
     /// Backend configuration
     config: BackendConfig,
 
     /// FireCracker runtime configuration
     firecracker_config: FireCrackerConfig,
+
+    /// Process ids of the VMs currently running executions
+    live_vms: LiveSet<u32>,
 }
 
 impl FireCrackerBackend {
@@ -44,19 +59,23 @@ impl FireCrackerBackend {
             return Err(BackendError::InvalidConfig {
                 backend: "FireCracker",
                 details: format!(
-                    "Invalid image format: {}. Expected format: 'name:tag'",
+                    "Invalid image format: {}. Expected format: 'name:tag' or \
+                     'name@sha256:<digest>'",
                     image
                 ),
             });
         }
+        let image = ImageReference::parse(&image)
+            .map_err(|details| BackendError::InvalidConfig { backend: "FireCracker", details })?;
 
         let firecracker_config = FireCrackerConfig::from_backend_config(&config)?;
         firecracker_config.verify_installation()?;
 
         Ok(Self {
-            _image: image,
+            image,
             config,
             firecracker_config,
+            live_vms: LiveSet::default(),
         })
     }
 
@@ -73,34 +92,7 @@ impl FireCrackerBackend {
 
     /// Validate container image format
     fn is_valid_image_format(image: &str) -> bool {
-        if !image.contains(':') {
-            return false;
-        }
-
-        let parts: Vec<&str> = image.splitn(2, ':').collect();
-        if parts.len() != 2 {
-            return false;
-        }
-
-        let (name, tag) = (parts[0], parts[1]);
-
-        if name.is_empty()
-            || !name
-                .chars()
-                .all(|c| c.is_alphanumeric() || c == '/' || c == '-' || c == '_' || c == '.')
-        {
-            return false;
-        }
-
-        if tag.is_empty()
-            || !tag
-                .chars()
-                .all(|c| c.is_alphanumeric() || c == '.' || c == '-' || c == '_')
-        {
-            return false;
-        }
-
-        true
+        ImageReference::parse(image).is_ok()
     }
 
     /// Check if FireCracker binary is available
@@ -115,13 +107,114 @@ impl FireCrackerBackend {
     }
 }
 
+/// Locate the root filesystem a request boots from and its content digest
+///
+/// Unversioned requests boot the rootfs artifact of an image held in the
+/// offline store, identified by its manifest digest; otherwise the
+/// configured rootfs is hashed.
+fn prepare_rootfs(
+    fc_config: &FireCrackerConfig,
+    store: Option<&ImageStore>,
+    image: &ImageReference,
+    language: &str,
+) -> BackendResult<(PathBuf, String)> {
+    if let (Some(store), (_, None)) = (store, language::split_version(language))
+        && let Some(stored) = store.lookup(image)
+        && let Some(rootfs) = store.rootfs(&stored)
+    {
+        return Ok((rootfs, stored.digest));
+    }
+
+    let rootfs = fc_config.rootfs_for(language)?;
+    // The rootfs is the image content the VM runs; a pinned digest guards
+    // against it being swapped under a mutable tag
+    let digest = digest::rootfs_digest(&rootfs).map_err(|e| BackendError::ProcessFailed {
+        details: format!("Failed to hash rootfs {}: {}", rootfs.display(), e),
+    })?;
+    Ok((rootfs, digest))
+}
+
 impl ExecutionBackend for FireCrackerBackend {
     fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
-        let fc_config = self.firecracker_config.clone();
+        let mut fc_config = self.firecracker_config.clone();
         let backend_config = self.config.clone();
         let backend_name = self.backend_type();
+        let live_vms = self.live_vms.clone();
+        let image = self.image.clone();
+        let store = ImageStore::from_backend_config(&self.config);
 
         AsyncTaskBuilder::new(async move {
+            // Host venvs and desktop services do not reach the guest, whose
+            // kernel and rootfs are built for the host's CPU; its stdin is
+            // not connected to the host
+            if let Err(e) = python_env::reject_in_guest(&request, backend_name)
+                .and_then(|()| desktop::reject_in_guest(&request, backend_name))
+                .and_then(|()| input_stream::reject_unpiped(&request, backend_name))
+                .and_then(|()| arch::check(&request, backend_name))
+            {
+                return ExecutionResult::failure(-1, e.to_string());
+            }
+
+            // Hashing a multi-gigabyte rootfs on slow storage is bounded by
+            // the rootfs budget and left to finish in the background
+            request.report_progress(
+                ProvisioningStage::BootingVm,
+                None,
+                "Preparing root filesystem",
+            );
+            let budget = StageBudget::start(
+                backend_name,
+                ProvisioningStage::BootingVm,
+                backend_config.provisioning.rootfs_timeout,
+            );
+            let prepared = budget.run(async {
+                blocking::backend_io()
+                    .run({
+                        let (fc_config, store, image) = (fc_config.clone(), store, image.clone());
+                        let language = request.language.clone();
+                        move || prepare_rootfs(&fc_config, store.as_ref(), &image, &language)
+                    })
+                    .await
+                    .map_err(|e| BackendError::ProcessFailed {
+                        details: format!("Task join failed: {}", e),
+                    })?
+            });
+            let digest = match prepared.await {
+                Ok((rootfs, digest)) => {
+                    fc_config.rootfs_path = rootfs;
+                    digest
+                }
+                Err(e) => return ExecutionResult::failure(-1, e.to_string()),
+            };
+            if let Err(e) = image.verify(&digest) {
+                return ExecutionResult::failure(-1, e);
+            }
+
+            // A session's VMs boot from its own copy of the rootfs, which
+            // keeps what earlier executions wrote to it
+            let stateful = request.sandbox_state.is_some();
+            if let Some(state) = request.sandbox_state.clone() {
+                let (base, digest) = (fc_config.rootfs_path.clone(), digest.clone());
+                let copied = blocking::backend_io()
+                    .run(move || state.rootfs(&base, &digest))
+                    .await;
+                match copied {
+                    Ok(Ok(rootfs)) => fc_config.rootfs_path = rootfs,
+                    Ok(Err(e)) => {
+                        return ExecutionResult::failure(
+                            -1,
+                            format!("Failed to copy the rootfs for the session: {}", e),
+                        );
+                    }
+                    Err(e) => {
+                        return ExecutionResult::failure(-1, format!("Task join failed: {}", e));
+                    }
+                }
+            }
+
+            let booting = format!("Booting microVM for {}", request.language);
+            request.report_progress(ProvisioningStage::BootingVm, Some(0), booting.as_str());
+
             let vm = match VMInstance::create(&request, &backend_config) {
                 Ok(vm) => vm,
                 Err(e) => {
@@ -132,25 +225,42 @@ impl ExecutionBackend for FireCrackerBackend {
                 }
             };
 
+            // Drive images of exposed paths outlive a VM that never starts
+            let path_drives = vm.path_drives.clone();
             if let Err(e) = vm.generate_config(&fc_config, &request) {
+                shared_paths::remove_images(&path_drives);
                 return ExecutionResult::failure(
                     -1,
                     format!("Failed to generate VM config: {}", e),
                 );
             }
 
+            let security = fc_config.security_report();
+            request.report_progress(ProvisioningStage::BootingVm, Some(30), booting.as_str());
             let started_vm = match vm.start(fc_config).await {
                 Ok(Ok(vm)) => vm,
                 Ok(Err(e)) => {
+                    shared_paths::remove_images(&path_drives);
                     return ExecutionResult::failure(-1, format!("Failed to start VM: {}", e));
                 }
                 Err(e) => {
+                    shared_paths::remove_images(&path_drives);
                     return ExecutionResult::failure(-1, format!("VM start task panicked: {}", e));
                 }
             };
 
-            let result = match started_vm.clone().execute(request).await {
-                Ok(Ok(result)) => result,
+            request.report_progress(ProvisioningStage::BootingVm, Some(100), booting);
+            request.report_progress(ProvisioningStage::Ready, None, "microVM booted");
+
+            let live_guard = started_vm.pid.map(|pid| live_vms.track(pid));
+            let execution = started_vm.clone().execute(request).await;
+            let exited = matches!(&execution, Ok(Ok(result)) if !result.is_timed_out());
+            let mut result = match execution {
+                Ok(Ok(mut result)) => {
+                    result.security = Some(security);
+                    image.record(&mut result, digest);
+                    result
+                }
                 Ok(Err(e)) => ExecutionResult::failure(
                     -1,
                     format!("{} execution failed: {}", backend_name, e),
@@ -161,14 +271,37 @@ impl ExecutionBackend for FireCrackerBackend {
                 ),
             };
 
-            let _ = started_vm.cleanup().await;
+            let retained = retention::retain_on_failure(
+                backend_config.retain_workspace_on_failure,
+                started_vm.evidence_paths(),
+                backend_name,
+                &mut result,
+            );
+            // A VM that could not be shut down still holds its memory
+            let shut_down = matches!(started_vm.cleanup(retained).await, Ok(Ok(_)));
+            if !shut_down && let Some(guard) = live_guard {
+                guard.linger();
+            }
+
+            // The guest writes the session's rootfs in place, which only
+            // holds a consistent filesystem once the program exited and the
+            // VM shut down
+            if stateful {
+                let saved = match (exited, shut_down) {
+                    (true, true) => "saved".to_string(),
+                    (false, _) => "not saved: the execution did not exit on its own".to_string(),
+                    (true, false) => "not saved: the VM did not shut down cleanly".to_string(),
+                };
+                result.metadata.insert(SANDBOX_STATE_METADATA.to_string(), saved);
+            }
 
             result
         }).spawn()
     }
 
-    fn health_check(&self) -> AsyncTask<HealthStatus> {
+    fn health_check(&self, level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
         let fc_config = self.firecracker_config.clone();
+        let backend = self.clone();
 
         AsyncTaskBuilder::new(async move {
             if !Self::is_platform_supported() {
@@ -181,48 +314,55 @@ impl ExecutionBackend for FireCrackerBackend {
                     .with_metric("installation_valid", "false");
             }
 
+            let status = |message: &str| {
+                HealthStatus::healthy(message)
+                    .with_metric("platform_supported", "true")
+                    .with_metric("installation_valid", "true")
+                    .with_metric("memory_size_mb", fc_config.memory_size_mb.to_string())
+                    .with_metric("vcpu_count", fc_config.vcpu_count.to_string())
+            };
+            if level == HealthCheckLevel::Liveness {
+                return status("FireCracker installation present");
+            }
+
             if !Self::is_firecracker_available() {
                 return HealthStatus::unhealthy("FireCracker binary not available")
                     .with_metric("firecracker_available", "false");
             }
 
-            HealthStatus::healthy("FireCracker backend operational")
-                .with_metric("platform_supported", "true")
-                .with_metric("installation_valid", "true")
-                .with_metric("firecracker_available", "true")
-                .with_metric("memory_size_mb", &fc_config.memory_size_mb.to_string())
-                .with_metric("vcpu_count", &fc_config.vcpu_count.to_string())
+            // Boot a VM and run a trivial program in it
+            let test_request = ExecutionRequest::new("echo 'health check'", "bash")
+                .with_timeout(Duration::from_secs(30));
+            match backend.execute_code(test_request).await {
+                Ok(result) if result.is_success() => status("FireCracker backend operational")
+                    .with_metric("firecracker_available", "true")
+                    .with_metric("test_execution", "success"),
+                Ok(result) => {
+                    HealthStatus::unhealthy(format!("Test execution failed: {}", result.stderr))
+                        .with_metric("test_execution", "failed")
+                        .with_metric("exit_code", result.exit_code.to_string())
+                }
+                Err(e) => HealthStatus::unhealthy(format!("Health check task error: {}", e))
+                    .with_metric("test_execution", "task_error"),
+            }
         }).spawn()
     }
 
     fn cleanup(&self) -> AsyncTask<crate::execution_env::CyloResult<()>> {
         AsyncTaskBuilder::new(async move {
-            let output = Command::new("ps").args(&["aux"]).output();
-
-            if let Ok(output) = output {
-                let processes = String::from_utf8_lossy(&output.stdout);
-                for line in processes.lines() {
-                    if line.contains("firecracker") && line.contains("cylo-") {
-                        let fields: Vec<&str> = line.split_whitespace().collect();
-                        if fields.len() > 1 {
-                            if let Ok(pid) = fields[1].parse::<u32>() {
-                                let _ = Command::new("kill")
-                                    .args(&["-TERM", &pid.to_string()])
-                                    .status();
-                            }
-                        }
-                    }
-                }
-            }
-
-            if let Ok(entries) = fs::read_dir(std::env::temp_dir()) {
-                for entry in entries.filter_map(Result::ok) {
-                    if let Ok(file_name) = entry.file_name().into_string() {
-                        if file_name.starts_with("cylo-") {
-                            let _ = fs::remove_file(entry.path());
-                        }
-                    }
-                }
+            // Kill every VM this process booted and remove its socket,
+            // config, and log files; the reaper knows exactly which PIDs
+            // belong to us, so no process-table string matching is needed
+            let reaped = blocking::backend_io()
+                .run(|| global_reaper().reap_backend("FireCracker"))
+                .await?;
+
+            if reaped > 0 {
+                tracing::info!(
+                    target: targets::BACKEND_FIRECRACKER,
+                    "FireCracker cleanup terminated {} VM(s)",
+                    reaped
+                );
             }
 
             Ok(())
@@ -238,7 +378,8 @@ impl ExecutionBackend for FireCrackerBackend {
     }
 
     fn supports_language(&self, language: &str) -> bool {
-        self.supported_languages().contains(&language)
+        let (name, _) = language::split_version(language);
+        self.supported_languages().contains(&name)
     }
 
     fn supported_languages(&self) -> &[&'static str] {
@@ -252,8 +393,40 @@ impl ExecutionBackend for FireCrackerBackend {
             "bash",
             "sh",
             "go",
+            "r",
+            "R",
+            "rscript",
+            "sql",
+            "sqlite",
+            "sqlite3",
+            "duckdb",
         ]
     }
+
+    fn instance_metrics(&self) -> AsyncTask<Option<InstanceMetrics>> {
+        let live_vms = self.live_vms.clone();
+
+        AsyncTaskBuilder::new(async move {
+            let samples = live_vms.sample(|&pid| {
+                let memory = monitoring::get_memory_usage(pid).ok()?;
+                let cpu = monitoring::get_process_cpu_time(pid).unwrap_or(0);
+                Some((memory, cpu))
+            });
+            Some(InstanceMetrics::from_samples(samples))
+        }).spawn()
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        let memory = u64::from(self.firecracker_config.memory_size_mb) * 1024 * 1024;
+        BackendCapabilities {
+            isolation: IsolationLevel::MicroVM,
+            max_memory_bytes: Some(memory),
+            network_control: true,
+            persistent_sessions: true,
+            artifact_collection: false,
+            streaming: false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -270,6 +443,9 @@ mod tests {
         assert!(!FireCrackerBackend::is_valid_image_format("python"));
         assert!(!FireCrackerBackend::is_valid_image_format(""));
         assert!(!FireCrackerBackend::is_valid_image_format(":tag"));
+
+        let pinned = format!("python@sha256:{}", "0f".repeat(32));
+        assert!(FireCrackerBackend::is_valid_image_format(&pinned));
     }
 
     #[test]
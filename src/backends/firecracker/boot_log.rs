@@ -0,0 +1,253 @@
+// ============================================================================
+// File: packages/cylo/src/backends/firecracker/boot_log.rs
+// ----------------------------------------------------------------------------
+// Boot failure diagnostics for FireCracker VMs.
+//
+// Firecracker's stdout carries the guest's serial console and its stderr
+// and log file carry the VMM's own errors; both are captured to files next
+// to the VM's config. When a VM does not reach Running, their tails are
+// read back and matched against the failures operators hit most often, so
+// the error names the cause and what to do about it instead of reporting
+// a bare timeout.
+// ============================================================================
+
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::Child;
+
+use super::config::FireCrackerConfig;
+use super::vm_instance::VMInstance;
+use crate::backends::BackendError;
+
+/// Lines of each output kept in the error
+const TAIL_LINES: usize = 20;
+
+/// Bytes read from the end of each output file
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// Recognized reasons a VM fails to boot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootFailure {
+    /// The configured kernel image does not exist
+    KernelMissing,
+    /// The kernel image exists but Firecracker cannot load it
+    KernelInvalid,
+    /// The configured root filesystem image does not exist
+    RootfsMissing,
+    /// The guest kernel could not mount the root filesystem
+    RootfsCorrupt,
+    /// /dev/kvm exists but this user may not open it
+    KvmPermissionDenied,
+    /// /dev/kvm does not exist
+    KvmUnavailable,
+    /// The guest kernel panicked for another reason
+    GuestPanic,
+    /// Firecracker exited before the VM was running
+    VmmExited,
+}
+
+impl BootFailure {
+    /// What the operator can do about the failure
+    pub fn remediation(self, config: &FireCrackerConfig) -> String {
+        match self {
+            Self::KernelMissing => format!(
+                "Point kernel_path at an existing uncompressed vmlinux (currently {})",
+                config.kernel_path.display()
+            ),
+            Self::KernelInvalid => format!(
+                "{} must be an uncompressed ELF vmlinux built for this architecture, not a \
+                 bzImage",
+                config.kernel_path.display()
+            ),
+            Self::RootfsMissing => format!(
+                "Point rootfs_path at an existing ext4 image (currently {})",
+                config.rootfs_path.display()
+            ),
+            Self::RootfsCorrupt => format!(
+                "Check {} with e2fsck -f or rebuild it; it must be ext4 and match the kernel's \
+                 root= device",
+                config.rootfs_path.display()
+            ),
+            Self::KvmPermissionDenied => {
+                "Add this user to the kvm group (usermod -aG kvm $USER) or grant rw on /dev/kvm"
+                    .to_string()
+            }
+            Self::KvmUnavailable => {
+                "Enable virtualization in firmware and load kvm_intel or kvm_amd; nested guests \
+                 need nested virtualization enabled on the host"
+                    .to_string()
+            }
+            Self::GuestPanic => {
+                "The serial console below shows the panic; check the kernel's config and boot \
+                 arguments"
+                    .to_string()
+            }
+            Self::VmmExited => {
+                "Firecracker's output below shows why it exited; check its version against the \
+                 VM configuration"
+                    .to_string()
+            }
+        }
+    }
+}
+
+impl fmt::Display for BootFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::KernelMissing => "kernel image not found",
+            Self::KernelInvalid => "kernel image could not be loaded",
+            Self::RootfsMissing => "root filesystem image not found",
+            Self::RootfsCorrupt => "root filesystem could not be mounted",
+            Self::KvmPermissionDenied => "permission denied opening /dev/kvm",
+            Self::KvmUnavailable => "KVM is not available",
+            Self::GuestPanic => "guest kernel panicked",
+            Self::VmmExited => "Firecracker exited",
+        })
+    }
+}
+
+/// Match captured output against known failures
+///
+/// Configured paths are checked first since they are certain; then the
+/// VMM's errors, then the guest console. `exited` tells whether
+/// Firecracker was gone by the time the boot failed.
+pub fn classify(
+    config: &FireCrackerConfig,
+    console: &str,
+    vmm_output: &str,
+    exited: bool,
+) -> Option<BootFailure> {
+    if !config.kernel_path.exists() {
+        return Some(BootFailure::KernelMissing);
+    }
+    if !config.rootfs_path.exists() {
+        return Some(BootFailure::RootfsMissing);
+    }
+
+    let vmm = vmm_output.to_ascii_lowercase();
+    let console = console.to_ascii_lowercase();
+    let has = |text: &str, needles: &[&str]| needles.iter().any(|needle| text.contains(needle));
+
+    if vmm.contains("kvm") {
+        if has(&vmm, &["permission denied", "os error 13"]) {
+            return Some(BootFailure::KvmPermissionDenied);
+        }
+        if has(&vmm, &["no such file", "os error 2", "not supported"]) {
+            return Some(BootFailure::KvmUnavailable);
+        }
+    }
+    if has(&vmm, &["invalidelfmagicnumber", "kernel loader", "cannot load kernel", "invalid elf"]) {
+        return Some(BootFailure::KernelInvalid);
+    }
+    if has(&console, &["vfs: unable to mount root", "vfs: cannot open root", "ext4-fs error"]) {
+        return Some(BootFailure::RootfsCorrupt);
+    }
+    if console.contains("kernel panic") {
+        return Some(BootFailure::GuestPanic);
+    }
+    exited.then_some(BootFailure::VmmExited)
+}
+
+/// Turn a failed boot into an error carrying its diagnosis and output
+///
+/// # Arguments
+/// * `vm` - VM that failed to boot
+/// * `config` - Configuration it was booted with
+/// * `child` - Firecracker process, checked for having exited
+/// * `error` - Error the boot stopped with, kept as the cause when the
+///   output matches no known failure
+pub fn diagnose(
+    vm: &VMInstance,
+    config: &FireCrackerConfig,
+    child: &mut Child,
+    error: BackendError,
+) -> BackendError {
+    let exited = matches!(child.try_wait(), Ok(Some(_)));
+    let console = tail(&vm.console_path());
+    let vmm_output = [tail(&vm.stderr_path()), tail(&vm.log_path())]
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let (cause, remediation) = match classify(config, &console, &vmm_output, exited) {
+        Some(failure) => (failure.to_string(), failure.remediation(config)),
+        None => (
+            error.to_string(),
+            "See the serial console and VMM output for the last thing the VM did".to_string(),
+        ),
+    };
+    BackendError::BootFailed {
+        backend: "FireCracker",
+        cause,
+        remediation,
+        console,
+        vmm_output,
+    }
+}
+
+/// Last `TAIL_LINES` lines of a file, empty when it cannot be read
+fn tail(path: &Path) -> String {
+    let Ok(mut file) = File::open(path) else {
+        return String::new();
+    };
+    let len = file.metadata().map_or(0, |metadata| metadata.len());
+    if file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES))).is_err() {
+        return String::new();
+    }
+    let mut bytes = Vec::new();
+    if file.read_to_end(&mut bytes).is_err() {
+        return String::new();
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(TAIL_LINES)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_boot_failures_are_recognized() {
+        let missing = FireCrackerConfig {
+            kernel_path: "/nonexistent/vmlinux".into(),
+            ..FireCrackerConfig::default()
+        };
+        assert_eq!(classify(&missing, "", "", false), Some(BootFailure::KernelMissing));
+
+        // Paths that exist, so classification falls through to the output
+        let present = FireCrackerConfig {
+            kernel_path: std::env::temp_dir(),
+            rootfs_path: std::env::temp_dir(),
+            ..FireCrackerConfig::default()
+        };
+        let kvm = "Error creating KVM object: Error opening /dev/kvm: Permission denied (os \
+                   error 13)";
+        assert_eq!(classify(&present, "", kvm, true), Some(BootFailure::KvmPermissionDenied));
+        let kvm = "Error opening /dev/kvm: No such file or directory (os error 2)";
+        assert_eq!(classify(&present, "", kvm, true), Some(BootFailure::KvmUnavailable));
+        assert_eq!(
+            classify(&present, "", "Kernel loader: InvalidElfMagicNumber", true),
+            Some(BootFailure::KernelInvalid)
+        );
+        let panic = "VFS: Unable to mount root fs on unknown-block(254,0)\nKernel panic";
+        assert_eq!(classify(&present, panic, "", false), Some(BootFailure::RootfsCorrupt));
+        assert_eq!(classify(&present, "", "", true), Some(BootFailure::VmmExited));
+        assert_eq!(classify(&present, "", "", false), None);
+
+        let error = BackendError::BootFailed {
+            backend: "FireCracker",
+            cause: BootFailure::RootfsCorrupt.to_string(),
+            remediation: BootFailure::RootfsCorrupt.remediation(&present),
+            console: panic.to_string(),
+            vmm_output: String::new(),
+        };
+        let message = error.to_string();
+        assert!(message.contains("root filesystem could not be mounted"));
+        assert!(message.contains("--- serial console ---\nVFS: Unable to mount root fs"));
+        assert!(!message.contains("--- VMM ---"));
+    }
+}
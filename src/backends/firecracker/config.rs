@@ -7,7 +7,10 @@
 use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 
-use crate::backends::{BackendConfig, BackendError, BackendResult};
+use crate::backends::runtime::{compare_versions, version_matches};
+use crate::backends::{
+    BackendConfig, BackendError, BackendResult, IsolationLevel, SecurityReport, language,
+};
 
 /// FireCracker-specific configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +52,67 @@ impl Default for FireCrackerConfig {
 }
 
 impl FireCrackerConfig {
+    /// Isolation guaranteed to code run in a VM with this configuration
+    ///
+    /// The guest rootfs is attached writable and memory is capped by the
+    /// VM size; the network is off unless explicitly enabled. The guest
+    /// kernel shares no IPC objects or display with the host.
+    pub fn security_report(&self) -> SecurityReport {
+        SecurityReport {
+            isolation: IsolationLevel::MicroVM,
+            network_disabled: !self.network_enabled,
+            filesystem_read_only: false,
+            memory_limit_bytes: Some(u64::from(self.memory_size_mb) * 1024 * 1024),
+            ipc_isolated: true,
+            ui_restricted: true,
+        }
+    }
+
+    /// Root filesystem image for a requested language
+    ///
+    /// Unpinned requests use `rootfs_path`. A pinned version (`python@3.12`)
+    /// selects the newest matching `rootfs-<language>-<version>.ext4` from
+    /// the directory holding `rootfs_path`.
+    ///
+    /// # Returns
+    /// Image path, or RuntimeVersionUnavailable listing the versioned
+    /// images present for the language
+    pub fn rootfs_for(&self, language: &str) -> BackendResult<PathBuf> {
+        let (name, Some(requested)) = language::split_version(language) else {
+            return Ok(self.rootfs_path.clone());
+        };
+        let spec = language::resolve(name).ok_or_else(|| BackendError::UnsupportedLanguage {
+            backend: "FireCracker",
+            language: language.to_string(),
+        })?;
+
+        let dir = self.rootfs_path.parent().unwrap_or_else(|| Path::new("."));
+        let prefix = format!("rootfs-{}-", spec.name);
+        let mut installed: Vec<String> = std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| {
+                        let file_name = entry.file_name().into_string().ok()?;
+                        let version = file_name.strip_prefix(&prefix)?.strip_suffix(".ext4")?;
+                        Some(version.to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        installed.sort_by(|a, b| compare_versions(b, a));
+
+        match installed.iter().find(|version| version_matches(version, requested)) {
+            Some(version) => Ok(dir.join(format!("{prefix}{version}.ext4"))),
+            None => Err(BackendError::RuntimeVersionUnavailable {
+                backend: "FireCracker",
+                language: spec.name.to_string(),
+                requested: requested.to_string(),
+                installed,
+            }),
+        }
+    }
+
     /// Initialize FireCracker configuration from backend config
     pub fn from_backend_config(config: &BackendConfig) -> BackendResult<Self> {
         let mut fc_config = FireCrackerConfig::default();
@@ -119,3 +183,29 @@ impl FireCrackerConfig {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_version_selects_versioned_rootfs() {
+        let dir = tempfile::tempdir().unwrap();
+        for image in ["rootfs.ext4", "rootfs-python-3.11.9.ext4", "rootfs-python-3.12.4.ext4"] {
+            std::fs::write(dir.path().join(image), b"").unwrap();
+        }
+        let config = FireCrackerConfig {
+            rootfs_path: dir.path().join("rootfs.ext4"),
+            ..FireCrackerConfig::default()
+        };
+
+        assert_eq!(config.rootfs_for("python").unwrap(), config.rootfs_path);
+        assert_eq!(
+            config.rootfs_for("python@3.12").unwrap(),
+            dir.path().join("rootfs-python-3.12.4.ext4")
+        );
+
+        let err = config.rootfs_for("python@3.13").unwrap_err().to_string();
+        assert!(err.contains("installed versions: 3.12.4, 3.11.9"), "{err}");
+    }
+}
@@ -0,0 +1,77 @@
+// ============================================================================
+// File: packages/cylo/src/backends/firecracker/digest.rs
+// ----------------------------------------------------------------------------
+// Content digests of rootfs images, used to verify pinned image references.
+//
+// Hashing a multi-hundred-megabyte rootfs on every boot would dominate
+// execution time, so digests are cached per path and invalidated when the
+// file's size or modification time changes.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use crate::backends::image_store::sha256_file;
+
+/// File identity a cached digest is valid for
+type Stamp = (u64, Option<SystemTime>);
+
+fn cache() -> &'static Mutex<HashMap<PathBuf, (Stamp, String)>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, (Stamp, String)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Compute the `sha256:` digest of a rootfs image
+///
+/// # Arguments
+/// * `path` - Rootfs image file
+///
+/// # Returns
+/// Lowercase `sha256:<hex>` digest of the file's content
+pub(super) fn rootfs_digest(path: &Path) -> io::Result<String> {
+    let metadata = std::fs::metadata(path)?;
+    let stamp = (metadata.len(), metadata.modified().ok());
+
+    if let Some((cached, digest)) = cache()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(path)
+        && *cached == stamp
+    {
+        return Ok(digest.clone());
+    }
+
+    let digest = sha256_file(path)?;
+
+    cache()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(path.to_path_buf(), (stamp, digest.clone()));
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_tracks_file_content() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("rootfs.ext4");
+
+        std::fs::write(&path, b"").expect("write");
+        assert_eq!(
+            rootfs_digest(&path).expect("digest"),
+            "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        std::fs::write(&path, b"abc").expect("rewrite");
+        assert_eq!(
+            rootfs_digest(&path).expect("digest"),
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -6,7 +6,10 @@
 // This module decomposes the original monolithic firecracker.rs (1,648 lines)
 // into logical separation of concerns:
 // - api_client: HTTP API client for VM management (390 lines)
+// - boot_log: Classification of boot failures from captured VM output
 // - config: Configuration structures and validation (121 lines)
+// - digest: Cached rootfs content digests for pinned image references
+// - shared_paths: Read-only drives exposing request paths to the guest
 // - ssh: SSH configuration and session management (86 lines)
 // - vm_instance: VM struct and basic operations (170 lines)
 // - vm_lifecycle: VM startup and configuration (246 lines)
@@ -20,7 +23,10 @@
 #![cfg(target_os = "linux")]
 
 mod api_client;
+mod boot_log;
 mod config;
+mod digest;
+mod shared_paths;
 mod ssh;
 mod vm_instance;
 mod vm_lifecycle;
@@ -31,6 +37,7 @@ mod backend;
 pub use backend::FireCrackerBackend;
 
 // Re-export commonly used types
+pub use boot_log::BootFailure;
 pub use api_client::{FireCrackerApiClient, ResourceStats, SecurityPolicy, FilesystemRestrictions};
 pub use config::FireCrackerConfig;
 pub use vm_instance::VMInstance;
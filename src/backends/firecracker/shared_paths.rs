@@ -0,0 +1,194 @@
+// ============================================================================
+// File: packages/cylo/src/backends/firecracker/shared_paths.rs
+// ----------------------------------------------------------------------------
+// Exposure of request-supplied host paths to a microVM.
+//
+// A VM cannot bind host directories, so each readable path is copied into
+// an ext4 image that is attached as a read-only drive and mounted at the
+// same path inside the guest. Writes to a drive never reach the host, so
+// writable paths are rejected instead of silently discarding output.
+// ============================================================================
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::paths::ExposedPath;
+use crate::backends::{BackendError, BackendResult};
+use crate::hardening::{Helper, HelperCommand};
+
+/// Extra drives a VM can take after the rootfs (/dev/vdb ..= /dev/vdz)
+const MAX_PATH_DRIVES: usize = 25;
+
+/// Free space added to each image beyond the copied content
+const IMAGE_HEADROOM_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Read-only drive holding a copy of an exposed host directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathDrive {
+    /// Drive ID in the VM configuration
+    pub drive_id: String,
+    /// ext4 image on the host
+    pub image: PathBuf,
+    /// Guest path the drive is mounted at
+    pub target: PathBuf,
+}
+
+/// Build a drive image for each exposed path
+///
+/// # Arguments
+/// * `vm_id` - VM the images belong to, used to name them
+/// * `exposed` - Resolved exposed paths of the request
+///
+/// # Returns
+/// Drives in attachment order; on error no image is left behind
+pub(super) fn build_drives(vm_id: &str, exposed: &[ExposedPath]) -> BackendResult<Vec<PathDrive>> {
+    let invalid = |details: String| BackendError::InvalidConfig {
+        backend: "FireCracker",
+        details,
+    };
+    if exposed.len() > MAX_PATH_DRIVES {
+        return Err(invalid(format!(
+            "{} exposed paths exceed the {} drives a VM can attach",
+            exposed.len(),
+            MAX_PATH_DRIVES
+        )));
+    }
+
+    let mut drives: Vec<PathDrive> = Vec::with_capacity(exposed.len());
+    for (index, path) in exposed.iter().enumerate() {
+        let checked = if path.writable {
+            Err(invalid(format!(
+                "writable path {} cannot be exposed to a VM: drive writes are not synced back",
+                path.target.display()
+            )))
+        } else if !path.source.is_dir() {
+            Err(invalid(format!(
+                "readable path {} must be a directory to be exposed to a VM",
+                path.target.display()
+            )))
+        } else {
+            let image = std::env::temp_dir().join(format!("{vm_id}-path{index}.ext4"));
+            pack_directory(&path.source, &image).map(|()| image)
+        };
+
+        match checked {
+            Ok(image) => drives.push(PathDrive {
+                drive_id: format!("path{index}"),
+                image,
+                target: path.target.clone(),
+            }),
+            Err(e) => {
+                remove_images(&drives);
+                return Err(e);
+            }
+        }
+    }
+    Ok(drives)
+}
+
+/// Delete the images of previously built drives
+pub(super) fn remove_images(drives: &[PathDrive]) {
+    for drive in drives {
+        let _ = fs::remove_file(&drive.image);
+    }
+}
+
+/// Shell lines mounting the drives inside the guest
+///
+/// Extra drives follow the rootfs (/dev/vda) in attachment order. A failed
+/// mount aborts the run rather than executing against a missing path.
+pub(super) fn mount_script(drives: &[PathDrive]) -> String {
+    drives
+        .iter()
+        .enumerate()
+        .map(|(index, drive)| {
+            let device = format!("/dev/vd{}", char::from(b'b' + index as u8));
+            let target = shell_quote(&drive.target.to_string_lossy());
+            format!("mkdir -p {target} && mount -o ro {device} {target} || exit 1\n")
+        })
+        .collect()
+}
+
+/// Copy a directory into a fresh ext4 image sized to fit it
+fn pack_directory(source: &Path, image: &Path) -> BackendResult<()> {
+    let size_kib = (directory_size(source) * 5 / 4 + IMAGE_HEADROOM_BYTES) / 1024;
+    let output = Command::new("mkfs.ext4")
+        .args(["-q", "-F", "-d"])
+        .arg(source)
+        .arg(image)
+        .arg(format!("{size_kib}k"))
+        .helper_output(Helper::FileTool)
+        .map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to run mkfs.ext4: {}", e),
+        })?;
+
+    if !output.status.success() {
+        let _ = fs::remove_file(image);
+        return Err(BackendError::FileSystemFailed {
+            details: format!(
+                "Failed to pack {} into a drive image: {}",
+                source.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Total size of the regular files below a directory
+fn directory_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => directory_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map_or(0, |m| m.len()),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Single-quote a string for the guest shell
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writable_and_file_paths_are_rejected() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("notes.txt");
+        fs::write(&file, b"x").expect("write");
+
+        let exposed = |source: PathBuf, writable| ExposedPath {
+            target: source.clone(),
+            source,
+            writable,
+        };
+        assert!(build_drives("vm", &[exposed(dir.path().to_path_buf(), true)]).is_err());
+        assert!(build_drives("vm", &[exposed(file, false)]).is_err());
+    }
+
+    #[test]
+    fn drives_mount_in_attachment_order() {
+        let drive = |index: usize, target: &str| PathDrive {
+            drive_id: format!("path{index}"),
+            image: PathBuf::from(format!("/tmp/vm-path{index}.ext4")),
+            target: PathBuf::from(target),
+        };
+        let script = mount_script(&[drive(0, "/data"), drive(1, "/srv/it's")]);
+        assert_eq!(
+            script,
+            "mkdir -p '/data' && mount -o ro /dev/vdb '/data' || exit 1\n\
+             mkdir -p '/srv/it'\\''s' && mount -o ro /dev/vdc '/srv/it'\\''s' || exit 1\n"
+        );
+    }
+}
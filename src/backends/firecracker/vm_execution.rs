@@ -4,14 +4,25 @@
 // Code execution inside VM via SSH and script preparation.
 // ============================================================================
 
-use std::fs;
-use std::io::Read;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
 use std::path::Path;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::async_task::AsyncTaskBuilder;
-use crate::backends::{AsyncTask, BackendError, BackendResult, ExecutionRequest, ExecutionResult, ResourceUsage};
+use crate::backends::{
+    AsyncTask, BackendError, BackendResult, CLOCK_METADATA, CORE_DUMP_METADATA, CompilerOptions,
+    CrashReport, ExecutionOutcome, ExecutionRequest, ExecutionResult, ResourceUsage,
+    SIGNAL_METADATA, WATCHDOG_METADATA,
+};
+use crate::backends::blocking;
+use crate::backends::output::{self, RawOutput};
+use crate::backends::paths::relative_inside;
+use crate::backends::{
+    SqlEngine, SqlOptions, clock, compile_phase, compiler, go_cache, language, r_library, sql,
+};
 
+use super::shared_paths;
 use super::vm_instance::VMInstance;
 
 impl VMInstance {
@@ -20,7 +31,55 @@ impl VMInstance {
         AsyncTaskBuilder::new(async move {
             let start_time = Instant::now();
 
-            let exec_script = prepare_execution_script(&request)?;
+            // The guest has neither the host's git nor, usually, a network
+            // route to the remote
+            if request.git_repo.is_some() {
+                return Err(BackendError::NotAvailable {
+                    backend: "FireCracker",
+                    reason: "git checkouts are not supported inside VMs".to_string(),
+                });
+            }
+            // The workspace lives in the guest, out of reach of the host's
+            // guarded unpacking and packing
+            if request.workspace_archive.is_some() || request.output_archive.is_some() {
+                return Err(BackendError::NotAvailable {
+                    backend: "FireCracker",
+                    reason: "workspace archives are not supported inside VMs".to_string(),
+                });
+            }
+
+            // Code travels as its own file and the runner script only names
+            // that file, so nothing from the request is ever parsed by a shell
+            let guest_code_path = guest_source_path(&self.vm_id, &request.language)?;
+            let guest_script_path = format!("/tmp/exec-{}-run.sh", self.vm_id);
+            // Drives are mounted, the environment normalized and the guest
+            // clock set right before the code starts, so an offset clock is
+            // not skewed by boot time
+            let mut setup = shared_paths::mount_script(&self.path_drives);
+            let files = guest_files(&request)?;
+            let (staged_files, files_setup) = stage_files(&self.vm_id, &files)?;
+            setup.push_str(&files_setup);
+            let home = format!("/tmp/exec-{}-home", self.vm_id);
+            setup.push_str(&format!("mkdir -p {home} || exit 1\n"));
+            setup.push_str(&request.environment.shell_setup(&home));
+            let cache_root = format!("/tmp/exec-{}-cache", self.vm_id);
+            let cache_vars = go_cache::go_env(&request, &cache_root)
+                .into_iter()
+                .chain(r_library::r_env(&request, &cache_root));
+            for (key, value) in cache_vars {
+                let value = value.replace('\'', r"'\''");
+                setup.push_str(&format!("export {key}='{value}'\n"));
+            }
+            if let Some(clock) = &request.clock {
+                setup.push_str(&clock::guest_clock_setup(clock, chrono::Utc::now()));
+            }
+            let exec_script = prepare_execution_script(
+                &request.language,
+                &guest_code_path,
+                &setup,
+                request.compiler.as_ref(),
+                request.sql.as_ref(),
+            )?;
 
             let ssh_config = self
                 .ssh_config
@@ -30,73 +89,128 @@ impl VMInstance {
                     details: "SSH configuration not available for VM".to_string(),
                 })?;
 
-            let script_path = format!("/tmp/exec-{}.sh", self.vm_id);
-            let guest_script_path = format!("/tmp/exec-{}.sh", self.vm_id);
-
-            fs::write(&script_path, &exec_script).map_err(|e| BackendError::FileSystemFailed {
-                details: format!("Failed to write script: {}", e),
-            })?;
-
-            copy_script_to_vm(&ssh_config, &script_path, &guest_script_path).await?;
+            // Install the DNS policy directly as well, for guests whose init
+            // does not read it from MMDS
+            if let Some(dns) = &request.dns {
+                if let Some(resolv_conf) = dns.resolv_conf() {
+                    copy_to_vm(ssh_config, resolv_conf.into_bytes(), "/etc/resolv.conf", 0o644)
+                        .await?;
+                }
+                copy_to_vm(ssh_config, dns.hosts().into_bytes(), "/etc/hosts", 0o644).await?;
+            }
 
-            let (exit_code, stdout, stderr) = execute_script_in_vm(&ssh_config, &guest_script_path).await?;
+            for (staging, contents) in staged_files {
+                copy_to_vm(ssh_config, contents.clone(), &staging, 0o644).await?;
+            }
+            copy_to_vm(ssh_config, request.code.clone().into_bytes(), &guest_code_path, 0o644)
+                .await?;
+            copy_to_vm(ssh_config, exec_script.into_bytes(), &guest_script_path, 0o755).await?;
 
-            let _ = fs::remove_file(&script_path);
+            let output = execute_script_in_vm(
+                ssh_config,
+                &guest_script_path,
+                request.timeout,
+                request.termination_grace,
+            )
+            .await?;
 
+            // Metrics are read from the VM either way, so timed-out runs
+            // still report usage up to the kill point
             let resource_usage = collect_resource_metrics(&self).await;
 
             let duration = start_time.elapsed();
 
-            Ok(ExecutionResult {
-                exit_code,
-                stdout,
-                stderr,
-                duration,
-                resource_usage,
-                metadata: {
-                    let mut meta = std::collections::HashMap::new();
-                    meta.insert("backend".to_string(), "FireCracker".to_string());
-                    meta.insert("vm_id".to_string(), self.vm_id.clone());
-                    meta.insert("execution_method".to_string(), "SSH".to_string());
-                    meta
-                },
-            })
+            let mut result = if output.timed_out {
+                let mut result =
+                    ExecutionResult::timed_out(output.stdout, output.stderr, request.timeout);
+                result.duration = duration;
+                result.resource_usage = resource_usage;
+                result
+            } else {
+                ExecutionResult {
+                    exit_code: output.exit_code,
+                    stdout: output.stdout,
+                    stderr: output.stderr,
+                    duration,
+                    resource_usage,
+                    metadata: std::collections::HashMap::new(),
+                    verdict: None,
+                    outcome: ExecutionOutcome::Completed,
+                    security: None,
+                    cost: None,
+                    crash: None,
+                    result_sets: None,
+                    workspace_archive: None,
+                    blobs: std::collections::HashMap::new(),
+                    processed: None,
+                    raw_output: None,
+                    compilation: None,
+                }
+            };
+            result.raw_output = output.raw;
+
+            result.metadata.insert("backend".to_string(), "FireCracker".to_string());
+            result.metadata.insert("vm_id".to_string(), self.vm_id.clone());
+            result.metadata.insert("execution_method".to_string(), "SSH".to_string());
+            if let Some(dns) = &request.dns {
+                dns.record(&mut result);
+            }
+            if let Some(clock) = &request.clock {
+                result
+                    .metadata
+                    .insert(CLOCK_METADATA.to_string(), clock.mode().to_string());
+            }
+            if request.stall_timeout.is_some() {
+                // Liveness needs the sandbox's CPU use, which only the guest sees
+                result.metadata.insert(
+                    WATCHDOG_METADATA.to_string(),
+                    "not enforced: guest CPU use is not observable from the host".to_string(),
+                );
+            }
+
+            // bash reports a signal death as 128 + N; the core, if any,
+            // stays on the guest's disk
+            if !result.is_timed_out() {
+                result.crash = CrashReport::from_exit_code(result.exit_code);
+            }
+            if let Some(crash) = &result.crash {
+                result
+                    .metadata
+                    .insert(SIGNAL_METADATA.to_string(), crash.signal_name.clone());
+                if request.core_dump_limit.is_some() && crash.dumps_core() {
+                    result.metadata.insert(
+                        CORE_DUMP_METADATA.to_string(),
+                        "not captured: core dumps stay inside the VM".to_string(),
+                    );
+                }
+            }
+
+            Ok(result)
         }).spawn()
     }
 }
 
-/// Copy script to VM via SCP
-async fn copy_script_to_vm(
+/// Copy in-memory contents to a file in the VM via SCP
+async fn copy_to_vm(
     ssh_config: &super::ssh::SshConfig,
-    script_path: &str,
-    guest_script_path: &str,
+    contents: Vec<u8>,
+    guest_path: &str,
+    mode: i32,
 ) -> BackendResult<()> {
-    tokio::task::spawn_blocking({
+    blocking::backend_io().run({
         let ssh_cfg = ssh_config.clone();
-        let script = script_path.to_string();
-        let guest_script = guest_script_path.to_string();
+        let guest_path = guest_path.to_string();
         move || -> BackendResult<()> {
             let session = ssh_cfg.create_session()?;
-            let metadata = fs::metadata(&script).map_err(|e| BackendError::FileSystemFailed {
-                details: format!("Failed to read script metadata: {}", e),
-            })?;
-
-            let mut local_file = std::fs::File::open(&script).map_err(|e| {
-                BackendError::FileSystemFailed {
-                    details: format!("Failed to open script: {}", e),
-                }
-            })?;
 
             let mut remote_file = session
-                .scp_send(Path::new(&guest_script), 0o755, metadata.len(), None)
+                .scp_send(Path::new(&guest_path), mode, contents.len() as u64, None)
                 .map_err(|e| BackendError::ProcessFailed {
                     details: format!("SCP failed: {}", e),
                 })?;
 
-            std::io::copy(&mut local_file, &mut remote_file).map_err(|e| {
-                BackendError::ProcessFailed {
-                    details: format!("File copy failed: {}", e),
-                }
+            remote_file.write_all(&contents).map_err(|e| BackendError::ProcessFailed {
+                details: format!("File copy failed: {}", e),
             })?;
 
             remote_file.send_eof().map_err(|e| BackendError::ProcessFailed {
@@ -117,16 +231,42 @@ async fn copy_script_to_vm(
     Ok(())
 }
 
+/// Output of a script run inside the VM
+struct GuestOutput {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+    raw: Option<RawOutput>,
+    timed_out: bool,
+}
+
+/// Exit codes coreutils `timeout` uses for SIGTERM and SIGKILL terminations
+const GUEST_TIMEOUT_EXIT_CODES: [i32; 2] = [124, 137];
+
+/// Extra time the host waits on the SSH channel beyond the guest deadline
+const SSH_DEADLINE_SLACK: Duration = Duration::from_secs(10);
+
 /// Execute script in VM via SSH
+///
+/// The script runs under coreutils `timeout` inside the guest, which sends
+/// SIGTERM at the deadline and SIGKILL after the grace period. The SSH
+/// session carries its own deadline so a wedged guest cannot hang the host;
+/// output read before either deadline is kept.
 async fn execute_script_in_vm(
     ssh_config: &super::ssh::SshConfig,
     guest_script_path: &str,
-) -> BackendResult<(i32, String, String)> {
-    tokio::task::spawn_blocking({
+    timeout: Duration,
+    grace: Duration,
+) -> BackendResult<GuestOutput> {
+    blocking::backend_io().run({
         let ssh_cfg = ssh_config.clone();
         let guest_script = guest_script_path.to_string();
-        move || -> BackendResult<(i32, String, String)> {
+        move || -> BackendResult<GuestOutput> {
+            let started = Instant::now();
             let session = ssh_cfg.create_session()?;
+            let host_deadline = timeout + grace + SSH_DEADLINE_SLACK;
+            session.set_timeout(host_deadline.as_millis().min(u32::MAX as u128) as u32);
+
             let mut channel = session
                 .channel_session()
                 .map_err(|e| BackendError::ProcessFailed {
@@ -134,24 +274,37 @@ async fn execute_script_in_vm(
                 })?;
 
             channel
-                .exec(&format!("bash {}", guest_script))
+                .exec(&format!(
+                    "timeout --signal=TERM --kill-after={}s {}s bash {}",
+                    grace.as_secs().max(1),
+                    timeout.as_secs().max(1),
+                    guest_script
+                ))
                 .map_err(|e| BackendError::ProcessFailed {
                     details: format!("Exec failed: {}", e),
                 })?;
 
-            let mut stdout = String::new();
-            channel.read_to_string(&mut stdout).map_err(|e| {
-                BackendError::ProcessFailed {
-                    details: format!("Read stdout failed: {}", e),
-                }
-            })?;
+            // read_to_end keeps whatever arrived before an error, so a host
+            // deadline still yields the partial output
+            let mut stdout = Vec::new();
+            let stdout_complete = channel.read_to_end(&mut stdout).is_ok();
 
-            let mut stderr = String::new();
-            channel.stderr().read_to_string(&mut stderr).map_err(|e| {
-                BackendError::ProcessFailed {
-                    details: format!("Read stderr failed: {}", e),
-                }
-            })?;
+            let mut stderr = Vec::new();
+            let stderr_complete = channel.stderr().read_to_end(&mut stderr).is_ok();
+
+            let (stdout, raw_stdout) = output::decode(stdout);
+            let (stderr, raw_stderr) = output::decode(stderr);
+            let raw = output::raw_output(&stdout, raw_stdout, &stderr, raw_stderr);
+
+            if !(stdout_complete && stderr_complete) {
+                return Ok(GuestOutput {
+                    exit_code: ExecutionResult::TIMEOUT_EXIT_CODE,
+                    stdout,
+                    stderr,
+                    raw,
+                    timed_out: true,
+                });
+            }
 
             channel.wait_close().map_err(|e| BackendError::ProcessFailed {
                 details: format!("Wait close failed: {}", e),
@@ -161,7 +314,14 @@ async fn execute_script_in_vm(
                 details: format!("Get exit status failed: {}", e),
             })?;
 
-            Ok((exit_code, stdout, stderr))
+            Ok(GuestOutput {
+                exit_code,
+                stdout,
+                stderr,
+                raw,
+                timed_out: GUEST_TIMEOUT_EXIT_CODES.contains(&exit_code)
+                    && started.elapsed() >= timeout,
+            })
         }
     })
     .await
@@ -203,43 +363,156 @@ async fn collect_resource_metrics(vm: &VMInstance) -> ResourceUsage {
     }
 }
 
-/// Prepare execution script for the VM
-fn prepare_execution_script(request: &ExecutionRequest) -> BackendResult<String> {
-    let script = match request.language.as_str() {
-        "python" | "python3" => {
-            format!(
-                "#!/bin/bash\necho '{}' | python3",
-                request.code.replace('\'', "'\"'\"'")
-            )
+/// Guest path the request's source code is copied to
+fn guest_source_path(vm_id: &str, language: &str) -> BackendResult<String> {
+    let spec = language::resolve(language).ok_or_else(|| BackendError::UnsupportedLanguage {
+        backend: "FireCracker",
+        language: language.to_string(),
+    })?;
+    Ok(format!("/tmp/exec-{}-main.{}", vm_id, spec.extension))
+}
+
+/// Contents of the request's additional files and blobs by relative path
+///
+/// Blobs are read from their store here; the guest cannot reach it.
+fn guest_files(request: &ExecutionRequest) -> BackendResult<BTreeMap<String, Vec<u8>>> {
+    let mut files: BTreeMap<String, Vec<u8>> = request
+        .files
+        .iter()
+        .map(|(name, contents)| (name.clone(), contents.clone().into_bytes()))
+        .collect();
+    for (name, blob) in &request.blob_files {
+        let contents = std::fs::read(&blob.source).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to read blob {}: {}", blob.digest, e),
+        })?;
+        files.insert(name.clone(), contents);
+    }
+    Ok(files)
+}
+
+/// Guest staging paths for the request's additional files and blobs
+///
+/// scp cannot create directories, so each file is copied flat and moved to
+/// its place below the source file's directory by the returned setup lines.
+///
+/// # Returns
+/// Staging path and contents per file, and the shell lines placing them
+fn stage_files<'a, C: AsRef<[u8]>>(
+    vm_id: &str,
+    files: &'a BTreeMap<String, C>,
+) -> BackendResult<(Vec<(String, &'a C)>, String)> {
+    let quote = |path: &Path| format!("'{}'", path.display().to_string().replace('\'', r"'\''"));
+    let mut staged = Vec::with_capacity(files.len());
+    let mut setup = String::new();
+    for (index, (name, contents)) in files.iter().enumerate() {
+        let staging = format!("/tmp/exec-{vm_id}-file-{index}");
+        let target = Path::new("/tmp").join(relative_inside(name)?);
+        if let Some(parent) = target.parent() {
+            setup.push_str(&format!("mkdir -p {} || exit 1\n", quote(parent)));
         }
-        "javascript" | "js" | "node" => {
-            format!(
-                "#!/bin/bash\necho '{}' | node",
-                request.code.replace('\'', "'\"'\"'")
+        setup.push_str(&format!("mv {staging} {} || exit 1\n", quote(&target)));
+        staged.push((staging, contents));
+    }
+    Ok((staged, setup))
+}
+
+/// Prepare the runner script for the VM
+///
+/// The script only references the already-copied source file; the code
+/// itself is never embedded, so quotes or `$(` in it cannot break out.
+///
+/// # Arguments
+/// * `language` - Programming language
+/// * `code_path` - Guest path of the source file
+/// * `setup` - Shell lines run before the code, e.g. drive mounts
+/// * `compiler` - Compiler options for compiled languages
+/// * `sql` - Engine and seeds of a SQL request
+fn prepare_execution_script(
+    language: &str,
+    code_path: &str,
+    setup: &str,
+    compiler: Option<&CompilerOptions>,
+    sql: Option<&SqlOptions>,
+) -> BackendResult<String> {
+    let (name, _) = language::split_version(language);
+    let options = compiler.cloned().unwrap_or_default();
+    let command = match name.to_lowercase().as_str() {
+        "python" | "python3" | "py" => format!("exec python3 {code_path}"),
+        "javascript" | "js" | "node" => format!("exec node {code_path}"),
+        "rust" | "rs" => {
+            let binary = code_path.trim_end_matches(".rs");
+            let args = compiler::shell_args(&options.rustc_args());
+            compile_phase::compile_then_run(
+                &format!("rustc {args}{code_path} -o {binary}"),
+                &format!("exec {binary}"),
             )
         }
-        "rust" => {
-            format!(
-                "#!/bin/bash\necho '{}' > /tmp/main.rs && cd /tmp && rustc main.rs && ./main",
-                request.code.replace('\'', "'\"'\"'")
+        "bash" | "sh" => format!("exec bash {code_path}"),
+        "go" | "golang" => {
+            let args = compiler::shell_args(&options.go_args());
+            let binary = code_path.trim_end_matches(".go");
+            compile_phase::compile_then_run(
+                &format!("cd /tmp && go build -o {binary} {args}{code_path}"),
+                &format!("exec {binary}"),
             )
         }
-        "bash" | "sh" => {
-            format!("#!/bin/bash\n{}", request.code)
-        }
-        "go" => {
-            format!(
-                "#!/bin/bash\necho '{}' > /tmp/main.go && cd /tmp && go run main.go",
-                request.code.replace('\'', "'\"'\"'")
-            )
+        "r" | "rscript" => r_library::rscript_command("Rscript", code_path),
+        "sql" | "sqlite" | "sqlite3" | "duckdb" => {
+            let engine = SqlEngine::of(language, sql);
+            let args = sql::shell_args(engine, sql, code_path).map_err(|details| {
+                BackendError::InvalidConfig {
+                    backend: "FireCracker",
+                    details,
+                }
+            })?;
+            format!("exec {} {}", engine.program(), compiler::shell_args(&args).trim_end())
         }
         _ => {
             return Err(BackendError::UnsupportedLanguage {
                 backend: "FireCracker",
-                language: request.language.clone(),
+                language: language.to_string(),
             });
         }
     };
 
-    Ok(script)
+    Ok(format!("#!/bin/bash\n{setup}{command}\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::RustEdition;
+
+    #[test]
+    fn script_references_code_file_only() {
+        let code_path = guest_source_path("cylo-abc", "python").unwrap();
+        assert_eq!(code_path, "/tmp/exec-cylo-abc-main.py");
+
+        let script = prepare_execution_script("python", &code_path, "", None, None).unwrap();
+        assert_eq!(script, "#!/bin/bash\nexec python3 /tmp/exec-cylo-abc-main.py\n");
+
+        assert!(guest_source_path("cylo-abc", "cobol").is_err());
+        assert_eq!(guest_source_path("cylo-abc", "r").unwrap(), "/tmp/exec-cylo-abc-main.R");
+
+        let files = BTreeMap::from([("lib/it's.py".to_string(), String::new())]);
+        let (staged, setup) = stage_files("cylo-abc", &files).unwrap();
+        assert_eq!(staged[0].0, "/tmp/exec-cylo-abc-file-0");
+        assert_eq!(
+            setup,
+            "mkdir -p '/tmp/lib' || exit 1\n\
+             mv /tmp/exec-cylo-abc-file-0 '/tmp/lib/it'\\''s.py' || exit 1\n"
+        );
+
+        let edition = CompilerOptions::default().with_edition(RustEdition::E2021);
+        let script =
+            prepare_execution_script("rust", "/tmp/m.rs", "", Some(&edition), None).unwrap();
+        assert!(script.contains("; rustc --edition 2021 /tmp/m.rs -o /tmp/m;"), "{script}");
+        assert!(script.contains("exit \"$__cylo_rc\"; exec /tmp/m\n"), "{script}");
+
+        let seeded = SqlOptions::default().with_seed("/data/sales.csv");
+        let script = prepare_execution_script("sql", "/tmp/q.sql", "", None, Some(&seeded));
+        let script = script.unwrap();
+        assert!(script.contains("exec sqlite3 -bail -json -cmd '.import --csv "), "{script}");
+        assert!(script.contains(" sales' :memory: '.read "), "{script}");
+    }
 }
@@ -12,10 +12,17 @@ use std::time::{Duration, SystemTime};
 use serde::{Deserialize, Serialize};
 
 use crate::async_task::AsyncTaskBuilder;
-use crate::backends::{AsyncTask, BackendConfig, BackendError, BackendResult, ExecutionRequest};
+use crate::backends::io_throttle;
+use crate::backends::paths::exposed_paths;
+use crate::backends::{
+    AsyncTask, BackendConfig, BackendError, BackendResult, CpuSet, DnsPolicy, ExecutionRequest,
+};
+use crate::ids;
+use crate::reaper::global_reaper;
 
 use super::api_client::FireCrackerApiClient;
 use super::config::FireCrackerConfig;
+use super::shared_paths::{self, PathDrive};
 use super::ssh::SshConfig;
 
 /// VM instance information
@@ -42,21 +49,52 @@ pub struct VMInstance {
 
     /// SSH configuration for guest access
     pub ssh_config: Option<SshConfig>,
+
+    /// Execution that owns this VM
+    pub execution_id: Option<String>,
+
+    /// Execution timeout, used to bound how long the reaper lets the VM live
+    pub timeout: Duration,
+
+    /// Reaper registration for the running VM process
+    #[serde(skip)]
+    pub reaper_id: Option<u64>,
+
+    /// DNS policy published to the guest over MMDS
+    #[serde(default)]
+    pub dns: Option<DnsPolicy>,
+
+    /// Read-only drives carrying the request's readable paths
+    #[serde(default)]
+    pub path_drives: Vec<PathDrive>,
+
+    /// Host CPUs the VMM and its vCPU threads run on
+    #[serde(default)]
+    pub cpuset: Option<CpuSet>,
+
+    /// Host NUMA nodes guest memory is allocated from
+    #[serde(default)]
+    pub numa_nodes: Option<CpuSet>,
+
+    /// Disk bandwidth of each drive in bytes/sec, reads and writes together
+    #[serde(default)]
+    pub io_bandwidth: Option<u64>,
 }
 
 impl VMInstance {
     /// Create VM instance for execution
-    pub fn create(_request: &ExecutionRequest, backend_config: &BackendConfig) -> BackendResult<Self> {
-        let vm_id = format!(
-            "cylo-{}-{}",
-            uuid::Uuid::new_v4().simple(),
-            std::process::id()
+    pub fn create(request: &ExecutionRequest, backend_config: &BackendConfig) -> BackendResult<Self> {
+        let vm_id = ids::artifact_name(
+            ids::ID_PREFIX,
+            &backend_config.owner_id,
+            &ids::language_tag(&request.language),
         );
 
         let socket_path = std::env::temp_dir().join(format!("{}.sock", vm_id));
         let config_path = std::env::temp_dir().join(format!("{}.json", vm_id));
 
         let ssh_config = Self::build_ssh_config(backend_config);
+        let path_drives = shared_paths::build_drives(&vm_id, &exposed_paths(request)?)?;
 
         Ok(VMInstance {
             vm_id,
@@ -66,6 +104,14 @@ impl VMInstance {
             api_client: None,
             created_at: SystemTime::now(),
             ssh_config,
+            execution_id: request.execution_id.clone(),
+            timeout: request.timeout,
+            reaper_id: None,
+            dns: request.dns.clone(),
+            path_drives,
+            cpuset: request.limits.cpuset.clone(),
+            numa_nodes: request.limits.numa_nodes.clone(),
+            io_bandwidth: io_throttle::combined_bps(&request.limits),
         })
     }
 
@@ -108,26 +154,39 @@ impl VMInstance {
 
     /// Generate VM configuration file
     pub fn generate_config(&self, fc_config: &FireCrackerConfig, _request: &ExecutionRequest) -> BackendResult<()> {
+        let mut drives = vec![serde_json::json!({
+            "drive_id": "rootfs",
+            "path_on_host": fc_config.rootfs_path.display().to_string(),
+            "is_root_device": true,
+            "is_read_only": false
+        })];
+        drives.extend(self.path_drives.iter().map(|drive| {
+            serde_json::json!({
+                "drive_id": drive.drive_id,
+                "path_on_host": drive.image.display().to_string(),
+                "is_root_device": false,
+                "is_read_only": true
+            })
+        }));
+        if let Some(rate_limiter) = self.drive_rate_limiter() {
+            for drive in &mut drives {
+                drive["rate_limiter"] = rate_limiter.clone();
+            }
+        }
+
         let vm_config = serde_json::json!({
             "boot-source": {
                 "kernel_image_path": fc_config.kernel_path.display().to_string(),
                 "boot_args": "console=ttyS0 reboot=k panic=1 pci=off"
             },
-            "drives": [
-                {
-                    "drive_id": "rootfs",
-                    "path_on_host": fc_config.rootfs_path.display().to_string(),
-                    "is_root_device": true,
-                    "is_read_only": false
-                }
-            ],
+            "drives": drives,
             "machine-config": {
                 "vcpu_count": fc_config.vcpu_count,
                 "mem_size_mib": fc_config.memory_size_mb,
                 "ht_enabled": false
             },
             "logger": {
-                "log_path": format!("/tmp/{}.log", self.vm_id),
+                "log_path": self.log_path().display().to_string(),
                 "level": "Info"
             }
         });
@@ -144,8 +203,47 @@ impl VMInstance {
         Ok(())
     }
 
+    /// Token bucket refilled with `io_bandwidth` bytes every second, for
+    /// the `rate_limiter` of a drive
+    pub fn drive_rate_limiter(&self) -> Option<serde_json::Value> {
+        self.io_bandwidth.map(|bytes| {
+            serde_json::json!({
+                "bandwidth": { "size": bytes, "refill_time": 1000 }
+            })
+        })
+    }
+
+    /// Firecracker's log of this VM
+    pub fn log_path(&self) -> PathBuf {
+        PathBuf::from(format!("/tmp/{}.log", self.vm_id))
+    }
+
+    /// Guest serial console output, captured from Firecracker's stdout
+    pub fn console_path(&self) -> PathBuf {
+        PathBuf::from(format!("/tmp/{}.console", self.vm_id))
+    }
+
+    /// Firecracker's stderr
+    pub fn stderr_path(&self) -> PathBuf {
+        PathBuf::from(format!("/tmp/{}.stderr", self.vm_id))
+    }
+
+    /// Host files worth keeping when an execution in this VM fails: its
+    /// configuration, log, console output and stderr
+    pub fn evidence_paths(&self) -> Vec<PathBuf> {
+        vec![
+            self.config_path.clone(),
+            self.log_path(),
+            self.console_path(),
+            self.stderr_path(),
+        ]
+    }
+
     /// Stop and cleanup VM
-    pub fn cleanup(self) -> AsyncTask<BackendResult<()>> {
+    ///
+    /// # Arguments
+    /// * `retain_evidence` - Leave `evidence_paths` in place for inspection
+    pub fn cleanup(self, retain_evidence: bool) -> AsyncTask<BackendResult<()>> {
         AsyncTaskBuilder::new(async move {
             if let Some(pid) = self.pid {
                 let _ = Command::new("kill")
@@ -160,8 +258,16 @@ impl VMInstance {
             }
 
             let _ = fs::remove_file(&self.socket_path);
-            let _ = fs::remove_file(&self.config_path);
-            let _ = fs::remove_file(format!("/tmp/{}.log", self.vm_id));
+            if !retain_evidence {
+                for path in self.evidence_paths() {
+                    let _ = fs::remove_file(path);
+                }
+            }
+            shared_paths::remove_images(&self.path_drives);
+
+            if let Some(reaper_id) = self.reaper_id {
+                global_reaper().release(reaper_id);
+            }
 
             Ok(())
         }).spawn()
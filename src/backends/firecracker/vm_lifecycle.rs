@@ -4,7 +4,8 @@
 // VM startup, configuration, and lifecycle management.
 // ============================================================================
 
-use std::process::{Command, Stdio};
+use std::fs::File;
+use std::process::{Child, Command};
 use std::time::Duration;
 
 use bytes::Bytes;
@@ -12,9 +13,11 @@ use http_body_util::Full;
 use hyper::{Method, Request};
 
 use crate::async_task::AsyncTaskBuilder;
-use crate::backends::{AsyncTask, BackendError, BackendResult};
+use crate::backends::{AsyncTask, BackendError, BackendResult, DnsPolicy, cpuset};
+use crate::reaper::{DEADLINE_GRACE, ResourceKind, global_reaper};
 
 use super::api_client::FireCrackerApiClient;
+use super::boot_log;
 use super::config::FireCrackerConfig;
 use super::vm_instance::VMInstance;
 
@@ -30,49 +33,85 @@ impl VMInstance {
                 self.config_path.to_str().unwrap_or(""),
             ]);
 
-            cmd.stdout(Stdio::null());
-            cmd.stderr(Stdio::piped());
+            // The serial console and the VMM's errors are kept for boot
+            // failure diagnostics
+            let capture = |path: std::path::PathBuf| {
+                File::create(&path).map_err(|e| BackendError::FileSystemFailed {
+                    details: format!("Failed to create {}: {}", path.display(), e),
+                })
+            };
+            cmd.stdout(capture(self.console_path())?);
+            cmd.stderr(capture(self.stderr_path())?);
 
-            let child = cmd.spawn().map_err(|e| BackendError::ProcessFailed {
+            // vCPU threads inherit the VMM's placement
+            cpuset::pin(&mut cmd, self.cpuset.as_ref(), self.numa_nodes.as_ref());
+
+            let mut child = cmd.spawn().map_err(|e| BackendError::ProcessFailed {
                 details: format!("Failed to start FireCracker: {}", e),
             })?;
 
             self.pid = Some(child.id());
+            self.reaper_id = Some(global_reaper().register(
+                ResourceKind::Vm {
+                    pid: child.id(),
+                    socket_path: self.socket_path.clone(),
+                    artifacts: self.evidence_paths(),
+                },
+                "FireCracker",
+                self.execution_id.as_deref(),
+                Some(self.timeout + DEADLINE_GRACE),
+            ));
+
+            let booted = Self::boot(&self, &fc_config, &mut child).await;
+            let api_client = booted
+                .map_err(|e| boot_log::diagnose(&self, &fc_config, &mut child, e))?;
 
-            let api_client = FireCrackerApiClient::new(self.socket_path.clone()).map_err(|e| {
-                BackendError::InvalidConfig {
-                    backend: "FireCracker",
-                    details: format!("Failed to create API client: {}", e),
-                }
-            })?;
-
-            let machine_config = serde_json::json!({
-                "vcpu_count": fc_config.vcpu_count,
-                "mem_size_mib": fc_config.memory_size_mb,
-                "cpu_template": "C3",
-                "track_dirty_pages": false
-            });
-
-            api_client.configure_vm(&machine_config).await?;
+            if let Some(ssh_cfg) = &self.ssh_config {
+                Self::wait_for_ssh_ready(ssh_cfg).await?;
+            }
 
-            Self::configure_boot_source(&api_client, &self, &fc_config).await?;
-            Self::configure_rootfs(&api_client, &self, &fc_config).await?;
+            self.api_client = Some(api_client);
+            Ok(self)
+        }).spawn()
+    }
 
-            if fc_config.network_enabled {
-                Self::configure_network(&api_client, &self).await?;
+    /// Configure and start a spawned VM and wait for it to run
+    async fn boot(
+        vm: &VMInstance,
+        fc_config: &FireCrackerConfig,
+        child: &mut Child,
+    ) -> BackendResult<FireCrackerApiClient> {
+        let api_client = FireCrackerApiClient::new(vm.socket_path.clone()).map_err(|e| {
+            BackendError::InvalidConfig {
+                backend: "FireCracker",
+                details: format!("Failed to create API client: {}", e),
             }
+        })?;
 
-            api_client.start_vm().await?;
+        let machine_config = serde_json::json!({
+            "vcpu_count": fc_config.vcpu_count,
+            "mem_size_mib": fc_config.memory_size_mb,
+            "cpu_template": "C3",
+            "track_dirty_pages": false
+        });
 
-            Self::wait_for_vm_ready(&api_client).await?;
+        api_client.configure_vm(&machine_config).await?;
 
-            if let Some(ssh_cfg) = &self.ssh_config {
-                Self::wait_for_ssh_ready(ssh_cfg).await?;
+        Self::configure_boot_source(&api_client, vm, fc_config).await?;
+        Self::configure_rootfs(&api_client, vm, fc_config).await?;
+
+        if fc_config.network_enabled {
+            Self::configure_network(&api_client, vm).await?;
+            if let Some(dns) = &vm.dns {
+                Self::configure_mmds(&api_client, vm, dns).await?;
             }
+        }
 
-            self.api_client = Some(api_client);
-            Ok(self)
-        }).spawn()
+        api_client.start_vm().await?;
+
+        Self::wait_for_vm_ready(&api_client, child).await?;
+
+        Ok(api_client)
     }
 
     async fn configure_boot_source(
@@ -119,12 +158,15 @@ impl VMInstance {
         vm: &VMInstance,
         fc_config: &FireCrackerConfig,
     ) -> BackendResult<()> {
-        let rootfs_config = serde_json::json!({
+        let mut rootfs_config = serde_json::json!({
             "drive_id": "rootfs",
             "path_on_host": fc_config.rootfs_path,
             "is_root_device": true,
             "is_read_only": false
         });
+        if let Some(rate_limiter) = vm.drive_rate_limiter() {
+            rootfs_config["rate_limiter"] = rate_limiter;
+        }
 
         let rootfs_body = serde_json::to_vec(&rootfs_config).map_err(|e| {
             BackendError::InvalidConfig {
@@ -195,8 +237,68 @@ impl VMInstance {
         Ok(())
     }
 
-    async fn wait_for_vm_ready(api_client: &FireCrackerApiClient) -> BackendResult<()> {
+    /// Publish the DNS policy to the guest's metadata service
+    ///
+    /// Guest init scripts read `/cylo/dns` from MMDS and install the
+    /// rendered resolv.conf and hosts file before bringing up eth0.
+    async fn configure_mmds(
+        api_client: &FireCrackerApiClient,
+        vm: &VMInstance,
+        dns: &DnsPolicy,
+    ) -> BackendResult<()> {
+        let mmds_config = serde_json::json!({
+            "version": "V2",
+            "network_interfaces": ["eth0"]
+        });
+        let metadata = serde_json::json!({
+            "cylo": {
+                "dns": {
+                    "resolv_conf": dns.resolv_conf(),
+                    "hosts": dns.hosts()
+                }
+            }
+        });
+
+        for (path, body) in [("/mmds/config", mmds_config), ("/mmds", metadata)] {
+            let body = serde_json::to_vec(&body).map_err(|e| BackendError::InvalidConfig {
+                backend: "FireCracker",
+                details: format!("Failed to serialize MMDS config: {}", e),
+            })?;
+
+            let uri = format!("unix://{}:{}", vm.socket_path.display(), path);
+            let request = Request::builder()
+                .method(Method::PUT)
+                .uri(uri)
+                .header("Content-Type", "application/json")
+                .body(Full::new(Bytes::from(body)))
+                .map_err(|e| BackendError::InvalidConfig {
+                    backend: "FireCracker",
+                    details: format!("Failed to create MMDS request: {}", e),
+                })?;
+
+            api_client.http_client()
+                .request(request)
+                .await
+                .map_err(|e| BackendError::NetworkFailed {
+                    details: format!("MMDS configuration failed: {}", e),
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Poll until the VM runs, giving up early if Firecracker exits
+    async fn wait_for_vm_ready(
+        api_client: &FireCrackerApiClient,
+        child: &mut Child,
+    ) -> BackendResult<()> {
         for attempt in 0..30 {
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(BackendError::ProcessFailed {
+                    details: format!("FireCracker exited with {} during boot", status),
+                });
+            }
+
             match api_client.get_vm_metrics().await {
                 Ok(metrics) => {
                     if let Some(state) = metrics.get("state").and_then(|v| v.as_str()) {
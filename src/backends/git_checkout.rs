@@ -0,0 +1,518 @@
+// ============================================================================
+// File: packages/cylo/src/backends/git_checkout.rs
+// ----------------------------------------------------------------------------
+// Git repositories checked out into the workspace before execution.
+//
+// A request naming a repository and revision gets a shallow checkout of it
+// below its workspace, so "run the tests of this repo at this commit" is a
+// single request. The host's git does the fetch, outside the sandbox and
+// before the code starts: only https and ssh remotes are allowed, the
+// checkout is killed once it grows past its size cap, and credentials reach
+// git through a one-off credential helper reading the environment, never
+// through the command line or the remote URL.
+//
+// The fetch acts for the request, not for the host: git reads no global or
+// system config, no credential helper, askpass or netrc of the host, and
+// without request credentials ssh offers neither the host's keys nor its
+// agent. It follows the request's network policy too, so a request denied
+// the network gets no checkout and a DNS policy decides whether and where
+// the remote's name resolves.
+// ============================================================================
+
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::backends::paths::relative_inside;
+use crate::backends::volumes::directory_size;
+use crate::backends::{BackendError, BackendResult, DnsResolvers, ExecutionRequest};
+use crate::hardening::{Helper, HelperCommand};
+
+/// Workspace directory a repository is checked out into by default
+pub const DEFAULT_CHECKOUT_PATH: &str = "repo";
+
+/// Size cap of a checkout that sets none
+pub const DEFAULT_MAX_BYTES: u64 = 512 * 1024 * 1024; // 512MB
+
+/// Longest a checkout may take, fetch included
+const CHECKOUT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often the size of a running fetch is checked
+const SIZE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Credential helper answering git's prompts from the environment
+const CREDENTIAL_HELPER: &str = "credential.helper=!f() { test \"$1\" = get || exit 0; \
+    echo \"username=$CYLO_GIT_USERNAME\"; echo \"password=$CYLO_GIT_PASSWORD\"; }; f";
+
+/// ssh offering no identity and reading no config of the host
+const ISOLATED_SSH: &str =
+    "ssh -F /dev/null -o BatchMode=yes -o IdentitiesOnly=yes -o IdentityFile=/dev/null";
+
+/// Host variables that would hand git config, credentials or an ssh
+/// program other than the ones set here
+const HOST_GIT_ENV: &[&str] = &[
+    "GIT_ASKPASS",
+    "SSH_ASKPASS",
+    "GIT_SSH",
+    "GIT_CONFIG_PARAMETERS",
+    "GIT_CONFIG_COUNT",
+];
+
+/// User name and password or token for an https remote
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitCredentials {
+    /// User name; forges taking tokens usually accept any non-empty name
+    pub username: String,
+    /// Password or access token
+    pub password: String,
+}
+
+impl fmt::Debug for GitCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GitCredentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// A repository revision to check out into the workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitCheckout {
+    /// Remote URL: `https://`, `ssh://` or scp-like `user@host:path`
+    pub url: String,
+    /// Branch, tag or commit to check out
+    pub rev: String,
+    /// Commits of history to fetch; 1 when unset
+    #[serde(default)]
+    pub depth: Option<u32>,
+    /// Most bytes the checkout may take, `.git` included;
+    /// `DEFAULT_MAX_BYTES` when unset
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+    /// Workspace-relative directory to check out into
+    #[serde(default = "default_checkout_path")]
+    pub path: String,
+    /// Credentials for the remote
+    #[serde(default)]
+    pub credentials: Option<GitCredentials>,
+}
+
+fn default_checkout_path() -> String {
+    DEFAULT_CHECKOUT_PATH.to_string()
+}
+
+impl GitCheckout {
+    /// Check out `rev` of the repository at `url`
+    ///
+    /// # Arguments
+    /// * `url` - Remote URL
+    /// * `rev` - Branch, tag or commit
+    pub fn new<U: Into<String>, R: Into<String>>(url: U, rev: R) -> Self {
+        Self {
+            url: url.into(),
+            rev: rev.into(),
+            depth: None,
+            max_bytes: None,
+            path: default_checkout_path(),
+            credentials: None,
+        }
+    }
+
+    /// Fetch this many commits of history instead of one
+    pub fn with_depth(mut self, depth: u32) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Cap the size of the checkout
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Check out into this workspace-relative directory
+    pub fn with_path<P: Into<String>>(mut self, path: P) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Authenticate against the remote
+    pub fn with_credentials<U: Into<String>, P: Into<String>>(
+        mut self,
+        username: U,
+        password: P,
+    ) -> Self {
+        self.credentials = Some(GitCredentials {
+            username: username.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// Check the checkout can be attempted
+    ///
+    /// # Returns
+    /// Ok(()) or the reason it cannot
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_allowed_url(&self.url) {
+            return Err(format!(
+                "'{}' is not an https, ssh or scp-like remote URL",
+                self.url.escape_debug()
+            ));
+        }
+        if self.rev.is_empty()
+            || self.rev.starts_with('-')
+            || self.rev.chars().any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(format!("'{}' is not a valid revision", self.rev.escape_debug()));
+        }
+        if self.depth == Some(0) {
+            return Err("depth must be non-zero".to_string());
+        }
+        if self.max_bytes == Some(0) {
+            return Err("size cap must be non-zero".to_string());
+        }
+        match relative_inside(&self.path) {
+            Ok(relative) if relative.as_os_str().is_empty() => {
+                Err("path must name a directory below the workspace".to_string())
+            }
+            Ok(_) => Ok(()),
+            Err(_) => Err(format!("'{}' escapes the workspace", self.path.escape_debug())),
+        }
+    }
+
+    /// Whether a workspace-relative file lies inside the checkout
+    pub fn contains(&self, file: &str) -> bool {
+        match (relative_inside(&self.path), relative_inside(file)) {
+            (Ok(path), Ok(file)) => file.starts_with(path),
+            _ => false,
+        }
+    }
+
+    fn max_bytes(&self) -> u64 {
+        self.max_bytes.unwrap_or(DEFAULT_MAX_BYTES)
+    }
+}
+
+/// Check out a repository into a workspace
+///
+/// The directory is removed again if the checkout fails.
+///
+/// # Arguments
+/// * `checkout` - Repository and revision
+/// * `workspace` - Host directory of the execution's workspace
+/// * `request` - Execution request whose network policy the fetch follows
+///
+/// # Returns
+/// Host directory holding the checkout
+pub async fn checkout(
+    checkout: &GitCheckout,
+    workspace: &Path,
+    request: &ExecutionRequest,
+) -> BackendResult<PathBuf> {
+    checkout.validate().map_err(|details| BackendError::InvalidConfig {
+        backend: "git",
+        details,
+    })?;
+    let remote = Remote::parse(&checkout.url);
+    let pinned = remote.address(request).map_err(|details| BackendError::InvalidConfig {
+        backend: "git",
+        details,
+    })?;
+    let fetch = Fetch {
+        checkout,
+        remote,
+        pinned,
+    };
+    let dest = workspace.join(relative_inside(&checkout.path)?);
+    fs::create_dir_all(&dest).map_err(|e| BackendError::FileSystemFailed {
+        details: format!("Failed to create checkout directory: {e}"),
+    })?;
+
+    let outcome = match tokio::time::timeout(CHECKOUT_TIMEOUT, fetch.run(&dest)).await {
+        Ok(outcome) => outcome,
+        Err(_) => Err(BackendError::ProcessFailed {
+            details: format!(
+                "Checking out {} timed out after {}s",
+                checkout.url,
+                CHECKOUT_TIMEOUT.as_secs()
+            ),
+        }),
+    };
+    if let Err(e) = outcome {
+        let _ = fs::remove_dir_all(&dest);
+        return Err(e);
+    }
+    Ok(dest)
+}
+
+/// Host and port of a remote, as the network policy sees them
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Remote {
+    host: String,
+    port: u16,
+    ssh: bool,
+}
+
+impl Remote {
+    /// Remote of a URL `GitCheckout::validate` accepted
+    fn parse(url: &str) -> Self {
+        let (authority, ssh) = match url.split_once("://") {
+            Some((scheme, rest)) => (rest.split('/').next().unwrap_or(""), scheme == "ssh"),
+            // scp-like `[user@]host:path` has no port
+            None => (url.split_once(':').map_or("", |(host, _)| host), true),
+        };
+        let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(bracketed) => match bracketed.split_once(']') {
+                Some((host, port)) => (host, port.strip_prefix(':')),
+                None => (bracketed, None),
+            },
+            None => match authority.split_once(':') {
+                Some((host, port)) if url.contains("://") => (host, Some(port)),
+                _ => (authority, None),
+            },
+        };
+        let default_port = if ssh { 22 } else { 443 };
+        Self {
+            host: host.to_ascii_lowercase(),
+            port: port.and_then(|port| port.parse().ok()).unwrap_or(default_port),
+            ssh,
+        }
+    }
+
+    /// Address the remote's name is pinned to under the request's network
+    /// policy
+    ///
+    /// # Returns
+    /// The static host address, None where the host's resolvers may look
+    /// the name up, or why the policy forbids fetching from the remote
+    fn address(&self, request: &ExecutionRequest) -> Result<Option<IpAddr>, String> {
+        if !request.network_allowed() {
+            return Err(format!(
+                "the request has no network access, so {} cannot be fetched",
+                self.host
+            ));
+        }
+        let Some(dns) = &request.dns else {
+            return Ok(None);
+        };
+        if self.host.parse::<IpAddr>().is_ok() {
+            return Ok(None);
+        }
+        let name = self.host.trim_end_matches('.');
+        if dns.blocked_domains.iter().any(|blocked| blocked.trim_end_matches('.') == name) {
+            return Err(format!("the DNS policy blocks {name}"));
+        }
+        if let Some(address) = dns
+            .static_hosts
+            .iter()
+            .find(|(host, _)| host.trim_end_matches('.') == name)
+            .map(|(_, address)| *address)
+        {
+            return Ok(Some(address));
+        }
+        match dns.resolvers {
+            DnsResolvers::Host => Ok(None),
+            // The host's git can only use the host's resolvers
+            DnsResolvers::Fixed(_) => Err(format!(
+                "the host cannot resolve {name} through the DNS policy's resolvers; \
+                 add it as a static host"
+            )),
+            DnsResolvers::None => Err(format!("the DNS policy does not resolve {name}")),
+        }
+    }
+}
+
+/// A checkout fetched on the request's behalf
+struct Fetch<'a> {
+    checkout: &'a GitCheckout,
+    remote: Remote,
+    /// Address the remote's name is pinned to by the DNS policy
+    pinned: Option<IpAddr>,
+}
+
+impl Fetch<'_> {
+    async fn run(&self, dest: &Path) -> BackendResult<()> {
+        let checkout = self.checkout;
+        let depth = format!("--depth={}", checkout.depth.unwrap_or(1));
+        self.git(dest, &["init", "--quiet"]).await?;
+        self.git(
+            dest,
+            &["fetch", "--quiet", "--no-tags", &depth, &checkout.url, &checkout.rev],
+        )
+        .await?;
+        self.git(dest, &["checkout", "--quiet", "--detach", "FETCH_HEAD"]).await
+    }
+
+    /// Run one git command in the checkout, killing it once the checkout
+    /// outgrows its cap
+    async fn git(&self, dest: &Path, args: &[&str]) -> BackendResult<()> {
+        let checkout = self.checkout;
+        let mut command = Command::new("git");
+        // An empty helper drops any helper configured on the host
+        command
+            .arg("-C")
+            .arg(dest)
+            .args(["-c", "protocol.file.allow=never", "-c", "core.hooksPath=/dev/null"])
+            .args(["-c", "credential.helper="])
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .env("GIT_ALLOW_PROTOCOL", "https:ssh")
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            // curl reads .netrc from HOME; the fresh checkout has none
+            .env("HOME", dest)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        for var in HOST_GIT_ENV {
+            command.env_remove(var);
+        }
+
+        let mut ssh = match &checkout.credentials {
+            Some(credentials) => {
+                command
+                    .args(["-c", CREDENTIAL_HELPER])
+                    .env("CYLO_GIT_USERNAME", &credentials.username)
+                    .env("CYLO_GIT_PASSWORD", &credentials.password);
+                std::env::var("GIT_SSH_COMMAND")
+                    .unwrap_or_else(|_| "ssh -o BatchMode=yes".to_string())
+            }
+            None => {
+                command.env_remove("SSH_AUTH_SOCK");
+                ISOLATED_SSH.to_string()
+            }
+        };
+        if let Some(address) = self.pinned {
+            if self.remote.ssh {
+                ssh.push_str(&format!(" -o HostName={address}"));
+            } else {
+                let address = match address {
+                    IpAddr::V4(v4) => v4.to_string(),
+                    IpAddr::V6(v6) => format!("[{v6}]"),
+                };
+                let Remote { host, port, .. } = &self.remote;
+                command.arg("-c").arg(format!("http.curloptResolve={host}:{port}:{address}"));
+            }
+        }
+        command.env("GIT_SSH_COMMAND", ssh);
+        command.args(args);
+
+        // Confined like any helper; the ssh git starts inherits it
+        let child = command
+            .helper_spawn(Helper::NetworkClient)
+            .map_err(|e| BackendError::ProcessFailed {
+                details: format!("Failed to run git (is it installed?): {e}"),
+            })?;
+        let output = child.wait_with_output();
+        tokio::pin!(output);
+        let mut poll = tokio::time::interval(SIZE_POLL_INTERVAL);
+        let output = loop {
+            tokio::select! {
+                output = &mut output => break output,
+                _ = poll.tick() => {
+                    if directory_size(dest) > checkout.max_bytes() {
+                        return Err(BackendError::ProcessFailed {
+                            details: format!(
+                                "Checkout of {} exceeds its {} byte cap",
+                                checkout.url,
+                                checkout.max_bytes()
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+        .map_err(|e| BackendError::ProcessFailed {
+            details: format!("Failed to run git: {e}"),
+        })?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(BackendError::ProcessFailed {
+                details: format!(
+                    "git {} of {} exited with {}: {}",
+                    args[0],
+                    checkout.url,
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            })
+        }
+    }
+}
+
+fn is_allowed_url(url: &str) -> bool {
+    if url.starts_with('-') || url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return false;
+    }
+    if let Some((scheme, rest)) = url.split_once("://") {
+        return matches!(scheme, "https" | "ssh") && !rest.is_empty();
+    }
+    // scp-like `[user@]host:path`; a slash before the colon would make it a
+    // local path
+    url.split_once(':').is_some_and(|(host, path)| {
+        !host.is_empty() && !host.contains('/') && !path.is_empty()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_allows_only_remote_checkouts() {
+        let checkout = GitCheckout::new("https://github.com/cyrup-ai/cylo.git", "main");
+        assert!(checkout.validate().is_ok());
+        assert!(GitCheckout::new("git@github.com:cyrup-ai/cylo.git", "v1.0").validate().is_ok());
+
+        for url in ["file:///etc", "ext::sh -c id", "/srv/repo", "./repo:x", "--upload-pack=x"] {
+            assert!(GitCheckout::new(url, "main").validate().is_err(), "{url}");
+        }
+        assert!(GitCheckout::new("https://x/y", "--orphan").validate().is_err());
+        assert!(checkout.clone().with_depth(0).validate().is_err());
+        assert!(checkout.clone().with_path("../up").validate().is_err());
+        assert!(checkout.clone().with_path(".").validate().is_err());
+
+        assert!(checkout.contains("repo/tests/fixture.txt"));
+        assert!(!checkout.contains("repository.txt"));
+
+        let debug = format!("{:?}", checkout.with_credentials("ci", "s3cret"));
+        assert!(debug.contains("ci") && !debug.contains("s3cret"));
+    }
+
+    #[test]
+    fn fetches_follow_the_network_policy() {
+        use crate::backends::DnsPolicy;
+
+        let remote = Remote::parse("https://ci@Git.Internal:8443/team/repo.git");
+        assert_eq!((remote.host.as_str(), remote.port, remote.ssh), ("git.internal", 8443, false));
+        let remote = Remote::parse("git@github.com:cyrup-ai/cylo.git");
+        assert_eq!((remote.host.as_str(), remote.port, remote.ssh), ("github.com", 22, true));
+        assert_eq!(Remote::parse("ssh://[::1]:2222/repo").host, "::1");
+
+        let remote = Remote::parse("https://git.internal/repo.git");
+        let request = ExecutionRequest::new("ls", "bash");
+        assert_eq!(remote.address(&request), Ok(None));
+        let mut offline = request.clone();
+        offline.limits.max_network_bandwidth = Some(0);
+        assert!(remote.address(&offline).is_err());
+
+        let internal: IpAddr = "10.0.4.7".parse().unwrap();
+        let pinned = request.clone().with_dns(DnsPolicy::only([("git.internal", internal)]));
+        assert_eq!(remote.address(&pinned), Ok(Some(internal)));
+        let unresolved = request.clone().with_dns(DnsPolicy::only([("api.internal", internal)]));
+        assert!(remote.address(&unresolved).is_err());
+        let blocked = request.with_dns(DnsPolicy::default().with_blocked_domain("git.internal"));
+        assert!(remote.address(&blocked).unwrap_err().contains("blocks"));
+    }
+}
@@ -0,0 +1,115 @@
+// ============================================================================
+// File: packages/cylo/src/backends/go_cache.rs
+// ----------------------------------------------------------------------------
+// Module and build cache isolation for Go executions.
+//
+// Left alone, `go run` puts downloaded modules and build artifacts under the
+// invoking user's home and fetches from whatever proxy the host configures.
+// Every Go execution instead keeps both caches inside its own workspace, so
+// nothing outlives it or leaks between executions, and resolves modules from
+// an optional read-only warm cache first, then from the public proxy only
+// when the execution may use the network.
+// ============================================================================
+
+use std::path::Path;
+
+use crate::backends::{ExecutionRequest, language};
+
+/// Module proxy used when the execution may reach the network
+pub const PUBLIC_GO_PROXY: &str = "https://proxy.golang.org";
+
+/// `GOPROXY` value for an execution
+///
+/// A module cache's `cache/download` directory is laid out as a proxy, so a
+/// warm cache is served through a `file://` proxy entry; modules extracted
+/// from it land in the writable per-execution cache and the warm cache is
+/// never written to.
+///
+/// # Arguments
+/// * `warm_cache` - Sandbox path of a read-only module cache, if any
+/// * `network` - Whether the execution may reach the network
+pub fn go_proxy(warm_cache: Option<&Path>, network: bool) -> String {
+    let mut proxies = Vec::new();
+    if let Some(warm) = warm_cache {
+        proxies.push(format!("file://{}/cache/download", warm.display()));
+    }
+    if network {
+        proxies.push(PUBLIC_GO_PROXY.to_string());
+    }
+    if proxies.is_empty() {
+        "off".to_string()
+    } else {
+        proxies.join(",")
+    }
+}
+
+/// Variables isolating a Go execution's caches
+///
+/// Empty for any other language. Backends apply them after the environment
+/// profile and before the request's variables, which still win.
+///
+/// # Arguments
+/// * `request` - Execution request
+/// * `cache_root` - Writable directory inside the sandbox that is removed
+///   with the workspace
+pub fn go_env(request: &ExecutionRequest, cache_root: &str) -> Vec<(&'static str, String)> {
+    if language::resolve(&request.language).map(|spec| spec.name) != Some("go") {
+        return Vec::new();
+    }
+
+    let network = request.network_allowed();
+    let mut vars = vec![
+        ("GOPATH", format!("{cache_root}/go")),
+        ("GOMODCACHE", format!("{cache_root}/go/pkg/mod")),
+        ("GOCACHE", format!("{cache_root}/go-build")),
+        // Extracted modules are read-only by default, which would keep the
+        // workspace from being removed
+        ("GOFLAGS", "-modcacherw".to_string()),
+        // A go.mod asking for a newer toolchain must not trigger a download
+        ("GOTOOLCHAIN", "local".to_string()),
+        ("GOPROXY", go_proxy(request.go_module_cache.as_deref(), network)),
+    ];
+    // The checksum database is unreachable offline; a warm cache is trusted
+    // as provisioned
+    if !network {
+        vars.push(("GOSUMDB", "off".to_string()));
+    }
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::ResourceLimits;
+
+    #[test]
+    fn caches_stay_in_the_workspace() {
+        let vars = go_env(&ExecutionRequest::new("package main", "golang"), "/workspace/.cache");
+        let var = |name: &str| vars.iter().find(|(key, _)| *key == name).map(|(_, v)| v.as_str());
+        assert_eq!(var("GOMODCACHE"), Some("/workspace/.cache/go/pkg/mod"));
+        assert_eq!(var("GOCACHE"), Some("/workspace/.cache/go-build"));
+        assert_eq!(var("GOPROXY"), Some(PUBLIC_GO_PROXY));
+        assert_eq!(var("GOSUMDB"), None);
+
+        assert!(go_env(&ExecutionRequest::new("print(1)", "python"), "/tmp").is_empty());
+    }
+
+    #[test]
+    fn proxy_follows_warm_cache_and_network() {
+        let warm = Path::new("/var/cache/go-mod");
+        assert_eq!(
+            go_proxy(Some(warm), true),
+            "file:///var/cache/go-mod/cache/download,https://proxy.golang.org"
+        );
+        assert_eq!(go_proxy(Some(warm), false), "file:///var/cache/go-mod/cache/download");
+        assert_eq!(go_proxy(None, false), "off");
+
+        let offline = ExecutionRequest::new("package main", "go").with_limits(ResourceLimits {
+            max_network_bandwidth: Some(0),
+            ..ResourceLimits::default()
+        });
+        let vars = go_env(&offline, "/tmp/cache");
+        assert!(vars.contains(&("GOPROXY", "off".to_string())));
+        assert!(vars.contains(&("GOSUMDB", "off".to_string())));
+    }
+}
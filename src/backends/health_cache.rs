@@ -0,0 +1,294 @@
+// ============================================================================
+// File: packages/cylo/src/backends/health_cache.rs
+// ----------------------------------------------------------------------------
+// Shared cache of backend health check results.
+//
+// Health checks start containers or VMs and run probe programs, so callers
+// go through this cache instead of invoking `health_check()` directly.
+// Healthy results are reused for a TTL; unhealthy results are cached too,
+// with a backoff that doubles on every consecutive failure so a broken
+// backend is not probed on every request. Results are kept per check
+// level, and a fresh readiness result also answers liveness queries.
+// ============================================================================
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ExecutionBackend, HealthCheckLevel, HealthStatus};
+use crate::async_task::AsyncTaskBuilder;
+
+/// Freshness rules for cached health results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthCacheConfig {
+    /// How long a healthy result is reused
+    pub ttl: Duration,
+    /// How long the first unhealthy result is reused
+    pub negative_ttl: Duration,
+    /// Upper bound for the unhealthy backoff
+    pub max_negative_ttl: Duration,
+}
+
+impl Default for HealthCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            negative_ttl: Duration::from_secs(5),
+            max_negative_ttl: Duration::from_secs(300),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedHealth {
+    status: HealthStatus,
+    checked_at: Instant,
+    valid_for: Duration,
+    consecutive_failures: u32,
+}
+
+impl CachedHealth {
+    fn is_fresh(&self) -> bool {
+        self.checked_at.elapsed() < self.valid_for
+    }
+}
+
+/// Health results keyed by instance id or backend name
+#[derive(Debug, Default)]
+pub struct HealthCache {
+    config: RwLock<HealthCacheConfig>,
+    entries: Mutex<HashMap<(String, HealthCheckLevel), CachedHealth>>,
+    refreshing: Mutex<HashSet<(String, HealthCheckLevel)>>,
+}
+
+impl HealthCache {
+    /// Create an empty cache
+    pub fn new(config: HealthCacheConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            entries: Mutex::new(HashMap::new()),
+            refreshing: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Current freshness rules
+    pub fn config(&self) -> HealthCacheConfig {
+        self.config
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Replace the freshness rules; cached entries keep their expiry
+    pub fn set_config(&self, config: HealthCacheConfig) {
+        *self
+            .config
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = config;
+    }
+
+    /// Cached result for a key at a level, if it has not expired
+    ///
+    /// Fresh results from deeper levels count too, so a failed readiness
+    /// check is visible to liveness queries; the most recent one wins.
+    pub fn get(&self, key: &str, level: HealthCheckLevel) -> Option<HealthStatus> {
+        let entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        [HealthCheckLevel::Liveness, HealthCheckLevel::Readiness]
+            .into_iter()
+            .filter(|cached| cached.covers(level))
+            .filter_map(|cached| entries.get(&(key.to_string(), cached)))
+            .filter(|entry| entry.is_fresh())
+            .max_by_key(|entry| entry.checked_at)
+            .map(|entry| entry.status.clone())
+    }
+
+    /// Whether a key has a fresh unhealthy result at any level
+    pub fn is_backing_off(&self, key: &str) -> bool {
+        self.get(key, HealthCheckLevel::Liveness)
+            .is_some_and(|status| !status.is_healthy)
+    }
+
+    /// Store a health result
+    ///
+    /// # Returns
+    /// How long the result will be reused
+    pub fn record(&self, key: &str, level: HealthCheckLevel, status: HealthStatus) -> Duration {
+        let key = (key.to_string(), level);
+        let config = self.config();
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let consecutive_failures = if status.is_healthy {
+            0
+        } else {
+            entries
+                .get(&key)
+                .map_or(0, |previous| previous.consecutive_failures)
+                .saturating_add(1)
+        };
+        let valid_for = if status.is_healthy {
+            config.ttl
+        } else {
+            let doublings = consecutive_failures.saturating_sub(1).min(16);
+            config
+                .negative_ttl
+                .saturating_mul(1 << doublings)
+                .min(config.max_negative_ttl)
+        };
+
+        entries.insert(
+            key,
+            CachedHealth {
+                status,
+                checked_at: Instant::now(),
+                valid_for,
+                consecutive_failures,
+            },
+        );
+        valid_for
+    }
+
+    /// Drop a key's cached results so the next check probes the backend
+    pub fn invalidate(&self, key: &str) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .retain(|(cached, _), _| cached != key);
+    }
+
+    /// Health of a backend, probing it only when the cached result expired
+    ///
+    /// # Arguments
+    /// * `key` - Cache key, e.g. an instance id or backend name
+    /// * `backend` - Backend probed on a cache miss
+    /// * `level` - How deep the probe goes
+    /// * `force` - Probe even if a fresh result is cached
+    ///
+    /// # Returns
+    /// Cached or freshly probed status; a failed probe counts as unhealthy
+    pub async fn check(
+        &self,
+        key: &str,
+        backend: &dyn ExecutionBackend,
+        level: HealthCheckLevel,
+        force: bool,
+    ) -> HealthStatus {
+        if !force && let Some(status) = self.get(key, level) {
+            return status;
+        }
+
+        let status = match backend.health_check(level).await {
+            Ok(status) => status,
+            Err(e) => HealthStatus::unhealthy(format!("Health check failed: {}", e)),
+        };
+        self.record(key, level, status.clone());
+        status
+    }
+
+    /// Probe a backend in the background unless a fresh result is cached
+    /// or a refresh for the same key and level is already running
+    pub fn refresh_in_background(
+        self: &Arc<Self>,
+        key: &str,
+        backend: Arc<dyn ExecutionBackend>,
+        level: HealthCheckLevel,
+    ) {
+        if self.get(key, level).is_some() {
+            return;
+        }
+        let slot = (key.to_string(), level);
+        if !self
+            .refreshing
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(slot.clone())
+        {
+            return;
+        }
+
+        let cache = Arc::clone(self);
+        AsyncTaskBuilder::new(async move {
+            cache.check(&slot.0, backend.as_ref(), level, true).await;
+            cache
+                .refreshing
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .remove(&slot);
+        })
+        .spawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIVE: HealthCheckLevel = HealthCheckLevel::Liveness;
+    const READY: HealthCheckLevel = HealthCheckLevel::Readiness;
+
+    fn cache() -> HealthCache {
+        HealthCache::new(HealthCacheConfig {
+            ttl: Duration::from_secs(60),
+            negative_ttl: Duration::from_secs(1),
+            max_negative_ttl: Duration::from_secs(3),
+        })
+    }
+
+    #[test]
+    fn unhealthy_results_back_off_exponentially() {
+        let cache = cache();
+        let down = || HealthStatus::unhealthy("down");
+
+        assert_eq!(cache.record("LandLock", LIVE, down()), Duration::from_secs(1));
+        assert_eq!(cache.record("LandLock", LIVE, down()), Duration::from_secs(2));
+        assert_eq!(cache.record("LandLock", LIVE, down()), Duration::from_secs(3));
+        assert!(cache.is_backing_off("LandLock"));
+
+        assert_eq!(
+            cache.record("LandLock", LIVE, HealthStatus::healthy("up")),
+            Duration::from_secs(60)
+        );
+        assert!(!cache.is_backing_off("LandLock"));
+        assert_eq!(cache.record("LandLock", LIVE, down()), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn invalidate_and_expiry_force_a_new_probe() {
+        let cache = cache();
+        cache.record("a", LIVE, HealthStatus::healthy("up"));
+        assert!(cache.get("a", LIVE).is_some());
+        cache.invalidate("a");
+        assert!(cache.get("a", LIVE).is_none());
+
+        cache.set_config(HealthCacheConfig {
+            ttl: Duration::ZERO,
+            ..cache.config()
+        });
+        cache.record("b", LIVE, HealthStatus::healthy("up"));
+        assert!(cache.get("b", LIVE).is_none());
+    }
+
+    #[test]
+    fn readiness_results_answer_liveness_queries() {
+        let cache = cache();
+        cache.record("a", LIVE, HealthStatus::healthy("binary present"));
+        assert!(cache.get("a", READY).is_none());
+        assert!(!cache.is_backing_off("a"));
+
+        cache.record("a", READY, HealthStatus::unhealthy("probe failed"));
+        let live = cache.get("a", LIVE).unwrap();
+        assert!(!live.is_healthy);
+        assert!(cache.is_backing_off("a"));
+
+        cache.invalidate("a");
+        assert!(cache.get("a", LIVE).is_none());
+    }
+}
@@ -0,0 +1,304 @@
+// ============================================================================
+// File: packages/cylo/src/backends/image_ref.rs
+// ----------------------------------------------------------------------------
+// Parsing of container/VM image references, including digest pins.
+//
+// A reference is `name:tag`, `name@sha256:<hex>` or `name:tag@sha256:<hex>`.
+// Tags can be moved to other content by whoever controls the registry; a
+// digest cannot, so backends verify a pinned digest before executing and
+// record the digest they actually ran in result metadata.
+// ============================================================================
+
+use std::fmt;
+
+use crate::backends::registry_auth::DEFAULT_REGISTRY;
+use crate::backends::runtime::version_matches;
+use crate::backends::{BackendError, BackendResult, ExecutionResult, language};
+
+/// Result metadata key holding the reference an execution was started from
+pub const IMAGE_REFERENCE_METADATA: &str = "image.reference";
+
+/// Result metadata key holding the digest of the image that actually ran
+pub const IMAGE_DIGEST_METADATA: &str = "image.digest";
+
+/// Repositories publishing one tag per runtime version, by language
+const VERSIONED_REPOSITORIES: &[(&str, &str)] = &[
+    ("python", "python"),
+    ("javascript", "node"),
+    ("rust", "rust"),
+    ("go", "golang"),
+    ("bash", "bash"),
+    ("r", "r-base"),
+];
+
+/// Parsed image reference
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImageReference {
+    /// Repository name, including any registry host
+    pub name: String,
+    /// Tag, e.g. "3.12-alpine"
+    pub tag: Option<String>,
+    /// Pinned content digest, e.g. "sha256:4f…"
+    pub digest: Option<String>,
+}
+
+impl ImageReference {
+    /// Parse a reference
+    ///
+    /// # Arguments
+    /// * `reference` - `name:tag`, `name@sha256:<hex>` or both combined
+    ///
+    /// # Returns
+    /// The parsed reference, or a description of what is malformed
+    pub fn parse(reference: &str) -> Result<Self, String> {
+        let (rest, digest) = match reference.split_once('@') {
+            Some((rest, digest)) => {
+                if !is_valid_digest(digest) {
+                    return Err(format!(
+                        "invalid digest '{digest}' (expected sha256: followed by 64 hex digits)"
+                    ));
+                }
+                (rest, Some(digest.to_string()))
+            }
+            None => (reference, None),
+        };
+
+        // A colon after the last slash separates the tag; earlier colons
+        // belong to a registry port
+        let last_slash = rest.rfind('/').map_or(0, |i| i + 1);
+        let (name, tag) = match rest[last_slash..].find(':') {
+            Some(i) => (&rest[..last_slash + i], Some(&rest[last_slash + i + 1..])),
+            None => (rest, None),
+        };
+
+        if !is_valid_name(name) {
+            return Err(format!("invalid image name '{name}'"));
+        }
+        if let Some(tag) = tag
+            && (tag.is_empty()
+                || !tag
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '.' || c == '-' || c == '_'))
+        {
+            return Err(format!("invalid image tag '{tag}'"));
+        }
+        if tag.is_none() && digest.is_none() {
+            return Err(format!("image '{name}' needs a tag or a digest"));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            tag: tag.map(str::to_string),
+            digest,
+        })
+    }
+
+    /// Registry host the image is pulled from
+    ///
+    /// The first path component names a registry when it looks like a host
+    /// (has a dot or port, or is "localhost"); otherwise it is Docker Hub.
+    pub fn registry(&self) -> &str {
+        match self.name.split_once('/') {
+            Some((host, _))
+                if host.contains('.') || host.contains(':') || host == "localhost" =>
+            {
+                host
+            }
+            _ => DEFAULT_REGISTRY,
+        }
+    }
+
+    /// Check that `actual` is the pinned digest, if one is pinned
+    ///
+    /// # Returns
+    /// Ok(()) when nothing is pinned or the digests match, otherwise a
+    /// message naming both digests
+    pub fn verify(&self, actual: &str) -> Result<(), String> {
+        match &self.digest {
+            Some(pinned) if !pinned.eq_ignore_ascii_case(actual) => Err(format!(
+                "digest mismatch for {}: pinned {}, found {}",
+                self.name, pinned, actual
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record in a result's metadata that it ran this reference, resolved
+    /// to `digest`
+    pub fn record(&self, result: &mut ExecutionResult, digest: String) {
+        result
+            .metadata
+            .insert(IMAGE_REFERENCE_METADATA.to_string(), self.to_string());
+        result.metadata.insert(IMAGE_DIGEST_METADATA.to_string(), digest);
+    }
+
+    /// Reference of the image running a language's pinned runtime version
+    ///
+    /// An unpinned language runs this image, as does a pinned one whose
+    /// version the tag already carries. Otherwise the runtime's own
+    /// repository is retagged with the requested version, keeping the
+    /// variant (`python:3.12-alpine` runs `python@3.11` as
+    /// `python:3.11-alpine`).
+    ///
+    /// # Returns
+    /// The reference to run, or RuntimeVersionUnavailable when the image is
+    /// not the runtime's repository or a digest pins other content
+    pub fn for_runtime(&self, language: &str, backend: &'static str) -> BackendResult<Self> {
+        let (name, Some(requested)) = language::split_version(language) else {
+            return Ok(self.clone());
+        };
+        let spec = language::resolve(name).ok_or_else(|| BackendError::UnsupportedLanguage {
+            backend,
+            language: language.to_string(),
+        })?;
+        let (installed, variant) = match self.tag.as_deref() {
+            Some(tag) => split_tag(tag),
+            None => (None, String::new()),
+        };
+        if installed.is_some_and(|installed| version_matches(installed, requested)) {
+            return Ok(self.clone());
+        }
+
+        let repository = self.name.rsplit('/').next().unwrap_or(&self.name);
+        let versioned = VERSIONED_REPOSITORIES
+            .iter()
+            .any(|&(language, published)| language == spec.name && published == repository);
+        if !versioned || self.digest.is_some() {
+            return Err(BackendError::RuntimeVersionUnavailable {
+                backend,
+                language: spec.name.to_string(),
+                requested: requested.to_string(),
+                installed: installed.map(str::to_string).into_iter().collect(),
+            });
+        }
+        Ok(Self {
+            name: self.name.clone(),
+            tag: Some(format!("{requested}{variant}")),
+            digest: None,
+        })
+    }
+}
+
+/// Split a tag into its leading version and the variant after it
+///
+/// `3.12-alpine` splits into `3.12` and `-alpine`; a tag without a version
+/// is all variant (`alpine` becomes `-alpine`, `latest` nothing).
+fn split_tag(tag: &str) -> (Option<&str>, String) {
+    let end = tag
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(tag.len());
+    let version = tag[..end].trim_end_matches('.');
+    if version.is_empty() {
+        let variant = match tag {
+            "latest" => String::new(),
+            _ => format!("-{tag}"),
+        };
+        return (None, variant);
+    }
+    (Some(version), tag[version.len()..].to_string())
+}
+
+impl fmt::Display for ImageReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{tag}")?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@{digest}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check a digest of the form `sha256:<64 hex digits>`
+pub(crate) fn is_valid_digest(digest: &str) -> bool {
+    digest
+        .strip_prefix("sha256:")
+        .is_some_and(|hex| hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let (registry, path) = match name.split_once('/') {
+        Some((registry, path)) if registry.contains(':') => (Some(registry), path),
+        _ => (None, name),
+    };
+    if let Some(registry) = registry {
+        let (host, port) = registry.split_once(':').unwrap_or((registry, ""));
+        if host.is_empty() || port.is_empty() || !port.chars().all(|c| c.is_ascii_digit()) {
+            return false;
+        }
+    }
+    !path.is_empty()
+        && path
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '/' || c == '-' || c == '_' || c == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "sha256:4f7c1a36c5a1c1b0e3d1c65d14b0e5ad2b8b87e8a1c7a0e6f2d3b4c5d6e7f8a9";
+
+    #[test]
+    fn references_parse_with_tags_and_digests() {
+        let tagged = ImageReference::parse("python:3.12").expect("tagged");
+        assert_eq!((tagged.name.as_str(), tagged.tag.as_deref()), ("python", Some("3.12")));
+        assert_eq!(tagged.digest, None);
+
+        let pinned = ImageReference::parse(&format!("python@{DIGEST}")).expect("pinned");
+        assert_eq!((pinned.tag, pinned.digest.as_deref()), (None, Some(DIGEST)));
+
+        let both = format!("localhost:5000/team/python:3.12@{DIGEST}");
+        let parsed = ImageReference::parse(&both).expect("registry with port");
+        assert_eq!(parsed.name, "localhost:5000/team/python");
+        assert_eq!(parsed.to_string(), both);
+        assert_eq!(parsed.registry(), "localhost:5000");
+        assert_eq!(tagged.registry(), "docker.io");
+        assert_eq!(ImageReference::parse("ghcr.io/org/app:1").expect("ghcr").registry(), "ghcr.io");
+        assert_eq!(ImageReference::parse("library/python:3").expect("hub").registry(), "docker.io");
+
+        for invalid in ["python", "", ":tag", "image:", "image:tag:extra", "python@sha256:abc"] {
+            assert!(ImageReference::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn pinned_digest_must_match() {
+        let pinned = ImageReference::parse(&format!("python@{DIGEST}")).expect("pinned");
+        assert!(pinned.verify(&DIGEST.to_uppercase().replace("SHA256", "sha256")).is_ok());
+        let other = format!("sha256:{}", "0".repeat(64));
+        assert!(pinned.verify(&other).unwrap_err().contains("digest mismatch"));
+
+        let tagged = ImageReference::parse("python:3.12").expect("tagged");
+        assert!(tagged.verify(&other).is_ok());
+    }
+
+    #[test]
+    fn pinned_runtimes_run_a_version_tagged_image() {
+        let image = ImageReference::parse("python:3.12-alpine").expect("tagged");
+        let run = |image: &ImageReference, language| {
+            image.for_runtime(language, "Docker").map(|reference| reference.to_string())
+        };
+
+        assert_eq!(run(&image, "python").expect("unpinned"), "python:3.12-alpine");
+        assert_eq!(run(&image, "python@3.12").expect("matching"), "python:3.12-alpine");
+        assert_eq!(run(&image, "py@3.11").expect("retagged"), "python:3.11-alpine");
+        let hub = ImageReference::parse("docker.io/library/node:latest").expect("node");
+        assert_eq!(run(&hub, "js@20").expect("latest"), "docker.io/library/node:20");
+
+        // Another runtime's image, or pinned content, cannot be retagged
+        for unavailable in [
+            ImageReference::parse("ubuntu:22.04").expect("ubuntu"),
+            ImageReference::parse(&format!("python:3.12@{DIGEST}")).expect("pinned"),
+        ] {
+            match unavailable.for_runtime("python@3.11", "Docker") {
+                Err(BackendError::RuntimeVersionUnavailable { requested, .. }) => {
+                    assert_eq!(requested, "3.11");
+                }
+                other => panic!("expected an unavailable version, got {other:?}"),
+            }
+        }
+    }
+}
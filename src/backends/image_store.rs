@@ -0,0 +1,513 @@
+// ============================================================================
+// File: packages/cylo/src/backends/image_store.rs
+// ----------------------------------------------------------------------------
+// Offline image store for air-gapped hosts.
+//
+// OCI image tarballs are imported into a local content-addressed store laid
+// out as an OCI image layout (oci-layout, index.json, blobs/sha256/<hex>).
+// Container backends load images from it instead of pulling, and VM
+// backends boot rootfs artifacts stored in it, so no registry is contacted.
+// ============================================================================
+
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::backends::ImageReference;
+use crate::backends::image_ref::is_valid_digest;
+use crate::execution_env::{CyloError, CyloResult};
+use crate::hardening::{Helper, HelperCommand};
+
+/// Backend config key naming the image store directory
+pub const IMAGE_STORE_KEY: &str = "image_store";
+
+/// Media type of a layer holding a bootable ext4 rootfs for VM backends
+pub const ROOTFS_MEDIA_TYPE: &str = "application/vnd.cylo.rootfs.ext4";
+
+/// Annotation holding the full image name (containerd, nerdctl, buildkit)
+const CONTAINERD_NAME_ANNOTATION: &str = "io.containerd.image.name";
+
+/// Annotation holding the reference name per the OCI image layout spec
+const OCI_REF_ANNOTATION: &str = "org.opencontainers.image.ref.name";
+
+/// One manifest entry of an OCI `index.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    media_type: String,
+    digest: String,
+    size: u64,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    annotations: serde_json::Map<String, Value>,
+    /// Fields kept verbatim, such as `platform`
+    #[serde(flatten)]
+    extra: serde_json::Map<String, Value>,
+}
+
+impl Descriptor {
+    fn reference(&self) -> Option<&str> {
+        [CONTAINERD_NAME_ANNOTATION, OCI_REF_ANNOTATION]
+            .iter()
+            .find_map(|key| self.annotations.get(*key).and_then(Value::as_str))
+    }
+}
+
+/// OCI `index.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Index {
+    schema_version: u32,
+    #[serde(default)]
+    manifests: Vec<Descriptor>,
+}
+
+impl Default for Index {
+    fn default() -> Self {
+        Self {
+            schema_version: 2,
+            manifests: Vec::new(),
+        }
+    }
+}
+
+/// An image held by the store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredImage {
+    /// Reference the image was imported under
+    pub reference: String,
+    /// Digest of its manifest (or image index)
+    pub digest: String,
+}
+
+/// Content store of imported OCI images
+#[derive(Debug, Clone)]
+pub struct ImageStore {
+    root: PathBuf,
+}
+
+impl ImageStore {
+    /// Open a store rooted at `root`; it is created on first import
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Store configured for a backend through `IMAGE_STORE_KEY`, if any
+    pub fn from_backend_config(config: &crate::backends::BackendConfig) -> Option<Self> {
+        config.backend_specific.get(IMAGE_STORE_KEY).map(Self::new)
+    }
+
+    /// Default store location: `$XDG_DATA_HOME/cylo/images`, falling back
+    /// to `~/.local/share/cylo/images`
+    pub fn default_root() -> PathBuf {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+            .unwrap_or_else(std::env::temp_dir)
+            .join("cylo")
+            .join("images")
+    }
+
+    /// Store root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Import every image of an OCI image tarball
+    ///
+    /// Each blob is checked against its content digest before it enters
+    /// the store. Images already imported under the same reference are
+    /// replaced.
+    ///
+    /// # Arguments
+    /// * `tarball` - OCI image layout archive (e.g. from `skopeo copy
+    ///   oci-archive:` or `docker buildx build --output type=oci`)
+    ///
+    /// # Returns
+    /// The images that were imported
+    pub fn import(&self, tarball: &Path) -> CyloResult<Vec<StoredImage>> {
+        let staging = tempfile::tempdir()
+            .map_err(|e| CyloError::internal(format!("Failed to create staging dir: {e}")))?;
+        let status = Command::new("tar")
+            .arg("-xf")
+            .arg(tarball)
+            .arg("-C")
+            .arg(staging.path())
+            .arg("--no-same-owner")
+            .helper_status(Helper::FileTool)
+            .map_err(|e| CyloError::internal(format!("Failed to run tar: {e}")))?;
+        if !status.success() {
+            return Err(CyloError::validation(format!(
+                "Failed to extract {}: tar exited with {status}",
+                tarball.display()
+            )));
+        }
+        if !staging.path().join("oci-layout").is_file() {
+            return Err(CyloError::validation(format!(
+                "{} is not an OCI image archive (no oci-layout file)",
+                tarball.display()
+            )));
+        }
+        let imported: Index = read_json(&staging.path().join("index.json"))?;
+
+        let blobs = self.blobs_dir();
+        fs::create_dir_all(&blobs).map_err(|e| store_error(&blobs, e))?;
+        let staged_blobs = staging.path().join("blobs").join("sha256");
+        for entry in fs::read_dir(&staged_blobs).map_err(|e| store_error(&staged_blobs, e))? {
+            let path = entry.map_err(|e| store_error(&staged_blobs, e))?.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            let actual = sha256_file(&path).map_err(|e| store_error(&path, e))?;
+            if actual != format!("sha256:{name}") {
+                return Err(CyloError::validation(format!(
+                    "Blob {name} in {} is corrupt: content hashes to {actual}",
+                    tarball.display()
+                )));
+            }
+            let target = blobs.join(name);
+            if !target.exists() {
+                fs::copy(&path, &target).map_err(|e| store_error(&target, e))?;
+            }
+        }
+
+        let mut index = self.index()?;
+        let mut stored = Vec::new();
+        for manifest in imported.manifests {
+            let Some(reference) = manifest.reference().map(str::to_string) else {
+                continue;
+            };
+            if !is_valid_digest(&manifest.digest) || !self.blob(&manifest.digest).is_file() {
+                return Err(CyloError::validation(format!(
+                    "{} references missing manifest {}",
+                    tarball.display(),
+                    manifest.digest
+                )));
+            }
+            index.manifests.retain(|existing| existing.reference() != Some(reference.as_str()));
+            stored.push(StoredImage {
+                reference,
+                digest: manifest.digest.clone(),
+            });
+            index.manifests.push(manifest);
+        }
+        if stored.is_empty() {
+            return Err(CyloError::validation(format!(
+                "{} names no images (manifests carry no {} annotation)",
+                tarball.display(),
+                OCI_REF_ANNOTATION
+            )));
+        }
+
+        fs::write(self.root.join("oci-layout"), r#"{"imageLayoutVersion":"1.0.0"}"#)
+            .map_err(|e| store_error(&self.root, e))?;
+        write_json(&self.root.join("index.json"), &index)?;
+        Ok(stored)
+    }
+
+    /// Images in the store
+    pub fn list(&self) -> CyloResult<Vec<StoredImage>> {
+        Ok(self
+            .index()?
+            .manifests
+            .iter()
+            .filter_map(|manifest| {
+                Some(StoredImage {
+                    reference: manifest.reference()?.to_string(),
+                    digest: manifest.digest.clone(),
+                })
+            })
+            .collect())
+    }
+
+    /// Find an image by reference
+    ///
+    /// A pinned digest matches regardless of name; otherwise name and tag
+    /// must match, treating `python:3` and `docker.io/library/python:3`
+    /// as the same image.
+    pub fn lookup(&self, reference: &ImageReference) -> Option<StoredImage> {
+        self.list().ok()?.into_iter().find(|stored| {
+            if let Some(digest) = &reference.digest {
+                return stored.digest.eq_ignore_ascii_case(digest);
+            }
+            ImageReference::parse(&stored.reference).is_ok_and(|candidate| {
+                canonical_name(&candidate.name) == canonical_name(&reference.name)
+                    && candidate.tag == reference.tag
+            })
+        })
+    }
+
+    /// Rootfs artifact of a stored image, for VM backends
+    ///
+    /// # Returns
+    /// Path of the first layer with `ROOTFS_MEDIA_TYPE`, if the image has one
+    pub fn rootfs(&self, image: &StoredImage) -> Option<PathBuf> {
+        let manifest: Value = read_json(&self.blob(&image.digest)).ok()?;
+        manifest
+            .get("layers")?
+            .as_array()?
+            .iter()
+            .find(|layer| layer.get("mediaType").and_then(Value::as_str) == Some(ROOTFS_MEDIA_TYPE))
+            .and_then(|layer| layer.get("digest")?.as_str())
+            .map(|digest| self.blob(digest))
+            .filter(|path| path.is_file())
+    }
+
+    /// Write a single image as an OCI archive that container runtimes load
+    ///
+    /// # Arguments
+    /// * `image` - Image to export
+    /// * `archive` - Path of the tarball to create
+    pub fn export(&self, image: &StoredImage, archive: &Path) -> CyloResult<()> {
+        let layout = tempfile::tempdir()
+            .map_err(|e| CyloError::internal(format!("Failed to create export dir: {e}")))?;
+        let blobs = layout.path().join("blobs").join("sha256");
+        fs::create_dir_all(&blobs).map_err(|e| store_error(&blobs, e))?;
+
+        for digest in self.referenced_blobs(&image.digest)? {
+            let source = self.blob(&digest);
+            let target = blobs.join(digest.trim_start_matches("sha256:"));
+            if fs::hard_link(&source, &target).is_err() {
+                fs::copy(&source, &target).map_err(|e| store_error(&target, e))?;
+            }
+        }
+
+        let manifest = self
+            .index()?
+            .manifests
+            .into_iter()
+            .find(|manifest| manifest.digest == image.digest)
+            .ok_or_else(|| CyloError::validation(format!("{} is not stored", image.reference)))?;
+        let index = Index {
+            manifests: vec![manifest],
+            ..Index::default()
+        };
+        fs::write(layout.path().join("oci-layout"), r#"{"imageLayoutVersion":"1.0.0"}"#)
+            .map_err(|e| store_error(layout.path(), e))?;
+        write_json(&layout.path().join("index.json"), &index)?;
+
+        let status = Command::new("tar")
+            .arg("-cf")
+            .arg(archive)
+            .arg("-C")
+            .arg(layout.path())
+            .arg(".")
+            .helper_status(Helper::FileTool)
+            .map_err(|e| CyloError::internal(format!("Failed to run tar: {e}")))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(CyloError::internal(format!(
+                "Failed to write {}: tar exited with {status}",
+                archive.display()
+            )))
+        }
+    }
+
+    /// Digests of a manifest and every blob it references, transitively
+    /// through image indexes
+    fn referenced_blobs(&self, digest: &str) -> CyloResult<BTreeSet<String>> {
+        let mut seen = BTreeSet::new();
+        let mut pending = vec![digest.to_string()];
+        while let Some(digest) = pending.pop() {
+            if !seen.insert(digest.clone()) {
+                continue;
+            }
+            let path = self.blob(&digest);
+            if !path.is_file() {
+                return Err(CyloError::validation(format!(
+                    "Blob {digest} is missing from the store"
+                )));
+            }
+            // Only manifests and indexes are JSON documents worth walking
+            let Ok(document) = read_json::<Value>(&path) else {
+                continue;
+            };
+            let children = ["config", "layers", "manifests"]
+                .iter()
+                .filter_map(|key| document.get(*key))
+                .flat_map(|value| match value {
+                    Value::Array(items) => items.iter().collect::<Vec<_>>(),
+                    other => vec![other],
+                })
+                .filter_map(|descriptor| descriptor.get("digest")?.as_str())
+                .filter(|digest| is_valid_digest(digest))
+                .map(str::to_string);
+            pending.extend(children);
+        }
+        Ok(seen)
+    }
+
+    fn index(&self) -> CyloResult<Index> {
+        let path = self.root.join("index.json");
+        if path.is_file() {
+            read_json(&path)
+        } else {
+            Ok(Index::default())
+        }
+    }
+
+    fn blobs_dir(&self) -> PathBuf {
+        self.root.join("blobs").join("sha256")
+    }
+
+    fn blob(&self, digest: &str) -> PathBuf {
+        self.blobs_dir().join(digest.trim_start_matches("sha256:"))
+    }
+}
+
+/// Compute the `sha256:` digest of a file's content
+pub(crate) fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    let hex: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    Ok(format!("sha256:{hex}"))
+}
+
+/// Image name with Docker Hub's implicit registry and namespace removed
+fn canonical_name(name: &str) -> &str {
+    let name = name.strip_prefix("docker.io/").unwrap_or(name);
+    name.strip_prefix("library/").unwrap_or(name)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> CyloResult<T> {
+    let content = fs::read(path).map_err(|e| store_error(path, e))?;
+    serde_json::from_slice(&content)
+        .map_err(|e| CyloError::validation(format!("Invalid {}: {e}", path.display())))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> CyloResult<()> {
+    let content = serde_json::to_vec_pretty(value)
+        .map_err(|e| CyloError::internal(format!("Failed to encode {}: {e}", path.display())))?;
+    fs::write(path, content).map_err(|e| store_error(path, e))
+}
+
+fn store_error(path: &Path, e: io::Error) -> CyloError {
+    CyloError::internal(format!("Image store I/O on {} failed: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest_of(content: &[u8]) -> String {
+        let hex: String = Sha256::digest(content)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        format!("sha256:{hex}")
+    }
+
+    /// Build an OCI archive holding one image with a rootfs layer
+    fn oci_archive(dir: &Path, name: &str) -> (PathBuf, String) {
+        let layout = dir.join("layout");
+        let blobs = layout.join("blobs").join("sha256");
+        fs::create_dir_all(&blobs).unwrap();
+        let put = |content: &[u8]| {
+            let digest = digest_of(content);
+            fs::write(blobs.join(digest.trim_start_matches("sha256:")), content).unwrap();
+            (digest, content.len())
+        };
+
+        let (rootfs, rootfs_size) = put(b"ext4 image bytes");
+        let (config, config_size) = put(b"{}");
+        let manifest = serde_json::json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "config": { "mediaType": "application/vnd.oci.image.config.v1+json",
+                        "digest": config, "size": config_size },
+            "layers": [{ "mediaType": ROOTFS_MEDIA_TYPE, "digest": rootfs, "size": rootfs_size }]
+        });
+        let manifest_bytes = serde_json::to_vec(&manifest).unwrap();
+        let (manifest_digest, manifest_size) = put(&manifest_bytes);
+
+        let index = serde_json::json!({
+            "schemaVersion": 2,
+            "manifests": [{
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": manifest_digest,
+                "size": manifest_size,
+                "annotations": { OCI_REF_ANNOTATION: name }
+            }]
+        });
+        fs::write(layout.join("index.json"), serde_json::to_vec(&index).unwrap()).unwrap();
+        fs::write(layout.join("oci-layout"), r#"{"imageLayoutVersion":"1.0.0"}"#).unwrap();
+
+        let archive = dir.join("image.tar");
+        let status = Command::new("tar")
+            .arg("-cf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&layout)
+            .arg(".")
+            .helper_status(Helper::FileTool)
+            .unwrap();
+        assert!(status.success());
+        (archive, manifest_digest)
+    }
+
+    #[test]
+    fn imported_images_are_found_by_tag_and_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let (archive, digest) = oci_archive(dir.path(), "docker.io/library/python:3.12");
+        let store = ImageStore::new(dir.path().join("store"));
+
+        let imported = store.import(&archive).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].digest, digest);
+
+        let by_tag = store.lookup(&ImageReference::parse("python:3.12").unwrap()).unwrap();
+        assert_eq!(by_tag.digest, digest);
+        let pinned = ImageReference::parse(&format!("python@{digest}")).unwrap();
+        assert_eq!(store.lookup(&pinned), Some(by_tag.clone()));
+        assert!(store.lookup(&ImageReference::parse("python:3.11").unwrap()).is_none());
+
+        let rootfs = store.rootfs(&by_tag).unwrap();
+        assert_eq!(fs::read(rootfs).unwrap(), b"ext4 image bytes");
+
+        let exported = dir.path().join("export.tar");
+        store.export(&by_tag, &exported).unwrap();
+        let reimported = ImageStore::new(dir.path().join("copy")).import(&exported).unwrap();
+        assert_eq!(reimported, imported);
+    }
+
+    #[test]
+    fn corrupt_blobs_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = dir.path().join("layout");
+        let blobs = layout.join("blobs").join("sha256");
+        fs::create_dir_all(&blobs).unwrap();
+        fs::write(blobs.join("0".repeat(64)), b"not what the name says").unwrap();
+        fs::write(layout.join("index.json"), r#"{"schemaVersion":2,"manifests":[]}"#).unwrap();
+        fs::write(layout.join("oci-layout"), "{}").unwrap();
+        let archive = dir.path().join("bad.tar");
+        Command::new("tar")
+            .arg("-cf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&layout)
+            .arg(".")
+            .helper_status(Helper::FileTool)
+            .unwrap();
+
+        let err = ImageStore::new(dir.path().join("store")).import(&archive).unwrap_err();
+        assert!(err.to_string().contains("corrupt"), "{err}");
+    }
+}
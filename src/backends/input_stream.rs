@@ -0,0 +1,180 @@
+// ============================================================================
+// File: packages/cylo/src/backends/input_stream.rs
+// ----------------------------------------------------------------------------
+// Stdin streamed into an execution from an async reader.
+//
+// `ExecutionRequest::input` holds the whole payload in memory, which does
+// not scale to hundreds of megabytes piped into a script. An input stream
+// is instead copied into the program's stdin as the program reads it, so
+// only a copy buffer is held at a time and a slow reader applies
+// backpressure to the source. Backends that hand input to their guest in
+// one piece refuse streamed input instead of buffering it. A request that
+// carries a transcript recorder has each chunk recorded as it is copied.
+// ============================================================================
+
+use std::fmt;
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::task::JoinHandle;
+
+use crate::backends::transcript::take_complete;
+use crate::backends::{BackendError, BackendResult, ExecutionRequest, TranscriptStream};
+
+type Reader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Bytes copied into stdin per read
+const COPY_CHUNK: usize = 64 * 1024;
+
+/// Source of an execution's stdin, read as the program consumes it
+///
+/// Clones share the reader, which only one execution can consume.
+#[derive(Clone)]
+pub struct InputStream {
+    reader: Arc<Mutex<Option<Reader>>>,
+}
+
+impl InputStream {
+    /// Stream stdin from `reader`; the program sees end of input once the
+    /// reader is exhausted
+    pub fn new<R: AsyncRead + Send + Unpin + 'static>(reader: R) -> Self {
+        Self {
+            reader: Arc::new(Mutex::new(Some(Box::new(reader)))),
+        }
+    }
+
+    /// Whether an execution has already taken the reader
+    pub fn is_consumed(&self) -> bool {
+        self.reader
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_none()
+    }
+
+    fn take(&self) -> Option<Reader> {
+        self.reader
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+    }
+}
+
+impl fmt::Debug for InputStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InputStream")
+            .field("consumed", &self.is_consumed())
+            .finish()
+    }
+}
+
+/// Refuse a streamed input on a backend that cannot pipe it to its guest
+pub fn reject_unpiped(request: &ExecutionRequest, backend: &'static str) -> BackendResult<()> {
+    match &request.input_stream {
+        Some(_) => Err(BackendError::NotAvailable {
+            backend,
+            reason: "stdin cannot be streamed into this backend".to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Copy running from an input stream into a child's stdin; dropping it
+/// stops the copy
+#[derive(Debug)]
+pub(crate) struct InputPump(JoinHandle<()>);
+
+impl Drop for InputPump {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Start copying the request's input stream into the child's stdin
+///
+/// Must be called within the runtime. The child's stdin is closed once
+/// the stream ends. Chunks are recorded in the request's transcript, if
+/// it has one, once the child has accepted them.
+///
+/// # Returns
+/// The running copy, None when the request streams no input, or
+/// ProcessFailed when its stream was already consumed
+pub(crate) fn pipe(
+    child: &mut Child,
+    request: &ExecutionRequest,
+) -> BackendResult<Option<InputPump>> {
+    let Some(stream) = &request.input_stream else {
+        return Ok(None);
+    };
+    let mut reader = stream.take().ok_or_else(|| BackendError::ProcessFailed {
+        details: "input stream was already consumed by another execution".to_string(),
+    })?;
+    let Some(stdin) = child.stdin.take() else {
+        return Ok(None);
+    };
+    let mut stdin =
+        tokio::process::ChildStdin::from_std(stdin).map_err(|e| BackendError::ProcessFailed {
+            details: format!("Failed to stream stdin: {e}"),
+        })?;
+
+    let transcript = request.transcript.clone();
+
+    Ok(Some(InputPump(tokio::spawn(async move {
+        // A program may exit without reading all of its input, which
+        // closes the pipe; that is not an error
+        let mut chunk = vec![0; COPY_CHUNK];
+        let mut pending = Vec::new();
+        while let Ok(read @ 1..) = reader.read(&mut chunk).await {
+            if stdin.write_all(&chunk[..read]).await.is_err() {
+                break;
+            }
+            if let Some(transcript) = &transcript {
+                pending.extend_from_slice(&chunk[..read]);
+                transcript.record(TranscriptStream::Input, take_complete(&mut pending));
+            }
+        }
+        if let Some(transcript) = &transcript {
+            transcript.record(TranscriptStream::Input, String::from_utf8_lossy(&pending));
+        }
+        let _ = stdin.shutdown().await;
+    }))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stream_is_copied_into_stdin_once() {
+        let payload = vec![b'x'; 4 * 1024 * 1024];
+        let stream = InputStream::new(std::io::Cursor::new(payload));
+        let transcript = crate::backends::TranscriptRecorder::default();
+        let mut request =
+            ExecutionRequest::new("wc -c", "bash").with_transcript(transcript.clone());
+        request.input_stream = Some(stream.clone());
+
+        let mut child = Command::new("wc")
+            .arg("-c")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let pump = pipe(&mut child, &request).unwrap();
+        assert!(pump.is_some());
+        assert!(stream.is_consumed());
+        let output = tokio::task::spawn_blocking(move || child.wait_with_output())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "4194304");
+        let recorded: usize = transcript.transcript().events.iter().map(|e| e.data.len()).sum();
+        assert_eq!(recorded, 4 * 1024 * 1024);
+
+        let mut again = Command::new("true").stdin(Stdio::piped()).spawn().unwrap();
+        assert!(pipe(&mut again, &request).is_err());
+        let _ = again.wait();
+        assert!(reject_unpiped(&request, "FireCracker").is_err());
+    }
+}
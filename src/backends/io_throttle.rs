@@ -0,0 +1,88 @@
+// ============================================================================
+// File: packages/cylo/src/backends/io_throttle.rs
+// ----------------------------------------------------------------------------
+// Disk I/O bandwidth throttling of executions.
+//
+// Code that streams to or from disk as fast as it can starves every other
+// tenant of the host's storage. `ResourceLimits::max_read_bps` and
+// `max_write_bps` cap it: on Linux through the io controller of the
+// execution's cgroup, in FireCracker through the VM's drive rate limiters
+// and on Windows through the Job Object's I/O rate control. Limiters that
+// cannot tell reads from writes apart get the lower of the two. Backends
+// that cannot throttle refuse the request rather than run it unthrottled.
+// ============================================================================
+
+use crate::backends::{BackendError, BackendResult, ExecutionRequest, ResourceLimits};
+
+/// Bandwidth for limiters covering reads and writes together
+pub fn combined_bps(limits: &ResourceLimits) -> Option<u64> {
+    match (limits.max_read_bps, limits.max_write_bps) {
+        (Some(read), Some(write)) => Some(read.min(write)),
+        (read, write) => read.or(write),
+    }
+}
+
+/// Refuse throttling on a backend that cannot throttle its executions
+pub fn reject_unthrottled(request: &ExecutionRequest, backend: &'static str) -> BackendResult<()> {
+    if request.limits.throttles_io() {
+        return Err(BackendError::NotAvailable {
+            backend,
+            reason: "disk I/O bandwidth cannot be throttled".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// `io.max` lines capping every disk of the host
+///
+/// Partitions and memory-backed devices are left out: the io controller
+/// throttles whole disks, and RAM disks cost no disk bandwidth.
+#[cfg(target_os = "linux")]
+pub(crate) fn io_max_lines(limits: &ResourceLimits) -> Vec<String> {
+    let bps = |limit: Option<u64>| limit.map_or_else(|| "max".to_string(), |bps| bps.to_string());
+    let (rbps, wbps) = (bps(limits.max_read_bps), bps(limits.max_write_bps));
+    let Ok(disks) = std::fs::read_dir("/sys/block") else {
+        return Vec::new();
+    };
+    let mut lines: Vec<String> = disks
+        .flatten()
+        .filter(|disk| {
+            let name = disk.file_name();
+            let name = name.to_string_lossy();
+            !["loop", "ram", "zram"].iter().any(|prefix| name.starts_with(prefix))
+        })
+        .filter_map(|disk| std::fs::read_to_string(disk.path().join("dev")).ok())
+        .map(|dev| format!("{} rbps={rbps} wbps={wbps}", dev.trim()))
+        .collect();
+    lines.sort();
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttles_combine_to_the_lower_bandwidth() {
+        let limits = |read: Option<u64>, write: Option<u64>| ResourceLimits {
+            max_read_bps: read,
+            max_write_bps: write,
+            ..ResourceLimits::default()
+        };
+        assert_eq!(combined_bps(&limits(None, None)), None);
+        assert_eq!(combined_bps(&limits(Some(10), None)), Some(10));
+        assert_eq!(combined_bps(&limits(None, Some(20))), Some(20));
+        assert_eq!(combined_bps(&limits(Some(30), Some(20))), Some(20));
+
+        let throttled =
+            ExecutionRequest::new("print(1)", "python").with_limits(limits(Some(1024), None));
+        assert!(reject_unthrottled(&throttled, "Apple").is_err());
+        let free = ExecutionRequest::new("print(1)", "python");
+        assert!(reject_unthrottled(&free, "Apple").is_ok());
+
+        #[cfg(target_os = "linux")]
+        for line in io_max_lines(&limits(Some(1024), None)) {
+            assert!(line.ends_with(" rbps=1024 wbps=max"), "{line}");
+        }
+    }
+}
@@ -0,0 +1,893 @@
+// ============================================================================
+// File: packages/cylo/src/backends/kubernetes/execution.rs
+// ----------------------------------------------------------------------------
+// Job execution logic for the Kubernetes backend.
+//
+// kubectl creates the execution's objects, then the host polls until the
+// pod has started, follows its logs until the code exits and reads the exit
+// code from the pod's status. The timeout starts once the pod runs, so
+// scheduling and the image pull are bounded by the pull budget instead.
+// ============================================================================
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Mutex, OnceLock};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+
+use crate::AsyncTaskBuilder;
+use crate::backends::paths::exposed_paths;
+use crate::backends::process::{self, CapturedOutput, OutputCapture, WaitOutcome};
+use crate::backends::provisioning::StageBudget;
+use crate::backends::{
+    ARCH_METADATA, AsyncTask, BackendError, BackendResult, CLOCK_METADATA, CORE_DUMP_METADATA,
+    CompilerOptions, CrashReport, ExecutionOutcome, ExecutionRequest,
+    ExecutionResult, IMAGE_DIGEST_METADATA, IsolationLevel, ProvisioningLimits,
+    ProvisioningStage, SIGNAL_METADATA, SecurityReport, SqlEngine, SqlOptions, WATCHDOG_METADATA,
+};
+use crate::backends::{clock, compile_phase, compiler, language, r_library, sql};
+use crate::hardening::{Helper, HelperCommand};
+use crate::ids;
+
+use super::{K8S_NETWORK_POLICY_METADATA, K8S_TERMINATION_REASON_METADATA};
+use super::manifest::{
+    self, EXECUTION_LABEL, INPUT_FILE, JobTemplate, MAIN_CONTAINER, execution_selector,
+};
+
+/// Longest a single kubectl call may take
+const KUBECTL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between pod status polls
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long the exit code may take to show in the pod status once the log
+/// stream has ended
+const EXIT_STATUS_WAIT: Duration = Duration::from_secs(30);
+
+/// Kinds of the objects created for an execution
+const EXECUTION_KINDS: &str = "jobs,configmaps,networkpolicies";
+
+/// How long cleanup leaves a finished job, or objects without a job, to
+/// the execution still reading its logs or still creating the job
+const CLEANUP_GRACE: Duration = Duration::from_secs(300);
+
+/// Network plugins known to enforce NetworkPolicies, as named by their
+/// DaemonSets, containers or images
+const POLICY_ENFORCING_PLUGINS: [&str; 9] = [
+    "calico",
+    "cilium",
+    "antrea",
+    "kube-router",
+    "weave-net",
+    "canal",
+    "anetd",
+    "aws-network-policy-agent",
+    "kube-network-policies",
+];
+
+/// Waiting reasons of a container that will not start without intervention
+const START_FAILURES: [&str; 5] = [
+    "ErrImagePull",
+    "ImagePullBackOff",
+    "InvalidImageName",
+    "CreateContainerConfigError",
+    "CreateContainerError",
+];
+
+/// Cluster, context and namespace every kubectl call targets
+#[derive(Debug, Clone)]
+pub(super) struct Kubectl {
+    /// kubectl program to run
+    pub program: String,
+    /// kubeconfig context; the current one when unset
+    pub context: Option<String>,
+    /// Namespace the jobs run in
+    pub namespace: String,
+}
+
+impl Kubectl {
+    /// kubectl command targeting the context and namespace
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        if let Some(context) = &self.context {
+            cmd.args(["--context", context]);
+        }
+        cmd.args(["--namespace", &self.namespace]);
+        cmd
+    }
+
+    /// Run kubectl to completion
+    ///
+    /// # Arguments
+    /// * `args` - Subcommand and its arguments
+    /// * `stdin` - Bytes written to kubectl's stdin, e.g. a manifest
+    ///
+    /// # Returns
+    /// kubectl's stdout, or ProcessFailed with its stderr when it fails
+    pub(super) async fn output(
+        &self,
+        args: &[&str],
+        stdin: Option<Vec<u8>>,
+    ) -> BackendResult<String> {
+        let mut cmd = tokio::process::Command::from(self.command());
+        cmd.args(args)
+            .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = cmd
+            .helper_spawn(Helper::ContainerCli)
+            .map_err(|e| BackendError::ProcessFailed {
+                details: format!("Failed to run {} (is it installed?): {e}", self.program),
+            })?;
+        if let Some(bytes) = stdin
+            && let Some(mut pipe) = child.stdin.take()
+        {
+            pipe.write_all(&bytes)
+                .await
+                .map_err(|e| BackendError::ProcessFailed {
+                    details: format!("Failed to write to kubectl stdin: {e}"),
+                })?;
+        }
+
+        let output = tokio::time::timeout(KUBECTL_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| BackendError::ProcessFailed {
+                details: format!("kubectl {} did not finish within {KUBECTL_TIMEOUT:?}", args[0]),
+            })?
+            .map_err(|e| BackendError::ProcessFailed {
+                details: format!("kubectl {} failed: {e}", args[0]),
+            })?;
+        if !output.status.success() {
+            // Some subcommands, such as `auth can-i`, answer on stdout only
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = match stderr.trim() {
+                "" => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+                stderr => stderr.to_string(),
+            };
+            return Err(BackendError::ProcessFailed {
+                details: format!("kubectl {} failed: {reason}", args[0]),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Current status of an execution's pod
+    async fn pod_status(&self, name: &str) -> BackendResult<PodStatus> {
+        let selector = execution_selector(name);
+        let pods = self
+            .output(&["get", "pods", "--selector", &selector, "--output", "json"], None)
+            .await?;
+        let pods: Value = serde_json::from_str(&pods).map_err(|e| BackendError::ProcessFailed {
+            details: format!("Unreadable pod status: {e}"),
+        })?;
+        Ok(PodStatus::parse(&pods))
+    }
+
+    /// Network plugin of the cluster enforcing NetworkPolicies, if any
+    ///
+    /// Looked up among the cluster's DaemonSets and remembered per
+    /// context once they could be listed; a plugin this backend does not
+    /// know, or DaemonSets it may not list, count as no enforcement.
+    async fn network_policy_enforcer(&self) -> Option<String> {
+        type Enforcers = Mutex<HashMap<(String, Option<String>), Option<String>>>;
+        static ENFORCERS: OnceLock<Enforcers> = OnceLock::new();
+        let key = (self.program.clone(), self.context.clone());
+        let enforcers = ENFORCERS.get_or_init(Default::default);
+        if let Some(known) = enforcers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&key)
+        {
+            return known.clone();
+        }
+
+        let listed = self
+            .output(&["get", "daemonsets", "--all-namespaces", "--output", "json"], None)
+            .await
+            .ok()?;
+        let enforcer = serde_json::from_str(&listed).ok().and_then(|list| policy_enforcer(&list));
+        enforcers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(key, enforcer.clone());
+        enforcer
+    }
+
+    /// Delete an execution's job without waiting; its pod gets SIGTERM,
+    /// then SIGKILL once its termination grace period is over
+    fn delete_job(&self, name: &str) {
+        let _ = self
+            .command()
+            .args(["delete", "job", name, "--wait=false", "--ignore-not-found"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .helper_status(Helper::ContainerCli);
+    }
+
+    /// Delete every object of an execution without waiting for them to go
+    pub(super) fn delete_execution(&self, name: &str) {
+        let _ = self
+            .command()
+            .args(["delete", EXECUTION_KINDS, "--selector", &execution_selector(name)])
+            .args(["--wait=false", "--ignore-not-found"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .helper_status(Helper::ContainerCli);
+    }
+}
+
+/// Backend configuration an execution needs
+#[derive(Debug, Clone)]
+pub(super) struct JobOptions {
+    /// Container image specification
+    pub image: String,
+    /// Executor identity recorded on the created objects
+    pub owner_id: String,
+    /// Backend instance identity recorded on the created objects
+    pub instance_id: String,
+    /// CPU quantity the pod is limited to
+    pub cpu: Option<String>,
+    /// Secret the kubelet pulls the image with
+    pub pull_secret: Option<String>,
+    /// Budget of scheduling the pod and pulling its image
+    pub provisioning: ProvisioningLimits,
+}
+
+/// Execute code as a Kubernetes Job
+///
+/// # Arguments
+/// * `kubectl` - Cluster, context and namespace to run in
+/// * `options` - Image, owner and scheduling settings from the backend config
+/// * `request` - Execution request with code and configuration
+///
+/// # Returns
+/// AsyncTask that resolves to execution result
+pub(super) fn execute_job(
+    kubectl: Kubectl,
+    options: JobOptions,
+    request: ExecutionRequest,
+) -> AsyncTask<BackendResult<ExecutionResult>> {
+    AsyncTaskBuilder::new(async move {
+        let start_time = Instant::now();
+        reject_host_state(&request)?;
+
+        // The command only names the source file and never embeds the code
+        let (source_file, exec_cmd) = prepare_execution_command(
+            &request.language,
+            request.compiler.as_ref(),
+            request.sql.as_ref(),
+        )?;
+        let exec_cmd = with_setup(&setup_script(&request), exec_cmd);
+
+        // The cluster stops the pod on its own should this process die;
+        // the deadline covers the pull, the run and its grace period
+        let name = ids::generate(&ids::language_tag(&request.language));
+        let pull_timeout = options.provisioning.pull_timeout;
+        let job = JobTemplate {
+            name: name.clone(),
+            owner_id: options.owner_id.clone(),
+            instance_id: options.instance_id.clone(),
+            image: options.image.clone(),
+            cpu: options.cpu.clone(),
+            pull_secret: options.pull_secret.clone(),
+            active_deadline: pull_timeout.unwrap_or_default()
+                + request.timeout
+                + request.termination_grace,
+        };
+        let objects = manifest::objects(&job, &request, &source_file, exec_cmd)?;
+
+        request.report_progress(ProvisioningStage::PreparingWorkspace, None, "Submitting job");
+        kubectl
+            .output(&["create", "--filename", "-"], Some(objects.to_string().into_bytes()))
+            .await?;
+        // Deletes the objects however the execution ends from here on
+        let _objects = ExecutionObjects {
+            kubectl: kubectl.clone(),
+            name: name.clone(),
+        };
+
+        request.report_progress(ProvisioningStage::PullingImage, None, "Waiting for the job pod");
+        let budget = StageBudget::start("K8sJob", ProvisioningStage::PullingImage, pull_timeout);
+        let started = budget.run(wait_for_start(&kubectl, &name)).await?;
+        request.report_progress(ProvisioningStage::Ready, None, "Job pod started");
+
+        // Follow the pod's logs; the client exits once the container does
+        let mut cmd = kubectl.command();
+        cmd.args(["logs", "--follow", "--container", MAIN_CONTAINER, &format!("job/{name}")]);
+        cmd.stdin(Stdio::null());
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        // Lead a new process group so a forced kill reaches the whole client tree
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let mut child = cmd
+            .helper_spawn(Helper::ContainerCli)
+            .map_err(|e| BackendError::ProcessFailed {
+                details: format!("Failed to spawn {}: {e}", kubectl.program),
+            })?;
+        let capture = OutputCapture::streaming(&mut child, request.output_sink.clone());
+
+        // Wait for completion; on timeout delete the job so the pod gets
+        // SIGTERM and its grace period, then kill the log client
+        let timeout_duration = request.timeout;
+        #[cfg(unix)]
+        let client_pid = child.id();
+        let outcome = process::wait_with_grace(
+            &mut child,
+            timeout_duration,
+            request.termination_grace,
+            || kubectl.delete_job(&name),
+            || {
+                #[cfg(unix)]
+                process::kill_group(client_pid);
+            },
+        )
+        .await
+        .map_err(|e| BackendError::ProcessFailed {
+            details: format!("Job execution failed: {e}"),
+        })?;
+
+        let CapturedOutput { stdout, stderr, raw } = capture.collect(process::OUTPUT_DRAIN).await;
+        let mut termination_reason = None;
+        let mut result = match outcome {
+            WaitOutcome::Exited(_) => {
+                let (exit_code, reason) = wait_for_exit(&kubectl, &name).await?;
+                termination_reason = reason;
+                ExecutionResult {
+                    exit_code,
+                    stdout,
+                    stderr,
+                    duration: start_time.elapsed(),
+                    resource_usage: Default::default(),
+                    metadata: HashMap::new(),
+                    verdict: None,
+                    outcome: ExecutionOutcome::Completed,
+                    security: None,
+                    cost: None,
+                    crash: None,
+                    result_sets: None,
+                    workspace_archive: None,
+                    blobs: HashMap::new(),
+                    processed: None,
+                    raw_output: None,
+                    compilation: None,
+                }
+            }
+            // No watchdog runs here, so a stall can only be a timeout
+            WaitOutcome::TimedOut { graceful } | WaitOutcome::Stalled { graceful } => {
+                let mut result = ExecutionResult::timed_out(stdout, stderr, timeout_duration);
+                result.duration = start_time.elapsed();
+                result.metadata.insert(
+                    "termination".to_string(),
+                    if graceful { "graceful" } else { "forced" }.to_string(),
+                );
+                result
+            }
+        };
+
+        // The deny-all policy only holds where a network plugin enforces it
+        let mut enforcer = None;
+        if !request.network_allowed() {
+            enforcer = kubectl.network_policy_enforcer().await;
+            let enforcement = match &enforcer {
+                Some(plugin) => format!("enforced by {plugin}"),
+                None => "not enforced: no known NetworkPolicy-enforcing network plugin found"
+                    .to_string(),
+            };
+            result
+                .metadata
+                .insert(K8S_NETWORK_POLICY_METADATA.to_string(), enforcement);
+        }
+
+        result.raw_output = raw;
+        result.security = Some(SecurityReport {
+            isolation: IsolationLevel::Container,
+            network_disabled: enforcer.is_some(),
+            filesystem_read_only: false,
+            memory_limit_bytes: request.limits.max_memory,
+            ipc_isolated: true,
+            ui_restricted: true,
+        });
+        result
+            .metadata
+            .insert("backend".to_string(), "K8sJob".to_string());
+        result
+            .metadata
+            .insert("namespace".to_string(), kubectl.namespace.clone());
+        result.metadata.insert("image".to_string(), options.image);
+        result.metadata.insert("job_name".to_string(), name);
+        // Pod logs interleave both streams; stderr only carries kubectl's own
+        result.metadata.insert(
+            "output".to_string(),
+            "stdout and stderr are merged in the pod logs".to_string(),
+        );
+        if let Some(digest) = started.image_digest {
+            result.metadata.insert(IMAGE_DIGEST_METADATA.to_string(), digest);
+        }
+        if let Some(reason) = termination_reason {
+            result
+                .metadata
+                .insert(K8S_TERMINATION_REASON_METADATA.to_string(), reason);
+        }
+        if let Some(arch) = request.arch {
+            result.metadata.insert(ARCH_METADATA.to_string(), arch.to_string());
+        }
+        if let Some(dns) = &request.dns {
+            dns.record(&mut result);
+        }
+        if let Some(clock) = &request.clock {
+            result
+                .metadata
+                .insert(CLOCK_METADATA.to_string(), clock.mode().to_string());
+        }
+        if request.stall_timeout.is_some() {
+            result.metadata.insert(
+                WATCHDOG_METADATA.to_string(),
+                "not enforced: pod CPU use is not observable from the host".to_string(),
+            );
+        }
+
+        // The runtime reports a signal death as 128 + N; the core, if any,
+        // stays on the node
+        if !result.is_timed_out() {
+            result.crash = CrashReport::from_exit_code(result.exit_code);
+        }
+        if let Some(crash) = &result.crash {
+            result
+                .metadata
+                .insert(SIGNAL_METADATA.to_string(), crash.signal_name.clone());
+            if request.core_dump_limit.is_some() && crash.dumps_core() {
+                result.metadata.insert(
+                    CORE_DUMP_METADATA.to_string(),
+                    "not captured: core dumps stay on the node".to_string(),
+                );
+            }
+        }
+
+        Ok(result)
+    })
+    .spawn()
+}
+
+/// Objects of a submitted execution, deleted when dropped
+struct ExecutionObjects {
+    kubectl: Kubectl,
+    name: String,
+}
+
+impl Drop for ExecutionObjects {
+    fn drop(&mut self) {
+        self.kubectl.delete_execution(&self.name);
+    }
+}
+
+/// Refuse what only exists on the host
+///
+/// The workspace is assembled inside the cluster, out of reach of host
+/// paths, the host's git and the guarded archive unpacking.
+fn reject_host_state(request: &ExecutionRequest) -> BackendResult<()> {
+    let reason = if !exposed_paths(request)?.is_empty() {
+        "host paths cannot be exposed to cluster pods"
+    } else if request.git_repo.is_some() {
+        "git checkouts are not supported in cluster pods"
+    } else if request.workspace_archive.is_some() || request.output_archive.is_some() {
+        "workspace archives are not supported in cluster pods"
+    } else {
+        return Ok(());
+    };
+    Err(BackendError::NotAvailable {
+        backend: "K8sJob",
+        reason: reason.to_string(),
+    })
+}
+
+/// Shell lines run before the code: the umask, the CPU time rlimit, stdin
+/// from the input file and a virtual clock preloading libfaketime
+fn setup_script(request: &ExecutionRequest) -> String {
+    let mut setup = format!("umask {:03o}\n", request.environment.umask);
+    if let Some(cpu_time) = request.limits.max_cpu_time {
+        setup.push_str(&format!("ulimit -t {cpu_time} || exit 1\n"));
+    }
+    if request.input.is_some() {
+        setup.push_str(&format!("exec < {INPUT_FILE}\n"));
+    }
+    if let Some(clock) = &request.clock {
+        setup.push_str(&clock::faketime_shell_setup(clock));
+    }
+    setup
+}
+
+/// Wrap a command so shell `setup` lines run before it
+fn with_setup(setup: &str, command: Vec<String>) -> Vec<String> {
+    let script = format!("{setup}exec \"$@\"");
+    ["sh".to_string(), "-c".to_string(), script, "sh".to_string()]
+        .into_iter()
+        .chain(command)
+        .collect()
+}
+
+/// Poll until the execution's pod runs or has already finished
+async fn wait_for_start(kubectl: &Kubectl, name: &str) -> BackendResult<PodStatus> {
+    loop {
+        let status = kubectl.pod_status(name).await?;
+        match &status.state {
+            PodState::Pending => tokio::time::sleep(POLL_INTERVAL).await,
+            PodState::Failed(reason) => {
+                return Err(BackendError::ProcessFailed {
+                    details: format!("Job pod failed to start: {reason}"),
+                });
+            }
+            PodState::Running | PodState::Terminated { .. } => return Ok(status),
+        }
+    }
+}
+
+/// Poll until the pod reports the code's exit code
+///
+/// # Returns
+/// The exit code and the runtime's reason, e.g. "OOMKilled"
+async fn wait_for_exit(kubectl: &Kubectl, name: &str) -> BackendResult<(i32, Option<String>)> {
+    let deadline = Instant::now() + EXIT_STATUS_WAIT;
+    loop {
+        match kubectl.pod_status(name).await?.state {
+            PodState::Terminated { exit_code, reason } => return Ok((exit_code, reason)),
+            PodState::Failed(reason) => {
+                return Err(BackendError::ProcessFailed {
+                    details: format!("Job pod failed: {reason}"),
+                });
+            }
+            PodState::Pending | PodState::Running if Instant::now() < deadline => {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+            PodState::Pending | PodState::Running => {
+                return Err(BackendError::ProcessFailed {
+                    details: "log stream ended before the job pod finished".to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Where an execution's pod is in its life
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum PodState {
+    /// Not scheduled, pulling its image or setting up the workspace
+    Pending,
+    /// The code is running
+    Running,
+    /// The code exited
+    Terminated {
+        exit_code: i32,
+        reason: Option<String>,
+    },
+    /// The pod will not run the code, for the given reason
+    Failed(String),
+}
+
+/// State of an execution's pod and the image it runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct PodStatus {
+    pub state: PodState,
+    /// Digest of the image the kubelet started, once known
+    pub image_digest: Option<String>,
+}
+
+impl PodStatus {
+    /// Read the status of the first pod in `kubectl get pods -o json` output
+    pub(super) fn parse(pods: &Value) -> Self {
+        let Some(status) = pods["items"].get(0).map(|pod| &pod["status"]) else {
+            return Self {
+                state: PodState::Pending,
+                image_digest: None,
+            };
+        };
+        let main = status["containerStatuses"]
+            .as_array()
+            .and_then(|statuses| statuses.iter().find(|c| c["name"] == MAIN_CONTAINER));
+        let image_digest = main
+            .and_then(|container| container["imageID"].as_str())
+            .and_then(|id| id.rsplit_once('@'))
+            .map(|(_, digest)| digest.to_string());
+
+        let state = match main.map(|container| &container["state"]) {
+            Some(state) if state["terminated"].is_object() => PodState::Terminated {
+                exit_code: state["terminated"]["exitCode"].as_i64().unwrap_or(-1) as i32,
+                reason: state["terminated"]["reason"].as_str().map(String::from),
+            },
+            Some(state) if state["running"].is_object() => PodState::Running,
+            _ => match start_failure(status) {
+                Some(reason) => PodState::Failed(reason),
+                None => PodState::Pending,
+            },
+        };
+        Self {
+            state,
+            image_digest,
+        }
+    }
+}
+
+/// Why a pod that has not run its code never will, if it will not
+fn start_failure(status: &Value) -> Option<String> {
+    let containers = ["initContainerStatuses", "containerStatuses"]
+        .into_iter()
+        .filter_map(|key| status[key].as_array())
+        .flatten();
+    for container in containers {
+        let state = &container["state"];
+        if let Some(reason) = state["waiting"]["reason"].as_str()
+            && START_FAILURES.contains(&reason)
+        {
+            let message = state["waiting"]["message"].as_str().unwrap_or_default();
+            return Some(format!("{reason}: {message}").trim_end_matches(": ").to_string());
+        }
+        if state["terminated"]["exitCode"].as_i64().is_some_and(|code| code != 0) {
+            return Some(format!("{} container failed", container["name"].as_str().unwrap_or("?")));
+        }
+    }
+    (status["phase"] == "Failed").then(|| {
+        status["message"]
+            .as_str()
+            .or(status["reason"].as_str())
+            .unwrap_or("pod failed")
+            .to_string()
+    })
+}
+
+/// Known NetworkPolicy-enforcing plugin among listed DaemonSets
+///
+/// # Arguments
+/// * `daemonsets` - `kubectl get daemonsets -o json` output
+pub(super) fn policy_enforcer(daemonsets: &Value) -> Option<String> {
+    let items = daemonsets["items"].as_array()?;
+    let names = items.iter().flat_map(|item| {
+        let pod = &item["spec"]["template"]["spec"];
+        let containers = ["initContainers", "containers"]
+            .into_iter()
+            .filter_map(|key| pod[key].as_array())
+            .flatten()
+            .flat_map(|container| [&container["name"], &container["image"]]);
+        std::iter::once(&item["metadata"]["name"]).chain(containers)
+    });
+    names.filter_map(Value::as_str).find_map(|name| {
+        let name = name.to_ascii_lowercase();
+        POLICY_ENFORCING_PLUGINS
+            .into_iter()
+            .find(|plugin| name.contains(plugin))
+            .map(String::from)
+    })
+}
+
+/// Executions whose objects are left to delete: those of jobs finished
+/// more than `CLEANUP_GRACE` ago, and ConfigMaps and NetworkPolicies
+/// created that long ago whose job is gone
+///
+/// # Arguments
+/// * `jobs` - `kubectl get jobs -o json` output
+/// * `others` - `kubectl get configmaps,networkpolicies -o json` output
+/// * `now` - Time the lists were taken
+pub(super) fn stale_executions(
+    jobs: &Value,
+    others: &Value,
+    now: DateTime<Utc>,
+) -> BTreeSet<String> {
+    let items = |list: &Value| list["items"].as_array().cloned().unwrap_or_default();
+    let execution =
+        |item: &Value| item["metadata"]["labels"][EXECUTION_LABEL].as_str().map(String::from);
+    // An unreadable time counts as recent; the job's TTL still applies
+    let long_ago = |time: &Value| {
+        time.as_str()
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .and_then(|time| (now - time.with_timezone(&Utc)).to_std().ok())
+            .is_some_and(|age| age >= CLEANUP_GRACE)
+    };
+
+    let jobs = items(jobs);
+    let existing: BTreeSet<String> = jobs.iter().filter_map(execution).collect();
+    let finished = jobs.iter().filter(|job| {
+        job["status"]["conditions"].as_array().is_some_and(|conditions| {
+            conditions.iter().any(|condition| {
+                matches!(condition["type"].as_str(), Some("Complete" | "Failed"))
+                    && condition["status"] == "True"
+                    && long_ago(&condition["lastTransitionTime"])
+            })
+        })
+    });
+    let orphaned = items(others)
+        .into_iter()
+        .filter(|item| long_ago(&item["metadata"]["creationTimestamp"]))
+        .filter_map(|item| execution(&item))
+        .filter(|name| !existing.contains(name));
+    finished.filter_map(execution).chain(orphaned).collect()
+}
+
+/// Prepare execution command for specific language
+///
+/// # Arguments
+/// * `language` - Programming language
+/// * `compiler` - Compiler options for compiled languages
+/// * `sql` - Engine and seeds of a SQL request
+///
+/// # Returns
+/// Source file name to write into the workspace and the command arguments
+/// for the pod
+pub(super) fn prepare_execution_command(
+    language: &str,
+    compiler: Option<&CompilerOptions>,
+    sql: Option<&SqlOptions>,
+) -> BackendResult<(String, Vec<String>)> {
+    // The pinned version picked the image (`ImageReference::for_runtime`)
+    let (name, _) = language::split_version(language);
+    let options = compiler.cloned().unwrap_or_default();
+    let shell = |script: String| vec!["sh".to_string(), "-c".to_string(), script];
+    let (source_file, command) = match name.to_lowercase().as_str() {
+        "python" | "python3" => ("main.py", vec!["python3".into(), "/cylo-src/main.py".into()]),
+        "javascript" | "js" | "node" => {
+            ("main.js", vec!["node".into(), "/cylo-src/main.js".into()])
+        }
+        "rust" => (
+            "main.rs",
+            shell(compile_phase::compile_then_run(
+                &format!(
+                    "rustc {}/cylo-src/main.rs -o /tmp/main",
+                    compiler::shell_args(&options.rustc_args())
+                ),
+                "exec /tmp/main",
+            )),
+        ),
+        "bash" | "sh" => ("main.sh", vec!["sh".into(), "/cylo-src/main.sh".into()]),
+        "go" => (
+            "main.go",
+            shell(compile_phase::compile_then_run(
+                &format!(
+                    "cd /tmp && go build -o /tmp/main {}/cylo-src/main.go",
+                    compiler::shell_args(&options.go_args())
+                ),
+                "exec /tmp/main",
+            )),
+        ),
+        "r" | "rscript" => (
+            "main.R",
+            shell(r_library::rscript_command("Rscript", "/cylo-src/main.R")),
+        ),
+        "sql" | "sqlite" | "sqlite3" | "duckdb" => {
+            let engine = SqlEngine::of(language, sql);
+            let args = sql::shell_args(engine, sql, "/cylo-src/main.sql").map_err(|details| {
+                BackendError::InvalidConfig {
+                    backend: "K8sJob",
+                    details,
+                }
+            })?;
+            let mut command = vec![engine.program().to_string()];
+            command.extend(args);
+            ("main.sql", command)
+        }
+        _ => {
+            return Err(BackendError::UnsupportedLanguage {
+                backend: "K8sJob",
+                language: language.to_string(),
+            });
+        }
+    };
+
+    Ok((source_file.to_string(), command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pod_status_and_stale_objects_are_read_from_kubectl_json() {
+        let pods = |status: Value| json!({ "items": [{ "status": status }] });
+        assert_eq!(PodStatus::parse(&json!({ "items": [] })).state, PodState::Pending);
+
+        let pulling = pods(json!({
+            "phase": "Pending",
+            "containerStatuses": [{
+                "name": "main",
+                "state": { "waiting": { "reason": "ImagePullBackOff", "message": "not found" } },
+            }],
+        }));
+        assert_eq!(
+            PodStatus::parse(&pulling).state,
+            PodState::Failed("ImagePullBackOff: not found".to_string())
+        );
+
+        let done = PodStatus::parse(&pods(json!({
+            "phase": "Failed",
+            "containerStatuses": [{
+                "name": "main",
+                "imageID": "docker.io/library/python@sha256:abc",
+                "state": { "terminated": { "exitCode": 137, "reason": "OOMKilled" } },
+            }],
+        })));
+        assert_eq!(
+            done.state,
+            PodState::Terminated {
+                exit_code: 137,
+                reason: Some("OOMKilled".to_string())
+            }
+        );
+        assert_eq!(done.image_digest.as_deref(), Some("sha256:abc"));
+
+        let setup_failed = pods(json!({
+            "phase": "Failed",
+            "initContainerStatuses": [{
+                "name": "workspace",
+                "state": { "terminated": { "exitCode": 1 } },
+            }],
+        }));
+        assert!(matches!(PodStatus::parse(&setup_failed).state, PodState::Failed(_)));
+
+        let now: DateTime<Utc> = "2026-01-01T12:00:00Z".parse().unwrap();
+        let (old, recent) = ("2026-01-01T11:00:00Z", "2026-01-01T11:59:00Z");
+        let labelled = |name: &str, created: &str| {
+            json!({ "labels": { "cylo.dev/execution": name }, "creationTimestamp": created })
+        };
+        let complete = |at: &str| {
+            json!({ "conditions": [
+                { "type": "Complete", "status": "True", "lastTransitionTime": at },
+            ] })
+        };
+        let jobs = json!({ "items": [
+            { "metadata": labelled("cylo-py-1", old), "status": complete(old) },
+            { "metadata": labelled("cylo-py-2", old), "status": { "active": 1 } },
+            // Finished a moment ago; its logs may still be read
+            { "metadata": labelled("cylo-py-4", old), "status": complete(recent) },
+        ] });
+        let others = json!({ "items": [
+            { "metadata": labelled("cylo-py-2", old) },
+            { "metadata": labelled("cylo-py-3", old) },
+            // Its job may not have been created yet
+            { "metadata": labelled("cylo-py-5", recent) },
+        ] });
+        let stale: Vec<_> = stale_executions(&jobs, &others, now).into_iter().collect();
+        assert_eq!(stale, ["cylo-py-1", "cylo-py-3"]);
+
+        let daemonset = |name: &str, image: &str| {
+            json!({
+                "metadata": { "name": name },
+                "spec": { "template": { "spec": { "containers": [
+                    { "name": name, "image": image },
+                ] } } },
+            })
+        };
+        let flannel = json!({ "items": [
+            daemonset("kube-proxy", "registry.k8s.io/kube-proxy:v1.30.0"),
+            daemonset("kube-flannel-ds", "docker.io/flannel/flannel:v0.25.1"),
+        ] });
+        assert_eq!(policy_enforcer(&flannel), None);
+        let calico = json!({ "items": [
+            daemonset("kube-proxy", "registry.k8s.io/kube-proxy:v1.30.0"),
+            daemonset("node", "docker.io/calico/node:v3.28.0"),
+        ] });
+        assert_eq!(policy_enforcer(&calico).as_deref(), Some("calico"));
+    }
+
+    #[test]
+    fn setup_redirects_input_and_limits_cpu_time() {
+        let mut request = ExecutionRequest::new("read x; echo $x", "bash").with_input("1");
+        request.limits.max_cpu_time = Some(5);
+        let setup = setup_script(&request);
+        assert!(setup.contains("ulimit -t 5"), "{setup}");
+        assert!(setup.contains(&format!("exec < {INPUT_FILE}")), "{setup}");
+
+        let (file, command) = prepare_execution_command("bash", None, None).unwrap();
+        assert_eq!(file, "main.sh");
+        let wrapped = with_setup(&setup, command);
+        assert_eq!(wrapped[..2], ["sh", "-c"]);
+        assert_eq!(wrapped[4..], ["sh", "/cylo-src/main.sh"]);
+        assert!(prepare_execution_command("cobol", None, None).is_err());
+
+        request.writable_paths.push(std::env::temp_dir());
+        assert!(reject_host_state(&request).is_err());
+    }
+}
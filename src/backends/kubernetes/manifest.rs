@@ -0,0 +1,614 @@
+// ============================================================================
+// File: packages/cylo/src/backends/kubernetes/manifest.rs
+// ----------------------------------------------------------------------------
+// Kubernetes objects submitted for one execution.
+//
+// An execution becomes a ConfigMap carrying its files and input, a Job
+// running a single pod, and, when the request has no network access, a
+// NetworkPolicy denying that pod all traffic. The objects share the
+// execution's label, so they are found and deleted together.
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::time::Duration;
+
+use serde_json::{Map, Value, json};
+
+use crate::backends::paths::relative_inside;
+use crate::backends::{
+    BackendError, BackendResult, BlobFile, DnsPolicy, DnsResolvers, ExecutionRequest, go_cache,
+    r_library,
+};
+
+/// Label naming the executor that created an object
+pub(super) const OWNER_LABEL: &str = "cylo.dev/owner";
+
+/// Label naming the backend instance that created an object, so one
+/// instance's cleanup leaves the objects of others alone
+pub(super) const INSTANCE_LABEL: &str = "cylo.dev/instance";
+
+/// Label naming the execution an object belongs to
+pub(super) const EXECUTION_LABEL: &str = "cylo.dev/execution";
+
+/// Name of the container running the code
+pub(super) const MAIN_CONTAINER: &str = "main";
+
+/// Pod path of the writable workspace the source file is copied to
+pub(super) const SOURCE_MOUNT: &str = "/cylo-src";
+
+/// Pod path of the request's stdin, when it has any
+pub(super) const INPUT_FILE: &str = "/cylo-input/stdin";
+
+/// Pod path the ConfigMap is mounted at for the copy into the workspace
+const CONFIG_MOUNT: &str = "/cylo-config";
+
+/// Directory of the ConfigMap volume holding the workspace files; the
+/// volume root also holds the kubelet's own `..data` links
+const FILES_DIR: &str = "files";
+
+/// Name of the home directory inside the workspace
+const HOME_DIR: &str = "home";
+
+/// Name of the Go and R cache directory inside the workspace
+const CACHE_DIR: &str = ".cache";
+
+/// ConfigMap key of the request's stdin
+const INPUT_KEY: &str = "stdin";
+
+/// Largest payload a ConfigMap carries; the API server caps whole objects
+/// at 1MiB, so room is left for keys and metadata
+const MAX_PAYLOAD_BYTES: usize = 1024 * 1024 - 64 * 1024;
+
+/// How long finished jobs stay for inspection before the cluster removes them
+const FINISHED_TTL_SECONDS: u64 = 600;
+
+/// Job settings that come from the backend rather than the request
+#[derive(Debug, Clone)]
+pub(super) struct JobTemplate {
+    /// Job name, also naming the ConfigMap and NetworkPolicy
+    pub name: String,
+    /// Executor identity, recorded as the owner label
+    pub owner_id: String,
+    /// Backend instance identity, recorded as the instance label
+    pub instance_id: String,
+    /// Container image specification
+    pub image: String,
+    /// CPU quantity the pod is limited to, e.g. "500m"
+    pub cpu: Option<String>,
+    /// Secret the kubelet pulls the image with
+    pub pull_secret: Option<String>,
+    /// Longest the pod may exist, image pull included
+    pub active_deadline: Duration,
+}
+
+/// List of the objects to create for an execution
+///
+/// # Arguments
+/// * `job` - Backend settings of the job
+/// * `request` - Execution request
+/// * `source_file` - Name the code is written to in the workspace
+/// * `command` - Command running the source file, setup included
+///
+/// # Returns
+/// A `List` for `kubectl create -f -`, or InvalidConfig when the files
+/// and input do not fit a ConfigMap
+pub(super) fn objects(
+    job: &JobTemplate,
+    request: &ExecutionRequest,
+    source_file: &str,
+    command: Vec<String>,
+) -> BackendResult<Value> {
+    let payload = Payload::collect(request, source_file)?;
+    let mut items = vec![config_map(job, payload.data, payload.binary_data)];
+    // Created before the job so the pod never starts unrestricted; whether
+    // it is enforced is up to the cluster's network plugin
+    if !request.network_allowed() {
+        items.push(deny_all_policy(job));
+    }
+    items.push(json!({
+        "apiVersion": "batch/v1",
+        "kind": "Job",
+        "metadata": metadata(job),
+        "spec": {
+            "backoffLimit": 0,
+            "activeDeadlineSeconds": job.active_deadline.as_secs().max(1),
+            "ttlSecondsAfterFinished": FINISHED_TTL_SECONDS,
+            "template": {
+                "metadata": { "labels": labels(job) },
+                "spec": pod_spec(job, request, payload.items, command)?,
+            },
+        },
+    }));
+    Ok(json!({ "apiVersion": "v1", "kind": "List", "items": items }))
+}
+
+/// Label value naming an executor; label values are limited to 63
+/// alphanumerics, `-`, `_` and `.`, starting and ending alphanumeric
+pub(super) fn owner_label(owner_id: &str) -> String {
+    let value: String = owner_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '-' })
+        .take(63)
+        .collect();
+    value.trim_matches(|c: char| !c.is_ascii_alphanumeric()).to_string()
+}
+
+/// Label selector matching the objects of one execution
+pub(super) fn execution_selector(name: &str) -> String {
+    format!("{EXECUTION_LABEL}={name}")
+}
+
+fn labels(job: &JobTemplate) -> Value {
+    let mut labels = Map::new();
+    labels.insert(OWNER_LABEL.to_string(), owner_label(&job.owner_id).into());
+    labels.insert(INSTANCE_LABEL.to_string(), job.instance_id.clone().into());
+    labels.insert(EXECUTION_LABEL.to_string(), job.name.clone().into());
+    Value::Object(labels)
+}
+
+fn metadata(job: &JobTemplate) -> Value {
+    json!({ "name": job.name, "labels": labels(job) })
+}
+
+fn config_map(
+    job: &JobTemplate,
+    data: Map<String, Value>,
+    binary_data: Map<String, Value>,
+) -> Value {
+    json!({
+        "apiVersion": "v1",
+        "kind": "ConfigMap",
+        "metadata": metadata(job),
+        "immutable": true,
+        "data": data,
+        "binaryData": binary_data,
+    })
+}
+
+fn deny_all_policy(job: &JobTemplate) -> Value {
+    let mut selector = Map::new();
+    selector.insert(EXECUTION_LABEL.to_string(), job.name.clone().into());
+    json!({
+        "apiVersion": "networking.k8s.io/v1",
+        "kind": "NetworkPolicy",
+        "metadata": metadata(job),
+        "spec": {
+            "podSelector": { "matchLabels": selector },
+            "policyTypes": ["Ingress", "Egress"],
+        },
+    })
+}
+
+/// Pod running the code
+///
+/// An init container copies the files out of the read-only ConfigMap into
+/// an emptyDir, so the code gets a writable workspace as on other backends.
+fn pod_spec(
+    job: &JobTemplate,
+    request: &ExecutionRequest,
+    items: Vec<Value>,
+    command: Vec<String>,
+) -> BackendResult<Value> {
+    let copy = format!(
+        "cp -RL {CONFIG_MOUNT}/{FILES_DIR}/. {SOURCE_MOUNT}/ && \
+         mkdir -p {SOURCE_MOUNT}/{HOME_DIR} {SOURCE_MOUNT}/{CACHE_DIR}"
+    );
+    let workspace_mount = json!({ "name": "workspace", "mountPath": SOURCE_MOUNT });
+    let mut mounts = vec![workspace_mount.clone()];
+    let mut volumes = vec![
+        json!({ "name": "workspace", "emptyDir": {} }),
+        json!({ "name": "config", "configMap": { "name": job.name, "items": items } }),
+    ];
+    if request.input.is_some() {
+        let (dir, file) = INPUT_FILE.rsplit_once('/').unwrap_or(("/", INPUT_FILE));
+        mounts.push(json!({ "name": "input", "mountPath": dir, "readOnly": true }));
+        volumes.push(json!({
+            "name": "input",
+            "configMap": { "name": job.name, "items": [{ "key": INPUT_KEY, "path": file }] },
+        }));
+    }
+
+    let mut container = json!({
+        "name": MAIN_CONTAINER,
+        "image": job.image,
+        "command": command,
+        "env": env(request),
+        "volumeMounts": mounts,
+        "resources": resources(job, request),
+        "securityContext": container_security(),
+    });
+    // `..` escapes are rejected and the path is always absolute in the pod
+    if let Some(workdir) = &request.working_dir {
+        let relative = relative_inside(workdir)?;
+        container["workingDir"] = format!("/{}", relative.display()).into();
+    }
+
+    let mut pod = json!({
+        "restartPolicy": "Never",
+        "automountServiceAccountToken": false,
+        "enableServiceLinks": false,
+        "terminationGracePeriodSeconds": request.termination_grace.as_secs_f64().ceil() as u64,
+        "initContainers": [{
+            "name": "workspace",
+            "image": job.image,
+            "command": ["sh", "-c", copy],
+            "volumeMounts": [
+                workspace_mount,
+                { "name": "config", "mountPath": CONFIG_MOUNT, "readOnly": true },
+            ],
+            "resources": resources(job, request),
+            "securityContext": container_security(),
+        }],
+        "containers": [container],
+        "volumes": volumes,
+    });
+    if let Some(secret) = &job.pull_secret {
+        pod["imagePullSecrets"] = json!([{ "name": secret }]);
+    }
+    // Multi-arch images provide the requested variant on matching nodes
+    if let Some(arch) = request.arch {
+        pod["nodeSelector"] = json!({ "kubernetes.io/arch": arch.oci_name() });
+    }
+    if let Some(dns) = &request.dns {
+        let aliases = host_aliases(dns);
+        if !aliases.is_empty() {
+            pod["hostAliases"] = aliases.into();
+        }
+        if request.network_allowed() {
+            apply_resolvers(&mut pod, dns);
+        }
+    }
+    Ok(pod)
+}
+
+/// Nothing the code runs can regain a privilege
+fn container_security() -> Value {
+    json!({
+        "allowPrivilegeEscalation": false,
+        "capabilities": { "drop": ["ALL"] },
+        "seccompProfile": { "type": "RuntimeDefault" },
+    })
+}
+
+/// Resource limits of the pod's containers
+///
+/// Requests equal the limits, so the scheduler only places the pod where
+/// it fits. The file size limit caps the pod's scratch space, which
+/// includes its logs; CPU time is an rlimit set by the command's setup.
+/// Process counts are left to the kubelet's pod PID limit.
+fn resources(job: &JobTemplate, request: &ExecutionRequest) -> Value {
+    let limits = &request.limits;
+    let mut quantities = Map::new();
+    if let Some(memory) = limits.max_memory {
+        quantities.insert("memory".to_string(), memory.to_string().into());
+    }
+    if let Some(cpu) = &job.cpu {
+        quantities.insert("cpu".to_string(), cpu.clone().into());
+    }
+    if let Some(file_size) = limits.max_file_size {
+        quantities.insert("ephemeral-storage".to_string(), file_size.to_string().into());
+    }
+    json!({ "limits": quantities, "requests": quantities })
+}
+
+/// Normalized environment, Go caches and R library first, so request
+/// variables win; HOME and the caches are directories in the workspace
+fn env(request: &ExecutionRequest) -> Vec<Value> {
+    let home = format!("{SOURCE_MOUNT}/{HOME_DIR}");
+    let cache_root = format!("{SOURCE_MOUNT}/{CACHE_DIR}");
+    let profile = request.environment.variables(&home).into_iter();
+    let caches = go_cache::go_env(request, &cache_root)
+        .into_iter()
+        .chain(r_library::r_env(request, &cache_root));
+    let request_vars: BTreeMap<_, _> = request.env_vars.iter().collect();
+
+    profile
+        .chain(caches)
+        .map(|(key, value)| (key.to_string(), value))
+        .chain(request_vars.into_iter().map(|(key, value)| (key.clone(), value.clone())))
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect()
+}
+
+/// Static and blocked names, as entries of the pod's /etc/hosts
+fn host_aliases(policy: &DnsPolicy) -> Vec<Value> {
+    let mut by_address: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for (name, address) in &policy.static_hosts {
+        by_address.entry(address.to_string()).or_default().push(name);
+    }
+    // Blocked names map to the unspecified address, as in a rendered hosts file
+    for name in &policy.blocked_domains {
+        by_address.entry("0.0.0.0".to_string()).or_default().push(name);
+        by_address.entry("::".to_string()).or_default().push(name);
+    }
+    by_address
+        .into_iter()
+        .map(|(ip, hostnames)| json!({ "ip": ip, "hostnames": hostnames }))
+        .collect()
+}
+
+/// Point the pod at the policy's resolvers instead of the cluster DNS
+///
+/// A pod cannot be left without resolvers, so one resolving nothing is
+/// pointed at its own loopback where nothing answers.
+fn apply_resolvers(pod: &mut Value, policy: &DnsPolicy) {
+    let nameservers: Vec<String> = match &policy.resolvers {
+        DnsResolvers::Host => Vec::new(),
+        DnsResolvers::Fixed(servers) => servers.iter().map(ToString::to_string).collect(),
+        DnsResolvers::None => vec!["127.0.0.1".to_string()],
+    };
+    let mut config = Map::new();
+    if !nameservers.is_empty() {
+        pod["dnsPolicy"] = "None".into();
+        config.insert("nameservers".to_string(), nameservers.into());
+    }
+    if !policy.search_domains.is_empty() {
+        config.insert("searches".to_string(), policy.search_domains.clone().into());
+    }
+    if !config.is_empty() {
+        pod["dnsConfig"] = Value::Object(config);
+    }
+}
+
+/// ConfigMap contents and the volume items placing them in the workspace
+struct Payload {
+    data: Map<String, Value>,
+    binary_data: Map<String, Value>,
+    items: Vec<Value>,
+}
+
+impl Payload {
+    /// Gather the request's files, blobs, code and input, in that order;
+    /// the source file wins over a file of the same name
+    ///
+    /// Blob sizes are taken from the store before any blob is read, so an
+    /// oversized payload is refused without loading it.
+    fn collect(request: &ExecutionRequest, source_file: &str) -> BackendResult<Self> {
+        let mut files: BTreeMap<String, Contents<'_>> = BTreeMap::new();
+        for (name, contents) in &request.files {
+            files.insert(workspace_path(name)?, Contents::Text(contents));
+        }
+        for (name, blob) in &request.blob_files {
+            files.insert(workspace_path(name)?, Contents::Blob(name, blob));
+        }
+        files.insert(source_file.to_string(), Contents::Text(&request.code));
+
+        // Raw sizes are a lower bound of what the entries take encoded
+        let mut raw = request.input.as_ref().map_or(0, |input| input.len() as u64);
+        for contents in files.values() {
+            raw += contents.size()?;
+        }
+        check_payload_size(raw)?;
+
+        let mut payload = Self {
+            data: Map::new(),
+            binary_data: Map::new(),
+            items: Vec::new(),
+        };
+        let mut total = 0;
+        for (index, (path, contents)) in files.into_iter().enumerate() {
+            let key = format!("f{index}");
+            total += payload.insert(&key, contents.read()?);
+            payload
+                .items
+                .push(json!({ "key": key, "path": format!("{FILES_DIR}/{path}") }));
+        }
+        if let Some(input) = &request.input {
+            total += payload.insert(INPUT_KEY, input.clone().into_bytes());
+        }
+
+        check_payload_size(total as u64)?;
+        Ok(payload)
+    }
+
+    /// Add one entry, text as is and anything else base64 encoded
+    ///
+    /// # Returns
+    /// Bytes the entry takes in the object
+    fn insert(&mut self, key: &str, contents: Vec<u8>) -> usize {
+        match String::from_utf8(contents) {
+            Ok(text) => {
+                let size = text.len();
+                self.data.insert(key.to_string(), text.into());
+                size
+            }
+            Err(binary) => {
+                let encoded = encode_base64(binary.as_bytes());
+                let size = encoded.len();
+                self.binary_data.insert(key.to_string(), encoded.into());
+                size
+            }
+        }
+    }
+}
+
+/// A workspace file of the payload, blobs left unread until sized
+enum Contents<'a> {
+    Text(&'a str),
+    /// Workspace name the blob is requested under, and the blob
+    Blob(&'a str, &'a BlobFile),
+}
+
+impl Contents<'_> {
+    /// Bytes of the contents, from the blob's metadata for blobs
+    fn size(&self) -> BackendResult<u64> {
+        match self {
+            Self::Text(text) => Ok(text.len() as u64),
+            Self::Blob(name, blob) => blob
+                .source
+                .metadata()
+                .map(|metadata| metadata.len())
+                .map_err(|e| blob_error(name, blob, e)),
+        }
+    }
+
+    /// The contents, reading no more of a blob than a payload may carry
+    fn read(self) -> BackendResult<Vec<u8>> {
+        match self {
+            Self::Text(text) => Ok(text.as_bytes().to_vec()),
+            Self::Blob(name, blob) => {
+                let mut contents = Vec::new();
+                File::open(&blob.source)
+                    .and_then(|file| {
+                        file.take(MAX_PAYLOAD_BYTES as u64 + 1)
+                            .read_to_end(&mut contents)
+                    })
+                    .map_err(|e| blob_error(name, blob, e))?;
+                Ok(contents)
+            }
+        }
+    }
+}
+
+fn blob_error(name: &str, blob: &BlobFile, e: std::io::Error) -> BackendError {
+    BackendError::FileSystemFailed {
+        details: format!("Failed to read blob {} for {}: {}", blob.digest, name, e),
+    }
+}
+
+/// Refuse a payload larger than a ConfigMap carries
+fn check_payload_size(total: u64) -> BackendResult<()> {
+    if total > MAX_PAYLOAD_BYTES as u64 {
+        return Err(BackendError::InvalidConfig {
+            backend: "K8sJob",
+            details: format!(
+                "files, code and input take {total} bytes; a ConfigMap carries at most \
+                 {MAX_PAYLOAD_BYTES}"
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Workspace-relative path of a file, with `/` separators
+fn workspace_path(name: &str) -> BackendResult<String> {
+    let relative = relative_inside(name)?;
+    let parts: Vec<_> = relative.iter().map(|part| part.to_string_lossy()).collect();
+    if parts.is_empty() {
+        return Err(BackendError::InvalidConfig {
+            backend: "K8sJob",
+            details: format!("'{name}' does not name a file"),
+        });
+    }
+    Ok(parts.join("/"))
+}
+
+/// Standard padded base64, as `binaryData` expects
+fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for (i, shift) in [18, 12, 6, 0].into_iter().enumerate() {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((n >> shift) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::ResourceLimits;
+
+    fn template() -> JobTemplate {
+        JobTemplate {
+            name: "cylo-py-0123456789ab".to_string(),
+            owner_id: "Team_A!".to_string(),
+            instance_id: "cylo-k8sjob-0123456789ab".to_string(),
+            image: "python:3.12-alpine".to_string(),
+            cpu: Some("500m".to_string()),
+            pull_secret: None,
+            active_deadline: Duration::from_secs(630),
+        }
+    }
+
+    #[test]
+    fn request_maps_to_job_objects() {
+        let mut request = ExecutionRequest::new("print(input())", "python")
+            .with_input("42")
+            .with_dns(DnsPolicy::only([("api.internal", "10.0.0.1".parse().unwrap())]));
+        request.files.insert("data/rows.csv".to_string(), "a,b\n".to_string());
+        request.files.insert("main.py".to_string(), "shadowed".to_string());
+        request.limits = ResourceLimits {
+            max_memory: Some(64 * 1024 * 1024),
+            max_network_bandwidth: Some(0),
+            ..ResourceLimits::default()
+        };
+        let command = vec!["python3".to_string(), "/cylo-src/main.py".to_string()];
+        let list = objects(&template(), &request, "main.py", command).unwrap();
+        let items = list["items"].as_array().unwrap();
+        let kinds: Vec<_> = items.iter().map(|item| item["kind"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["ConfigMap", "NetworkPolicy", "Job"]);
+
+        // The source file wins and input has its own key
+        let config = &items[0];
+        assert_eq!(config["data"]["f0"], "a,b\n");
+        assert_eq!(config["data"]["f1"], "print(input())");
+        assert_eq!(config["data"]["stdin"], "42");
+        assert_eq!(config["metadata"]["labels"][OWNER_LABEL], "Team_A");
+        assert_eq!(config["metadata"]["labels"][INSTANCE_LABEL], "cylo-k8sjob-0123456789ab");
+
+        let job = &items[2];
+        assert_eq!(job["spec"]["backoffLimit"], 0);
+        assert_eq!(job["spec"]["activeDeadlineSeconds"], 630);
+        let pod = &job["spec"]["template"]["spec"];
+        assert_eq!(pod["automountServiceAccountToken"], false);
+        assert_eq!(pod["hostAliases"][0]["hostnames"][0], "api.internal");
+        // Without network the resolvers are left alone
+        assert!(pod.get("dnsPolicy").is_none());
+        let main = &pod["containers"][0];
+        assert_eq!(main["resources"]["limits"]["memory"], "67108864");
+        assert_eq!(main["resources"]["limits"]["cpu"], "500m");
+        assert_eq!(main["securityContext"]["capabilities"]["drop"][0], "ALL");
+        let volumes = pod["volumes"].as_array().unwrap();
+        assert_eq!(volumes[1]["configMap"]["items"][1]["path"], "files/main.py");
+        assert_eq!(volumes[2]["configMap"]["items"][0]["path"], "stdin");
+    }
+
+    #[test]
+    fn payload_limits_and_encoding() {
+        let mut request = ExecutionRequest::new("x".repeat(MAX_PAYLOAD_BYTES + 1), "bash");
+        assert!(objects(&template(), &request, "main.sh", Vec::new()).is_err());
+        request.code = "echo hi".to_string();
+        request.files.insert("../escape".to_string(), String::new());
+        assert!(objects(&template(), &request, "main.sh", Vec::new()).is_err());
+        request.files.clear();
+
+        // Blobs are sized before they are read
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("big.bin");
+        File::create(&source)
+            .unwrap()
+            .set_len(MAX_PAYLOAD_BYTES as u64 + 1)
+            .unwrap();
+        let blob = BlobFile {
+            digest: "sha256:big".to_string(),
+            source,
+        };
+        request.blob_files.insert("big.bin".to_string(), blob.clone());
+        let error = objects(&template(), &request, "main.sh", Vec::new()).unwrap_err();
+        assert!(error.to_string().contains("ConfigMap carries at most"), "{error}");
+        let missing = BlobFile {
+            source: dir.path().join("missing"),
+            ..blob
+        };
+        request.blob_files.insert("big.bin".to_string(), missing);
+        assert!(objects(&template(), &request, "main.sh", Vec::new()).is_err());
+
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"f"), "Zg==");
+        assert_eq!(encode_base64(b"fo"), "Zm8=");
+        assert_eq!(encode_base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(encode_base64(&[0xff, 0x00, 0xfe]), "/wD+");
+        assert_eq!(owner_label("--a.b--"), "a.b");
+    }
+}
@@ -0,0 +1,406 @@
+// ============================================================================
+// File: packages/cylo/src/backends/kubernetes/mod.rs
+// ----------------------------------------------------------------------------
+// Kubernetes Job backend for secure code execution.
+//
+// Implements ExecutionBackend trait by driving the kubectl CLI against a
+// cluster. Hosts inside a cluster often have no node-local sandbox at all;
+// this backend makes every execution a Job in a namespace instead, with:
+// - Files and input shipped in a ConfigMap, the workspace an emptyDir
+// - Memory, CPU and scratch space limits enforced by the kubelet
+// - A deny-all NetworkPolicy for executions without network access,
+//   reported as enforced only when the cluster runs a plugin enforcing it
+// - Per-execution objects deleted on exit, timeout and cleanup
+// ============================================================================
+
+mod execution;
+mod manifest;
+
+#[cfg(test)]
+mod tests;
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::AsyncTaskBuilder;
+use crate::backends::{
+    AsyncTask, BackendConfig, BackendError, BackendResult, ExecutionBackend, ExecutionRequest,
+    ExecutionResult, HealthCheckLevel, HealthStatus, IMAGE_REFERENCE_METADATA, ImageReference,
+};
+use crate::backends::{
+    arch, cpuset, desktop, input_stream, io_throttle, language, python_env, runtime,
+};
+use crate::ids;
+
+use execution::{JobOptions, Kubectl};
+use manifest::{INSTANCE_LABEL, OWNER_LABEL, owner_label};
+
+/// `backend_specific` key naming the kubectl program to drive; by default
+/// kubectl from PATH
+pub const KUBECTL_KEY: &str = "kubectl";
+
+/// `backend_specific` key naming the namespace jobs run in; "default"
+/// unless set
+pub const K8S_NAMESPACE_KEY: &str = "k8s_namespace";
+
+/// `backend_specific` key naming the kubeconfig context to use; the
+/// current context unless set
+pub const K8S_CONTEXT_KEY: &str = "k8s_context";
+
+/// `backend_specific` key setting the CPU quantity each pod is limited to,
+/// e.g. "500m" or "2"
+pub const K8S_CPU_KEY: &str = "k8s_cpu";
+
+/// `backend_specific` key naming the Secret the kubelet pulls the image with
+pub const K8S_IMAGE_PULL_SECRET_KEY: &str = "k8s_image_pull_secret";
+
+/// Result metadata key carrying why the container terminated, as the
+/// kubelet reports it, e.g. "OOMKilled"
+pub const K8S_TERMINATION_REASON_METADATA: &str = "k8s.termination_reason";
+
+/// Result metadata key telling whether the deny-all NetworkPolicy of an
+/// execution without network access is enforced, and by which plugin
+pub const K8S_NETWORK_POLICY_METADATA: &str = "k8s.network_policy";
+
+/// Namespace jobs run in unless configured
+const DEFAULT_NAMESPACE: &str = "default";
+
+/// kubectl installed on the host PATH, if any
+///
+/// Only looks for the binary, so it is cheap enough for platform
+/// detection; whether a cluster answers is up to the health check.
+pub fn installed_runtime() -> Option<&'static str> {
+    let search_path = runtime::host_search_path();
+    search_path
+        .iter()
+        .any(|dir| dir.join("kubectl").is_file() || dir.join("kubectl.exe").is_file())
+        .then_some("kubectl")
+}
+
+/// Kubernetes Job backend
+///
+/// Runs every execution as a Job of the configured image, deleted once it
+/// finishes. The pod's logs interleave stdout and stderr, so the result's
+/// stdout carries both and its stderr only what kubectl itself reports.
+#[derive(Debug, Clone)]
+pub struct K8sJobBackend {
+    /// Cluster, context and namespace kubectl targets
+    kubectl: Kubectl,
+
+    /// Container image specification (e.g., "python:3.12-alpine")
+    image: String,
+
+    /// Parsed image specification, carrying any pinned digest
+    reference: ImageReference,
+
+    /// CPU quantity each pod is limited to
+    cpu: Option<String>,
+
+    /// Secret the kubelet pulls the image with
+    pull_secret: Option<String>,
+
+    /// Identity of this instance, labelling the objects it creates
+    instance_id: String,
+
+    /// Backend configuration
+    config: BackendConfig,
+}
+
+impl K8sJobBackend {
+    /// Create a new Kubernetes Job backend instance
+    ///
+    /// # Arguments
+    /// * `image` - Container image specification
+    /// * `config` - Backend configuration; `K8S_NAMESPACE_KEY`,
+    ///   `K8S_CONTEXT_KEY`, `K8S_CPU_KEY`, `K8S_IMAGE_PULL_SECRET_KEY` and
+    ///   `KUBECTL_KEY` pick the cluster and shape the pods
+    ///
+    /// # Returns
+    /// New backend instance, or error if kubectl is not installed or a
+    /// setting is malformed
+    pub fn new(image: String, config: BackendConfig) -> BackendResult<Self> {
+        let reference =
+            ImageReference::parse(&image).map_err(|details| BackendError::InvalidConfig {
+                backend: "K8sJob",
+                details: format!(
+                    "Invalid image format: {image}. Expected format: 'name:tag' or \
+                     'name@sha256:<digest>': {details}"
+                ),
+            })?;
+        let setting = |key: &str| config.backend_specific.get(key).cloned();
+        let invalid = |details: String| BackendError::InvalidConfig {
+            backend: "K8sJob",
+            details,
+        };
+
+        let program = match setting(KUBECTL_KEY) {
+            Some(program) => program,
+            None => installed_runtime()
+                .ok_or_else(|| BackendError::NotAvailable {
+                    backend: "K8sJob",
+                    reason: "kubectl is not installed".to_string(),
+                })?
+                .to_string(),
+        };
+        let namespace = setting(K8S_NAMESPACE_KEY).unwrap_or_else(|| DEFAULT_NAMESPACE.to_string());
+        if !is_dns_label(&namespace) {
+            return Err(invalid(format!("Invalid namespace '{namespace}'")));
+        }
+        let cpu = setting(K8S_CPU_KEY);
+        if let Some(cpu) = &cpu
+            && !is_cpu_quantity(cpu)
+        {
+            return Err(invalid(format!(
+                "Invalid CPU quantity '{cpu}'; expected cores such as '2' or millicores such as \
+                 '500m'"
+            )));
+        }
+        let pull_secret = setting(K8S_IMAGE_PULL_SECRET_KEY);
+        if let Some(secret) = &pull_secret
+            && secret.is_empty()
+        {
+            return Err(invalid("Image pull secret name cannot be empty".to_string()));
+        }
+        let context = setting(K8S_CONTEXT_KEY);
+
+        Ok(Self {
+            kubectl: Kubectl {
+                program,
+                context,
+                namespace,
+            },
+            image,
+            reference,
+            cpu,
+            pull_secret,
+            instance_id: ids::generate("k8sjob"),
+            config,
+        })
+    }
+
+    /// kubectl program this backend drives
+    pub fn kubectl(&self) -> &str {
+        &self.kubectl.program
+    }
+
+    /// Namespace jobs run in
+    pub fn namespace(&self) -> &str {
+        &self.kubectl.namespace
+    }
+
+    fn job_options(&self) -> JobOptions {
+        JobOptions {
+            image: self.image.clone(),
+            owner_id: self.config.owner_id.clone(),
+            instance_id: self.instance_id.clone(),
+            cpu: self.cpu.clone(),
+            pull_secret: self.pull_secret.clone(),
+            provisioning: self.config.provisioning.clone(),
+        }
+    }
+}
+
+impl ExecutionBackend for K8sJobBackend {
+    fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
+        let kubectl = self.kubectl.clone();
+        let mut options = self.job_options();
+        let configured = self.reference.clone();
+        let backend_name = self.backend_type();
+
+        AsyncTaskBuilder::new(async move {
+            if let Err(e) = python_env::reject_in_guest(&request, backend_name)
+                .and_then(|()| desktop::reject_in_guest(&request, backend_name))
+                .and_then(|()| io_throttle::reject_unthrottled(&request, backend_name))
+                .and_then(|()| cpuset::reject_unpinnable(&request, backend_name))
+                .and_then(|()| input_stream::reject_unpiped(&request, backend_name))
+                .and_then(|()| arch::check(&request, backend_name))
+            {
+                return ExecutionResult::failure(-1, e.to_string());
+            }
+
+            // A pinned runtime version runs the image tagged with it
+            let reference = match configured.for_runtime(&request.language, backend_name) {
+                Ok(runtime_reference) => runtime_reference,
+                Err(e) => return ExecutionResult::failure(-1, e.to_string()),
+            };
+            if reference != configured {
+                options.image = reference.to_string();
+            }
+
+            // The kubelet pulls the image, by digest when one is pinned
+            match execution::execute_job(kubectl, options, request).await {
+                Ok(Ok(mut result)) => {
+                    result
+                        .metadata
+                        .insert(IMAGE_REFERENCE_METADATA.to_string(), reference.to_string());
+                    result
+                }
+                Ok(Err(e)) => {
+                    ExecutionResult::failure(-1, format!("{backend_name} execution failed: {e}"))
+                }
+                Err(e) => ExecutionResult::failure(
+                    -1,
+                    format!("{backend_name} execution task failed: {e}"),
+                ),
+            }
+        })
+        .spawn()
+    }
+
+    fn health_check(&self, level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
+        let kubectl = self.kubectl.clone();
+        let mut options = self.job_options();
+        let image = self.image.clone();
+
+        AsyncTaskBuilder::new(async move {
+            // Answers yes only once the cluster is reachable and the
+            // credentials may create jobs in the namespace
+            let namespace = kubectl.namespace.clone();
+            let allowed = kubectl.output(&["auth", "can-i", "create", "jobs"], None).await;
+            if let Err(e) = allowed.and_then(|answer| match answer.trim() {
+                "yes" => Ok(()),
+                other => Err(BackendError::NotAvailable {
+                    backend: "K8sJob",
+                    reason: format!("kubectl answered '{other}'"),
+                }),
+            }) {
+                return HealthStatus::unhealthy(format!(
+                    "Cannot create jobs in namespace {namespace}: {e}"
+                ))
+                .with_metric("namespace", &namespace)
+                .with_metric("cluster_reachable", "false");
+            }
+
+            if level == HealthCheckLevel::Liveness {
+                return HealthStatus::healthy("Cluster accepts jobs")
+                    .with_metric("namespace", &namespace)
+                    .with_metric("cluster_reachable", "true");
+            }
+
+            // Test a job end to end; the pull is bounded like any other, so
+            // an image not cached on a node may fail the probe
+            let test_request = ExecutionRequest::new("echo 'health check'", "bash")
+                .with_timeout(Duration::from_secs(10));
+            options.provisioning.pull_timeout = Some(Duration::from_secs(60));
+            match execution::execute_job(kubectl, options, test_request).await {
+                Ok(Ok(result)) if result.is_success() => {
+                    HealthStatus::healthy("K8sJob backend operational")
+                        .with_metric("namespace", &namespace)
+                        .with_metric("cluster_reachable", "true")
+                        .with_metric("test_execution", "success")
+                        .with_metric("image", &image)
+                }
+                Ok(Ok(result)) => {
+                    HealthStatus::unhealthy(format!("Test execution failed: {}", result.stdout))
+                        .with_metric("test_execution", "failed")
+                        .with_metric("exit_code", result.exit_code.to_string())
+                }
+                Ok(Err(e)) => HealthStatus::unhealthy(format!("Health check execution error: {e}"))
+                    .with_metric("test_execution", "error"),
+                Err(e) => HealthStatus::unhealthy(format!("Health check task error: {e}"))
+                    .with_metric("test_execution", "task_error"),
+            }
+        })
+        .spawn()
+    }
+
+    fn cleanup(&self) -> AsyncTask<crate::execution_env::CyloResult<()>> {
+        let kubectl = self.kubectl.clone();
+        let selector = format!(
+            "{OWNER_LABEL}={},{INSTANCE_LABEL}={}",
+            owner_label(&self.config.owner_id),
+            self.instance_id
+        );
+
+        AsyncTaskBuilder::new(async move {
+            // Objects of this instance's finished jobs, whose execution died
+            // before deleting them or which wait out their TTL, and of jobs
+            // already removed; recent ones may still be read and are left
+            let list = |kinds: &'static str| {
+                let kubectl = kubectl.clone();
+                let selector = selector.clone();
+                async move {
+                    let listed = kubectl
+                        .output(&["get", kinds, "--selector", &selector, "--output", "json"], None)
+                        .await
+                        .ok()?;
+                    serde_json::from_str::<Value>(&listed).ok()
+                }
+            };
+            if let (Some(jobs), Some(others)) =
+                (list("jobs").await, list("configmaps,networkpolicies").await)
+            {
+                let now = chrono::Utc::now();
+                for name in execution::stale_executions(&jobs, &others, now) {
+                    kubectl.delete_execution(&name);
+                }
+            }
+
+            Ok(())
+        })
+        .spawn()
+    }
+
+    fn get_config(&self) -> &BackendConfig {
+        &self.config
+    }
+
+    fn backend_type(&self) -> &'static str {
+        "K8sJob"
+    }
+
+    fn supports_language(&self, language: &str) -> bool {
+        let (name, _) = language::split_version(language);
+        self.supported_languages().contains(&name)
+    }
+
+    fn supported_languages(&self) -> &[&'static str] {
+        &[
+            "python",
+            "python3",
+            "javascript",
+            "js",
+            "node",
+            "rust",
+            "bash",
+            "sh",
+            "go",
+            "r",
+            "R",
+            "rscript",
+            "sql",
+            "sqlite",
+            "sqlite3",
+            "duckdb",
+        ]
+    }
+}
+
+/// Whether `name` is a DNS-1123 label, as namespaces must be
+fn is_dns_label(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 63
+        && name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+}
+
+/// Whether `quantity` is a positive CPU quantity: cores, optionally with a
+/// fraction, or whole millicores
+fn is_cpu_quantity(quantity: &str) -> bool {
+    let (number, fraction_allowed) = match quantity.strip_suffix('m') {
+        Some(millicores) => (millicores, false),
+        None => (quantity, true),
+    };
+    let valid = match number.split_once('.') {
+        Some((whole, fraction)) => {
+            fraction_allowed
+                && [whole, fraction]
+                    .iter()
+                    .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        }
+        None => !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()),
+    };
+    valid && number.bytes().any(|b| matches!(b, b'1'..=b'9'))
+}
@@ -0,0 +1,50 @@
+// ============================================================================
+// File: packages/cylo/src/backends/kubernetes/tests.rs
+// ----------------------------------------------------------------------------
+// Tests for the Kubernetes Job backend.
+// ============================================================================
+
+use std::time::Duration;
+
+use crate::backends::{BackendConfig, ExecutionBackend};
+
+use super::{K8S_CPU_KEY, K8S_NAMESPACE_KEY, K8sJobBackend, KUBECTL_KEY};
+
+#[test]
+fn backend_creation_reads_cluster_config() {
+    let config = BackendConfig::new("test_k8s")
+        .with_timeout(Duration::from_secs(60))
+        .with_config(KUBECTL_KEY, "/usr/local/bin/kubectl");
+
+    let backend = K8sJobBackend::new("python:3.12-alpine".to_string(), config.clone())
+        .expect("test should successfully create k8s job backend");
+    assert_eq!(backend.kubectl(), "/usr/local/bin/kubectl");
+    assert_eq!(backend.namespace(), "default");
+    assert_eq!(backend.backend_type(), "K8sJob");
+    assert!(backend.supports_language("python"));
+    assert!(backend.supports_language("go"));
+    assert!(!backend.supports_language("cobol"));
+
+    let scoped = config
+        .clone()
+        .with_config(K8S_NAMESPACE_KEY, "cylo-jobs")
+        .with_config(K8S_CPU_KEY, "500m");
+    let backend = K8sJobBackend::new("python:3.12-alpine".to_string(), scoped).unwrap();
+    assert_eq!(backend.namespace(), "cylo-jobs");
+
+    // Invalid settings should fail
+    for (key, value) in [
+        (K8S_NAMESPACE_KEY, "Cylo_Jobs"),
+        (K8S_CPU_KEY, "0"),
+        (K8S_CPU_KEY, "1.5m"),
+        (K8S_CPU_KEY, "two"),
+    ] {
+        let invalid = config.clone().with_config(key, value);
+        assert!(K8sJobBackend::new("python:3.12-alpine".to_string(), invalid).is_err());
+    }
+    assert!(K8sJobBackend::new("invalid".to_string(), config.clone()).is_err());
+    assert!(
+        K8sJobBackend::new("python:3.12".to_string(), config.with_config(K8S_CPU_KEY, "1.5"))
+            .is_ok()
+    );
+}
@@ -7,23 +7,60 @@
 // - Sandboxed process spawning with bubblewrap
 // - Language-specific command preparation
 // - Resource limiting and monitoring
-// - Timeout handling and process cleanup
+// - Graceful timeout handling with partial output capture
+// - Early termination of stalled executions
 // ============================================================================
 
 use std::collections::HashMap;
+use std::os::unix::process::CommandExt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
 use crate::async_task::AsyncTaskBuilder;
 use crate::backends::AsyncTask;
-use crate::backends::{BackendError, BackendResult, ExecutionRequest, ExecutionResult, ResourceUsage};
+use crate::backends::cgroup::CgroupSlice;
+use crate::backends::cpuset;
+use crate::backends::input_stream;
+use crate::backends::process::{self, CapturedOutput, OutputCapture, WaitOutcome};
+use crate::backends::watchdog::Watchdog;
+use crate::backends::python_env::PythonEnv;
+use crate::backends::sampler::global_sampler;
+use crate::backends::{
+    Arch, SqlEngine, SqlOptions, arch, archive, clock, compile_phase, compiler, crash, dotnet,
+    git_checkout, go_cache, language, paths, r_library, retention, runtime, sql, wasm_module,
+};
+use crate::backends::{
+    ARCH_METADATA, BackendError, BackendResult, CLOCK_METADATA, CORE_DUMP_METADATA,
+    CompilerOptions, CrashReport, ExecutionOutcome, ExecutionRequest,
+    ExecutionResult, IsolationLevel, SIGNAL_METADATA, SecurityReport,
+};
+use crate::reaper::{DEADLINE_GRACE, ResourceKind, global_reaper};
+#[cfg(feature = "toolchains")]
+use crate::toolchains;
 
 use super::jail::JailEnvironment;
-use super::monitoring::{
-    count_process_tree, get_disk_io_stats, get_disk_read_stats, get_memory_usage,
-    get_process_cpu_time,
-};
+use super::monitoring::get_process_tree_cpu_time;
+
+/// Host directories bound read-only into the sandbox
+const SANDBOX_SYSTEM_DIRS: &[&str] = &["/usr", "/bin", "/sbin"];
+
+/// `PATH` entries whose binaries are visible inside the sandbox, followed
+/// by the installed toolchains bound into it
+pub(super) fn sandbox_search_path() -> Vec<PathBuf> {
+    #[allow(unused_mut)]
+    let mut search_path: Vec<PathBuf> = runtime::host_search_path()
+        .into_iter()
+        .filter(|dir| SANDBOX_SYSTEM_DIRS.iter().any(|root| dir.starts_with(root)))
+        .collect();
+    #[cfg(feature = "toolchains")]
+    search_path.extend(
+        toolchains::activated_toolchains()
+            .into_iter()
+            .map(|toolchain| toolchain.bin_dir),
+    );
+    search_path
+}
 
 /// Sandboxed code executor using bubblewrap and LandLock
 pub struct SandboxedExecutor;
@@ -35,6 +72,10 @@ impl SandboxedExecutor {
     /// * `jail_path` - Base jail directory
     /// * `request` - Execution request
     /// * `exec_dir` - Execution directory path
+    /// * `slice` - cgroup the sandbox is started in, if the instance has one
+    /// * `retention` - How long the execution directory is kept if it fails
+    /// * `share_host_ipc` - Keep the host's IPC namespace and /dev/shm
+    ///   instead of private ones
     ///
     /// # Returns
     /// AsyncTask that resolves to execution result
@@ -42,12 +83,60 @@ impl SandboxedExecutor {
         jail_path: PathBuf,
         request: ExecutionRequest,
         exec_dir: PathBuf,
+        slice: Option<CgroupSlice>,
+        retention: Duration,
+        share_host_ipc: bool,
     ) -> AsyncTask<BackendResult<ExecutionResult>> {
         AsyncTaskBuilder::new(async move {
             let start_time = Instant::now();
 
-            // Prepare execution command
-            let (program, args) = Self::prepare_command(&request.language, &exec_dir)?;
+            // Prepare execution command; a foreign architecture is
+            // cross-compiled and run through the qemu-user binfmt handler
+            if let Err(e) = arch::check(&request, "LandLock") {
+                JailEnvironment::cleanup(&exec_dir);
+                return Err(e);
+            }
+            let cross = request.arch.filter(|arch| !arch.is_native());
+            let venv = match PythonEnv::of(&request, "LandLock") {
+                Ok(venv) => venv,
+                Err(e) => {
+                    JailEnvironment::cleanup(&exec_dir);
+                    return Err(e);
+                }
+            };
+            if let Some(repo) = &request.git_repo
+                && let Err(e) = git_checkout::checkout(repo, &exec_dir, &request).await
+            {
+                JailEnvironment::cleanup(&exec_dir);
+                return Err(e);
+            }
+            let (program, args) = Self::prepare_command(
+                &request.language,
+                request.compiler.as_ref(),
+                request.sql.as_ref(),
+                venv.as_ref(),
+                cross,
+                &exec_dir,
+            )?;
+            let exposed = match paths::exposed_paths(&request) {
+                Ok(exposed) => exposed,
+                Err(e) => {
+                    JailEnvironment::cleanup(&exec_dir);
+                    return Err(e);
+                }
+            };
+            let faketime = match (&request.clock, clock::find_libfaketime()) {
+                (Some(clock), Some(library)) => clock.faketime_env(&library),
+                (Some(_), None) => {
+                    JailEnvironment::cleanup(&exec_dir);
+                    return Err(BackendError::NotAvailable {
+                        backend: "LandLock",
+                        reason: "a virtual clock needs libfaketime, which is not installed"
+                            .to_string(),
+                    });
+                }
+                (None, _) => Vec::new(),
+            };
 
             // Build sandboxed command using bwrap (bubblewrap)
             let mut cmd = Command::new("bwrap");
@@ -79,11 +168,67 @@ impl SandboxedExecutor {
                 exec_dir.to_str().unwrap_or(""),
                 "/workspace", // Writable workspace
                 "--chdir",
-                "/workspace",    // Change to workspace
-                "--unshare-all", // Unshare all namespaces
-                "--share-net",   // Share network (if needed)
+                "/workspace", // Change to workspace
+                // Every namespace but the network's (shared if needed) and
+                // IPC's, which is decided below. The user namespace maps
+                // the sandbox back to the caller's UID, so to the kernel
+                // every sandbox is still the caller: sockets in shared
+                // paths and on the shared network are not told apart by UID
+                "--unshare-user-try",
+                "--unshare-pid",
+                "--unshare-uts",
+                "--unshare-cgroup-try",
             ]);
 
+            // SysV IPC and POSIX message queues live in the IPC namespace,
+            // POSIX shared memory in /dev/shm
+            if share_host_ipc {
+                cmd.args(["--bind", "/dev/shm", "/dev/shm"]);
+            } else {
+                cmd.args(["--unshare-ipc", "--tmpfs", "/dev/shm"]);
+            }
+
+            // Replace name resolution with the request's DNS policy
+            if request.dns.is_some() {
+                let dns_dir = JailEnvironment::dns_dir(&exec_dir);
+                for file in ["resolv.conf", "hosts"] {
+                    cmd.arg("--ro-bind")
+                        .arg(dns_dir.join(file))
+                        .arg(format!("/etc/{file}"));
+                }
+            }
+
+            // Host paths the request exposes; nothing outside the binds
+            // exists in the sandbox, so the binds are the allowlist
+            for path in &exposed {
+                cmd.arg(if path.writable { "--bind" } else { "--ro-bind" })
+                    .arg(&path.source)
+                    .arg(&path.target);
+            }
+            // Display, audio and bus sockets only for requests granted them
+            for (path, device) in request.desktop.host_paths() {
+                cmd.arg(if device { "--dev-bind" } else { "--ro-bind" })
+                    .arg(&path)
+                    .arg(&path);
+            }
+            // The venv's interpreter links to the system Python bound above
+            if let Some(venv) = &venv {
+                cmd.arg("--ro-bind").arg(venv.root()).arg(venv.root());
+            }
+            // Installed toolchains run from where they were installed, as
+            // `sandbox_search_path` resolves them
+            #[cfg(feature = "toolchains")]
+            for toolchain in toolchains::activated_toolchains() {
+                let dir = toolchain.install_dir();
+                cmd.arg("--ro-bind").arg(dir).arg(dir);
+            }
+
+            // Set inside the sandbox rather than on bwrap, whose loader
+            // drops LD_PRELOAD when it runs setuid
+            for (key, value) in &faketime {
+                cmd.args(["--setenv", key, value]);
+            }
+
             // Add resource limits
             if let Some(memory) = request.limits.max_memory {
                 // Convert to MB for ulimit
@@ -92,11 +237,13 @@ impl SandboxedExecutor {
                     "--",
                     "bash",
                     "-c",
+                    // Arguments are quoted, so `bash -c` scripts and SQL
+                    // seed statements reach the program intact
                     &format!(
                         "ulimit -v {} && exec {} {}",
                         memory_mb,
                         program,
-                        args.join(" ")
+                        compiler::shell_args(&args)
                     ),
                 ]);
             } else {
@@ -105,6 +252,56 @@ impl SandboxedExecutor {
                 cmd.args(&args);
             }
 
+            // The sandbox inherits this process's environment, which on a
+            // desktop points at the display, sound server and session bus
+            for var in request.desktop.stripped_vars() {
+                cmd.env_remove(var);
+            }
+
+            // Normalized environment, Go caches, R library and .NET state
+            // first, so request variables win
+            for (key, value) in request.environment.variables("/workspace") {
+                cmd.env(key, value);
+            }
+            for (key, value) in go_cache::go_env(&request, "/workspace/.cache") {
+                cmd.env(key, value);
+            }
+            for (key, value) in r_library::r_env(&request, "/workspace/.cache") {
+                cmd.env(key, value);
+            }
+            for (key, value) in dotnet::dotnet_env(&request, "/workspace/.cache") {
+                cmd.env(key, value);
+            }
+            if let Some(cross) = cross {
+                cmd.env("GOARCH", cross.go_arch());
+                // Dynamically linked Rust binaries load the target's libc
+                cmd.env("QEMU_LD_PREFIX", cross.cross_sysroot());
+            }
+            if let Some(venv) = &venv {
+                let path = std::env::var("PATH").ok();
+                for (key, value) in venv.env_vars(path.as_deref()) {
+                    cmd.env(key, value);
+                }
+            }
+            let umask = request.environment.umask as libc::mode_t;
+            // Cores are written only when requested, and never past the cap
+            let core_limit = request.core_dump_limit.unwrap_or(0) as libc::rlim_t;
+            // SAFETY: umask(2) and setrlimit(2) are async-signal-safe; bwrap
+            // and the code inherit the mask and limit across exec
+            unsafe {
+                cmd.pre_exec(move || {
+                    libc::umask(umask);
+                    let limit = libc::rlimit {
+                        rlim_cur: core_limit,
+                        rlim_max: core_limit,
+                    };
+                    if libc::setrlimit(libc::RLIMIT_CORE, &limit) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    Ok(())
+                });
+            }
+
             // Set environment variables
             for (key, value) in &request.env_vars {
                 cmd.env(key, value);
@@ -115,73 +312,34 @@ impl SandboxedExecutor {
             cmd.stderr(Stdio::piped());
             cmd.stdin(Stdio::piped());
 
+            // Lead a new process group so timeouts can signal the whole tree
+            cmd.process_group(0);
+            if let Some(slice) = &slice {
+                slice.attach(&mut cmd);
+            }
+            // Keep the sandbox on its CPUs and NUMA nodes once it is in the
+            // slice, whose cpuset then stops the code from widening them
+            let limits = &request.limits;
+            cpuset::pin(&mut cmd, limits.cpuset.as_ref(), limits.numa_nodes.as_ref());
+
             // Spawn the process
             let mut child = cmd.spawn().map_err(|e| BackendError::ProcessFailed {
                 details: format!("Failed to spawn sandboxed process: {}", e),
             })?;
 
-            // Start background resource monitoring task
+            // Sample resource usage alongside every other running sandbox
             let pid = child.id();
-            let (tx, mut rx) = tokio::sync::oneshot::channel();
-
-            #[cfg(target_os = "linux")]
-            let monitor_handle = tokio::spawn(async move {
-                let mut peak_memory = 0u64;
-                let mut final_cpu_time = 0u64;
-                let mut final_process_count = 1usize;
-                let mut final_disk_written = 0u64;
-                let mut final_disk_read = 0u64;
-
-                loop {
-                    // Poll every 100ms
-                    tokio::select! {
-                        _ = tokio::time::sleep(Duration::from_millis(100)) => {
-                            // Track peak memory
-                            if let Ok(mem) = get_memory_usage(pid) {
-                                peak_memory = peak_memory.max(mem);
-                            }
-
-                            // Track latest CPU time (cumulative)
-                            if let Ok(cpu) = get_process_cpu_time(pid) {
-                                final_cpu_time = cpu;
-                            }
-
-                            // Track process count
-                            if let Ok(count) = count_process_tree(pid) {
-                                final_process_count = count;
-                            }
-
-                            // Track disk I/O
-                            if let Ok(written) = get_disk_io_stats(pid) {
-                                final_disk_written = written;
-                            }
-                            if let Ok(read) = get_disk_read_stats(pid) {
-                                final_disk_read = read;
-                            }
-                        }
-                        _ = &mut rx => {
-                            // Stop signal received
-                            break;
-                        }
-                    }
-                }
-
-                ResourceUsage {
-                    peak_memory,
-                    cpu_time_ms: final_cpu_time,
-                    process_count: final_process_count as u32,
-                    disk_bytes_written: final_disk_written,
-                    disk_bytes_read: final_disk_read,
-                    network_bytes_sent: 0,
-                    network_bytes_received: 0,
-                }
-            });
-
-            #[cfg(not(target_os = "linux"))]
-            let monitor_handle = tokio::spawn(async move {
-                let _ = rx.await;
-                ResourceUsage::default()
-            });
+            let reaper_guard = global_reaper().track(
+                ResourceKind::Process { pid },
+                "LandLock",
+                request.execution_id.as_deref(),
+                Some(request.timeout + DEADLINE_GRACE),
+            );
+            let usage_watch = global_sampler().watch(pid);
+
+            // Capture output incrementally so it survives a forced kill, and
+            // stream it to the request's sink as it arrives
+            let capture = OutputCapture::streaming(&mut child, request.output_sink.clone());
 
             // Write input if provided
             if let Some(input) = &request.input {
@@ -195,108 +353,289 @@ impl SandboxedExecutor {
                         })?;
                 }
             }
+            let _input_pump = input_stream::pipe(&mut child, &request)?;
 
-            // Wait for completion with timeout
+            // Wait for completion; on timeout or stall send SIGTERM, allow
+            // the grace period, then SIGKILL. The sandbox leads its own
+            // process group so both signals reach every process inside it.
             let timeout_duration = request.timeout;
-            let child_id = child.id();
-            let result =
-                tokio::time::timeout(timeout_duration, async { child.wait_with_output() }).await;
-
-            let output = match result {
-                Ok(Ok(output)) => output,
-                Ok(Err(e)) => {
-                    return Err(BackendError::ProcessFailed {
-                        details: format!("Process execution failed: {}", e),
-                    });
-                }
-                Err(_) => {
-                    // Kill the process on timeout using saved PID
-                    #[cfg(target_os = "linux")]
-                    {
-                        use nix::sys::signal::{kill, Signal};
-                        use nix::unistd::Pid;
-                        let _ = kill(Pid::from_raw(child_id as i32), Signal::SIGKILL);
-                    }
-                    #[cfg(not(target_os = "linux"))]
-                    {
-                        let _ = child_id; // Suppress unused warning
-                    }
-                    return Err(BackendError::ExecutionTimeout {
-                        seconds: timeout_duration.as_secs(),
-                    });
-                }
-            };
+            let watchdog = request.stall_timeout.map(|idle| {
+                Watchdog::new(idle, || {
+                    capture.bytes_captured() + get_process_tree_cpu_time(pid).unwrap_or(0)
+                })
+            });
+            let outcome = process::wait_supervised(
+                &mut child,
+                timeout_duration,
+                request.termination_grace,
+                watchdog,
+                || process::terminate_group(pid),
+                || process::kill_group(pid),
+            )
+            .await
+            .map_err(|e| BackendError::ProcessFailed {
+                details: format!("Process execution failed: {}", e),
+            })?;
+            // The sandbox has been waited on, so its PID may be reused
+            reaper_guard.release();
 
             let duration = start_time.elapsed();
-
-            // Stop monitoring and collect final resource statistics
-            let _ = tx.send(());
-            let resource_usage = match monitor_handle.await {
-                Ok(usage) => usage,
-                Err(_) => {
-                    // Monitoring task failed, return defaults
-                    ResourceUsage {
-                        peak_memory: 0,
-                        cpu_time_ms: 0,
-                        process_count: 1,
-                        disk_bytes_written: 0,
-                        disk_bytes_read: 0,
-                        network_bytes_sent: 0,
-                        network_bytes_received: 0,
+            let CapturedOutput { stdout, stderr, raw } =
+                capture.collect(process::OUTPUT_DRAIN).await;
+
+            // Stop sampling and collect final resource statistics
+            let resource_usage = usage_watch.finish();
+
+            // A core dump lives in the workspace, so it is moved out before
+            // the execution directory is removed
+            let mut crash_metadata = None;
+            let crash = match &outcome {
+                WaitOutcome::Exited(status) => CrashReport::from_status(status),
+                WaitOutcome::TimedOut { .. } | WaitOutcome::Stalled { .. } => None,
+            }
+            .map(|mut crash| {
+                if let Some(limit) = request.core_dump_limit
+                    && crash.dumps_core()
+                {
+                    let name = exec_dir.file_name().unwrap_or_default().to_string_lossy();
+                    if !crash::capture_core(&exec_dir, &name, limit, &mut crash) {
+                        crash_metadata = Some(crash::missing_core_reason());
                     }
                 }
-            };
+                crash
+            });
 
-            // Clean up execution directory
-            JailEnvironment::cleanup(&exec_dir);
-
-            Ok(ExecutionResult {
-                exit_code: output.status.code().unwrap_or(-1),
-                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-                duration,
-                resource_usage,
-                metadata: {
-                    let mut meta = HashMap::new();
-                    meta.insert("backend".to_string(), "LandLock".to_string());
-                    meta.insert("jail_path".to_string(), jail_path.display().to_string());
-                    meta.insert("exec_dir".to_string(), exec_dir.display().to_string());
-                    meta
+            let mut result = match outcome {
+                WaitOutcome::Exited(status) => ExecutionResult {
+                    exit_code: status.code().unwrap_or(-1),
+                    stdout,
+                    stderr,
+                    duration,
+                    resource_usage,
+                    metadata: HashMap::new(),
+                    verdict: None,
+                    outcome: ExecutionOutcome::Completed,
+                    security: None,
+                    cost: None,
+                    crash,
+                    result_sets: None,
+                    workspace_archive: None,
+                    blobs: HashMap::new(),
+                    processed: None,
+                    raw_output: None,
+                    compilation: None,
                 },
-            })
+                WaitOutcome::TimedOut { graceful } | WaitOutcome::Stalled { graceful } => {
+                    let mut result = match request.stall_timeout {
+                        Some(idle) if matches!(outcome, WaitOutcome::Stalled { .. }) => {
+                            ExecutionResult::stalled(stdout, stderr, idle)
+                        }
+                        _ => ExecutionResult::timed_out(stdout, stderr, timeout_duration),
+                    };
+                    result.duration = duration;
+                    result.resource_usage = resource_usage;
+                    result.metadata.insert(
+                        "termination".to_string(),
+                        if graceful { "graceful" } else { "forced" }.to_string(),
+                    );
+                    result
+                }
+            };
+
+            result.raw_output = raw;
+            // Clean up execution directory once the output archive is packed,
+            // unless a failed execution's directory is kept for inspection
+            archive::attach_output(&request, &exec_dir, &mut result);
+            let mut workspace = vec![exec_dir.clone()];
+            let dns_dir = JailEnvironment::dns_dir(&exec_dir);
+            if dns_dir.exists() {
+                workspace.push(dns_dir);
+            }
+            if !retention::retain_on_failure(retention, workspace, "LandLock", &mut result) {
+                JailEnvironment::cleanup(&exec_dir);
+            }
+
+            // bubblewrap shares the host network; system directories are
+            // bound read-only and only the workspace and writable paths are
+            // writable
+            result.security = Some(SecurityReport {
+                isolation: IsolationLevel::Namespace,
+                network_disabled: false,
+                filesystem_read_only: request.writable_paths.is_empty(),
+                memory_limit_bytes: request.limits.max_memory,
+                ipc_isolated: !share_host_ipc,
+                // Abstract sockets such as X11's are reachable over the
+                // shared network namespace
+                ui_restricted: false,
+            });
+            result.metadata.insert("backend".to_string(), "LandLock".to_string());
+            result
+                .metadata
+                .insert("jail_path".to_string(), jail_path.display().to_string());
+            result
+                .metadata
+                .insert("exec_dir".to_string(), exec_dir.display().to_string());
+            if let Some(slice) = &slice {
+                result
+                    .metadata
+                    .insert("cgroup".to_string(), slice.path().display().to_string());
+            }
+            if let Some(arch) = request.arch {
+                result.metadata.insert(ARCH_METADATA.to_string(), arch.to_string());
+            }
+            if let Some(dns) = &request.dns {
+                dns.record(&mut result);
+            }
+            if let Some(clock) = &request.clock {
+                result
+                    .metadata
+                    .insert(CLOCK_METADATA.to_string(), clock.mode().to_string());
+            }
+            if let Some(crash) = &result.crash {
+                result
+                    .metadata
+                    .insert(SIGNAL_METADATA.to_string(), crash.signal_name.clone());
+            }
+            if let Some(reason) = crash_metadata {
+                result.metadata.insert(CORE_DUMP_METADATA.to_string(), reason);
+            }
+
+            Ok(result)
         }).spawn()
     }
 
     /// Prepare execution command for specific language
     ///
+    /// A pinned version (`python@3.12`) selects a matching runtime from the
+    /// system directories bound into the sandbox; a virtual environment
+    /// replaces the Python interpreter with its own. C# gets its generated
+    /// project written into the execution directory.
+    ///
     /// # Arguments
-    /// * `language` - Programming language
+    /// * `language` - Programming language, optionally pinned as `name@version`
+    /// * `compiler` - Compiler options for compiled languages
+    /// * `sql` - Engine and seeds of a SQL request
+    /// * `venv` - Virtual environment of a Python request
+    /// * `cross` - Foreign architecture Rust is cross-compiled for
     /// * `exec_dir` - Execution directory path
     ///
     /// # Returns
     /// Command program and arguments
     fn prepare_command(
         language: &str,
-        _exec_dir: &Path,
+        compiler: Option<&CompilerOptions>,
+        sql: Option<&SqlOptions>,
+        venv: Option<&PythonEnv>,
+        cross: Option<Arch>,
+        exec_dir: &Path,
     ) -> BackendResult<(String, Vec<String>)> {
-        match language.to_lowercase().as_str() {
-            "python" | "python3" => Ok(("python3".to_string(), vec!["main.py".to_string()])),
-            "javascript" | "js" | "node" => Ok(("node".to_string(), vec!["main.js".to_string()])),
+        let spec = language::resolve(language).ok_or_else(|| BackendError::UnsupportedLanguage {
+            backend: "LandLock",
+            language: language.to_string(),
+        })?;
+        let search_path = sandbox_search_path();
+        let pinned = runtime::resolve_runtime("LandLock", language, &search_path)?;
+        let program = |default: &str| match &pinned {
+            Some(runtime) => runtime.program.display().to_string(),
+            None => default.to_string(),
+        };
+
+        let options = compiler.cloned().unwrap_or_default();
+
+        match spec.name {
+            "python" => {
+                let interpreter = match venv {
+                    Some(venv) => venv.interpreter().display().to_string(),
+                    None => program("python3"),
+                };
+                Ok((interpreter, vec!["main.py".to_string()]))
+            }
+            "javascript" => Ok((program("node"), vec!["main.js".to_string()])),
             "rust" => {
-                // Compile and run Rust code
+                // Compile and run Rust code; the linker flag is ours, not
+                // the request's, so the compiler allowlist does not apply
+                let target = match cross {
+                    Some(arch) => format!(
+                        "--target {} -C linker={} ",
+                        arch.rust_target(),
+                        arch.cross_linker()
+                    ),
+                    None => String::new(),
+                };
                 Ok((
                     "bash".to_string(),
                     vec![
                         "-c".to_string(),
-                        "rustc main.rs -o main && ./main".to_string(),
+                        compile_phase::compile_then_run(
+                            &format!(
+                                "{} {}{}main.rs -o main",
+                                program("rustc"),
+                                target,
+                                compiler::shell_args(&options.rustc_args())
+                            ),
+                            "./main",
+                        ),
                     ],
                 ))
             }
-            "bash" | "sh" => Ok(("bash".to_string(), vec!["code".to_string()])),
+            "bash" => Ok((program("bash"), vec!["code".to_string()])),
             "go" => Ok((
                 "bash".to_string(),
-                vec!["-c".to_string(), "go run main.go".to_string()],
+                vec![
+                    "-c".to_string(),
+                    compile_phase::compile_then_run(
+                        &format!(
+                            "{} build -o main {}main.go",
+                            program("go"),
+                            compiler::shell_args(&options.go_args())
+                        ),
+                        "./main",
+                    ),
+                ],
+            )),
+            "r" => Ok((
+                "bash".to_string(),
+                vec![
+                    "-c".to_string(),
+                    r_library::rscript_command(&program("Rscript"), "main.R"),
+                ],
             )),
+            "sql" => {
+                let engine = SqlEngine::of(language, sql);
+                let args = sql::shell_args(engine, sql, "main.sql")
+                    .map_err(|details| BackendError::InvalidConfig {
+                        backend: "LandLock",
+                        details,
+                    })?;
+                Ok((engine.program().to_string(), args))
+            }
+            // Zig's global cache defaults to the user's home
+            "zig" => Ok((
+                program("zig"),
+                vec![
+                    "run".to_string(),
+                    "--global-cache-dir".to_string(),
+                    "/workspace/.cache/zig".to_string(),
+                    "main.zig".to_string(),
+                ],
+            )),
+            "csharp" => {
+                let sdk_version = match &pinned {
+                    Some(runtime) => Some(runtime.version.clone()),
+                    None => runtime::installed_runtimes("csharp", &search_path)
+                        .into_iter()
+                        .next()
+                        .map(|runtime| runtime.version),
+                };
+                dotnet::write_project(exec_dir, sdk_version.as_deref())?;
+                Ok((program("dotnet"), dotnet::run_args(Path::new("/workspace"))))
+            }
+            // Unpinned Lua runs whichever of lua and luajit is installed
+            "lua" => Ok((
+                program(runtime::toolchain_program("lua", &search_path).unwrap_or("lua")),
+                vec!["main.lua".to_string()],
+            )),
+            "wasm" => Ok((program("wasmtime"), wasm_module::wasmtime_args("main.wasm"))),
             _ => Err(BackendError::UnsupportedLanguage {
                 backend: "LandLock",
                 language: language.to_string(),
@@ -322,22 +661,61 @@ impl SandboxedExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backends::OptLevel;
 
     #[test]
     fn command_preparation() {
         let exec_dir = PathBuf::from("/tmp/test");
+        let prepare = |language: &str, compiler: Option<&CompilerOptions>| {
+            SandboxedExecutor::prepare_command(language, compiler, None, None, None, &exec_dir)
+        };
 
-        let (prog, args) = SandboxedExecutor::prepare_command("python", &exec_dir)
+        let (prog, args) = prepare("python", None)
             .expect("test should successfully prepare python execution command");
         assert_eq!(prog, "python3");
         assert_eq!(args, vec!["main.py"]);
 
-        let (prog, args) = SandboxedExecutor::prepare_command("rust", &exec_dir)
+        let release = CompilerOptions::default().with_opt_level(OptLevel::Full);
+        let (prog, args) = prepare("rust", Some(&release))
             .expect("test should successfully prepare rust execution command");
         assert_eq!(prog, "bash");
-        assert!(args[1].contains("rustc"));
-
-        let unsupported = SandboxedExecutor::prepare_command("cobol", &exec_dir);
+        assert!(args[1].contains("rustc -C opt-level=2 main.rs"), "{}", args[1]);
+
+        let (_, args) = SandboxedExecutor::prepare_command(
+            "rust",
+            None,
+            None,
+            None,
+            Some(Arch::Arm64),
+            &exec_dir,
+        )
+        .expect("test should successfully prepare cross-compiled rust command");
+        assert!(
+            args[1].contains("--target aarch64-unknown-linux-gnu -C linker=aarch64-linux-gnu-gcc"),
+            "{}",
+            args[1]
+        );
+
+        let (prog, args) = prepare("duckdb", None)
+            .expect("test should successfully prepare sql execution command");
+        assert_eq!(prog, "duckdb");
+        assert_eq!(args.last().map(String::as_str), Some(".read 'main.sql'"));
+
+        let (prog, args) = prepare("zig", None)
+            .expect("test should successfully prepare zig execution command");
+        assert_eq!(prog, "zig");
+        assert_eq!(args.first().map(String::as_str), Some("run"));
+        assert_eq!(args.last().map(String::as_str), Some("main.zig"));
+
+        let (prog, args) = prepare("wasi", None)
+            .expect("test should successfully prepare wasm execution command");
+        assert_eq!(prog, "wasmtime");
+        assert_eq!(args, ["run", "--dir", ".", "main.wasm"]);
+
+        let unsupported = prepare("cobol", None);
         assert!(unsupported.is_err());
+
+        let missing = prepare("python@0.0", None);
+        assert!(matches!(missing, Err(BackendError::RuntimeVersionUnavailable { .. })));
     }
 }
@@ -7,6 +7,7 @@
 // - Path validation and security checks
 // - Execution directory creation
 // - Language-specific code file setup
+// - DNS policy files bound over the sandbox's /etc
 // - Permission management
 // ============================================================================
 
@@ -14,7 +15,11 @@ use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 
-use crate::backends::{BackendError, BackendResult, ExecutionRequest};
+use crate::backends::{archive, language, wasm_module};
+use crate::backends::blob_store::write_blob_files;
+use crate::backends::paths::{confine_working_dir, write_files};
+use crate::backends::{BackendError, BackendResult, DnsPolicy, ExecutionRequest};
+use crate::ids;
 
 /// Jail environment manager
 pub struct JailEnvironment;
@@ -76,20 +81,19 @@ impl JailEnvironment {
     ///
     /// # Arguments
     /// * `jail_path` - Base jail directory
+    /// * `owner_id` - Executor identity that owns the directory
     /// * `request` - Execution request
     ///
     /// # Returns
     /// Path to execution directory within jail
     pub fn setup_environment(
         jail_path: &Path,
+        owner_id: &str,
         request: &ExecutionRequest,
     ) -> BackendResult<PathBuf> {
         // Create unique execution directory
-        let exec_id = format!(
-            "exec-{}-{}",
-            uuid::Uuid::new_v4().simple(),
-            std::process::id()
-        );
+        let exec_id =
+            ids::artifact_name("exec", owner_id, &ids::language_tag(&request.language));
         let exec_dir = jail_path.join(&exec_id);
 
         // Create execution directory
@@ -104,20 +108,81 @@ impl JailEnvironment {
             }
         })?;
 
-        // Create working directory if specified
-        if let Some(workdir) = &request.working_dir {
-            let work_path = exec_dir.join(workdir.trim_start_matches('/'));
-            fs::create_dir_all(&work_path).map_err(|e| BackendError::FileSystemFailed {
-                details: format!("Failed to create working directory: {}", e),
-            })?;
+        // Create working directory if specified, confined to the exec dir
+        if let Some(workdir) = &request.working_dir
+            && let Err(e) = confine_working_dir(&exec_dir, workdir)
+        {
+            Self::cleanup(&exec_dir);
+            return Err(e);
+        }
+
+        // The archive is the base layer the files and source file go over
+        if let Err(e) = archive::unpack_workspace(request, &exec_dir, "LandLock") {
+            Self::cleanup(&exec_dir);
+            return Err(e);
+        }
+
+        // Additional files first, so the source file wins over a namesake
+        if let Err(e) = write_files(&exec_dir, &request.files)
+            .and_then(|()| write_blob_files(&exec_dir, &request.blob_files))
+        {
+            Self::cleanup(&exec_dir);
+            return Err(e);
         }
 
         // Create language-specific code files
         Self::create_code_file(&exec_dir, request)?;
 
+        if let Some(dns) = &request.dns
+            && let Err(e) = Self::write_dns_files(&exec_dir, dns)
+        {
+            Self::cleanup(&exec_dir);
+            return Err(e);
+        }
+
         Ok(exec_dir)
     }
 
+    /// Directory holding the resolv.conf and hosts file of an execution
+    ///
+    /// It sits beside the workspace rather than inside it, so sandboxed
+    /// code cannot rewrite its own DNS policy.
+    pub fn dns_dir(exec_dir: &Path) -> PathBuf {
+        let mut dir = exec_dir.as_os_str().to_owned();
+        dir.push(".dns");
+        PathBuf::from(dir)
+    }
+
+    /// Render a DNS policy into the execution's DNS directory
+    ///
+    /// A policy that keeps the host resolvers gets a copy of the host's
+    /// resolv.conf, so both files can always be bound into the sandbox.
+    ///
+    /// # Arguments
+    /// * `exec_dir` - Execution directory
+    /// * `policy` - DNS policy of the request
+    ///
+    /// # Returns
+    /// Path to the DNS directory
+    fn write_dns_files(exec_dir: &Path, policy: &DnsPolicy) -> BackendResult<PathBuf> {
+        let dns_dir = Self::dns_dir(exec_dir);
+        let write = |name: &str, content: String| {
+            fs::write(dns_dir.join(name), content).map_err(|e| BackendError::FileSystemFailed {
+                details: format!("Failed to write {}: {}", name, e),
+            })
+        };
+
+        fs::create_dir_all(&dns_dir).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to create DNS directory: {}", e),
+        })?;
+        let resolv_conf = policy
+            .resolv_conf()
+            .unwrap_or_else(|| fs::read_to_string("/etc/resolv.conf").unwrap_or_default());
+        write("resolv.conf", resolv_conf)?;
+        write("hosts", policy.hosts())?;
+        Ok(dns_dir)
+    }
+
     /// Create code file for specific language
     ///
     /// # Arguments
@@ -127,8 +192,9 @@ impl JailEnvironment {
     /// # Returns
     /// Ok(()) if successful, Err otherwise
     fn create_code_file(exec_dir: &Path, request: &ExecutionRequest) -> BackendResult<()> {
-        match request.language.as_str() {
-            "python" | "python3" => {
+        let language = language::resolve(&request.language).map(|spec| spec.name);
+        match language {
+            Some("python") => {
                 let code_file = exec_dir.join("main.py");
                 fs::write(&code_file, &request.code).map_err(|e| {
                     BackendError::FileSystemFailed {
@@ -136,7 +202,7 @@ impl JailEnvironment {
                     }
                 })?;
             }
-            "rust" => {
+            Some("rust") => {
                 let code_file = exec_dir.join("main.rs");
                 fs::write(&code_file, &request.code).map_err(|e| {
                     BackendError::FileSystemFailed {
@@ -144,7 +210,7 @@ impl JailEnvironment {
                     }
                 })?;
             }
-            "javascript" | "js" | "node" => {
+            Some("javascript") => {
                 let code_file = exec_dir.join("main.js");
                 fs::write(&code_file, &request.code).map_err(|e| {
                     BackendError::FileSystemFailed {
@@ -152,7 +218,7 @@ impl JailEnvironment {
                     }
                 })?;
             }
-            "go" => {
+            Some("go") => {
                 let code_file = exec_dir.join("main.go");
                 fs::write(&code_file, &request.code).map_err(|e| {
                     BackendError::FileSystemFailed {
@@ -160,6 +226,59 @@ impl JailEnvironment {
                     }
                 })?;
             }
+            Some("r") => {
+                let code_file = exec_dir.join("main.R");
+                fs::write(&code_file, &request.code).map_err(|e| {
+                    BackendError::FileSystemFailed {
+                        details: format!("Failed to write R code file: {}", e),
+                    }
+                })?;
+            }
+            Some("sql") => {
+                let code_file = exec_dir.join("main.sql");
+                fs::write(&code_file, &request.code).map_err(|e| {
+                    BackendError::FileSystemFailed {
+                        details: format!("Failed to write SQL code file: {}", e),
+                    }
+                })?;
+            }
+            Some("zig") => {
+                let code_file = exec_dir.join("main.zig");
+                fs::write(&code_file, &request.code).map_err(|e| {
+                    BackendError::FileSystemFailed {
+                        details: format!("Failed to write Zig code file: {}", e),
+                    }
+                })?;
+            }
+            Some("csharp") => {
+                let code_file = exec_dir.join("main.cs");
+                fs::write(&code_file, &request.code).map_err(|e| {
+                    BackendError::FileSystemFailed {
+                        details: format!("Failed to write C# code file: {}", e),
+                    }
+                })?;
+            }
+            Some("lua") => {
+                let code_file = exec_dir.join("main.lua");
+                fs::write(&code_file, &request.code).map_err(|e| {
+                    BackendError::FileSystemFailed {
+                        details: format!("Failed to write Lua code file: {}", e),
+                    }
+                })?;
+            }
+            Some("wasm") => {
+                let module = wasm_module::module_bytes(&request.code).map_err(|details| {
+                    BackendError::InvalidConfig {
+                        backend: "LandLock",
+                        details,
+                    }
+                })?;
+                fs::write(exec_dir.join("main.wasm"), module).map_err(|e| {
+                    BackendError::FileSystemFailed {
+                        details: format!("Failed to write WebAssembly module: {}", e),
+                    }
+                })?;
+            }
             _ => {
                 // For shell scripts and other languages, write to a generic file
                 let code_file = exec_dir.join("code");
@@ -170,7 +289,7 @@ impl JailEnvironment {
                 })?;
 
                 // Make executable for shell scripts
-                if matches!(request.language.as_str(), "bash" | "sh") {
+                if language == Some("bash") {
                     fs::set_permissions(&code_file, fs::Permissions::from_mode(0o755)).map_err(
                         |e| BackendError::FileSystemFailed {
                             details: format!("Failed to set executable permissions: {}", e),
@@ -189,17 +308,20 @@ impl JailEnvironment {
     /// * `exec_dir` - Execution directory to remove
     pub fn cleanup(exec_dir: &Path) {
         let _ = fs::remove_dir_all(exec_dir);
+        let _ = fs::remove_dir_all(Self::dns_dir(exec_dir));
     }
 
-    /// Clean up leftover execution directories
+    /// Clean up leftover execution directories owned by an executor
     ///
     /// # Arguments
     /// * `jail_path` - Base jail directory
-    pub fn cleanup_all(jail_path: &Path) {
+    /// * `owner_id` - Executor identity whose directories are removed
+    pub fn cleanup_all(jail_path: &Path, owner_id: &str) {
+        let prefix = format!("exec-{}-", owner_id);
         if let Ok(entries) = fs::read_dir(jail_path) {
             for entry in entries.filter_map(Result::ok) {
                 if let Ok(file_name) = entry.file_name().into_string() {
-                    if file_name.starts_with("exec-") {
+                    if file_name.starts_with(&prefix) {
                         let _ = fs::remove_dir_all(entry.path());
                     }
                 }
@@ -223,4 +345,37 @@ mod tests {
         let relative_path = PathBuf::from("relative/path");
         assert!(JailEnvironment::validate_path(&relative_path).is_err());
     }
+
+    #[test]
+    fn cleanup_all_only_removes_owned_directories() {
+        let jail = std::env::temp_dir().join(format!("cylo_test_jail_{}", uuid::Uuid::new_v4()));
+        let owned = jail.join("exec-aaaaaaaaaaaa-1-1");
+        let foreign = jail.join("exec-bbbbbbbbbbbb-1-1");
+        fs::create_dir_all(&owned).unwrap();
+        fs::create_dir_all(&foreign).unwrap();
+
+        JailEnvironment::cleanup_all(&jail, "aaaaaaaaaaaa");
+
+        assert!(!owned.exists());
+        assert!(foreign.exists());
+        let _ = fs::remove_dir_all(&jail);
+    }
+
+    #[test]
+    fn dns_policy_is_written_outside_the_workspace() {
+        let jail = std::env::temp_dir().join(format!("cylo_test_jail_{}", uuid::Uuid::new_v4()));
+        let policy = DnsPolicy::only([("api.internal", "10.0.4.7".parse().unwrap())]);
+        let request = ExecutionRequest::new("print(1)", "python").with_dns(policy);
+
+        let exec_dir = JailEnvironment::setup_environment(&jail, "aaaaaaaaaaaa", &request).unwrap();
+        let dns_dir = JailEnvironment::dns_dir(&exec_dir);
+        assert!(!dns_dir.starts_with(&exec_dir));
+        let hosts = fs::read_to_string(dns_dir.join("hosts")).unwrap();
+        assert!(hosts.contains("api.internal"));
+        assert!(dns_dir.join("resolv.conf").exists());
+
+        JailEnvironment::cleanup(&exec_dir);
+        assert!(!dns_dir.exists());
+        let _ = fs::remove_dir_all(&jail);
+    }
 }
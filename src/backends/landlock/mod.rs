@@ -11,25 +11,47 @@
 // - Zero-overhead sandboxing
 // ============================================================================
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crate::async_task::AsyncTaskBuilder;
 use crate::backends::AsyncTask;
+use crate::backends::cgroup::{self, CGROUP_PARENT_KEY, CgroupSlice};
+use crate::backends::{language, runtime};
 use crate::backends::{
     BackendConfig, BackendResult, ExecutionBackend, ExecutionRequest,
-    ExecutionResult, HealthStatus,
+    ExecutionResult, HealthCheckLevel, HealthStatus, ProvisioningStage,
 };
 
 mod execution;
 mod features;
 mod jail;
-mod monitoring;
+pub(crate) mod monitoring;
 
 use execution::SandboxedExecutor;
 use features::{LandLockFeatures, PlatformSupport};
 use jail::JailEnvironment;
 
+/// Languages whose toolchains every supported host is expected to have
+const BASE_LANGUAGES: &[&str] = &[
+    "python",
+    "python3",
+    "javascript",
+    "js",
+    "node",
+    "rust",
+    "bash",
+    "sh",
+    "go",
+    "r",
+    "R",
+    "rscript",
+    "sql",
+    "sqlite",
+    "sqlite3",
+    "duckdb",
+];
+
 /// LandLock backend for secure code execution
 ///
 /// Uses LandLock Linux security module to provide filesystem access
@@ -44,6 +66,10 @@ pub struct LandLockBackend {
 
     /// Cached LandLock feature detection
     landlock_features: LandLockFeatures,
+
+    /// Supported languages, including optional ones whose toolchain is
+    /// visible inside the sandbox
+    languages: Vec<&'static str>,
 }
 
 impl LandLockBackend {
@@ -67,12 +93,27 @@ impl LandLockBackend {
         // Detect LandLock features
         let landlock_features = LandLockFeatures::detect()?;
 
+        let mut languages = BASE_LANGUAGES.to_vec();
+        languages.extend(runtime::detected_languages(&execution::sandbox_search_path()));
+
         Ok(Self {
             jail_path,
             config,
             landlock_features,
+            languages,
         })
     }
+
+    /// Name of this instance's cgroup slice
+    fn slice_name(&self) -> String {
+        let name: String = self
+            .config
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        format!("cylo-{}-{}", self.config.owner_id, name)
+    }
 }
 
 impl ExecutionBackend for LandLockBackend {
@@ -80,8 +121,18 @@ impl ExecutionBackend for LandLockBackend {
         let jail_path = self.jail_path.clone();
         let backend_name = self.backend_type();
 
+        request.report_progress(
+            ProvisioningStage::PreparingWorkspace,
+            None,
+            "Preparing sandbox workspace",
+        );
+
         // Setup jail environment before async block to avoid self borrow issues
-        let exec_dir = match JailEnvironment::setup_environment(&self.jail_path, &request) {
+        let exec_dir = match JailEnvironment::setup_environment(
+            &self.jail_path,
+            &self.config.owner_id,
+            &request,
+        ) {
             Ok(dir) => dir,
             Err(e) => {
                 return AsyncTaskBuilder::new(async move {
@@ -93,10 +144,63 @@ impl ExecutionBackend for LandLockBackend {
             }
         };
 
+        // Concurrent executions on this instance each get their own cgroup
+        // below the instance's slice when a delegated parent is configured
+        let slice = match self.config.backend_specific.get(CGROUP_PARENT_KEY) {
+            Some(parent) => {
+                let execution = exec_dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                match CgroupSlice::create(
+                    Path::new(parent),
+                    &self.slice_name(),
+                    &execution,
+                    &request.limits,
+                ) {
+                    Ok(slice) => Some(slice),
+                    Err(e) => {
+                        JailEnvironment::cleanup(&exec_dir);
+                        return AsyncTaskBuilder::new(async move {
+                            ExecutionResult::failure(
+                                -1,
+                                format!("Failed to create cgroup under {}: {}", parent, e),
+                            )
+                        }).spawn();
+                    }
+                }
+            }
+            // Only a cgroup's io controller can throttle the sandbox's disk
+            None if request.limits.throttles_io() => {
+                JailEnvironment::cleanup(&exec_dir);
+                return AsyncTaskBuilder::new(async move {
+                    ExecutionResult::failure(
+                        -1,
+                        format!(
+                            "Disk I/O throttling needs a delegated cgroup; set {}",
+                            CGROUP_PARENT_KEY
+                        ),
+                    )
+                }).spawn();
+            }
+            None => None,
+        };
+        request.report_progress(ProvisioningStage::Ready, None, "Sandbox prepared");
+        let retention = self.config.retain_workspace_on_failure;
+        let share_host_ipc = self.config.share_host_ipc;
+
         AsyncTaskBuilder::new(async move {
 
             // Execute with LandLock sandboxing
-            match SandboxedExecutor::execute(jail_path, request, exec_dir).await {
+            let execution = SandboxedExecutor::execute(
+                jail_path,
+                request,
+                exec_dir,
+                slice,
+                retention,
+                share_host_ipc,
+            );
+            match execution.await {
                 Ok(Ok(result)) => result,
                 Ok(Err(e)) => ExecutionResult::failure(
                     -1,
@@ -110,9 +214,10 @@ impl ExecutionBackend for LandLockBackend {
         }).spawn()
     }
 
-    fn health_check(&self) -> AsyncTask<HealthStatus> {
+    fn health_check(&self, level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
         let jail_path = self.jail_path.clone();
         let features = self.landlock_features.clone();
+        let backend = self.clone();
 
         AsyncTaskBuilder::new(async move {
             // Check LandLock availability
@@ -133,47 +238,52 @@ impl ExecutionBackend for LandLockBackend {
                     .with_metric("jail_path_valid", "false");
             }
 
-            // Test execution with simple command
-            let backend = match LandLockBackend::new(
-                jail_path.display().to_string(),
-                crate::backends::BackendConfig::new("health_check"),
-            ) {
-                Ok(backend) => backend,
-                Err(e) => {
-                    return HealthStatus::unhealthy(format!("Backend creation failed: {}", e));
-                }
+            let live = |message: &str| {
+                HealthStatus::healthy(message)
+                    .with_metric("landlock_available", "true")
+                    .with_metric("bwrap_available", "true")
+                    .with_metric("jail_path_valid", "true")
+                    .with_metric("abi_version", features.abi_version.to_string())
+                    .with_metric("access_fs", format!("0x{:x}", features.supported_access_fs))
             };
+            if level == HealthCheckLevel::Liveness {
+                return live("LandLock backend available");
+            }
 
+            // Test execution with simple command
             let test_request = ExecutionRequest::new("echo 'health check'", "bash")
                 .with_timeout(Duration::from_secs(10));
 
-            match JailEnvironment::setup_environment(&backend.jail_path, &test_request) {
-                Ok(exec_dir) => {
-                    // Clean up test directory
-                    JailEnvironment::cleanup(&exec_dir);
-
-                    HealthStatus::healthy("LandLock backend operational")
-                        .with_metric("landlock_available", "true")
-                        .with_metric("bwrap_available", "true")
-                        .with_metric("jail_path_valid", "true")
-                        .with_metric("abi_version", &features.abi_version.to_string())
-                        .with_metric(
-                            "access_fs",
-                            &format!("0x{:x}", features.supported_access_fs),
-                        )
+            match backend.execute_code(test_request).await {
+                Ok(result) if result.is_success() => {
+                    live("LandLock backend operational").with_metric("test_execution", "success")
                 }
-                Err(e) => HealthStatus::unhealthy(format!("Test environment setup failed: {}", e))
-                    .with_metric("test_setup", "failed"),
+                Ok(result) => {
+                    HealthStatus::unhealthy(format!("Test execution failed: {}", result.stderr))
+                        .with_metric("test_execution", "failed")
+                        .with_metric("exit_code", result.exit_code.to_string())
+                }
+                Err(e) => HealthStatus::unhealthy(format!("Health check task error: {}", e))
+                    .with_metric("test_execution", "task_error"),
             }
         }).spawn()
     }
 
     fn cleanup(&self) -> AsyncTask<crate::execution_env::CyloResult<()>> {
         let jail_path = self.jail_path.clone();
+        let owner_id = self.config.owner_id.clone();
+        let slice = self
+            .config
+            .backend_specific
+            .get(CGROUP_PARENT_KEY)
+            .map(|parent| (PathBuf::from(parent), self.slice_name()));
 
         AsyncTaskBuilder::new(async move {
-            // Clean up any leftover execution directories
-            JailEnvironment::cleanup_all(&jail_path);
+            // Clean up leftover execution directories created by this executor
+            JailEnvironment::cleanup_all(&jail_path, &owner_id);
+            if let Some((parent, instance)) = slice {
+                cgroup::remove_instance(&parent, &instance);
+            }
             Ok(())
         }).spawn()
     }
@@ -187,21 +297,12 @@ impl ExecutionBackend for LandLockBackend {
     }
 
     fn supports_language(&self, language: &str) -> bool {
-        self.supported_languages().contains(&language)
+        let (name, _) = language::split_version(language);
+        self.supported_languages().contains(&name)
     }
 
     fn supported_languages(&self) -> &[&'static str] {
-        &[
-            "python",
-            "python3",
-            "javascript",
-            "js",
-            "node",
-            "rust",
-            "bash",
-            "sh",
-            "go",
-        ]
+        &self.languages
     }
 }
 
@@ -242,6 +343,7 @@ mod tests {
             assert!(backend.supports_language("python"));
             assert!(backend.supports_language("rust"));
             assert!(backend.supports_language("bash"));
+            assert!(backend.supports_language("R"));
             assert!(!backend.supports_language("cobol"));
         }
 
@@ -4,7 +4,7 @@
 // Resource monitoring for sandboxed processes.
 //
 // Provides Linux /proc filesystem monitoring including:
-// - CPU time tracking (user + kernel mode)
+// - CPU time tracking (user + kernel mode), per process and per tree
 // - Memory usage (RSS) tracking
 // - Disk I/O statistics (read/write bytes)
 // - Process tree counting (threads + children)
@@ -53,6 +53,60 @@ pub fn get_process_cpu_time(_pid: u32) -> Result<u64, std::io::Error> {
     Ok(0)
 }
 
+/// Get CPU time consumed by a process and all of its descendants
+///
+/// Includes the CPU time of descendants that already exited and were
+/// reaped, so the total never goes down while the tree is alive.
+///
+/// # Arguments
+/// * `pid` - Root process ID
+///
+/// # Returns
+/// Total CPU time in milliseconds or error
+#[cfg(target_os = "linux")]
+pub fn get_process_tree_cpu_time(pid: u32) -> Result<u64, std::io::Error> {
+    let stat_content = std::fs::read_to_string(format!("/proc/{}/stat", pid))?;
+
+    // Fields after the parenthesized command name, which may contain spaces
+    let fields: Vec<&str> = stat_content
+        .rsplit_once(')')
+        .map_or("", |(_, rest)| rest)
+        .split_whitespace()
+        .collect();
+    if fields.len() < 15 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Invalid stat format",
+        ));
+    }
+
+    // utime, stime, cutime, cstime (fields 14-17 of the full line)
+    let total_ticks: u64 = fields[11..15]
+        .iter()
+        .map(|field| field.parse::<u64>().unwrap_or(0))
+        .sum();
+    let clock_ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) } as u64;
+    let mut cpu_time_ms = (total_ticks * 1000) / clock_ticks_per_sec;
+
+    let children_path = format!("/proc/{}/task/{}/children", pid, pid);
+    if let Ok(children_content) = std::fs::read_to_string(&children_path) {
+        for child_pid in children_content
+            .split_whitespace()
+            .filter_map(|child| child.parse::<u32>().ok())
+        {
+            // Children can exit between listing and reading
+            cpu_time_ms += get_process_tree_cpu_time(child_pid).unwrap_or(0);
+        }
+    }
+
+    Ok(cpu_time_ms)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_process_tree_cpu_time(_pid: u32) -> Result<u64, std::io::Error> {
+    Ok(0)
+}
+
 /// Count process tree including threads and child processes
 ///
 /// # Arguments
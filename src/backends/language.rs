@@ -0,0 +1,196 @@
+// ============================================================================
+// File: packages/cylo/src/backends/language.rs
+// ----------------------------------------------------------------------------
+// Registry of languages the built-in backends know how to run
+// ============================================================================
+
+/// Language known to cylo and the names it may be requested by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LanguageSpec {
+    /// Canonical language name
+    pub name: &'static str,
+    /// Alternative names accepted in requests
+    pub aliases: &'static [&'static str],
+    /// Source file extension used when code is written to disk
+    pub extension: &'static str,
+}
+
+impl LanguageSpec {
+    /// Whether a requested name refers to this language (case-insensitive)
+    pub fn matches(&self, requested: &str) -> bool {
+        self.name.eq_ignore_ascii_case(requested)
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(requested))
+    }
+}
+
+/// Languages supported by the built-in backends
+pub const LANGUAGES: &[LanguageSpec] = &[
+    LanguageSpec {
+        name: "python",
+        aliases: &["python3", "py"],
+        extension: "py",
+    },
+    LanguageSpec {
+        name: "javascript",
+        aliases: &["js", "node"],
+        extension: "js",
+    },
+    LanguageSpec {
+        name: "rust",
+        aliases: &["rs"],
+        extension: "rs",
+    },
+    LanguageSpec {
+        name: "bash",
+        aliases: &["sh"],
+        extension: "sh",
+    },
+    LanguageSpec {
+        name: "go",
+        aliases: &["golang"],
+        extension: "go",
+    },
+    LanguageSpec {
+        name: "r",
+        aliases: &["rscript"],
+        extension: "R",
+    },
+    LanguageSpec {
+        name: "sql",
+        aliases: &["sqlite", "sqlite3", "duckdb"],
+        extension: "sql",
+    },
+    LanguageSpec {
+        name: "zig",
+        aliases: &[],
+        extension: "zig",
+    },
+    LanguageSpec {
+        name: "csharp",
+        aliases: &["c#", "cs", "dotnet"],
+        extension: "cs",
+    },
+    LanguageSpec {
+        name: "lua",
+        aliases: &["luajit"],
+        extension: "lua",
+    },
+    // Compiled to a WASI module; only the Wasm backend runs it
+    LanguageSpec {
+        name: "assemblyscript",
+        aliases: &["asc"],
+        extension: "ts",
+    },
+    // Code is a precompiled module; see `wasm_module`
+    LanguageSpec {
+        name: "wasm",
+        aliases: &["wasi", "webassembly"],
+        extension: "wasm",
+    },
+];
+
+/// Look up a language by canonical name or alias
+///
+/// A pinned runtime version (`python@3.12`) is ignored; see `split_version`.
+pub fn resolve(language: &str) -> Option<&'static LanguageSpec> {
+    let (name, _) = split_version(language);
+    LANGUAGES.iter().find(|spec| spec.matches(name))
+}
+
+/// Split a requested language into its name and pinned runtime version
+///
+/// `node@20` yields `("node", Some("20"))`; a name without `@` yields no
+/// version.
+pub fn split_version(language: &str) -> (&str, Option<&str>) {
+    match language.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (language, None),
+    }
+}
+
+/// Whether a pinned version is usable as an image tag and file name part
+pub fn is_valid_version(version: &str) -> bool {
+    !version.is_empty()
+        && version.len() <= 64
+        && version.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Canonical names of every known language
+pub fn known_languages() -> Vec<&'static str> {
+    LANGUAGES.iter().map(|spec| spec.name).collect()
+}
+
+/// Suggest the known language closest to a misspelled name
+///
+/// # Returns
+/// Canonical name of the closest language within edit distance 2, if any
+pub fn suggest(language: &str) -> Option<&'static str> {
+    let requested = split_version(language).0.to_ascii_lowercase();
+    LANGUAGES
+        .iter()
+        .flat_map(|spec| {
+            std::iter::once(spec.name)
+                .chain(spec.aliases.iter().copied())
+                .map(move |candidate| (spec.name, edit_distance(&requested, candidate)))
+        })
+        .filter(|(_, distance)| *distance <= 2)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(name, _)| name)
+}
+
+/// Levenshtein distance between two ASCII-lowercase strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = Vec::with_capacity(b.len() + 1);
+        current.push(i + 1);
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_aliases_case_insensitively() {
+        assert_eq!(resolve("Python3").map(|s| s.name), Some("python"));
+        assert_eq!(resolve("node").map(|s| s.name), Some("javascript"));
+        assert_eq!(resolve("R").map(|s| s.name), Some("r"));
+        assert_eq!(resolve("C#").map(|s| s.name), Some("csharp"));
+        assert!(resolve("cobol").is_none());
+    }
+
+    #[test]
+    fn pinned_versions_split_from_name() {
+        assert_eq!(split_version("python@3.12"), ("python", Some("3.12")));
+        assert_eq!(split_version("rust"), ("rust", None));
+        assert_eq!(resolve("node@20").map(|s| s.name), Some("javascript"));
+        assert!(is_valid_version("1.78.0"));
+        assert!(!is_valid_version(""));
+        assert!(!is_valid_version("3.12/../x"));
+        assert!(!is_valid_version("-rc"));
+    }
+
+    #[test]
+    fn suggests_close_matches() {
+        assert_eq!(suggest("pyhton"), Some("python"));
+        assert_eq!(suggest("rsut"), Some("rust"));
+        assert_eq!(suggest("javascirpt"), Some("javascript"));
+        assert_eq!(suggest("haskell"), None);
+    }
+}
@@ -0,0 +1,154 @@
+// ============================================================================
+// File: packages/cylo/src/backends/live.rs
+// ----------------------------------------------------------------------------
+// Registry of the VMs and containers a backend instance is running, so
+// instance-level metrics can be sampled while they are alive. One that an
+// execution could not confirm gone (stopped on timeout, failed cleanup)
+// lingers in the registry until a sample finds it has disappeared, so an
+// idle instance still reports what it holds.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Tracked resource; a lingering one has outlived its execution
+#[derive(Debug)]
+struct Entry<T> {
+    item: T,
+    lingering: bool,
+}
+
+type Entries<T> = Arc<Mutex<HashMap<u64, Entry<T>>>>;
+
+/// Shared set of live resources; clones share the same set
+#[derive(Debug)]
+pub(crate) struct LiveSet<T> {
+    next_id: Arc<AtomicU64>,
+    items: Entries<T>,
+}
+
+impl<T> Clone for LiveSet<T> {
+    fn clone(&self) -> Self {
+        Self {
+            next_id: Arc::clone(&self.next_id),
+            items: Arc::clone(&self.items),
+        }
+    }
+}
+
+impl<T> Default for LiveSet<T> {
+    fn default() -> Self {
+        Self {
+            next_id: Arc::new(AtomicU64::new(0)),
+            items: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<T: Clone> LiveSet<T> {
+    /// Track a resource until the returned guard is dropped
+    pub(crate) fn track(&self, item: T) -> LiveGuard<T> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.items
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(
+                id,
+                Entry {
+                    item,
+                    lingering: false,
+                },
+            );
+        LiveGuard {
+            id,
+            items: Arc::clone(&self.items),
+        }
+    }
+
+    /// Sample every tracked resource
+    ///
+    /// Sampling runs without the set locked. A lingering resource that
+    /// yields no sample is gone and stops being tracked.
+    pub(crate) fn sample<S>(&self, sample: impl Fn(&T) -> Option<S>) -> Vec<S> {
+        let tracked: Vec<(u64, T, bool)> = self
+            .items
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(id, entry)| (*id, entry.item.clone(), entry.lingering))
+            .collect();
+
+        let mut samples = Vec::with_capacity(tracked.len());
+        let mut gone = Vec::new();
+        for (id, item, lingering) in tracked {
+            match sample(&item) {
+                Some(taken) => samples.push(taken),
+                None if lingering => gone.push(id),
+                None => {}
+            }
+        }
+        if !gone.is_empty() {
+            let mut items = self.items.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            for id in gone {
+                items.remove(&id);
+            }
+        }
+        samples
+    }
+}
+
+/// Removes its resource from the set when dropped
+#[derive(Debug)]
+pub(crate) struct LiveGuard<T> {
+    id: u64,
+    items: Entries<T>,
+}
+
+impl<T> LiveGuard<T> {
+    /// Keep tracking a resource its execution could not confirm gone,
+    /// until a sample finds it has disappeared
+    pub(crate) fn linger(self) {
+        if let Some(entry) = self
+            .items
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get_mut(&self.id)
+        {
+            entry.lingering = true;
+        }
+    }
+}
+
+impl<T> Drop for LiveGuard<T> {
+    fn drop(&mut self) {
+        let mut items = self.items.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if items.get(&self.id).is_some_and(|entry| !entry.lingering) {
+            items.remove(&self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lingering_resources_are_sampled_until_gone() {
+        let live = LiveSet::default();
+        let running = live.track("running");
+        live.track("lingering").linger();
+        drop(live.track("finished"));
+
+        let mut sampled = live.sample(|name| Some(*name));
+        sampled.sort_unstable();
+        assert_eq!(sampled, ["lingering", "running"]);
+
+        // A live resource without a sample stays tracked; a lingering one
+        // is gone
+        assert!(live.sample(|_| None::<()>).is_empty());
+        assert_eq!(live.sample(|name| Some(*name)), ["running"]);
+        drop(running);
+        assert!(live.sample(|name| Some(*name)).is_empty());
+    }
+}
@@ -16,13 +16,112 @@ mod types;
 mod config;
 mod errors;
 mod factory;
+pub(crate) mod registry;
+mod backend_plugin;
+mod expectations;
+mod process;
+pub(crate) mod output;
+mod output_stream;
+mod paths;
+pub(crate) mod post_process;
+pub(crate) mod archive;
+pub(crate) mod arch;
+pub(crate) mod blob_store;
+mod health_cache;
+pub(crate) mod blocking;
+pub(crate) mod sampler;
+mod dns;
+mod clock;
+pub(crate) mod compile_phase;
+mod compiler;
+pub(crate) mod cpuset;
+mod crash;
+pub(crate) mod desktop;
+mod environment;
+mod dotnet;
+mod git_checkout;
+mod go_cache;
+mod python_env;
+mod r_library;
+mod sql;
+pub(crate) mod volumes;
+pub(crate) mod budget;
+mod wasm_module;
+mod watchdog;
+mod retention;
+mod image_ref;
+mod image_store;
+pub(crate) mod io_throttle;
+pub(crate) mod input_stream;
+mod registry_auth;
+pub(crate) mod progress;
+pub(crate) mod provisioning;
+pub(crate) mod live;
+pub(crate) mod transcript;
+mod sandbox_state;
+#[cfg(target_os = "linux")]
+pub(crate) mod cgroup;
+pub mod language;
+pub mod runtime;
 
 // Re-export core types and traits
 pub use trait_def::{AsyncTask, ExecutionBackend};
-pub use types::{ExecutionRequest, ExecutionResult, HealthStatus, ResourceUsage};
-pub use config::{BackendConfig, ResourceLimits};
+pub use types::{
+    BackendCapabilities, ExecutionCost, ExecutionOutcome, ExecutionRequest, ExecutionResult,
+    HealthCheckLevel, HealthStatus, InstanceMetrics, IsolationLevel, ResourceUsage,
+    SecurityReport,
+};
+pub use config::{BackendConfig, ResourceLimits, executor_identity};
 pub use errors::{BackendError, BackendResult};
 pub use factory::{available_backends, create_backend};
+pub use registry::{
+    BackendFactory, register_backend, registered_backends, unregister_backend,
+};
+pub use backend_plugin::{
+    HOST_FEATURES, HostOffer, PLUGIN_ABI_VERSION, PluginManifest, PluginPath, PluginRequest,
+    PluginResponse, load_backend_plugin,
+};
+pub use expectations::{ExpectationCheck, ExpectationVerdict, Expectations};
+pub use output::RawOutput;
+pub use output_stream::{OutputChunk, OutputSink, OutputStream};
+pub use post_process::{ErrorFrame, PostProcessor, ProcessedOutput, Traceback, TracebackLanguage};
+pub use input_stream::InputStream;
+pub use crash::{
+    CORE_DUMP_MAX_BYTES, CORE_DUMP_METADATA, CORE_DUMP_RETENTION, CrashReport, SIGNAL_METADATA,
+    core_dump_dir,
+};
+pub use clock::{CLOCK_METADATA, VirtualClock};
+pub use compile_phase::CompilationPhase;
+pub use compiler::{CompilerOptions, OptLevel, RustEdition};
+pub use cpuset::CpuSet;
+pub use sql::{ResultSet, SqlEngine, SqlOptions, parse_result_sets};
+pub use volumes::{
+    VOLUME_USAGE_METADATA, Volume, VolumeMount, VolumeSnapshot, VolumeStore,
+};
+pub use arch::{ARCH_METADATA, Arch};
+pub use archive::{
+    ARCHIVE_ERROR_METADATA, ArchiveFormat, ArchiveLimits, OutputArchive, WorkspaceArchive,
+};
+pub use blob_store::{Blob, BlobFile, BlobStore, OutputBlobs};
+pub use budget::{BudgetLimits, BudgetUsage, ExecutionBudget};
+pub use blocking::{BLOCKING_POOL_SIZE_ENV, BlockingPoolStats};
+pub use desktop::DesktopAccess;
+pub use environment::EnvironmentProfile;
+pub use git_checkout::{DEFAULT_CHECKOUT_PATH, GitCheckout, GitCredentials};
+pub use watchdog::WATCHDOG_METADATA;
+pub use retention::{RETAINED_UNTIL_METADATA, RETAINED_WORKSPACE_METADATA};
+pub use dns::{DNS_ENFORCEMENT_METADATA, DNS_METADATA, DnsPolicy, DnsResolvers};
+pub use health_cache::{HealthCache, HealthCacheConfig};
+pub use image_ref::{IMAGE_DIGEST_METADATA, IMAGE_REFERENCE_METADATA, ImageReference};
+pub use image_store::{IMAGE_STORE_KEY, ImageStore, ROOTFS_MEDIA_TYPE, StoredImage};
+pub use progress::{ProgressReporter, ProvisioningEvent, ProvisioningStage};
+pub use provisioning::ProvisioningLimits;
+pub use registry_auth::{RegistryAuth, RegistryCredentials, RegistryLogin};
+pub use transcript::{
+    DEFAULT_TRANSCRIPT_MAX_BYTES, Transcript, TranscriptEvent, TranscriptRecorder,
+    TranscriptStream,
+};
+pub use sandbox_state::{SANDBOX_STATE_METADATA, SandboxState};
 
 // Platform-conditional module imports
 #[cfg(target_os = "macos")]
@@ -40,9 +139,27 @@ pub mod firecracker;
 #[cfg(target_os = "linux")]
 pub use firecracker::FireCrackerBackend;
 
+// Docker/Podman backend (wherever a container engine is installed)
+pub mod docker;
+pub use docker::{CONTAINER_RUNTIME_KEY, DockerBackend};
+
+// WebAssembly backend (wherever wasmtime is installed)
+pub mod wasm;
+pub use wasm::{
+    ASSEMBLYSCRIPT_CONFIG_KEY, DEFAULT_FUEL_PER_CPU_SECOND, FUEL_PER_CPU_SECOND_KEY,
+    WASM_FUEL_METADATA, WasmBackend,
+};
+
+// Kubernetes Job backend (wherever kubectl is installed)
+pub mod kubernetes;
+pub use kubernetes::{
+    K8S_CONTEXT_KEY, K8S_CPU_KEY, K8S_IMAGE_PULL_SECRET_KEY, K8S_NAMESPACE_KEY,
+    K8S_NETWORK_POLICY_METADATA, K8S_TERMINATION_REASON_METADATA, K8sJobBackend, KUBECTL_KEY,
+};
+
 // SweetMCP plugin backend (available on all platforms)
 pub mod sweetmcp_plugin;
-pub use sweetmcp_plugin::SweetMcpPluginBackend;
+pub use sweetmcp_plugin::{PluginGrants, SweetMcpPluginBackend};
 
 #[cfg(target_os = "windows")]
 pub mod windows;
@@ -0,0 +1,249 @@
+// ============================================================================
+// File: packages/cylo/src/backends/output.rs
+// ----------------------------------------------------------------------------
+// Output buffers and UTF-8 decoding for captured program output.
+//
+// Capture buffers come preallocated from a shared pool, so a busy service
+// does not grow a fresh vector from zero for every stream it reads. Output
+// is validated as UTF-8 chunk by chunk while it is read; valid output is
+// then copied into an exactly sized `String` without being checked again,
+// so the large buffer goes back to the pool; a buffer grown too large to
+// pool becomes the `String` itself instead. Only output that is not valid
+// UTF-8 pays for a lossy decode, in which case the exact bytes are kept
+// alongside it.
+// ============================================================================
+
+use std::sync::{Mutex, OnceLock};
+
+use bytes::{Bytes, BytesMut};
+
+/// Capacity capture buffers are preallocated with
+const BUFFER_CAPACITY: usize = 64 * 1024;
+
+/// Buffers kept for reuse at most
+const MAX_POOLED: usize = 64;
+
+/// Buffers that grew past this are freed rather than kept for reuse
+const MAX_POOLED_CAPACITY: usize = 1024 * 1024;
+
+/// Exact bytes a program wrote, kept when they were not valid UTF-8
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawOutput {
+    /// Bytes written to stdout
+    pub stdout: Bytes,
+    /// Bytes written to stderr
+    pub stderr: Bytes,
+}
+
+/// Preallocated buffers shared by every output capture
+#[derive(Debug)]
+pub(crate) struct BufferPool {
+    free: Mutex<Vec<BytesMut>>,
+}
+
+impl BufferPool {
+    /// Empty buffer with at least the preallocated capacity
+    pub(crate) fn take(&self) -> BytesMut {
+        self.free
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .pop()
+            .unwrap_or_else(|| BytesMut::with_capacity(BUFFER_CAPACITY))
+    }
+
+    /// Return a buffer whose contents are no longer needed
+    pub(crate) fn give(&self, mut buffer: BytesMut) {
+        if buffer.capacity() < BUFFER_CAPACITY || buffer.capacity() > MAX_POOLED_CAPACITY {
+            return;
+        }
+        buffer.clear();
+        let mut free = self.free.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if free.len() < MAX_POOLED {
+            free.push(buffer);
+        }
+    }
+
+    #[cfg(test)]
+    fn pooled(&self) -> usize {
+        self.free.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).len()
+    }
+}
+
+/// Pool capture buffers are taken from
+pub(crate) fn buffer_pool() -> &'static BufferPool {
+    static POOL: OnceLock<BufferPool> = OnceLock::new();
+    POOL.get_or_init(|| BufferPool {
+        free: Mutex::new(Vec::new()),
+    })
+}
+
+/// Bytes of one output stream, validated as UTF-8 as they arrive
+#[derive(Debug)]
+pub(crate) struct OutputBuffer {
+    bytes: BytesMut,
+    /// Length of the prefix known to be valid UTF-8
+    valid_up_to: usize,
+    /// Whether an invalid sequence was seen; a trailing incomplete
+    /// sequence is not invalid until the stream ends
+    invalid: bool,
+}
+
+impl Default for OutputBuffer {
+    fn default() -> Self {
+        Self {
+            bytes: buffer_pool().take(),
+            valid_up_to: 0,
+            invalid: false,
+        }
+    }
+}
+
+impl OutputBuffer {
+    /// Append a chunk, validating only the bytes not validated yet
+    pub(crate) fn extend(&mut self, chunk: &[u8]) {
+        self.bytes.extend_from_slice(chunk);
+        if self.invalid {
+            return;
+        }
+        match std::str::from_utf8(&self.bytes[self.valid_up_to..]) {
+            Ok(_) => self.valid_up_to = self.bytes.len(),
+            Err(e) => {
+                self.valid_up_to += e.valid_up_to();
+                self.invalid = e.error_len().is_some();
+            }
+        }
+    }
+
+    /// Bytes captured so far
+    pub(crate) fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn is_utf8(&self) -> bool {
+        !self.invalid && self.valid_up_to == self.bytes.len()
+    }
+
+    /// Decode the captured bytes, leaving the buffer empty
+    ///
+    /// The text is copied out at its exact size and the buffer keeps its
+    /// capacity, so it returns to the pool when dropped. A buffer too large
+    /// to be pooled is moved into the text without copying, and replaced
+    /// by one from the pool.
+    ///
+    /// # Returns
+    /// The text, plus the exact bytes when they were not valid UTF-8
+    pub(crate) fn take(&mut self) -> (String, Option<Bytes>) {
+        let decoded = if self.bytes.capacity() > MAX_POOLED_CAPACITY {
+            let utf8 = self.is_utf8();
+            let bytes = Vec::from(std::mem::replace(&mut self.bytes, buffer_pool().take()));
+            if utf8 {
+                // SAFETY: validated as in `snapshot`
+                (unsafe { String::from_utf8_unchecked(bytes) }, None)
+            } else {
+                decode(bytes)
+            }
+        } else {
+            self.snapshot()
+        };
+        self.bytes.clear();
+        self.valid_up_to = 0;
+        self.invalid = false;
+        decoded
+    }
+
+    /// Decode a copy of the captured bytes, keeping the buffer
+    pub(crate) fn snapshot(&self) -> (String, Option<Bytes>) {
+        if self.is_utf8() {
+            // SAFETY: every byte was validated as UTF-8 when it was appended,
+            // and a complete stream ends on a character boundary
+            let text = unsafe { std::str::from_utf8_unchecked(&self.bytes) };
+            (text.to_owned(), None)
+        } else {
+            let bytes = Bytes::copy_from_slice(&self.bytes);
+            (String::from_utf8_lossy(&bytes).into_owned(), Some(bytes))
+        }
+    }
+}
+
+impl Drop for OutputBuffer {
+    fn drop(&mut self) {
+        buffer_pool().give(std::mem::take(&mut self.bytes));
+    }
+}
+
+/// Decode bytes read in one piece, without copying them when they are
+/// valid UTF-8
+///
+/// # Returns
+/// The text, plus the exact bytes when they were not valid UTF-8
+pub(crate) fn decode(bytes: Vec<u8>) -> (String, Option<Bytes>) {
+    match String::from_utf8(bytes) {
+        Ok(text) => (text, None),
+        Err(e) => {
+            let bytes = Bytes::from(e.into_bytes());
+            (String::from_utf8_lossy(&bytes).into_owned(), Some(bytes))
+        }
+    }
+}
+
+/// Keep both streams' exact bytes if either was not valid UTF-8
+pub(crate) fn raw_output(
+    stdout: &str,
+    raw_stdout: Option<Bytes>,
+    stderr: &str,
+    raw_stderr: Option<Bytes>,
+) -> Option<RawOutput> {
+    if raw_stdout.is_none() && raw_stderr.is_none() {
+        return None;
+    }
+    let exact = |text: &str, raw: Option<Bytes>| {
+        raw.unwrap_or_else(|| Bytes::copy_from_slice(text.as_bytes()))
+    };
+    Some(RawOutput {
+        stdout: exact(stdout, raw_stdout),
+        stderr: exact(stderr, raw_stderr),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffers_validate_incrementally_and_keep_invalid_bytes() {
+        // A character split across chunks is valid once completed
+        let mut buffer = OutputBuffer::default();
+        let snowman = "☃".as_bytes();
+        buffer.extend(b"a");
+        buffer.extend(&snowman[..1]);
+        assert!(!buffer.is_utf8());
+        buffer.extend(&snowman[1..]);
+        assert_eq!(buffer.snapshot(), ("a☃".to_string(), None));
+        assert_eq!(buffer.take(), ("a☃".to_string(), None));
+        assert_eq!(buffer.len(), 0);
+        // Taking the output keeps the pooled capacity for the next capture
+        assert!(buffer.bytes.capacity() >= BUFFER_CAPACITY);
+
+        let mut buffer = OutputBuffer::default();
+        buffer.extend(b"ok\xff");
+        buffer.extend(b"more");
+        let (text, raw) = buffer.take();
+        assert_eq!(text, "ok\u{fffd}more");
+        assert_eq!(raw.as_deref(), Some(&b"ok\xffmore"[..]));
+
+        // Output too large to pool becomes the text without a copy
+        let mut buffer = OutputBuffer::default();
+        buffer.extend(&vec![b'x'; MAX_POOLED_CAPACITY + 1]);
+        let grown = buffer.bytes.as_ptr();
+        let (text, raw) = buffer.take();
+        assert_eq!((text.len(), text.as_ptr(), raw), (MAX_POOLED_CAPACITY + 1, grown, None));
+        assert!(buffer.bytes.capacity() <= MAX_POOLED_CAPACITY);
+
+        let (text, raw) = decode(b"\x80".to_vec());
+        let raw = raw_output("out", None, &text, raw).unwrap();
+        assert_eq!((&raw.stdout[..], &raw.stderr[..]), (&b"out"[..], &b"\x80"[..]));
+
+        drop(OutputBuffer::default());
+        assert!(buffer_pool().pooled() > 0);
+    }
+}
@@ -0,0 +1,125 @@
+// ============================================================================
+// File: packages/cylo/src/backends/output_stream.rs
+// ----------------------------------------------------------------------------
+// Live stdout/stderr of a running execution.
+//
+// A long-running script used to give no feedback until it finished and its
+// buffered output came back in the result. Requests can now carry a sink
+// that process-spawning backends feed each chunk to as the child writes
+// it. The sink never blocks: a consumer that falls behind loses the oldest
+// chunks and is told how many through `RecvError::Lagged`, rather than
+// stalling the child on a full pipe. The result still holds the complete
+// output either way. A sink can also feed a transcript recorder, which
+// sees every chunk however far behind the consumer is.
+// ============================================================================
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::async_task::{EventSender, EventStream, event_stream};
+use crate::backends::transcript::TranscriptTap;
+
+/// Chunks buffered for a consumer that is not keeping up
+const OUTPUT_CAPACITY: usize = 1024;
+
+/// Stream a chunk was written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OutputStream {
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+}
+
+/// Bytes an execution wrote to one of its streams, in the order read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputChunk {
+    /// Stream the bytes were written to
+    pub stream: OutputStream,
+    /// The bytes, which may split a UTF-8 sequence or a line; compiled
+    /// languages also stream the line marking the end of compilation
+    pub data: Bytes,
+}
+
+impl OutputChunk {
+    /// The bytes, lossily decoded as UTF-8
+    pub fn text(&self) -> std::borrow::Cow<'_, str> {
+        String::from_utf8_lossy(&self.data)
+    }
+
+    /// Create a sink and the bounded stream its chunks arrive on
+    ///
+    /// # Returns
+    /// Sink to attach to a request, and the receiving end for the caller
+    pub fn channel() -> (OutputSink, EventStream<OutputChunk>) {
+        let (tx, rx) = event_stream(Some(OUTPUT_CAPACITY));
+        (OutputSink { tx: Some(tx), tap: None }, rx)
+    }
+}
+
+/// Sending half of an output chunk channel
+///
+/// Emitting never blocks and never fails, so backends emit
+/// unconditionally; the stream closes once every clone is dropped.
+#[derive(Debug, Clone)]
+pub struct OutputSink {
+    /// Channel to the consumer; None for a sink that only feeds a tap
+    tx: Option<EventSender<OutputChunk>>,
+    /// Transcript the chunks are also recorded in
+    tap: Option<TranscriptTap>,
+}
+
+impl OutputSink {
+    /// Sink without a consumer, to be tapped
+    pub(crate) fn detached() -> Self {
+        Self { tx: None, tap: None }
+    }
+
+    /// Record every chunk in `tap` as well as sending it on
+    pub(crate) fn tapped(mut self, tap: TranscriptTap) -> Self {
+        self.tap = Some(tap);
+        self
+    }
+
+    /// Send a chunk to the consumer, if it is still listening; empty
+    /// chunks are skipped
+    pub fn emit(&self, stream: OutputStream, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        if let Some(tap) = &self.tap {
+            tap.output(stream, data);
+        }
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(OutputChunk {
+                stream,
+                data: Bytes::copy_from_slice(data),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn chunks_arrive_in_order_until_every_sink_is_gone() {
+        let (sink, mut chunks) = OutputChunk::channel();
+        let backend = sink.clone();
+        backend.emit(OutputStream::Stdout, b"step 1\n");
+        backend.emit(OutputStream::Stderr, b"");
+        backend.emit(OutputStream::Stderr, "warn \u{2013} slow\n".as_bytes());
+        drop(backend);
+
+        let first = chunks.recv().await.unwrap();
+        assert_eq!(first.stream, OutputStream::Stdout);
+        assert_eq!(first.text(), "step 1\n");
+        let second = chunks.recv().await.unwrap();
+        assert_eq!(second.stream, OutputStream::Stderr);
+        assert_eq!(second.text(), "warn \u{2013} slow\n");
+
+        drop(sink);
+        assert!(chunks.recv().await.is_err());
+    }
+}
@@ -0,0 +1,281 @@
+// ============================================================================
+// File: packages/cylo/src/backends/paths.rs
+// ----------------------------------------------------------------------------
+// Confinement of request-supplied paths to a sandbox workspace, and
+// resolution of the host paths a request exposes inside the sandbox
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::backends::{BackendError, BackendResult, ExecutionRequest, language};
+
+/// Resolve a requested working directory inside a sandbox root
+///
+/// The request path is always interpreted relative to `root`; leading `/`,
+/// drive letters, and UNC prefixes are stripped rather than honoured. `..`
+/// components are rejected outright. The deepest part of the path that
+/// already exists is canonicalized and checked against the root before the
+/// rest is created, so a symlink planted inside the workspace can neither
+/// redirect the working directory outside of it nor get directories created
+/// on the host.
+///
+/// # Arguments
+/// * `root` - Sandbox workspace directory (must exist)
+/// * `requested` - Working directory from the execution request
+///
+/// # Returns
+/// Canonical path of the working directory, guaranteed to be inside `root`
+pub fn confine_working_dir(root: &Path, requested: &str) -> BackendResult<PathBuf> {
+    let relative = relative_inside(requested)?;
+    let escape = || BackendError::PathEscape {
+        path: requested.to_string(),
+    };
+
+    let canonical_root = root.canonicalize().map_err(|e| BackendError::FileSystemFailed {
+        details: format!("Failed to resolve sandbox root: {}", e),
+    })?;
+
+    // A dangling symlink counts as existing, so it is resolved (and fails)
+    // here rather than followed by the directory creation below
+    let target = root.join(&relative);
+    let existing = target
+        .ancestors()
+        .find(|path| fs::symlink_metadata(path).is_ok())
+        .unwrap_or(root);
+    let canonical_existing = existing
+        .canonicalize()
+        .map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to resolve working directory: {}", e),
+        })?;
+    if !canonical_existing.starts_with(&canonical_root) {
+        return Err(escape());
+    }
+
+    let missing = target.strip_prefix(existing).map_err(|_| escape())?;
+    let created = canonical_existing.join(missing);
+    fs::create_dir_all(&created).map_err(|e| BackendError::FileSystemFailed {
+        details: format!("Failed to create working directory: {}", e),
+    })?;
+
+    let canonical_target = created
+        .canonicalize()
+        .map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to resolve working directory: {}", e),
+        })?;
+    if !canonical_target.starts_with(&canonical_root) {
+        return Err(escape());
+    }
+
+    Ok(canonical_target)
+}
+
+/// Lexically reduce a requested path to a relative path with no escapes
+///
+/// # Returns
+/// Relative path made only of normal components (empty for the root itself)
+pub fn relative_inside(requested: &str) -> BackendResult<PathBuf> {
+    if requested.contains('\0') {
+        return Err(BackendError::PathEscape {
+            path: requested.escape_debug().to_string(),
+        });
+    }
+
+    let mut relative = PathBuf::new();
+    for component in Path::new(requested).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::RootDir | Component::Prefix(_) | Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(BackendError::PathEscape {
+                    path: requested.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(relative)
+}
+
+/// Write a request's additional files into a workspace
+///
+/// Paths are confined like working directories and missing parent
+/// directories are created. Backends write the files before the source
+/// file, so the source file wins over a file of the same name.
+///
+/// # Arguments
+/// * `root` - Sandbox workspace directory (must exist and be fresh, so no
+///   symlink can redirect a write)
+/// * `files` - Files by relative path
+pub fn write_files(root: &Path, files: &BTreeMap<String, String>) -> BackendResult<()> {
+    for (name, contents) in files {
+        let target = root.join(relative_inside(name)?);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| BackendError::FileSystemFailed {
+                details: format!("Failed to create directory for {}: {}", name, e),
+            })?;
+        }
+        fs::write(&target, contents).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to write {}: {}", name, e),
+        })?;
+    }
+    Ok(())
+}
+
+/// Host path a request exposes inside the sandbox
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExposedPath {
+    /// Canonical host path that is bound
+    pub source: PathBuf,
+    /// Path the sandbox sees, as given in the request
+    pub target: PathBuf,
+    /// Whether the sandbox may write through the binding
+    pub writable: bool,
+}
+
+/// Resolve the readable and writable paths of a request
+///
+/// Sources are canonicalized when the request executes, so a symlink is
+/// bound by what it points at then. Readable paths come first, so a
+/// writable path nested inside a readable one is bound over it. A Go
+/// request's warm module cache counts as a readable path; volumes come
+/// last, bound read-write at their targets.
+///
+/// # Arguments
+/// * `request` - Execution request (already validated)
+///
+/// # Returns
+/// Bindings in the order they must be applied, or an error naming the
+/// first path that does not exist
+pub fn exposed_paths(request: &ExecutionRequest) -> BackendResult<Vec<ExposedPath>> {
+    let is_go = language::resolve(&request.language).is_some_and(|spec| spec.name == "go");
+    let warm_cache = request.go_module_cache.iter().filter(|_| is_go);
+    let readable = request.readable_paths.iter().chain(warm_cache).map(|path| (path, false));
+    let writable = request.writable_paths.iter().map(|path| (path, path, true));
+    let volumes = request
+        .volumes
+        .iter()
+        .map(|mount| (&mount.source, &mount.target, true));
+
+    readable
+        .map(|(path, writable)| (path, path, writable))
+        .chain(writable)
+        .chain(volumes)
+        .map(|(source, target, writable)| {
+            let source = source
+                .canonicalize()
+                .map_err(|e| BackendError::FileSystemFailed {
+                    details: format!("Exposed path {} is not accessible: {}", target.display(), e),
+                })?;
+            Ok(ExposedPath {
+                source,
+                target: target.clone(),
+                writable,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("cylo_paths_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn absolute_paths_are_rebased_into_root() {
+        let root = test_root();
+        let dir = confine_working_dir(&root, "/src/app").unwrap();
+        assert!(dir.starts_with(root.canonicalize().unwrap()));
+        assert!(dir.ends_with("src/app"));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parent_components_are_rejected() {
+        assert!(matches!(
+            relative_inside("a/../../etc"),
+            Err(BackendError::PathEscape { .. })
+        ));
+        assert!(relative_inside("bad\0path").is_err());
+        assert_eq!(relative_inside("./a/./b").unwrap(), PathBuf::from("a/b"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_escapes_are_rejected() {
+        let root = test_root();
+        std::os::unix::fs::symlink("/", root.join("escape")).unwrap();
+
+        assert!(matches!(
+            confine_working_dir(&root, "escape/tmp"),
+            Err(BackendError::PathEscape { .. })
+        ));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_escapes_create_nothing_outside_the_root() {
+        let root = test_root();
+        let outside = test_root();
+        std::os::unix::fs::symlink(&outside, root.join("linked")).unwrap();
+        let dangling = outside.join("absent");
+        std::os::unix::fs::symlink(&dangling, root.join("dangling")).unwrap();
+
+        assert!(matches!(
+            confine_working_dir(&root, "linked/created"),
+            Err(BackendError::PathEscape { .. })
+        ));
+        assert!(!outside.join("created").exists());
+        assert!(confine_working_dir(&root, "dangling/created").is_err());
+        assert!(!dangling.exists());
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&outside);
+    }
+
+    #[test]
+    fn files_are_written_below_the_root() {
+        let root = test_root();
+        let files = BTreeMap::from([
+            ("/lib/util.py".to_string(), "X = 1".to_string()),
+            ("package.json".to_string(), "{}".to_string()),
+        ]);
+        write_files(&root, &files).unwrap();
+        assert_eq!(fs::read_to_string(root.join("lib/util.py")).unwrap(), "X = 1");
+        assert!(root.join("package.json").is_file());
+
+        let escape = BTreeMap::from([("../x".to_string(), String::new())]);
+        assert!(write_files(&root, &escape).is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn exposed_paths_keep_readable_before_writable() {
+        let root = test_root();
+        let output = root.join("output");
+        fs::create_dir_all(&output).unwrap();
+
+        let request = ExecutionRequest::new("x", "python")
+            .with_writable_path(&output)
+            .with_readable_path(&root);
+        let exposed = exposed_paths(&request).unwrap();
+        assert_eq!(exposed.len(), 2);
+        assert!(!exposed[0].writable && exposed[0].target == root);
+        assert!(exposed[1].writable && exposed[1].source == output.canonicalize().unwrap());
+
+        let missing = ExecutionRequest::new("x", "python").with_readable_path(root.join("absent"));
+        assert!(exposed_paths(&missing).is_err());
+
+        let go = ExecutionRequest::new("x", "go").with_go_module_cache(&root);
+        assert!(!exposed_paths(&go).unwrap()[0].writable);
+        let python = ExecutionRequest::new("x", "python").with_go_module_cache(&root);
+        assert!(exposed_paths(&python).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,567 @@
+// ============================================================================
+// File: packages/cylo/src/backends/post_process.rs
+// ----------------------------------------------------------------------------
+// Post-processing of execution results.
+//
+// Callers list PostProcessors on an ExecutionRequest; after execution the
+// executor strips terminal escapes from the output, extracts the last JSON
+// object printed to stdout and parses Python, Node and Rust tracebacks into
+// ErrorFrames, so agents don't have to pick structures out of raw output.
+// ============================================================================
+
+use std::borrow::Cow;
+use std::mem;
+use std::sync::OnceLock;
+
+use bytes::Bytes;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backends::output::RawOutput;
+use crate::backends::types::ExecutionResult;
+
+/// Trailing bytes of stdout searched for a JSON object
+const JSON_SCAN_BYTES: usize = 1024 * 1024; // 1MB
+
+/// Step applied to an execution result after the code has run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PostProcessor {
+    /// Remove ANSI escape sequences from stdout and stderr; the exact
+    /// bytes stay available through `ExecutionResult::stdout_bytes`
+    StripAnsi,
+    /// Parse the last JSON object printed to stdout
+    ExtractJson,
+    /// Parse a Python, Node or Rust traceback from stderr, or from stdout
+    /// when stderr holds none
+    ParseTraceback,
+}
+
+/// Structures extracted from an execution's output
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessedOutput {
+    /// Last JSON object printed to stdout, when `ExtractJson` ran and found one
+    #[serde(default)]
+    pub json: Option<Value>,
+
+    /// Traceback of the error the code failed with, when `ParseTraceback`
+    /// ran and found one
+    #[serde(default)]
+    pub traceback: Option<Traceback>,
+}
+
+/// Language whose traceback format was recognized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TracebackLanguage {
+    /// `Traceback (most recent call last):` blocks and syntax errors
+    Python,
+    /// Error lines followed by `at` frames
+    Node,
+    /// `thread '...' panicked at` messages and their backtraces
+    Rust,
+}
+
+/// Error an execution failed with, parsed from its output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Traceback {
+    /// Format the traceback was recognized as
+    pub language: TracebackLanguage,
+
+    /// Exception or error class, such as `ValueError`; `panic` for Rust
+    pub error_type: Option<String>,
+
+    /// Error message
+    pub message: String,
+
+    /// Stack frames, innermost first; frames without a source location
+    /// are omitted
+    pub frames: Vec<ErrorFrame>,
+}
+
+/// One stack frame of a traceback
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorFrame {
+    /// Source file of the frame
+    pub file: String,
+
+    /// Line number, counted from 1
+    pub line: Option<u32>,
+
+    /// Column number, counted from 1
+    pub column: Option<u32>,
+
+    /// Function the frame is in
+    pub function: Option<String>,
+
+    /// Source line the traceback quoted for the frame
+    pub source: Option<String>,
+}
+
+/// Apply post-processors to a result
+///
+/// Escapes are stripped before anything is extracted, and extraction always
+/// reads the output without escapes, whatever order the processors are in.
+///
+/// # Arguments
+/// * `processors` - Processors the request asked for
+/// * `result` - Execution result to process in place
+pub fn apply(processors: &[PostProcessor], result: &mut ExecutionResult) {
+    if processors.is_empty() {
+        return;
+    }
+
+    if processors.contains(&PostProcessor::StripAnsi) {
+        let stdout = strip_ansi(&result.stdout);
+        let stderr = strip_ansi(&result.stderr);
+        if matches!(stdout, Cow::Owned(_)) || matches!(stderr, Cow::Owned(_)) {
+            let (stdout, stderr) = (stdout.into_owned(), stderr.into_owned());
+            let stdout = mem::replace(&mut result.stdout, stdout);
+            let stderr = mem::replace(&mut result.stderr, stderr);
+            // The exact bytes stay reachable through stdout_bytes() and
+            // stderr_bytes()
+            if result.raw_output.is_none() {
+                result.raw_output = Some(RawOutput {
+                    stdout: Bytes::from(stdout),
+                    stderr: Bytes::from(stderr),
+                });
+            }
+        }
+    }
+
+    let mut processed = ProcessedOutput::default();
+    if processors.contains(&PostProcessor::ExtractJson) {
+        processed.json = extract_last_json(&strip_ansi(&result.stdout));
+    }
+    if processors.contains(&PostProcessor::ParseTraceback) {
+        processed.traceback = parse_traceback(&strip_ansi(&result.stderr))
+            .or_else(|| parse_traceback(&strip_ansi(&result.stdout)));
+    }
+    result.processed = Some(processed);
+}
+
+/// Remove ANSI escape sequences from text
+fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+    ansi_pattern().replace_all(text, "")
+}
+
+/// Find the last complete JSON object in the tail of the output
+fn extract_last_json(stdout: &str) -> Option<Value> {
+    let mut start = stdout.len().saturating_sub(JSON_SCAN_BYTES);
+    while !stdout.is_char_boundary(start) {
+        start += 1;
+    }
+    let tail = &stdout[start..];
+
+    let mut last = None;
+    let mut offset = 0;
+    while let Some(found) = tail[offset..].find('{') {
+        let begin = offset + found;
+        let mut values = serde_json::Deserializer::from_str(&tail[begin..]).into_iter::<Value>();
+        match values.next() {
+            Some(Ok(value)) => {
+                // Skip past the object so its nested objects aren't
+                // taken for later ones
+                offset = begin + values.byte_offset();
+                last = Some(value);
+            }
+            _ => offset = begin + 1,
+        }
+    }
+    last
+}
+
+/// Parse the last traceback in the output, trying each known format
+fn parse_traceback(text: &str) -> Option<Traceback> {
+    parse_python(text)
+        .or_else(|| parse_rust(text))
+        .or_else(|| parse_node(text))
+}
+
+/// Parse a Python traceback, or the report of a syntax error
+fn parse_python(text: &str) -> Option<Traceback> {
+    const HEADER: &str = "Traceback (most recent call last):";
+
+    let body = match text.rfind(HEADER) {
+        Some(start) => &text[start + HEADER.len()..],
+        None => {
+            // Syntax errors are reported with a bare frame and no header
+            let frame = python_frame_pattern().find(text)?;
+            let line_start = text[..frame.start()].rfind('\n').map_or(0, |i| i + 1);
+            &text[line_start..]
+        }
+    };
+
+    let mut frames: Vec<ErrorFrame> = Vec::new();
+    let mut error_line = None;
+    for line in body.lines() {
+        if let Some(captures) = python_frame_pattern().captures(line) {
+            frames.push(ErrorFrame {
+                file: captures[1].to_string(),
+                line: captures[2].parse().ok(),
+                column: None,
+                function: captures
+                    .get(3)
+                    .map(|function| function.as_str().to_string()),
+                source: None,
+            });
+        } else if line.starts_with(char::is_whitespace) {
+            let trimmed = line.trim();
+            // Caret and tilde lines only mark the failing expression
+            let marker = trimmed.chars().all(|c| matches!(c, '^' | '~' | ' '));
+            if let Some(frame) = frames.last_mut()
+                && frame.source.is_none()
+                && !marker
+            {
+                frame.source = Some(trimmed.to_string());
+            }
+        } else if !line.is_empty() {
+            error_line = Some(line);
+            break;
+        }
+    }
+
+    if frames.is_empty() && error_line.is_none() {
+        return None;
+    }
+    let (error_type, message) = error_line.map(split_error_line).unwrap_or_default();
+    frames.reverse();
+    Some(Traceback {
+        language: TracebackLanguage::Python,
+        error_type,
+        message,
+        frames,
+    })
+}
+
+/// Parse the last Rust panic in the output, with its backtrace if printed
+fn parse_rust(text: &str) -> Option<Traceback> {
+    let lines: Vec<&str> = text.lines().collect();
+    let (index, captures) = lines
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(index, line)| Some((index, rust_panic_pattern().captures(line)?)))?;
+    let rest = captures.get(1)?.as_str();
+
+    let mut following = lines[index + 1..].iter().copied().peekable();
+    let (location, message) = match rest.strip_prefix('\'') {
+        // Before Rust 1.73: panicked at 'message', file:line:col
+        Some(quoted) => {
+            let (message, location) = quoted.rsplit_once("', ")?;
+            (location, message.to_string())
+        }
+        None => {
+            let location = rest.strip_suffix(':').unwrap_or(rest);
+            let mut message = Vec::new();
+            while let Some(line) = following.next_if(|line| {
+                !line.is_empty() && !line.starts_with("note:") && *line != "stack backtrace:"
+            }) {
+                message.push(line);
+            }
+            (location, message.join("\n"))
+        }
+    };
+
+    let mut frames = Vec::new();
+    if following.any(|line| line == "stack backtrace:") {
+        let mut function = None;
+        for line in following {
+            if let Some(captures) = rust_frame_pattern().captures(line) {
+                function = Some(captures[1].to_string());
+            } else if let Some(location) = line.trim_start().strip_prefix("at ")
+                && line.starts_with(char::is_whitespace)
+            {
+                let (file, line, column) = split_location(location);
+                frames.push(ErrorFrame {
+                    file,
+                    line,
+                    column,
+                    function: function.take(),
+                    source: None,
+                });
+            } else if !line.starts_with(char::is_whitespace) {
+                break;
+            }
+        }
+    }
+    if frames.is_empty() {
+        let (file, line, column) = split_location(location);
+        frames.push(ErrorFrame {
+            file,
+            line,
+            column,
+            ..ErrorFrame::default()
+        });
+    }
+
+    Some(Traceback {
+        language: TracebackLanguage::Rust,
+        error_type: Some("panic".to_string()),
+        message,
+        frames,
+    })
+}
+
+/// Parse the last Node error in the output from its block of `at` frames
+fn parse_node(text: &str) -> Option<Traceback> {
+    let lines: Vec<&str> = text.lines().collect();
+    let end = lines
+        .iter()
+        .rposition(|line| parse_node_frame(line).is_some())?;
+    let mut start = end;
+    while start > 0 && parse_node_frame(lines[start - 1]).is_some() {
+        start -= 1;
+    }
+
+    // The error is the paragraph right above the frames; its first line
+    // names the error and any further lines continue the message
+    let mut head = start;
+    while head > 0 && !lines[head - 1].trim().is_empty() {
+        head -= 1;
+    }
+    let (error_type, message) = match lines[head..start].split_first() {
+        Some((first, rest)) => {
+            let (error_type, mut message) = split_error_line(first);
+            for line in rest {
+                message.push('\n');
+                message.push_str(line);
+            }
+            (error_type, message)
+        }
+        None => (None, String::new()),
+    };
+
+    Some(Traceback {
+        language: TracebackLanguage::Node,
+        error_type,
+        message,
+        frames: lines[start..=end]
+            .iter()
+            .copied()
+            .filter_map(parse_node_frame)
+            .collect(),
+    })
+}
+
+/// Parse an `at function (file:line:col)` or `at file:line:col` frame
+fn parse_node_frame(line: &str) -> Option<ErrorFrame> {
+    if !line.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = line.trim().strip_prefix("at ")?;
+    let (function, location) = match rest.strip_suffix(')').and_then(|r| r.rsplit_once(" (")) {
+        Some((function, location)) => (Some(function.to_string()), location),
+        // Java frames read `at pkg.Class.method(File.java:10)`
+        None if rest.contains('(') => return None,
+        None => (None, rest),
+    };
+
+    let (file, line, column) = split_location(location);
+    Some(ErrorFrame {
+        file,
+        line,
+        column,
+        function,
+        source: None,
+    })
+}
+
+/// Split a `file:line:col` location; the file itself may contain colons
+fn split_location(location: &str) -> (String, Option<u32>, Option<u32>) {
+    let Some((head, last)) = location
+        .rsplit_once(':')
+        .and_then(|(head, last)| Some((head, last.parse::<u32>().ok()?)))
+    else {
+        return (location.to_string(), None, None);
+    };
+
+    match head
+        .rsplit_once(':')
+        .and_then(|(file, line)| Some((file, line.parse::<u32>().ok()?)))
+    {
+        Some((file, line)) => (file.to_string(), Some(line), Some(last)),
+        None => (head.to_string(), Some(last), None),
+    }
+}
+
+/// Split an `ErrorType: message` line; lines that don't start with an
+/// error name are all message
+fn split_error_line(line: &str) -> (Option<String>, String) {
+    let line = line.trim();
+    match line.split_once(": ") {
+        Some((name, message)) if error_name_pattern().is_match(name) => {
+            (Some(name.to_string()), message.to_string())
+        }
+        None if error_name_pattern().is_match(line) => (Some(line.to_string()), String::new()),
+        _ => (None, line.to_string()),
+    }
+}
+
+fn ansi_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        // CSI sequences (colors, cursor movement), OSC sequences (titles,
+        // hyperlinks) and two-byte escapes
+        Regex::new(concat!(
+            r"\x1b\[[0-?]*[ -/]*[@-~]",
+            r"|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)",
+            r"|\x1b[@-Z\\-_]",
+        ))
+        .expect("ANSI pattern is valid")
+    })
+}
+
+fn python_frame_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r#"(?m)^[ \t]*File "([^"]+)", line (\d+)(?:, in (.+))?$"#)
+            .expect("Python frame pattern is valid")
+    })
+}
+
+fn rust_panic_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^thread '[^']*'(?: \(\d+\))? panicked at (.+)$")
+            .expect("Rust panic pattern is valid")
+    })
+}
+
+fn rust_frame_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^\s*\d+: (.+)$").expect("Rust frame pattern is valid"))
+}
+
+fn error_name_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^[A-Za-z_$][\w$.]*(?: \[[A-Za-z0-9_]+\])?$")
+            .expect("error name pattern is valid")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_ansi_removes_colors_and_hyperlinks() {
+        let text = "\x1b[1;31merror\x1b[0m: \x1b]8;;https://x.dev\x07link\x1b]8;;\x07 done";
+        assert_eq!(strip_ansi(text), "error: link done");
+        assert!(matches!(strip_ansi("plain"), Cow::Borrowed("plain")));
+    }
+
+    #[test]
+    fn extract_json_finds_the_last_object() {
+        let stdout = "progress {not json}\n{\"step\": 1}\nlog line\n\
+                      {\"result\": {\"score\": 0.5}, \"ok\": true}\ntrailing {";
+        let json = extract_last_json(stdout).expect("an object is present");
+        assert_eq!(json["result"]["score"], 0.5);
+        assert_eq!(json["ok"], true);
+
+        assert_eq!(extract_last_json("no objects [1, 2]"), None);
+    }
+
+    #[test]
+    fn parses_python_traceback() {
+        let stderr = "Traceback (most recent call last):\n  \
+                      File \"/workspace/main.py\", line 7, in <module>\n    main()\n  \
+                      File \"/workspace/main.py\", line 4, in main\n    \
+                      return 1 / count\n           ~~^~~~~~~\n\
+                      ZeroDivisionError: division by zero\n";
+        let traceback = parse_traceback(stderr).expect("traceback is recognized");
+        assert_eq!(traceback.language, TracebackLanguage::Python);
+        assert_eq!(traceback.error_type.as_deref(), Some("ZeroDivisionError"));
+        assert_eq!(traceback.message, "division by zero");
+        assert_eq!(traceback.frames.len(), 2);
+        assert_eq!(traceback.frames[0].function.as_deref(), Some("main"));
+        assert_eq!(traceback.frames[0].line, Some(4));
+        assert_eq!(
+            traceback.frames[0].source.as_deref(),
+            Some("return 1 / count")
+        );
+        assert_eq!(traceback.frames[1].function.as_deref(), Some("<module>"));
+
+        let syntax = "  File \"/workspace/main.py\", line 1\n    print(\n         ^\n\
+                      SyntaxError: '(' was never closed\n";
+        let traceback = parse_traceback(syntax).expect("syntax error is recognized");
+        assert_eq!(traceback.error_type.as_deref(), Some("SyntaxError"));
+        assert_eq!(traceback.frames[0].source.as_deref(), Some("print("));
+    }
+
+    #[test]
+    fn parses_node_stack() {
+        let stderr = "/workspace/main.js:3\n  throw new TypeError(\"bad input\");\n  ^\n\n\
+                      TypeError: bad input\n    at parse (/workspace/main.js:3:9)\n    \
+                      at /workspace/main.js:6:1\n    \
+                      at node:internal/main/run_main_module:28:49\n\nNode.js v20.11.0\n";
+        let traceback = parse_traceback(stderr).expect("stack is recognized");
+        assert_eq!(traceback.language, TracebackLanguage::Node);
+        assert_eq!(traceback.error_type.as_deref(), Some("TypeError"));
+        assert_eq!(traceback.message, "bad input");
+        assert_eq!(traceback.frames.len(), 3);
+        assert_eq!(traceback.frames[0].function.as_deref(), Some("parse"));
+        assert_eq!(traceback.frames[0].file, "/workspace/main.js");
+        assert_eq!(traceback.frames[0].column, Some(9));
+        assert_eq!(traceback.frames[1].function, None);
+        assert_eq!(
+            traceback.frames[2].file,
+            "node:internal/main/run_main_module"
+        );
+    }
+
+    #[test]
+    fn parses_rust_panics() {
+        let stderr = "thread 'main' panicked at src/main.rs:2:5:\nindex out of bounds\n\
+                      note: run with `RUST_BACKTRACE=1` environment variable to display a \
+                      backtrace\n";
+        let traceback = parse_traceback(stderr).expect("panic is recognized");
+        assert_eq!(traceback.language, TracebackLanguage::Rust);
+        assert_eq!(traceback.error_type.as_deref(), Some("panic"));
+        assert_eq!(traceback.message, "index out of bounds");
+        assert_eq!(traceback.frames[0].file, "src/main.rs");
+        assert_eq!(traceback.frames[0].line, Some(2));
+
+        let old = "thread 'main' panicked at 'explicit panic', src/main.rs:4:9\n";
+        let traceback = parse_traceback(old).expect("old panic format is recognized");
+        assert_eq!(traceback.message, "explicit panic");
+        assert_eq!(traceback.frames[0].column, Some(9));
+
+        let backtrace = "thread 'main' panicked at src/main.rs:2:5:\nboom\nstack backtrace:\n   \
+                         0: rust_begin_unwind\n   1: main::run\n             \
+                         at ./src/main.rs:2:5\n   2: main::main\n             \
+                         at ./src/main.rs:6:5\nnote: Some details are omitted\n";
+        let traceback = parse_traceback(backtrace).expect("backtrace is recognized");
+        assert_eq!(traceback.frames.len(), 2);
+        assert_eq!(traceback.frames[0].function.as_deref(), Some("main::run"));
+        assert_eq!(traceback.frames[1].line, Some(6));
+    }
+
+    #[test]
+    fn apply_runs_requested_processors() {
+        let mut result = ExecutionResult::failure(
+            1,
+            "\x1b[31mTraceback (most recent call last):\n  \
+             File \"main.py\", line 1, in <module>\n    fail()\nValueError: nope\x1b[0m\n",
+        );
+        result.stdout = "\x1b[32m{\"partial\": true}\x1b[0m\n".to_string();
+
+        apply(
+            &[PostProcessor::ExtractJson, PostProcessor::ParseTraceback],
+            &mut result,
+        );
+        assert!(result.stdout.contains('\x1b'));
+        let processed = result.processed.clone().expect("processors ran");
+        assert_eq!(processed.json, Some(serde_json::json!({"partial": true})));
+        assert_eq!(processed.traceback.unwrap().message, "nope");
+
+        apply(&[PostProcessor::StripAnsi], &mut result);
+        assert_eq!(result.stdout, "{\"partial\": true}\n");
+        assert!(!result.stderr.contains('\x1b'));
+        assert!(result.stdout_bytes().starts_with(b"\x1b[32m"));
+        assert_eq!(result.processed, Some(ProcessedOutput::default()));
+    }
+}
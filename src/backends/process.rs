@@ -0,0 +1,439 @@
+// ============================================================================
+// File: packages/cylo/src/backends/process.rs
+// ----------------------------------------------------------------------------
+// Process supervision helpers shared by the process-spawning backends.
+//
+// Provides:
+// - Incremental stdout/stderr capture that survives a forced kill, also fed
+//   live to the request's output sink
+// - Timeout handling with a graceful termination phase before the kill
+// - Early termination of stalled children through a liveness watchdog
+// - Platform signal helpers (process-group SIGTERM/SIGKILL, CTRL_BREAK)
+// ============================================================================
+
+use std::io::{self, Read};
+use std::process::{Child, ExitStatus};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::backends::output::{self, OutputBuffer, RawOutput};
+use crate::backends::output_stream::{OutputSink, OutputStream};
+use crate::backends::watchdog::Watchdog;
+
+/// How often a supervised child is polled for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long a killed child is given to be reaped by the OS
+const KILL_WAIT: Duration = Duration::from_secs(5);
+
+/// How long output readers may keep draining after the child exits
+pub const OUTPUT_DRAIN: Duration = Duration::from_millis(500);
+
+/// Buffer a pipe is read into as data arrives
+type SharedBuffer = Arc<Mutex<OutputBuffer>>;
+
+/// Output of a child, decoded once capture ends
+#[derive(Debug, Default)]
+pub struct CapturedOutput {
+    /// Standard output, lossily decoded as UTF-8
+    pub stdout: String,
+    /// Standard error, lossily decoded as UTF-8
+    pub stderr: String,
+    /// Exact bytes of both streams, when either was not valid UTF-8
+    pub raw: Option<RawOutput>,
+}
+
+/// Output captured from a child's stdout and stderr pipes
+///
+/// Reader threads append to shared buffers as data arrives, so everything
+/// the program printed before it was killed is still available.
+#[derive(Debug)]
+pub struct OutputCapture {
+    stdout: SharedBuffer,
+    stderr: SharedBuffer,
+    readers: Vec<JoinHandle<()>>,
+}
+
+impl OutputCapture {
+    /// Start capturing the child's piped stdout and stderr
+    ///
+    /// Takes ownership of the pipes; streams that were not piped are
+    /// reported as empty.
+    pub fn start(child: &mut Child) -> Self {
+        Self::streaming(child, None)
+    }
+
+    /// Start capturing, also passing each chunk to `sink` as it is read
+    ///
+    /// # Arguments
+    /// * `child` - Child whose piped stdout and stderr are taken
+    /// * `sink` - Receiver of live output, normally the request's
+    pub fn streaming(child: &mut Child, sink: Option<OutputSink>) -> Self {
+        let stdout = SharedBuffer::default();
+        let stderr = SharedBuffer::default();
+        let mut readers = Vec::with_capacity(2);
+
+        if let Some(pipe) = child.stdout.take() {
+            let live = sink.clone().map(|sink| (sink, OutputStream::Stdout));
+            readers.push(spawn_reader(pipe, Arc::clone(&stdout), live));
+        }
+        if let Some(pipe) = child.stderr.take() {
+            let live = sink.map(|sink| (sink, OutputStream::Stderr));
+            readers.push(spawn_reader(pipe, Arc::clone(&stderr), live));
+        }
+
+        Self {
+            stdout,
+            stderr,
+            readers,
+        }
+    }
+
+    /// Wait briefly for the pipes to close, then return what was captured
+    ///
+    /// # Arguments
+    /// * `drain` - Maximum time to wait for readers to reach end of stream
+    ///
+    /// # Returns
+    /// Captured output; buffers no reader holds any more are decoded in
+    /// place rather than copied
+    pub async fn collect(self, drain: Duration) -> CapturedOutput {
+        // Grandchildren that inherited the pipes can hold them open after
+        // the child is gone, so never block indefinitely on the readers
+        let deadline = Instant::now() + drain;
+        while Instant::now() < deadline && self.readers.iter().any(|r| !r.is_finished()) {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        let (stdout, raw_stdout) = decode(self.stdout);
+        let (stderr, raw_stderr) = decode(self.stderr);
+        CapturedOutput {
+            raw: output::raw_output(&stdout, raw_stdout, &stderr, raw_stderr),
+            stdout,
+            stderr,
+        }
+    }
+
+    /// Total bytes captured so far on both streams
+    pub fn bytes_captured(&self) -> u64 {
+        let len = |buffer: &SharedBuffer| buffer.lock().map_or(0, |buf| buf.len() as u64);
+        len(&self.stdout) + len(&self.stderr)
+    }
+}
+
+/// Copy a pipe into a shared buffer until end of stream, passing each
+/// chunk on to a live sink too
+fn spawn_reader<R: Read + Send + 'static>(
+    mut pipe: R,
+    buffer: SharedBuffer,
+    live: Option<(OutputSink, OutputStream)>,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut chunk = [0u8; 8192];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if let Some((sink, stream)) = &live {
+                        sink.emit(*stream, &chunk[..n]);
+                    }
+                    match buffer.lock() {
+                        Ok(mut buf) => buf.extend(&chunk[..n]),
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Decode a shared buffer: in place once its reader is gone, otherwise
+/// a copy of what the still-running reader has appended so far
+fn decode(buffer: SharedBuffer) -> (String, Option<bytes::Bytes>) {
+    match Arc::try_unwrap(buffer) {
+        Ok(buffer) => buffer
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take(),
+        Err(buffer) => buffer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .snapshot(),
+    }
+}
+
+/// How a supervised child finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitOutcome {
+    /// Exited on its own before the timeout
+    Exited(ExitStatus),
+    /// Hit the timeout and was terminated
+    TimedOut {
+        /// Whether it exited during the grace period rather than being killed
+        graceful: bool,
+    },
+    /// Made no progress for the watchdog's stall timeout and was terminated
+    Stalled {
+        /// Whether it exited during the grace period rather than being killed
+        graceful: bool,
+    },
+}
+
+/// Why a supervised child stopped being polled
+enum Wake {
+    Exited(ExitStatus),
+    Deadline,
+    Stalled,
+}
+
+/// Wait for a child, escalating from a polite termination request to a kill
+///
+/// On timeout `terminate` is invoked (SIGTERM, CTRL_BREAK, ...) and the
+/// child gets `grace` to exit; if it is still running afterwards `kill` is
+/// invoked. Stdin is closed before waiting so programs reading to end of
+/// input do not hang.
+///
+/// # Arguments
+/// * `child` - Spawned child process
+/// * `timeout` - Time the child may run before termination starts
+/// * `grace` - Time between the termination request and the kill
+/// * `terminate` - Sends the graceful termination request
+/// * `kill` - Forcefully stops the child
+pub async fn wait_with_grace(
+    child: &mut Child,
+    timeout: Duration,
+    grace: Duration,
+    terminate: impl FnOnce(),
+    kill: impl FnOnce(),
+) -> io::Result<WaitOutcome> {
+    let watchdog: Option<Watchdog<fn() -> u64>> = None;
+    wait_supervised(child, timeout, grace, watchdog, terminate, kill).await
+}
+
+/// Wait for a child like `wait_with_grace`, also terminating it once the
+/// watchdog reports it stalled
+///
+/// # Arguments
+/// * `child` - Spawned child process
+/// * `timeout` - Time the child may run before termination starts
+/// * `grace` - Time between the termination request and the kill
+/// * `watchdog` - Liveness watchdog, or None to wait for the timeout only
+/// * `terminate` - Sends the graceful termination request
+/// * `kill` - Forcefully stops the child
+pub async fn wait_supervised<P: FnMut() -> u64>(
+    child: &mut Child,
+    timeout: Duration,
+    grace: Duration,
+    mut watchdog: Option<Watchdog<P>>,
+    terminate: impl FnOnce(),
+    kill: impl FnOnce(),
+) -> io::Result<WaitOutcome> {
+    drop(child.stdin.take());
+
+    let stalled = match poll_until(child, timeout, watchdog.as_mut()).await? {
+        Wake::Exited(status) => return Ok(WaitOutcome::Exited(status)),
+        Wake::Deadline => false,
+        Wake::Stalled => true,
+    };
+    let terminated = |graceful| {
+        if stalled {
+            WaitOutcome::Stalled { graceful }
+        } else {
+            WaitOutcome::TimedOut { graceful }
+        }
+    };
+
+    // Escalation is not watched; the child is already being stopped
+    let unwatched = || None::<&mut Watchdog<P>>;
+    terminate();
+    if !grace.is_zero()
+        && matches!(poll_until(child, grace, unwatched()).await?, Wake::Exited(_))
+    {
+        return Ok(terminated(true));
+    }
+
+    kill();
+    // Reap the child so it does not linger as a zombie
+    let _ = poll_until(child, KILL_WAIT, unwatched()).await?;
+    Ok(terminated(false))
+}
+
+/// Poll a child until it exits, the duration elapses, or the watchdog
+/// reports it stalled
+///
+/// Durations too large to represent as a deadline (e.g. `Duration::MAX`)
+/// wait indefinitely.
+async fn poll_until<P: FnMut() -> u64>(
+    child: &mut Child,
+    duration: Duration,
+    mut watchdog: Option<&mut Watchdog<P>>,
+) -> io::Result<Wake> {
+    let deadline = Instant::now().checked_add(duration);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Wake::Exited(status));
+        }
+        if let Some(watchdog) = watchdog.as_deref_mut()
+            && watchdog.is_stalled(Instant::now())
+        {
+            return Ok(Wake::Stalled);
+        }
+        let remaining = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => POLL_INTERVAL,
+        };
+        if remaining.is_zero() {
+            return Ok(Wake::Deadline);
+        }
+        tokio::time::sleep(POLL_INTERVAL.min(remaining)).await;
+    }
+}
+
+/// Send SIGTERM to every process in a process group
+#[cfg(target_os = "linux")]
+pub fn terminate_group(pgid: u32) {
+    use nix::sys::signal::{Signal, killpg};
+    use nix::unistd::Pid;
+
+    let _ = killpg(Pid::from_raw(pgid as i32), Signal::SIGTERM);
+}
+
+/// Send SIGKILL to every process in a process group
+#[cfg(target_os = "linux")]
+pub fn kill_group(pgid: u32) {
+    use nix::sys::signal::{Signal, killpg};
+    use nix::unistd::Pid;
+
+    let _ = killpg(Pid::from_raw(pgid as i32), Signal::SIGKILL);
+}
+
+/// Send SIGKILL to every process in a process group
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn kill_group(pgid: u32) {
+    let _ = std::process::Command::new("kill")
+        .args(["-KILL", "--", &format!("-{pgid}")])
+        .stderr(std::process::Stdio::null())
+        .status();
+}
+
+/// Send CTRL_BREAK to a console process group
+///
+/// The child must have been spawned with `CREATE_NEW_PROCESS_GROUP` so the
+/// event reaches only the child and its descendants.
+#[cfg(target_os = "windows")]
+pub fn send_ctrl_break(process_group_id: u32) {
+    use windows::Win32::System::Console::{CTRL_BREAK_EVENT, GenerateConsoleCtrlEvent};
+
+    unsafe {
+        let _ = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, process_group_id);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+
+    #[tokio::test]
+    async fn exited_child_reports_status_and_output() {
+        let mut child = Command::new("sh")
+            .args(["-c", "echo out; echo err >&2"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let capture = OutputCapture::start(&mut child);
+
+        let outcome =
+            wait_with_grace(&mut child, Duration::from_secs(5), Duration::ZERO, || {}, || {})
+                .await
+                .unwrap();
+        let CapturedOutput { stdout, stderr, .. } = capture.collect(OUTPUT_DRAIN).await;
+
+        assert!(matches!(outcome, WaitOutcome::Exited(status) if status.success()));
+        assert_eq!(stdout, "out\n");
+        assert_eq!(stderr, "err\n");
+    }
+
+    #[tokio::test]
+    async fn output_reaches_the_sink_while_the_child_runs() {
+        let mut child = Command::new("sh")
+            .args(["-c", "echo first; sleep 30"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let (sink, mut chunks) = crate::backends::OutputChunk::channel();
+        let capture = OutputCapture::streaming(&mut child, Some(sink));
+
+        let chunk = tokio::time::timeout(Duration::from_secs(10), chunks.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk.stream, OutputStream::Stdout);
+        assert_eq!(chunk.text(), "first\n");
+
+        crate::reaper::kill_process(pid);
+        let _ = child.wait();
+        assert_eq!(capture.collect(OUTPUT_DRAIN).await.stdout, "first\n");
+    }
+
+    #[tokio::test]
+    async fn timed_out_child_keeps_partial_output() {
+        let mut child = Command::new("sh")
+            .args(["-c", "echo before; sleep 30; echo after"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let capture = OutputCapture::start(&mut child);
+
+        let outcome = wait_with_grace(
+            &mut child,
+            Duration::from_millis(300),
+            Duration::from_millis(300),
+            || {},
+            || crate::reaper::kill_process(pid),
+        )
+        .await
+        .unwrap();
+        let stdout = capture.collect(OUTPUT_DRAIN).await.stdout;
+
+        assert_eq!(outcome, WaitOutcome::TimedOut { graceful: false });
+        assert_eq!(stdout, "before\n");
+    }
+
+    #[tokio::test]
+    async fn idle_child_is_stopped_by_the_watchdog() {
+        let mut child = Command::new("sh")
+            .args(["-c", "echo waiting; sleep 30"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let capture = OutputCapture::start(&mut child);
+
+        let started = Instant::now();
+        let watchdog = Watchdog::new(Duration::from_millis(500), || capture.bytes_captured());
+        let outcome = wait_supervised(
+            &mut child,
+            Duration::from_secs(30),
+            Duration::ZERO,
+            Some(watchdog),
+            || {},
+            || crate::reaper::kill_process(pid),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, WaitOutcome::Stalled { graceful: false });
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let stdout = capture.collect(OUTPUT_DRAIN).await.stdout;
+        assert_eq!(stdout, "waiting\n");
+    }
+}
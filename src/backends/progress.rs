@@ -0,0 +1,153 @@
+// ============================================================================
+// File: packages/cylo/src/backends/progress.rs
+// ----------------------------------------------------------------------------
+// Structured progress events for slow provisioning steps.
+//
+// Pulling images, booting VMs and preparing workspaces can take tens of
+// seconds before any code runs. Backends report each step through the
+// reporter attached to the request so UIs can show "Pulling python:3.12 …
+// 60%" instead of a silent wait.
+// ============================================================================
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::async_task::{EventSender, EventStream, event_stream};
+
+/// Events buffered for a receiver that is not keeping up
+const PROGRESS_CAPACITY: usize = 256;
+
+/// Provisioning step a progress event belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProvisioningStage {
+    /// Fetching a container image
+    PullingImage,
+    /// Booting a microVM
+    BootingVm,
+    /// Creating a language environment (virtualenv, toolchain cache)
+    CreatingEnvironment,
+    /// Laying out the execution's working directory
+    PreparingWorkspace,
+    /// Provisioning finished; the code is about to run
+    Ready,
+}
+
+impl fmt::Display for ProvisioningStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::PullingImage => "pulling image",
+            Self::BootingVm => "booting vm",
+            Self::CreatingEnvironment => "creating environment",
+            Self::PreparingWorkspace => "preparing workspace",
+            Self::Ready => "ready",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One progress update of a provisioning step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvisioningEvent {
+    /// Step being reported
+    pub stage: ProvisioningStage,
+    /// Completion of the step, when the backend can measure it
+    pub percent: Option<u8>,
+    /// Human-readable description, e.g. "Pulling python:3.12"
+    pub message: String,
+}
+
+impl ProvisioningEvent {
+    /// Create an event; `percent` is clamped to 100
+    pub fn new<M: Into<String>>(stage: ProvisioningStage, percent: Option<u8>, message: M) -> Self {
+        Self {
+            stage,
+            percent: percent.map(|p| p.min(100)),
+            message: message.into(),
+        }
+    }
+
+    /// Create a reporter and the bounded stream its events arrive on
+    ///
+    /// # Returns
+    /// Reporter to attach to a request, and the receiving end for the UI
+    pub fn channel() -> (ProgressReporter, EventStream<ProvisioningEvent>) {
+        let (tx, rx) = event_stream(Some(PROGRESS_CAPACITY));
+        (ProgressReporter { tx }, rx)
+    }
+}
+
+impl fmt::Display for ProvisioningEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.percent {
+            Some(percent) => write!(f, "{} … {}%", self.message, percent),
+            None => write!(f, "{} …", self.message),
+        }
+    }
+}
+
+/// Sending half of a provisioning event channel
+///
+/// Reporting never blocks and never fails: events are dropped once the
+/// receiver is gone, and the oldest ones when it falls behind, so backends
+/// report unconditionally.
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    tx: EventSender<ProvisioningEvent>,
+}
+
+impl ProgressReporter {
+    /// Send an event to the receiver, if it is still listening
+    pub fn report<M: Into<String>>(
+        &self,
+        stage: ProvisioningStage,
+        percent: Option<u8>,
+        message: M,
+    ) {
+        let _ = self.tx.send(ProvisioningEvent::new(stage, percent, message));
+    }
+}
+
+/// Extract the last percentage (e.g. "60%") printed on a progress line
+///
+/// # Arguments
+/// * `line` - One line of a tool's progress output
+///
+/// # Returns
+/// The percentage, clamped to 100, or None if the line has none
+pub(crate) fn parse_percent(line: &str) -> Option<u8> {
+    line.split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | ','))
+        .rev()
+        .filter_map(|token| token.strip_suffix('%'))
+        .filter_map(|number| number.parse::<f64>().ok())
+        .find(|value| value.is_finite() && *value >= 0.0)
+        .map(|value| value.min(100.0) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_reach_the_receiver_and_render() {
+        let (reporter, mut rx) = ProvisioningEvent::channel();
+        reporter.report(ProvisioningStage::PullingImage, Some(60), "Pulling python:3.12");
+        reporter.report(ProvisioningStage::Ready, Some(250), "Ready");
+
+        let pulling = rx.try_recv().expect("first event");
+        assert_eq!(pulling.to_string(), "Pulling python:3.12 … 60%");
+        assert_eq!(rx.try_recv().expect("second event").percent, Some(100));
+
+        drop(rx);
+        reporter.report(ProvisioningStage::Ready, None, "ignored once the receiver is gone");
+    }
+
+    #[test]
+    fn percentages_are_parsed_from_tool_output() {
+        assert_eq!(parse_percent("Fetching layer 3/5 [=====>   ] 60%"), Some(60));
+        assert_eq!(parse_percent("sha256:ab12 (42.7%)"), Some(42));
+        assert_eq!(parse_percent("downloaded 100%, unpacking 5%"), Some(5));
+        assert_eq!(parse_percent("resolving manifest"), None);
+        assert_eq!(parse_percent("odd 300%"), Some(100));
+    }
+}
@@ -0,0 +1,173 @@
+// ============================================================================
+// File: packages/cylo/src/backends/provisioning.rs
+// ----------------------------------------------------------------------------
+// Time and size budgets for provisioning steps.
+//
+// A huge image or a registry that trickles bytes used to hold an execution
+// in provisioning for as long as the pull took. Each backend config now
+// bounds how long pulling an image and preparing a VM's root filesystem
+// may take, and how much a pull may download; a step over its budget is
+// abandoned with a typed error naming the step.
+// ============================================================================
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{BackendError, BackendResult, ProvisioningStage};
+
+/// Budgets of a backend's provisioning steps; None leaves a step unbounded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProvisioningLimits {
+    /// Time an image pull or load from the image store may take
+    pub pull_timeout: Option<Duration>,
+    /// Time locating and verifying a VM's root filesystem may take
+    pub rootfs_timeout: Option<Duration>,
+    /// Bytes an image pull may download
+    pub max_download_bytes: Option<u64>,
+}
+
+impl Default for ProvisioningLimits {
+    fn default() -> Self {
+        Self {
+            pull_timeout: Some(Duration::from_secs(600)),
+            rootfs_timeout: Some(Duration::from_secs(300)),
+            max_download_bytes: None,
+        }
+    }
+}
+
+/// Time budget of one provisioning step, running from its creation
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StageBudget {
+    backend: &'static str,
+    stage: ProvisioningStage,
+    limit: Option<Duration>,
+    started: Instant,
+}
+
+impl StageBudget {
+    /// Start timing a step
+    pub(crate) fn start(
+        backend: &'static str,
+        stage: ProvisioningStage,
+        limit: Option<Duration>,
+    ) -> Self {
+        Self {
+            backend,
+            stage,
+            limit,
+            started: Instant::now(),
+        }
+    }
+
+    /// Time left; None for an unbounded step, zero once it is spent
+    pub(crate) fn remaining(&self) -> Option<Duration> {
+        self.limit
+            .map(|limit| limit.saturating_sub(self.started.elapsed()))
+    }
+
+    /// Error reported once the budget is spent
+    pub(crate) fn exceeded(&self) -> BackendError {
+        BackendError::ProvisioningTimeout {
+            backend: self.backend,
+            stage: self.stage,
+            limit: self.limit.unwrap_or_default(),
+        }
+    }
+
+    /// Run `step`, abandoning it once the budget is spent
+    ///
+    /// Blocking work should run on the blocking pool so it cannot hold up
+    /// the timer; it is left to finish in the background.
+    pub(crate) async fn run<T, F>(&self, step: F) -> BackendResult<T>
+    where
+        F: Future<Output = BackendResult<T>>,
+    {
+        match self.remaining() {
+            Some(remaining) => tokio::time::timeout(remaining, step)
+                .await
+                .unwrap_or_else(|_| Err(self.exceeded())),
+            None => step.await,
+        }
+    }
+}
+
+/// Largest byte count printed on a progress line, e.g. 45600000 for
+/// "12.3 MB / 45.6 MB"
+///
+/// On a line showing progress against a total this is the total, and
+/// otherwise the amount transferred so far.
+pub(crate) fn parse_bytes(line: &str) -> Option<u64> {
+    let tokens: Vec<&str> = line
+        .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | ',' | '/'))
+        .filter(|token| !token.is_empty())
+        .collect();
+    let mut largest = None;
+    for (index, token) in tokens.iter().enumerate() {
+        // The unit is either attached ("12.3MB") or the next token
+        let split = token.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(token.len());
+        let (number, unit) = token.split_at(split);
+        let unit = match (unit, tokens.get(index + 1)) {
+            ("", Some(next)) => *next,
+            (unit, _) => unit,
+        };
+        let (Ok(value), Some(scale)) = (number.parse::<f64>(), unit_scale(unit)) else {
+            continue;
+        };
+        if value.is_finite() && value >= 0.0 {
+            let bytes = (value * scale as f64) as u64;
+            largest = largest.max(Some(bytes));
+        }
+    }
+    largest
+}
+
+/// Bytes per unit of a size suffix
+fn unit_scale(unit: &str) -> Option<u64> {
+    Some(match unit {
+        "B" => 1,
+        "kB" | "KB" => 1000,
+        "MB" => 1000 * 1000,
+        "GB" => 1000 * 1000 * 1000,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn download_sizes_are_read_from_progress_lines() {
+        assert_eq!(parse_bytes("Fetching 12.3 MB / 45.6 MB (27%)"), Some(45_600_000));
+        assert_eq!(parse_bytes("[3/5] 512KiB/1.5GiB"), Some(3 * (1 << 29)));
+        assert_eq!(parse_bytes("Downloaded 800B"), Some(800));
+        assert_eq!(parse_bytes("Resolving layers 3 of 5"), None);
+    }
+
+    #[tokio::test]
+    async fn steps_over_budget_are_abandoned() {
+        let budget = StageBudget::start(
+            "Apple",
+            ProvisioningStage::PullingImage,
+            Some(Duration::from_millis(20)),
+        );
+        let slow = budget.run(async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        });
+        let error = slow.await.unwrap_err();
+        assert!(matches!(error, BackendError::ProvisioningTimeout { .. }));
+        assert_eq!(error.to_string(), "Apple gave up pulling image after 20ms");
+
+        let unbounded = StageBudget::start("Apple", ProvisioningStage::PullingImage, None);
+        assert_eq!(unbounded.remaining(), None);
+        assert_eq!(unbounded.run(async { Ok(7) }).await.unwrap(), 7);
+    }
+}
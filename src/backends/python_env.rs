@@ -0,0 +1,168 @@
+// ============================================================================
+// File: packages/cylo/src/backends/python_env.rs
+// ----------------------------------------------------------------------------
+// Activation of a named Python virtual environment for an execution.
+//
+// The sandbox module builds venvs (usually inside the ramdisk) and registers
+// them by name. A Python request naming one runs the venv's interpreter with
+// the venv exposed read-only and activated the way its `activate` script
+// would: VIRTUAL_ENV set and the venv's scripts directory first on PATH.
+// ============================================================================
+
+use std::path::{Path, PathBuf};
+
+use crate::backends::{BackendError, BackendResult, ExecutionRequest, language};
+use crate::sandbox::registered_environment;
+
+/// Variables the activation computes itself rather than taking from the
+/// environment's recorded ones
+const ACTIVATION_VARS: &[&str] = &["VIRTUAL_ENV", "PATH", "PYTHONHOME"];
+
+/// Virtual environment a Python execution runs in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PythonEnv {
+    /// Canonical venv root
+    root: PathBuf,
+    /// Variables recorded when the venv was created, minus the activation's
+    extra_vars: Vec<(String, String)>,
+}
+
+impl PythonEnv {
+    /// Resolve the venv a request names
+    ///
+    /// # Arguments
+    /// * `request` - Execution request
+    /// * `backend` - Backend name used in errors
+    ///
+    /// # Returns
+    /// None for requests that name no venv or are not Python, otherwise the
+    /// venv, or an error when the name is unknown or not a usable Python
+    /// environment
+    pub fn of(request: &ExecutionRequest, backend: &'static str) -> BackendResult<Option<Self>> {
+        let is_python =
+            language::resolve(&request.language).is_some_and(|spec| spec.name == "python");
+        let Some(name) = request.python_env.as_deref().filter(|_| is_python) else {
+            return Ok(None);
+        };
+        let invalid = |details: String| BackendError::InvalidConfig { backend, details };
+
+        let env = registered_environment(name)
+            .ok_or_else(|| invalid(format!("no environment is registered as '{name}'")))?;
+        if env.env_type != "python" || !env.is_valid {
+            return Err(invalid(format!("'{name}' is not a usable Python environment")));
+        }
+        let root = env.path.canonicalize().map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Python environment '{}' is not accessible: {}", name, e),
+        })?;
+
+        let extra_vars = env
+            .env_vars
+            .into_iter()
+            .filter(|(key, _)| !ACTIVATION_VARS.contains(&key.as_str()))
+            .collect();
+        Ok(Some(Self { root, extra_vars }))
+    }
+
+    /// Venv root, exposed read-only at the same location
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Directory holding the venv's interpreter and console scripts
+    pub fn scripts_dir(&self) -> PathBuf {
+        if cfg!(windows) {
+            self.root.join("Scripts")
+        } else {
+            self.root.join("bin")
+        }
+    }
+
+    /// Interpreter to run the code with
+    pub fn interpreter(&self) -> PathBuf {
+        if cfg!(windows) {
+            self.scripts_dir().join("python.exe")
+        } else {
+            self.scripts_dir().join("python")
+        }
+    }
+
+    /// Variables activating the venv
+    ///
+    /// Backends apply them after the environment profile and before the
+    /// request's variables, which still win.
+    ///
+    /// # Arguments
+    /// * `path` - PATH the sandboxed process would otherwise get
+    pub fn env_vars(&self, path: Option<&str>) -> Vec<(String, String)> {
+        let scripts = self.scripts_dir().display().to_string();
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        let path = match path.filter(|path| !path.is_empty()) {
+            Some(path) => format!("{scripts}{separator}{path}"),
+            None => scripts,
+        };
+
+        let mut vars = vec![
+            ("VIRTUAL_ENV".to_string(), self.root.display().to_string()),
+            ("PATH".to_string(), path),
+        ];
+        vars.extend(self.extra_vars.iter().cloned());
+        vars
+    }
+}
+
+/// Refuse a Python request naming a venv on a backend running a guest OS
+///
+/// A host venv links to the host's interpreter and holds host-built
+/// extension modules, neither of which a VM or container image can run;
+/// such requests fail instead of silently running the system Python.
+pub fn reject_in_guest(request: &ExecutionRequest, backend: &'static str) -> BackendResult<()> {
+    let is_python = language::resolve(&request.language).is_some_and(|spec| spec.name == "python");
+    match &request.python_env {
+        Some(name) if is_python => Err(BackendError::NotAvailable {
+            backend,
+            reason: format!("host virtual environment '{name}' cannot run in a guest"),
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandbox::{SandboxedEnvironment, register_environment, unregister_environment};
+
+    #[test]
+    fn registered_venv_is_activated() {
+        let root = std::env::temp_dir().join(format!("cylo_venv_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let name = root.file_name().unwrap().to_string_lossy().to_string();
+
+        let mut env = SandboxedEnvironment::new("python", root.clone());
+        env.is_valid = true;
+        env.add_env_var("PATH", "/stale");
+        env.add_env_var("PYTHONUSERBASE", &root.display().to_string());
+        register_environment(&name, env);
+
+        let request = ExecutionRequest::new("print(1)", "python").with_python_env(&name);
+        let venv = PythonEnv::of(&request, "test").unwrap().expect("python request names a venv");
+        assert_eq!(venv.root(), root.canonicalize().unwrap());
+
+        let vars = venv.env_vars(Some("/usr/bin"));
+        let var = |name: &str| vars.iter().find(|(key, _)| key == name).map(|(_, v)| v.clone());
+        let scripts = venv.scripts_dir().display().to_string();
+        assert!(var("PATH").unwrap().starts_with(&scripts));
+        assert!(var("PATH").unwrap().ends_with("/usr/bin"));
+        assert!(var("PYTHONUSERBASE").is_some());
+        assert_eq!(vars.iter().filter(|(key, _)| key == "PATH").count(), 1);
+
+        let node = ExecutionRequest::new("1", "node").with_python_env(&name);
+        assert_eq!(PythonEnv::of(&node, "test").unwrap(), None);
+
+        unregister_environment(&name);
+        assert!(matches!(
+            PythonEnv::of(&request, "test"),
+            Err(BackendError::InvalidConfig { .. })
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,61 @@
+// ============================================================================
+// File: packages/cylo/src/backends/r_library.rs
+// ----------------------------------------------------------------------------
+// Per-execution package library for R.
+//
+// `install.packages()` writes into the first writable entry of `.libPaths()`,
+// which on a typical host is the invoking user's personal library. Every R
+// execution instead gets its own library inside its workspace: packages it
+// installs are visible to it alone and are removed with the workspace.
+// ============================================================================
+
+use crate::backends::{ExecutionRequest, language};
+
+/// Library directory below an execution's cache root
+pub const R_LIBRARY_DIR: &str = "R/library";
+
+/// Variables giving an R execution its own package library
+///
+/// Empty for any other language. R ignores an `R_LIBS_USER` that does not
+/// exist, so backends create the directory before starting Rscript. They
+/// apply the variables after the environment profile and before the
+/// request's variables, which still win.
+///
+/// # Arguments
+/// * `request` - Execution request
+/// * `cache_root` - Writable directory inside the sandbox that is removed
+///   with the workspace
+pub fn r_env(request: &ExecutionRequest, cache_root: &str) -> Vec<(&'static str, String)> {
+    if language::resolve(&request.language).map(|spec| spec.name) != Some("r") {
+        return Vec::new();
+    }
+    vec![("R_LIBS_USER", format!("{cache_root}/{R_LIBRARY_DIR}"))]
+}
+
+/// Shell command running an R script with its library in place
+///
+/// # Arguments
+/// * `rscript` - Rscript binary
+/// * `script` - Path of the script to run
+pub fn rscript_command(rscript: &str, script: &str) -> String {
+    format!("mkdir -p \"$R_LIBS_USER\" && exec {rscript} {script}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn library_stays_in_the_workspace() {
+        let request = ExecutionRequest::new("print(1)", "R");
+        assert_eq!(
+            r_env(&request, "/workspace/.cache"),
+            [("R_LIBS_USER", "/workspace/.cache/R/library".to_string())]
+        );
+        assert!(r_env(&ExecutionRequest::new("print(1)", "python"), "/tmp").is_empty());
+        assert_eq!(
+            rscript_command("Rscript", "main.R"),
+            "mkdir -p \"$R_LIBS_USER\" && exec Rscript main.R"
+        );
+    }
+}
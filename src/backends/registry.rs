@@ -0,0 +1,247 @@
+// ============================================================================
+// File: packages/cylo/src/backends/registry.rs
+// ----------------------------------------------------------------------------
+// Registry of third-party backends.
+//
+// Downstream crates register a factory for their own ExecutionBackend (an
+// in-house scheduler, a proprietary sandbox) under a name; from then on the
+// backend takes part in platform detection, routing and the CLI like the
+// built-in ones, through `Cylo::Custom` environments naming it. Register
+// backends before creating an executor: it reads the available backends
+// when it is created and on each platform cache refresh.
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::backends::config::BackendConfig;
+use crate::backends::trait_def::ExecutionBackend;
+use crate::backends::types::{BackendCapabilities, ExecutionRequest, IsolationLevel};
+use crate::execution_env::{CyloError, CyloResult};
+use crate::platform::BackendAvailability;
+
+/// Names of the built-in backends, which cannot be registered over
+const BUILT_IN_BACKENDS: [&str; 8] = [
+    "Apple",
+    "LandLock",
+    "FireCracker",
+    "Docker",
+    "Wasm",
+    "K8sJob",
+    "WindowsJob",
+    "SweetMcpPlugin",
+];
+
+/// Creates instances of a third-party backend
+pub trait BackendFactory: Send + Sync {
+    /// Create a backend instance
+    ///
+    /// # Arguments
+    /// * `env_config` - Backend-specific configuration of the environment,
+    ///   e.g. a path or image
+    /// * `config` - Backend configuration
+    ///
+    /// # Returns
+    /// Boxed backend instance or error if it cannot be created
+    fn create(
+        &self,
+        env_config: &str,
+        config: BackendConfig,
+    ) -> CyloResult<Box<dyn ExecutionBackend>>;
+
+    /// Check an environment's configuration before an instance is created
+    fn validate(&self, env_config: &str) -> CyloResult<()> {
+        let _ = env_config;
+        Ok(())
+    }
+
+    /// Environment configuration routing creates an instance with to serve
+    /// a request
+    fn env_config_for(&self, request: &ExecutionRequest) -> String {
+        let _ = request;
+        String::new()
+    }
+
+    /// Whether the backend can run on this host
+    ///
+    /// # Returns
+    /// Ok(()) or the reason it cannot
+    fn detect(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// What the backend offers executions, consulted by routing before any
+    /// instance exists
+    ///
+    /// Defaults to the weakest descriptor: process isolation and no
+    /// optional features.
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            isolation: IsolationLevel::Process,
+            max_memory_bytes: None,
+            network_control: false,
+            persistent_sessions: false,
+            artifact_collection: false,
+            streaming: false,
+        }
+    }
+
+    /// Performance rating (0-100) routing weighs the backend by
+    fn performance_rating(&self) -> u8 {
+        50
+    }
+}
+
+type Registry = RwLock<BTreeMap<&'static str, Arc<dyn BackendFactory>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+/// Register a third-party backend
+///
+/// # Arguments
+/// * `name` - Backend name: letters, digits, `-` and `_`, not one of the
+///   built-in backends
+/// * `factory` - Creates its instances
+///
+/// # Returns
+/// Error if the name is invalid or already registered
+pub fn register_backend(name: &'static str, factory: Box<dyn BackendFactory>) -> CyloResult<()> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(CyloError::validation(format!("'{name}' is not a valid backend name")));
+    }
+    if BUILT_IN_BACKENDS.contains(&name) {
+        return Err(CyloError::validation(format!("'{name}' is a built-in backend")));
+    }
+
+    let mut registry = registry()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if registry.contains_key(name) {
+        return Err(CyloError::validation(format!("Backend '{name}' is already registered")));
+    }
+    registry.insert(name, Arc::from(factory));
+    Ok(())
+}
+
+/// Remove a registered backend; instances already created keep running
+///
+/// # Returns
+/// false if no backend was registered under the name
+pub fn unregister_backend(name: &str) -> bool {
+    registry()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .remove(name)
+        .is_some()
+}
+
+/// Names of the registered backends, sorted
+pub fn registered_backends() -> Vec<&'static str> {
+    registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .keys()
+        .copied()
+        .collect()
+}
+
+/// Registered factory by backend name, with the name as registered
+pub(crate) fn factory(name: &str) -> Option<(&'static str, Arc<dyn BackendFactory>)> {
+    registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_key_value(name)
+        .map(|(name, factory)| (*name, Arc::clone(factory)))
+}
+
+/// Capabilities of a registered backend
+pub(crate) fn capabilities(name: &str) -> Option<BackendCapabilities> {
+    factory(name).map(|(_, factory)| factory.capabilities())
+}
+
+/// Availability of every registered backend on this host
+pub(crate) fn availability() -> Vec<BackendAvailability> {
+    let factories: Vec<_> = registry()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .map(|(name, factory)| (*name, Arc::clone(factory)))
+        .collect();
+
+    // Detection may probe the host, so it runs outside the lock
+    factories
+        .into_iter()
+        .map(|(name, factory)| {
+            let detected = factory.detect();
+            BackendAvailability {
+                name: name.to_string(),
+                available: detected.is_ok(),
+                reason: detected.err().unwrap_or_else(|| "Registered backend".to_string()),
+                capabilities: Some(factory.capabilities()),
+                performance_rating: factory.performance_rating(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct UnavailableFactory;
+
+    impl BackendFactory for UnavailableFactory {
+        fn create(&self, _: &str, _: BackendConfig) -> CyloResult<Box<dyn ExecutionBackend>> {
+            Err(CyloError::backend_unavailable("Scheduler", "no scheduler configured"))
+        }
+
+        fn detect(&self) -> Result<(), String> {
+            Err("no scheduler configured".to_string())
+        }
+
+        fn capabilities(&self) -> BackendCapabilities {
+            BackendCapabilities {
+                isolation: IsolationLevel::MicroVM,
+                ..BackendFactory::capabilities(&PlainFactory)
+            }
+        }
+    }
+
+    struct PlainFactory;
+
+    impl BackendFactory for PlainFactory {
+        fn create(&self, _: &str, _: BackendConfig) -> CyloResult<Box<dyn ExecutionBackend>> {
+            Err(CyloError::internal("not creatable"))
+        }
+    }
+
+    #[test]
+    fn registered_backends_join_detection_and_capabilities() {
+        assert!(register_backend("LandLock", Box::new(PlainFactory)).is_err());
+        assert!(register_backend("in house", Box::new(PlainFactory)).is_err());
+
+        register_backend("RegistryTestScheduler", Box::new(UnavailableFactory)).unwrap();
+        assert!(register_backend("RegistryTestScheduler", Box::new(PlainFactory)).is_err());
+        assert!(registered_backends().contains(&"RegistryTestScheduler"));
+
+        let detected = availability()
+            .into_iter()
+            .find(|backend| backend.name == "RegistryTestScheduler")
+            .unwrap();
+        assert!(!detected.available);
+        assert_eq!(detected.reason, "no scheduler configured");
+        assert_eq!(
+            IsolationLevel::of_backend("RegistryTestScheduler"),
+            Some(IsolationLevel::MicroVM)
+        );
+
+        let (name, _) = factory("RegistryTestScheduler").unwrap();
+        assert_eq!(name, "RegistryTestScheduler");
+        assert!(unregister_backend("RegistryTestScheduler"));
+        assert!(capabilities("RegistryTestScheduler").is_none());
+    }
+}
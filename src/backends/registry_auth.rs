@@ -0,0 +1,232 @@
+// ============================================================================
+// File: packages/cylo/src/backends/registry_auth.rs
+// ----------------------------------------------------------------------------
+// Credentials for pulling images from private registries.
+//
+// Credentials are configured per registry host, either inline (user name
+// and password, or an access token) or by naming a docker-credential-*
+// helper that is asked at pull time, so secrets can stay in the OS keychain
+// or a cloud credential provider instead of the config file.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{BackendError, BackendResult};
+
+/// Registry host of images referenced without one
+pub const DEFAULT_REGISTRY: &str = "docker.io";
+
+/// User name sent with access tokens
+///
+/// Token-authenticated registries either expect this name (GCR, Artifact
+/// Registry) or ignore it (GHCR, Quay); use `Basic` where a registry needs
+/// a specific user alongside the token.
+pub const TOKEN_USERNAME: &str = "oauth2accesstoken";
+
+/// How to authenticate against one registry
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RegistryAuth {
+    /// User name and password
+    Basic {
+        /// User name
+        username: String,
+        /// Password
+        password: String,
+    },
+    /// Access token
+    Token {
+        /// Token value
+        token: String,
+    },
+    /// Credential helper, e.g. "osxkeychain" for docker-credential-osxkeychain
+    Helper {
+        /// Helper name without the docker-credential- prefix
+        helper: String,
+    },
+}
+
+impl fmt::Debug for RegistryAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            Self::Token { .. } => f.debug_struct("Token").field("token", &"<redacted>").finish(),
+            Self::Helper { helper } => f.debug_struct("Helper").field("helper", helper).finish(),
+        }
+    }
+}
+
+/// Resolved user name and secret for one registry login
+#[derive(Clone, PartialEq, Eq)]
+pub struct RegistryLogin {
+    /// Registry host the login is for
+    pub registry: String,
+    /// User name
+    pub username: String,
+    /// Password or token
+    pub secret: String,
+}
+
+impl fmt::Debug for RegistryLogin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegistryLogin")
+            .field("registry", &self.registry)
+            .field("username", &self.username)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Registry credentials keyed by registry host
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegistryCredentials {
+    /// Credentials per registry host (e.g. "ghcr.io", "localhost:5000")
+    pub registries: HashMap<String, RegistryAuth>,
+    /// Helper asked for registries without an entry
+    pub default_helper: Option<String>,
+}
+
+impl RegistryCredentials {
+    /// Configure credentials for a registry host
+    pub fn with_registry<R: Into<String>>(mut self, registry: R, auth: RegistryAuth) -> Self {
+        self.registries.insert(registry.into(), auth);
+        self
+    }
+
+    /// Ask `helper` for registries without an explicit entry
+    pub fn with_default_helper<H: Into<String>>(mut self, helper: H) -> Self {
+        self.default_helper = Some(helper.into());
+        self
+    }
+
+    /// Whether no credentials are configured at all
+    pub fn is_empty(&self) -> bool {
+        self.registries.is_empty() && self.default_helper.is_none()
+    }
+
+    /// Resolve the login for a registry host
+    ///
+    /// Helpers are run at this point, so rotated secrets are picked up on
+    /// the next pull.
+    ///
+    /// # Returns
+    /// The login, None when the registry needs no credentials, or an error
+    /// when a helper failed
+    pub fn resolve(&self, registry: &str) -> BackendResult<Option<RegistryLogin>> {
+        let login = |username: &str, secret: &str| RegistryLogin {
+            registry: registry.to_string(),
+            username: username.to_string(),
+            secret: secret.to_string(),
+        };
+        match self.registries.get(registry) {
+            Some(RegistryAuth::Basic { username, password }) => Ok(Some(login(username, password))),
+            Some(RegistryAuth::Token { token }) => Ok(Some(login(TOKEN_USERNAME, token))),
+            Some(RegistryAuth::Helper { helper }) => run_helper(helper, registry),
+            None => match &self.default_helper {
+                Some(helper) => run_helper(helper, registry),
+                None => Ok(None),
+            },
+        }
+    }
+}
+
+/// Credential helper response, per the docker-credential-helpers protocol
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HelperResponse {
+    username: String,
+    secret: String,
+}
+
+/// Ask a docker-credential-* helper for a registry's credentials
+fn run_helper(helper: &str, registry: &str) -> BackendResult<Option<RegistryLogin>> {
+    let program = format!("docker-credential-{helper}");
+    let failed = |details: String| BackendError::InvalidConfig {
+        backend: "registry",
+        details,
+    };
+
+    let mut child = Command::new(&program)
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(format!("Failed to run credential helper {program}: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(registry.as_bytes())
+            .map_err(|e| failed(format!("Failed to query credential helper {program}: {e}")))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failed(format!("Credential helper {program} failed: {e}")))?;
+
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stdout);
+        // Helpers report unknown registries this way; pull anonymously
+        if message.contains("credentials not found") {
+            return Ok(None);
+        }
+        return Err(failed(format!(
+            "Credential helper {program} failed for {registry}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let response: HelperResponse = serde_json::from_slice(&output.stdout)
+        .map_err(|e| failed(format!("Invalid response from {program}: {e}")))?;
+    Ok(Some(RegistryLogin {
+        registry: registry.to_string(),
+        username: response.username,
+        secret: response.secret,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_credentials_resolve_and_stay_out_of_logs() {
+        let credentials = RegistryCredentials::default()
+            .with_registry(
+                "registry.corp.example",
+                RegistryAuth::Basic {
+                    username: "ci".to_string(),
+                    password: "hunter2".to_string(),
+                },
+            )
+            .with_registry("ghcr.io", RegistryAuth::Token { token: "ghp_secret".to_string() });
+
+        let basic = credentials.resolve("registry.corp.example").expect("resolve");
+        let basic = basic.expect("basic login");
+        assert_eq!((basic.username.as_str(), basic.secret.as_str()), ("ci", "hunter2"));
+
+        let token = credentials.resolve("ghcr.io").expect("resolve").expect("token login");
+        assert_eq!(token.username, TOKEN_USERNAME);
+        assert_eq!(token.secret, "ghp_secret");
+
+        assert_eq!(credentials.resolve(DEFAULT_REGISTRY).expect("resolve"), None);
+
+        let logged = format!("{credentials:?} {basic:?}");
+        assert!(!logged.contains("hunter2") && !logged.contains("ghp_secret"), "{logged}");
+    }
+
+    #[test]
+    fn missing_helper_is_an_error() {
+        let credentials =
+            RegistryCredentials::default().with_default_helper("cylo-test-missing-helper");
+        assert!(credentials.resolve("registry.corp.example").is_err());
+    }
+}
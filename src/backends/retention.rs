@@ -0,0 +1,106 @@
+// ============================================================================
+// File: packages/cylo/src/backends/retention.rs
+// ----------------------------------------------------------------------------
+// Retention of failed executions' workspaces for debugging.
+//
+// Backends normally delete an execution's workspace as soon as the result
+// is collected, which also deletes the evidence of why it failed. With
+// `BackendConfig::retain_workspace_on_failure` set, the workspace of a
+// failed execution is left in place instead, its location and expiry are
+// recorded in the result metadata, and the reaper removes it once the
+// retention period is over.
+// ============================================================================
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use tracing::info;
+
+use crate::backends::ExecutionResult;
+use crate::logging::targets;
+use crate::reaper::{ResourceKind, global_reaper};
+
+/// Result metadata key with the retained workspace paths, in the
+/// platform's path-list format (as in `PATH`)
+pub const RETAINED_WORKSPACE_METADATA: &str = "retained_workspace";
+
+/// Result metadata key with the RFC 3339 time the retained workspace is
+/// removed after
+pub const RETAINED_UNTIL_METADATA: &str = "retained_until";
+
+/// How often the reaper looks for retained workspaces past their expiry
+/// when nothing else started its sweeper
+const RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Keep a failed execution's workspace for inspection
+///
+/// # Arguments
+/// * `retention` - How long to keep it; zero keeps nothing
+/// * `paths` - Workspace directories and files of the execution
+/// * `backend` - Backend name the reaper records
+/// * `result` - Execution result; only failed results are retained
+///
+/// # Returns
+/// Whether the workspace was retained; when false the caller removes it
+/// as usual
+pub(crate) fn retain_on_failure(
+    retention: Duration,
+    paths: Vec<PathBuf>,
+    backend: &str,
+    result: &mut ExecutionResult,
+) -> bool {
+    if retention.is_zero() || result.is_success() || paths.is_empty() {
+        return false;
+    }
+    let Ok(joined) = std::env::join_paths(&paths) else {
+        return false;
+    };
+
+    let until = chrono::DateTime::<chrono::Utc>::from(SystemTime::now() + retention);
+    global_reaper().register(ResourceKind::Workspace { paths }, backend, None, Some(retention));
+    global_reaper().ensure_sweeper(RETENTION_SWEEP_INTERVAL);
+
+    let joined = joined.to_string_lossy().into_owned();
+    info!(
+        target: targets::BACKEND,
+        "Retaining workspace of failed {} execution until {}: {}",
+        backend,
+        until.to_rfc3339(),
+        joined
+    );
+    result.metadata.insert(RETAINED_WORKSPACE_METADATA.to_string(), joined);
+    result
+        .metadata
+        .insert(RETAINED_UNTIL_METADATA.to_string(), until.to_rfc3339());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_failed_workspaces_are_retained() {
+        let dir = std::env::temp_dir().join(format!("cylo-retention-{}", std::process::id()));
+        let retain = |retention, result: &mut ExecutionResult| {
+            retain_on_failure(retention, vec![dir.clone()], "test", result)
+        };
+
+        let mut success = ExecutionResult::success("ok");
+        assert!(!retain(Duration::from_secs(60), &mut success));
+        assert!(success.metadata.is_empty());
+
+        let mut failure = ExecutionResult::failure(1, "boom");
+        assert!(!retain(Duration::ZERO, &mut failure));
+        assert!(!failure.metadata.contains_key(RETAINED_WORKSPACE_METADATA));
+
+        assert!(retain(Duration::from_secs(60), &mut failure));
+        let recorded = &failure.metadata[RETAINED_WORKSPACE_METADATA];
+        assert_eq!(std::env::split_paths(recorded).collect::<Vec<_>>(), vec![dir.clone()]);
+        assert!(failure.metadata.contains_key(RETAINED_UNTIL_METADATA));
+        assert!(global_reaper().tracked().iter().any(|resource| {
+            resource.kind == ResourceKind::Workspace { paths: vec![dir.clone()] }
+                && resource.deadline.is_some()
+        }));
+    }
+}
@@ -0,0 +1,354 @@
+// ============================================================================
+// File: packages/cylo/src/backends/runtime.rs
+// ----------------------------------------------------------------------------
+// Discovery of language runtimes installed on the host, used to honour
+// pinned versions (`python@3.12`) in backends that run host binaries
+// ============================================================================
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+use super::errors::{BackendError, BackendResult};
+use super::language;
+
+/// A language runtime binary found on the host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostRuntime {
+    /// Version reported by the binary, e.g. `3.12.1`
+    pub version: String,
+    /// Absolute path of the binary
+    pub program: PathBuf,
+}
+
+/// Directories on the host `PATH`
+pub fn host_search_path() -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default()
+}
+
+/// Directories searched for language runtimes: the host `PATH`, then the
+/// executables of toolchains installed by an activated `ToolchainManager`
+pub fn runtime_search_path() -> Vec<PathBuf> {
+    #[allow(unused_mut)]
+    let mut search_path = host_search_path();
+    #[cfg(feature = "toolchains")]
+    search_path.extend(
+        crate::toolchains::activated_toolchains()
+            .into_iter()
+            .map(|toolchain| toolchain.bin_dir),
+    );
+    search_path
+}
+
+/// Languages whose toolchain hosts often lack, with the binaries providing
+/// it in order of preference
+const OPTIONAL_TOOLCHAINS: &[(&str, &[&str])] = &[
+    ("zig", &["zig"]),
+    ("csharp", &["dotnet"]),
+    ("lua", &["lua", "luajit"]),
+    ("wasm", &["wasmtime"]),
+];
+
+/// Preferred binary of an optional language's toolchain in `search_path`
+///
+/// # Returns
+/// None for languages that are not optional or whose toolchain is missing
+pub fn toolchain_program(language: &str, search_path: &[PathBuf]) -> Option<&'static str> {
+    let (_, programs) = OPTIONAL_TOOLCHAINS.iter().find(|(name, _)| *name == language)?;
+    programs.iter().copied().find(|program| {
+        search_path.iter().any(|dir| {
+            dir.join(program).is_file() || dir.join(format!("{program}.exe")).is_file()
+        })
+    })
+}
+
+/// Names of the optional languages whose toolchain is in `search_path`
+///
+/// Backends running host binaries list these languages in
+/// `supported_languages()` only when this finds their toolchain.
+///
+/// # Returns
+/// Canonical name and aliases of every detected language
+pub fn detected_languages(search_path: &[PathBuf]) -> Vec<&'static str> {
+    OPTIONAL_TOOLCHAINS
+        .iter()
+        .filter(|(name, _)| toolchain_program(name, search_path).is_some())
+        .filter_map(|(name, _)| language::resolve(name))
+        .flat_map(|spec| std::iter::once(spec.name).chain(spec.aliases.iter().copied()))
+        .collect()
+}
+
+/// Resolve the runtime binary for a requested language
+///
+/// # Arguments
+/// * `backend` - Backend name reported in errors
+/// * `language` - Requested language, optionally pinned as `name@version`
+/// * `search_path` - Directories to look for runtime binaries in
+///
+/// # Returns
+/// None when no version is pinned (the backend's default binary is used),
+/// the newest installed runtime matching the pin, or
+/// RuntimeVersionUnavailable listing the versions that are installed
+pub fn resolve_runtime(
+    backend: &'static str,
+    language: &str,
+    search_path: &[PathBuf],
+) -> BackendResult<Option<HostRuntime>> {
+    let (name, Some(requested)) = language::split_version(language) else {
+        return Ok(None);
+    };
+    let spec = language::resolve(name).ok_or_else(|| BackendError::UnsupportedLanguage {
+        backend,
+        language: language.to_string(),
+    })?;
+
+    let installed = installed_runtimes(spec.name, search_path);
+    match installed
+        .iter()
+        .find(|runtime| version_matches(&runtime.version, requested))
+    {
+        Some(runtime) => Ok(Some(runtime.clone())),
+        None => Err(BackendError::RuntimeVersionUnavailable {
+            backend,
+            language: spec.name.to_string(),
+            requested: requested.to_string(),
+            installed: installed.into_iter().map(|runtime| runtime.version).collect(),
+        }),
+    }
+}
+
+/// Every runtime for a language found in `search_path`, newest first
+///
+/// # Arguments
+/// * `language` - Canonical language name
+/// * `search_path` - Directories to look for runtime binaries in
+pub fn installed_runtimes(language: &str, search_path: &[PathBuf]) -> Vec<HostRuntime> {
+    let mut seen = Vec::new();
+    let mut runtimes = Vec::new();
+
+    for dir in search_path {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut candidates: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| is_candidate(language, name))
+            })
+            .collect();
+        candidates.sort();
+
+        for program in candidates {
+            // /bin and /usr/bin often hold the same binaries via symlinks;
+            // the link itself is kept since its target may lie outside a
+            // sandbox's bind mounts
+            let target = fs::canonicalize(&program).unwrap_or_else(|_| program.clone());
+            if seen.contains(&target) {
+                continue;
+            }
+
+            if let Some(version) = probe_version(language, &target)
+                && !runtimes.iter().any(|known: &HostRuntime| known.version == version)
+            {
+                runtimes.push(HostRuntime { version, program });
+            }
+            seen.push(target);
+        }
+    }
+
+    runtimes.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    runtimes
+}
+
+/// Whether an installed version satisfies a pin
+///
+/// A pin matches the same version or any more specific release of it, so
+/// `3.12` matches `3.12.4` but not `3.1` or `3.120`.
+pub fn version_matches(installed: &str, requested: &str) -> bool {
+    installed == requested
+        || installed
+            .strip_prefix(requested)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// Compare dotted versions numerically (`1.10` sorts after `1.9`)
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |version: &str| -> Vec<u64> {
+        version
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    parts(a).cmp(&parts(b))
+}
+
+/// Whether a binary name is a runtime for the language
+fn is_candidate(language: &str, file_name: &str) -> bool {
+    let name = file_name.strip_suffix(".exe").unwrap_or(file_name);
+    let versioned = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit() || c == '.'))
+    };
+
+    match language {
+        "python" => matches!(name, "python" | "python3") || versioned("python3."),
+        "javascript" => matches!(name, "node" | "nodejs"),
+        "rust" => name == "rustc",
+        "go" => name == "go" || versioned("go1."),
+        "bash" => name == "bash",
+        "r" => name == "Rscript",
+        "zig" => name == "zig",
+        "csharp" => name == "dotnet",
+        // LuaJIT reports its own 2.x versions, so pins pick between the two
+        "lua" => versioned("lua") || name == "luajit",
+        "wasm" => name == "wasmtime",
+        _ => false,
+    }
+}
+
+/// Ask a runtime binary for its version, caching the answer per binary
+fn probe_version(language: &str, program: &Path) -> Option<String> {
+    static VERSIONS: OnceLock<Mutex<HashMap<PathBuf, Option<String>>>> = OnceLock::new();
+    let cache = VERSIONS.get_or_init(|| Mutex::new(HashMap::new()));
+
+    if let Some(version) = cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(program)
+    {
+        return version.clone();
+    }
+
+    let flag = match language {
+        "go" | "zig" => "version",
+        "lua" => "-v",
+        _ => "--version",
+    };
+    let version = Command::new(program)
+        .arg(flag)
+        .stdin(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            // Older Pythons print their version to stderr
+            let text = format!(
+                "{} {}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            parse_version(&text)
+        });
+
+    cache
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(program.to_path_buf(), version.clone());
+    version
+}
+
+/// Extract the first dotted version number from `--version` output
+///
+/// Handles `Python 3.12.1`, `v20.11.0`, `rustc 1.78.0 (...)`,
+/// `go version go1.22.1 linux/amd64`, `GNU bash, version 5.2.15(1)`, and
+/// `Rscript (R) version 4.3.2 (...)`, `Lua 5.4.6  Copyright (...)`, and the
+/// bare `0.13.0` of `zig version` and `8.0.100` of `dotnet --version`.
+fn parse_version(text: &str) -> Option<String> {
+    text.split_whitespace().find_map(|token| {
+        let token = token
+            .strip_prefix("go")
+            .or_else(|| token.strip_prefix('v'))
+            .unwrap_or(token);
+        let version: String = token
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        let version = version.trim_end_matches('.');
+        (version.contains('.') && version.starts_with(|c: char| c.is_ascii_digit()))
+            .then(|| version.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_runtime_version_output() {
+        assert_eq!(parse_version("Python 3.12.1\n").as_deref(), Some("3.12.1"));
+        assert_eq!(parse_version("v20.11.0").as_deref(), Some("20.11.0"));
+        assert_eq!(
+            parse_version("rustc 1.78.0 (9b00956e5 2024-04-29)").as_deref(),
+            Some("1.78.0")
+        );
+        assert_eq!(
+            parse_version("go version go1.22.1 linux/amd64").as_deref(),
+            Some("1.22.1")
+        );
+        assert_eq!(
+            parse_version("GNU bash, version 5.2.15(1)-release").as_deref(),
+            Some("5.2.15")
+        );
+        assert_eq!(
+            parse_version("Rscript (R) version 4.3.2 (2023-10-31)").as_deref(),
+            Some("4.3.2")
+        );
+        assert_eq!(parse_version("0.13.0\n").as_deref(), Some("0.13.0"));
+        assert_eq!(parse_version("no version here"), None);
+    }
+
+    #[test]
+    fn pins_match_releases_below_them() {
+        assert!(version_matches("3.12.4", "3.12"));
+        assert!(version_matches("20.11.0", "20"));
+        assert!(!version_matches("3.1", "3.12"));
+        assert!(!version_matches("3.120.0", "3.12"));
+        assert_eq!(compare_versions("1.10.0", "1.9.2"), Ordering::Greater);
+    }
+
+    #[test]
+    fn candidate_names_per_language() {
+        assert!(is_candidate("python", "python3.12"));
+        assert!(is_candidate("python", "python.exe"));
+        assert!(!is_candidate("python", "python3-config"));
+        assert!(is_candidate("go", "go1.22.1"));
+        assert!(!is_candidate("go", "gofmt"));
+        assert!(is_candidate("csharp", "dotnet.exe"));
+        assert!(!is_candidate("csharp", "dotnet-script"));
+        assert!(is_candidate("lua", "lua5.4"));
+        assert!(is_candidate("lua", "luajit"));
+        assert!(!is_candidate("lua", "luac"));
+    }
+
+    #[test]
+    fn optional_toolchains_are_detected_on_the_search_path() {
+        let dir = std::env::temp_dir().join(format!("cylo_toolchain_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        assert!(detected_languages(&[dir.clone()]).is_empty());
+
+        fs::write(dir.join("zig"), "").unwrap();
+        fs::write(dir.join("luajit"), "").unwrap();
+        let detected = detected_languages(&[dir.clone()]);
+        assert!(detected.contains(&"zig"));
+        assert!(detected.contains(&"luajit"));
+        assert!(!detected.contains(&"csharp"));
+        assert_eq!(toolchain_program("lua", &[dir.clone()]), Some("luajit"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_pin_lists_installed_versions() {
+        let err = resolve_runtime("LandLock", "python@0.0", &[]).unwrap_err();
+        assert!(matches!(err, BackendError::RuntimeVersionUnavailable { .. }));
+        assert!(err.to_string().contains("installed versions: none"), "{err}");
+        assert_eq!(resolve_runtime("LandLock", "python", &[]).unwrap(), None);
+    }
+}
@@ -0,0 +1,217 @@
+// ============================================================================
+// File: packages/cylo/src/backends/sampler.rs
+// ----------------------------------------------------------------------------
+// Shared resource sampler for sandboxed processes.
+//
+// Polling each execution's process from its own task costs a timer and a
+// wake-up per execution every interval; under high concurrency that is
+// thousands of tasks doing the same thing. A single sampler task instead
+// walks a registry of watched PIDs each interval and folds every reading
+// into that execution's usage. The task starts with the first watch and
+// exits once nothing is watched.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use super::types::ResourceUsage;
+
+/// How often watched processes are sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Reads one process's current usage into the running totals
+pub(crate) type Probe = fn(pid: u32, usage: &mut ResourceUsage);
+
+#[derive(Debug, Default)]
+struct SamplerState {
+    watched: HashMap<u64, (u32, ResourceUsage)>,
+    next_id: u64,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Samples every watched process from one task
+#[derive(Debug, Clone)]
+pub(crate) struct Sampler {
+    state: Arc<Mutex<SamplerState>>,
+    interval: Duration,
+    probe: Probe,
+}
+
+impl Sampler {
+    /// Create a sampler
+    ///
+    /// # Arguments
+    /// * `interval` - Time between samples
+    /// * `probe` - Reads a process's usage
+    pub(crate) fn new(interval: Duration, probe: Probe) -> Self {
+        Self {
+            state: Arc::default(),
+            interval,
+            probe,
+        }
+    }
+
+    /// Start sampling a process
+    ///
+    /// Must be called from within a Tokio runtime, which the sampler task
+    /// is spawned on when it is not already running.
+    pub(crate) fn watch(&self, pid: u32) -> Watch {
+        let mut state = self.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        let usage = ResourceUsage {
+            process_count: 1,
+            ..ResourceUsage::default()
+        };
+        state.watched.insert(id, (pid, usage));
+
+        // A runtime shutting down ends the task without it unregistering,
+        // so a finished handle counts as not running
+        if state.task.as_ref().is_none_or(JoinHandle::is_finished) {
+            state.task = Some(tokio::spawn(self.clone().run()));
+        }
+        Watch {
+            sampler: self.clone(),
+            id,
+        }
+    }
+
+    /// Number of processes being sampled
+    pub(crate) fn watched(&self) -> usize {
+        self.lock().watched.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SamplerState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+
+            // Probing reads /proc, so it runs on a snapshot outside the lock
+            let pids: Vec<(u64, u32)> = {
+                let mut state = self.lock();
+                if state.watched.is_empty() {
+                    state.task = None;
+                    return;
+                }
+                state.watched.iter().map(|(id, (pid, _))| (*id, *pid)).collect()
+            };
+            let readings: Vec<(u64, ResourceUsage)> = pids
+                .into_iter()
+                .map(|(id, pid)| {
+                    let mut reading = ResourceUsage::default();
+                    (self.probe)(pid, &mut reading);
+                    (id, reading)
+                })
+                .collect();
+
+            let mut state = self.lock();
+            for (id, reading) in readings {
+                if let Some((_, usage)) = state.watched.get_mut(&id) {
+                    fold(usage, &reading);
+                }
+            }
+        }
+    }
+}
+
+/// Fold a reading into running totals: peaks for memory, latest values for
+/// the cumulative counters, which a failed read leaves at zero
+fn fold(usage: &mut ResourceUsage, reading: &ResourceUsage) {
+    usage.peak_memory = usage.peak_memory.max(reading.peak_memory);
+    if reading.cpu_time_ms > 0 {
+        usage.cpu_time_ms = reading.cpu_time_ms;
+    }
+    if reading.process_count > 0 {
+        usage.process_count = reading.process_count;
+    }
+    if reading.disk_bytes_written > 0 {
+        usage.disk_bytes_written = reading.disk_bytes_written;
+    }
+    if reading.disk_bytes_read > 0 {
+        usage.disk_bytes_read = reading.disk_bytes_read;
+    }
+}
+
+/// A process being sampled; dropping it stops sampling
+#[derive(Debug)]
+pub(crate) struct Watch {
+    sampler: Sampler,
+    id: u64,
+}
+
+impl Watch {
+    /// Stop sampling
+    ///
+    /// # Returns
+    /// Usage observed while the process was watched
+    pub(crate) fn finish(self) -> ResourceUsage {
+        self.sampler
+            .lock()
+            .watched
+            .remove(&self.id)
+            .map(|(_, usage)| usage)
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        self.sampler.lock().watched.remove(&self.id);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn probe_proc(pid: u32, usage: &mut ResourceUsage) {
+    use super::landlock::monitoring;
+
+    usage.peak_memory = monitoring::get_memory_usage(pid).unwrap_or(0);
+    usage.cpu_time_ms = monitoring::get_process_cpu_time(pid).unwrap_or(0);
+    usage.process_count = monitoring::count_process_tree(pid).map_or(0, |count| count as u32);
+    usage.disk_bytes_written = monitoring::get_disk_io_stats(pid).unwrap_or(0);
+    usage.disk_bytes_read = monitoring::get_disk_read_stats(pid).unwrap_or(0);
+}
+
+#[cfg(not(target_os = "linux"))]
+fn probe_proc(_pid: u32, _usage: &mut ResourceUsage) {}
+
+/// Sampler for processes backends run on this host
+pub(crate) fn global_sampler() -> &'static Sampler {
+    static SAMPLER: OnceLock<Sampler> = OnceLock::new();
+    SAMPLER.get_or_init(|| Sampler::new(SAMPLE_INTERVAL, probe_proc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(pid: u32, usage: &mut ResourceUsage) {
+        usage.peak_memory = u64::from(pid) * 1024;
+        usage.cpu_time_ms = 7;
+    }
+
+    #[tokio::test]
+    async fn one_task_samples_every_watched_process() {
+        let sampler = Sampler::new(Duration::from_millis(5), probe);
+        let first = sampler.watch(1);
+        let second = sampler.watch(2);
+        assert_eq!(sampler.watched(), 2);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let usage = second.finish();
+        assert_eq!((usage.peak_memory, usage.cpu_time_ms), (2048, 7));
+        assert_eq!(usage.process_count, 1);
+
+        drop(first);
+        assert_eq!(sampler.watched(), 0);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(sampler.lock().task.is_none());
+    }
+}
@@ -0,0 +1,397 @@
+// ============================================================================
+// File: packages/cylo/src/backends/sandbox_state.rs
+// ----------------------------------------------------------------------------
+// Sandbox filesystems carried from one execution to the next.
+//
+// Every execution normally starts from a pristine sandbox: a container is
+// removed once it exits and a microVM boots from the configured root
+// filesystem. A request carrying a sandbox state continues where the last
+// execution with that state left off instead. The Docker backend commits
+// the exited container to the state's image and starts the next container
+// from it; the FireCracker backend boots from the state's own copy of the
+// root filesystem, which the guest writes to. Snapshots keep the image and
+// the copies under an ID so the state can be rolled back to them. A restore
+// is staged first and swapped in afterwards, so a session can restore its
+// workspace along with the sandbox and undo both if either fails.
+//
+// Memory is not carried over: each execution's program has exited by the
+// time its sandbox is saved, so the filesystem is all the state there is.
+// Bind mounts, such as a container's source directory, are not part of it.
+// Only an execution that exits on its own is reported saved; a microVM
+// stopped on timeout may still have written part of its changes to the copy.
+// ============================================================================
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::execution_env::{CyloError, CyloResult};
+use crate::hardening::{Helper, HelperCommand};
+
+/// Result metadata key reporting whether the sandbox was saved to the
+/// request's state: "saved", or why it was not
+pub const SANDBOX_STATE_METADATA: &str = "sandbox_state";
+
+/// Repository the state images are tagged in, followed by the state key
+const IMAGE_REPOSITORY: &str = "cylo-state";
+
+/// Tag of the image the next container starts from
+const CURRENT_TAG: &str = "current";
+
+/// Tag keeping the image a restore replaced until it is finished
+const UNDO_TAG: &str = "undo";
+
+/// Directory holding root filesystem copies, one per base image
+const ROOTFS_DIR: &str = "rootfs";
+
+/// Directory holding snapshots, one subdirectory each
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// File naming the container CLI that committed the state's image
+const RUNTIME_FILE: &str = "container_runtime";
+
+/// Where a series of executions keeps its sandbox filesystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxState {
+    /// Lowercase key the state's images are named by
+    key: String,
+    /// Directory holding root filesystem copies and snapshots
+    dir: PathBuf,
+}
+
+impl SandboxState {
+    /// State named by `key` (lowercase letters, digits and `-`) and kept
+    /// below `dir`
+    pub(crate) fn new<K: Into<String>, P: Into<PathBuf>>(key: K, dir: P) -> Self {
+        Self {
+            key: key.into(),
+            dir: dir.into(),
+        }
+    }
+
+    /// Image the next container starts from, once a container was committed
+    pub fn image(&self) -> String {
+        self.tagged(CURRENT_TAG)
+    }
+
+    fn tagged(&self, tag: &str) -> String {
+        format!("{IMAGE_REPOSITORY}-{}:{tag}", self.key)
+    }
+
+    /// Whether `runtime` holds the state's image
+    pub(crate) fn has_image(&self, runtime: &str) -> bool {
+        image_exists(runtime, &self.image())
+    }
+
+    /// Save an exited container as the state's image
+    ///
+    /// # Returns
+    /// Why the container could not be committed, on failure
+    pub(crate) fn commit(&self, runtime: &str, container: &str) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .and_then(|()| fs::write(self.dir.join(RUNTIME_FILE), runtime))
+            .map_err(|e| format!("failed to record the container runtime: {e}"))?;
+        run(runtime, &["commit", container, &self.image()])
+    }
+
+    /// Copy of the root filesystem `base` the guest boots from, made on
+    /// first use
+    ///
+    /// # Arguments
+    /// * `base` - Root filesystem image the copy starts as
+    /// * `digest` - Content digest of `base`, naming the copy
+    pub(crate) fn rootfs(&self, base: &Path, digest: &str) -> std::io::Result<PathBuf> {
+        let name = digest.rsplit(':').next().unwrap_or(digest);
+        let copy = self.dir.join(ROOTFS_DIR).join(format!("{name}.ext4"));
+        if !copy.is_file() {
+            // Copied under a temporary name so a failed copy is not booted
+            let partial = copy.with_extension("partial");
+            fs::create_dir_all(self.dir.join(ROOTFS_DIR))?;
+            fs::copy(base, &partial)?;
+            fs::rename(&partial, &copy)?;
+        }
+        Ok(copy)
+    }
+
+    /// Keep the current image and root filesystems as snapshot `id`
+    pub(crate) fn snapshot(&self, id: &str) -> CyloResult<()> {
+        let root = self.dir.join(SNAPSHOTS_DIR).join(id);
+        let failed = |details: String| {
+            let _ = fs::remove_dir_all(&root);
+            CyloError::internal(format!("Failed to snapshot the sandbox: {details}"))
+        };
+        fs::create_dir_all(&root).map_err(|e| failed(e.to_string()))?;
+        if let Some(runtime) = self.runtime()
+            && self.has_image(&runtime)
+        {
+            run(&runtime, &["tag", &self.image(), &self.tagged(id)]).map_err(failed)?;
+        }
+        let rootfs = self.dir.join(ROOTFS_DIR);
+        if rootfs.is_dir() {
+            copy_files(&rootfs, &root.join(ROOTFS_DIR)).map_err(|e| failed(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Copy the root filesystems of snapshot `id` next to the current
+    /// ones, ready to be swapped in with its image
+    ///
+    /// Parts the snapshot did not have yet are reset to pristine once
+    /// swapped.
+    pub(crate) fn stage_restore(&self, id: &str) -> CyloResult<StagedSandbox<'_>> {
+        let root = self.dir.join(SNAPSHOTS_DIR).join(id);
+        if !root.is_dir() {
+            return Err(CyloError::validation(format!("The sandbox has no snapshot '{id}'")));
+        }
+        let staged = StagedSandbox {
+            state: self,
+            id: id.to_string(),
+            runtime: self.runtime(),
+            swapped: false,
+        };
+        // A leftover undo tag would be put back in place of no image
+        if let Some(runtime) = &staged.runtime
+            && image_exists(runtime, &self.tagged(UNDO_TAG))
+        {
+            run(runtime, &["rmi", &self.tagged(UNDO_TAG)]).map_err(restore_failed)?;
+        }
+        let _ = fs::remove_dir_all(staged.staged());
+        if root.join(ROOTFS_DIR).is_dir() {
+            copy_files(&root.join(ROOTFS_DIR), &staged.staged())
+                .map_err(|e| restore_failed(e.to_string()))?;
+        }
+        Ok(staged)
+    }
+
+    /// Remove the state's images, copies and snapshots
+    pub(crate) fn discard(&self) -> CyloResult<()> {
+        if let Some(runtime) = self.runtime() {
+            let snapshots = fs::read_dir(self.dir.join(SNAPSHOTS_DIR)).into_iter().flatten();
+            let tags = snapshots
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .chain([CURRENT_TAG.to_string()]);
+            for tag in tags {
+                let image = self.tagged(&tag);
+                if image_exists(&runtime, &image) {
+                    let _ = run(&runtime, &["rmi", &image]);
+                }
+            }
+        }
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(CyloError::internal(
+                format!("Failed to remove sandbox state {}: {e}", self.dir.display()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Container CLI that committed the state's image, if any did
+    fn runtime(&self) -> Option<String> {
+        fs::read_to_string(self.dir.join(RUNTIME_FILE))
+            .ok()
+            .map(|runtime| runtime.trim().to_string())
+            .filter(|runtime| !runtime.is_empty())
+    }
+}
+
+/// Snapshot root filesystems copied next to the state's, not yet swapped
+/// in
+///
+/// Dropping it removes whichever of the copies and the replaced state is
+/// left over.
+pub(crate) struct StagedSandbox<'a> {
+    state: &'a SandboxState,
+    id: String,
+    /// Container CLI holding the state's images, if any
+    runtime: Option<String>,
+    swapped: bool,
+}
+
+impl StagedSandbox<'_> {
+    /// Put the snapshot's image and root filesystems in place, keeping
+    /// what they replace until the restore is finished or undone
+    pub(crate) fn swap(&mut self) -> CyloResult<()> {
+        let state = self.state;
+        if let Some(runtime) = &self.runtime {
+            let (current, snapshot) = (state.image(), state.tagged(&self.id));
+            if state.has_image(runtime) {
+                run(runtime, &["tag", &current, &state.tagged(UNDO_TAG)])
+                    .map_err(restore_failed)?;
+            }
+            let retagged = if image_exists(runtime, &snapshot) {
+                run(runtime, &["tag", &snapshot, &current])
+            } else if state.has_image(runtime) {
+                run(runtime, &["rmi", &current])
+            } else {
+                Ok(())
+            };
+            if let Err(e) = retagged {
+                self.undo_image(runtime);
+                return Err(restore_failed(e));
+            }
+        }
+
+        let (rootfs, staged, replaced) = (self.rootfs(), self.staged(), self.replaced());
+        let _ = fs::remove_dir_all(&replaced);
+        let swapped = match fs::rename(&rootfs, &replaced) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ if staged.is_dir() => fs::rename(&staged, &rootfs).inspect_err(|_| {
+                let _ = fs::rename(&replaced, &rootfs);
+            }),
+            _ => Ok(()),
+        };
+        if let Err(e) = swapped {
+            if let Some(runtime) = &self.runtime {
+                self.undo_image(runtime);
+            }
+            return Err(restore_failed(e.to_string()));
+        }
+        self.swapped = true;
+        Ok(())
+    }
+
+    /// Put the replaced image and root filesystems back after a swap
+    pub(crate) fn undo(&mut self) {
+        if !self.swapped {
+            return;
+        }
+        let (rootfs, replaced) = (self.rootfs(), self.replaced());
+        let _ = fs::remove_dir_all(&rootfs);
+        if replaced.is_dir() {
+            let _ = fs::rename(&replaced, &rootfs);
+        }
+        if let Some(runtime) = &self.runtime {
+            self.undo_image(runtime);
+        }
+        self.swapped = false;
+    }
+
+    /// Keep the restored image and root filesystems
+    pub(crate) fn finish(self) {}
+
+    /// Tag the image the swap replaced as current again, or remove the
+    /// current tag if there was none
+    fn undo_image(&self, runtime: &str) {
+        let (current, undo) = (self.state.image(), self.state.tagged(UNDO_TAG));
+        if image_exists(runtime, &undo) {
+            let _ = run(runtime, &["tag", &undo, &current]);
+        } else if self.state.has_image(runtime) {
+            let _ = run(runtime, &["rmi", &current]);
+        }
+    }
+
+    fn rootfs(&self) -> PathBuf {
+        self.state.dir.join(ROOTFS_DIR)
+    }
+
+    fn staged(&self) -> PathBuf {
+        self.state.dir.join(format!("{ROOTFS_DIR}.restore"))
+    }
+
+    fn replaced(&self) -> PathBuf {
+        self.state.dir.join(format!("{ROOTFS_DIR}.old"))
+    }
+}
+
+impl Drop for StagedSandbox<'_> {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(self.staged());
+        let _ = fs::remove_dir_all(self.replaced());
+        if let Some(runtime) = &self.runtime
+            && image_exists(runtime, &self.state.tagged(UNDO_TAG))
+        {
+            let _ = run(runtime, &["rmi", &self.state.tagged(UNDO_TAG)]);
+        }
+    }
+}
+
+fn restore_failed(details: String) -> CyloError {
+    CyloError::internal(format!("Failed to restore the sandbox: {details}"))
+}
+
+fn image_exists(runtime: &str, image: &str) -> bool {
+    Command::new(runtime)
+        .args(["image", "inspect", image])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .helper_status(Helper::ContainerCli)
+        .is_ok_and(|status| status.success())
+}
+
+/// Run a container CLI command, returning its error output on failure
+fn run(runtime: &str, args: &[&str]) -> Result<(), String> {
+    let output = Command::new(runtime)
+        .args(args)
+        .stdin(Stdio::null())
+        .helper_output(Helper::ContainerCli)
+        .map_err(|e| format!("failed to run {runtime} {}: {e}", args[0]))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{runtime} {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Copy the files directly inside `source` into a new directory `target`
+fn copy_files(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            fs::copy(entry.path(), target.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_filesystems_roll_back_to_snapshots() {
+        let dir = std::env::temp_dir().join(format!("cylo_sandbox_{}", uuid::Uuid::new_v4()));
+        let base = dir.with_extension("base.ext4");
+        fs::write(&base, "pristine").unwrap();
+        let state = SandboxState::new("test", dir.join("state"));
+        assert_eq!(state.image(), "cylo-state-test:current");
+
+        // Nothing saved yet: a snapshot resets to pristine
+        state.snapshot("empty").unwrap();
+        let rootfs = state.rootfs(&base, "sha256:abc").unwrap();
+        assert_eq!(rootfs.file_name().unwrap(), "abc.ext4");
+        fs::write(&rootfs, "installed").unwrap();
+        assert_eq!(state.rootfs(&base, "sha256:abc").unwrap(), rootfs);
+        assert_eq!(fs::read_to_string(&rootfs).unwrap(), "installed");
+
+        state.snapshot("good").unwrap();
+        fs::write(&rootfs, "broken").unwrap();
+        let mut staged = state.stage_restore("good").unwrap();
+        staged.swap().unwrap();
+        assert_eq!(fs::read_to_string(&rootfs).unwrap(), "installed");
+        // An undone restore leaves the state as it was
+        staged.undo();
+        drop(staged);
+        assert_eq!(fs::read_to_string(&rootfs).unwrap(), "broken");
+
+        let mut staged = state.stage_restore("good").unwrap();
+        staged.swap().unwrap();
+        staged.finish();
+        assert_eq!(fs::read_to_string(&rootfs).unwrap(), "installed");
+        state.stage_restore("empty").unwrap().swap().unwrap();
+        assert!(!rootfs.exists());
+        assert!(state.stage_restore("missing").is_err());
+        assert!(!dir.join("state").join("rootfs.old").exists());
+        assert_eq!(fs::read_to_string(state.rootfs(&base, "abc").unwrap()).unwrap(), "pristine");
+
+        state.discard().unwrap();
+        assert!(!dir.join("state").exists());
+        let _ = fs::remove_file(&base);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,307 @@
+// ============================================================================
+// File: packages/cylo/src/backends/sql.rs
+// ----------------------------------------------------------------------------
+// SQL execution against an ephemeral embedded database.
+//
+// A `sql` request runs its code with the sqlite3 or duckdb shell against an
+// in-memory database that disappears with the execution. Datasets the
+// request exposes read-only can seed it: SQL scripts are run, CSV and
+// Parquet files loaded as tables, and database files attached. Both shells
+// print result sets as JSON, which is parsed into structured rows.
+// ============================================================================
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::backends::language;
+
+/// Embedded database engine a SQL request runs on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlEngine {
+    /// SQLite, through the sqlite3 shell
+    #[default]
+    Sqlite,
+    /// DuckDB, through the duckdb shell
+    Duckdb,
+}
+
+impl SqlEngine {
+    /// Engine a request runs on
+    ///
+    /// Explicit options win; otherwise requesting the language as `duckdb`
+    /// selects DuckDB and any other name SQLite.
+    pub fn of(language: &str, options: Option<&SqlOptions>) -> Self {
+        match options.and_then(|options| options.engine) {
+            Some(engine) => engine,
+            None if language::split_version(language).0.eq_ignore_ascii_case("duckdb") => {
+                Self::Duckdb
+            }
+            None => Self::Sqlite,
+        }
+    }
+
+    /// Shell binary of the engine
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::Sqlite => "sqlite3",
+            Self::Duckdb => "duckdb",
+        }
+    }
+}
+
+/// How a SQL request's database is set up
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SqlOptions {
+    /// Engine; None picks it from the requested language name
+    pub engine: Option<SqlEngine>,
+    /// Datasets loaded before the code runs, in order
+    ///
+    /// Each must lie inside one of the request's readable paths. `.sql`
+    /// scripts are run, `.csv` (and for DuckDB `.parquet`) files become a
+    /// table named after the file, and `.db`/`.sqlite`/`.sqlite3` (and for
+    /// DuckDB `.duckdb`) files are attached read-only under that name.
+    pub seeds: Vec<PathBuf>,
+}
+
+impl SqlOptions {
+    /// Select the engine
+    pub fn with_engine(mut self, engine: SqlEngine) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    /// Append a dataset to seed the database from
+    pub fn with_seed<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.seeds.push(path.into());
+        self
+    }
+
+    /// Check the seeds against the engine and the paths the request exposes
+    ///
+    /// # Returns
+    /// Ok(()) if every seed is exposed, of a supported kind and names a
+    /// valid table, otherwise a description of the first problem
+    pub fn validate(&self, language: &str, readable: &[PathBuf]) -> Result<(), String> {
+        if language::resolve(language).map(|spec| spec.name) != Some("sql") {
+            return Err(format!("'{language}' is not sql"));
+        }
+        let engine = SqlEngine::of(language, Some(self));
+        for seed in &self.seeds {
+            if !readable.iter().any(|path| seed.starts_with(path)) {
+                return Err(format!("seed '{}' is not in a readable path", seed.display()));
+            }
+            seed_statement(engine, seed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Shell arguments running a SQL script
+///
+/// The database is in memory; seed statements run first through `-cmd`,
+/// then the script is read. `-bail` stops at the first failing statement
+/// with a non-zero exit code.
+///
+/// # Arguments
+/// * `engine` - Engine running the script
+/// * `options` - Seeds of the request, already validated
+/// * `script` - Path of the script inside the sandbox
+pub fn shell_args(
+    engine: SqlEngine,
+    options: Option<&SqlOptions>,
+    script: &str,
+) -> Result<Vec<String>, String> {
+    let mut args = vec!["-bail".to_string(), "-json".to_string()];
+    for seed in options.map(|options| options.seeds.as_slice()).unwrap_or_default() {
+        args.extend(["-cmd".to_string(), seed_statement(engine, seed)?]);
+    }
+    args.extend([":memory:".to_string(), format!(".read {}", quote(script))]);
+    Ok(args)
+}
+
+/// Statement loading one seed
+fn seed_statement(engine: SqlEngine, seed: &Path) -> Result<String, String> {
+    let path = quote(&seed.display().to_string());
+    let extension = seed
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if extension == "sql" {
+        return Ok(format!(".read {path}"));
+    }
+
+    let table = seed
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| is_table_name(stem))
+        .ok_or_else(|| format!("seed '{}' does not name a valid table", seed.display()))?;
+    let statement = match (engine, extension.as_str()) {
+        (SqlEngine::Sqlite, "csv") => format!(".import --csv {path} {table}"),
+        (SqlEngine::Duckdb, "csv") => {
+            format!("CREATE TABLE {table} AS SELECT * FROM read_csv_auto({path});")
+        }
+        (SqlEngine::Duckdb, "parquet") => {
+            format!("CREATE TABLE {table} AS SELECT * FROM read_parquet({path});")
+        }
+        (SqlEngine::Sqlite, "db" | "sqlite" | "sqlite3") => {
+            format!("ATTACH DATABASE {path} AS {table};")
+        }
+        (SqlEngine::Duckdb, "db" | "sqlite" | "sqlite3") => {
+            format!("ATTACH {path} AS {table} (TYPE SQLITE, READ_ONLY);")
+        }
+        (SqlEngine::Duckdb, "duckdb") => format!("ATTACH {path} AS {table} (READ_ONLY);"),
+        _ => {
+            return Err(format!(
+                "seed '{}' is not a kind {} can load",
+                seed.display(),
+                engine.program()
+            ));
+        }
+    };
+    Ok(statement)
+}
+
+/// Quote a path as a SQL string literal, which the shells' dot commands
+/// accept as well
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn is_table_name(value: &str) -> bool {
+    value.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Rows one statement returned
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResultSet {
+    /// Column names in select order
+    pub columns: Vec<String>,
+    /// Row values, in the order of `columns`
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// Parse the result sets a SQL execution printed
+///
+/// Both shells print every result set as one JSON array of row objects;
+/// statements returning no rows print nothing.
+///
+/// # Returns
+/// One entry per non-empty result set, or None if stdout is not JSON
+/// result sets, e.g. because the code changed the output mode
+pub fn parse_result_sets(stdout: &str) -> Option<Vec<ResultSet>> {
+    let mut sets = Vec::new();
+    for rows in serde_json::Deserializer::from_str(stdout).into_iter::<Vec<Row>>() {
+        let rows = rows.ok()?;
+        let mut set = ResultSet {
+            columns: rows
+                .first()
+                .map(|row| row.0.iter().map(|(column, _)| column.clone()).collect())
+                .unwrap_or_default(),
+            rows: Vec::with_capacity(rows.len()),
+        };
+        for row in rows {
+            let values = row.0.into_iter().map(|(_, value)| value).collect::<Vec<_>>();
+            if values.len() != set.columns.len() {
+                return None;
+            }
+            set.rows.push(values);
+        }
+        sets.push(set);
+    }
+    Some(sets)
+}
+
+/// Row object with its columns kept in printed order
+struct Row(Vec<(String, serde_json::Value)>);
+
+impl<'de> Deserialize<'de> for Row {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RowVisitor;
+
+        impl<'de> Visitor<'de> for RowVisitor {
+            type Value = Row;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a row object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Row, A::Error> {
+                let mut columns = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry()? {
+                    columns.push(entry);
+                }
+                Ok(Row(columns))
+            }
+        }
+
+        deserializer.deserialize_map(RowVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_become_setup_commands() {
+        let data = PathBuf::from("/data");
+        let options = SqlOptions::default()
+            .with_seed("/data/schema.sql")
+            .with_seed("/data/sales.csv")
+            .with_seed("/data/it's.db");
+        assert!(options.validate("sql", &[data.clone()]).is_err());
+
+        let options = SqlOptions::default()
+            .with_seed("/data/schema.sql")
+            .with_seed("/data/sales.csv")
+            .with_seed("/data/history.db");
+        assert!(options.validate("sqlite", &[data.clone()]).is_ok());
+        assert!(options.validate("sql", &[PathBuf::from("/other")]).is_err());
+        assert_eq!(
+            shell_args(SqlEngine::Sqlite, Some(&options), "main.sql").unwrap(),
+            [
+                "-bail",
+                "-json",
+                "-cmd",
+                ".read '/data/schema.sql'",
+                "-cmd",
+                ".import --csv '/data/sales.csv' sales",
+                "-cmd",
+                "ATTACH DATABASE '/data/history.db' AS history;",
+                ":memory:",
+                ".read 'main.sql'",
+            ]
+        );
+
+        let parquet = SqlOptions::default().with_seed("/data/events.parquet");
+        assert!(parquet.validate("sql", &[data.clone()]).is_err());
+        assert!(parquet.validate("duckdb", &[data]).is_ok());
+        assert_eq!(SqlEngine::of("DuckDB", None), SqlEngine::Duckdb);
+        let forced = parquet.with_engine(SqlEngine::Sqlite);
+        assert_eq!(SqlEngine::of("duckdb", Some(&forced)), SqlEngine::Sqlite);
+    }
+
+    #[test]
+    fn result_sets_keep_column_order() {
+        let stdout = concat!(
+            "[{\"name\":\"a\",\"count\":2},\n",
+            "{\"name\":\"b\",\"count\":null}]\n",
+            "[{\"x\":1.5}]\n",
+        );
+        let sets = parse_result_sets(stdout).unwrap();
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].columns, ["name", "count"]);
+        assert_eq!(sets[0].rows[1], [serde_json::json!("b"), serde_json::Value::Null]);
+        assert_eq!(sets[1].rows, [[serde_json::json!(1.5)]]);
+
+        assert_eq!(parse_result_sets(""), Some(Vec::new()));
+        assert_eq!(parse_result_sets("name|count\na|2\n"), None);
+    }
+}
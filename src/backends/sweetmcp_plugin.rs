@@ -4,8 +4,8 @@
 //! It provides secure execution of tools via WASM sandboxing while maintaining
 //! the same interface as other Cylo backends.
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
@@ -52,22 +52,155 @@ pub struct ToolInfo {
 }
 
 use super::{
-    AsyncTask, BackendConfig, BackendError, BackendResult, ExecutionBackend, ExecutionRequest,
-    ExecutionResult, HealthStatus, ResourceUsage,
+    AsyncTask, BackendConfig, BackendError, BackendResult, ExecutionBackend, ExecutionOutcome,
+    ExecutionRequest, ExecutionResult, HealthCheckLevel, HealthStatus, IsolationLevel,
+    ResourceUsage, SecurityReport, cpuset, input_stream, io_throttle,
 };
 use crate::execution_env::CyloResult;
 
+/// Backend config key listing host directories to preopen, as
+/// comma-separated `host=guest` pairs (`host` alone mounts at the same path)
+pub const PREOPENED_DIRS_KEY: &str = "preopened_dirs";
+
+/// Backend config key listing hosts a plugin may reach, comma-separated
+pub const ALLOWED_HOSTS_KEY: &str = "allowed_hosts";
+
+/// Size of a WebAssembly memory page
+const WASM_PAGE_BYTES: u64 = 64 * 1024;
+
+/// WASI capabilities granted to one plugin execution
+///
+/// Plugins get nothing by default: no preopened directories and no hosts.
+/// Directories and hosts come from the backend config, so a request cannot
+/// widen them; a request whose network bandwidth limit is zero gets no hosts
+/// even if the config allows some. Memory and time are capped from the
+/// request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PluginGrants {
+    /// Preopened directories, host path to guest path
+    pub preopened_dirs: BTreeMap<String, PathBuf>,
+    /// Hosts the plugin may send requests to
+    pub allowed_hosts: Vec<String>,
+    /// Linear memory cap in 64 KiB pages
+    pub memory_max_pages: Option<u32>,
+    /// Wall-clock cap on the plugin call
+    pub timeout: Option<Duration>,
+}
+
+impl PluginGrants {
+    /// Grants for a request under a backend configuration
+    ///
+    /// # Returns
+    /// The grants, or InvalidConfig if a preopened directory does not exist
+    pub fn derive(config: &BackendConfig, request: &ExecutionRequest) -> BackendResult<Self> {
+        let list = |key: &str| -> Vec<String> {
+            config
+                .backend_specific
+                .get(key)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|entry| !entry.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut preopened_dirs = BTreeMap::new();
+        for entry in list(PREOPENED_DIRS_KEY) {
+            let (host, guest) = entry.split_once('=').unwrap_or((&entry, &entry));
+            if !Path::new(host).is_dir() {
+                return Err(BackendError::InvalidConfig {
+                    backend: "SweetMcpPlugin",
+                    details: format!("Preopened directory not found: {}", host),
+                });
+            }
+            preopened_dirs.insert(host.to_string(), PathBuf::from(guest));
+        }
+
+        let allowed_hosts = if request.limits.max_network_bandwidth == Some(0) {
+            Vec::new()
+        } else {
+            list(ALLOWED_HOSTS_KEY)
+        };
+
+        let memory_max_pages = request.limits.max_memory.map(|bytes| {
+            u32::try_from(bytes.div_ceil(WASM_PAGE_BYTES)).unwrap_or(u32::MAX)
+        });
+        let timeout = (!request.timeout.is_zero()).then_some(request.timeout);
+
+        Ok(Self {
+            preopened_dirs,
+            allowed_hosts,
+            memory_max_pages,
+            timeout,
+        })
+    }
+
+    /// Plugin manifest carrying exactly these grants
+    pub fn manifest(&self, plugin_path: &Path) -> Manifest {
+        let mut manifest = Manifest::new([Wasm::file(plugin_path)])
+            .with_allowed_hosts(self.allowed_hosts.iter().cloned());
+        for (host, guest) in &self.preopened_dirs {
+            manifest = manifest.with_allowed_path(host.clone(), guest);
+        }
+        if let Some(pages) = self.memory_max_pages {
+            manifest = manifest.with_memory_max(pages);
+        }
+        if let Some(timeout) = self.timeout {
+            manifest = manifest.with_timeout(timeout);
+        }
+        manifest
+    }
+
+    /// Isolation the grants leave the plugin under
+    pub fn security_report(&self) -> SecurityReport {
+        SecurityReport {
+            isolation: IsolationLevel::Process,
+            network_disabled: self.allowed_hosts.is_empty(),
+            filesystem_read_only: self.preopened_dirs.is_empty(),
+            memory_limit_bytes: self
+                .memory_max_pages
+                .map(|pages| u64::from(pages) * WASM_PAGE_BYTES),
+            // WASI exposes no IPC or windowing to the module
+            ipc_isolated: true,
+            ui_restricted: true,
+        }
+    }
+
+    /// Record the granted set in result metadata
+    pub fn record(&self, metadata: &mut HashMap<String, String>) {
+        let dirs: Vec<String> = self
+            .preopened_dirs
+            .iter()
+            .map(|(host, guest)| format!("{}={}", host, guest.display()))
+            .collect();
+        metadata.insert("wasi.preopened_dirs".to_string(), dirs.join(","));
+        metadata.insert("wasi.allowed_hosts".to_string(), self.allowed_hosts.join(","));
+        if let Some(pages) = self.memory_max_pages {
+            metadata.insert("wasi.memory_max_pages".to_string(), pages.to_string());
+        }
+        if let Some(timeout) = self.timeout {
+            metadata.insert("wasi.timeout_ms".to_string(), timeout.as_millis().to_string());
+        }
+    }
+}
+
 /// SweetMCP Plugin backend implementation
 ///
 /// Executes SweetMCP WASM plugins using the Extism runtime for secure isolation.
-/// Tools are executed via the MCP tool protocol but locally without network calls.
+/// Tools are executed via the MCP tool protocol. Each execution gets a fresh
+/// plugin instance holding only the capabilities in its `PluginGrants`; the
+/// shared instance is used for `describe` calls only.
 #[derive(Debug)]
 pub struct SweetMcpPluginBackend {
     /// Path to the WASM plugin file
     plugin_path: PathBuf,
     /// Backend configuration
     config: BackendConfig,
-    /// Shared plugin instance (with interior mutability)
+    /// Shared plugin instance without grants, for `describe` calls
     plugin: Arc<Mutex<Plugin>>,
     /// Supported languages (determined by plugin capabilities)
     supported_languages: Vec<String>,
@@ -193,6 +326,17 @@ impl SweetMcpPluginBackend {
                 duration,
                 resource_usage: ResourceUsage::default(),
                 metadata: HashMap::new(),
+                verdict: None,
+                outcome: ExecutionOutcome::Completed,
+                security: None,
+                cost: None,
+                crash: None,
+                result_sets: None,
+                workspace_archive: None,
+                blobs: HashMap::new(),
+                processed: None,
+                raw_output: None,
+                compilation: None,
             };
         }
 
@@ -228,6 +372,17 @@ impl SweetMcpPluginBackend {
                 duration,
                 resource_usage: ResourceUsage::default(),
                 metadata: HashMap::new(),
+                verdict: None,
+                outcome: ExecutionOutcome::Completed,
+                security: None,
+                cost: None,
+                crash: None,
+                result_sets: None,
+                workspace_archive: None,
+                blobs: HashMap::new(),
+                processed: None,
+                raw_output: None,
+                compilation: None,
             }
         } else {
             // Fallback for plain text results
@@ -238,6 +393,17 @@ impl SweetMcpPluginBackend {
                 duration,
                 resource_usage: ResourceUsage::default(),
                 metadata: HashMap::new(),
+                verdict: None,
+                outcome: ExecutionOutcome::Completed,
+                security: None,
+                cost: None,
+                crash: None,
+                result_sets: None,
+                workspace_archive: None,
+                blobs: HashMap::new(),
+                processed: None,
+                raw_output: None,
+                compilation: None,
             }
         }
     }
@@ -245,12 +411,27 @@ impl SweetMcpPluginBackend {
 
 impl ExecutionBackend for SweetMcpPluginBackend {
     fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
-        let plugin = Arc::clone(&self.plugin);
         let backend = self.clone_for_async();
 
         tokio::spawn(async move {
             let start_time = SystemTime::now();
 
+            // Plugins run on the executor's own threads and files, and take
+            // their input as one tool argument
+            if let Err(e) = cpuset::reject_unpinnable(&request, "SweetMcpPlugin")
+                .and_then(|()| io_throttle::reject_unthrottled(&request, "SweetMcpPlugin"))
+                .and_then(|()| input_stream::reject_unpiped(&request, "SweetMcpPlugin"))
+            {
+                return ExecutionResult::failure(1, e.to_string());
+            }
+
+            let grants = match PluginGrants::derive(&backend.config, &request) {
+                Ok(grants) => grants,
+                Err(e) => {
+                    return ExecutionResult::failure(1, format!("Invalid plugin grants: {}", e));
+                }
+            };
+
             // Convert request to tool call
             let tool_request = backend.execution_to_tool_request(&request);
 
@@ -266,13 +447,29 @@ impl ExecutionBackend for SweetMcpPluginBackend {
                         duration,
                         resource_usage: ResourceUsage::default(),
                         metadata: HashMap::new(),
+                        verdict: None,
+                        outcome: ExecutionOutcome::Completed,
+                        security: None,
+                        cost: None,
+                        crash: None,
+                        result_sets: None,
+                        workspace_archive: None,
+                        blobs: HashMap::new(),
+                        processed: None,
+                        raw_output: None,
+                        compilation: None,
                     };
                 }
             };
 
-            // Call the plugin
-            let mut plugin_guard = plugin.lock().await;
-            let response_str = match plugin_guard.call::<String, String>("call", request_json) {
+            // Call a plugin instance holding only this request's grants
+            let mut plugin = match Plugin::new(grants.manifest(&backend.plugin_path), [], true) {
+                Ok(plugin) => plugin,
+                Err(e) => {
+                    return ExecutionResult::failure(1, format!("Failed to load plugin: {}", e));
+                }
+            };
+            let response_str = match plugin.call::<String, String>("call", request_json) {
                 Ok(response) => response,
                 Err(e) => {
                     let duration = start_time.elapsed().unwrap_or_default();
@@ -283,10 +480,21 @@ impl ExecutionBackend for SweetMcpPluginBackend {
                         duration,
                         resource_usage: ResourceUsage::default(),
                         metadata: HashMap::new(),
+                        verdict: None,
+                        outcome: ExecutionOutcome::Completed,
+                        security: None,
+                        cost: None,
+                        crash: None,
+                        result_sets: None,
+                        workspace_archive: None,
+                        blobs: HashMap::new(),
+                        processed: None,
+                        raw_output: None,
+                        compilation: None,
                     };
                 }
             };
-            drop(plugin_guard);
+            drop(plugin);
 
             // Parse response
             let tool_result: CallToolResult = match serde_json::from_str(&response_str) {
@@ -300,16 +508,31 @@ impl ExecutionBackend for SweetMcpPluginBackend {
                         duration,
                         resource_usage: ResourceUsage::default(),
                         metadata: HashMap::new(),
+                        verdict: None,
+                        outcome: ExecutionOutcome::Completed,
+                        security: None,
+                        cost: None,
+                        crash: None,
+                        result_sets: None,
+                        workspace_archive: None,
+                        blobs: HashMap::new(),
+                        processed: None,
+                        raw_output: None,
+                        compilation: None,
                     };
                 }
             };
 
             let duration = start_time.elapsed().unwrap_or_default();
-            backend.tool_result_to_execution(tool_result, duration)
+            let mut result = backend.tool_result_to_execution(tool_result, duration);
+
+            result.security = Some(grants.security_report());
+            grants.record(&mut result.metadata);
+            result
         })
     }
 
-    fn health_check(&self) -> AsyncTask<HealthStatus> {
+    fn health_check(&self, level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
         let plugin_path = self.plugin_path.clone();
         let plugin = Arc::clone(&self.plugin);
 
@@ -321,6 +544,10 @@ impl ExecutionBackend for SweetMcpPluginBackend {
                     plugin_path.display()
                 ));
             }
+            if level == HealthCheckLevel::Liveness {
+                return HealthStatus::healthy("Plugin file present")
+                    .with_metric("plugin_path", plugin_path.display().to_string().as_str());
+            }
 
             // Try calling describe function to verify plugin is functional
             let mut plugin_guard = plugin.lock().await;
@@ -375,3 +602,50 @@ impl SweetMcpPluginBackend {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::ResourceLimits;
+
+    #[test]
+    fn grants_come_from_config_and_limits() {
+        let dir = std::env::temp_dir();
+        let mut config = BackendConfig::new("plugin");
+        let request = ExecutionRequest::new("1 + 1", "python");
+
+        let none = PluginGrants::derive(&config, &request).unwrap();
+        assert!(none.preopened_dirs.is_empty() && none.allowed_hosts.is_empty());
+        assert!(none.security_report().network_disabled);
+        assert_eq!(none.memory_max_pages, Some(8192));
+
+        config.backend_specific.insert(
+            PREOPENED_DIRS_KEY.to_string(),
+            format!("{}=/data", dir.display()),
+        );
+        config
+            .backend_specific
+            .insert(ALLOWED_HOSTS_KEY.to_string(), "api.example.com, ".to_string());
+        let granted = PluginGrants::derive(&config, &request).unwrap();
+        assert_eq!(granted.allowed_hosts, ["api.example.com"]);
+        assert_eq!(
+            granted.preopened_dirs.get(&dir.display().to_string()),
+            Some(&PathBuf::from("/data"))
+        );
+
+        let mut metadata = HashMap::new();
+        granted.record(&mut metadata);
+        assert_eq!(metadata["wasi.allowed_hosts"], "api.example.com");
+
+        let offline = request.clone().with_limits(ResourceLimits {
+            max_network_bandwidth: Some(0),
+            ..ResourceLimits::default()
+        });
+        assert!(PluginGrants::derive(&config, &offline).unwrap().allowed_hosts.is_empty());
+
+        config
+            .backend_specific
+            .insert(PREOPENED_DIRS_KEY.to_string(), "/nonexistent/cylo".to_string());
+        assert!(PluginGrants::derive(&config, &request).is_err());
+    }
+}
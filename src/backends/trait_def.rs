@@ -4,8 +4,13 @@
 // ExecutionBackend trait definition
 // ============================================================================
 
+use crate::async_task::EventStream;
 use crate::backends::config::BackendConfig;
-use crate::backends::types::{ExecutionRequest, ExecutionResult, HealthStatus};
+use crate::backends::output_stream::{OutputChunk, OutputStream};
+use crate::backends::types::{
+    BackendCapabilities, ExecutionRequest, ExecutionResult, HealthCheckLevel, HealthStatus,
+    InstanceMetrics, IsolationLevel,
+};
 use crate::execution_env::CyloResult;
 
 // Local AsyncTask type alias to avoid circular dependency with fluent_ai_domain
@@ -25,14 +30,53 @@ pub trait ExecutionBackend: Send + Sync + std::fmt::Debug {
     /// AsyncTask that resolves to execution result
     fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult>;
 
+    /// Execute code, streaming stdout and stderr while it runs
+    ///
+    /// Chunks arrive on the returned stream as the program writes them
+    /// when `capabilities().streaming` is set; other backends send each
+    /// stream as one chunk once the execution finishes. The stream closes
+    /// after the last chunk, and the result still holds the full output.
+    ///
+    /// # Arguments
+    /// * `request` - Execution request; any sink it already carries is
+    ///   replaced
+    ///
+    /// # Returns
+    /// Stream of output chunks, and AsyncTask that resolves to the result
+    fn execute_code_streaming(
+        &self,
+        mut request: ExecutionRequest,
+    ) -> (EventStream<OutputChunk>, AsyncTask<ExecutionResult>) {
+        let (sink, chunks) = OutputChunk::channel();
+        let live = self.capabilities().streaming;
+        request.output_sink = Some(sink.clone());
+        let execution = self.execute_code(request);
+        let task = tokio::spawn(async move {
+            let result = execution.await.unwrap_or_else(|e| {
+                ExecutionResult::failure(-1, format!("Execution task failed: {}", e))
+            });
+            if !live {
+                sink.emit(OutputStream::Stdout, result.stdout_bytes());
+                sink.emit(OutputStream::Stderr, result.stderr_bytes());
+            }
+            result
+        });
+        (chunks, task)
+    }
+
     /// Perform health check on this backend
     ///
-    /// Verifies that the backend is available and functional.
-    /// Should be fast and non-destructive.
+    /// `Liveness` only confirms the backend's binaries, sockets, and
+    /// directories are present and is cheap enough for hot paths.
+    /// `Readiness` additionally runs a test execution and may take seconds.
+    /// Both should be non-destructive.
+    ///
+    /// # Arguments
+    /// * `level` - How thoroughly to probe the backend
     ///
     /// # Returns
     /// AsyncTask that resolves to health status
-    fn health_check(&self) -> AsyncTask<HealthStatus>;
+    fn health_check(&self, level: HealthCheckLevel) -> AsyncTask<HealthStatus>;
 
     /// Clean up resources for this backend
     ///
@@ -65,4 +109,30 @@ pub trait ExecutionBackend: Send + Sync + std::fmt::Debug {
     /// # Returns
     /// List of supported programming languages
     fn supported_languages(&self) -> &[&'static str];
+
+    /// Describe what this backend can offer an execution
+    ///
+    /// Defaults to the built-in descriptor for `backend_type()`; backends
+    /// not known to cylo get the weakest one (process isolation, no
+    /// optional features) unless they override this.
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::of_backend(self.backend_type()).unwrap_or(BackendCapabilities {
+            isolation: IsolationLevel::Process,
+            max_memory_bytes: None,
+            network_control: false,
+            persistent_sessions: false,
+            artifact_collection: false,
+            streaming: false,
+        })
+    }
+
+    /// Sample live memory and CPU of the VMs or containers this instance
+    /// is running or has left behind
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to the sample, or None for backends that run
+    /// executions as plain host processes and hold nothing between them
+    fn instance_metrics(&self) -> AsyncTask<Option<InstanceMetrics>> {
+        tokio::spawn(async { None })
+    }
 }
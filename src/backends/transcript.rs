@@ -0,0 +1,481 @@
+// ============================================================================
+// File: packages/cylo/src/backends/transcript.rs
+// ----------------------------------------------------------------------------
+// Timestamped input/output transcripts of executions.
+//
+// Auditing or debugging an agent-driven run needs more than its final
+// stdout: what was fed in, what came back, and when. A transcript records
+// each chunk of input and output with its offset from the start, as it is
+// exchanged, and exports to asciinema's asciicast v2 format for replay in
+// a terminal player, or to plain text for logs and review. A request that
+// carries a recorder is transcribed live: the executor records its input
+// and taps its output sink, and the input pump records streamed stdin as
+// the program reads it. Backends that only return output once done are
+// transcribed with that output at completion. A finished one-shot
+// execution can also be turned into a transcript after the fact.
+//
+// A transcript keeps at most `max_bytes` of chunk data, so a long session
+// or a chatty program cannot grow it without bound. Chunks past the cap are
+// counted rather than kept, and both exports say how much was dropped.
+// ============================================================================
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{ExecutionRequest, ExecutionResult, OutputSink, OutputStream};
+
+/// Terminal size written into asciicast headers
+const ASCIICAST_SIZE: (u16, u16) = (80, 24);
+
+/// Bytes of chunk data a transcript keeps unless given another cap
+pub const DEFAULT_TRANSCRIPT_MAX_BYTES: usize = 16 * 1024 * 1024; // 16MB
+
+/// Stream a transcript chunk travelled on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptStream {
+    /// Input sent to the program
+    Input,
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+}
+
+impl TranscriptStream {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Input => "stdin",
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+        }
+    }
+}
+
+/// One chunk of input or output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptEvent {
+    /// Time since the transcript started
+    pub offset: Duration,
+    /// Stream the chunk travelled on
+    pub stream: TranscriptStream,
+    /// The chunk, as text
+    pub data: String,
+}
+
+/// Timestamped record of everything exchanged with an execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transcript {
+    /// Title for players and reviewers, e.g. the execution ID
+    pub title: Option<String>,
+    /// When recording started
+    pub started_at: SystemTime,
+    /// Chunks in the order they were exchanged
+    pub events: Vec<TranscriptEvent>,
+    /// Most bytes of chunk data kept
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+    /// Bytes of chunk data kept so far
+    #[serde(default)]
+    pub recorded_bytes: usize,
+    /// Bytes of chunk data dropped once the cap was reached
+    #[serde(default)]
+    pub dropped_bytes: u64,
+}
+
+fn default_max_bytes() -> usize {
+    DEFAULT_TRANSCRIPT_MAX_BYTES
+}
+
+impl Transcript {
+    /// Start recording now
+    pub fn new() -> Self {
+        Self {
+            title: None,
+            started_at: SystemTime::now(),
+            events: Vec::new(),
+            max_bytes: DEFAULT_TRANSCRIPT_MAX_BYTES,
+            recorded_bytes: 0,
+            dropped_bytes: 0,
+        }
+    }
+
+    /// Set the title
+    pub fn with_title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Keep at most `max_bytes` of chunk data instead of the default cap
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Record a chunk exchanged now; empty chunks are skipped
+    ///
+    /// Offsets never go backwards, even if the wall clock does.
+    pub fn record<D: Into<String>>(&mut self, stream: TranscriptStream, data: D) {
+        let elapsed = SystemTime::now()
+            .duration_since(self.started_at)
+            .unwrap_or_default();
+        self.record_at(elapsed, stream, data);
+    }
+
+    /// Record a chunk at a known offset from the start
+    ///
+    /// Once the cap is reached the rest of the chunk, cut at a character
+    /// boundary, and every later chunk only add to `dropped_bytes`.
+    pub fn record_at<D: Into<String>>(
+        &mut self,
+        offset: Duration,
+        stream: TranscriptStream,
+        data: D,
+    ) {
+        let mut data = data.into();
+        // Nothing is kept after a gap, so the transcript stays in order
+        let room = match self.dropped_bytes {
+            0 => self.max_bytes.saturating_sub(self.recorded_bytes),
+            _ => 0,
+        };
+        if data.len() > room {
+            let mut keep = room;
+            while !data.is_char_boundary(keep) {
+                keep -= 1;
+            }
+            self.dropped_bytes += (data.len() - keep) as u64;
+            data.truncate(keep);
+        }
+        if data.is_empty() {
+            return;
+        }
+        self.recorded_bytes += data.len();
+        let last = self.events.last().map_or(Duration::ZERO, |event| event.offset);
+        self.events.push(TranscriptEvent {
+            offset: offset.max(last),
+            stream,
+            data,
+        });
+    }
+
+    /// Transcript of a finished one-shot execution
+    ///
+    /// Input is placed at the start and output at the end of the
+    /// execution, since a one-shot run only returns output once done.
+    pub fn of_execution(request: &ExecutionRequest, result: &ExecutionResult) -> Self {
+        let mut transcript = Self::new();
+        transcript.started_at = SystemTime::now()
+            .checked_sub(result.duration)
+            .unwrap_or(transcript.started_at);
+        transcript.title = request.execution_id.clone();
+        if let Some(input) = &request.input {
+            transcript.record_at(Duration::ZERO, TranscriptStream::Input, input.as_str());
+        }
+        transcript.record_at(result.duration, TranscriptStream::Stdout, result.stdout.as_str());
+        transcript.record_at(result.duration, TranscriptStream::Stderr, result.stderr.as_str());
+        transcript
+    }
+
+    /// Time from the start to the last chunk
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |event| event.offset)
+    }
+
+    /// Export as an asciicast v2 recording
+    ///
+    /// Output on either stream becomes an "o" event, as a terminal shows
+    /// both; input becomes an "i" event. Dropped data is noted in an "m"
+    /// marker at the end.
+    pub fn to_asciicast(&self) -> String {
+        let (width, height) = ASCIICAST_SIZE;
+        let mut header = serde_json::json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": self
+                .started_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            "duration": self.duration().as_secs_f64(),
+        });
+        if let Some(title) = &self.title {
+            header["title"] = title.as_str().into();
+        }
+
+        let mut cast = header.to_string();
+        for event in &self.events {
+            let code = match event.stream {
+                TranscriptStream::Input => "i",
+                TranscriptStream::Stdout | TranscriptStream::Stderr => "o",
+            };
+            let line = serde_json::json!([event.offset.as_secs_f64(), code, event.data]);
+            cast.push('\n');
+            cast.push_str(&line.to_string());
+        }
+        if let Some(note) = self.dropped_note() {
+            let marker = serde_json::json!([self.duration().as_secs_f64(), "m", note]);
+            cast.push('\n');
+            cast.push_str(&marker.to_string());
+        }
+        cast.push('\n');
+        cast
+    }
+
+    /// Export as plain text, one line per line of each chunk, prefixed with
+    /// its offset and stream
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if let Some(title) = &self.title {
+            let _ = writeln!(text, "# {title}");
+        }
+        for event in &self.events {
+            let offset = event.offset.as_secs_f64();
+            for line in event.data.lines() {
+                let _ = writeln!(text, "[{offset:>9.3}] {:<6} | {line}", event.stream.label());
+            }
+        }
+        if let Some(note) = self.dropped_note() {
+            let _ = writeln!(text, "# {note}");
+        }
+        text
+    }
+
+    /// Why the transcript is incomplete, if it is
+    fn dropped_note(&self) -> Option<String> {
+        (self.dropped_bytes > 0).then(|| {
+            format!(
+                "{} bytes not recorded: transcript cap of {} bytes reached",
+                self.dropped_bytes, self.max_bytes
+            )
+        })
+    }
+}
+
+impl Default for Transcript {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Transcript that executions record into as they run
+///
+/// Clones record into the same transcript, so one recorder can follow
+/// every execution of a session.
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptRecorder {
+    transcript: Arc<Mutex<Transcript>>,
+}
+
+impl TranscriptRecorder {
+    /// Record into `transcript`, which keeps its title and start time
+    pub fn new(transcript: Transcript) -> Self {
+        Self {
+            transcript: Arc::new(Mutex::new(transcript)),
+        }
+    }
+
+    /// Record a chunk exchanged now; empty chunks are skipped
+    pub fn record<D: Into<String>>(&self, stream: TranscriptStream, data: D) {
+        self.lock().record(stream, data);
+    }
+
+    /// Copy of everything recorded so far
+    pub fn transcript(&self) -> Transcript {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Transcript> {
+        self.transcript
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Decode and remove everything in `buffer` but an incomplete UTF-8
+/// sequence at its end, which waits for the rest of its bytes; anything
+/// else invalid is replaced
+pub(crate) fn take_complete(buffer: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(buffer) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => buffer.len(),
+    };
+    let tail = buffer.split_off(complete);
+    String::from_utf8_lossy(&std::mem::replace(buffer, tail)).into_owned()
+}
+
+/// One execution's connection to a recorder
+///
+/// Output is decoded per stream, so a UTF-8 sequence split across chunks
+/// is recorded whole.
+#[derive(Debug, Clone)]
+pub(crate) struct TranscriptTap {
+    recorder: TranscriptRecorder,
+    /// Undecoded tails of stdout and stderr
+    pending: Arc<Mutex<[Vec<u8>; 2]>>,
+    /// Whether any output arrived while the execution ran
+    streamed: Arc<AtomicBool>,
+}
+
+impl TranscriptTap {
+    /// Record the request's input and route its output through the tap,
+    /// on to any sink the request already had
+    pub(crate) fn attach(recorder: TranscriptRecorder, request: &mut ExecutionRequest) -> Self {
+        if let Some(input) = &request.input {
+            recorder.record(TranscriptStream::Input, input.as_str());
+        }
+        let tap = Self {
+            recorder,
+            pending: Arc::new(Mutex::new([Vec::new(), Vec::new()])),
+            streamed: Arc::new(AtomicBool::new(false)),
+        };
+        let sink = request.output_sink.take().unwrap_or_else(OutputSink::detached);
+        request.output_sink = Some(sink.tapped(tap.clone()));
+        tap
+    }
+
+    /// Record a chunk of live output
+    pub(crate) fn output(&self, stream: OutputStream, data: &[u8]) {
+        self.streamed.store(true, Ordering::Relaxed);
+        let (index, stream) = Self::slot(stream);
+        let decoded = {
+            let mut pending = self.pending.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            pending[index].extend_from_slice(data);
+            take_complete(&mut pending[index])
+        };
+        self.recorder.record(stream, decoded);
+    }
+
+    /// Flush held-back bytes once the execution is over, and record the
+    /// result's output if none arrived while it ran
+    pub(crate) fn finish(&self, result: Option<&ExecutionResult>) {
+        let streamed = self.streamed.load(Ordering::Relaxed);
+        if let Some(result) = result.filter(|_| !streamed) {
+            self.recorder.record(TranscriptStream::Stdout, result.stdout.as_str());
+            self.recorder.record(TranscriptStream::Stderr, result.stderr.as_str());
+        } else {
+            let mut pending = self.pending.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            for stream in [OutputStream::Stdout, OutputStream::Stderr] {
+                let (index, stream) = Self::slot(stream);
+                let tail = std::mem::take(&mut pending[index]);
+                self.recorder.record(stream, String::from_utf8_lossy(&tail));
+            }
+        }
+    }
+
+    fn slot(stream: OutputStream) -> (usize, TranscriptStream) {
+        match stream {
+            OutputStream::Stdout => (0, TranscriptStream::Stdout),
+            OutputStream::Stderr => (1, TranscriptStream::Stderr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcripts_export_as_asciicast_and_text() {
+        let mut transcript = Transcript::new().with_title("exec-1");
+        transcript.record_at(Duration::ZERO, TranscriptStream::Input, "2+2\n");
+        transcript.record_at(Duration::from_millis(1500), TranscriptStream::Stdout, "4\n");
+        // Offsets never go backwards; empty chunks are dropped
+        transcript.record_at(Duration::from_secs(1), TranscriptStream::Stderr, "warn\n");
+        transcript.record(TranscriptStream::Stdout, "");
+        assert_eq!(transcript.events.len(), 3);
+        assert_eq!(transcript.duration(), Duration::from_millis(1500));
+
+        let cast = transcript.to_asciicast();
+        let lines: Vec<serde_json::Value> =
+            cast.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["title"], "exec-1");
+        assert_eq!(lines[1], serde_json::json!([0.0, "i", "2+2\n"]));
+        assert_eq!(lines[2], serde_json::json!([1.5, "o", "4\n"]));
+        assert_eq!(lines[3], serde_json::json!([1.5, "o", "warn\n"]));
+
+        let text = transcript.to_text();
+        assert_eq!(
+            text,
+            "# exec-1\n\
+             [    0.000] stdin  | 2+2\n\
+             [    1.500] stdout | 4\n\
+             [    1.500] stderr | warn\n"
+        );
+
+        let request = ExecutionRequest::new("print(input())", "python").with_input("hi\n");
+        let mut result = ExecutionResult::success("hi\n");
+        result.duration = Duration::from_millis(20);
+        let one_shot = Transcript::of_execution(&request, &result);
+        let streams: Vec<_> = one_shot.events.iter().map(|e| (e.offset, e.stream)).collect();
+        assert_eq!(
+            streams,
+            [
+                (Duration::ZERO, TranscriptStream::Input),
+                (Duration::from_millis(20), TranscriptStream::Stdout),
+            ]
+        );
+    }
+
+    #[test]
+    fn transcripts_stop_growing_at_their_cap() {
+        let mut transcript = Transcript::new().with_max_bytes(8);
+        transcript.record_at(Duration::ZERO, TranscriptStream::Input, "12345");
+        // Cut before the two-byte character that would cross the cap
+        transcript.record_at(Duration::ZERO, TranscriptStream::Stdout, "ab\u{e9}cd");
+        transcript.record_at(Duration::ZERO, TranscriptStream::Stderr, "dropped");
+        let kept: Vec<_> = transcript.events.iter().map(|e| e.data.as_str()).collect();
+        assert_eq!(kept, ["12345", "ab"]);
+        assert_eq!((transcript.recorded_bytes, transcript.dropped_bytes), (7, 11));
+
+        let note = "11 bytes not recorded: transcript cap of 8 bytes reached";
+        assert!(transcript.to_text().ends_with(&format!("# {note}\n")));
+        let cast = transcript.to_asciicast();
+        let marker: serde_json::Value = serde_json::from_str(cast.lines().last().unwrap()).unwrap();
+        assert_eq!(marker, serde_json::json!([0.0, "m", note]));
+    }
+
+    #[test]
+    fn taps_record_live_output_or_the_result_once_done() {
+        let recorder = TranscriptRecorder::default();
+        let mut request = ExecutionRequest::new("cat", "bash").with_input("hi\n");
+        let tap = TranscriptTap::attach(recorder.clone(), &mut request);
+        let sink = request.output_sink.clone().unwrap();
+
+        // A character split across chunks is recorded whole
+        let dash = "\u{2013}".as_bytes();
+        sink.emit(OutputStream::Stdout, &[b"a ", &dash[..1]].concat());
+        sink.emit(OutputStream::Stderr, b"warn\n");
+        sink.emit(OutputStream::Stdout, &[&dash[1..], b" b\n"].concat());
+        tap.finish(Some(&ExecutionResult::success("ignored")));
+        let recorded: Vec<_> = recorder
+            .transcript()
+            .events
+            .into_iter()
+            .map(|event| (event.stream, event.data))
+            .collect();
+        assert_eq!(
+            recorded,
+            [
+                (TranscriptStream::Input, "hi\n".to_string()),
+                (TranscriptStream::Stdout, "a ".to_string()),
+                (TranscriptStream::Stderr, "warn\n".to_string()),
+                (TranscriptStream::Stdout, "\u{2013} b\n".to_string()),
+            ]
+        );
+
+        // Backends that do not stream are transcribed from their result
+        let recorder = TranscriptRecorder::default();
+        let mut request = ExecutionRequest::new("echo 4", "bash");
+        let tap = TranscriptTap::attach(recorder.clone(), &mut request);
+        tap.finish(Some(&ExecutionResult::success("4\n")));
+        let events = recorder.transcript().events;
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].stream, events[0].data.as_str()), (TranscriptStream::Stdout, "4\n"));
+    }
+}
@@ -4,12 +4,250 @@
 // Execution request and result types
 // ============================================================================
 
-use std::collections::HashMap;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
+use tokio::io::AsyncRead;
 
+use crate::backends::arch::Arch;
+use crate::backends::archive::{ArchiveLimits, OutputArchive, WorkspaceArchive};
+use crate::backends::blob_store::{Blob, BlobFile, BlobStore, OutputBlobs};
+use crate::backends::budget::ExecutionBudget;
+use crate::backends::clock::VirtualClock;
+use crate::backends::compile_phase::CompilationPhase;
+use crate::backends::compiler::CompilerOptions;
 use crate::backends::config::ResourceLimits;
+use crate::backends::crash::CrashReport;
+use crate::backends::desktop::DesktopAccess;
+use crate::backends::dns::DnsPolicy;
+use crate::backends::environment::EnvironmentProfile;
+use crate::backends::expectations::{ExpectationVerdict, Expectations};
+use crate::backends::git_checkout::GitCheckout;
+use crate::backends::image_ref::is_valid_digest;
+use crate::backends::input_stream::InputStream;
+use crate::backends::language;
+use crate::backends::output::RawOutput;
+use crate::backends::output_stream::OutputSink;
+use crate::backends::paths::relative_inside;
+use crate::backends::post_process::{PostProcessor, ProcessedOutput};
+use crate::backends::progress::{ProgressReporter, ProvisioningStage};
+use crate::backends::registry;
+use crate::backends::sandbox_state::SandboxState;
+use crate::backends::sql::{ResultSet, SqlOptions};
+use crate::backends::transcript::TranscriptRecorder;
+use crate::backends::volumes::{Volume, VolumeMount};
+use crate::backends::wasm_module;
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Strength of the isolation boundary a backend places around execution
+///
+/// Levels are ordered, so a requirement is met by its own level and any
+/// stronger one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum IsolationLevel {
+    /// Host process with OS resource limits (Windows Job Objects, WASM plugins
+    /// and modules)
+    Process,
+    /// Host process in its own Linux namespaces (LandLock/bubblewrap)
+    Namespace,
+    /// Container with its own root filesystem (Apple, Docker, Kubernetes Jobs)
+    Container,
+    /// Dedicated microVM with its own kernel (FireCracker)
+    MicroVM,
+}
+
+impl IsolationLevel {
+    /// Isolation provided by a built-in or registered backend, by backend
+    /// name
+    pub fn of_backend(backend: &str) -> Option<Self> {
+        match backend {
+            "WindowsJob" | "SweetMcpPlugin" | "Wasm" => Some(Self::Process),
+            "LandLock" => Some(Self::Namespace),
+            "Apple" | "Docker" | "K8sJob" => Some(Self::Container),
+            "FireCracker" => Some(Self::MicroVM),
+            _ => registry::capabilities(backend).map(|capabilities| capabilities.isolation),
+        }
+    }
+}
+
+impl std::fmt::Display for IsolationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Process => "process",
+            Self::Namespace => "namespace",
+            Self::Container => "container",
+            Self::MicroVM => "microVM",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What a backend can offer an execution
+///
+/// Routing consults the descriptors of built-in and registered backends by
+/// name, before any instance exists; an instance may refine its own descriptor from its
+/// configuration (e.g. a FireCracker VM's memory size).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackendCapabilities {
+    /// Isolation boundary placed around every execution
+    pub isolation: IsolationLevel,
+    /// Largest memory limit an execution can be given, in bytes; None when
+    /// only host memory bounds it
+    pub max_memory_bytes: Option<u64>,
+    /// Executions can be run without network access
+    pub network_control: bool,
+    /// State can persist across executions in a session
+    pub persistent_sessions: bool,
+    /// Files produced by an execution can be collected
+    pub artifact_collection: bool,
+    /// Output can be streamed while the execution runs
+    pub streaming: bool,
+}
+
+impl BackendCapabilities {
+    /// Capabilities of a built-in or registered backend, by backend name
+    pub fn of_backend(backend: &str) -> Option<Self> {
+        if let Some(capabilities) = registry::capabilities(backend) {
+            return Some(capabilities);
+        }
+        let isolation = IsolationLevel::of_backend(backend)?;
+        let process = Self {
+            isolation,
+            max_memory_bytes: None,
+            network_control: false,
+            persistent_sessions: false,
+            artifact_collection: false,
+            streaming: false,
+        };
+        Some(match backend {
+            // Plugins get no network hosts in their manifest
+            "SweetMcpPlugin" => Self {
+                network_control: true,
+                ..process
+            },
+            // Children's pipes are read by the backend as data arrives
+            "LandLock" | "Apple" | "WindowsJob" => Self {
+                streaming: true,
+                ..process
+            },
+            // Containers start with --network none unless network is
+            // allowed; a session's container is committed between runs
+            "Docker" => Self {
+                network_control: true,
+                persistent_sessions: true,
+                streaming: true,
+                ..process
+            },
+            // Modules get no sockets unless network is allowed
+            "Wasm" => Self {
+                network_control: true,
+                streaming: true,
+                ..process
+            },
+            // Pods without network get a deny-all NetworkPolicy; logs are
+            // followed as the pod writes them
+            "K8sJob" => Self {
+                network_control: true,
+                streaming: true,
+                ..process
+            },
+            // Default VM size; the network is off unless enabled, and a
+            // session's VMs boot from its own copy of the rootfs
+            "FireCracker" => Self {
+                max_memory_bytes: Some(512 * 1024 * 1024),
+                network_control: true,
+                persistent_sessions: true,
+                ..process
+            },
+            _ => process,
+        })
+    }
+
+    /// Whether an execution with this memory limit fits the backend
+    pub fn fits_memory(&self, bytes: Option<u64>) -> bool {
+        match (bytes, self.max_memory_bytes) {
+            (Some(requested), Some(max)) => requested <= max,
+            _ => true,
+        }
+    }
+}
+
+/// Live resource usage of a backend instance
+///
+/// Covers the VMs or containers the instance is running at the time of
+/// sampling, including ones earlier executions left behind (stopped on
+/// timeout, or not shut down), so an idle instance reports what it still
+/// holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceMetrics {
+    /// VMs or containers currently running
+    pub active: u32,
+    /// Resident memory they hold, in bytes
+    pub memory_bytes: u64,
+    /// CPU time they have consumed so far, in milliseconds
+    pub cpu_time_ms: u64,
+    /// When the sample was taken
+    pub sampled_at: SystemTime,
+}
+
+impl InstanceMetrics {
+    /// Sum per-resource samples of (memory bytes, CPU milliseconds)
+    pub fn from_samples<I: IntoIterator<Item = (u64, u64)>>(samples: I) -> Self {
+        let mut metrics = Self {
+            active: 0,
+            memory_bytes: 0,
+            cpu_time_ms: 0,
+            sampled_at: SystemTime::now(),
+        };
+        for (memory_bytes, cpu_time_ms) in samples {
+            metrics.active += 1;
+            metrics.memory_bytes += memory_bytes;
+            metrics.cpu_time_ms += cpu_time_ms;
+        }
+        metrics
+    }
+}
+
+/// Isolation an execution actually ran under
+///
+/// Reported by the backend alongside the result so policy engines can
+/// verify untrusted code got the isolation it required.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityReport {
+    /// Isolation boundary the code ran behind
+    pub isolation: IsolationLevel,
+    /// Code had no network access
+    pub network_disabled: bool,
+    /// Filesystem outside the execution workspace was read-only
+    pub filesystem_read_only: bool,
+    /// Memory cap enforced on the execution in bytes, if any
+    pub memory_limit_bytes: Option<u64>,
+    /// Code could not attach to SysV IPC objects, POSIX shared memory or
+    /// message queues of the host or of other executions
+    #[serde(default)]
+    pub ipc_isolated: bool,
+    /// Code could not reach other processes' windows, the clipboard,
+    /// global atoms or desktop settings
+    #[serde(default)]
+    pub ui_restricted: bool,
+}
+
+/// Billable usage of one execution and the price the cost model put on it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionCost {
+    /// CPU time consumed, in seconds
+    pub cpu_seconds: f64,
+    /// Memory reserved (limit, or peak usage when unlimited) times wall
+    /// time, in GiB-seconds
+    pub gb_seconds: f64,
+    /// MicroVMs booted to serve the execution
+    pub vm_boots: u32,
+    /// Price of the usage under the executor's cost model; zero when no
+    /// cost model is configured
+    pub amount: f64,
+}
 
 /// Execution request parameters
 ///
@@ -25,6 +263,38 @@ pub struct ExecutionRequest {
     /// Optional input data for the code
     pub input: Option<String>,
 
+    /// Input streamed into the program's stdin as it reads, instead of
+    /// `input`; not serialized
+    #[serde(skip)]
+    pub input_stream: Option<InputStream>,
+
+    /// Additional files by path relative to the workspace, written next to
+    /// the source file; the source file wins over a file of the same name
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+
+    /// Stored blobs by path relative to the workspace, copied in next to
+    /// the additional files
+    #[serde(default)]
+    pub blob_files: BTreeMap<String, BlobFile>,
+
+    /// Initial workspace contents, unpacked before the files and the
+    /// source file are written
+    #[serde(default)]
+    pub workspace_archive: Option<WorkspaceArchive>,
+
+    /// Archive of the final workspace to return in the result
+    #[serde(default)]
+    pub output_archive: Option<OutputArchive>,
+
+    /// Caps on unpacking the workspace archive and packing the output one
+    #[serde(default)]
+    pub archive_limits: ArchiveLimits,
+
+    /// Store for outputs too large to return inline
+    #[serde(default)]
+    pub output_blobs: Option<OutputBlobs>,
+
     /// Environment variables to set
     pub env_vars: HashMap<String, String>,
 
@@ -37,11 +307,160 @@ pub struct ExecutionRequest {
     /// Resource limits
     pub limits: ResourceLimits,
 
+    /// Whether `timeout` was chosen by the caller; a request keeping the
+    /// default gets its language profile's timeout. Set by `with_timeout`
+    #[serde(default)]
+    pub timeout_chosen: bool,
+
+    /// Whether `limits` were chosen by the caller; a request keeping the
+    /// defaults gets the executor's configured ones. Set by `with_limits`
+    #[serde(default)]
+    pub limits_chosen: bool,
+
     /// Backend-specific configuration
     pub backend_config: HashMap<String, String>,
+
+    /// Exit-policy assertions evaluated after execution
+    #[serde(default)]
+    pub expectations: Option<Expectations>,
+
+    /// Identifier of the execution, used to attribute spawned resources
+    #[serde(default)]
+    pub execution_id: Option<String>,
+
+    /// Time between the graceful termination request and the forced kill
+    /// when the execution times out
+    #[serde(default = "default_termination_grace")]
+    pub termination_grace: Duration,
+
+    /// Minimum isolation the executing backend must provide
+    #[serde(default)]
+    pub required_isolation: Option<IsolationLevel>,
+
+    /// Backend the request must run on, overriding the routing strategy
+    #[serde(default)]
+    pub required_backend: Option<String>,
+
+    /// CPU architecture to run the code as; the host's when unset
+    #[serde(default)]
+    pub arch: Option<Arch>,
+
+    /// Tenant the execution's cost is attributed to
+    #[serde(default)]
+    pub tenant: Option<String>,
+
+    /// Host paths the sandbox may read, exposed at the same location
+    #[serde(default)]
+    pub readable_paths: Vec<PathBuf>,
+
+    /// Host paths the sandbox may read and write, exposed at the same location
+    #[serde(default)]
+    pub writable_paths: Vec<PathBuf>,
+
+    /// Persistent volumes attached read-write, exposed at their targets
+    #[serde(default)]
+    pub volumes: Vec<VolumeMount>,
+
+    /// Repository checked out into the workspace before the code runs
+    #[serde(default)]
+    pub git_repo: Option<GitCheckout>,
+
+    /// Host Go module cache served read-only to Go executions as a warm
+    /// layer, exposed at the same location
+    #[serde(default)]
+    pub go_module_cache: Option<PathBuf>,
+
+    /// Name of a registered Python virtual environment the code runs in
+    #[serde(default)]
+    pub python_env: Option<String>,
+
+    /// Resolvers and name restrictions applied when network access is allowed
+    #[serde(default)]
+    pub dns: Option<DnsPolicy>,
+
+    /// Clock the executed code observes instead of the host's
+    #[serde(default)]
+    pub clock: Option<VirtualClock>,
+
+    /// Timezone, locale, umask and home directory of the executed code
+    #[serde(default)]
+    pub environment: EnvironmentProfile,
+
+    /// Host display, audio and session bus the code may use; none unless
+    /// the request opts in
+    #[serde(default)]
+    pub desktop: DesktopAccess,
+
+    /// Capture a core dump of crashing code, capped at this many bytes
+    #[serde(default)]
+    pub core_dump_limit: Option<u64>,
+
+    /// Optimization level, edition and flags for compiled languages
+    #[serde(default)]
+    pub compiler: Option<CompilerOptions>,
+
+    /// Engine and seed datasets of a SQL request
+    #[serde(default)]
+    pub sql: Option<SqlOptions>,
+
+    /// Steps applied to the result after the code has run, such as ANSI
+    /// stripping and traceback parsing
+    #[serde(default)]
+    pub post_processors: Vec<PostProcessor>,
+
+    /// Terminate the execution early once it has written no output and
+    /// used no CPU for this long
+    #[serde(default)]
+    pub stall_timeout: Option<Duration>,
+
+    /// Bound on everything done for the execution, provisioning, queueing
+    /// and the program included; the executor's default when unset
+    #[serde(default)]
+    pub total_timeout: Option<Duration>,
+
+    /// Receiver of provisioning progress events; not serialized
+    #[serde(skip)]
+    pub progress: Option<ProgressReporter>,
+
+    /// Receiver of stdout and stderr chunks as the code writes them; not
+    /// serialized
+    #[serde(skip)]
+    pub output_sink: Option<OutputSink>,
+
+    /// Budget shared with the other requests of a series, drawn on as the
+    /// request is admitted; not serialized
+    #[serde(skip)]
+    pub budget: Option<ExecutionBudget>,
+
+    /// Transcript the execution's input and output are recorded in as they
+    /// are exchanged; not serialized
+    #[serde(skip)]
+    pub transcript: Option<TranscriptRecorder>,
+
+    /// Sandbox filesystem to continue from and save back to, set by sticky
+    /// sessions; not serialized
+    #[serde(skip)]
+    pub sandbox_state: Option<SandboxState>,
+}
+
+fn default_termination_grace() -> Duration {
+    Duration::from_secs(2)
 }
 
 impl ExecutionRequest {
+    /// Largest accepted source code payload
+    pub const MAX_CODE_BYTES: usize = 10 * 1024 * 1024; // 10MB
+
+    /// Largest accepted stdin payload
+    pub const MAX_INPUT_BYTES: usize = 64 * 1024 * 1024; // 64MB
+
+    /// Smallest memory limit any runtime can start under
+    pub const MIN_MEMORY_BYTES: u64 = 1024 * 1024; // 1MB
+
+    /// Timeout of a new request; the executor replaces it with the
+    /// language's profile timeout unless the request chose its own
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
     /// Create a new execution request
     ///
     /// # Arguments
@@ -52,11 +471,48 @@ impl ExecutionRequest {
             code: code.into(),
             language: language.into(),
             input: None,
+            input_stream: None,
+            files: BTreeMap::new(),
+            blob_files: BTreeMap::new(),
+            workspace_archive: None,
+            output_archive: None,
+            archive_limits: ArchiveLimits::default(),
+            output_blobs: None,
             env_vars: HashMap::new(),
             working_dir: None,
-            timeout: Duration::from_secs(30),
+            timeout: Self::DEFAULT_TIMEOUT,
             limits: ResourceLimits::default(),
+            timeout_chosen: false,
+            limits_chosen: false,
             backend_config: HashMap::new(),
+            expectations: None,
+            execution_id: None,
+            termination_grace: default_termination_grace(),
+            required_isolation: None,
+            required_backend: None,
+            arch: None,
+            tenant: None,
+            readable_paths: Vec::new(),
+            writable_paths: Vec::new(),
+            volumes: Vec::new(),
+            git_repo: None,
+            go_module_cache: None,
+            python_env: None,
+            dns: None,
+            clock: None,
+            environment: EnvironmentProfile::default(),
+            desktop: DesktopAccess::default(),
+            core_dump_limit: None,
+            compiler: None,
+            sql: None,
+            post_processors: Vec::new(),
+            stall_timeout: None,
+            total_timeout: None,
+            progress: None,
+            output_sink: None,
+            budget: None,
+            transcript: None,
+            sandbox_state: None,
         }
     }
 
@@ -66,6 +522,62 @@ impl ExecutionRequest {
         self
     }
 
+    /// Stream the execution's stdin from `reader` as the program reads it,
+    /// for payloads too large to hold in memory
+    pub fn with_input_stream<R: AsyncRead + Send + Unpin + 'static>(mut self, reader: R) -> Self {
+        self.input_stream = Some(InputStream::new(reader));
+        self
+    }
+
+    /// Add a file to the workspace, e.g. a package.json or a module the
+    /// code imports
+    pub fn with_file<P: Into<String>, C: Into<String>>(mut self, path: P, contents: C) -> Self {
+        self.files.insert(path.into(), contents.into());
+        self
+    }
+
+    /// Copy a stored blob into the workspace, e.g. a dataset or model
+    /// weights too large to embed in every request
+    pub fn with_blob_file<P: Into<String>>(mut self, path: P, blob: &Blob) -> Self {
+        self.blob_files.insert(
+            path.into(),
+            BlobFile {
+                digest: blob.digest.clone(),
+                source: blob.path.clone(),
+            },
+        );
+        self
+    }
+
+    /// Start from the contents of a tar or zip archive; files and the
+    /// source file are written over it
+    pub fn with_workspace_archive(mut self, archive: WorkspaceArchive) -> Self {
+        self.workspace_archive = Some(archive);
+        self
+    }
+
+    /// Return the final workspace, or a subtree of it, as an archive
+    pub fn with_output_archive(mut self, output: OutputArchive) -> Self {
+        self.output_archive = Some(output);
+        self
+    }
+
+    /// Store stdout, stderr and the workspace archive in `store` when they
+    /// are larger than `threshold_bytes`, returning them as blobs
+    pub fn with_output_blobs(mut self, store: &BlobStore, threshold_bytes: u64) -> Self {
+        self.output_blobs = Some(OutputBlobs {
+            root: store.root().to_path_buf(),
+            threshold_bytes,
+        });
+        self
+    }
+
+    /// Cap what the workspace archives may unpack to or be packed from
+    pub fn with_archive_limits(mut self, limits: ArchiveLimits) -> Self {
+        self.archive_limits = limits;
+        self
+    }
+
     /// Add environment variable
     pub fn with_env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
         self.env_vars.insert(key.into(), value.into());
@@ -81,12 +593,20 @@ impl ExecutionRequest {
     /// Set execution timeout
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
+        self.timeout_chosen = true;
+        self
+    }
+
+    /// Set the grace period between SIGTERM/CTRL_BREAK and the forced kill
+    pub fn with_termination_grace(mut self, grace: Duration) -> Self {
+        self.termination_grace = grace;
         self
     }
 
     /// Set resource limits
     pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
         self.limits = limits;
+        self.limits_chosen = true;
         self
     }
 
@@ -99,6 +619,527 @@ impl ExecutionRequest {
         self.backend_config.insert(key.into(), value.into());
         self
     }
+
+    /// Set the execution identifier used to attribute spawned resources
+    pub fn with_execution_id<I: Into<String>>(mut self, id: I) -> Self {
+        self.execution_id = Some(id.into());
+        self
+    }
+
+    /// Set exit-policy assertions to evaluate against the result
+    pub fn with_expectations(mut self, expectations: Expectations) -> Self {
+        self.expectations = Some(expectations);
+        self
+    }
+
+    /// Add a step applied to the result after the code has run
+    pub fn with_post_processor(mut self, processor: PostProcessor) -> Self {
+        if !self.post_processors.contains(&processor) {
+            self.post_processors.push(processor);
+        }
+        self
+    }
+
+    /// Require a minimum isolation level from the executing backend
+    pub fn with_required_isolation(mut self, level: IsolationLevel) -> Self {
+        self.required_isolation = Some(level);
+        self
+    }
+
+    /// Require execution on a specific backend (e.g. "FireCracker")
+    pub fn with_backend<B: Into<String>>(mut self, backend: B) -> Self {
+        self.required_backend = Some(backend.into());
+        self
+    }
+
+    /// Run the code as the given architecture, e.g. x86_64 on an arm64
+    /// host; routing only picks backends able to
+    pub fn with_arch(mut self, arch: Arch) -> Self {
+        self.arch = Some(arch);
+        self
+    }
+
+    /// Attribute the execution's cost to a tenant
+    pub fn with_tenant<T: Into<String>>(mut self, tenant: T) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    /// Expose a host path to the sandbox read-only
+    pub fn with_readable_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.readable_paths.push(path.into());
+        self
+    }
+
+    /// Expose a host path to the sandbox read-write
+    pub fn with_writable_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.writable_paths.push(path.into());
+        self
+    }
+
+    /// Attach a persistent volume read-write at an absolute sandbox path
+    ///
+    /// Whatever the execution leaves in it is there for the next execution
+    /// the volume is attached to.
+    pub fn with_volume<P: Into<PathBuf>>(mut self, volume: &Volume, target: P) -> Self {
+        self.volumes.push(VolumeMount {
+            name: volume.name.clone(),
+            source: volume.path.clone(),
+            target: target.into(),
+        });
+        self
+    }
+
+    /// Check out a repository revision into the workspace's `repo`
+    /// directory before the code runs
+    ///
+    /// # Arguments
+    /// * `url` - https, ssh or scp-like remote URL
+    /// * `rev` - Branch, tag or commit
+    pub fn with_git_repo<U: Into<String>, R: Into<String>>(self, url: U, rev: R) -> Self {
+        self.with_git_checkout(GitCheckout::new(url, rev))
+    }
+
+    /// Check out a repository with a custom depth, size cap, directory or
+    /// credentials
+    pub fn with_git_checkout(mut self, checkout: GitCheckout) -> Self {
+        self.git_repo = Some(checkout);
+        self
+    }
+
+    /// Resolve Go modules from a pre-populated host module cache first
+    ///
+    /// The directory is a `GOMODCACHE` from an earlier download; it is
+    /// mounted read-only and never written to.
+    pub fn with_go_module_cache<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.go_module_cache = Some(path.into());
+        self
+    }
+
+    /// Run Python code in a registered virtual environment
+    ///
+    /// The name refers to an environment registered with
+    /// `sandbox::register_environment`; its interpreter runs the code and
+    /// its packages are importable, while the venv itself stays read-only.
+    pub fn with_python_env<N: Into<String>>(mut self, name: N) -> Self {
+        self.python_env = Some(name.into());
+        self
+    }
+
+    /// Whether the execution may use the network
+    pub fn network_allowed(&self) -> bool {
+        self.limits.max_network_bandwidth != Some(0)
+    }
+
+    /// Apply a DNS policy to the execution's network access
+    pub fn with_dns(mut self, policy: DnsPolicy) -> Self {
+        self.dns = Some(policy);
+        self
+    }
+
+    /// Replace the normalized environment profile
+    pub fn with_environment(mut self, environment: EnvironmentProfile) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    /// Let GUI code use the host's display, audio or session bus
+    ///
+    /// Only backends running code on the host can grant it; guests refuse
+    /// such requests.
+    pub fn with_desktop_access(mut self, access: DesktopAccess) -> Self {
+        self.desktop = access;
+        self
+    }
+
+    /// Capture core dumps of crashing code, up to `max_bytes` each
+    pub fn with_core_dumps(mut self, max_bytes: u64) -> Self {
+        self.core_dump_limit = Some(max_bytes);
+        self
+    }
+
+    /// Terminate the execution as stalled after `idle` without output or
+    /// CPU use, even before the timeout
+    pub fn with_stall_timeout(mut self, idle: Duration) -> Self {
+        self.stall_timeout = Some(idle);
+        self
+    }
+
+    /// Bound the whole execution, provisioning and queueing included, not
+    /// just the program
+    pub fn with_total_timeout(mut self, total: Duration) -> Self {
+        self.total_timeout = Some(total);
+        self
+    }
+
+    /// Compile the code with the given compiler options
+    pub fn with_compiler_options(mut self, options: CompilerOptions) -> Self {
+        self.compiler = Some(options);
+        self
+    }
+
+    /// Run SQL on the given engine, seeding the database from datasets
+    pub fn with_sql_options(mut self, options: SqlOptions) -> Self {
+        self.sql = Some(options);
+        self
+    }
+
+    /// Run the code against a virtual clock
+    pub fn with_clock(mut self, clock: VirtualClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Report provisioning progress of this execution to `reporter`
+    pub fn with_progress(mut self, reporter: ProgressReporter) -> Self {
+        self.progress = Some(reporter);
+        self
+    }
+
+    /// Stream the execution's stdout and stderr to `sink` as they are written
+    pub fn with_output_sink(mut self, sink: OutputSink) -> Self {
+        self.output_sink = Some(sink);
+        self
+    }
+
+    /// Draw this execution's usage from `budget`, shared with the other
+    /// requests it is attached to
+    pub fn with_budget(mut self, budget: ExecutionBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Record the execution's input and output in `transcript` as they
+    /// are exchanged
+    pub fn with_transcript(mut self, transcript: TranscriptRecorder) -> Self {
+        self.transcript = Some(transcript);
+        self
+    }
+
+    /// Report a provisioning step to the attached reporter, if any
+    pub fn report_progress<M: Into<String>>(
+        &self,
+        stage: ProvisioningStage,
+        percent: Option<u8>,
+        message: M,
+    ) {
+        if let Some(reporter) = &self.progress {
+            reporter.report(stage, percent, message);
+        }
+    }
+
+    /// Validate the request before any backend resources are allocated
+    ///
+    /// Rejects empty or oversized code, input and files, file and blob
+    /// paths that escape the workspace or collide, empty workspace archives, zero archive limits,
+    /// output archive subtrees outside the workspace, unknown languages (with a
+    /// suggestion when the name looks like a typo), malformed pinned runtime
+    /// versions, compiler options that are not allowlisted, SQL seeds that
+    /// are not exposed or cannot be loaded, virtual
+    /// environments named for anything but unpinned Python, zero-sized
+    /// limits, malformed environment variables, working directories that
+    /// try to escape the sandbox, exposed paths that are relative or
+    /// ambiguous, git checkouts of local or malformed remotes or over the
+    /// request's files, environment profiles no backend can apply, DNS policies
+    /// that are malformed or set while networking is disabled, and stall
+    /// timeouts that could never fire.
+    ///
+    /// # Returns
+    /// Ok(()) if the request is well-formed, InvalidRequest otherwise
+    pub fn validate(&self) -> CyloResult<()> {
+        if self.code.trim().is_empty() {
+            return Err(CyloError::invalid_request("code", "code is empty"));
+        }
+        if self.code.len() > Self::MAX_CODE_BYTES {
+            return Err(CyloError::invalid_request(
+                "code",
+                format!(
+                    "{} bytes exceeds the {} byte limit",
+                    self.code.len(),
+                    Self::MAX_CODE_BYTES
+                ),
+            ));
+        }
+        if let Some(input) = &self.input
+            && input.len() > Self::MAX_INPUT_BYTES
+        {
+            return Err(CyloError::invalid_request(
+                "input",
+                format!(
+                    "{} bytes exceeds the {} byte limit",
+                    input.len(),
+                    Self::MAX_INPUT_BYTES
+                ),
+            ));
+        }
+        if self.input.is_some() && self.input_stream.is_some() {
+            return Err(CyloError::invalid_request(
+                "input_stream",
+                "cannot be combined with input",
+            ));
+        }
+        for path in self.files.keys() {
+            let invalid =
+                |reason: &str| CyloError::invalid_request("files", format!("'{path}' {reason}"));
+            let relative = relative_inside(path).map_err(|_| invalid("escapes the workspace"))?;
+            if relative.as_os_str().is_empty() {
+                return Err(invalid("names no file"));
+            }
+        }
+        for (path, blob) in &self.blob_files {
+            let invalid = |reason: &str| {
+                CyloError::invalid_request("blob_files", format!("'{path}' {reason}"))
+            };
+            let relative = relative_inside(path).map_err(|_| invalid("escapes the workspace"))?;
+            if relative.as_os_str().is_empty() {
+                return Err(invalid("names no file"));
+            }
+            if self.files.contains_key(path) {
+                return Err(invalid("is also an additional file"));
+            }
+            if !is_valid_digest(&blob.digest) {
+                return Err(invalid("does not reference a sha256 digest"));
+            }
+        }
+        let files_bytes: usize = self.files.values().map(String::len).sum();
+        if files_bytes > Self::MAX_CODE_BYTES {
+            return Err(CyloError::invalid_request(
+                "files",
+                format!("{files_bytes} bytes exceeds the {} byte limit", Self::MAX_CODE_BYTES),
+            ));
+        }
+
+        if language::resolve(&self.language).is_none() {
+            let reason = match language::suggest(&self.language) {
+                Some(suggestion) => format!(
+                    "unknown language '{}', did you mean '{}'?",
+                    self.language, suggestion
+                ),
+                None => format!(
+                    "unknown language '{}', expected one of: {}",
+                    self.language,
+                    language::known_languages().join(", ")
+                ),
+            };
+            return Err(CyloError::invalid_request("language", reason));
+        }
+        if let (name, Some(version)) = language::split_version(&self.language)
+            && !language::is_valid_version(version)
+        {
+            return Err(CyloError::invalid_request(
+                "language",
+                format!("invalid runtime version '{version}' in '{name}@{version}'"),
+            ));
+        }
+
+        if language::resolve(&self.language).is_some_and(|spec| spec.name == "wasm") {
+            wasm_module::module_bytes(&self.code)
+                .map_err(|reason| CyloError::invalid_request("code", reason))?;
+        }
+        if let Some(compiler) = &self.compiler {
+            compiler
+                .validate(&self.language)
+                .map_err(|reason| CyloError::invalid_request("compiler", reason))?;
+        }
+        if let Some(sql) = &self.sql {
+            sql.validate(&self.language, &self.readable_paths)
+                .map_err(|reason| CyloError::invalid_request("sql", reason))?;
+        }
+        if let Some(name) = &self.python_env {
+            let reason = if name.trim().is_empty() {
+                Some("environment name is empty")
+            } else if language::resolve(&self.language).is_none_or(|spec| spec.name != "python") {
+                Some("virtual environments only apply to python")
+            } else if language::split_version(&self.language).1.is_some() {
+                Some("a virtual environment fixes the interpreter; drop the pinned version")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                return Err(CyloError::invalid_request("python_env", reason));
+            }
+        }
+
+        if self.timeout.is_zero() {
+            return Err(CyloError::invalid_request("timeout", "timeout must be non-zero"));
+        }
+        if let Some(memory) = self.limits.max_memory
+            && memory < Self::MIN_MEMORY_BYTES
+        {
+            return Err(CyloError::invalid_request(
+                "limits.max_memory",
+                format!(
+                    "{} bytes is below the {} byte minimum",
+                    memory,
+                    Self::MIN_MEMORY_BYTES
+                ),
+            ));
+        }
+        if self.limits.max_cpu_time == Some(0) {
+            return Err(CyloError::invalid_request(
+                "limits.max_cpu_time",
+                "CPU time limit must be non-zero",
+            ));
+        }
+        if self.core_dump_limit == Some(0) {
+            return Err(CyloError::invalid_request(
+                "core_dump_limit",
+                "core dump size cap must be non-zero",
+            ));
+        }
+        if let Some(idle) = self.stall_timeout
+            && (idle.is_zero() || idle >= self.timeout)
+        {
+            return Err(CyloError::invalid_request(
+                "stall_timeout",
+                "stall timeout must be non-zero and shorter than the timeout",
+            ));
+        }
+        if let Some(total) = self.total_timeout
+            && total < self.timeout
+        {
+            return Err(CyloError::invalid_request(
+                "total_timeout",
+                "total timeout must be at least the timeout",
+            ));
+        }
+        if self.limits.max_processes == Some(0) {
+            return Err(CyloError::invalid_request(
+                "limits.max_processes",
+                "process limit must be non-zero",
+            ));
+        }
+
+        for key in self.env_vars.keys() {
+            if key.is_empty() || key.contains('=') || key.contains('\0') {
+                return Err(CyloError::invalid_request(
+                    "env_vars",
+                    format!("invalid variable name '{}'", key.escape_debug()),
+                ));
+            }
+        }
+
+        if let Some(dir) = &self.working_dir {
+            if dir.contains('\0') {
+                return Err(CyloError::invalid_request(
+                    "working_dir",
+                    "path contains a NUL byte",
+                ));
+            }
+            if Path::new(dir)
+                .components()
+                .any(|c| matches!(c, Component::ParentDir))
+            {
+                return Err(CyloError::invalid_request(
+                    "working_dir",
+                    format!("'{}' escapes the sandbox via '..'", dir),
+                ));
+            }
+        }
+
+        let volume_targets: Vec<PathBuf> =
+            self.volumes.iter().map(|mount| mount.target.clone()).collect();
+        for (field, paths) in [
+            ("readable_paths", self.readable_paths.as_slice()),
+            ("writable_paths", self.writable_paths.as_slice()),
+            ("go_module_cache", self.go_module_cache.as_slice()),
+            ("volumes", volume_targets.as_slice()),
+        ] {
+            for path in paths {
+                let shown = path.display();
+                if path.as_os_str().to_string_lossy().contains('\0') {
+                    return Err(CyloError::invalid_request(field, "path contains a NUL byte"));
+                }
+                if !path.is_absolute() || path.parent().is_none() {
+                    return Err(CyloError::invalid_request(
+                        field,
+                        format!("'{shown}' must be an absolute path below the root"),
+                    ));
+                }
+                if path.components().any(|c| matches!(c, Component::ParentDir)) {
+                    return Err(CyloError::invalid_request(
+                        field,
+                        format!("'{shown}' must not contain '..'"),
+                    ));
+                }
+            }
+        }
+        if let Some(path) = self
+            .writable_paths
+            .iter()
+            .find(|path| self.readable_paths.contains(path))
+        {
+            return Err(CyloError::invalid_request(
+                "writable_paths",
+                format!("'{}' is also listed as readable", path.display()),
+            ));
+        }
+        if let Some((index, path)) = volume_targets.iter().enumerate().find(|(index, path)| {
+            volume_targets[..*index].contains(path)
+                || self.readable_paths.contains(path)
+                || self.writable_paths.contains(path)
+        }) {
+            let name = &self.volumes[index].name;
+            return Err(CyloError::invalid_request(
+                "volumes",
+                format!("'{}' of '{}' is already exposed", path.display(), name),
+            ));
+        }
+
+        if let Some(archive) = &self.workspace_archive
+            && archive.data.is_empty()
+        {
+            return Err(CyloError::invalid_request("workspace_archive", "archive is empty"));
+        }
+        if self.archive_limits.max_bytes == 0 || self.archive_limits.max_entries == 0 {
+            return Err(CyloError::invalid_request(
+                "archive_limits",
+                "archive limits must be non-zero",
+            ));
+        }
+        if let Some(subtree) = self.output_archive.as_ref().and_then(|o| o.subtree.as_ref())
+            && relative_inside(subtree).is_err()
+        {
+            return Err(CyloError::invalid_request(
+                "output_archive",
+                format!("'{}' escapes the workspace", subtree.escape_debug()),
+            ));
+        }
+
+        if let Some(checkout) = &self.git_repo {
+            checkout
+                .validate()
+                .map_err(|reason| CyloError::invalid_request("git_repo", reason))?;
+            if let Some(file) = self
+                .files
+                .keys()
+                .chain(self.blob_files.keys())
+                .find(|file| checkout.contains(file))
+            {
+                return Err(CyloError::invalid_request(
+                    "git_repo",
+                    format!("file '{}' would be overwritten by the checkout", file),
+                ));
+            }
+        }
+
+        self.environment
+            .validate()
+            .map_err(|reason| CyloError::invalid_request("environment", reason))?;
+
+        if let Some(dns) = &self.dns {
+            dns.validate()
+                .map_err(|reason| CyloError::invalid_request("dns", reason))?;
+            if !self.network_allowed() && !dns.is_default() {
+                return Err(CyloError::invalid_request(
+                    "dns",
+                    "a DNS policy has no effect while network access is disabled",
+                ));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Execution result from backend
@@ -109,23 +1150,91 @@ pub struct ExecutionResult {
     /// Exit code from execution (0 = success)
     pub exit_code: i32,
 
-    /// Standard output from execution
+    /// Standard output from execution; of the run alone when
+    /// `compilation` is set
     pub stdout: String,
 
-    /// Standard error from execution
+    /// Standard error from execution; of the run alone when `compilation`
+    /// is set
     pub stderr: String,
 
-    /// Execution duration
+    /// Execution duration, including any compilation
     pub duration: Duration,
 
+    /// Compile phase of a compiled language, kept apart from the run's
+    /// output; a failed compile means the code never ran
+    #[serde(default)]
+    pub compilation: Option<CompilationPhase>,
+
     /// Resource usage statistics
     pub resource_usage: ResourceUsage,
 
     /// Any backend-specific metadata
     pub metadata: HashMap<String, String>,
+
+    /// Verdict of the request's expectations, if any were set
+    #[serde(default)]
+    pub verdict: Option<ExpectationVerdict>,
+
+    /// How the execution ended
+    #[serde(default)]
+    pub outcome: ExecutionOutcome,
+
+    /// Isolation achieved and controls enforced, when the backend ran the code
+    #[serde(default)]
+    pub security: Option<SecurityReport>,
+
+    /// Usage and cost attributed by the executor
+    #[serde(default)]
+    pub cost: Option<ExecutionCost>,
+
+    /// Signal and core dump of an execution that crashed
+    #[serde(default)]
+    pub crash: Option<CrashReport>,
+
+    /// Result sets a SQL execution returned, parsed from its output
+    #[serde(default)]
+    pub result_sets: Option<Vec<ResultSet>>,
+
+    /// Archive of the final workspace, when the request asked for one
+    #[serde(default)]
+    pub workspace_archive: Option<WorkspaceArchive>,
+
+    /// Outputs stored because they were larger than the request's
+    /// `output_blobs` threshold, by field name (`stdout`, `stderr`,
+    /// `workspace_archive`); each such field is left empty
+    #[serde(default)]
+    pub blobs: HashMap<String, Blob>,
+
+    /// JSON and traceback extracted by the request's post-processors, when
+    /// it listed any
+    #[serde(default)]
+    pub processed: Option<ProcessedOutput>,
+
+    /// Exact bytes of stdout and stderr, when either was not valid UTF-8
+    /// and had to be decoded lossily; not serialized
+    #[serde(skip)]
+    pub raw_output: Option<RawOutput>,
+}
+
+/// How an execution ended
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionOutcome {
+    /// Program ran to completion (with any exit code)
+    #[default]
+    Completed,
+    /// Program hit its timeout and was terminated; output and resource
+    /// usage cover everything captured up to that point
+    TimedOutWithPartialOutput,
+    /// Program made no progress for the request's stall timeout and was
+    /// terminated early; output covers everything captured until then
+    Stalled,
 }
 
 impl ExecutionResult {
+    /// Exit code reported for executions terminated on timeout
+    pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
     /// Create a successful execution result
     pub fn success<O: Into<String>>(stdout: O) -> Self {
         Self {
@@ -133,8 +1242,19 @@ impl ExecutionResult {
             stdout: stdout.into(),
             stderr: String::new(),
             duration: Duration::from_millis(0),
+            compilation: None,
             resource_usage: ResourceUsage::default(),
             metadata: HashMap::new(),
+            verdict: None,
+            outcome: ExecutionOutcome::Completed,
+            security: None,
+            cost: None,
+            crash: None,
+            result_sets: None,
+            workspace_archive: None,
+            blobs: HashMap::new(),
+            processed: None,
+            raw_output: None,
         }
     }
 
@@ -145,26 +1265,121 @@ impl ExecutionResult {
             stdout: String::new(),
             stderr: stderr.into(),
             duration: Duration::from_millis(0),
+            compilation: None,
             resource_usage: ResourceUsage::default(),
             metadata: HashMap::new(),
+            verdict: None,
+            outcome: ExecutionOutcome::Completed,
+            security: None,
+            cost: None,
+            crash: None,
+            result_sets: None,
+            workspace_archive: None,
+            blobs: HashMap::new(),
+            processed: None,
+            raw_output: None,
         }
     }
 
+    /// Create a result for an execution terminated on timeout
+    ///
+    /// # Arguments
+    /// * `stdout` - Output captured before termination
+    /// * `stderr` - Error output captured before termination
+    /// * `timeout` - Timeout that was exceeded
+    pub fn timed_out<O: Into<String>, E: Into<String>>(
+        stdout: O,
+        stderr: E,
+        timeout: Duration,
+    ) -> Self {
+        let mut result = Self::failure(Self::TIMEOUT_EXIT_CODE, stderr);
+        result.stdout = stdout.into();
+        result.outcome = ExecutionOutcome::TimedOutWithPartialOutput;
+        result
+            .metadata
+            .insert("timeout_secs".to_string(), timeout.as_secs().to_string());
+        result
+    }
+
+    /// Create a result for an execution the watchdog terminated as stalled
+    ///
+    /// # Arguments
+    /// * `stdout` - Output captured before termination
+    /// * `stderr` - Error output captured before termination
+    /// * `idle` - Stall timeout that was exceeded
+    pub fn stalled<O: Into<String>, E: Into<String>>(stdout: O, stderr: E, idle: Duration) -> Self {
+        let mut result = Self::failure(Self::TIMEOUT_EXIT_CODE, stderr);
+        result.stdout = stdout.into();
+        result.outcome = ExecutionOutcome::Stalled;
+        result
+            .metadata
+            .insert("stalled_after_ms".to_string(), idle.as_millis().to_string());
+        result
+    }
+
+    /// Exact bytes the program wrote to stdout
+    pub fn stdout_bytes(&self) -> &[u8] {
+        self.raw_output
+            .as_ref()
+            .map_or(self.stdout.as_bytes(), |raw| &raw.stdout)
+    }
+
+    /// Exact bytes the program wrote to stderr
+    pub fn stderr_bytes(&self) -> &[u8] {
+        self.raw_output
+            .as_ref()
+            .map_or(self.stderr.as_bytes(), |raw| &raw.stderr)
+    }
+
     /// Check if execution was successful
     pub fn is_success(&self) -> bool {
-        self.exit_code == 0
+        self.exit_code == 0 && self.outcome == ExecutionOutcome::Completed
     }
 
-    /// Get combined output (stdout + stderr)
-    pub fn combined_output(&self) -> String {
-        if self.stderr.is_empty() {
-            self.stdout.clone()
-        } else if self.stdout.is_empty() {
-            self.stderr.clone()
-        } else {
-            format!("{}\n{}", self.stdout, self.stderr)
+    /// Check whether the execution was terminated on timeout
+    pub fn is_timed_out(&self) -> bool {
+        self.outcome == ExecutionOutcome::TimedOutWithPartialOutput
+    }
+
+    /// Check whether the watchdog terminated the execution as stalled
+    pub fn is_stalled(&self) -> bool {
+        self.outcome == ExecutionOutcome::Stalled
+    }
+
+    /// Check whether the execution provably ran with at least `required` isolation
+    ///
+    /// Results without a security report never satisfy a requirement.
+    pub fn satisfies_isolation(&self, required: IsolationLevel) -> bool {
+        self.security
+            .as_ref()
+            .is_some_and(|security| security.isolation >= required)
+    }
+
+    /// Check whether the request's expectations were met
+    ///
+    /// Results without a verdict fall back to the exit code.
+    pub fn expectations_met(&self) -> bool {
+        match &self.verdict {
+            Some(verdict) => verdict.passed,
+            None => self.is_success(),
         }
     }
+
+    /// Get combined output: compiler output, then stdout and stderr
+    pub fn combined_output(&self) -> String {
+        let compilation = self.compilation.iter().flat_map(|phase| [&phase.stdout, &phase.stderr]);
+        let streams: Vec<&str> = compilation
+            .chain([&self.stdout, &self.stderr])
+            .map(String::as_str)
+            .filter(|stream| !stream.is_empty())
+            .collect();
+        streams.join("\n")
+    }
+
+    /// Whether the code failed to compile, and so never ran
+    pub fn compile_failed(&self) -> bool {
+        self.compilation.as_ref().is_some_and(|phase| !phase.succeeded())
+    }
 }
 
 /// Resource usage statistics
@@ -194,6 +1409,25 @@ pub struct ResourceUsage {
     pub network_bytes_received: u64,
 }
 
+/// How thoroughly a health check probes a backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HealthCheckLevel {
+    /// Cheap presence checks: binaries, sockets, and directories exist
+    Liveness,
+    /// Full check that runs a test execution through the backend
+    Readiness,
+}
+
+impl HealthCheckLevel {
+    /// Whether a result at this level also answers a check at `other`
+    ///
+    /// A readiness check includes every liveness check, so a readiness
+    /// result answers both.
+    pub fn covers(self, other: HealthCheckLevel) -> bool {
+        self == other || self == HealthCheckLevel::Readiness
+    }
+}
+
 /// Backend health status
 ///
 /// Indicates the current health and availability of a backend.
@@ -263,6 +1497,210 @@ mod tests {
         assert_eq!(request.working_dir, Some("/tmp".to_string()));
     }
 
+    #[test]
+    fn isolation_report_is_checked_against_requirement() {
+        let mut result = ExecutionResult::success("ok");
+        assert!(!result.satisfies_isolation(IsolationLevel::Process));
+
+        result.security = Some(SecurityReport {
+            isolation: IsolationLevel::Container,
+            network_disabled: true,
+            filesystem_read_only: false,
+            memory_limit_bytes: Some(64 * 1024 * 1024),
+            ipc_isolated: true,
+            ui_restricted: true,
+        });
+        assert!(result.satisfies_isolation(IsolationLevel::Namespace));
+        assert!(result.satisfies_isolation(IsolationLevel::Container));
+        assert!(!result.satisfies_isolation(IsolationLevel::MicroVM));
+        assert_eq!(IsolationLevel::of_backend("FireCracker"), Some(IsolationLevel::MicroVM));
+    }
+
+    #[test]
+    fn builtin_backends_describe_capabilities() {
+        let builtin = [
+            "Apple",
+            "LandLock",
+            "FireCracker",
+            "Docker",
+            "Wasm",
+            "K8sJob",
+            "WindowsJob",
+            "SweetMcpPlugin",
+        ];
+        for backend in builtin {
+            let capabilities = BackendCapabilities::of_backend(backend).unwrap();
+            assert_eq!(Some(capabilities.isolation), IsolationLevel::of_backend(backend));
+        }
+        assert!(BackendCapabilities::of_backend("Kata").is_none());
+        assert!(BackendCapabilities::of_backend("Docker").unwrap().network_control);
+
+        let vm = BackendCapabilities::of_backend("FireCracker").unwrap();
+        assert!(vm.network_control);
+        assert!(vm.fits_memory(Some(256 * 1024 * 1024)));
+        assert!(!vm.fits_memory(Some(1024 * 1024 * 1024)));
+        assert!(BackendCapabilities::of_backend("LandLock").unwrap().fits_memory(Some(u64::MAX)));
+    }
+
+    #[test]
+    fn validate_accepts_well_formed_request() {
+        let request = ExecutionRequest::new("print('hi')", "Python3").with_working_dir("src/app");
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_malformed_requests() {
+        let field = |request: ExecutionRequest| match request.validate() {
+            Err(CyloError::InvalidRequest { field, .. }) => field,
+            other => panic!("expected InvalidRequest, got {other:?}"),
+        };
+
+        assert_eq!(field(ExecutionRequest::new("  ", "python")), "code");
+        assert_eq!(field(ExecutionRequest::new("x", "cobol")), "language");
+        assert_eq!(field(ExecutionRequest::new("x", "python@")), "language");
+        assert!(ExecutionRequest::new("x", "python@3.12").validate().is_ok());
+        assert_eq!(
+            field(ExecutionRequest::new("x", "python").with_working_dir("../etc")),
+            "working_dir"
+        );
+        assert_eq!(
+            field(ExecutionRequest::new("x", "python").with_limits(ResourceLimits {
+                max_memory: Some(0),
+                ..ResourceLimits::default()
+            })),
+            "limits.max_memory"
+        );
+        assert_eq!(
+            field(ExecutionRequest::new("x", "python").with_readable_path("data")),
+            "readable_paths"
+        );
+        assert_eq!(
+            field(ExecutionRequest::new("x", "python").with_writable_path("/")),
+            "writable_paths"
+        );
+        assert_eq!(
+            field(ExecutionRequest::new("x", "go").with_go_module_cache("go/pkg/mod")),
+            "go_module_cache"
+        );
+        assert_eq!(
+            field(ExecutionRequest::new("x", "node").with_python_env("ml")),
+            "python_env"
+        );
+        assert_eq!(
+            field(ExecutionRequest::new("x", "python@3.12").with_python_env("ml")),
+            "python_env"
+        );
+        assert!(ExecutionRequest::new("x", "python3").with_python_env("ml").validate().is_ok());
+        let seeded = crate::backends::SqlOptions::default().with_seed("/data/sales.csv");
+        assert_eq!(
+            field(ExecutionRequest::new("x", "sql").with_sql_options(seeded.clone())),
+            "sql"
+        );
+        let exposed = ExecutionRequest::new("x", "sql").with_readable_path("/data");
+        assert!(exposed.with_sql_options(seeded).validate().is_ok());
+        assert_eq!(field(ExecutionRequest::new("print(1)", "wasm")), "code");
+        assert_eq!(
+            field(ExecutionRequest::new("x", "bash").with_git_repo("file:///srv/repo", "main")),
+            "git_repo"
+        );
+        let checkout = ExecutionRequest::new("x", "bash")
+            .with_git_repo("https://github.com/cyrup-ai/cylo.git", "main");
+        assert!(checkout.clone().validate().is_ok());
+        assert_eq!(field(checkout.with_file("repo/Cargo.toml", "")), "git_repo");
+        let archive = crate::backends::WorkspaceArchive::from_bytes(Vec::new());
+        assert_eq!(
+            field(ExecutionRequest::new("x", "bash").with_workspace_archive(archive)),
+            "workspace_archive"
+        );
+        let output = crate::backends::OutputArchive::new(crate::backends::ArchiveFormat::Zip)
+            .with_subtree("..");
+        assert_eq!(
+            field(ExecutionRequest::new("x", "bash").with_output_archive(output)),
+            "output_archive"
+        );
+        let volume = crate::backends::Volume {
+            name: "repo".to_string(),
+            path: PathBuf::from("/volumes/repo/data"),
+            quota_bytes: None,
+            created_at: SystemTime::UNIX_EPOCH,
+            last_used_at: None,
+        };
+        let attached = ExecutionRequest::new("x", "python").with_volume(&volume, "/repo");
+        assert!(attached.clone().validate().is_ok());
+        assert_eq!(field(attached.clone().with_volume(&volume, "/repo")), "volumes");
+        assert_eq!(field(attached.with_writable_path("/repo")), "volumes");
+        assert_eq!(
+            field(ExecutionRequest::new("x", "python").with_volume(&volume, "repo")),
+            "volumes"
+        );
+        assert!(ExecutionRequest::new("AGFzbQEAAAA=", "wasi").validate().is_ok());
+        assert_eq!(
+            field(ExecutionRequest::new("x", "python").with_file("../escape.py", "")),
+            "files"
+        );
+        assert_eq!(field(ExecutionRequest::new("x", "python").with_file("/", "")), "files");
+        let nested = ExecutionRequest::new("x", "python").with_file("/lib/util.py", "");
+        assert!(nested.validate().is_ok());
+        let blob = crate::backends::Blob {
+            digest: format!("sha256:{}", "0".repeat(64)),
+            path: PathBuf::from("/blobs/sha256/0"),
+            size: 0,
+        };
+        let blob_request = ExecutionRequest::new("x", "python").with_blob_file("data.bin", &blob);
+        assert!(blob_request.clone().validate().is_ok());
+        assert_eq!(field(blob_request.with_file("data.bin", "")), "blob_files");
+        let data = std::env::temp_dir().join("data");
+        assert_eq!(
+            field(
+                ExecutionRequest::new("x", "python")
+                    .with_readable_path(&data)
+                    .with_writable_path(&data)
+            ),
+            "writable_paths"
+        );
+        assert_eq!(
+            field(
+                ExecutionRequest::new("x", "python")
+                    .with_dns(DnsPolicy::default().with_blocked_domain("not a domain"))
+            ),
+            "dns"
+        );
+        let offline = ResourceLimits {
+            max_network_bandwidth: Some(0),
+            ..ResourceLimits::default()
+        };
+        let ip = "10.0.0.1".parse().expect("ip");
+        assert_eq!(
+            field(
+                ExecutionRequest::new("x", "python")
+                    .with_limits(offline)
+                    .with_dns(DnsPolicy::only([("api.internal", ip)]))
+            ),
+            "dns"
+        );
+        assert_eq!(
+            field(
+                ExecutionRequest::new("x", "python")
+                    .with_timeout(Duration::from_secs(10))
+                    .with_stall_timeout(Duration::from_secs(10))
+            ),
+            "stall_timeout"
+        );
+        let link_arg = CompilerOptions::default().with_flag("-Clink-arg=-nostartfiles");
+        assert_eq!(
+            field(ExecutionRequest::new("x", "rust").with_compiler_options(link_arg)),
+            "compiler"
+        );
+    }
+
+    #[test]
+    fn validate_suggests_similar_language() {
+        match ExecutionRequest::new("x", "pyhton").validate() {
+            Err(CyloError::InvalidRequest { reason, .. }) => assert!(reason.contains("python")),
+            other => panic!("expected InvalidRequest, got {other:?}"),
+        }
+    }
+
     #[test]
     fn execution_result_success() {
         let result = ExecutionResult::success("Hello, World!");
@@ -279,6 +1717,19 @@ mod tests {
         assert_eq!(result.stderr, "Error occurred");
     }
 
+    #[test]
+    fn execution_result_timed_out() {
+        let result = ExecutionResult::timed_out("partial", "", Duration::from_secs(5));
+        assert!(!result.is_success());
+        assert!(result.is_timed_out());
+        assert_eq!(result.exit_code, ExecutionResult::TIMEOUT_EXIT_CODE);
+        assert_eq!(result.stdout, "partial");
+        assert_eq!(result.metadata.get("timeout_secs"), Some(&"5".to_string()));
+
+        let stalled = ExecutionResult::stalled("", "", Duration::from_millis(1500));
+        assert!(stalled.is_stalled() && !stalled.is_timed_out() && !stalled.is_success());
+    }
+
     #[test]
     fn health_status_creation() {
         let healthy = HealthStatus::healthy("All systems operational")
@@ -0,0 +1,738 @@
+// ============================================================================
+// File: packages/cylo/src/backends/volumes.rs
+// ----------------------------------------------------------------------------
+// Named persistent volumes shared across executions.
+//
+// A volume is a directory kept in a store on disk (or inside the ramdisk)
+// that outlives the executions it is attached to, so a session can build up
+// state over many runs, e.g. an agent growing a repository across tool
+// calls. Requests attach volumes read-write at a path of their choosing;
+// the executor refuses to attach a volume that is over its quota, stops an
+// execution that grows one past it, and reports the usage of every
+// attached volume after the run.
+//
+// Snapshots copy a volume's contents aside so an agent can branch from a
+// known-good state and roll a failed experiment back. A volume is only
+// restored while no running execution has it attached. Sticky sessions
+// snapshot their workspace volume together with the sandbox's own
+// filesystem (see `sandbox_state`).
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::RamdiskConfig;
+use crate::execution_env::{CyloError, CyloResult};
+use crate::reaper::global_reaper;
+
+/// Result metadata key with the bytes used by each attached volume, as a
+/// JSON object keyed by volume name
+pub const VOLUME_USAGE_METADATA: &str = "volume_usage";
+
+/// Volume details kept next to its data
+const METADATA_FILE: &str = "volume.json";
+
+/// Directory holding the volume's contents
+const DATA_DIR: &str = "data";
+
+/// Directory holding a volume's snapshots, one subdirectory each
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// Snapshot details kept next to its copy of the contents
+const SNAPSHOT_FILE: &str = "snapshot.json";
+
+/// Directory holding the sandbox state of a session workspace
+const SANDBOX_DIR: &str = "sandbox";
+
+/// How often a running execution's volumes are measured against their
+/// quotas
+const QUOTA_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A named volume in a store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Volume {
+    /// Name the volume is attached by
+    pub name: String,
+    /// Directory holding its contents
+    pub path: PathBuf,
+    /// Most bytes it may hold; attaching it beyond that is refused, and
+    /// an execution growing it beyond that is stopped
+    pub quota_bytes: Option<u64>,
+    /// When it was created
+    pub created_at: SystemTime,
+    /// When it was last attached to an execution
+    pub last_used_at: Option<SystemTime>,
+}
+
+impl Volume {
+    /// Bytes currently stored in the volume
+    pub fn usage(&self) -> u64 {
+        directory_size(&self.path)
+    }
+
+    /// Whether the volume holds more than its quota
+    pub fn over_quota(&self) -> bool {
+        self.quota_bytes.is_some_and(|quota| self.usage() > quota)
+    }
+
+    /// Load the volume whose contents live at `path`
+    fn at(path: &Path) -> CyloResult<Self> {
+        let root = path
+            .parent()
+            .ok_or_else(|| CyloError::validation(format!("{} is not a volume", path.display())))?;
+        let text = fs::read_to_string(root.join(METADATA_FILE)).map_err(|e| {
+            CyloError::validation(format!("{} is not a volume: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&text).map_err(|e| {
+            CyloError::internal(format!("Corrupt volume metadata in {}: {e}", root.display()))
+        })
+    }
+
+    fn save(&self) -> CyloResult<()> {
+        let root = self.path.parent().unwrap_or(&self.path);
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|e| CyloError::internal(format!("Failed to encode volume metadata: {e}")))?;
+        fs::write(root.join(METADATA_FILE), text).map_err(|e| {
+            CyloError::internal(format!("Failed to write volume metadata: {e}"))
+        })
+    }
+}
+
+/// Point-in-time copy of a volume's contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolumeSnapshot {
+    /// ID the snapshot is restored by
+    pub id: String,
+    /// Name of the volume it was taken of
+    pub volume: String,
+    /// When it was taken
+    pub created_at: SystemTime,
+    /// Bytes it holds
+    pub size_bytes: u64,
+}
+
+/// A volume attached to an execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolumeMount {
+    /// Volume name
+    pub name: String,
+    /// Host directory holding the volume's contents
+    pub source: PathBuf,
+    /// Absolute path the sandbox sees it at
+    pub target: PathBuf,
+}
+
+impl VolumeMount {
+    /// The attached volume, as currently stored
+    pub fn volume(&self) -> CyloResult<Volume> {
+        Volume::at(&self.source)
+    }
+}
+
+/// Store of named volumes below one directory
+#[derive(Debug, Clone)]
+pub struct VolumeStore {
+    root: PathBuf,
+}
+
+impl VolumeStore {
+    /// Open a store rooted at `root`; it is created with the first volume
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Store inside a ramdisk, whose volumes vanish with it
+    pub fn in_ramdisk(config: &RamdiskConfig) -> Self {
+        Self::new(config.mount_point.join("volumes"))
+    }
+
+    /// Default store location: `$XDG_DATA_HOME/cylo/volumes`, falling back
+    /// to `~/.local/share/cylo/volumes`
+    pub fn default_root() -> PathBuf {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+            .unwrap_or_else(std::env::temp_dir)
+            .join("cylo")
+            .join("volumes")
+    }
+
+    /// Store root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Create an empty volume
+    ///
+    /// # Arguments
+    /// * `name` - Volume name: letters, digits, `-`, `_` and `.`, not
+    ///   starting with `.`
+    /// * `quota_bytes` - Most bytes it may hold, if limited
+    ///
+    /// # Returns
+    /// The new volume, or an error if the name is invalid or taken
+    pub fn create(&self, name: &str, quota_bytes: Option<u64>) -> CyloResult<Volume> {
+        let root = self.volume_root(name)?;
+        if root.exists() {
+            return Err(CyloError::validation(format!("Volume '{name}' already exists")));
+        }
+        let path = root.join(DATA_DIR);
+        fs::create_dir_all(&path)
+            .map_err(|e| CyloError::internal(format!("Failed to create volume '{name}': {e}")))?;
+
+        let volume = Volume {
+            name: name.to_string(),
+            path,
+            quota_bytes,
+            created_at: SystemTime::now(),
+            last_used_at: None,
+        };
+        if let Err(e) = volume.save() {
+            let _ = fs::remove_dir_all(&root);
+            return Err(e);
+        }
+        Ok(volume)
+    }
+
+    /// Look up a volume by name
+    pub fn get(&self, name: &str) -> CyloResult<Option<Volume>> {
+        let path = self.volume_root(name)?.join(DATA_DIR);
+        if !path.is_dir() {
+            return Ok(None);
+        }
+        Volume::at(&path).map(Some)
+    }
+
+    /// Every volume in the store, by name
+    pub fn list(&self) -> CyloResult<Vec<Volume>> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(CyloError::internal(format!("Failed to list volumes: {e}")));
+            }
+        };
+
+        let mut volumes: Vec<Volume> = entries
+            .flatten()
+            .filter_map(|entry| Volume::at(&entry.path().join(DATA_DIR)).ok())
+            .collect();
+        volumes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(volumes)
+    }
+
+    /// Change a volume's quota
+    ///
+    /// Lowering it below the current usage is allowed; the volume cannot
+    /// be attached again until enough is deleted from it.
+    pub fn set_quota(&self, name: &str, quota_bytes: Option<u64>) -> CyloResult<Volume> {
+        let mut volume = self.require(name)?;
+        volume.quota_bytes = quota_bytes;
+        volume.save()?;
+        Ok(volume)
+    }
+
+    /// Delete a volume and its contents
+    ///
+    /// # Returns
+    /// false if no volume has the name
+    pub fn remove(&self, name: &str) -> CyloResult<bool> {
+        let root = self.volume_root(name)?;
+        if !root.join(METADATA_FILE).is_file() {
+            return Ok(false);
+        }
+        fs::remove_dir_all(&root)
+            .map_err(|e| CyloError::internal(format!("Failed to remove volume '{name}': {e}")))?;
+        Ok(true)
+    }
+
+    /// Delete volumes not attached to any execution for `max_idle`
+    ///
+    /// Volumes never attached count from their creation.
+    ///
+    /// # Returns
+    /// Names of the removed volumes
+    pub fn prune(&self, max_idle: Duration) -> CyloResult<Vec<String>> {
+        let now = SystemTime::now();
+        let mut removed = Vec::new();
+        for volume in self.list()? {
+            let used = volume.last_used_at.unwrap_or(volume.created_at);
+            if now.duration_since(used).is_ok_and(|idle| idle > max_idle)
+                && self.remove(&volume.name)?
+            {
+                removed.push(volume.name);
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Copy a volume's current contents into a new snapshot
+    ///
+    /// Snapshots do not count towards the volume's quota and are removed
+    /// with it.
+    pub fn snapshot(&self, name: &str) -> CyloResult<VolumeSnapshot> {
+        let volume = self.require(name)?;
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let root = self.volume_root(name)?.join(SNAPSHOTS_DIR).join(&id);
+        let failed = |e: std::io::Error| {
+            let _ = fs::remove_dir_all(&root);
+            CyloError::internal(format!("Failed to snapshot volume '{name}': {e}"))
+        };
+        copy_tree(&volume.path, &root.join(DATA_DIR)).map_err(failed)?;
+
+        let snapshot = VolumeSnapshot {
+            id,
+            volume: volume.name,
+            created_at: SystemTime::now(),
+            size_bytes: directory_size(&root.join(DATA_DIR)),
+        };
+        let text = serde_json::to_string_pretty(&snapshot).map_err(|e| {
+            CyloError::internal(format!("Failed to encode snapshot metadata: {e}"))
+        })?;
+        fs::write(root.join(SNAPSHOT_FILE), text).map_err(failed)?;
+        Ok(snapshot)
+    }
+
+    /// Snapshots of a volume, oldest first
+    pub fn snapshots(&self, name: &str) -> CyloResult<Vec<VolumeSnapshot>> {
+        self.require(name)?;
+        let entries = match fs::read_dir(self.volume_root(name)?.join(SNAPSHOTS_DIR)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(CyloError::internal(format!("Failed to list snapshots: {e}")));
+            }
+        };
+
+        let mut snapshots: Vec<VolumeSnapshot> = entries
+            .flatten()
+            .filter_map(|entry| fs::read_to_string(entry.path().join(SNAPSHOT_FILE)).ok())
+            .filter_map(|text| serde_json::from_str(&text).ok())
+            .collect();
+        snapshots.sort_by_key(|snapshot| snapshot.created_at);
+        Ok(snapshots)
+    }
+
+    /// Replace a volume's contents with those of one of its snapshots
+    ///
+    /// The snapshot is kept, so the volume can be rolled back to it again.
+    ///
+    /// # Returns
+    /// The restored volume, or a validation error while a running
+    /// execution has it attached
+    pub fn restore(&self, name: &str, snapshot_id: &str) -> CyloResult<Volume> {
+        let mut staged = self.stage_restore(name, snapshot_id)?;
+        staged.swap()?;
+        Ok(staged.finish())
+    }
+
+    /// Copy one of a volume's snapshots next to its contents, ready to be
+    /// swapped in
+    ///
+    /// No execution attaches the volume until the returned restore is
+    /// dropped, so other state can be restored along with it and the swap
+    /// undone if that fails.
+    pub(crate) fn stage_restore(
+        &self,
+        name: &str,
+        snapshot_id: &str,
+    ) -> CyloResult<StagedRestore> {
+        let volume = self.require(name)?;
+        // Held until the contents are swapped, so no execution attaches
+        // the volume mid-restore
+        let attached = attached().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if attached.contains_key(&volume.path) {
+            return Err(CyloError::validation(format!(
+                "Volume '{name}' is attached to a running execution; restore it once that \
+                 execution has finished"
+            )));
+        }
+        let snapshot = self.snapshot_root(name, snapshot_id)?;
+        if !snapshot.join(SNAPSHOT_FILE).is_file() {
+            return Err(CyloError::validation(format!(
+                "Volume '{name}' has no snapshot '{snapshot_id}'"
+            )));
+        }
+
+        // The copy is made next to the contents and swapped in by renames,
+        // so a failed copy leaves the volume as it was
+        let root = self.volume_root(name)?;
+        let staged = StagedRestore {
+            volume,
+            staged: root.join(format!("{DATA_DIR}.restore")),
+            replaced: root.join(format!("{DATA_DIR}.old")),
+            swapped: false,
+            _attached: attached,
+        };
+        let _ = fs::remove_dir_all(&staged.staged);
+        let _ = fs::remove_dir_all(&staged.replaced);
+        copy_tree(&snapshot.join(DATA_DIR), &staged.staged)
+            .map_err(|e| CyloError::internal(format!("Failed to restore volume '{name}': {e}")))?;
+        Ok(staged)
+    }
+
+    /// Delete one of a volume's snapshots
+    ///
+    /// # Returns
+    /// false if the volume has no snapshot with the ID
+    pub fn remove_snapshot(&self, name: &str, snapshot_id: &str) -> CyloResult<bool> {
+        let snapshot = self.snapshot_root(name, snapshot_id)?;
+        if !snapshot.join(SNAPSHOT_FILE).is_file() {
+            return Ok(false);
+        }
+        fs::remove_dir_all(&snapshot).map_err(|e| {
+            CyloError::internal(format!("Failed to remove snapshot '{snapshot_id}': {e}"))
+        })?;
+        Ok(true)
+    }
+
+    /// Directory kept with a volume, and removed with it, for the sandbox
+    /// state of the session the volume is the workspace of
+    pub(crate) fn sandbox_dir(&self, name: &str) -> CyloResult<PathBuf> {
+        Ok(self.volume_root(name)?.join(SANDBOX_DIR))
+    }
+
+    fn snapshot_root(&self, name: &str, snapshot_id: &str) -> CyloResult<PathBuf> {
+        if !is_valid_name(snapshot_id) {
+            return Err(CyloError::invalid_request(
+                "volumes",
+                format!("'{snapshot_id}' is not a valid snapshot ID"),
+            ));
+        }
+        Ok(self.volume_root(name)?.join(SNAPSHOTS_DIR).join(snapshot_id))
+    }
+
+    fn require(&self, name: &str) -> CyloResult<Volume> {
+        self.get(name)?
+            .ok_or_else(|| CyloError::validation(format!("Volume '{name}' does not exist")))
+    }
+
+    fn volume_root(&self, name: &str) -> CyloResult<PathBuf> {
+        if !is_valid_name(name) {
+            return Err(CyloError::invalid_request(
+                "volumes",
+                format!("'{name}' is not a valid volume name"),
+            ));
+        }
+        Ok(self.root.join(name))
+    }
+}
+
+/// Volumes attached to running executions, by contents directory, with
+/// the number of executions attaching each
+fn attached() -> &'static Mutex<HashMap<PathBuf, usize>> {
+    static ATTACHED: OnceLock<Mutex<HashMap<PathBuf, usize>>> = OnceLock::new();
+    ATTACHED.get_or_init(Mutex::default)
+}
+
+/// Snapshot contents copied next to a volume's, not yet swapped in
+///
+/// Dropping it removes whichever of the copy and the replaced contents is
+/// left over, and lets executions attach the volume again.
+pub(crate) struct StagedRestore {
+    volume: Volume,
+    staged: PathBuf,
+    replaced: PathBuf,
+    swapped: bool,
+    _attached: MutexGuard<'static, HashMap<PathBuf, usize>>,
+}
+
+impl StagedRestore {
+    /// Swap the copy in, keeping the replaced contents until the restore
+    /// is finished or undone
+    pub(crate) fn swap(&mut self) -> CyloResult<()> {
+        let failed = |e: std::io::Error| {
+            CyloError::internal(format!("Failed to restore volume '{}': {e}", self.volume.name))
+        };
+        fs::rename(&self.volume.path, &self.replaced).map_err(failed)?;
+        if let Err(e) = fs::rename(&self.staged, &self.volume.path) {
+            let _ = fs::rename(&self.replaced, &self.volume.path);
+            return Err(failed(e));
+        }
+        self.swapped = true;
+        Ok(())
+    }
+
+    /// Put the replaced contents back after a swap
+    pub(crate) fn undo(&mut self) {
+        if !self.swapped || fs::rename(&self.volume.path, &self.staged).is_err() {
+            return;
+        }
+        if fs::rename(&self.replaced, &self.volume.path).is_ok() {
+            self.swapped = false;
+        } else {
+            let _ = fs::rename(&self.staged, &self.volume.path);
+        }
+    }
+
+    /// Keep the restored contents
+    pub(crate) fn finish(self) -> Volume {
+        self.volume.clone()
+    }
+}
+
+impl Drop for StagedRestore {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.staged);
+        let _ = fs::remove_dir_all(&self.replaced);
+    }
+}
+
+/// Volumes an admitted execution has attached; dropping it, once the
+/// execution is over, detaches them
+#[derive(Debug)]
+pub(crate) struct Attachment(Vec<PathBuf>);
+
+impl Drop for Attachment {
+    fn drop(&mut self) {
+        let mut attached = attached().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for path in &self.0 {
+            if let Some(count) = attached.get_mut(path) {
+                *count -= 1;
+                if *count == 0 {
+                    attached.remove(path);
+                }
+            }
+        }
+    }
+}
+
+/// Check the volumes a request attaches before it runs
+///
+/// Records the attachment time of each volume.
+///
+/// # Returns
+/// The attachment, to be held until the execution is over, or
+/// ResourceLimitExceeded for the first volume over its quota
+pub(crate) fn admit(mounts: &[VolumeMount]) -> CyloResult<Attachment> {
+    check_quotas(mounts)?;
+    for mount in mounts {
+        let mut volume = mount.volume()?;
+        volume.last_used_at = Some(SystemTime::now());
+        volume.save()?;
+    }
+
+    let paths: Vec<PathBuf> = mounts.iter().map(|mount| mount.source.clone()).collect();
+    let mut attached = attached().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    for path in &paths {
+        *attached.entry(path.clone()).or_default() += 1;
+    }
+    Ok(Attachment(paths))
+}
+
+/// Check the volumes a request attaches against their quotas without
+/// recording anything
+///
+/// # Returns
+/// ResourceLimitExceeded for the first volume over its quota
+pub(crate) fn check_quotas(mounts: &[VolumeMount]) -> CyloResult<()> {
+    for mount in mounts {
+        let volume = mount.volume()?;
+        if let Some(quota) = volume.quota_bytes
+            && volume.usage() > quota
+        {
+            return Err(CyloError::ResourceLimitExceeded {
+                backend: "executor",
+                resource: format!("volume '{}'", volume.name),
+                limit: format!("{quota} bytes"),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Run an execution, stopping it once it grows an attached volume past its
+/// quota
+///
+/// The volumes are measured every `QUOTA_POLL_INTERVAL`, so a fast writer
+/// can overshoot the quota by what it writes in between.
+///
+/// # Returns
+/// The execution's outcome, or ResourceLimitExceeded once a volume is over
+/// its quota, after the execution's processes have been killed
+pub(crate) async fn within_quotas<T>(
+    mounts: &[VolumeMount],
+    execution_id: &str,
+    execution: impl Future<Output = CyloResult<T>>,
+) -> CyloResult<T> {
+    let mut quotas = Vec::new();
+    for mount in mounts {
+        if let Some(quota) = mount.volume()?.quota_bytes {
+            quotas.push((mount, quota));
+        }
+    }
+    if quotas.is_empty() {
+        return execution.await;
+    }
+
+    tokio::pin!(execution);
+    let mut poll = tokio::time::interval(QUOTA_POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            output = &mut execution => return output,
+            _ = poll.tick() => {
+                let over = quotas
+                    .iter()
+                    .find(|(mount, quota)| directory_size(&mount.source) > *quota);
+                if let Some((mount, quota)) = over {
+                    global_reaper().kill_execution(execution_id);
+                    return Err(CyloError::ResourceLimitExceeded {
+                        backend: "executor",
+                        resource: format!("volume '{}'", mount.name),
+                        limit: format!("{quota} bytes"),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Bytes used by each attached volume, as stored in `VOLUME_USAGE_METADATA`
+pub(crate) fn usage_metadata(mounts: &[VolumeMount]) -> String {
+    let usage: serde_json::Map<String, serde_json::Value> = mounts
+        .iter()
+        .map(|mount| (mount.name.clone(), directory_size(&mount.source).into()))
+        .collect();
+    serde_json::Value::Object(usage).to_string()
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 128
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Copy a directory tree, recreating symlinks rather than following them
+fn copy_tree(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let destination = target.join(entry.file_name());
+        let kind = entry.file_type()?;
+        if kind.is_dir() {
+            copy_tree(&entry.path(), &destination)?;
+        } else if kind.is_file() {
+            fs::copy(entry.path(), &destination)?;
+        } else if kind.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// Total size of the regular files below a directory, not following links
+pub(crate) fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => directory_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volumes_persist_and_enforce_quota() {
+        let root = std::env::temp_dir().join(format!("cylo_volumes_{}", uuid::Uuid::new_v4()));
+        let store = VolumeStore::new(&root);
+        assert!(store.create("../escape", None).is_err());
+
+        let volume = store.create("repo", Some(4)).unwrap();
+        assert!(store.create("repo", None).is_err());
+        let mount = VolumeMount {
+            name: volume.name.clone(),
+            source: volume.path.clone(),
+            target: PathBuf::from("/volumes/repo"),
+        };
+        assert!(admit(std::slice::from_ref(&mount)).is_ok());
+        assert!(store.get("repo").unwrap().unwrap().last_used_at.is_some());
+
+        fs::write(volume.path.join("notes.txt"), "too long").unwrap();
+        assert_eq!(usage_metadata(std::slice::from_ref(&mount)), r#"{"repo":8}"#);
+        assert!(matches!(
+            admit(std::slice::from_ref(&mount)),
+            Err(CyloError::ResourceLimitExceeded { .. })
+        ));
+        store.set_quota("repo", None).unwrap();
+        assert!(admit(std::slice::from_ref(&mount)).is_ok());
+
+        // Roll back to a snapshot after a failed experiment, once nothing
+        // has the volume attached
+        let snapshot = store.snapshot("repo").unwrap();
+        assert_eq!(snapshot.size_bytes, 8);
+        fs::write(volume.path.join("notes.txt"), "broken").unwrap();
+        fs::create_dir(volume.path.join("scratch")).unwrap();
+        let running = admit(std::slice::from_ref(&mount)).unwrap();
+        let again = admit(std::slice::from_ref(&mount)).unwrap();
+        assert!(matches!(store.restore("repo", &snapshot.id), Err(CyloError::Validation { .. })));
+        drop(running);
+        assert!(store.restore("repo", &snapshot.id).is_err());
+        drop(again);
+        // An undone restore leaves the contents as they were
+        let mut staged = store.stage_restore("repo", &snapshot.id).unwrap();
+        staged.swap().unwrap();
+        staged.undo();
+        drop(staged);
+        assert!(volume.path.join("scratch").exists());
+        assert!(!volume.path.with_extension("old").exists());
+        store.restore("repo", &snapshot.id).unwrap();
+        assert_eq!(fs::read_to_string(volume.path.join("notes.txt")).unwrap(), "too long");
+        assert!(!volume.path.join("scratch").exists());
+        assert_eq!(store.snapshots("repo").unwrap(), [snapshot.clone()]);
+        assert!(store.restore("repo", "missing").is_err());
+        assert!(store.restore("repo", "../data").is_err());
+        assert!(store.remove_snapshot("repo", &snapshot.id).unwrap());
+        assert!(store.snapshots("repo").unwrap().is_empty());
+
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert_eq!(store.prune(Duration::from_secs(3600)).unwrap(), Vec::<String>::new());
+        assert!(store.remove("repo").unwrap());
+        assert!(store.get("repo").unwrap().is_none());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn executions_stop_once_they_outgrow_a_volume() {
+        let root = std::env::temp_dir().join(format!("cylo_volumes_{}", uuid::Uuid::new_v4()));
+        let store = VolumeStore::new(&root);
+        let volume = store.create("cache", Some(4)).unwrap();
+        let mount = VolumeMount {
+            name: volume.name.clone(),
+            source: volume.path.clone(),
+            target: PathBuf::from("/volumes/cache"),
+        };
+        let mounts = std::slice::from_ref(&mount);
+
+        let quick = within_quotas(mounts, "exec-within", async { Ok(1) }).await;
+        assert_eq!(quick.unwrap(), 1);
+
+        let path = volume.path.clone();
+        let runaway = within_quotas(mounts, "exec-runaway", async move {
+            fs::write(path.join("blob"), "far too long").unwrap();
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok(())
+        });
+        assert!(matches!(runaway.await, Err(CyloError::ResourceLimitExceeded { .. })));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,396 @@
+// ============================================================================
+// File: packages/cylo/src/backends/wasm/execution.rs
+// ----------------------------------------------------------------------------
+// Compile and run logic for the WebAssembly backend.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::AsyncTaskBuilder;
+use crate::backends::blob_store::write_blob_files;
+use crate::backends::input_stream;
+use crate::backends::paths::{exposed_paths, write_files};
+use crate::backends::process::{self, CapturedOutput, OutputCapture, WaitOutcome};
+use crate::backends::{
+    AsyncTask, BackendError, BackendResult, CompilationPhase, ExecutionOutcome, ExecutionRequest,
+    ExecutionResult, IsolationLevel, SecurityReport, WATCHDOG_METADATA,
+};
+use crate::backends::{archive, git_checkout, retention};
+use crate::ids;
+use crate::reaper::{DEADLINE_GRACE, ResourceKind, global_reaper};
+
+use super::fuel;
+use super::toolchain::{self, MODULE_FILE, ModuleSource};
+
+/// Backend configuration an execution needs
+#[derive(Debug, Clone)]
+pub(super) struct WasmOptions {
+    /// wasmtime CLI to drive
+    pub wasmtime: String,
+    /// Fuel one CPU second of the request's limits is converted into
+    pub fuel_per_cpu_second: u64,
+    /// asconfig.json AssemblyScript is compiled with
+    pub assemblyscript_config: Option<PathBuf>,
+    /// Executor identity embedded in the workspace name
+    pub owner_id: String,
+    /// How long the workspace is kept if the execution fails
+    pub retention: Duration,
+}
+
+/// Compile a request's code to a module if needed, then run it
+///
+/// The compile step and the run share the request's timeout.
+///
+/// # Arguments
+/// * `options` - wasmtime, metering and retention settings
+/// * `request` - Execution request with code and configuration
+///
+/// # Returns
+/// AsyncTask that resolves to execution result
+pub(super) fn execute_module(
+    options: WasmOptions,
+    request: ExecutionRequest,
+) -> AsyncTask<BackendResult<ExecutionResult>> {
+    AsyncTaskBuilder::new(async move {
+        let start_time = Instant::now();
+
+        // Paths are preopened read-write or not at all
+        if !request.readable_paths.is_empty() {
+            return Err(BackendError::InvalidConfig {
+                backend: "Wasm",
+                details: "readable paths cannot be preopened read-only for WASI modules; \
+                          expose them as writable paths or use another backend"
+                    .to_string(),
+            });
+        }
+        let exposed = exposed_paths(&request)?;
+
+        let source = toolchain::module_source(&request, options.assemblyscript_config.as_deref())?;
+        let workspace = Workspace::create(&options.owner_id, &request)?;
+        if let Some(repo) = &request.git_repo {
+            git_checkout::checkout(repo, workspace.path(), &request).await?;
+        }
+
+        // Compile in a jail of its own; a failed compile is the result
+        let compilation = match source {
+            ModuleSource::Precompiled(bytes) => {
+                workspace.write(MODULE_FILE, &bytes)?;
+                None
+            }
+            ModuleSource::Compiled {
+                source_file,
+                program,
+                args,
+                reads,
+            } => {
+                workspace.write(source_file, request.code.as_bytes())?;
+                let phase = compile(&program, &args, &reads, workspace.path(), &request).await?;
+                if !phase.succeeded() {
+                    let mut result = ExecutionResult::failure(phase.exit_code, "");
+                    result.duration = start_time.elapsed();
+                    result.compilation = Some(phase);
+                    result.metadata.insert("backend".to_string(), "Wasm".to_string());
+                    return Ok(result);
+                }
+                Some(phase)
+            }
+        };
+
+        // The guest sees the workspace as its current directory and home
+        let mut env: Vec<(String, String)> = request
+            .environment
+            .variables(".")
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+        env.extend(request.env_vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+        let budget = fuel::fuel_budget(&request, options.fuel_per_cpu_second);
+        let args = fuel::wasmtime_args(
+            &request,
+            options.fuel_per_cpu_second,
+            &exposed,
+            &env,
+            MODULE_FILE,
+        );
+
+        let mut cmd = Command::new(&options.wasmtime);
+        cmd.args(&args)
+            .current_dir(workspace.path())
+            .env_clear()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let mut child = cmd.spawn().map_err(|e| BackendError::ProcessFailed {
+            details: format!("Failed to spawn {}: {e}", options.wasmtime),
+        })?;
+        let reaper_guard = global_reaper().track(
+            ResourceKind::Process { pid: child.id() },
+            "Wasm",
+            request.execution_id.as_deref(),
+            Some(request.timeout + DEADLINE_GRACE),
+        );
+
+        // Capture output incrementally so it survives a forced kill, and
+        // stream it to the request's sink as it arrives
+        let capture = OutputCapture::streaming(&mut child, request.output_sink.clone());
+        write_input(&mut child, request.input.as_deref())?;
+        let _input_pump = input_stream::pipe(&mut child, &request)?;
+
+        // The compile step already spent part of the timeout
+        let timeout = request.timeout.saturating_sub(start_time.elapsed());
+        let outcome = wait(&mut child, timeout, request.termination_grace)
+            .await
+            .map_err(|e| BackendError::ProcessFailed {
+                details: format!("wasmtime execution failed: {e}"),
+            })?;
+        // wasmtime has been waited on, so its PID may be reused
+        reaper_guard.release();
+
+        let duration = start_time.elapsed();
+        let CapturedOutput { stdout, stderr, raw } = capture.collect(process::OUTPUT_DRAIN).await;
+
+        let mut result = match outcome {
+            WaitOutcome::Exited(status) => ExecutionResult {
+                exit_code: status.code().unwrap_or(-1),
+                stdout,
+                stderr,
+                duration,
+                resource_usage: Default::default(),
+                metadata: HashMap::new(),
+                verdict: None,
+                outcome: ExecutionOutcome::Completed,
+                security: None,
+                cost: None,
+                crash: None,
+                result_sets: None,
+                workspace_archive: None,
+                blobs: HashMap::new(),
+                processed: None,
+                raw_output: None,
+                compilation: None,
+            },
+            // No watchdog runs here, so a stall can only be a timeout
+            WaitOutcome::TimedOut { graceful } | WaitOutcome::Stalled { graceful } => {
+                let mut result = ExecutionResult::timed_out(stdout, stderr, request.timeout);
+                result.duration = duration;
+                result.metadata.insert(
+                    "termination".to_string(),
+                    if graceful { "graceful" } else { "forced" }.to_string(),
+                );
+                result
+            }
+        };
+
+        result.raw_output = raw;
+        result.compilation = compilation;
+        result.security = Some(SecurityReport {
+            isolation: IsolationLevel::Process,
+            network_disabled: !request.network_allowed(),
+            filesystem_read_only: false,
+            memory_limit_bytes: request.limits.max_memory,
+            ipc_isolated: true,
+            ui_restricted: true,
+        });
+        result.metadata.insert("backend".to_string(), "Wasm".to_string());
+        result
+            .metadata
+            .insert("wasmtime".to_string(), options.wasmtime.clone());
+        fuel::record_fuel(budget, &mut result);
+        if request.stall_timeout.is_some() {
+            // Fuel bounds the work a module does, not how long it idles
+            result.metadata.insert(
+                WATCHDOG_METADATA.to_string(),
+                "not enforced: module CPU use is metered as fuel".to_string(),
+            );
+        }
+
+        archive::attach_output(&request, workspace.path(), &mut result);
+        let paths = vec![workspace.path().to_path_buf()];
+        if retention::retain_on_failure(options.retention, paths, "Wasm", &mut result) {
+            workspace.retain();
+        }
+
+        Ok(result)
+    })
+    .spawn()
+}
+
+/// Run a compiler jailed in the workspace with a scrubbed environment
+///
+/// # Returns
+/// The compile phase, failed with the timeout exit code if the compiler
+/// did not finish within the request's timeout
+async fn compile(
+    program: &str,
+    args: &[String],
+    reads: &[PathBuf],
+    workspace: &Path,
+    request: &ExecutionRequest,
+) -> BackendResult<CompilationPhase> {
+    let started = Instant::now();
+    let mut cmd = toolchain::jailed_compiler(program, args, workspace, reads)?;
+    cmd.current_dir(workspace)
+        .env_clear()
+        .envs(toolchain::compiler_env())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    let mut child = cmd.spawn().map_err(|e| BackendError::ProcessFailed {
+        details: format!("Failed to spawn {program}: {e}"),
+    })?;
+    let reaper_guard = global_reaper().track(
+        ResourceKind::Process { pid: child.id() },
+        "Wasm",
+        request.execution_id.as_deref(),
+        Some(request.timeout + DEADLINE_GRACE),
+    );
+
+    let capture = OutputCapture::start(&mut child);
+    let outcome = wait(&mut child, request.timeout, request.termination_grace)
+        .await
+        .map_err(|e| BackendError::ProcessFailed {
+            details: format!("{program} failed: {e}"),
+        })?;
+    // The compiler has been waited on, so its PID may be reused
+    reaper_guard.release();
+    let CapturedOutput { stdout, mut stderr, .. } = capture.collect(process::OUTPUT_DRAIN).await;
+
+    let exit_code = match outcome {
+        WaitOutcome::Exited(status) => status.code().unwrap_or(-1),
+        WaitOutcome::TimedOut { .. } | WaitOutcome::Stalled { .. } => {
+            stderr.push_str(&format!("\n{program} did not finish within the timeout\n"));
+            ExecutionResult::TIMEOUT_EXIT_CODE
+        }
+    };
+    Ok(CompilationPhase {
+        exit_code,
+        stdout,
+        stderr,
+        duration: started.elapsed(),
+    })
+}
+
+/// Wait for a compiler or wasmtime, stopping its whole process group on
+/// timeout
+async fn wait(
+    child: &mut Child,
+    timeout: Duration,
+    grace: Duration,
+) -> std::io::Result<WaitOutcome> {
+    let pid = child.id();
+    process::wait_with_grace(
+        child,
+        timeout,
+        grace,
+        || terminate(pid),
+        || kill(pid),
+    )
+    .await
+}
+
+/// Ask a process group to stop
+#[cfg(target_os = "linux")]
+fn terminate(pid: u32) {
+    process::terminate_group(pid);
+}
+
+/// Ask a process group to stop; only the kill reaches it elsewhere
+#[cfg(not(target_os = "linux"))]
+fn terminate(_pid: u32) {}
+
+/// Kill a process group
+#[cfg(unix)]
+fn kill(pid: u32) {
+    process::kill_group(pid);
+}
+
+/// Kill a process and its children
+#[cfg(not(unix))]
+fn kill(pid: u32) {
+    crate::reaper::kill_process(pid);
+}
+
+/// Feed the request's input to the module and close its stdin
+fn write_input(child: &mut Child, input: Option<&str>) -> BackendResult<()> {
+    if let Some(input) = input
+        && let Some(mut stdin) = child.stdin.take()
+    {
+        use std::io::Write;
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| BackendError::ProcessFailed {
+                details: format!("Failed to write to wasmtime stdin: {e}"),
+            })?;
+    }
+    Ok(())
+}
+
+/// Host directory the module runs in
+///
+/// Removed when dropped, on success and on every error path, unless it
+/// was retained.
+struct Workspace {
+    path: PathBuf,
+    retained: bool,
+}
+
+impl Workspace {
+    /// Create the directory and write the request's workspace archive,
+    /// additional files and blobs into it, in that order
+    fn create(owner_id: &str, request: &ExecutionRequest) -> BackendResult<Self> {
+        // `cylo_<owner>_wasm_<random>` so startup recovery finds leftovers
+        let path = std::env::temp_dir().join(ids::workspace_dir_name(owner_id, "wasm"));
+        fs::create_dir_all(&path).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to create workspace: {e}"),
+        })?;
+
+        // Dropping the guard removes the directory if a write fails
+        let workspace = Self {
+            path,
+            retained: false,
+        };
+        archive::unpack_workspace(request, &workspace.path, "Wasm")?;
+        write_files(&workspace.path, &request.files)?;
+        write_blob_files(&workspace.path, &request.blob_files)?;
+        Ok(workspace)
+    }
+
+    /// Write a file into the workspace
+    fn write(&self, file_name: &str, content: &[u8]) -> BackendResult<()> {
+        fs::write(self.path.join(file_name), content).map_err(|e| {
+            BackendError::FileSystemFailed {
+                details: format!("Failed to write {file_name}: {e}"),
+            }
+        })
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Leave the directory in place when dropped; the reaper removes it
+    /// once its retention period is over
+    fn retain(mut self) {
+        self.retained = true;
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        if !self.retained {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
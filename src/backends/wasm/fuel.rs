@@ -0,0 +1,132 @@
+// ============================================================================
+// File: packages/cylo/src/backends/wasm/fuel.rs
+// ----------------------------------------------------------------------------
+// Resource limits of the WebAssembly backend as wasmtime options.
+//
+// A module cannot be given CPU seconds, so `max_cpu_time` becomes a fuel
+// budget: wasmtime charges roughly one unit per instruction and traps the
+// module once the budget is spent, however many threads the host has.
+// Memory is capped per linear memory; the host process wasmtime runs in
+// is not limited beyond that.
+// ============================================================================
+
+use crate::backends::paths::ExposedPath;
+use crate::backends::{ExecutionRequest, ExecutionResult};
+
+/// Fuel one CPU second buys by default, a conservative estimate of the
+/// instructions a core retires per second running compiled WebAssembly
+pub const DEFAULT_FUEL_PER_CPU_SECOND: u64 = 500_000_000;
+
+/// Result metadata key holding the fuel budget, and whether the module
+/// exhausted it
+pub const WASM_FUEL_METADATA: &str = "wasm_fuel";
+
+/// What wasmtime prints when a module traps for running out of fuel
+const FUEL_EXHAUSTED_TRAP: &str = "all fuel consumed";
+
+/// Fuel a request may spend
+///
+/// # Returns
+/// None when the request sets no CPU time limit
+pub(super) fn fuel_budget(request: &ExecutionRequest, fuel_per_cpu_second: u64) -> Option<u64> {
+    request
+        .limits
+        .max_cpu_time
+        .map(|seconds| seconds.saturating_mul(fuel_per_cpu_second))
+}
+
+/// `wasmtime run` arguments running `module` under the request's limits
+///
+/// The workspace is preopened as the guest's current directory, and each
+/// exposed path at its target. WASI preopens cannot be made read-only
+/// from the command line, so callers pass only writable paths.
+///
+/// # Arguments
+/// * `request` - Execution request
+/// * `fuel_per_cpu_second` - Fuel one CPU second is converted into
+/// * `exposed` - Writable host paths the guest may use
+/// * `env` - Environment variables the guest sees
+/// * `module` - Module file, relative to the workspace
+pub(super) fn wasmtime_args(
+    request: &ExecutionRequest,
+    fuel_per_cpu_second: u64,
+    exposed: &[ExposedPath],
+    env: &[(String, String)],
+    module: &str,
+) -> Vec<String> {
+    let mut args = vec!["run".to_string(), "--dir".to_string(), ".".to_string()];
+    for path in exposed {
+        args.push("--dir".to_string());
+        args.push(format!("{}::{}", path.source.display(), path.target.display()));
+    }
+
+    if let Some(fuel) = fuel_budget(request, fuel_per_cpu_second) {
+        args.extend(["-W".to_string(), format!("fuel={fuel}")]);
+    }
+    if let Some(memory) = request.limits.max_memory {
+        args.extend(["-W".to_string(), format!("max-memory-size={memory}")]);
+    }
+
+    // Sockets are available to WASI but bound to no address unless the
+    // host network is inherited
+    if request.network_allowed() {
+        args.extend(["-S".to_string(), "inherit-network=y".to_string()]);
+        args.extend(["-S".to_string(), "allow-ip-name-lookup=y".to_string()]);
+    }
+
+    for (key, value) in env {
+        args.extend(["--env".to_string(), format!("{key}={value}")]);
+    }
+    args.push(module.to_string());
+    args
+}
+
+/// Record the fuel budget on a result, and whether the module trapped
+/// for exhausting it
+pub(super) fn record_fuel(budget: Option<u64>, result: &mut ExecutionResult) {
+    let Some(budget) = budget else {
+        return;
+    };
+    let exhausted = result.exit_code != 0 && result.stderr.contains(FUEL_EXHAUSTED_TRAP);
+    let value = if exhausted {
+        format!("{budget} exhausted")
+    } else {
+        budget.to_string()
+    };
+    result.metadata.insert(WASM_FUEL_METADATA.to_string(), value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::ResourceLimits;
+
+    #[test]
+    fn limits_become_fuel_and_memory_options() {
+        let mut request = ExecutionRequest::new("fn main() {}", "rust");
+        request.limits = ResourceLimits {
+            max_memory: Some(64 * 1024 * 1024),
+            max_cpu_time: Some(2),
+            max_network_bandwidth: Some(0),
+            ..ResourceLimits::default()
+        };
+        let env = [("TZ".to_string(), "UTC".to_string())];
+        let args = wasmtime_args(&request, 1000, &[], &env, "main.wasm");
+        assert_eq!(args[..3], ["run", "--dir", "."]);
+        assert!(args.windows(2).any(|pair| pair == ["-W", "fuel=2000"]));
+        assert!(args.windows(2).any(|pair| pair == ["-W", "max-memory-size=67108864"]));
+        assert!(args.windows(2).any(|pair| pair == ["--env", "TZ=UTC"]));
+        assert!(!args.iter().any(|arg| arg.starts_with("inherit-network")));
+        assert_eq!(args.last().map(String::as_str), Some("main.wasm"));
+
+        let mut trapped = ExecutionResult::failure(134, "Error: all fuel consumed by WebAssembly");
+        record_fuel(fuel_budget(&request, 1000), &mut trapped);
+        assert_eq!(trapped.metadata[WASM_FUEL_METADATA], "2000 exhausted");
+
+        request.limits.max_cpu_time = None;
+        assert_eq!(fuel_budget(&request, 1000), None);
+        let mut unmetered = ExecutionResult::success("");
+        record_fuel(None, &mut unmetered);
+        assert!(!unmetered.metadata.contains_key(WASM_FUEL_METADATA));
+    }
+}
@@ -0,0 +1,260 @@
+// ============================================================================
+// File: packages/cylo/src/backends/wasm/mod.rs
+// ----------------------------------------------------------------------------
+// WebAssembly backend for secure code execution.
+//
+// Implements ExecutionBackend trait by compiling code to a WASI module on
+// the host and running it under the wasmtime CLI. Needs no kernel feature,
+// container engine or privilege, so it works wherever wasmtime runs:
+// - Rust compiled for wasm32-wasip1, AssemblyScript compiled with asc,
+//   both in a bubblewrap jail on Linux, and precompiled modules anywhere
+// - CPU time metered as wasmtime fuel, memory capped per linear memory
+// - No network and no filesystem beyond the preopened workspace
+// ============================================================================
+
+mod execution;
+mod fuel;
+mod toolchain;
+
+#[cfg(test)]
+mod tests;
+
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::AsyncTaskBuilder;
+use crate::backends::{arch, desktop, io_throttle, language, python_env, runtime};
+use crate::backends::{
+    AsyncTask, BackendConfig, BackendError, BackendResult, ExecutionBackend, ExecutionRequest,
+    ExecutionResult, HealthCheckLevel, HealthStatus,
+};
+
+pub use fuel::{DEFAULT_FUEL_PER_CPU_SECOND, WASM_FUEL_METADATA};
+
+/// `backend_specific` key setting how much wasmtime fuel one second of
+/// `ResourceLimits::max_cpu_time` buys
+pub const FUEL_PER_CPU_SECOND_KEY: &str = "fuel_per_cpu_second";
+
+/// `backend_specific` key naming an asconfig.json passed to asc, e.g. the
+/// one of `@assemblyscript/wasi-shim`, which gives AssemblyScript code a
+/// WASI console; without it modules only import what the code declares
+pub const ASSEMBLYSCRIPT_CONFIG_KEY: &str = "assemblyscript_config";
+
+/// wasmtime CLI installed on the host PATH, if any
+///
+/// Only looks for the binary, so it is cheap enough for platform
+/// detection; whether the compilers are there too is up to the health
+/// check.
+pub fn installed_runtime() -> Option<&'static str> {
+    runtime::toolchain_program("wasm", &runtime::host_search_path())
+}
+
+/// WebAssembly backend
+///
+/// Runs every execution as a fresh wasmtime process whose WASI sandbox
+/// sees only the execution's workspace. Compilers run before the module
+/// starts, jailed to the workspace and their toolchain with a scrubbed
+/// environment.
+#[derive(Debug, Clone)]
+pub struct WasmBackend {
+    /// wasmtime CLI driven, "wasmtime" or an absolute path
+    wasmtime: String,
+
+    /// Fuel one CPU second of the request's limits is converted into
+    fuel_per_cpu_second: u64,
+
+    /// asconfig.json AssemblyScript is compiled with
+    assemblyscript_config: Option<PathBuf>,
+
+    /// Backend configuration
+    config: BackendConfig,
+}
+
+impl WasmBackend {
+    /// Create a new WebAssembly backend instance
+    ///
+    /// # Arguments
+    /// * `wasmtime` - wasmtime CLI to drive
+    /// * `config` - Backend configuration; `FUEL_PER_CPU_SECOND_KEY` and
+    ///   `ASSEMBLYSCRIPT_CONFIG_KEY` tune compilation and metering
+    ///
+    /// # Returns
+    /// New WebAssembly backend instance or error if the configuration is
+    /// invalid
+    pub fn new(wasmtime: String, config: BackendConfig) -> BackendResult<Self> {
+        if wasmtime.trim().is_empty() {
+            return Err(BackendError::InvalidConfig {
+                backend: "Wasm",
+                details: "wasmtime program cannot be empty".to_string(),
+            });
+        }
+
+        let fuel_per_cpu_second = match config.backend_specific.get(FUEL_PER_CPU_SECOND_KEY) {
+            Some(value) => value
+                .parse::<u64>()
+                .ok()
+                .filter(|fuel| *fuel > 0)
+                .ok_or_else(|| BackendError::InvalidConfig {
+                    backend: "Wasm",
+                    details: format!(
+                        "{FUEL_PER_CPU_SECOND_KEY} must be a positive integer, got '{value}'"
+                    ),
+                })?,
+            None => DEFAULT_FUEL_PER_CPU_SECOND,
+        };
+        let assemblyscript_config = config
+            .backend_specific
+            .get(ASSEMBLYSCRIPT_CONFIG_KEY)
+            .map(PathBuf::from);
+
+        Ok(Self {
+            wasmtime,
+            fuel_per_cpu_second,
+            assemblyscript_config,
+            config,
+        })
+    }
+
+    /// wasmtime CLI this backend drives
+    pub fn wasmtime(&self) -> &str {
+        &self.wasmtime
+    }
+
+    /// Fuel one CPU second of a request's limits is converted into
+    pub fn fuel_per_cpu_second(&self) -> u64 {
+        self.fuel_per_cpu_second
+    }
+
+    fn options(&self) -> execution::WasmOptions {
+        execution::WasmOptions {
+            wasmtime: self.wasmtime.clone(),
+            fuel_per_cpu_second: self.fuel_per_cpu_second,
+            assemblyscript_config: self.assemblyscript_config.clone(),
+            owner_id: self.config.owner_id.clone(),
+            retention: self.config.retain_workspace_on_failure,
+        }
+    }
+}
+
+impl ExecutionBackend for WasmBackend {
+    fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
+        let options = self.options();
+        let backend_name = self.backend_type();
+
+        AsyncTaskBuilder::new(async move {
+            if let Err(e) = python_env::reject_in_guest(&request, backend_name)
+                .and_then(|()| desktop::reject_in_guest(&request, backend_name))
+                .and_then(|()| io_throttle::reject_unthrottled(&request, backend_name))
+                .and_then(|()| arch::check(&request, backend_name))
+            {
+                return ExecutionResult::failure(-1, e.to_string());
+            }
+
+            match execution::execute_module(options, request).await {
+                Ok(Ok(result)) => result,
+                Ok(Err(e)) => {
+                    ExecutionResult::failure(-1, format!("{backend_name} execution failed: {e}"))
+                }
+                Err(e) => ExecutionResult::failure(
+                    -1,
+                    format!("{backend_name} execution task failed: {e}"),
+                ),
+            }
+        })
+        .spawn()
+    }
+
+    fn health_check(&self, level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
+        let options = self.options();
+
+        AsyncTaskBuilder::new(async move {
+            let wasmtime = options.wasmtime.clone();
+            let version = Command::new(&wasmtime)
+                .arg("--version")
+                .stdin(Stdio::null())
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+            let Some(version) = version else {
+                return HealthStatus::unhealthy(format!("{wasmtime} is not runnable"))
+                    .with_metric("wasmtime", &wasmtime)
+                    .with_metric("runtime_available", "false");
+            };
+
+            // Missing compilers only rule out their language
+            let search_path = runtime::host_search_path();
+            let compilers: Vec<&str> = toolchain::COMPILERS
+                .iter()
+                .filter(|(_, program)| {
+                    search_path.iter().any(|dir| {
+                        dir.join(program).is_file() || dir.join(format!("{program}.exe")).is_file()
+                    })
+                })
+                .map(|(language, _)| *language)
+                .collect();
+
+            if level == HealthCheckLevel::Liveness {
+                return HealthStatus::healthy(format!("{version} available"))
+                    .with_metric("wasmtime", &wasmtime)
+                    .with_metric("runtime_available", "true")
+                    .with_metric("runtime_version", &version)
+                    .with_metric("compilers", compilers.join(","));
+            }
+
+            // A module needs no compiler, so it probes wasmtime alone
+            let test_request = ExecutionRequest::new("(module (func (export \"_start\")))", "wasm")
+                .with_timeout(Duration::from_secs(10));
+            match execution::execute_module(options, test_request).await {
+                Ok(Ok(result)) if result.is_success() => {
+                    HealthStatus::healthy("Wasm backend operational")
+                        .with_metric("wasmtime", &wasmtime)
+                        .with_metric("runtime_version", &version)
+                        .with_metric("compilers", compilers.join(","))
+                        .with_metric("test_execution", "success")
+                }
+                Ok(Ok(result)) => {
+                    HealthStatus::unhealthy(format!("Test execution failed: {}", result.stderr))
+                        .with_metric("test_execution", "failed")
+                        .with_metric("exit_code", result.exit_code.to_string())
+                }
+                Ok(Err(e)) => HealthStatus::unhealthy(format!("Health check execution error: {e}"))
+                    .with_metric("test_execution", "error"),
+                Err(e) => HealthStatus::unhealthy(format!("Health check task error: {e}"))
+                    .with_metric("test_execution", "task_error"),
+            }
+        })
+        .spawn()
+    }
+
+    fn cleanup(&self) -> AsyncTask<crate::execution_env::CyloResult<()>> {
+        // Every execution removes its own workspace; nothing outlives it
+        AsyncTaskBuilder::new(async move { Ok(()) }).spawn()
+    }
+
+    fn get_config(&self) -> &BackendConfig {
+        &self.config
+    }
+
+    fn backend_type(&self) -> &'static str {
+        "Wasm"
+    }
+
+    fn supports_language(&self, language: &str) -> bool {
+        language::resolve(language)
+            .is_some_and(|spec| matches!(spec.name, "rust" | "assemblyscript" | "wasm"))
+    }
+
+    fn supported_languages(&self) -> &[&'static str] {
+        &[
+            "rust",
+            "rs",
+            "assemblyscript",
+            "asc",
+            "wasm",
+            "wasi",
+            "webassembly",
+        ]
+    }
+}
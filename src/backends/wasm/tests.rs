@@ -0,0 +1,35 @@
+// ============================================================================
+// File: packages/cylo/src/backends/wasm/tests.rs
+// ----------------------------------------------------------------------------
+// Tests for the WebAssembly backend.
+// ============================================================================
+
+use std::time::Duration;
+
+use crate::backends::{BackendConfig, ExecutionBackend};
+
+use super::{DEFAULT_FUEL_PER_CPU_SECOND, FUEL_PER_CPU_SECOND_KEY, WasmBackend};
+
+#[test]
+fn backend_creation_reads_metering_config() {
+    let config = BackendConfig::new("test_wasm").with_timeout(Duration::from_secs(60));
+
+    let backend = WasmBackend::new("wasmtime".to_string(), config.clone())
+        .expect("test should successfully create wasm backend");
+    assert_eq!(backend.wasmtime(), "wasmtime");
+    assert_eq!(backend.fuel_per_cpu_second(), DEFAULT_FUEL_PER_CPU_SECOND);
+    assert_eq!(backend.backend_type(), "Wasm");
+    assert!(backend.supports_language("rust"));
+    assert!(backend.supports_language("AssemblyScript"));
+    assert!(backend.supports_language("wasi"));
+    assert!(!backend.supports_language("python"));
+
+    let metered = config.clone().with_config(FUEL_PER_CPU_SECOND_KEY, "1000");
+    let backend = WasmBackend::new("/opt/bin/wasmtime".to_string(), metered).unwrap();
+    assert_eq!(backend.fuel_per_cpu_second(), 1000);
+
+    // Invalid settings should fail
+    let unmetered = config.clone().with_config(FUEL_PER_CPU_SECOND_KEY, "0");
+    assert!(WasmBackend::new("wasmtime".to_string(), unmetered).is_err());
+    assert!(WasmBackend::new(String::new(), config).is_err());
+}
@@ -0,0 +1,359 @@
+// ============================================================================
+// File: packages/cylo/src/backends/wasm/toolchain.rs
+// ----------------------------------------------------------------------------
+// Compilation of request code to WASI modules for the WebAssembly backend.
+//
+// Compilers run before the module's sandbox exists, so they get one of
+// their own: a bubblewrap jail without network whose only writable
+// directory is the workspace and whose only readable host paths, besides
+// the system directories, are the compiler's toolchain. `include_bytes!`
+// and friends then find no host files to pull into the module. Hosts
+// without bubblewrap refuse compiled languages instead of compiling
+// unconfined, and Rust code naming files through `include!`-style macros
+// is refused up front with a clearer error.
+// ============================================================================
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+#[cfg(target_os = "linux")]
+use std::process::Stdio;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::backends::{
+    BackendError, BackendResult, CompilerOptions, ExecutionRequest, language, runtime,
+    wasm_module,
+};
+
+/// Module file every execution runs, relative to the workspace
+pub(super) const MODULE_FILE: &str = "main.wasm";
+
+/// Compiled languages and the host program compiling each
+pub(super) const COMPILERS: &[(&str, &str)] = &[("rust", "rustc"), ("assemblyscript", "asc")];
+
+/// Rust target modules are compiled for
+const RUST_TARGET: &str = "wasm32-wasip1";
+
+/// Host paths every compiler jail sees read-only, where they exist
+#[cfg(target_os = "linux")]
+const JAIL_SYSTEM_PATHS: &[&str] = &[
+    "/usr",
+    "/lib",
+    "/lib64",
+    "/bin",
+    "/sbin",
+    "/etc/alternatives",
+    "/etc/ld.so.cache",
+];
+
+/// Variables compilers keep from the host environment; toolchain
+/// managers such as rustup find their installs through them
+const COMPILER_ENV: &[&str] = &["PATH", "HOME", "RUSTUP_HOME", "RUSTUP_TOOLCHAIN", "CARGO_HOME"];
+
+/// How a request's code becomes the module that runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum ModuleSource {
+    /// Source written to `source_file`, compiled by running `program`,
+    /// which may read `reads` besides its toolchain
+    Compiled {
+        source_file: &'static str,
+        program: String,
+        args: Vec<String>,
+        reads: Vec<PathBuf>,
+    },
+    /// Module bytes written as the module file
+    Precompiled(Vec<u8>),
+}
+
+/// Work out how a request's code becomes a module
+///
+/// # Arguments
+/// * `request` - Execution request
+/// * `assemblyscript_config` - asconfig.json AssemblyScript compiles with
+///
+/// # Returns
+/// The compile step or module bytes, UnsupportedLanguage for languages
+/// that do not target WASI, or InvalidConfig for code that is refused
+pub(super) fn module_source(
+    request: &ExecutionRequest,
+    assemblyscript_config: Option<&Path>,
+) -> BackendResult<ModuleSource> {
+    let unsupported = || BackendError::UnsupportedLanguage {
+        backend: "Wasm",
+        language: request.language.clone(),
+    };
+    let spec = language::resolve(&request.language).ok_or_else(unsupported)?;
+    let options = request.compiler.clone().unwrap_or_default();
+
+    match spec.name {
+        "rust" => {
+            reject_host_includes(&request.code)?;
+            let pinned =
+                runtime::resolve_runtime("Wasm", &request.language, &runtime::host_search_path())?;
+            let program = match pinned {
+                Some(runtime) => runtime.program.display().to_string(),
+                None => "rustc".to_string(),
+            };
+            Ok(ModuleSource::Compiled {
+                source_file: "main.rs",
+                program,
+                args: rustc_args(&options),
+                reads: Vec::new(),
+            })
+        }
+        "assemblyscript" => Ok(ModuleSource::Compiled {
+            source_file: "main.ts",
+            program: "asc".to_string(),
+            args: asc_args(assemblyscript_config),
+            // The config may extend others next to it
+            reads: assemblyscript_config
+                .and_then(Path::parent)
+                .map(Path::to_path_buf)
+                .into_iter()
+                .collect(),
+        }),
+        "wasm" => wasm_module::module_bytes(&request.code)
+            .map(ModuleSource::Precompiled)
+            .map_err(|details| BackendError::InvalidConfig {
+                backend: "Wasm",
+                details,
+            }),
+        _ => Err(unsupported()),
+    }
+}
+
+/// Host environment a compiler runs with
+pub(super) fn compiler_env() -> Vec<(String, String)> {
+    COMPILER_ENV
+        .iter()
+        .filter_map(|key| Some((key.to_string(), std::env::var(key).ok()?)))
+        .collect()
+}
+
+/// Command running a compiler confined to the workspace and its toolchain
+///
+/// # Arguments
+/// * `program` - Compiler, a name on the host PATH or an absolute path
+/// * `args` - Compiler arguments
+/// * `workspace` - Host directory of the workspace, the only writable one
+/// * `reads` - Further host paths the compiler may read
+///
+/// # Returns
+/// The jailed command, or NotAvailable where bubblewrap is missing
+#[cfg(target_os = "linux")]
+pub(super) fn jailed_compiler(
+    program: &str,
+    args: &[String],
+    workspace: &Path,
+    reads: &[PathBuf],
+) -> BackendResult<Command> {
+    if !bwrap_available() {
+        return Err(BackendError::NotAvailable {
+            backend: "Wasm",
+            reason: format!(
+                "{program} runs confined by bubblewrap, which is not installed; \
+                 install bwrap or send a precompiled module"
+            ),
+        });
+    }
+
+    let mut cmd = Command::new("bwrap");
+    // /tmp first, since the workspace may lie below it
+    cmd.args(["--unshare-all", "--die-with-parent", "--tmpfs", "/tmp"])
+        .args(["--proc", "/proc", "--dev", "/dev"]);
+    let readable = JAIL_SYSTEM_PATHS
+        .iter()
+        .map(PathBuf::from)
+        .chain(toolchain_paths(program))
+        .chain(reads.iter().cloned());
+    for path in readable {
+        cmd.arg("--ro-bind-try").arg(&path).arg(&path);
+    }
+    cmd.arg("--bind").arg(workspace).arg(workspace);
+    cmd.arg("--chdir").arg(workspace);
+    cmd.arg("--").arg(program).args(args);
+    Ok(cmd)
+}
+
+/// Compilers cannot be confined without bubblewrap, so they do not run
+#[cfg(not(target_os = "linux"))]
+pub(super) fn jailed_compiler(
+    program: &str,
+    _args: &[String],
+    _workspace: &Path,
+    _reads: &[PathBuf],
+) -> BackendResult<Command> {
+    Err(BackendError::NotAvailable {
+        backend: "Wasm",
+        reason: format!(
+            "{program} can only run confined on Linux hosts with bubblewrap; \
+             send a precompiled module instead"
+        ),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn bwrap_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        Command::new("bwrap")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+/// Host directories a compiler's toolchain lives in
+///
+/// The PATH directories hold the compiler and the programs it runs, such
+/// as node for asc. The compiler's own install is the directory its binary
+/// resolves into, or the whole `node_modules` for an npm package; rustup
+/// proxies need the rustup home with its toolchains. Cargo's home is left
+/// out but for its binaries, since it holds registry credentials.
+#[cfg(target_os = "linux")]
+fn toolchain_paths(program: &str) -> Vec<PathBuf> {
+    let search_path: Vec<PathBuf> = runtime::host_search_path()
+        .into_iter()
+        .filter(|dir| dir.is_absolute())
+        .collect();
+    let mut paths = search_path.clone();
+
+    let located = if Path::new(program).is_absolute() {
+        Some(PathBuf::from(program))
+    } else {
+        search_path.iter().map(|dir| dir.join(program)).find(|path| path.is_file())
+    };
+    if let Some(binary) = located.and_then(|path| std::fs::canonicalize(path).ok()) {
+        let install = binary
+            .ancestors()
+            .find(|dir| dir.file_name().is_some_and(|name| name == "node_modules"))
+            .or_else(|| binary.parent());
+        paths.extend(install.map(Path::to_path_buf));
+    }
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let rustup = std::env::var_os("RUSTUP_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".rustup")));
+    let cargo = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".cargo")));
+    paths.extend(rustup);
+    paths.extend(cargo.map(|cargo| cargo.join("bin")));
+    paths
+}
+
+/// rustc arguments compiling `main.rs` to the module file
+fn rustc_args(options: &CompilerOptions) -> Vec<String> {
+    let mut args = vec!["--target".to_string(), RUST_TARGET.to_string()];
+    args.extend(options.rustc_args());
+    args.extend(["main.rs", "-o", MODULE_FILE].map(String::from));
+    args
+}
+
+/// asc arguments compiling `main.ts` to the module file
+///
+/// Without a config the runtime's abort import is dropped, since nothing
+/// on the WASI side provides it.
+fn asc_args(config: Option<&Path>) -> Vec<String> {
+    let mut args = ["main.ts", "--outFile", MODULE_FILE, "--optimize"].map(String::from).to_vec();
+    match config {
+        Some(config) => args.extend(["--config".to_string(), config.display().to_string()]),
+        None => args.extend(["--use".to_string(), "abort=".to_string()]),
+    }
+    args
+}
+
+/// Refuse Rust code that pulls host files in at compile time
+///
+/// `include!`, `include_str!`, `include_bytes!` and `#[path]` modules
+/// read any path the compiler can, which would leak host files into the
+/// module or its diagnostics.
+fn reject_host_includes(code: &str) -> BackendResult<()> {
+    match include_pattern().find(code) {
+        Some(found) => Err(BackendError::InvalidConfig {
+            backend: "Wasm",
+            details: format!(
+                "'{}' is not allowed: code compiled for the Wasm backend cannot read host files",
+                found.as_str()
+            ),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn include_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\binclude(_str|_bytes)?\s*!|#\s*!?\s*\[\s*path\s*=")
+            .expect("include pattern is valid")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn languages_map_to_compile_steps() {
+        let rust = ExecutionRequest::new("fn main() { println!(\"hi\"); }", "rust");
+        let ModuleSource::Compiled { source_file, program, args, reads } =
+            module_source(&rust, None).unwrap()
+        else {
+            panic!("rust is compiled");
+        };
+        assert_eq!((source_file, program.as_str()), ("main.rs", "rustc"));
+        assert_eq!(args[..2], ["--target", "wasm32-wasip1"]);
+        assert_eq!(args[args.len() - 3..], ["main.rs", "-o", "main.wasm"]);
+        assert!(reads.is_empty());
+
+        let leak = ExecutionRequest::new("const K: &str = include_str! (\"/etc/shadow\");", "rs");
+        let err = module_source(&leak, None).unwrap_err();
+        assert!(err.to_string().contains("include_str"), "{err}");
+        let module = ExecutionRequest::new("#[path = \"/etc/passwd\"]\nmod leak;", "rust");
+        assert!(module_source(&module, None).is_err());
+
+        let script = ExecutionRequest::new("console.log(\"hi\")", "assemblyscript");
+        let shim = Path::new("/opt/wasi-shim/asconfig.json");
+        let ModuleSource::Compiled { args, reads, .. } = module_source(&script, Some(shim)).unwrap()
+        else {
+            panic!("assemblyscript is compiled");
+        };
+        assert_eq!(reads, [PathBuf::from("/opt/wasi-shim")]);
+        assert!(args.windows(2).any(|pair| pair == ["--config", "/opt/wasi-shim/asconfig.json"]));
+        assert!(asc_args(None).ends_with(&["--use".to_string(), "abort=".to_string()]));
+
+        let module = ExecutionRequest::new("(module)", "wasi");
+        assert_eq!(
+            module_source(&module, None).unwrap(),
+            ModuleSource::Precompiled(b"(module)".to_vec())
+        );
+        assert!(module_source(&ExecutionRequest::new("print(1)", "python"), None).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn compilers_see_only_the_workspace_and_their_toolchain() {
+        let workspace = Path::new("/tmp/cylo_wasm_ws");
+        let Ok(cmd) = jailed_compiler("rustc", &["main.rs".to_string()], workspace, &[]) else {
+            // No bubblewrap: refused rather than run unconfined
+            return;
+        };
+        let args: Vec<String> =
+            cmd.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect();
+        assert_eq!(cmd.get_program(), "bwrap");
+        assert!(args.contains(&"--unshare-all".to_string()));
+        let bound = workspace.to_str().unwrap();
+        assert!(args.windows(3).any(|bind| bind == ["--bind", bound, bound]));
+        assert!(args.ends_with(&["--".to_string(), "rustc".to_string(), "main.rs".to_string()]));
+
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        if let Some(home) = home.filter(|_| std::env::var_os("CARGO_HOME").is_none()) {
+            // Registry credentials stay out of reach
+            assert!(!toolchain_paths("rustc").contains(&home.join(".cargo")));
+        }
+    }
+}
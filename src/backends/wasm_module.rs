@@ -0,0 +1,90 @@
+// ============================================================================
+// File: packages/cylo/src/backends/wasm_module.rs
+// ----------------------------------------------------------------------------
+// Precompiled WebAssembly modules as request payloads.
+//
+// A `wasm` request carries no source to compile: its code is a module the
+// user built themselves, base64-encoded since request code is text, or the
+// module's WAT text form. Backends running host binaries write it out and
+// hand it to the wasmtime CLI, whose WASI sandbox sees only the workspace.
+// ============================================================================
+
+/// Magic number and version every binary module starts with
+const WASM_HEADER: &[u8] = b"\0asm";
+
+/// Module bytes a `wasm` request's code stands for
+///
+/// Code starting with `(` is taken as WAT text, which wasmtime compiles
+/// itself; anything else must be a base64-encoded binary module. Both the
+/// standard and URL-safe alphabets are accepted, and whitespace is ignored.
+///
+/// # Returns
+/// Bytes to write as the module file, or why the code is not a module
+pub fn module_bytes(code: &str) -> Result<Vec<u8>, String> {
+    let code = code.trim();
+    if code.starts_with('(') {
+        return Ok(code.as_bytes().to_vec());
+    }
+
+    let bytes = decode_base64(code).ok_or("code is neither WAT text nor base64")?;
+    if !bytes.starts_with(WASM_HEADER) {
+        return Err("decoded code is not a WebAssembly module".to_string());
+    }
+    Ok(bytes)
+}
+
+/// Arguments to wasmtime running a module with the workspace preopened
+///
+/// # Arguments
+/// * `module` - Path of the module file
+pub fn wasmtime_args(module: &str) -> Vec<String> {
+    vec![
+        "run".to_string(),
+        "--dir".to_string(),
+        ".".to_string(),
+        module.to_string(),
+    ]
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in text
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .take_while(|c| *c != b'=')
+    {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_is_base64_module_or_wat() {
+        let empty_module = b"\0asm\x01\0\0\0".to_vec();
+        assert_eq!(module_bytes("AGFzbQEAAAA=").unwrap(), empty_module);
+        assert_eq!(module_bytes(" AGFz\nbQEAAAA\n").unwrap(), empty_module);
+        assert_eq!(module_bytes("(module)\n").unwrap(), b"(module)");
+
+        assert!(module_bytes("print('hi')").is_err());
+        assert!(module_bytes("aGVsbG8=").is_err());
+    }
+}
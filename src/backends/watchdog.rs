@@ -0,0 +1,89 @@
+// ============================================================================
+// File: packages/cylo/src/backends/watchdog.rs
+// ----------------------------------------------------------------------------
+// Liveness watchdog for running executions.
+//
+// A program blocked on input that never comes, or deadlocked, sits idle
+// until the hard timeout, which for interactive use is usually generous.
+// The watchdog samples how much output the program has written and how
+// much CPU its processes have used; when neither moved for the request's
+// stall timeout the execution is terminated early as stalled.
+// ============================================================================
+
+use std::time::{Duration, Instant};
+
+/// Result metadata key explaining why a requested watchdog did not run
+pub const WATCHDOG_METADATA: &str = "watchdog.stall";
+
+/// How often activity is sampled
+///
+/// Sampling walks the sandbox's process tree, so it runs less often than
+/// the exit poll.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Detects an execution that stopped making progress
+///
+/// The probe returns a counter that grows with activity, e.g. bytes of
+/// output plus CPU milliseconds; any change counts as progress.
+pub struct Watchdog<P> {
+    stall_timeout: Duration,
+    probe: P,
+    activity: u64,
+    last_progress: Instant,
+    last_sample: Instant,
+}
+
+impl<P: FnMut() -> u64> Watchdog<P> {
+    /// Start watching
+    ///
+    /// # Arguments
+    /// * `stall_timeout` - Idle time after which the execution is stalled
+    /// * `probe` - Returns the current activity counter
+    pub fn new(stall_timeout: Duration, mut probe: P) -> Self {
+        let now = Instant::now();
+        Self {
+            stall_timeout,
+            activity: probe(),
+            probe,
+            last_progress: now,
+            last_sample: now,
+        }
+    }
+
+    /// Sample activity if due and report whether the execution is stalled
+    pub fn is_stalled(&mut self, now: Instant) -> bool {
+        if now.duration_since(self.last_sample) >= SAMPLE_INTERVAL {
+            self.last_sample = now;
+            let activity = (self.probe)();
+            if activity != self.activity {
+                self.activity = activity;
+                self.last_progress = now;
+            }
+        }
+        now.duration_since(self.last_progress) >= self.stall_timeout
+    }
+
+    /// Stall timeout being enforced
+    pub fn stall_timeout(&self) -> Duration {
+        self.stall_timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn activity_postpones_the_stall() {
+        let counter = Cell::new(0u64);
+        let start = Instant::now();
+        let mut watchdog = Watchdog::new(Duration::from_secs(1), || counter.get());
+
+        assert!(!watchdog.is_stalled(start + Duration::from_millis(600)));
+        counter.set(10);
+        assert!(!watchdog.is_stalled(start + Duration::from_millis(900)));
+        assert!(!watchdog.is_stalled(start + Duration::from_millis(1500)));
+        assert!(watchdog.is_stalled(start + Duration::from_millis(1900)));
+    }
+}
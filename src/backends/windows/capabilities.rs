@@ -0,0 +1,258 @@
+// ============================================================================
+// File: packages/cylo/src/backends/windows/capabilities.rs
+// ----------------------------------------------------------------------------
+// Job Object capability detection.
+//
+// Services and CI runners often start cylo inside a Job Object of their
+// own. Windows 8 and later nest the execution's job under it, but older
+// hosts refuse the assignment unless the outer job lets children break
+// away, and an outer job's processor affinity bounds every job nested in
+// it. The host is probed once: whether this process is in a job, what that
+// job allows, and whether nested jobs, CPU rate control and silos work, so
+// the backend picks a strategy that works instead of failing assignment.
+// ============================================================================
+
+use std::sync::OnceLock;
+
+use crate::backends::{BackendError, BackendResult};
+
+/// How executions are placed in their Job Object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStrategy {
+    /// Cylo runs outside any job; the execution's job is top-level
+    Direct,
+    /// The execution's job nests under the job cylo runs in
+    Nested,
+    /// Children break away from the job cylo runs in, then join their own
+    Breakaway,
+    /// Cylo runs in a job that neither nests nor lets children break away
+    Unavailable,
+}
+
+impl JobStrategy {
+    /// Name reported in health metrics
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Direct => "direct",
+            Self::Nested => "nested",
+            Self::Breakaway => "breakaway",
+            Self::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// What the host's Job Objects support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct JobSupport {
+    /// This process already runs inside a job
+    pub in_job: bool,
+    /// Jobs can nest, which came with Windows 8 and Server 2012
+    pub nested_jobs: bool,
+    /// The enclosing job lets child processes break away from it
+    pub breakaway_allowed: bool,
+    /// Processor affinity the enclosing job imposes, if any
+    pub parent_affinity: Option<usize>,
+    /// CPU rate control can cap a job's share of the processors
+    pub cpu_rate_control: bool,
+    /// Server silos can be created, which needs a server SKU and admin
+    pub silos: bool,
+}
+
+impl JobSupport {
+    /// Strategy for placing executions in their jobs
+    pub fn strategy(&self) -> JobStrategy {
+        if !self.in_job {
+            JobStrategy::Direct
+        } else if self.nested_jobs {
+            JobStrategy::Nested
+        } else if self.breakaway_allowed {
+            JobStrategy::Breakaway
+        } else {
+            JobStrategy::Unavailable
+        }
+    }
+
+    /// Refuse to run when no strategy can place the execution in a job
+    pub fn check_usable(&self) -> BackendResult<JobStrategy> {
+        match self.strategy() {
+            JobStrategy::Unavailable => Err(BackendError::NotAvailable {
+                backend: "WindowsJob",
+                reason: "cylo runs inside a Job Object that neither supports nested jobs nor \
+                         allows breakaway"
+                    .to_string(),
+            }),
+            strategy => Ok(strategy),
+        }
+    }
+
+    /// Refuse an affinity mask the enclosing job would not let a nested
+    /// job use
+    pub fn check_affinity(&self, mask: Option<usize>) -> BackendResult<()> {
+        let (Some(mask), Some(parent)) = (mask, self.parent_affinity) else {
+            return Ok(());
+        };
+        if self.strategy() == JobStrategy::Nested && mask & !parent != 0 {
+            return Err(BackendError::InvalidConfig {
+                backend: "WindowsJob",
+                details: format!(
+                    "cpuset mask {mask:#x} reaches outside the enclosing job's processors \
+                     {parent:#x}"
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Capabilities of this host, probed on first use
+pub fn job_support() -> &'static JobSupport {
+    static SUPPORT: OnceLock<JobSupport> = OnceLock::new();
+    SUPPORT.get_or_init(|| {
+        let support = detect();
+        tracing::debug!(
+            target: crate::logging::targets::BACKEND_WINDOWS,
+            "Job Object support: {:?}, strategy {}",
+            support,
+            support.strategy().as_str()
+        );
+        support
+    })
+}
+
+/// Probe the host's Job Object support
+fn detect() -> JobSupport {
+    use std::mem;
+    use windows::Win32::System::JobObjects::{
+        IsProcessInJob, JOB_OBJECT_LIMIT_AFFINITY, JOB_OBJECT_LIMIT_BREAKAWAY_OK,
+        JOB_OBJECT_LIMIT_SILENT_BREAKAWAY_OK, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JobObjectExtendedLimitInformation, QueryInformationJobObject,
+    };
+    use windows::Win32::System::Threading::GetCurrentProcess;
+
+    let mut in_job = windows::core::BOOL(0);
+    let in_job = unsafe { IsProcessInJob(GetCurrentProcess(), None, &mut in_job) }.is_ok()
+        && in_job.as_bool();
+
+    // Without a handle the query reads the job this process is in
+    let mut breakaway_allowed = false;
+    let mut parent_affinity = None;
+    if in_job {
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { mem::zeroed() };
+        let queried = unsafe {
+            QueryInformationJobObject(
+                None,
+                JobObjectExtendedLimitInformation,
+                &mut info as *mut _ as *mut std::ffi::c_void,
+                mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                None,
+            )
+        };
+        if queried.is_ok() {
+            let flags = info.BasicLimitInformation.LimitFlags;
+            breakaway_allowed = flags.0
+                & (JOB_OBJECT_LIMIT_BREAKAWAY_OK.0 | JOB_OBJECT_LIMIT_SILENT_BREAKAWAY_OK.0)
+                != 0;
+            if flags.0 & JOB_OBJECT_LIMIT_AFFINITY.0 != 0 {
+                parent_affinity = Some(info.BasicLimitInformation.Affinity);
+            }
+        }
+    }
+
+    let cpu_rate_control = probe_cpu_rate_control();
+    JobSupport {
+        in_job,
+        // Nested jobs and CPU rate control shipped together in Windows 8,
+        // and only the latter can be probed without a process to assign
+        nested_jobs: cpu_rate_control,
+        breakaway_allowed,
+        parent_affinity,
+        cpu_rate_control,
+        silos: probe_silos(),
+    }
+}
+
+/// Whether a throwaway job accepts a CPU rate cap
+fn probe_cpu_rate_control() -> bool {
+    use std::mem;
+    use windows::Win32::System::JobObjects::{
+        JOB_OBJECT_CPU_RATE_CONTROL_ENABLE, JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+        JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0,
+        JobObjectCpuRateControlInformation, SetInformationJobObject,
+    };
+
+    let Ok(job) = win32job::Job::create() else {
+        return false;
+    };
+    // The rate is in hundredths of a percent; this is the whole machine
+    let info = JOBOBJECT_CPU_RATE_CONTROL_INFORMATION {
+        ControlFlags: JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+        Anonymous: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0 { CpuRate: 10_000 },
+    };
+    unsafe {
+        SetInformationJobObject(
+            windows::Win32::Foundation::HANDLE(job.handle() as *mut std::ffi::c_void),
+            JobObjectCpuRateControlInformation,
+            &info as *const _ as *const std::ffi::c_void,
+            mem::size_of::<JOBOBJECT_CPU_RATE_CONTROL_INFORMATION>() as u32,
+        )
+    }
+    .is_ok()
+}
+
+/// Whether a throwaway job can be turned into a silo
+fn probe_silos() -> bool {
+    use windows::Win32::System::JobObjects::{JobObjectCreateSilo, SetInformationJobObject};
+
+    let Ok(job) = win32job::Job::create() else {
+        return false;
+    };
+    unsafe {
+        SetInformationJobObject(
+            windows::Win32::Foundation::HANDLE(job.handle() as *mut std::ffi::c_void),
+            JobObjectCreateSilo,
+            std::ptr::null(),
+            0,
+        )
+    }
+    .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategy_degrades_with_the_enclosing_job() {
+        let top_level = JobSupport::default();
+        assert_eq!(top_level.strategy(), JobStrategy::Direct);
+
+        let nested = JobSupport {
+            in_job: true,
+            nested_jobs: true,
+            parent_affinity: Some(0b0011),
+            ..JobSupport::default()
+        };
+        assert_eq!(nested.check_usable().unwrap(), JobStrategy::Nested);
+        assert!(nested.check_affinity(Some(0b0010)).is_ok());
+        assert!(nested.check_affinity(Some(0b0100)).is_err());
+
+        // Broken-away children are no longer bound by the outer affinity
+        let legacy = JobSupport {
+            in_job: true,
+            breakaway_allowed: true,
+            parent_affinity: Some(0b0011),
+            ..JobSupport::default()
+        };
+        assert_eq!(legacy.strategy(), JobStrategy::Breakaway);
+        assert!(legacy.check_affinity(Some(0b0100)).is_ok());
+
+        let trapped = JobSupport {
+            in_job: true,
+            ..JobSupport::default()
+        };
+        assert!(trapped.check_usable().is_err());
+
+        // Detection itself must not fail, whatever the host
+        let _ = job_support().strategy();
+    }
+}
@@ -4,7 +4,7 @@
 // Resource limit configuration helpers for Windows Job Objects
 // ============================================================================
 
-use crate::backends::{BackendResult, ResourceLimits};
+use crate::backends::{BackendError, BackendResult, ResourceLimits, io_throttle};
 
 /// Convert resource limits to Windows Job Object limit values
 ///
@@ -20,6 +20,12 @@ pub struct WindowsLimits {
     
     /// Maximum number of processes in the job
     pub max_processes: Option<u32>,
+
+    /// Processors the job's processes may run on, one bit per processor
+    pub affinity_mask: Option<usize>,
+
+    /// Disk bandwidth in bytes/sec, reads and writes together
+    pub io_bandwidth: Option<u64>,
 }
 
 impl WindowsLimits {
@@ -38,10 +44,32 @@ impl WindowsLimits {
 
         let max_processes = limits.max_processes;
 
+        // Job Objects pin processors within one group; memory follows the
+        // processors' node, so nodes are chosen through the cpuset
+        if limits.numa_nodes.is_some() {
+            return Err(BackendError::InvalidConfig {
+                backend: "WindowsJob",
+                details: "NUMA nodes cannot be set directly; pin the nodes' CPUs with cpuset"
+                    .to_string(),
+            });
+        }
+        let affinity_mask = limits
+            .cpuset
+            .as_ref()
+            .map(|cpus| {
+                cpus.affinity_mask().ok_or_else(|| BackendError::InvalidConfig {
+                    backend: "WindowsJob",
+                    details: format!("cpuset {} lies outside the first processor group", cpus),
+                })
+            })
+            .transpose()?;
+
         Ok(Self {
             memory_bytes,
             cpu_time_ms,
             max_processes,
+            affinity_mask,
+            io_bandwidth: io_throttle::combined_bps(limits),
         })
     }
 
@@ -62,6 +90,8 @@ impl WindowsLimits {
         self.memory_bytes.is_some() 
             || self.cpu_time_ms.is_some() 
             || self.max_processes.is_some()
+            || self.affinity_mask.is_some()
+            || self.io_bandwidth.is_some()
     }
 }
 
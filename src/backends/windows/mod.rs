@@ -10,24 +10,78 @@
 // - Windows-native sandboxing
 // ============================================================================
 
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::async_task::AsyncTaskBuilder;
 use crate::backends::AsyncTask;
 use crate::backends::{
-    BackendConfig, BackendError, BackendResult, ExecutionBackend, ExecutionRequest,
-    ExecutionResult, HealthStatus,
+    SqlEngine, SqlOptions, arch, archive, compiler, dotnet, git_checkout, language, r_library,
+    retention, runtime, sql, wasm_module,
 };
+use crate::backends::blob_store::write_blob_files;
+use crate::backends::input_stream;
+use crate::backends::paths::{confine_working_dir, write_files};
+use crate::backends::process::{self, CapturedOutput, OutputCapture, WaitOutcome};
+use crate::backends::python_env::PythonEnv;
+use crate::backends::watchdog::Watchdog;
+use crate::backends::{
+    BackendConfig, BackendError, BackendResult, CompilationPhase, CompilerOptions, ExecutionBackend,
+    ExecutionRequest, ExecutionResult, HealthCheckLevel, HealthStatus, IsolationLevel,
+    SecurityReport,
+};
+use crate::ids::{self, NameTarget};
+use crate::logging::{Sensitive, targets};
+use crate::reaper::{DEADLINE_GRACE, ResourceKind, global_reaper};
 
+mod capabilities;
 mod job;
 mod limits;
+mod workspace;
 
-use job::JobManager;
-use limits::WindowsLimits;
+pub use capabilities::{JobStrategy, JobSupport, job_support};
+pub(crate) use job::JobManager;
+pub(crate) use limits::WindowsLimits;
+use workspace::{WorkspaceTracker, unicode_path, verbatim};
+
+/// Backend-specific config key enabling the global temp directory sweep
+///
+/// When set to "true", `cleanup()` also removes every workspace directory in
+/// %TEMP% named for this backend's owner, including ones left by earlier
+/// processes with that identity. Other owners' directories are left alone.
+pub const GLOBAL_CLEANUP_KEY: &str = "global_cleanup";
+
+/// Backend-specific config key with extra rustc flags, separated by spaces
+///
+/// Flags are passed after the defaults, so e.g. `-C debuginfo=2` re-enables
+/// debug info. They are held to the same allowlist as request compiler
+/// options.
+pub const RUSTC_FLAGS_KEY: &str = "rustc_flags";
+
+/// Languages whose toolchains every supported host is expected to have
+const BASE_LANGUAGES: &[&str] = &[
+    "python",
+    "python3",
+    "javascript",
+    "js",
+    "node",
+    "rust",
+    "bash",
+    "sh",
+    "r",
+    "R",
+    "rscript",
+    "sql",
+    "sqlite",
+    "sqlite3",
+    "duckdb",
+];
 
 /// Windows Job Objects backend for secure code execution
 ///
@@ -40,6 +94,13 @@ pub struct WindowsJobBackend {
 
     /// Backend configuration
     config: BackendConfig,
+
+    /// Temp workspaces created by this backend instance
+    workspaces: Arc<WorkspaceTracker>,
+
+    /// Supported languages, including optional ones whose toolchain is on
+    /// PATH
+    languages: Vec<&'static str>,
 }
 
 impl WindowsJobBackend {
@@ -60,17 +121,22 @@ impl WindowsJobBackend {
             });
         }
 
-        // Validate workspace name
-        if workspace_name.is_empty() {
+        // Validate workspace name; it becomes a directory and Job name
+        if let Err(reason) = ids::check(NameTarget::WindowsObject, &workspace_name) {
             return Err(BackendError::InvalidConfig {
                 backend: "windows",
-                details: "Workspace name cannot be empty".to_string(),
+                details: format!("Workspace name {reason}"),
             });
         }
 
+        let mut languages = BASE_LANGUAGES.to_vec();
+        languages.extend(runtime::detected_languages(&runtime::runtime_search_path()));
+
         Ok(Self {
             workspace_name,
             config,
+            workspaces: Arc::new(WorkspaceTracker::default()),
+            languages,
         })
     }
 
@@ -79,13 +145,41 @@ impl WindowsJobBackend {
     /// # Arguments
     /// * `language` - Programming language
     /// * `file_path` - Path to the code file
+    /// * `compiler` - Compiler options for compiled languages
+    /// * `sql` - Engine and seeds of a SQL request
+    /// * `venv` - Virtual environment of a Python request, whose interpreter
+    ///   replaces the system one
+    /// * `backend_config` - Request's backend-specific configuration
     ///
     /// # Returns
-    /// Command to execute the code, or error if language is unsupported
-    fn get_execution_command(language: &str, file_path: &PathBuf) -> BackendResult<Command> {
-        let mut cmd = match language.to_lowercase().as_str() {
+    /// Command to execute the code and, for compiled languages, the compile
+    /// phase, which must have succeeded before the command is run; or error
+    /// if language is unsupported
+    fn get_execution_command(
+        language: &str,
+        file_path: &PathBuf,
+        compiler: Option<&CompilerOptions>,
+        sql: Option<&SqlOptions>,
+        venv: Option<&PythonEnv>,
+        backend_config: &HashMap<String, String>,
+    ) -> BackendResult<(Command, Option<CompilationPhase>)> {
+        // A pinned version (`node@20`) selects a matching runtime on PATH or
+        // among the installed toolchains
+        let search_path = runtime::runtime_search_path();
+        let pinned = runtime::resolve_runtime("WindowsJob", language, &search_path)?;
+        let program = |default: &str| match &pinned {
+            Some(runtime) => runtime.program.clone(),
+            None => PathBuf::from(default),
+        };
+
+        let (name, _) = language::split_version(language);
+        let mut compilation = None;
+        let mut cmd = match name.to_lowercase().as_str() {
             "python" | "python3" => {
-                let mut c = Command::new("python");
+                let mut c = match venv {
+                    Some(venv) => Command::new(venv.interpreter()),
+                    None => Command::new(program("python")),
+                };
                 c.arg(file_path);
                 c
             }
@@ -93,47 +187,62 @@ impl WindowsJobBackend {
                 // Compile Rust source to Windows executable
                 let exe_path = file_path.with_extension("exe");
 
-                log::debug!(
+                tracing::debug!(
+                    target: targets::BACKEND_WINDOWS,
                     "Compiling Rust code: {:?} -> {:?}",
                     file_path,
                     exe_path
                 );
 
-                // Execute rustc to compile the code
-                let compile_output = Command::new("rustc")
-                    .arg(file_path)
-                    .arg("-o")
-                    .arg(&exe_path)
+                // Compile inside the workspace, with rustc's own temp files
+                // there too, so everything goes when the workspace does
+                let workspace = file_path.parent().unwrap_or(Path::new("."));
+                let args = rustc_args(
+                    file_path,
+                    &exe_path,
+                    compiler,
+                    backend_config.get(RUSTC_FLAGS_KEY).map(String::as_str),
+                )?;
+                let compile_start = Instant::now();
+                let compile_output = Command::new(program("rustc"))
+                    .args(args)
+                    .current_dir(workspace)
+                    .env("TMP", workspace)
+                    .env("TEMP", workspace)
                     .output()
                     .map_err(|e| BackendError::ProcessFailed {
                         details: format!("Failed to execute rustc (is Rust installed?): {}", e)
                     })?;
 
-                // Check compilation result
-                if !compile_output.status.success() {
-                    let stderr = String::from_utf8_lossy(&compile_output.stderr);
-                    let stdout = String::from_utf8_lossy(&compile_output.stdout);
-                    let combined = if stdout.is_empty() {
-                        stderr.to_string()
-                    } else {
-                        format!("{}\n{}", stdout, stderr)
-                    };
-
-                    log::error!("Rust compilation failed: {}", combined);
-
-                    return Err(BackendError::ProcessFailed {
-                        details: format!("Rust compilation failed:\n{}", combined)
-                    });
+                // A failed compile is reported as the result's compile phase,
+                // apart from any run output
+                let phase = CompilationPhase {
+                    exit_code: compile_output.status.code().unwrap_or(-1),
+                    stdout: String::from_utf8_lossy(&compile_output.stdout).into_owned(),
+                    stderr: String::from_utf8_lossy(&compile_output.stderr).into_owned(),
+                    duration: compile_start.elapsed(),
+                };
+                if phase.succeeded() {
+                    tracing::debug!(
+                        target: targets::BACKEND_WINDOWS,
+                        "Rust compilation successful, executable: {:?}",
+                        exe_path
+                    );
+                } else {
+                    tracing::debug!(
+                        target: targets::BACKEND_WINDOWS,
+                        "Rust compilation failed: {}",
+                        Sensitive(&phase.stderr)
+                    );
                 }
-
-                log::debug!("Rust compilation successful, executable: {:?}", exe_path);
+                compilation = Some(phase);
 
                 // Return command to execute the compiled binary
                 let c = Command::new(&exe_path);
                 c
             }
             "javascript" | "js" | "node" => {
-                let mut c = Command::new("node");
+                let mut c = Command::new(program("node"));
                 c.arg(file_path);
                 c
             }
@@ -142,6 +251,59 @@ impl WindowsJobBackend {
                 c.arg("-File").arg(file_path);
                 c
             }
+            "r" | "rscript" => {
+                let mut c = Command::new(program("Rscript"));
+                c.arg(file_path);
+                c
+            }
+            "sql" | "sqlite" | "sqlite3" | "duckdb" => {
+                let engine = SqlEngine::of(language, sql);
+                let script = unicode_path(file_path, "WindowsJob")?;
+                let args = sql::shell_args(engine, sql, script).map_err(|details| {
+                    BackendError::InvalidConfig {
+                        backend: "WindowsJob",
+                        details,
+                    }
+                })?;
+                let mut c = Command::new(engine.program());
+                c.args(args);
+                c
+            }
+            "zig" => {
+                // Zig's global cache defaults to the user's profile
+                let workspace = file_path.parent().unwrap_or(Path::new("."));
+                let mut c = Command::new(program("zig"));
+                c.arg("run")
+                    .arg("--global-cache-dir")
+                    .arg(workspace.join(".cache").join("zig"))
+                    .arg(file_path);
+                c
+            }
+            "csharp" | "c#" | "cs" | "dotnet" => {
+                let workspace = file_path.parent().unwrap_or(Path::new("."));
+                let sdk_version = match &pinned {
+                    Some(runtime) => Some(runtime.version.clone()),
+                    None => runtime::installed_runtimes("csharp", &search_path)
+                        .into_iter()
+                        .next()
+                        .map(|runtime| runtime.version),
+                };
+                dotnet::write_project(workspace, sdk_version.as_deref())?;
+                let mut c = Command::new(program("dotnet"));
+                c.args(dotnet::run_args(workspace));
+                c
+            }
+            "lua" | "luajit" => {
+                let lua = runtime::toolchain_program("lua", &search_path).unwrap_or("lua");
+                let mut c = Command::new(program(lua));
+                c.arg(file_path);
+                c
+            }
+            "wasm" | "wasi" | "webassembly" => {
+                let mut c = Command::new(program("wasmtime"));
+                c.args(wasm_module::wasmtime_args(unicode_path(file_path, "WindowsJob")?));
+                c
+            }
             _ => {
                 return Err(BackendError::NotAvailable {
                     backend: "windows",
@@ -154,44 +316,97 @@ impl WindowsJobBackend {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        Ok(cmd)
+        Ok((cmd, compilation))
     }
 
     /// Execute code with Job Object isolation
     ///
     /// # Arguments
     /// * `workspace_name` - Name of the workspace for identification and logging
+    /// * `owner_id` - Executor identity embedded in the temp directory name
+    /// * `workspaces` - Tracker recording the temp directory this execution creates
     /// * `request` - Execution request
+    /// * `retention` - How long the temp directory is kept if the execution fails
+    /// * `share_host_ipc` - Leave the job's processes free to use user objects
+    ///   outside it, as do requests granted the display
     ///
     /// # Returns
     /// Execution result with output and metrics
-    async fn execute_with_job(workspace_name: String, request: ExecutionRequest) -> BackendResult<ExecutionResult> {
-        log::info!("Executing code in workspace: {}", workspace_name);
+    async fn execute_with_job(
+        workspace_name: String,
+        owner_id: String,
+        workspaces: Arc<WorkspaceTracker>,
+        request: ExecutionRequest,
+        retention: Duration,
+        share_host_ipc: bool,
+    ) -> BackendResult<ExecutionResult> {
+        tracing::info!(
+            target: targets::BACKEND_WINDOWS,
+            "Executing code in workspace: {}",
+            workspace_name
+        );
         let start_time = Instant::now();
-
-        // Create temporary directory for code execution
-        let temp_dir = std::env::temp_dir().join(&format!("cylo_{}_{}", workspace_name, uuid::Uuid::new_v4()));
-        fs::create_dir_all(&temp_dir)
-            .map_err(|e| BackendError::Internal {
-                message: format!("Failed to create temp directory: {}", e)
-            })?;
+        // Job objects run host binaries only; no emulation layer is used
+        arch::check(&request, "WindowsJob")?;
+
+        // Create temporary directory for code execution; it is removed when
+        // the guard drops, on success and on every error path
+        let workspace = workspaces.create(
+            &std::env::temp_dir(),
+            &ids::workspace_dir_name(&owner_id, &workspace_name),
+        )?;
+        let temp_dir = workspace.path().to_path_buf();
+        // The backend's own writes take the long-path form, since archives
+        // and files may nest deeper than MAX_PATH
+        let long_dir = verbatim(&temp_dir);
 
         // Determine file extension
-        let extension = match request.language.to_lowercase().as_str() {
+        let (language_name, _) = language::split_version(&request.language);
+        let extension = match language_name.to_lowercase().as_str() {
             "python" | "python3" => "py",
             "rust" => "rs",
             "javascript" | "js" | "node" => "js",
             "bash" | "sh" => "ps1", // Use PowerShell on Windows
+            "r" | "rscript" => "R",
+            "sql" | "sqlite" | "sqlite3" | "duckdb" => "sql",
+            "zig" => "zig",
+            "csharp" | "c#" | "cs" | "dotnet" => "cs",
+            "lua" | "luajit" => "lua",
+            "wasm" | "wasi" | "webassembly" => "wasm",
             _ => "txt",
         };
 
+        // The archive is the base layer, then additional files, so the code
+        // file wins over a namesake
+        archive::unpack_workspace(&request, &long_dir, "WindowsJob")?;
+        write_files(&long_dir, &request.files)?;
+        write_blob_files(&long_dir, &request.blob_files)?;
+        if let Some(repo) = &request.git_repo {
+            git_checkout::checkout(repo, &temp_dir, &request).await?;
+        }
+
         // Write code to temporary file
         let code_file = temp_dir.join(format!("code.{}", extension));
-        let mut file = fs::File::create(&code_file)
+        let mut file = fs::File::create(long_dir.join(format!("code.{}", extension)))
             .map_err(|e| BackendError::ProcessFailed {
                 details: format!("Failed to create code file: {}", e)
             })?;
-        file.write_all(request.code.as_bytes())
+        // A wasm request's code stands for the module bytes
+        let code = if extension == "wasm" {
+            wasm_module::module_bytes(&request.code).map_err(|details| {
+                BackendError::InvalidConfig {
+                    backend: "WindowsJob",
+                    details,
+                }
+            })?
+        } else if extension == "ps1" {
+            // Windows PowerShell reads scripts without a byte order mark in
+            // the ANSI code page, garbling non-ASCII code
+            [b"\xEF\xBB\xBF".as_slice(), request.code.as_bytes()].concat()
+        } else {
+            request.code.as_bytes().to_vec()
+        };
+        file.write_all(&code)
             .map_err(|e| BackendError::ProcessFailed {
                 details: format!("Failed to write code: {}", e)
             })?;
@@ -199,17 +414,72 @@ impl WindowsJobBackend {
         // Convert resource limits to Windows limits
         let windows_limits = WindowsLimits::from_resource_limits(&request.limits)?;
 
+        // Under a service or CI runner cylo may already be in a job, which
+        // decides how the execution can get a job of its own
+        let support = job_support();
+        let strategy = support.check_usable()?;
+        support.check_affinity(windows_limits.affinity_mask)?;
+
         // Create job object with limits
         let job = JobManager::create_with_limits(&windows_limits)?;
+        // GUI code granted the display needs the desktop's user objects
+        let ui_restricted = !share_host_ipc && !request.desktop.display;
+        if ui_restricted {
+            job.restrict_ui()?;
+        }
 
         // Get execution command
-        let mut cmd = Self::get_execution_command(&request.language, &code_file)?;
+        let venv = PythonEnv::of(&request, "WindowsJob")?;
+        let (mut cmd, compilation) = Self::get_execution_command(
+            &request.language,
+            &code_file,
+            request.compiler.as_ref(),
+            request.sql.as_ref(),
+            venv.as_ref(),
+            &request.backend_config,
+        )?;
+        // Code that does not compile never runs
+        if let Some(phase) = compilation.as_ref().filter(|phase| !phase.succeeded()) {
+            let mut result = ExecutionResult::failure(phase.exit_code, "");
+            result.duration = start_time.elapsed();
+            result.compilation = compilation;
+            result.metadata.insert("backend".to_string(), "WindowsJob".to_string());
+            result.metadata.insert("workspace".to_string(), workspace_name);
+            return Ok(result);
+        }
 
-        // Set working directory if specified
-        if let Some(ref work_dir) = request.working_dir {
-            cmd.current_dir(work_dir);
-        } else {
-            cmd.current_dir(&temp_dir);
+        // Set working directory, confined to the workspace
+        match request.working_dir {
+            Some(ref work_dir) => cmd.current_dir(confine_working_dir(&temp_dir, work_dir)?),
+            None => cmd.current_dir(&temp_dir),
+        };
+
+        // Normalized environment first, so request variables win; Windows
+        // has no umask, and the CRT and most runtimes honour TZ and LANG
+        let temp_dir_str = unicode_path(&temp_dir, "WindowsJob")?;
+        for (key, value) in request.environment.variables(temp_dir_str) {
+            cmd.env(key, value);
+        }
+        // Python writes pipes in the ANSI code page unless told otherwise,
+        // which cannot represent most non-ASCII output
+        cmd.env("PYTHONUTF8", "1").env("PYTHONIOENCODING", "utf-8");
+        // R installs packages into a library inside the workspace, which
+        // R only uses once it exists
+        let cache_root = format!("{}\\.cache", temp_dir_str);
+        for (key, value) in r_library::r_env(&request, &cache_root) {
+            fs::create_dir_all(&value).map_err(|e| BackendError::FileSystemFailed {
+                details: format!("Failed to create R library: {}", e)
+            })?;
+            cmd.env(key, value);
+        }
+        for (key, value) in dotnet::dotnet_env(&request, &cache_root) {
+            cmd.env(key, value);
+        }
+        if let Some(venv) = &venv {
+            let path = std::env::var("PATH").ok();
+            for (key, value) in venv.env_vars(path.as_deref()) {
+                cmd.env(key, value);
+            }
         }
 
         // Set environment variables
@@ -217,6 +487,20 @@ impl WindowsJobBackend {
             cmd.env(key, value);
         }
 
+        // Start a new console process group so CTRL_BREAK reaches only the
+        // child, leaving the enclosing job first where jobs cannot nest
+        {
+            use std::os::windows::process::CommandExt;
+            use windows::Win32::System::Threading::{
+                CREATE_BREAKAWAY_FROM_JOB, CREATE_NEW_PROCESS_GROUP,
+            };
+            let mut flags = CREATE_NEW_PROCESS_GROUP.0;
+            if strategy == JobStrategy::Breakaway {
+                flags |= CREATE_BREAKAWAY_FROM_JOB.0;
+            }
+            cmd.creation_flags(flags);
+        }
+
         // Spawn the process
         let mut child = cmd.spawn()
             .map_err(|e| BackendError::ProcessFailed {
@@ -225,6 +509,12 @@ impl WindowsJobBackend {
 
         // Get process ID and assign to job
         let process_id = child.id();
+        let reaper_guard = global_reaper().track(
+            ResourceKind::Process { pid: process_id },
+            "WindowsJob",
+            request.execution_id.as_deref(),
+            Some(request.timeout + DEADLINE_GRACE),
+        );
 
         // Validate PID before assignment
         if !job::is_valid_pid(process_id) {
@@ -235,6 +525,10 @@ impl WindowsJobBackend {
 
         job.assign_process(process_id)?;
 
+        // Capture output incrementally so it survives job termination, and
+        // stream it to the request's sink as it arrives
+        let capture = OutputCapture::streaming(&mut child, request.output_sink.clone());
+
         // Provide input if specified
         if let Some(ref input_data) = request.input {
             if let Some(ref mut stdin) = child.stdin {
@@ -244,32 +538,41 @@ impl WindowsJobBackend {
                     })?;
             }
         }
+        let _input_pump = input_stream::pipe(&mut child, &request)?;
 
-        // Wait for completion with timeout
-        let output = if request.timeout.as_secs() > 0 {
-            // Use tokio timeout
-            match tokio::time::timeout(request.timeout, async move {
-                child.wait_with_output()
-            }).await {
-                Ok(result) => result.map_err(|e| BackendError::ProcessFailed {
-                    details: format!("Process execution failed: {}", e)
-                })?,
-                Err(_) => {
-                    // Timeout occurred - terminate the job
-                    let _ = job.terminate_all(1);
-                    return Err(BackendError::ExecutionTimeout {
-                        seconds: request.timeout.as_secs()
-                    });
-                }
-            }
+        // Wait for completion; on timeout or stall send CTRL_BREAK, allow the
+        // grace period, then terminate the whole job. A zero timeout means
+        // no limit.
+        let timeout = if request.timeout.is_zero() {
+            Duration::MAX
         } else {
-            child.wait_with_output()
-                .map_err(|e| BackendError::ProcessFailed {
-                    details: format!("Process execution failed: {}", e)
-                })?
+            request.timeout
         };
+        let watchdog = request.stall_timeout.map(|idle| {
+            Watchdog::new(idle, || {
+                let cpu_time_ms = job.get_cpu_and_io_stats().map_or(0, |stats| stats.0);
+                capture.bytes_captured() + cpu_time_ms
+            })
+        });
+        let outcome = process::wait_supervised(
+            &mut child,
+            timeout,
+            request.termination_grace,
+            watchdog,
+            || process::send_ctrl_break(process_id),
+            || {
+                let _ = job.terminate_all(1);
+            },
+        )
+        .await
+        .map_err(|e| BackendError::ProcessFailed {
+            details: format!("Process execution failed: {}", e)
+        })?;
+        // The process has been waited on, so its PID may be reused
+        reaper_guard.release();
 
         let duration = start_time.elapsed();
+        let CapturedOutput { stdout, stderr, raw } = capture.collect(process::OUTPUT_DRAIN).await;
 
         // Query comprehensive job statistics
         let process_count = job.active_process_count().unwrap_or(1);
@@ -277,20 +580,32 @@ impl WindowsJobBackend {
             job.get_cpu_and_io_stats().unwrap_or((0, 0, 0, 0));
         let peak_memory = job.get_memory_usage().unwrap_or(0);
 
-        // Clean up temporary directory
-        let _ = fs::remove_dir_all(&temp_dir);
-
         // Build execution result
-        let exit_code = output.status.code().unwrap_or(-1);
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-        let mut result = if exit_code == 0 {
-            ExecutionResult::success(stdout)
-        } else {
-            ExecutionResult::failure(exit_code, stderr)
+        let mut result = match outcome {
+            WaitOutcome::Exited(status) => {
+                let exit_code = status.code().unwrap_or(-1);
+                if exit_code == 0 {
+                    ExecutionResult::success(stdout)
+                } else {
+                    ExecutionResult::failure(exit_code, stderr)
+                }
+            }
+            WaitOutcome::TimedOut { graceful } | WaitOutcome::Stalled { graceful } => {
+                let mut result = match request.stall_timeout {
+                    Some(idle) if matches!(outcome, WaitOutcome::Stalled { .. }) => {
+                        ExecutionResult::stalled(stdout, stderr, idle)
+                    }
+                    _ => ExecutionResult::timed_out(stdout, stderr, request.timeout),
+                };
+                result.metadata.insert(
+                    "termination".to_string(),
+                    if graceful { "graceful" } else { "forced" }.to_string(),
+                );
+                result
+            }
         };
 
+        result.raw_output = raw;
         result.duration = duration;
         result.resource_usage.process_count = process_count;
         result.resource_usage.cpu_time_ms = cpu_time_ms;
@@ -301,55 +616,176 @@ impl WindowsJobBackend {
         // Split evenly as approximation since Windows doesn't distinguish sent/received
         result.resource_usage.network_bytes_sent = network_other_bytes / 2;
         result.resource_usage.network_bytes_received = network_other_bytes / 2;
+        result.security = Some(SecurityReport {
+            isolation: IsolationLevel::Process,
+            network_disabled: false,
+            filesystem_read_only: false,
+            memory_limit_bytes: windows_limits.memory_bytes,
+            // Named kernel objects and file mappings live in the session's
+            // namespace, which a Job Object cannot make private
+            ipc_isolated: false,
+            ui_restricted,
+        });
         result.metadata.insert("backend".to_string(), "WindowsJob".to_string());
         result.metadata.insert("workspace".to_string(), workspace_name);
+        result.compilation = compilation;
+        archive::attach_output(&request, &long_dir, &mut result);
+
+        // Release every handle into the workspace before removing it, so a
+        // binary killed on timeout does not keep its .exe locked
+        drop(child);
+        drop(job);
+        if retention::retain_on_failure(retention, vec![temp_dir], "WindowsJob", &mut result) {
+            workspace.retain();
+        } else {
+            workspace.close().await;
+        }
 
         Ok(result)
     }
 }
 
+/// rustc arguments compiling `source` into `exe`
+///
+/// Debug info is off by default: it is rarely wanted for one-off runs and
+/// on MSVC it comes as a separate .pdb next to the binary.
+///
+/// # Arguments
+/// * `source` - Source file in the workspace
+/// * `exe` - Output binary in the workspace
+/// * `compiler` - Request's compiler options
+/// * `extra_flags` - Value of `RUSTC_FLAGS_KEY`, if set
+fn rustc_args(
+    source: &Path,
+    exe: &Path,
+    compiler: Option<&CompilerOptions>,
+    extra_flags: Option<&str>,
+) -> BackendResult<Vec<OsString>> {
+    let extra_flags: Vec<String> = extra_flags
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    compiler::check_rust_flags(&extra_flags).map_err(|details| BackendError::InvalidConfig {
+        backend: "WindowsJob",
+        details: format!("{RUSTC_FLAGS_KEY}: {details}"),
+    })?;
+    let mut flags = compiler.map(CompilerOptions::rustc_args).unwrap_or_default();
+    flags.extend(extra_flags);
+
+    let mut args: Vec<OsString> = vec![source.into(), "-C".into(), "debuginfo=0".into()];
+    // The MSVC linker writes a .pdb even without debug info unless told not to
+    let wants_debuginfo = flags.iter().any(|flag| flag == "-g" || flag.contains("debuginfo="));
+    if cfg!(target_env = "msvc") && !wants_debuginfo {
+        args.extend(["-C".into(), "link-arg=/DEBUG:NONE".into()]);
+    }
+    args.extend(flags.into_iter().map(OsString::from));
+
+    args.extend(["-o".into(), exe.into()]);
+    Ok(args)
+}
+
 impl ExecutionBackend for WindowsJobBackend {
     fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
         let workspace_name = self.workspace_name.clone();
+        let owner_id = self.config.owner_id.clone();
+        let workspaces = Arc::clone(&self.workspaces);
+        let retention = self.config.retain_workspace_on_failure;
+        let share_host_ipc = self.config.share_host_ipc;
         AsyncTaskBuilder::new(async move {
-            match Self::execute_with_job(workspace_name, request).await {
+            let execution = Self::execute_with_job(
+                workspace_name,
+                owner_id,
+                workspaces,
+                request,
+                retention,
+                share_host_ipc,
+            );
+            match execution.await {
                 Ok(result) => result,
                 Err(e) => ExecutionResult::failure(-1, format!("WindowsJob execution failed: {}", e)),
             }
         }).spawn()
     }
 
-    fn health_check(&self) -> AsyncTask<HealthStatus> {
+    fn health_check(&self, level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
         AsyncTaskBuilder::new(async move {
+            let temp_dir = std::env::temp_dir();
+            if !temp_dir.is_dir() {
+                return HealthStatus::unhealthy(format!(
+                    "Temp directory not available: {}",
+                    temp_dir.display()
+                ))
+                .with_metric("temp_dir", "missing");
+            }
+            if level == HealthCheckLevel::Liveness {
+                return HealthStatus::healthy("WindowsJob backend available")
+                    .with_metric("temp_dir", "present");
+            }
+
             // Check if we can create a basic job object
             let limits = WindowsLimits {
                 memory_bytes: None,
                 cpu_time_ms: None,
                 max_processes: None,
+                affinity_mask: None,
+                io_bandwidth: None,
             };
 
-            match JobManager::create_with_limits(&limits) {
-                Ok(_) => {
+            let support = job_support();
+            let status = match (JobManager::create_with_limits(&limits), support.check_usable()) {
+                (Ok(_), Ok(_)) => {
                     HealthStatus::healthy("WindowsJob backend operational")
                         .with_metric("job_creation", "success")
                 }
-                Err(e) => {
+                (Err(e), _) => {
                     HealthStatus::unhealthy(format!("Job creation failed: {}", e))
                         .with_metric("job_creation", "failed")
                 }
-            }
+                (Ok(_), Err(e)) => HealthStatus::unhealthy(e.to_string())
+                    .with_metric("job_creation", "success"),
+            };
+            status
+                .with_metric("job_strategy", support.strategy().as_str())
+                .with_metric("in_job", support.in_job.to_string())
+                .with_metric("cpu_rate_control", support.cpu_rate_control.to_string())
+                .with_metric("silos", support.silos.to_string())
         }).spawn()
     }
 
     fn cleanup(&self) -> AsyncTask<crate::execution_env::CyloResult<()>> {
+        let workspaces = Arc::clone(&self.workspaces);
+        let global_sweep = self
+            .config
+            .backend_specific
+            .get(GLOBAL_CLEANUP_KEY)
+            .is_some_and(|value| value == "true");
+        let owned_prefix = ids::workspace_dir_prefix(&self.config.owner_id);
+
         AsyncTaskBuilder::new(async move {
-            // Clean up any leftover temporary directories
-            let temp_base = std::env::temp_dir();
-            if let Ok(entries) = fs::read_dir(&temp_base) {
-                for entry in entries.flatten() {
-                    if let Ok(name) = entry.file_name().into_string() {
-                        if name.starts_with("cylo_") {
-                            let _ = fs::remove_dir_all(entry.path());
+            // Remove workspaces of completed executions owned by this backend
+            let removed = workspaces.cleanup_completed();
+            if removed > 0 {
+                tracing::info!(
+                    target: targets::BACKEND_WINDOWS,
+                    "Removed {} completed workspace(s)",
+                    removed
+                );
+            }
+
+            // Opt-in: sweep this owner's temp directories left by earlier
+            // processes, sparing this backend's in-flight executions
+            if global_sweep {
+                let temp_base = std::env::temp_dir();
+                if let Ok(entries) = fs::read_dir(&temp_base) {
+                    for entry in entries.flatten() {
+                        let path = entry.path();
+                        if let Ok(name) = entry.file_name().into_string()
+                            && name.starts_with(&owned_prefix)
+                            && path.is_dir()
+                            && !workspaces.is_in_flight(&path)
+                        {
+                            let _ = fs::remove_dir_all(&path);
                         }
                     }
                 }
@@ -367,20 +803,12 @@ impl ExecutionBackend for WindowsJobBackend {
     }
 
     fn supports_language(&self, language: &str) -> bool {
-        self.supported_languages().contains(&language)
+        let (name, _) = language::split_version(language);
+        self.supported_languages().contains(&name)
     }
 
     fn supported_languages(&self) -> &[&'static str] {
-        &[
-            "python",
-            "python3",
-            "javascript",
-            "js",
-            "node",
-            "rust",
-            "bash",
-            "sh",
-        ]
+        &self.languages
     }
 }
 
@@ -412,10 +840,29 @@ mod tests {
             assert!(backend.supports_language("javascript"));
             assert!(backend.supports_language("rust"));
             assert!(backend.supports_language("bash"));
+            assert!(backend.supports_language("r"));
             assert!(!backend.supports_language("cobol"));
         }
     }
 
+    #[test]
+    fn rustc_args_disable_debuginfo_and_keep_output_in_workspace() {
+        let (source, exe) = (Path::new("ws/code.rs"), Path::new("ws/code.exe"));
+        let edition = CompilerOptions::default().with_edition(crate::backends::RustEdition::E2021);
+        let args = rustc_args(source, exe, Some(&edition), Some("-C opt-level=2")).unwrap();
+        let args: Vec<_> = args.iter().map(|arg| arg.to_string_lossy()).collect();
+
+        assert!(args.windows(2).any(|pair| pair == ["-C", "debuginfo=0"]));
+        assert!(args.windows(2).any(|pair| pair == ["--edition", "2021"]));
+        assert!(args.windows(2).any(|pair| pair == ["-C", "opt-level=2"]));
+        assert_eq!(args[args.len() - 2..], ["-o", "ws/code.exe"]);
+
+        assert!(rustc_args(source, exe, None, Some("-o C:\\other.exe")).is_err());
+        assert!(rustc_args(source, exe, None, Some("--out-dir=C:\\bin")).is_err());
+        assert!(rustc_args(source, exe, None, Some("@flags.txt")).is_err());
+        assert!(rustc_args(source, exe, None, Some("-C link-arg=/NODEFAULTLIB")).is_err());
+    }
+
     #[cfg(target_os = "windows")]
     #[tokio::test]
     async fn test_rust_compilation_and_execution() {
@@ -467,6 +914,40 @@ fn main() {
         }
     }
 
+    #[cfg(target_os = "windows")]
+    #[tokio::test]
+    async fn non_ascii_code_and_deep_file_names_round_trip() {
+        use std::time::Duration;
+
+        let config = BackendConfig::new("test_unicode");
+        let backend = match WindowsJobBackend::new("test_unicode".to_string(), config) {
+            Ok(b) => b,
+            Err(_) => return,
+        };
+
+        // Nested far enough that the file's full path passes MAX_PATH
+        let deep = vec!["répertoire_Ωμέγα"; 16].join("/");
+        let rust_code = format!(
+            r#"
+fn main() {{
+    let text = std::fs::read_to_string("{deep}/données.txt").unwrap();
+    println!("héllo 世界 {{}}", text);
+}}
+"#
+        );
+        let request = ExecutionRequest::new(rust_code, "rust")
+            .with_file(format!("{deep}/données.txt"), "✓")
+            .with_timeout(Duration::from_secs(30));
+
+        let result = backend.execute_code(request).await;
+        if result.stderr.contains("Failed to execute rustc") {
+            eprintln!("Skipping test: rustc not installed");
+            return;
+        }
+        assert_eq!(result.exit_code, 0, "stderr: {}", result.stderr);
+        assert_eq!(result.stdout.trim(), "héllo 世界 ✓");
+    }
+
     #[cfg(target_os = "windows")]
     #[tokio::test]
     async fn test_rust_compilation_error() {
@@ -0,0 +1,290 @@
+// ============================================================================
+// File: packages/cylo/src/backends/windows/workspace.rs
+// ----------------------------------------------------------------------------
+// Per-backend tracking of temporary execution workspaces
+//
+// User code readily builds trees deeper than MAX_PATH under a workspace
+// in %TEMP%, so the backend's own file operations in a workspace go
+// through `\\?\` verbatim paths, which are exempt from the limit. Children
+// still get the plain path: not every interpreter copes with the prefix.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::backends::{BackendError, BackendResult};
+
+/// Removal attempts made by `WorkspaceGuard::close`
+const REMOVE_ATTEMPTS: u32 = 10;
+
+/// Delay between removal attempts
+const REMOVE_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// Lifecycle state of a tracked workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WorkspaceState {
+    /// Execution is still using the directory
+    InFlight,
+    /// Execution finished but the directory could not be removed yet
+    Completed,
+}
+
+/// Long-path form of an absolute path, e.g. `\\?\C:\Temp\ws` for
+/// `C:\Temp\ws` and `\\?\UNC\server\share` for `\\server\share`
+///
+/// Verbatim paths are passed to the file system untouched, so `..` and `/`
+/// are resolved here first. Paths that are already verbatim, device paths
+/// and paths that cannot be made absolute are returned as they are.
+pub fn verbatim(path: &Path) -> PathBuf {
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+    use std::path::{Component, Prefix};
+
+    let Ok(absolute) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+    let Some(Component::Prefix(prefix)) = absolute.components().next() else {
+        return absolute;
+    };
+    let mut long = match prefix.kind() {
+        Prefix::Disk(_) => OsString::from(r"\\?\"),
+        Prefix::UNC(..) => OsString::from(r"\\?\UNC"),
+        _ => return absolute,
+    };
+    // A UNC path keeps one of its two leading separators
+    let skip = if matches!(prefix.kind(), Prefix::UNC(..)) { 1 } else { 0 };
+    let wide: Vec<u16> = absolute.as_os_str().encode_wide().skip(skip).collect();
+    long.push(OsString::from_wide(&wide));
+    PathBuf::from(long)
+}
+
+/// Path as a string for tools that take paths as text
+///
+/// Windows paths are UTF-16 and may hold unpaired surrogates, which a
+/// lossy conversion would silently turn into a different path.
+pub fn unicode_path<'a>(path: &'a Path, backend: &'static str) -> BackendResult<&'a str> {
+    path.to_str().ok_or_else(|| BackendError::InvalidConfig {
+        backend,
+        details: format!("path {} is not valid Unicode", path.display()),
+    })
+}
+
+/// Temporary directories created by one backend instance
+///
+/// Cleanup only ever touches directories recorded here, so one backend
+/// can never delete a workspace that belongs to another backend or process.
+#[derive(Debug, Default)]
+pub struct WorkspaceTracker {
+    dirs: Mutex<HashMap<PathBuf, WorkspaceState>>,
+}
+
+impl WorkspaceTracker {
+    /// Create and track a new workspace directory under `base`
+    ///
+    /// # Arguments
+    /// * `base` - Parent directory (normally %TEMP%)
+    /// * `name` - Directory name
+    ///
+    /// # Returns
+    /// Guard that marks the workspace completed and removes it on drop
+    pub fn create(self: &Arc<Self>, base: &Path, name: &str) -> BackendResult<WorkspaceGuard> {
+        let path = base.join(name);
+        fs::create_dir_all(verbatim(&path)).map_err(|e| BackendError::Internal {
+            message: format!("Failed to create temp directory: {}", e),
+        })?;
+
+        if let Ok(mut dirs) = self.dirs.lock() {
+            dirs.insert(path.clone(), WorkspaceState::InFlight);
+        }
+
+        Ok(WorkspaceGuard {
+            tracker: Arc::clone(self),
+            path,
+            retained: false,
+        })
+    }
+
+    /// Mark a workspace completed and try to remove it
+    ///
+    /// Directories that cannot be removed yet (e.g. a file is still locked
+    /// by a lingering process) stay tracked for the next cleanup.
+    fn complete(&self, path: &Path) {
+        let removed = fs::remove_dir_all(verbatim(path)).is_ok() || !path.exists();
+        if let Ok(mut dirs) = self.dirs.lock() {
+            if removed {
+                dirs.remove(path);
+            } else {
+                dirs.insert(path.to_path_buf(), WorkspaceState::Completed);
+            }
+        }
+    }
+
+    /// Remove completed workspaces that are still on disk
+    ///
+    /// # Returns
+    /// Number of directories removed
+    pub fn cleanup_completed(&self) -> usize {
+        let Ok(mut dirs) = self.dirs.lock() else {
+            return 0;
+        };
+
+        let before = dirs.len();
+        dirs.retain(|path, state| {
+            *state == WorkspaceState::InFlight
+                || (fs::remove_dir_all(verbatim(path)).is_err() && path.exists())
+        });
+        before - dirs.len()
+    }
+
+    /// Stop tracking a workspace without removing it
+    fn forget(&self, path: &Path) {
+        if let Ok(mut dirs) = self.dirs.lock() {
+            dirs.remove(path);
+        }
+    }
+
+    /// Whether a directory belongs to an execution that is still running
+    pub fn is_in_flight(&self, path: &Path) -> bool {
+        self.dirs
+            .lock()
+            .map(|dirs| dirs.get(path) == Some(&WorkspaceState::InFlight))
+            .unwrap_or(false)
+    }
+}
+
+/// Workspace owned by a running execution
+#[derive(Debug)]
+pub struct WorkspaceGuard {
+    tracker: Arc<WorkspaceTracker>,
+    path: PathBuf,
+    retained: bool,
+}
+
+impl WorkspaceGuard {
+    /// Workspace directory
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Remove the workspace, retrying while files in it are still locked
+    ///
+    /// Windows keeps a killed program's executable locked until the last
+    /// handle to the process closes, which can trail the job termination.
+    /// A workspace still locked after the retries is left to
+    /// `cleanup_completed` as usual.
+    pub async fn close(self) {
+        for _ in 0..REMOVE_ATTEMPTS {
+            if fs::remove_dir_all(verbatim(&self.path)).is_ok() || !self.path.exists() {
+                break;
+            }
+            tokio::time::sleep(REMOVE_RETRY_DELAY).await;
+        }
+    }
+
+    /// Leave the workspace on disk for inspection; it is no longer
+    /// tracked here, and the reaper removes it once its retention is over
+    pub fn retain(mut self) {
+        self.retained = true;
+    }
+}
+
+impl Drop for WorkspaceGuard {
+    fn drop(&mut self) {
+        if self.retained {
+            self.tracker.forget(&self.path);
+        } else {
+            self.tracker.complete(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_base() -> PathBuf {
+        std::env::temp_dir().join(format!("cylo_workspace_test_{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn guard_removes_workspace_on_drop() {
+        let base = test_base();
+        let tracker = Arc::new(WorkspaceTracker::default());
+
+        let guard = tracker.create(&base, "ws").unwrap();
+        let path = guard.path().to_path_buf();
+        assert!(path.exists());
+        assert!(tracker.is_in_flight(&path));
+
+        drop(guard);
+        assert!(!path.exists());
+        assert!(!tracker.is_in_flight(&path));
+        assert_eq!(tracker.cleanup_completed(), 0);
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[tokio::test]
+    async fn close_removes_workspace_and_its_build_outputs() {
+        let base = test_base();
+        let tracker = Arc::new(WorkspaceTracker::default());
+
+        let guard = tracker.create(&base, "ws").unwrap();
+        let path = guard.path().to_path_buf();
+        fs::write(path.join("code.exe"), b"MZ").unwrap();
+        fs::write(path.join("code.pdb"), b"").unwrap();
+
+        guard.close().await;
+        assert!(!path.exists());
+        assert!(!tracker.is_in_flight(&path));
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn cleanup_skips_in_flight_and_untracked_dirs() {
+        let base = test_base();
+        let tracker = Arc::new(WorkspaceTracker::default());
+        let foreign = base.join("cylo_other");
+        fs::create_dir_all(&foreign).unwrap();
+
+        let guard = tracker.create(&base, "ws").unwrap();
+        assert_eq!(tracker.cleanup_completed(), 0);
+        assert!(guard.path().exists());
+        assert!(foreign.exists());
+
+        drop(guard);
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn deep_non_ascii_trees_are_created_and_removed() {
+        assert_eq!(verbatim(Path::new(r"C:\Temp\a\..\ws")), PathBuf::from(r"\\?\C:\Temp\ws"));
+        assert_eq!(
+            verbatim(Path::new(r"\\server\share\ws")),
+            PathBuf::from(r"\\?\UNC\server\share\ws")
+        );
+        let already = PathBuf::from(r"\\?\C:\Temp\ws");
+        assert_eq!(verbatim(&already), already);
+
+        let base = test_base();
+        let tracker = Arc::new(WorkspaceTracker::default());
+        let guard = tracker.create(&base, "données_工作区").unwrap();
+        let path = guard.path().to_path_buf();
+        assert_eq!(unicode_path(&path, "WindowsJob").unwrap(), path.to_string_lossy());
+
+        // Well past MAX_PATH, as user code creating nested output would go
+        let mut deep = verbatim(&path);
+        for _ in 0..30 {
+            deep.push("répertoire_Ωμέγα");
+        }
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(deep.join("résumé.txt"), "ünïcödé").unwrap();
+        assert!(deep.as_os_str().len() > 260);
+
+        drop(guard);
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(verbatim(&base));
+    }
+}
@@ -1,7 +1,15 @@
+use std::path::{Path, PathBuf};
+
 use clap::{Args, Parser, Subcommand};
-use log::info;
+use tracing::info;
 
-use crate::{config::RamdiskConfig, error::ExecError, exec};
+use crate::logging::targets;
+use crate::{
+    backends::ImageStore,
+    config::RamdiskConfig,
+    error::{Error, ExecError},
+    exec, platform,
+};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -20,7 +28,37 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Execute code in various languages
-    Exec(ExecArgs)}
+    Exec(ExecArgs),
+
+    /// Inspect execution backends
+    #[command(subcommand)]
+    Backends(BackendsCommand),
+
+    /// Manage the offline image store
+    #[command(subcommand)]
+    Images(ImagesCommand)}
+
+#[derive(Subcommand)]
+pub enum BackendsCommand {
+    /// List backends detected on this host and what each offers
+    List}
+
+#[derive(Subcommand)]
+pub enum ImagesCommand {
+    /// Import the images of an OCI image tarball into the store
+    Import {
+        /// OCI image archive to import
+        tarball: PathBuf,
+
+        /// Store directory (defaults to ~/.local/share/cylo/images)
+        #[arg(long)]
+        store: Option<PathBuf>},
+
+    /// List images held by the store
+    List {
+        /// Store directory (defaults to ~/.local/share/cylo/images)
+        #[arg(long)]
+        store: Option<PathBuf>}}
 
 #[derive(Args)]
 pub struct ExecArgs {
@@ -53,13 +91,14 @@ impl Cli {
 
     pub fn get_exec_args(&self) -> Option<&ExecArgs> {
         match &self.command {
-            Commands::Exec(args) => Some(args)}
+            Commands::Exec(args) => Some(args),
+            Commands::Backends(_) | Commands::Images(_) => None}
     }
 
-    pub fn execute(&self) -> Result<(), ExecError> {
+    pub fn execute(&self) -> Result<(), Error> {
         match &self.command {
             Commands::Exec(args) => {
-                info!("Executing {} code", args.lang());
+                info!(target: targets::CLI, "Executing {} code", args.lang());
                 // Create a default RamdiskConfig for the execution
                 let config = RamdiskConfig::default();
 
@@ -69,10 +108,48 @@ impl Cli {
                     "python" => exec::exec_python(&args.code(), &config)?,
                     "js" => exec::exec_js(&args.code(), &config)?,
                     "bash" => exec::exec_bash(&args.code(), &config)?,
-                    _ => return Err(ExecError::UnsupportedLanguage(args.lang().to_string()))}
-                info!("{} code executed successfully", args.lang());
+                    _ => return Err(ExecError::UnsupportedLanguage(args.lang().to_string()).into())}
+                info!(target: targets::CLI, "{} code executed successfully", args.lang());
             }
-        }
+            Commands::Backends(BackendsCommand::List) => list_backends(),
+            Commands::Images(ImagesCommand::Import { tarball, store }) => {
+                let store = open_store(store.as_deref());
+                let imported = store.import(tarball)?;
+                for image in imported {
+                    println!("Imported {} ({})", image.reference, image.digest);
+                }
+            }
+            Commands::Images(ImagesCommand::List { store }) => {
+                let store = open_store(store.as_deref());
+                let images = store.list()?;
+                for image in images {
+                    println!("{} {}", image.digest, image.reference);
+                }
+            }}
         Ok(())
     }
 }
+
+fn open_store(root: Option<&Path>) -> ImageStore {
+    ImageStore::new(root.map_or_else(ImageStore::default_root, Path::to_path_buf))
+}
+
+fn list_backends() {
+    let yes_no = |flag: bool| if flag { "yes" } else { "no" };
+    for backend in &platform::backend_availability() {
+        let status = if backend.available { "available" } else { "unavailable" };
+        println!("{} ({}): {}", backend.name, status, backend.reason);
+        let Some(capabilities) = &backend.capabilities else {
+            continue;
+        };
+        let memory = capabilities
+            .max_memory_bytes
+            .map_or("host".to_string(), |bytes| format!("{} MiB", bytes / (1024 * 1024)));
+        println!("  isolation:           {}", capabilities.isolation);
+        println!("  max memory:          {}", memory);
+        println!("  network control:     {}", yes_no(capabilities.network_control));
+        println!("  persistent sessions: {}", yes_no(capabilities.persistent_sessions));
+        println!("  artifact collection: {}", yes_no(capabilities.artifact_collection));
+        println!("  streaming:           {}", yes_no(capabilities.streaming));
+    }
+}
@@ -33,6 +33,112 @@ impl std::fmt::Display for FileSystem {
     }
 }
 
+/// How a ramdisk is provided on Windows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WindowsRamdiskProvider {
+    /// First provider that works, in order: ImDisk, Dev Drive, VHD, then
+    /// a temp directory
+    #[default]
+    Auto,
+    /// VHD attached and formatted with diskpart; requires administrator
+    Vhd,
+    /// ImDisk virtual disk driver, when installed; requires administrator
+    ImDisk,
+    /// ReFS Dev Drive on a VHD, Windows 11 and later; requires
+    /// administrator
+    DevDrive,
+    /// Directory under the temp dir mapped to the drive letter with
+    /// `subst`, its size quota enforced by cylo; needs no privileges
+    TempDir,
+}
+
+impl WindowsRamdiskProvider {
+    /// Providers to try, in order, when this one is configured
+    pub fn candidates(self) -> &'static [WindowsRamdiskProvider] {
+        use WindowsRamdiskProvider::*;
+        match self {
+            Auto => &[ImDisk, DevDrive, Vhd, TempDir],
+            Vhd => &[Vhd],
+            ImDisk => &[ImDisk],
+            DevDrive => &[DevDrive],
+            TempDir => &[TempDir],
+        }
+    }
+}
+
+impl std::fmt::Display for WindowsRamdiskProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Auto => "Auto",
+            Self::Vhd => "Vhd",
+            Self::ImDisk => "ImDisk",
+            Self::DevDrive => "DevDrive",
+            Self::TempDir => "TempDir",
+        })
+    }
+}
+
+impl std::str::FromStr for WindowsRamdiskProvider {
+    type Err = StorageError;
+
+    fn from_str(s: &str) -> Result<Self, StorageError> {
+        [Self::Auto, Self::Vhd, Self::ImDisk, Self::DevDrive, Self::TempDir]
+            .into_iter()
+            .find(|provider| provider.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| StorageError::Config(format!("Unknown Windows ramdisk provider: {s}")))
+    }
+}
+
+/// Whether a Windows ramdisk provider can be used on this host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RamdiskProviderStatus {
+    /// The provider
+    pub provider: WindowsRamdiskProvider,
+    /// Whether it can be used now
+    pub available: bool,
+    /// Why it can or cannot be used
+    pub reason: String,
+}
+
+/// How a Linux ramdisk is made visible to processes other than its creator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LinuxRamdiskStrategy {
+    /// tmpfs mounted in the host mount namespace with shared propagation,
+    /// visible to every process and to namespaces created later; needs
+    /// root or passwordless sudo
+    SharedMount,
+    /// tmpfs mounted inside a user and mount namespace kept alive by a
+    /// holder process; other processes enter it with `nsenter` and cylo
+    /// reaches its files through `/proc/<holder>/root`
+    NamespaceHolder,
+    /// tmpfs mounted in a namespace the creating process moves into;
+    /// only that process sees it
+    ProcessNamespace,
+}
+
+impl LinuxRamdiskStrategy {
+    /// One-line description of who can see a ramdisk created this way
+    pub fn visibility(self) -> &'static str {
+        match self {
+            Self::SharedMount => "visible to all processes through the host mount namespace",
+            Self::NamespaceHolder => {
+                "visible through nsenter into the holder process's namespace"
+            }
+            Self::ProcessNamespace => "visible only to the process that created it",
+        }
+    }
+}
+
+impl std::fmt::Display for LinuxRamdiskStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::SharedMount => "SharedMount",
+            Self::NamespaceHolder => "NamespaceHolder",
+            Self::ProcessNamespace => "ProcessNamespace",
+        })
+    }
+}
+
 /// Configuration for the ramdisk
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RamdiskConfig {
@@ -57,6 +163,11 @@ pub struct RamdiskConfig {
     #[cfg(target_os = "macos")]
     /// File system to use for macOS
     pub filesystem: FileSystem,
+
+    #[cfg(target_os = "windows")]
+    /// How the ramdisk is provided on Windows
+    #[serde(default)]
+    pub windows_provider: WindowsRamdiskProvider,
 }
 
 /// Default implementation for RamdiskConfig
@@ -71,6 +182,8 @@ impl Default for RamdiskConfig {
             check_apparmor: true,
             #[cfg(target_os = "macos")]
             filesystem: FileSystem::APFS,
+            #[cfg(target_os = "windows")]
+            windows_provider: WindowsRamdiskProvider::Auto,
         }
     }
 }
@@ -112,6 +225,13 @@ impl TryFrom<&str> for RamdiskConfig {
             FileSystem::Tmpfs
         };
 
+        // On Windows the optional fourth field names the provider instead
+        #[cfg(target_os = "windows")]
+        let windows_provider = match parts.get(3) {
+            Some(provider) => provider.parse()?,
+            None => WindowsRamdiskProvider::Auto,
+        };
+
         Ok(Self {
             use_ramdisk: true, // Add default value
             size_gb,
@@ -121,6 +241,8 @@ impl TryFrom<&str> for RamdiskConfig {
             check_apparmor: true,   // Add default value
             #[cfg(target_os = "macos")]
             filesystem,
+            #[cfg(target_os = "windows")]
+            windows_provider,
         })
     }
 }
@@ -1,8 +1,111 @@
-use std::{io, sync::Arc};
+use std::{io, sync::Arc, time::Duration};
 
 use anyhow;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::backends::BackendError;
+use crate::execution_env::CyloError;
+
+/// Any error returned by cylo's public API
+///
+/// Wraps the error of the subsystem that failed without flattening it, so
+/// callers can still match on the original variant, follow its `source()`
+/// chain, or just ask for its `category()`.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Executor, routing and instance errors
+    #[error(transparent)]
+    Cylo(#[from] CyloError),
+
+    /// Errors raised by an execution backend
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+
+    /// Errors of the direct language execution helpers
+    #[error(transparent)]
+    Exec(#[from] ExecError),
+
+    /// Ramdisk and filesystem setup errors
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+
+    /// Sandboxed language environment errors
+    #[error(transparent)]
+    Sandbox(#[from] SandboxError),
+}
+
+/// What kind of failure an error is, independent of where it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// A request, argument or name was rejected
+    InvalidInput,
+    /// Configuration is invalid
+    Configuration,
+    /// Not supported on this platform or by this backend
+    Unsupported,
+    /// A backend, runtime or tool is not available right now
+    Unavailable,
+    /// A named resource does not exist
+    NotFound,
+    /// A resource already exists or is in a conflicting state
+    Conflict,
+    /// Privileges are missing or access was denied
+    PermissionDenied,
+    /// An operation ran out of time
+    Timeout,
+    /// A resource limit was reached
+    ResourceExhausted,
+    /// The host is too busy; retry later
+    Overloaded,
+    /// The executed code, or a command run for it, failed
+    ExecutionFailed,
+    /// A filesystem, network or OS operation failed
+    Io,
+    /// Unexpected state inside cylo
+    Internal,
+}
+
+impl ErrorCategory {
+    /// Whether the same operation may succeed if retried later
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::Unavailable | Self::Timeout | Self::Overloaded)
+    }
+}
+
+impl Error {
+    /// What kind of failure this is
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Error::Cylo(e) => e.category(),
+            Error::Backend(e) => e.category(),
+            Error::Exec(e) => e.category(),
+            Error::Storage(e) => e.category(),
+            Error::Sandbox(e) => e.category(),
+        }
+    }
+
+    /// Whether the same operation may succeed if retried later
+    pub fn is_retryable(&self) -> bool {
+        self.category().is_retryable()
+    }
+
+    /// Suggested delay before retrying, when the error carries one
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::Cylo(e) => e.retry_after(),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Storage(StorageError::Io(error))
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ExecError {
     #[error("IO error: {0}")]
@@ -27,6 +130,22 @@ pub enum ExecError {
     Storage(#[from] StorageError),
 }
 
+impl ExecError {
+    /// What kind of failure this is
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ExecError::Io(_) => ErrorCategory::Io,
+            ExecError::CommandFailed(_) | ExecError::RuntimeError(_) => {
+                ErrorCategory::ExecutionFailed
+            }
+            ExecError::UnsupportedLanguage(_) => ErrorCategory::Unsupported,
+            ExecError::InvalidCode(_) => ErrorCategory::InvalidInput,
+            ExecError::SystemError(_) => ErrorCategory::Internal,
+            ExecError::Storage(e) => e.category(),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum StorageError {
     #[error("IO error: {0}")]
@@ -57,6 +176,23 @@ pub enum StorageError {
     Other(#[from] anyhow::Error),
 }
 
+impl StorageError {
+    /// What kind of failure this is
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            StorageError::Io(_)
+            | StorageError::CommandFailed(_)
+            | StorageError::PartialFailure(_) => ErrorCategory::Io,
+            StorageError::UnsupportedOs(_) => ErrorCategory::Unsupported,
+            StorageError::AlreadyMounted(_) => ErrorCategory::Conflict,
+            StorageError::Config(_) => ErrorCategory::Configuration,
+            StorageError::InsufficientPrivileges(_) => ErrorCategory::PermissionDenied,
+            StorageError::PathInvalid(_) => ErrorCategory::InvalidInput,
+            StorageError::Other(_) => ErrorCategory::Internal,
+        }
+    }
+}
+
 /// Comprehensive error types for sandbox operations with zero-allocation string sharing
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum SandboxError {
@@ -94,6 +230,27 @@ pub enum SandboxError {
     RuntimeNotFound { runtime: Arc<str> },
 }
 
+impl SandboxError {
+    /// What kind of failure this is
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            SandboxError::ConfigurationFailed { .. } | SandboxError::EnvironmentInvalid { .. } => {
+                ErrorCategory::Configuration
+            }
+            SandboxError::EnvironmentSetup { .. } | SandboxError::IoError { .. } => {
+                ErrorCategory::Io
+            }
+            SandboxError::ProcessLaunch { .. } => ErrorCategory::ExecutionFailed,
+            SandboxError::ResourceExhausted { .. } => ErrorCategory::ResourceExhausted,
+            SandboxError::PermissionDenied { .. } => ErrorCategory::PermissionDenied,
+            SandboxError::CommandNotFound { .. } | SandboxError::RuntimeNotFound { .. } => {
+                ErrorCategory::Unavailable
+            }
+            SandboxError::PathInvalid { .. } => ErrorCategory::InvalidInput,
+        }
+    }
+}
+
 /// Zero-allocation error conversion from std::io::Error
 impl From<io::Error> for SandboxError {
     #[inline]
@@ -149,3 +306,37 @@ pub type Result<T, E = ExecError> = std::result::Result<T, E>;
 
 // Specialized result type for sandbox operations
 pub type SandboxResult<T> = std::result::Result<T, SandboxError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn unified_errors_keep_the_original_and_its_category() {
+        let storage = StorageError::InsufficientPrivileges("mount needs root".into());
+        let error = Error::from(ExecError::from(storage));
+        assert_eq!(error.category(), ErrorCategory::PermissionDenied);
+        assert_eq!(error.to_string(), "Storage error: Insufficient privileges: mount needs root");
+        // The wrapped error's own source is still reachable
+        let source = error.source().map(ToString::to_string);
+        assert_eq!(source.as_deref(), Some("Insufficient privileges: mount needs root"));
+        assert!(matches!(error, Error::Exec(ExecError::Storage(_))));
+
+        let overloaded = Error::from(CyloError::host_overloaded(
+            "memory",
+            "95% used",
+            Duration::from_secs(5),
+        ));
+        assert!(overloaded.is_retryable());
+        assert_eq!(overloaded.retry_after(), Some(Duration::from_secs(5)));
+
+        let backend = Error::from(BackendError::PathEscape { path: "../x".into() });
+        assert_eq!(backend.category(), ErrorCategory::InvalidInput);
+        assert!(!backend.is_retryable());
+
+        let io = Error::from(io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(io.category(), ErrorCategory::Io);
+        assert!(matches!(io, Error::Storage(StorageError::Io(_))));
+    }
+}
@@ -2,11 +2,12 @@ use std::{io::Write, process::Command};
 #[cfg(unix)]
 use std::fs;
 
-use log::{error, info, warn};
 use tempfile::Builder as TempFileBuilder;
+use tracing::{error, info, warn};
 
 use crate::config::RamdiskConfig;
-use crate::error::{ExecError, Result};
+use crate::error::{Error, ExecError};
+use crate::logging::{Sensitive, targets};
 use crate::metadata::MetadataManager;
 use crate::sandbox::safe_path_to_string;
 
@@ -15,7 +16,7 @@ use super::utils::{find_command, get_safe_watched_dir};
 use super::utils::command_exists;
 
 /// Executes Bash shell scripts in a sandboxed environment
-pub fn exec_bash(code: &str, config: &RamdiskConfig) -> Result<()> {
+pub fn exec_bash(code: &str, config: &RamdiskConfig) -> Result<(), Error> {
     let watched_dir = get_safe_watched_dir(config);
 
     // Write code to a temporary file
@@ -25,7 +26,7 @@ pub fn exec_bash(code: &str, config: &RamdiskConfig) -> Result<()> {
         .tempfile_in(&watched_dir)?;
 
     write!(tmpfile, "{code}")?;
-    info!("Created Bash script: {:?}", tmpfile.path());
+    info!(target: targets::EXEC, "Created Bash script: {:?}", tmpfile.path());
 
     // Make the script executable
     #[cfg(unix)]
@@ -43,11 +44,11 @@ pub fn exec_bash(code: &str, config: &RamdiskConfig) -> Result<()> {
         None => {
             return Err(ExecError::CommandFailed(
                 "No Bash interpreter found for execution".into(),
-            ));
+            ).into());
         }
     };
 
-    info!("Using Bash interpreter at {}", bash_executable);
+    info!(target: targets::EXEC, "Using Bash interpreter at {}", bash_executable);
 
     // Execute the script in a controlled environment
     let mut cmd = Command::new(bash_executable);
@@ -70,7 +71,7 @@ pub fn exec_bash(code: &str, config: &RamdiskConfig) -> Result<()> {
 
     // Execute the command
     let output = cmd.output().map_err(|e| {
-        error!("Failed to execute Bash script: {}", e);
+        error!(target: targets::EXEC, "Failed to execute Bash script: {}", e);
         ExecError::CommandFailed(format!("Failed to execute Bash script: {e}"))
     })?;
 
@@ -78,20 +79,20 @@ pub fn exec_bash(code: &str, config: &RamdiskConfig) -> Result<()> {
     if let Some(parent_dir) = watched_dir.parent() {
         let metadata_manager = MetadataManager::new(parent_dir);
         if let Err(e) = metadata_manager.update_metadata(tmpfile.path(), "bash") {
-            warn!("Failed to update metadata: {}", e);
+            warn!(target: targets::EXEC, "Failed to update metadata: {}", e);
         }
     }
 
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout);
-        info!("Bash output (from sandbox): {}", stdout);
+        info!(target: targets::EXEC, "Bash output (from sandbox): {}", Sensitive(&stdout));
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        error!("Bash execution failed: {}", stderr);
+        error!(target: targets::EXEC, "Bash execution failed: {}", Sensitive(&stderr));
         Err(ExecError::CommandFailed(format!(
             "Bash execution failed: {stderr}"
-        )))
+        )).into())
     }
 }
 
@@ -1,10 +1,11 @@
 use std::{io::Write, process::Command};
 
-use log::{error, info, warn};
 use tempfile::Builder as TempFileBuilder;
+use tracing::{error, info, warn};
 
 use crate::config::RamdiskConfig;
-use crate::error::{ExecError, Result};
+use crate::error::{Error, ExecError};
+use crate::logging::{Sensitive, targets};
 use crate::metadata::MetadataManager;
 use crate::sandbox::create_go_environment;
 
@@ -13,7 +14,7 @@ use super::utils::get_safe_watched_dir;
 use super::utils::command_exists;
 
 /// Executes Go code in a sandboxed environment
-pub fn exec_go(code: &str, config: &RamdiskConfig) -> Result<()> {
+pub fn exec_go(code: &str, config: &RamdiskConfig) -> Result<(), Error> {
     let watched_dir = get_safe_watched_dir(config);
 
     // Create a temporary file for the Go code
@@ -23,16 +24,16 @@ pub fn exec_go(code: &str, config: &RamdiskConfig) -> Result<()> {
         .tempfile_in(&watched_dir)?;
 
     write!(tmpfile, "{code}")?;
-    info!("Created Go file: {:?}", tmpfile.path());
+    info!(target: targets::EXEC, "Created Go file: {:?}", tmpfile.path());
 
     // Create and use a sandboxed Go environment
-    info!("Creating sandboxed Go environment");
+    info!(target: targets::EXEC, "Creating sandboxed Go environment");
     let env = create_go_environment(config).map_err(|e| {
-        error!("Failed to create Go environment: {}", e);
+        error!(target: targets::EXEC, "Failed to create Go environment: {}", e);
         ExecError::CommandFailed(format!("Failed to create secure Go environment: {e}"))
     })?;
 
-    info!("Created Go environment at {:?}", env.path);
+    info!(target: targets::EXEC, "Created Go environment at {:?}", env.path);
 
     // Execute the code in the sandboxed environment
     let go_bin = env.get_binary_path("go");
@@ -49,7 +50,7 @@ pub fn exec_go(code: &str, config: &RamdiskConfig) -> Result<()> {
 
     // Execute the command
     let output = cmd.output().map_err(|e| {
-        error!("Failed to execute Go in sandbox: {}", e);
+        error!(target: targets::EXEC, "Failed to execute Go in sandbox: {}", e);
         ExecError::CommandFailed(format!("Failed to execute Go in sandbox: {e}"))
     })?;
 
@@ -57,20 +58,20 @@ pub fn exec_go(code: &str, config: &RamdiskConfig) -> Result<()> {
     if let Some(parent_dir) = watched_dir.parent() {
         let metadata_manager = MetadataManager::new(parent_dir);
         if let Err(e) = metadata_manager.update_metadata(tmpfile.path(), "go") {
-            warn!("Failed to update metadata: {}", e);
+            warn!(target: targets::EXEC, "Failed to update metadata: {}", e);
         }
     }
 
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout);
-        info!("Go output (from sandbox): {}", stdout);
+        info!(target: targets::EXEC, "Go output (from sandbox): {}", Sensitive(&stdout));
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        error!("Go execution in sandbox failed: {}", stderr);
+        error!(target: targets::EXEC, "Go execution in sandbox failed: {}", Sensitive(&stderr));
         Err(ExecError::CommandFailed(format!(
             "Go execution in sandbox failed: {stderr}"
-        )))
+        )).into())
     }
 }
 
@@ -1,10 +1,11 @@
 use std::{io::Write, process::Command};
 
-use log::{error, info, warn};
 use tempfile::Builder as TempFileBuilder;
+use tracing::{error, info, warn};
 
 use crate::config::RamdiskConfig;
-use crate::error::{ExecError, Result};
+use crate::error::{Error, ExecError};
+use crate::logging::{Sensitive, targets};
 use crate::metadata::MetadataManager;
 use crate::sandbox::create_node_environment;
 
@@ -13,7 +14,7 @@ use super::utils::get_safe_watched_dir;
 use super::utils::command_exists;
 
 /// Executes JavaScript code in a sandboxed environment
-pub fn exec_js(code: &str, config: &RamdiskConfig) -> Result<()> {
+pub fn exec_js(code: &str, config: &RamdiskConfig) -> Result<(), Error> {
     let watched_dir = get_safe_watched_dir(config);
 
     // Write code to a temporary file
@@ -23,18 +24,18 @@ pub fn exec_js(code: &str, config: &RamdiskConfig) -> Result<()> {
         .tempfile_in(&watched_dir)?;
 
     write!(tmpfile, "{code}")?;
-    info!("Created JS file: {:?}", tmpfile.path());
+    info!(target: targets::EXEC, "Created JS file: {:?}", tmpfile.path());
 
     // Create and use a sandboxed Node environment
-    info!("Creating sandboxed Node environment");
+    info!(target: targets::EXEC, "Creating sandboxed Node environment");
     let env = create_node_environment(config).map_err(|e| {
-        error!("Failed to create Node environment: {}", e);
+        error!(target: targets::EXEC, "Failed to create Node environment: {}", e);
         ExecError::CommandFailed(format!(
             "Failed to create secure JavaScript environment: {e}"
         ))
     })?;
 
-    info!("Created Node environment at {:?}", env.path);
+    info!(target: targets::EXEC, "Created Node environment at {:?}", env.path);
 
     // Execute the code in the sandboxed environment
     let node_bin = env.get_binary_path("node");
@@ -51,7 +52,7 @@ pub fn exec_js(code: &str, config: &RamdiskConfig) -> Result<()> {
 
     // Execute the command
     let output = cmd.output().map_err(|e| {
-        error!("Failed to execute JavaScript in sandbox: {}", e);
+        error!(target: targets::EXEC, "Failed to execute JavaScript in sandbox: {}", e);
         ExecError::CommandFailed(format!("Failed to execute JavaScript in sandbox: {e}"))
     })?;
 
@@ -59,20 +60,24 @@ pub fn exec_js(code: &str, config: &RamdiskConfig) -> Result<()> {
     if let Some(parent_dir) = watched_dir.parent() {
         let metadata_manager = MetadataManager::new(parent_dir);
         if let Err(e) = metadata_manager.update_metadata(tmpfile.path(), "javascript") {
-            warn!("Failed to update metadata: {}", e);
+            warn!(target: targets::EXEC, "Failed to update metadata: {}", e);
         }
     }
 
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout);
-        info!("JavaScript output (from sandbox): {}", stdout);
+        info!(target: targets::EXEC, "JavaScript output (from sandbox): {}", Sensitive(&stdout));
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        error!("JavaScript execution in sandbox failed: {}", stderr);
+        error!(
+            target: targets::EXEC,
+            "JavaScript execution in sandbox failed: {}",
+            Sensitive(&stderr)
+        );
         Err(ExecError::CommandFailed(format!(
             "JavaScript execution in sandbox failed: {stderr}"
-        )))
+        )).into())
     }
 }
 
@@ -1,10 +1,11 @@
 use std::{fs, io::Write, process::Command};
 
-use log::{error, info, warn};
 use tempfile::Builder as TempFileBuilder;
+use tracing::{error, info, warn};
 
 use crate::config::RamdiskConfig;
-use crate::error::{ExecError, Result};
+use crate::error::{Error, ExecError};
+use crate::logging::{Sensitive, targets};
 use crate::metadata::MetadataManager;
 use crate::sandbox::create_python_venv;
 
@@ -13,8 +14,8 @@ use super::utils::{find_command, get_safe_watched_dir};
 use super::utils::command_exists;
 
 /// Executes Python code in a sandboxed environment
-pub fn exec_python(code: &str, config: &RamdiskConfig) -> Result<()> {
-    info!("Executing Python code");
+pub fn exec_python(code: &str, config: &RamdiskConfig) -> Result<(), Error> {
+    info!(target: targets::EXEC, "Executing Python code");
 
     // Get the appropriate watched directory
     let watched_dir = get_safe_watched_dir(config);
@@ -22,12 +23,12 @@ pub fn exec_python(code: &str, config: &RamdiskConfig) -> Result<()> {
     // Ensure the directory exists
     if !watched_dir.exists() {
         fs::create_dir_all(&watched_dir).map_err(|e| {
-            error!("Failed to create watched directory: {}", e);
+            error!(target: targets::EXEC, "Failed to create watched directory: {}", e);
             ExecError::RuntimeError(format!("Failed to create directory: {e}"))
         })?;
     }
 
-    info!("Using watched directory: {}", watched_dir.display());
+    info!(target: targets::EXEC, "Using watched directory: {}", watched_dir.display());
 
     // Create a temporary file for the Python code
     let mut tmpfile = TempFileBuilder::new()
@@ -35,26 +36,26 @@ pub fn exec_python(code: &str, config: &RamdiskConfig) -> Result<()> {
         .suffix(".py")
         .tempfile_in(&watched_dir)
         .map_err(|e| {
-            error!("Failed to create temporary Python file: {}", e);
+            error!(target: targets::EXEC, "Failed to create temporary Python file: {}", e);
             ExecError::RuntimeError(format!("Failed to create temp file: {e}"))
         })?;
 
     write!(tmpfile, "{code}").map_err(|e| {
-        error!("Failed to write Python code to file: {}", e);
+        error!(target: targets::EXEC, "Failed to write Python code to file: {}", e);
         ExecError::RuntimeError(format!("Failed to write to temp file: {e}"))
     })?;
 
     let path = tmpfile.path().to_owned();
-    info!("Created Python file at {:?}", path);
+    info!(target: targets::EXEC, "Created Python file at {:?}", path);
 
     // Create and use a sandboxed Python environment for execution
-    info!("Creating sandboxed Python environment");
+    info!(target: targets::EXEC, "Creating sandboxed Python environment");
     let env = create_python_venv(config).map_err(|e| {
-        error!("Failed to create Python virtual environment: {}", e);
+        error!(target: targets::EXEC, "Failed to create Python virtual environment: {}", e);
         ExecError::CommandFailed(format!("Failed to create secure Python environment: {e}"))
     })?;
 
-    info!("Created Python virtual environment at {:?}", env.path);
+    info!(target: targets::EXEC, "Created Python virtual environment at {:?}", env.path);
 
     // In restricted environments like containers with Landlock, directly use system Python
     // but with the sandboxed environment variables
@@ -65,11 +66,12 @@ pub fn exec_python(code: &str, config: &RamdiskConfig) -> Result<()> {
         None => {
             return Err(ExecError::CommandFailed(
                 "No Python interpreter found for execution".into(),
-            ));
+            ).into());
         }
     };
 
     info!(
+        target: targets::EXEC,
         "Using system Python at {} with sandbox environment variables",
         python_executable
     );
@@ -83,7 +85,7 @@ pub fn exec_python(code: &str, config: &RamdiskConfig) -> Result<()> {
 
     // Execute the command
     let output = cmd.output().map_err(|e| {
-        error!("Failed to execute Python in venv: {}", e);
+        error!(target: targets::EXEC, "Failed to execute Python in venv: {}", e);
         ExecError::CommandFailed(format!("Failed to execute Python in sandbox: {e}"))
     })?;
 
@@ -91,20 +93,20 @@ pub fn exec_python(code: &str, config: &RamdiskConfig) -> Result<()> {
     if let Some(parent_dir) = watched_dir.parent() {
         let metadata_manager = MetadataManager::new(parent_dir);
         if let Err(e) = metadata_manager.update_metadata(&path, "python") {
-            warn!("Failed to update metadata: {}", e);
+            warn!(target: targets::EXEC, "Failed to update metadata: {}", e);
         }
     }
 
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout);
-        info!("Python output (from sandbox): {}", stdout);
+        info!(target: targets::EXEC, "Python output (from sandbox): {}", Sensitive(&stdout));
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        error!("Python execution in sandbox failed: {}", stderr);
+        error!(target: targets::EXEC, "Python execution in sandbox failed: {}", Sensitive(&stderr));
         Err(ExecError::CommandFailed(format!(
             "Python execution in sandbox failed: {stderr}"
-        )))
+        )).into())
     }
 }
 
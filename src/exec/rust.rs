@@ -1,10 +1,11 @@
 use std::{fs, io::Write, process::Command};
 
-use log::{error, info, warn};
 use tempfile::Builder as TempFileBuilder;
+use tracing::{error, info, warn};
 
 use crate::config::RamdiskConfig;
-use crate::error::{ExecError, Result};
+use crate::error::{Error, ExecError};
+use crate::logging::{Sensitive, targets};
 use crate::metadata::MetadataManager;
 use crate::sandbox::create_rust_environment;
 
@@ -13,7 +14,7 @@ use super::utils::get_safe_watched_dir;
 use super::utils::command_exists;
 
 /// Executes Rust code in a sandboxed environment
-pub fn exec_rust(code: &str, config: &RamdiskConfig) -> Result<()> {
+pub fn exec_rust(code: &str, config: &RamdiskConfig) -> Result<(), Error> {
     let watched_dir = get_safe_watched_dir(config);
 
     // Create a temporary file for the Rust code
@@ -23,16 +24,16 @@ pub fn exec_rust(code: &str, config: &RamdiskConfig) -> Result<()> {
         .tempfile_in(&watched_dir)?;
 
     write!(tmpfile, "{code}")?;
-    info!("Created Rust file: {:?}", tmpfile.path());
+    info!(target: targets::EXEC, "Created Rust file: {:?}", tmpfile.path());
 
     // Create and use a sandboxed Rust environment
-    info!("Creating sandboxed Rust environment");
+    info!(target: targets::EXEC, "Creating sandboxed Rust environment");
     let env = create_rust_environment(config).map_err(|e| {
-        error!("Failed to create Rust environment: {}", e);
+        error!(target: targets::EXEC, "Failed to create Rust environment: {}", e);
         ExecError::CommandFailed(format!("Failed to create secure Rust environment: {e}"))
     })?;
 
-    info!("Created Rust environment at {:?}", env.path);
+    info!(target: targets::EXEC, "Created Rust environment at {:?}", env.path);
 
     // Create a simple Cargo project for the code
     let project_dir = env.path.join("project");
@@ -73,7 +74,7 @@ edition = "2021"
 
     // Execute the command
     let output = cmd.output().map_err(|e| {
-        error!("Failed to execute Rust in sandbox: {}", e);
+        error!(target: targets::EXEC, "Failed to execute Rust in sandbox: {}", e);
         ExecError::CommandFailed(format!("Failed to execute Rust in sandbox: {e}"))
     })?;
 
@@ -81,20 +82,20 @@ edition = "2021"
     if let Some(parent_dir) = watched_dir.parent() {
         let metadata_manager = MetadataManager::new(parent_dir);
         if let Err(e) = metadata_manager.update_metadata(tmpfile.path(), "rust") {
-            warn!("Failed to update metadata: {}", e);
+            warn!(target: targets::EXEC, "Failed to update metadata: {}", e);
         }
     }
 
     if output.status.success() {
         let stdout = String::from_utf8_lossy(&output.stdout);
-        info!("Rust output (from sandbox): {}", stdout);
+        info!(target: targets::EXEC, "Rust output (from sandbox): {}", Sensitive(&stdout));
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        error!("Rust execution in sandbox failed: {}", stderr);
+        error!(target: targets::EXEC, "Rust execution in sandbox failed: {}", Sensitive(&stderr));
         Err(ExecError::CommandFailed(format!(
             "Rust execution in sandbox failed: {stderr}"
-        )))
+        )).into())
     }
 }
 
@@ -4,9 +4,10 @@ use std::{
     process::Command,
 };
 
-use log::{error, info};
+use tracing::{error, info};
 
 use crate::config::RamdiskConfig;
+use crate::logging::targets;
 use crate::ramdisk::get_watched_dir;
 
 /// Helper function to check if any of the commands exist in path
@@ -14,7 +15,7 @@ pub fn find_command<'a>(candidates: &[&'a str]) -> Option<&'a str> {
     for cmd in candidates {
         // First check if it's a direct path we can execute
         if Path::new(cmd).exists() {
-            info!("Found executable directly at path: {}", cmd);
+            info!(target: targets::EXEC, "Found executable directly at path: {}", cmd);
             return Some(cmd);
         }
 
@@ -26,11 +27,11 @@ pub fn find_command<'a>(candidates: &[&'a str]) -> Option<&'a str> {
             .unwrap_or(false);
 
         if exists {
-            info!("Found executable in PATH: {}", cmd);
+            info!(target: targets::EXEC, "Found executable in PATH: {}", cmd);
             return Some(cmd);
         }
     }
-    info!("Could not find any of these executables: {:?}", candidates);
+    info!(target: targets::EXEC, "Could not find any of these executables: {:?}", candidates);
     None
 }
 
@@ -47,7 +48,7 @@ pub fn get_safe_watched_dir(config: &RamdiskConfig) -> PathBuf {
 
     // Check if the ramdisk path exists
     if ramdisk_path.exists() {
-        info!("Using ramdisk watched directory at {:?}", ramdisk_path);
+        info!(target: targets::EXEC, "Using ramdisk watched directory at {:?}", ramdisk_path);
         return ramdisk_path;
     }
 
@@ -57,11 +58,19 @@ pub fn get_safe_watched_dir(config: &RamdiskConfig) -> PathBuf {
     // Ensure the local watched_dir exists
     if !local_path.exists() {
         match fs::create_dir_all(&local_path) {
-            Ok(_) => info!("Created local watched directory at {:?}", local_path),
-            Err(e) => error!("Failed to create local watched directory: {}", e),
+            Ok(_) => info!(
+                target: targets::EXEC,
+                "Created local watched directory at {:?}",
+                local_path
+            ),
+            Err(e) => error!(
+                target: targets::EXEC,
+                "Failed to create local watched directory: {}",
+                e
+            ),
         }
     } else {
-        info!("Using local watched directory at {:?}", local_path);
+        info!(target: targets::EXEC, "Using local watched directory at {:?}", local_path);
     }
 
     local_path
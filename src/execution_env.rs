@@ -7,6 +7,9 @@
 // - Cylo::LandLock("/path/to/jail").instance("name")
 // - Cylo::FireCracker("rust:alpine3.20").instance("name")
 // - Cylo::Apple("python:alpine3.20").instance("name")
+// - Cylo::Docker("python:3.12-alpine").instance("name")
+// - Cylo::Wasm("wasmtime").instance("name")
+// - Cylo::K8sJob("python:3.12-alpine").instance("name")
 //
 // Zero allocation patterns with string interning and efficient enum dispatch.
 // ============================================================================
@@ -15,13 +18,22 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
+use crate::backends::registry;
+use crate::backends::{ExecutionResult, ImageReference};
+use crate::error::ErrorCategory;
+use crate::ids::{self, NameTarget};
+
 /// Core execution environment specification
 ///
 /// Each variant represents a different secure execution backend:
 /// - LandLock: Linux kernel-based sandboxing with filesystem restrictions
 /// - FireCracker: Lightweight microVMs for complete isolation
 /// - Apple: Apple's containerization framework for macOS
+/// - Docker: Docker or Podman containers wherever an engine is installed
+/// - Wasm: WASI modules run under wasmtime, compiled on the host
+/// - K8sJob: Kubernetes Jobs submitted through kubectl
 /// - SweetMcpPlugin: WASM-based SweetMCP plugin execution
+/// - Custom: Third-party backend registered with `register_backend`
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Cylo {
     /// LandLock backend with jail directory path
@@ -36,6 +48,18 @@ pub enum Cylo {
     /// Example: Cylo::Apple("python:alpine3.20")
     Apple(String),
 
+    /// Docker/Podman container backend with image specification
+    /// Example: Cylo::Docker("python:3.12-alpine")
+    Docker(String),
+
+    /// WebAssembly backend with the wasmtime CLI to drive
+    /// Example: Cylo::Wasm("wasmtime")
+    Wasm(String),
+
+    /// Kubernetes Job backend with the image every pod runs
+    /// Example: Cylo::K8sJob("python:3.12-alpine")
+    K8sJob(String),
+
     /// SweetMCP plugin execution with plugin path
     /// Example: Cylo::SweetMcpPlugin("./plugins/eval-py.wasm")
     SweetMcpPlugin(String),
@@ -43,6 +67,15 @@ pub enum Cylo {
     /// Windows Job Objects backend for process sandboxing
     /// Example: Cylo::WindowsJob("kodegen-workspace")
     WindowsJob(String),
+
+    /// Third-party backend registered with `register_backend`
+    /// Example: Cylo::Custom { backend: "Nomad".into(), config: "batch".into() }
+    Custom {
+        /// Name the backend was registered under
+        backend: String,
+        /// Backend-specific configuration handed to its factory
+        config: String,
+    },
 }
 
 impl Cylo {
@@ -75,6 +108,9 @@ impl Cylo {
     /// - LandLock: Validates path exists and is accessible
     /// - FireCracker: Validates image format and registry accessibility
     /// - Apple: Validates image format and platform compatibility
+    /// - Docker: Validates image format
+    /// - Wasm: Validates a wasmtime CLI is named
+    /// - K8sJob: Validates image format
     ///
     /// # Returns
     /// Ok(()) if configuration is valid, Err(CyloError) otherwise
@@ -107,11 +143,11 @@ impl Cylo {
                     });
                 }
 
-                // Validate basic image format: name:tag or registry/name:tag
-                if !image.contains(':') {
+                // name:tag, name@sha256:<hex> or registry/name:tag@sha256:<hex>
+                if ImageReference::parse(image).is_err() {
                     return Err(CyloError::InvalidConfiguration {
                         backend: "FireCracker",
-                        message: "Image must include tag (e.g., 'rust:alpine3.20')",
+                        message: "Image must include a tag or digest (e.g., 'rust:alpine3.20')",
                     });
                 }
 
@@ -126,57 +162,96 @@ impl Cylo {
                     });
                 }
 
-                // Validate basic image format for Apple containerization
-                if !image.contains(':') {
+                // name:tag, name@sha256:<hex> or registry/name:tag@sha256:<hex>
+                if ImageReference::parse(image).is_err() {
                     return Err(CyloError::InvalidConfiguration {
                         backend: "Apple",
-                        message: "Image must include tag (e.g., 'python:alpine3.20')",
+                        message: "Image must include a tag or digest (e.g., 'python:alpine3.20')",
                     });
                 }
 
                 Ok(())
             }
 
-            Cylo::SweetMcpPlugin(plugin_path) => {
-                if plugin_path.is_empty() {
+            Cylo::Docker(image) => {
+                if image.is_empty() {
                     return Err(CyloError::InvalidConfiguration {
-                        backend: "SweetMcpPlugin",
-                        message: "Plugin path cannot be empty",
+                        backend: "Docker",
+                        message: "Image specification cannot be empty",
                     });
                 }
 
-                // Validate plugin file extension
-                if !plugin_path.ends_with(".wasm") {
+                // name:tag, name@sha256:<hex> or registry/name:tag@sha256:<hex>
+                if ImageReference::parse(image).is_err() {
                     return Err(CyloError::InvalidConfiguration {
-                        backend: "SweetMcpPlugin",
-                        message: "Plugin file must have .wasm extension",
+                        backend: "Docker",
+                        message: "Image must include a tag or digest (e.g., 'python:3.12-alpine')",
                     });
                 }
 
                 Ok(())
             }
 
-            Cylo::WindowsJob(workspace_name) => {
-                if workspace_name.is_empty() {
+            Cylo::Wasm(wasmtime) => {
+                if wasmtime.is_empty() {
+                    return Err(CyloError::InvalidConfiguration {
+                        backend: "Wasm",
+                        message: "wasmtime program cannot be empty",
+                    });
+                }
+
+                Ok(())
+            }
+
+            Cylo::K8sJob(image) => {
+                if image.is_empty() {
                     return Err(CyloError::InvalidConfiguration {
-                        backend: "WindowsJob",
-                        message: "Workspace name cannot be empty",
+                        backend: "K8sJob",
+                        message: "Image specification cannot be empty",
                     });
                 }
 
-                // Validate workspace name format
-                if !workspace_name
-                    .chars()
-                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
-                {
+                if ImageReference::parse(image).is_err() {
                     return Err(CyloError::InvalidConfiguration {
-                        backend: "WindowsJob",
-                        message: "Workspace name must contain only alphanumeric characters, hyphens, and underscores",
+                        backend: "K8sJob",
+                        message: "Image must include a tag or digest (e.g., 'python:3.12-alpine')",
                     });
                 }
 
                 Ok(())
             }
+
+            Cylo::SweetMcpPlugin(plugin_path) => {
+                if plugin_path.is_empty() {
+                    return Err(CyloError::InvalidConfiguration {
+                        backend: "SweetMcpPlugin",
+                        message: "Plugin path cannot be empty",
+                    });
+                }
+
+                // Validate plugin file extension
+                if !plugin_path.ends_with(".wasm") {
+                    return Err(CyloError::InvalidConfiguration {
+                        backend: "SweetMcpPlugin",
+                        message: "Plugin file must have .wasm extension",
+                    });
+                }
+
+                Ok(())
+            }
+
+            // Workspace names become directory and Job object names
+            Cylo::WindowsJob(workspace_name) => {
+                ids::validate_name(NameTarget::WindowsObject, workspace_name)
+            }
+
+            Cylo::Custom { backend, config } => match registry::factory(backend) {
+                Some((_, factory)) => factory.validate(config),
+                None => Err(CyloError::InvalidConfiguration {
+                    backend: "Custom",
+                    message: "Backend is not registered",
+                }),
+            },
         }
     }
 
@@ -187,8 +262,14 @@ impl Cylo {
             Cylo::LandLock(_) => "LandLock",
             Cylo::FireCracker(_) => "FireCracker",
             Cylo::Apple(_) => "Apple",
+            Cylo::Docker(_) => "Docker",
+            Cylo::Wasm(_) => "Wasm",
+            Cylo::K8sJob(_) => "K8sJob",
             Cylo::SweetMcpPlugin(_) => "SweetMcpPlugin",
             Cylo::WindowsJob(_) => "WindowsJob",
+            Cylo::Custom { backend, .. } => {
+                registry::factory(backend).map_or("Custom", |(name, _)| name)
+            }
         }
     }
 
@@ -199,8 +280,12 @@ impl Cylo {
             Cylo::LandLock(path) => path,
             Cylo::FireCracker(image) => image,
             Cylo::Apple(image) => image,
+            Cylo::Docker(image) => image,
+            Cylo::Wasm(wasmtime) => wasmtime,
+            Cylo::K8sJob(image) => image,
             Cylo::SweetMcpPlugin(plugin_path) => plugin_path,
             Cylo::WindowsJob(workspace_name) => workspace_name,
+            Cylo::Custom { config, .. } => config,
         }
     }
 }
@@ -211,8 +296,12 @@ impl fmt::Display for Cylo {
             Cylo::LandLock(path) => write!(f, "LandLock({path})"),
             Cylo::FireCracker(image) => write!(f, "FireCracker({image})"),
             Cylo::Apple(image) => write!(f, "Apple({image})"),
+            Cylo::Docker(image) => write!(f, "Docker({image})"),
+            Cylo::Wasm(wasmtime) => write!(f, "Wasm({wasmtime})"),
+            Cylo::K8sJob(image) => write!(f, "K8sJob({image})"),
             Cylo::SweetMcpPlugin(plugin_path) => write!(f, "SweetMcpPlugin({plugin_path})"),
             Cylo::WindowsJob(workspace_name) => write!(f, "WindowsJob({workspace_name})"),
+            Cylo::Custom { backend, config } => write!(f, "{backend}({config})"),
         }
     }
 }
@@ -250,27 +339,8 @@ impl CyloInstance {
         // Validate environment configuration
         self.env.validate()?;
 
-        // Validate instance name
-        if self.name.is_empty() {
-            return Err(CyloError::InvalidConfiguration {
-                backend: self.env.backend_type(),
-                message: "Instance name cannot be empty",
-            });
-        }
-
         // Instance names must be valid identifiers for security
-        if !self
-            .name
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
-        {
-            return Err(CyloError::InvalidConfiguration {
-                backend: self.env.backend_type(),
-                message: "Instance name must contain only alphanumeric characters, hyphens, and underscores",
-            });
-        }
-
-        Ok(())
+        validate_instance_name(&self.name)
     }
 
     /// Get the instance identifier for tracking
@@ -335,6 +405,14 @@ pub enum CyloError {
         timeout_secs: u64,
     },
 
+    /// A provisioning step such as an image pull ran past its budget
+    #[error("Provisioning timeout in {backend} environment: {stage} took over {timeout_secs}s")]
+    ProvisioningTimeout {
+        backend: &'static str,
+        stage: String,
+        timeout_secs: u64,
+    },
+
     /// Resource limits exceeded
     #[error("Resource limit exceeded in {backend}: {resource} limit {limit}")]
     ResourceLimitExceeded {
@@ -350,6 +428,64 @@ pub enum CyloError {
     /// Validation error
     #[error("Validation error: {message}")]
     Validation { message: String },
+
+    /// Execution request was rejected before reaching a backend
+    #[error("Invalid request field '{field}': {reason}")]
+    InvalidRequest { field: String, reason: String },
+
+    /// No backend can satisfy the request's routing requirements
+    #[error("Request requirements cannot be satisfied: {}", missing.join("; "))]
+    RequirementsUnsatisfiable { missing: Vec<String> },
+
+    /// Host lacks the resources to admit another execution
+    #[error("Host overloaded ({resource}): {details}; retry after {retry_after_secs}s")]
+    HostOverloaded {
+        resource: String,
+        details: String,
+        retry_after_secs: u64,
+    },
+
+    /// The same code kept crashing identically and was not run again
+    #[error(
+        "Crash loop detected: {failures} identical crashes within {window_secs}s; \
+         retry after {retry_after_secs}s"
+    )]
+    CrashLoopDetected {
+        failures: u32,
+        window_secs: u64,
+        retry_after_secs: u64,
+        /// Result of the most recent crash
+        last_result: Box<ExecutionResult>,
+    },
+
+    /// Installing the request's declared dependencies failed
+    #[error("Dependency installation with '{command}' failed")]
+    DependencyInstallFailed {
+        command: String,
+        /// Result of the install execution
+        result: Box<ExecutionResult>,
+    },
+
+    /// Execution was stopped through `CyloExecutor::kill`
+    #[error("Execution '{execution_id}' was killed")]
+    ExecutionKilled { execution_id: String },
+
+    /// The execution budget the request draws on is spent
+    #[error("Execution budget exhausted: {resource} limit of {limit} is spent")]
+    BudgetExhausted {
+        resource: &'static str,
+        limit: String,
+    },
+
+    /// What is left of the execution budget is held by running executions
+    #[error(
+        "Execution budget busy: the rest of its {resource} is held by running executions; \
+         retry after {retry_after_secs}s"
+    )]
+    BudgetHeld {
+        resource: &'static str,
+        retry_after_secs: u64,
+    },
 }
 
 impl CyloError {
@@ -390,6 +526,145 @@ impl CyloError {
             message: message.into(),
         }
     }
+
+    /// Create an invalid request error for a specific field
+    pub fn invalid_request(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InvalidRequest {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Create an error listing the request requirements no backend meets
+    pub fn requirements_unsatisfiable(missing: Vec<String>) -> Self {
+        Self::RequirementsUnsatisfiable { missing }
+    }
+
+    /// Create a host overloaded error with a retry-after hint
+    pub fn host_overloaded(
+        resource: impl Into<String>,
+        details: impl Into<String>,
+        retry_after: std::time::Duration,
+    ) -> Self {
+        Self::HostOverloaded {
+            resource: resource.into(),
+            details: details.into(),
+            retry_after_secs: retry_after.as_secs().max(1),
+        }
+    }
+
+    /// Create a crash loop error carrying the last crash's result
+    pub fn crash_loop_detected(
+        failures: u32,
+        window: std::time::Duration,
+        retry_after: std::time::Duration,
+        last_result: ExecutionResult,
+    ) -> Self {
+        Self::CrashLoopDetected {
+            failures,
+            window_secs: window.as_secs(),
+            retry_after_secs: retry_after.as_secs().max(1),
+            last_result: Box::new(last_result),
+        }
+    }
+
+    /// Create a dependency installation error carrying the install's result
+    pub fn dependency_install_failed(command: impl Into<String>, result: ExecutionResult) -> Self {
+        Self::DependencyInstallFailed {
+            command: command.into(),
+            result: Box::new(result),
+        }
+    }
+
+    /// Create an error for an execution stopped by an operator
+    pub fn execution_killed(execution_id: impl Into<String>) -> Self {
+        Self::ExecutionKilled {
+            execution_id: execution_id.into(),
+        }
+    }
+
+    /// Create an error for a request whose execution budget is spent
+    pub fn budget_exhausted(resource: &'static str, limit: impl Into<String>) -> Self {
+        Self::BudgetExhausted {
+            resource,
+            limit: limit.into(),
+        }
+    }
+
+    /// Create an error for a request whose budget is held by running
+    /// executions rather than spent
+    pub fn budget_held(resource: &'static str, retry_after: std::time::Duration) -> Self {
+        Self::BudgetHeld {
+            resource,
+            retry_after_secs: retry_after.as_secs().max(1),
+        }
+    }
+
+    /// Suggested delay before retrying, for transient errors
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::HostOverloaded {
+                retry_after_secs, ..
+            }
+            | Self::CrashLoopDetected {
+                retry_after_secs, ..
+            }
+            | Self::BudgetHeld {
+                retry_after_secs, ..
+            } => Some(std::time::Duration::from_secs(*retry_after_secs)),
+            _ => None,
+        }
+    }
+
+    /// What kind of failure this is
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::InvalidConfiguration { .. } => ErrorCategory::Configuration,
+            Self::PlatformUnsupported { .. } | Self::RequirementsUnsatisfiable { .. } => {
+                ErrorCategory::Unsupported
+            }
+            Self::BackendUnavailable { .. } => ErrorCategory::Unavailable,
+            Self::InstanceNotFound { .. } => ErrorCategory::NotFound,
+            Self::InstanceConflict { .. } => ErrorCategory::Conflict,
+            Self::ExecutionFailed { .. }
+            | Self::DependencyInstallFailed { .. }
+            | Self::ExecutionKilled { .. } => ErrorCategory::ExecutionFailed,
+            Self::ExecutionTimeout { .. } | Self::ProvisioningTimeout { .. } => {
+                ErrorCategory::Timeout
+            }
+            Self::ResourceLimitExceeded { .. } | Self::BudgetExhausted { .. } => {
+                ErrorCategory::ResourceExhausted
+            }
+            Self::Internal { .. } => ErrorCategory::Internal,
+            Self::Validation { .. } | Self::InvalidRequest { .. } => ErrorCategory::InvalidInput,
+            Self::HostOverloaded { .. }
+            | Self::CrashLoopDetected { .. }
+            | Self::BudgetHeld { .. } => ErrorCategory::Overloaded,
+        }
+    }
+
+    /// Create an error for when routing finds no usable backend
+    pub fn no_backend_available() -> Self {
+        Self::BackendUnavailable {
+            backend: "executor",
+            reason: "No execution backend is available for this request".to_string(),
+        }
+    }
+
+    /// Create an error for a backend name the executor cannot construct
+    pub fn unsupported_backend(backend: impl Into<String>) -> Self {
+        Self::Validation {
+            message: format!("Unsupported backend: {}", backend.into()),
+        }
+    }
+
+    /// Create an executor configuration error
+    pub fn invalid_configuration(message: &'static str) -> Self {
+        Self::InvalidConfiguration {
+            backend: "executor",
+            message,
+        }
+    }
 }
 
 impl From<tokio::task::JoinError> for CyloError {
@@ -406,8 +681,8 @@ pub type CyloResult<T> = Result<T, CyloError>;
 /// Validate an instance name for compliance with naming rules
 ///
 /// Instance names must:
-/// - Be non-empty
-/// - Contain only alphanumeric characters, hyphens, and underscores
+/// - Be non-empty and at most 63 characters
+/// - Contain only ASCII letters, digits, hyphens, and underscores
 /// - Not start or end with hyphens or underscores
 ///
 /// # Arguments
@@ -417,55 +692,16 @@ pub type CyloResult<T> = Result<T, CyloError>;
 /// * `Ok(())` if the name is valid
 /// * `Err(CyloError)` if the name is invalid
 pub fn validate_instance_name(name: &str) -> CyloResult<()> {
-    if name.is_empty() {
-        return Err(CyloError::validation("Instance name cannot be empty"));
-    }
-
-    if name.len() > 63 {
-        return Err(CyloError::validation(
-            "Instance name cannot exceed 63 characters",
-        ));
-    }
-
-    // Check first and last characters
-    let first_char = name
-        .chars()
-        .next()
-        .ok_or_else(|| CyloError::validation("Instance name cannot be empty"))?;
-    let last_char = name
-        .chars()
-        .last()
-        .ok_or_else(|| CyloError::validation("Instance name cannot be empty"))?;
-
-    if first_char == '-' || first_char == '_' {
-        return Err(CyloError::validation(
-            "Instance name cannot start with hyphen or underscore",
-        ));
-    }
-
-    if last_char == '-' || last_char == '_' {
-        return Err(CyloError::validation(
-            "Instance name cannot end with hyphen or underscore",
-        ));
-    }
-
-    // Check all characters are valid
-    for ch in name.chars() {
-        if !ch.is_alphanumeric() && ch != '-' && ch != '_' {
-            return Err(CyloError::validation(
-                "Instance name can only contain alphanumeric characters, hyphens, and underscores",
-            ));
-        }
-    }
-
-    Ok(())
+    ids::validate_name(NameTarget::Instance, name)
 }
 
 /// Validate an environment specification for the given backend type
 ///
 /// Different backends have different validation requirements:
 /// - LandLock: Path must be absolute and exist
-/// - FireCracker/Apple: Image specification must include tag
+/// - FireCracker/Apple/Docker/K8sJob: Image specification must include a tag or
+///   digest
+/// - Wasm: A wasmtime CLI must be named
 ///
 /// # Arguments
 /// * `env` - The Cylo environment to validate
@@ -486,24 +722,28 @@ pub fn validate_environment_spec(env: &Cylo) -> CyloResult<()> {
 
             Ok(())
         }
-        Cylo::FireCracker(image) | Cylo::Apple(image) => {
+        Cylo::FireCracker(image)
+        | Cylo::Apple(image)
+        | Cylo::Docker(image)
+        | Cylo::K8sJob(image) => {
             if image.is_empty() {
                 return Err(CyloError::validation(
                     "Container image specification cannot be empty",
                 ));
             }
 
-            if !image.contains(':') {
-                return Err(CyloError::validation(
-                    "Container image must include tag (e.g., 'image:tag')",
-                ));
+            if let Err(reason) = ImageReference::parse(image) {
+                return Err(CyloError::validation(format!(
+                    "Invalid container image format (expected 'name:tag' or \
+                     'name@sha256:<digest>'): {reason}"
+                )));
             }
 
-            let parts: Vec<&str> = image.split(':').collect();
-            if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-                return Err(CyloError::validation(
-                    "Invalid container image format (expected 'name:tag')",
-                ));
+            Ok(())
+        }
+        Cylo::Wasm(wasmtime) => {
+            if wasmtime.is_empty() {
+                return Err(CyloError::validation("wasmtime program cannot be empty"));
             }
 
             Ok(())
@@ -522,21 +762,12 @@ pub fn validate_environment_spec(env: &Cylo) -> CyloResult<()> {
             Ok(())
         }
         Cylo::WindowsJob(workspace_name) => {
-            if workspace_name.is_empty() {
-                return Err(CyloError::validation("Workspace name cannot be empty"));
-            }
-
-            if !workspace_name
-                .chars()
-                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
-            {
-                return Err(CyloError::validation(
-                    "Workspace name must contain only alphanumeric characters, hyphens, and underscores",
-                ));
-            }
-
-            Ok(())
+            ids::validate_name(NameTarget::WindowsObject, workspace_name)
         }
+        Cylo::Custom { backend, config } => match registry::factory(backend) {
+            Some((_, factory)) => factory.validate(config),
+            None => Err(CyloError::unsupported_backend(backend.as_str())),
+        },
     }
 }
 
@@ -601,6 +832,14 @@ mod tests {
         // Empty image
         let empty = Cylo::Apple("".to_string());
         assert!(empty.validate().is_err());
+
+        // Digest-pinned image, with and without a tag
+        let digest = format!("sha256:{}", "c".repeat(64));
+        let pinned = Cylo::Apple(format!("python@{digest}"));
+        assert!(pinned.validate().is_ok());
+        assert!(validate_environment_spec(&pinned).is_ok());
+        let tagged = Cylo::FireCracker(format!("python:3.12@{digest}"));
+        assert!(validate_environment_spec(&tagged).is_ok());
     }
 
     #[test]
@@ -0,0 +1,286 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/active.rs
+//! ----------------------------------------------------------------------------
+//! Registry of executions currently in flight.
+//!
+//! Every routed execution is registered under its execution ID from the
+//! moment the ID is assigned until its result is returned, so operators can
+//! list what is running and stop a runaway execution without restarting the
+//! host process. Killing an execution terminates whatever the reaper tracks
+//! for it and makes the executor return `ExecutionKilled` straight away.
+//! ============================================================================
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::execution_env::{CyloError, CyloResult};
+use crate::reaper::{TrackedResource, global_reaper};
+
+/// Where an in-flight execution is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionState {
+    /// Choosing a backend and preparing an instance
+    Routing,
+    /// Waiting for the host guard to admit it
+    Admitting,
+    /// Running on its backend, possibly still queued for a slot
+    Running,
+    /// Done on the backend; results are being post-processed
+    Finishing,
+}
+
+/// Resources an in-flight execution holds at the time of the snapshot
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceSnapshot {
+    /// Processes, containers and VMs the reaper tracks for it
+    pub tracked: Vec<TrackedResource>,
+    /// Processes and threads below its tracked processes (Linux only)
+    pub processes: usize,
+    /// Resident memory of its tracked processes in bytes (Linux only)
+    pub memory_bytes: u64,
+}
+
+impl ResourceSnapshot {
+    /// Snapshot what the reaper tracks for an execution
+    fn capture(execution_id: &str) -> Self {
+        let tracked: Vec<TrackedResource> = global_reaper()
+            .tracked()
+            .into_iter()
+            .filter(|resource| {
+                resource
+                    .execution_id
+                    .as_deref()
+                    .is_some_and(|owner| owner == execution_id || owner == hedge_id(execution_id))
+            })
+            .collect();
+
+        let (processes, memory_bytes) = process_usage(&tracked);
+        Self {
+            tracked,
+            processes,
+            memory_bytes,
+        }
+    }
+}
+
+/// Process count and resident memory of the tracked processes' trees
+#[cfg(target_os = "linux")]
+fn process_usage(tracked: &[TrackedResource]) -> (usize, u64) {
+    use crate::backends::landlock::monitoring::{count_process_tree, get_memory_usage};
+    use crate::reaper::ResourceKind;
+
+    let (mut processes, mut memory_bytes) = (0, 0);
+    for resource in tracked {
+        if let ResourceKind::Process { pid } | ResourceKind::Vm { pid, .. } = resource.kind {
+            processes += count_process_tree(pid).unwrap_or(0);
+            memory_bytes += get_memory_usage(pid).unwrap_or(0);
+        }
+    }
+    (processes, memory_bytes)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_usage(_tracked: &[TrackedResource]) -> (usize, u64) {
+    (0, 0)
+}
+
+/// ID the hedge leg of a hedged execution tags its resources with
+fn hedge_id(execution_id: &str) -> String {
+    format!("{execution_id}-hedge")
+}
+
+/// An execution in flight, as returned by `CyloExecutor::active_executions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveExecution {
+    /// Execution ID, as set on the request
+    pub execution_id: String,
+    /// Requested language
+    pub language: String,
+    /// Where the execution is
+    pub state: ExecutionState,
+    /// Backend it was routed to, once routing is done
+    pub backend: Option<String>,
+    /// When it was registered
+    pub started_at: SystemTime,
+    /// Resources it holds
+    pub resources: ResourceSnapshot,
+}
+
+#[derive(Debug)]
+struct Entry {
+    language: String,
+    state: ExecutionState,
+    backend: Option<String>,
+    started_at: SystemTime,
+    killed: Arc<Notify>,
+}
+
+/// In-flight executions of one executor, by execution ID
+#[derive(Debug, Default)]
+pub(super) struct ActiveRegistry {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl ActiveRegistry {
+    /// Register an execution; it is removed again when the guard drops
+    pub(super) fn register(self: &Arc<Self>, execution_id: &str, language: &str) -> ActiveGuard {
+        let killed = Arc::new(Notify::new());
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(
+                execution_id.to_string(),
+                Entry {
+                    language: language.to_string(),
+                    state: ExecutionState::Routing,
+                    backend: None,
+                    started_at: SystemTime::now(),
+                    killed: Arc::clone(&killed),
+                },
+            );
+        ActiveGuard {
+            registry: Arc::clone(self),
+            execution_id: execution_id.to_string(),
+            killed,
+        }
+    }
+
+    /// Snapshot every registered execution, oldest first
+    pub(super) fn snapshot(&self) -> Vec<ActiveExecution> {
+        let mut executions: Vec<ActiveExecution> = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(execution_id, entry)| ActiveExecution {
+                execution_id: execution_id.clone(),
+                language: entry.language.clone(),
+                state: entry.state,
+                backend: entry.backend.clone(),
+                started_at: entry.started_at,
+                resources: ResourceSnapshot::default(),
+            })
+            .collect();
+
+        // Resources are looked up outside the lock; /proc reads can be slow
+        for execution in &mut executions {
+            execution.resources = ResourceSnapshot::capture(&execution.execution_id);
+        }
+        executions.sort_by_key(|execution| execution.started_at);
+        executions
+    }
+
+    /// Stop a registered execution
+    ///
+    /// # Returns
+    /// false if no execution with the ID is in flight
+    pub(super) fn kill(&self, execution_id: &str) -> bool {
+        let killed = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(execution_id)
+            .map(|entry| Arc::clone(&entry.killed));
+        let Some(killed) = killed else {
+            return false;
+        };
+
+        // Stored as a permit, so a kill between two awaits is not lost
+        killed.notify_one();
+        global_reaper().kill_execution(execution_id);
+        global_reaper().kill_execution(&hedge_id(execution_id));
+        true
+    }
+
+    fn update(&self, execution_id: &str, update: impl FnOnce(&mut Entry)) {
+        if let Some(entry) = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get_mut(execution_id)
+        {
+            update(entry);
+        }
+    }
+}
+
+/// Registration of one in-flight execution
+pub(super) struct ActiveGuard {
+    registry: Arc<ActiveRegistry>,
+    execution_id: String,
+    killed: Arc<Notify>,
+}
+
+impl ActiveGuard {
+    /// Record where the execution is
+    pub(super) fn set_state(&self, state: ExecutionState) {
+        self.registry.update(&self.execution_id, |entry| entry.state = state);
+    }
+
+    /// Record the backend the execution was routed to
+    pub(super) fn set_backend(&self, backend: &str) {
+        self.registry
+            .update(&self.execution_id, |entry| entry.backend = Some(backend.to_string()));
+    }
+
+    /// Await a step of the execution unless the execution is killed first
+    ///
+    /// # Returns
+    /// The step's output, or ExecutionKilled; a killed step keeps running
+    /// in the background until the resources it waits on are gone
+    pub(super) async fn unless_killed<T>(
+        &self,
+        step: impl Future<Output = CyloResult<T>>,
+    ) -> CyloResult<T> {
+        tokio::select! {
+            biased;
+            _ = self.killed.notified() => {
+                // Leave the permit for the next step
+                self.killed.notify_one();
+                Err(CyloError::execution_killed(&self.execution_id))
+            }
+            output = step => output,
+        }
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        self.registry
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&self.execution_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn killed_execution_stops_waiting() {
+        let registry = Arc::new(ActiveRegistry::default());
+        let guard = registry.register("exec-1", "python");
+        guard.set_backend("LandLock");
+        guard.set_state(ExecutionState::Running);
+
+        let active = registry.snapshot();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].backend.as_deref(), Some("LandLock"));
+        assert_eq!(active[0].state, ExecutionState::Running);
+
+        assert!(!registry.kill("exec-2"));
+        assert!(registry.kill("exec-1"));
+        let outcome = guard.unless_killed(std::future::pending::<CyloResult<()>>()).await;
+        assert!(matches!(outcome, Err(CyloError::ExecutionKilled { .. })));
+
+        drop(guard);
+        assert!(registry.snapshot().is_empty());
+    }
+}
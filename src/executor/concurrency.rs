@@ -0,0 +1,203 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/concurrency.rs
+//! ----------------------------------------------------------------------------
+//! Per-backend concurrency limits shared by every executor in the process.
+//!
+//! Each backend gets one semaphore sized from its configured limit so
+//! expensive backends (e.g. FireCracker VM boots) cannot overload the host
+//! while cheap ones keep running many jobs in parallel. When an executor
+//! asks under a different limit the semaphore is resized: permits are added
+//! for a higher limit, and for a lower one free permits are forgotten and
+//! held ones retired as they are released.
+//! ============================================================================
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Semaphore of one backend and the limit its permits add up to
+#[derive(Debug)]
+struct BackendSlots {
+    semaphore: Arc<Semaphore>,
+    /// Limit last asked for; only changed under the registry lock
+    limit: u32,
+    /// Held permits to retire instead of returning, owed since the limit
+    /// was lowered while they were out
+    debt: Arc<AtomicUsize>,
+}
+
+impl BackendSlots {
+    fn new(limit: u32) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit as usize)),
+            limit,
+            debt: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Make the permits add up to a new limit
+    fn resize(&mut self, limit: u32) {
+        if limit > self.limit {
+            // Owed permits are forgiven before new ones are added
+            let mut grow = (limit - self.limit) as usize;
+            let forgiven = self
+                .debt
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |debt| {
+                    Some(debt.saturating_sub(grow))
+                })
+                .map_or(0, |debt| debt.min(grow));
+            grow -= forgiven;
+            self.semaphore.add_permits(grow);
+        } else if limit < self.limit {
+            let shrink = (self.limit - limit) as usize;
+            let forgotten = self.semaphore.forget_permits(shrink);
+            self.debt.fetch_add(shrink - forgotten, Ordering::AcqRel);
+        }
+        self.limit = limit;
+    }
+}
+
+/// Semaphores by backend
+type SlotRegistry = Mutex<HashMap<String, BackendSlots>>;
+
+/// Process-wide registry of backend semaphores
+static BACKEND_SLOTS: OnceLock<SlotRegistry> = OnceLock::new();
+
+/// An execution slot on a backend, given back when dropped
+///
+/// Retired instead when the backend's limit was lowered while it was held.
+#[derive(Debug)]
+pub struct SlotPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    debt: Arc<AtomicUsize>,
+}
+
+impl Drop for SlotPermit {
+    fn drop(&mut self) {
+        let owed = self
+            .debt
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |debt| debt.checked_sub(1))
+            .is_ok();
+        if owed && let Some(permit) = self.permit.take() {
+            permit.forget();
+        }
+    }
+}
+
+/// Get the semaphore for a backend, sized for a limit
+///
+/// # Returns
+/// The semaphore and the debt its permits are retired against
+fn backend_semaphore(
+    backend_name: &str,
+    limit: u32,
+) -> CyloResult<(Arc<Semaphore>, Arc<AtomicUsize>)> {
+    let registry = BACKEND_SLOTS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut slots = registry
+        .lock()
+        .map_err(|e| CyloError::internal(format!("Concurrency registry poisoned: {e}")))?;
+
+    let slots = slots
+        .entry(backend_name.to_string())
+        .or_insert_with(|| BackendSlots::new(limit));
+    slots.resize(limit);
+    Ok((Arc::clone(&slots.semaphore), Arc::clone(&slots.debt)))
+}
+
+/// Wait for an execution slot on a backend
+///
+/// # Arguments
+/// * `backend_name` - Backend the execution will run on
+/// * `limit` - Maximum concurrent executions, or None for unlimited
+///
+/// # Returns
+/// Permit held for the duration of the execution, or None when unlimited
+pub async fn acquire_slot(
+    backend_name: &str,
+    limit: Option<u32>,
+) -> CyloResult<Option<SlotPermit>> {
+    let Some(limit) = limit else {
+        return Ok(None);
+    };
+
+    if limit == 0 {
+        return Err(CyloError::backend_unavailable(
+            "executor",
+            format!("{backend_name} has max_concurrent_executions set to 0"),
+        ));
+    }
+
+    let (semaphore, debt) = backend_semaphore(backend_name, limit)?;
+    let permit = semaphore
+        .acquire_owned()
+        .await
+        .map_err(|e| CyloError::internal(format!("Concurrency semaphore closed: {e}")))?;
+    Ok(Some(SlotPermit {
+        permit: Some(permit),
+        debt,
+    }))
+}
+
+/// Number of free execution slots for a backend, if it is limited
+pub fn available_slots(backend_name: &str) -> Option<usize> {
+    let slots = BACKEND_SLOTS.get()?.lock().ok()?;
+    slots
+        .get(backend_name)
+        .map(|slots| slots.semaphore.available_permits())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn limit_bounds_concurrent_permits() {
+        let first = acquire_slot("ConcurrencyTest", Some(1)).await.unwrap();
+        assert!(first.is_some());
+        assert_eq!(available_slots("ConcurrencyTest"), Some(0));
+
+        drop(first);
+        assert_eq!(available_slots("ConcurrencyTest"), Some(1));
+    }
+
+    #[tokio::test]
+    async fn limit_changes_resize_the_one_semaphore() {
+        let held = acquire_slot("MixedLimitTest", Some(1)).await.unwrap();
+        let wider = acquire_slot("MixedLimitTest", Some(2)).await.unwrap();
+        assert!(wider.is_some());
+
+        // Lowering the limit again must still count both held permits
+        let narrow = || {
+            tokio::time::timeout(
+                std::time::Duration::from_millis(50),
+                acquire_slot("MixedLimitTest", Some(1)),
+            )
+        };
+        assert!(narrow().await.is_err());
+        drop(held);
+        assert!(narrow().await.is_err());
+        assert_eq!(available_slots("MixedLimitTest"), Some(0));
+
+        drop(wider);
+        let permit = narrow().await.unwrap().unwrap();
+        assert!(permit.is_some());
+        assert_eq!(available_slots("MixedLimitTest"), Some(0));
+        drop(permit);
+        assert_eq!(available_slots("MixedLimitTest"), Some(1));
+    }
+
+    #[tokio::test]
+    async fn unlimited_backends_skip_semaphore() {
+        assert!(acquire_slot("UnlimitedTest", None).await.unwrap().is_none());
+        assert_eq!(available_slots("UnlimitedTest"), None);
+    }
+
+    #[tokio::test]
+    async fn zero_limit_is_rejected() {
+        assert!(acquire_slot("ZeroTest", Some(0)).await.is_err());
+    }
+}
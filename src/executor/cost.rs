@@ -0,0 +1,154 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/cost.rs
+//! ----------------------------------------------------------------------------
+//! Per-execution usage metering and cost attribution.
+//!
+//! Every completed execution is metered into CPU-seconds, GiB-seconds, and
+//! VM boots. A pluggable `CostModel` prices that usage; the result carries
+//! the cost, metrics aggregate it per backend and per tenant, and an audit
+//! record is logged under the `cylo::audit` target.
+//! ============================================================================
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::backends::{ExecutionCost, ExecutionRequest, ExecutionResult};
+use crate::logging::targets;
+
+/// Tenant that executions without an explicit tenant are attributed to
+pub const DEFAULT_TENANT: &str = "default";
+
+const BYTES_PER_GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Prices metered execution usage
+///
+/// Implement this to bill with custom rates, per-backend pricing, or
+/// discounts; `RateCard` covers flat per-unit pricing.
+pub trait CostModel: fmt::Debug + Send + Sync {
+    /// Price the usage of one execution
+    ///
+    /// # Arguments
+    /// * `backend` - Backend that ran the execution
+    /// * `tenant` - Tenant the execution is attributed to
+    /// * `usage` - Metered usage; its `amount` is not yet set
+    fn price(&self, backend: &str, tenant: &str, usage: &ExecutionCost) -> f64;
+}
+
+/// Flat per-unit rates
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateCard {
+    /// Price per CPU-second
+    pub cpu_second: f64,
+    /// Price per GiB-second of reserved memory
+    pub gb_second: f64,
+    /// Surcharge per microVM boot
+    pub vm_boot: f64,
+}
+
+impl CostModel for RateCard {
+    fn price(&self, _backend: &str, _tenant: &str, usage: &ExecutionCost) -> f64 {
+        usage.cpu_seconds * self.cpu_second
+            + usage.gb_seconds * self.gb_second
+            + f64::from(usage.vm_boots) * self.vm_boot
+    }
+}
+
+/// Tenant a request's usage is attributed to
+pub(crate) fn tenant_of(request: &ExecutionRequest) -> &str {
+    request.tenant.as_deref().unwrap_or(DEFAULT_TENANT)
+}
+
+/// Measure the billable usage of a finished execution
+pub(crate) fn meter(
+    backend: &str,
+    request: &ExecutionRequest,
+    result: &ExecutionResult,
+) -> ExecutionCost {
+    let reserved_bytes = request
+        .limits
+        .max_memory
+        .unwrap_or(0)
+        .max(result.resource_usage.peak_memory);
+
+    ExecutionCost {
+        cpu_seconds: result.resource_usage.cpu_time_ms as f64 / 1000.0,
+        gb_seconds: reserved_bytes as f64 / BYTES_PER_GIB * result.duration.as_secs_f64(),
+        vm_boots: u32::from(backend == "FireCracker"),
+        amount: 0.0,
+    }
+}
+
+/// Meter and price an execution, then write its audit record
+///
+/// # Arguments
+/// * `model` - Cost model pricing the usage; None leaves the amount at zero
+/// * `backend` - Backend that ran the execution
+/// * `request` - Executed request, carrying the tenant and execution id
+/// * `result` - Result to meter
+pub(crate) fn attribute(
+    model: Option<&dyn CostModel>,
+    backend: &str,
+    request: &ExecutionRequest,
+    result: &ExecutionResult,
+) -> ExecutionCost {
+    let tenant = tenant_of(request);
+    let mut cost = meter(backend, request, result);
+    if let Some(model) = model {
+        cost.amount = model.price(backend, tenant, &cost);
+    }
+
+    info!(
+        target: targets::AUDIT,
+        "execution={} tenant={} backend={} exit_code={} cpu_seconds={:.3} \
+         gb_seconds={:.3} vm_boots={} cost={:.6}",
+        request.execution_id.as_deref().unwrap_or("-"),
+        tenant,
+        backend,
+        result.exit_code,
+        cost.cpu_seconds,
+        cost.gb_seconds,
+        cost.vm_boots,
+        cost.amount
+    );
+
+    cost
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::ResourceLimits;
+    use std::time::Duration;
+
+    #[test]
+    fn rate_card_prices_cpu_memory_and_boots() {
+        let request = ExecutionRequest::new("x", "python")
+            .with_tenant("acme")
+            .with_limits(ResourceLimits {
+                max_memory: Some(512 * 1024 * 1024),
+                ..ResourceLimits::default()
+            });
+        let mut result = ExecutionResult::success("");
+        result.duration = Duration::from_secs(4);
+        result.resource_usage.cpu_time_ms = 1500;
+
+        let rates = RateCard {
+            cpu_second: 2.0,
+            gb_second: 1.0,
+            vm_boot: 10.0,
+        };
+        let cost = attribute(Some(&rates), "FireCracker", &request, &result);
+
+        assert_eq!(cost.cpu_seconds, 1.5);
+        assert_eq!(cost.gb_seconds, 2.0);
+        assert_eq!(cost.vm_boots, 1);
+        assert_eq!(cost.amount, 1.5 * 2.0 + 2.0 + 10.0);
+
+        let unpriced = attribute(None, "LandLock", &request, &result);
+        assert_eq!((unpriced.vm_boots, unpriced.amount), (0, 0.0));
+        assert_eq!(tenant_of(&request), "acme");
+    }
+}
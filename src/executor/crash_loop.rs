@@ -0,0 +1,318 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/crash_loop.rs
+//! ----------------------------------------------------------------------------
+//! Crash loop detection for repeatedly submitted crashing code.
+//!
+//! Agents tend to retry a failed execution verbatim. When the code crashes
+//! deterministically every retry boots a sandbox only to crash the same way,
+//! so once the same code has crashed with the same signal often enough
+//! within a window, further attempts are refused with the last result
+//! instead of being executed again. Crashes are counted per tenant, so one
+//! tenant's crashing code never locks another out.
+//! ============================================================================
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::backends::{ExecutionRequest, ExecutionResult};
+use crate::execution_env::{CyloError, CyloResult};
+
+/// When repeated crashes short-circuit further attempts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashLoopConfig {
+    /// Whether crash loops are detected
+    pub enabled: bool,
+    /// Identical crashes within `window` after which attempts are refused
+    pub threshold: u32,
+    /// Period crashes are counted over
+    pub window: Duration,
+}
+
+impl Default for CrashLoopConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 3,
+            window: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Recent crashes of one piece of code
+#[derive(Debug)]
+struct CrashHistory {
+    /// Signal every counted crash ended with
+    signal: i32,
+    /// When each counted crash happened, oldest first
+    crashed_at: VecDeque<Instant>,
+    /// Result of the most recent crash
+    last_result: ExecutionResult,
+}
+
+/// Crash history per code fingerprint, shared by an executor's executions
+#[derive(Debug, Default)]
+pub(crate) struct CrashLoopTracker {
+    histories: HashMap<String, CrashHistory>,
+}
+
+impl CrashLoopTracker {
+    /// Refuse a request whose code is in a crash loop
+    ///
+    /// # Arguments
+    /// * `config` - Detection thresholds
+    /// * `fingerprint` - Fingerprint of the request's code
+    /// * `now` - Current time
+    ///
+    /// # Returns
+    /// Ok(()) if the request may run, CrashLoopDetected otherwise
+    pub(crate) fn check(
+        &mut self,
+        config: &CrashLoopConfig,
+        fingerprint: &str,
+        now: Instant,
+    ) -> CyloResult<()> {
+        if !config.enabled {
+            return Ok(());
+        }
+        let Some(history) = self.histories.get_mut(fingerprint) else {
+            return Ok(());
+        };
+
+        expire(&mut history.crashed_at, config.window, now);
+        let failures = history.crashed_at.len() as u32;
+        if failures < config.threshold {
+            return Ok(());
+        }
+
+        // Attempts are admitted again once the oldest counted crash ages out
+        let retry_after = history
+            .crashed_at
+            .front()
+            .map_or(config.window, |oldest| {
+                config.window.saturating_sub(now.duration_since(*oldest))
+            });
+        Err(CyloError::crash_loop_detected(
+            failures,
+            config.window,
+            retry_after,
+            history.last_result.clone(),
+        ))
+    }
+
+    /// Record how an execution of the fingerprinted code ended
+    ///
+    /// A crash with a different signal than the previous ones starts a new
+    /// count; any result that is not a crash clears the history.
+    ///
+    /// # Arguments
+    /// * `config` - Detection thresholds
+    /// * `fingerprint` - Fingerprint of the request's code
+    /// * `result` - Result of the execution
+    /// * `now` - Current time
+    pub(crate) fn record(
+        &mut self,
+        config: &CrashLoopConfig,
+        fingerprint: &str,
+        result: &ExecutionResult,
+        now: Instant,
+    ) {
+        if !config.enabled {
+            return;
+        }
+
+        // Drop histories whose crashes have all aged out
+        self.histories.retain(|_, history| {
+            expire(&mut history.crashed_at, config.window, now);
+            !history.crashed_at.is_empty()
+        });
+
+        let Some(crash) = &result.crash else {
+            self.histories.remove(fingerprint);
+            return;
+        };
+
+        let history = self
+            .histories
+            .entry(fingerprint.to_string())
+            .or_insert_with(|| CrashHistory {
+                signal: crash.signal,
+                crashed_at: VecDeque::new(),
+                last_result: result.clone(),
+            });
+        if history.signal != crash.signal {
+            history.signal = crash.signal;
+            history.crashed_at.clear();
+        }
+        history.crashed_at.push_back(now);
+        history.last_result = result.clone();
+    }
+}
+
+/// Fingerprint identifying "the same code" across retries
+///
+/// Covers the tenant, the language, the code, its input, files, blobs,
+/// environment variables, compiler options and SQL seeds; identical code
+/// fed different input, run in a different environment or built
+/// differently is a different attempt.
+pub(crate) fn fingerprint(request: &ExecutionRequest) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.language.as_bytes());
+    hasher.update([0]);
+    hasher.update(request.code.as_bytes());
+    hasher.update([0]);
+    if let Some(input) = &request.input {
+        hasher.update(input.as_bytes());
+    }
+    for (path, contents) in &request.files {
+        hasher.update([0]);
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(contents.as_bytes());
+    }
+    hasher.update([0]);
+    for (path, blob) in &request.blob_files {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update(blob.digest.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.update([0]);
+    let mut env_vars: Vec<_> = request.env_vars.iter().collect();
+    env_vars.sort();
+    for (key, value) in env_vars {
+        hasher.update(key.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.update([0]);
+    if let Some(tenant) = &request.tenant {
+        hasher.update(tenant.as_bytes());
+    }
+    hasher.update([0]);
+    if let Some(compiler) = &request.compiler
+        && let Ok(options) = serde_json::to_vec(compiler)
+    {
+        hasher.update(options);
+    }
+    hasher.update([0]);
+    if let Some(sql) = &request.sql
+        && let Ok(options) = serde_json::to_vec(sql)
+    {
+        hasher.update(options);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Forget crashes older than the window
+fn expire(crashed_at: &mut VecDeque<Instant>, window: Duration, now: Instant) {
+    while crashed_at
+        .front()
+        .is_some_and(|at| now.duration_since(*at) >= window)
+    {
+        crashed_at.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::{BlobFile, CrashReport};
+
+    fn crashed(signal: i32) -> ExecutionResult {
+        let mut result = ExecutionResult::failure(128 + signal, "");
+        result.crash = Some(CrashReport::new(signal));
+        result
+    }
+
+    #[test]
+    fn identical_crashes_trip_the_guard() {
+        let config = CrashLoopConfig::default();
+        let mut tracker = CrashLoopTracker::default();
+        let start = Instant::now();
+
+        for attempt in 0..3u64 {
+            let now = start + Duration::from_secs(attempt);
+            assert!(tracker.check(&config, "abc", now).is_ok());
+            tracker.record(&config, "abc", &crashed(11), now);
+        }
+
+        let now = start + Duration::from_secs(10);
+        match tracker.check(&config, "abc", now) {
+            Err(CyloError::CrashLoopDetected {
+                failures,
+                retry_after_secs,
+                last_result,
+                ..
+            }) => {
+                assert_eq!(failures, 3);
+                assert_eq!(retry_after_secs, 290);
+                assert_eq!(last_result.exit_code, 139);
+            }
+            other => panic!("expected CrashLoopDetected, got {other:?}"),
+        }
+        assert!(tracker.check(&config, "other", now).is_ok());
+
+        // Once the oldest crash leaves the window, one more attempt is allowed
+        assert!(tracker.check(&config, "abc", start + config.window).is_ok());
+    }
+
+    #[test]
+    fn differing_signals_and_successes_reset_the_count() {
+        let config = CrashLoopConfig::default();
+        let mut tracker = CrashLoopTracker::default();
+        let now = Instant::now();
+
+        tracker.record(&config, "abc", &crashed(11), now);
+        tracker.record(&config, "abc", &crashed(11), now);
+        tracker.record(&config, "abc", &crashed(6), now);
+        assert!(tracker.check(&config, "abc", now).is_ok());
+
+        tracker.record(&config, "abc", &crashed(6), now);
+        tracker.record(&config, "abc", &ExecutionResult::success("ok"), now);
+        tracker.record(&config, "abc", &crashed(6), now);
+        assert!(tracker.check(&config, "abc", now).is_ok());
+    }
+
+    #[test]
+    fn fingerprint_covers_what_the_code_runs_with() {
+        let request = ExecutionRequest::new("print(1)", "python");
+        assert_eq!(fingerprint(&request), fingerprint(&request.clone()));
+        assert_ne!(
+            fingerprint(&request),
+            fingerprint(&ExecutionRequest::new("print(1)", "ruby"))
+        );
+        assert_ne!(
+            fingerprint(&request),
+            fingerprint(&request.clone().with_input("1"))
+        );
+
+        let mut with_file = request.clone();
+        with_file.files.insert("data.csv".to_string(), "1,2".to_string());
+        assert_ne!(fingerprint(&request), fingerprint(&with_file));
+        let mut with_env = request.clone();
+        with_env.env_vars.insert("SEED".to_string(), "7".to_string());
+        assert_ne!(fingerprint(&request), fingerprint(&with_env));
+        let mut with_blob = request.clone();
+        with_blob.blob_files.insert(
+            "model.bin".to_string(),
+            BlobFile {
+                digest: "sha256:00".to_string(),
+                source: "/store/00".into(),
+            },
+        );
+        assert_ne!(fingerprint(&request), fingerprint(&with_blob));
+        assert_ne!(
+            fingerprint(&request),
+            fingerprint(&request.clone().with_tenant("acme"))
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/deadline.rs
+//! ----------------------------------------------------------------------------
+//! End-to-end execution budget and per-phase timing.
+//!
+//! A request's `timeout` bounds only its program; backends do not count
+//! image pulls, VM boots, jail setup or waiting for a slot against it. The
+//! total budget bounds everything the executor does for a request, from
+//! admission to the backend's answer. Every execution records how long it
+//! spent in each phase, so a slow or timed-out execution shows whether the
+//! time went to provisioning, queueing or the program itself.
+//! ============================================================================
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{ExecutionRequest, ExecutionResult};
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Result metadata key with the milliseconds spent in each phase, as a
+/// JSON object keyed by phase name
+pub const PHASE_TIMINGS_METADATA: &str = "phase_timings_ms";
+
+/// Result metadata key naming the phase the total budget ran out in
+pub const TIMEOUT_PHASE_METADATA: &str = "timeout_phase";
+
+/// Stage of an execution inside the executor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Phase {
+    /// Validation, dependency installs, routing and host admission
+    Preparing,
+    /// Creating or fetching the backend instance
+    Provisioning,
+    /// Waiting for a concurrency slot
+    Queued,
+    /// On the backend: sandbox setup, the program and teardown
+    Running,
+}
+
+impl Phase {
+    /// Name used in result metadata
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Preparing => "preparing",
+            Phase::Provisioning => "provisioning",
+            Phase::Queued => "queued",
+            Phase::Running => "running",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ClockState {
+    current: Phase,
+    entered_at: Instant,
+    spent: BTreeMap<Phase, Duration>,
+}
+
+/// Tracks which phase an execution is in and how long each phase took
+#[derive(Debug, Clone)]
+pub(crate) struct PhaseClock(Arc<Mutex<ClockState>>);
+
+impl Default for PhaseClock {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(ClockState {
+            current: Phase::Preparing,
+            entered_at: Instant::now(),
+            spent: BTreeMap::new(),
+        })))
+    }
+}
+
+impl PhaseClock {
+    /// Leave the current phase for `phase`
+    pub(crate) fn enter(&self, phase: Phase) {
+        let mut state = self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.entered_at);
+        let current = state.current;
+        *state.spent.entry(current).or_default() += elapsed;
+        state.current = phase;
+        state.entered_at = now;
+    }
+
+    /// Phase the execution is in
+    pub(crate) fn current(&self) -> Phase {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .current
+    }
+
+    /// Time spent per phase so far, the current phase included
+    pub(crate) fn timings(&self) -> BTreeMap<Phase, Duration> {
+        let state = self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut spent = state.spent.clone();
+        *spent.entry(state.current).or_default() += state.entered_at.elapsed();
+        spent
+    }
+
+    /// Record the time spent per phase under `PHASE_TIMINGS_METADATA`
+    pub(crate) fn record(&self, metadata: &mut HashMap<String, String>) {
+        let timings: serde_json::Map<String, serde_json::Value> = self
+            .timings()
+            .into_iter()
+            .map(|(phase, spent)| {
+                let millis = u64::try_from(spent.as_millis()).unwrap_or(u64::MAX);
+                (phase.name().to_string(), millis.into())
+            })
+            .collect();
+        metadata.insert(
+            PHASE_TIMINGS_METADATA.to_string(),
+            serde_json::Value::Object(timings).to_string(),
+        );
+    }
+}
+
+/// Point at which an execution's total budget runs out
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Deadline {
+    budget: Duration,
+    expires_at: tokio::time::Instant,
+}
+
+impl Deadline {
+    /// Start the clock on a request's budget: its own, else the executor's
+    /// default
+    ///
+    /// # Returns
+    /// None when neither sets a budget
+    pub(crate) fn start(request: &ExecutionRequest, default: Option<Duration>) -> Option<Self> {
+        let budget = request.total_timeout.or(default)?;
+        Some(Self {
+            budget,
+            expires_at: tokio::time::Instant::now() + budget,
+        })
+    }
+
+    /// Await `future`, giving up once the budget runs out
+    ///
+    /// # Returns
+    /// None if the budget ran out first
+    pub(crate) async fn race<F: Future>(&self, future: F) -> Option<F::Output> {
+        tokio::time::timeout_at(self.expires_at, future).await.ok()
+    }
+
+    /// Error for a budget that ran out before a backend was chosen
+    pub(crate) fn error(&self) -> CyloError {
+        CyloError::ExecutionTimeout {
+            backend: "executor",
+            timeout_secs: self.budget.as_secs(),
+        }
+    }
+
+    /// Result for a budget that ran out once a backend was chosen
+    pub(crate) fn exceeded(&self, clock: &PhaseClock) -> ExecutionResult {
+        let phase = clock.current();
+        let mut result = ExecutionResult::timed_out(
+            "",
+            format!(
+                "Execution exceeded its total budget of {}ms while {}",
+                self.budget.as_millis(),
+                phase.name()
+            ),
+            self.budget,
+        );
+        result
+            .metadata
+            .insert(TIMEOUT_PHASE_METADATA.to_string(), phase.name().to_string());
+        clock.record(&mut result.metadata);
+        result
+    }
+}
+
+/// Await a preparation step within the deadline, if there is one
+pub(crate) async fn within<T, F>(deadline: Option<&Deadline>, step: F) -> CyloResult<T>
+where
+    F: Future<Output = CyloResult<T>>,
+{
+    match deadline {
+        Some(deadline) => deadline.race(step).await.unwrap_or_else(|| Err(deadline.error())),
+        None => step.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn clock_attributes_time_to_phases() {
+        let clock = PhaseClock::default();
+        std::thread::sleep(Duration::from_millis(5));
+        clock.enter(Phase::Provisioning);
+        clock.enter(Phase::Running);
+        let timings = clock.timings();
+        assert!(timings[&Phase::Preparing] >= Duration::from_millis(5));
+        assert!(timings.contains_key(&Phase::Provisioning));
+
+        let mut metadata = HashMap::new();
+        clock.record(&mut metadata);
+        let recorded: serde_json::Value =
+            serde_json::from_str(&metadata[PHASE_TIMINGS_METADATA]).unwrap();
+        assert!(recorded["preparing"].as_u64().unwrap() >= 5);
+
+        let request = ExecutionRequest::new("1", "python");
+        assert!(Deadline::start(&request, None).is_none());
+        let request = request.with_total_timeout(Duration::from_millis(10));
+        let deadline = Deadline::start(&request, Some(Duration::from_secs(60))).unwrap();
+        let slow = tokio::time::sleep(Duration::from_secs(60));
+        assert!(deadline.race(slow).await.is_none());
+        assert!(within(Some(&deadline), async { Ok(()) }).await.is_err());
+
+        let result = deadline.exceeded(&clock);
+        assert!(result.is_timed_out());
+        assert_eq!(result.metadata[TIMEOUT_PHASE_METADATA], "running");
+    }
+}
@@ -0,0 +1,304 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/dependencies.rs
+//! ----------------------------------------------------------------------------
+//! Dependency installation for JavaScript requests that ship a package.json.
+//!
+//! The install runs as its own sandboxed execution under the dependency
+//! phase's limits, writing node_modules into a private host cache directory
+//! keyed by the manifest and lockfile and by the environment, node version
+//! and architecture the install ran in, since native modules are built for
+//! them. The request then runs in that same environment with the directory
+//! exposed read-only and on NODE_PATH, so an identical lockfile is installed
+//! once per environment and reused by every later execution there.
+//! ============================================================================
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::backends::arch::Arch;
+use crate::backends::{ExecutionRequest, ResourceLimits, language};
+use crate::execution_env::{CyloError, CyloResult};
+use crate::platform_utils;
+
+/// Result metadata key telling whether node_modules was installed or reused
+pub const DEPENDENCIES_METADATA: &str = "dependencies.node_modules";
+
+/// Manifest whose presence triggers the install
+const MANIFEST: &str = "package.json";
+
+/// How declared dependencies are installed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DependencyConfig {
+    /// Whether requests shipping a package.json get node_modules installed
+    pub enabled: bool,
+    /// Timeout of the install execution
+    pub timeout: Duration,
+    /// Limits of the install execution; it needs network access
+    pub limits: ResourceLimits,
+    /// Host directory installed trees are cached in; created closed to
+    /// other users
+    pub cache_dir: PathBuf,
+}
+
+impl Default for DependencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout: Duration::from_secs(300),
+            limits: ResourceLimits {
+                max_memory: Some(2 * 1024 * 1024 * 1024), // 2GB
+                max_cpu_time: Some(300),
+                max_processes: Some(64),
+                max_file_size: Some(512 * 1024 * 1024), // 512MB
+                max_network_bandwidth: Some(50 * 1024 * 1024), // 50MB/s
+                max_read_bps: None,
+                max_write_bps: None,
+                cpuset: None,
+                numa_nodes: None,
+            },
+            cache_dir: platform_utils::user_state_dir().join("node_modules"),
+        }
+    }
+}
+
+/// node_modules a request needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NodeDependencies {
+    /// Install command, chosen by the lockfile present
+    command: &'static str,
+    /// Manifest and lockfile copied into the install
+    files: BTreeMap<String, String>,
+    /// Cache key covering the command, manifest, lockfile, node version,
+    /// architecture and environment
+    key: String,
+}
+
+impl NodeDependencies {
+    /// Dependencies declared by a request, if it is JavaScript with a
+    /// package.json
+    ///
+    /// A pnpm lockfile selects `pnpm install --frozen-lockfile`, an npm
+    /// lockfile `npm ci`; without either `npm install` resolves versions
+    /// itself and the result is cached by the manifest alone.
+    ///
+    /// # Arguments
+    /// * `request` - Request that may declare dependencies
+    /// * `environment` - Environment the install and the request run in,
+    ///   naming the backend and its image
+    pub(crate) fn of(request: &ExecutionRequest, environment: &str) -> Option<Self> {
+        let manifest = request.files.get(MANIFEST).filter(|_| Self::declared_by(request))?;
+
+        let (command, lockfile) = if request.files.contains_key("pnpm-lock.yaml") {
+            ("pnpm install --frozen-lockfile", Some("pnpm-lock.yaml"))
+        } else if request.files.contains_key("package-lock.json") {
+            ("npm ci --no-audit --no-fund", Some("package-lock.json"))
+        } else {
+            ("npm install --no-audit --no-fund", None)
+        };
+
+        let mut files = BTreeMap::from([(MANIFEST.to_string(), manifest.clone())]);
+        // Native modules are built for the node, libc and architecture of
+        // the install; the language carries any pinned node version
+        let arch = request.arch.or_else(Arch::host).map(|arch| arch.to_string());
+        let mut hasher = Sha256::new();
+        for part in [environment, request.language.as_str(), arch.as_deref().unwrap_or_default()] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update(command.as_bytes());
+        hasher.update([0]);
+        hasher.update(manifest.as_bytes());
+        if let Some(name) = lockfile {
+            let contents = &request.files[name];
+            hasher.update([0]);
+            hasher.update(contents.as_bytes());
+            files.insert(name.to_string(), contents.clone());
+        }
+        let key = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        Some(Self {
+            command,
+            files,
+            key,
+        })
+    }
+
+    /// Whether a request is JavaScript shipping a package.json
+    pub(crate) fn declared_by(request: &ExecutionRequest) -> bool {
+        request.files.contains_key(MANIFEST)
+            && language::resolve(&request.language).is_some_and(|spec| spec.name == "javascript")
+    }
+
+    /// Install command
+    pub(crate) fn command(&self) -> &'static str {
+        self.command
+    }
+
+    /// Cached install for these dependencies
+    pub(crate) fn cached(&self, config: &DependencyConfig) -> Option<PathBuf> {
+        let dir = config.cache_dir.join(&self.key);
+        dir.join("node_modules").is_dir().then_some(dir)
+    }
+
+    /// Create an empty directory for an install to write into
+    ///
+    /// Each install gets its own directory, so concurrent installs of the
+    /// same lockfile never see each other's partial trees.
+    pub(crate) fn staging_dir(&self, config: &DependencyConfig) -> CyloResult<PathBuf> {
+        let dir = config
+            .cache_dir
+            .join(format!("{}.{}", self.key, uuid::Uuid::new_v4().simple()));
+        platform_utils::create_private_dir(&config.cache_dir)
+            .and_then(|()| fs::create_dir(&dir))
+            .map_err(|e| {
+                CyloError::internal(format!("Failed to create dependency directory: {}", e))
+            })?;
+        Ok(dir)
+    }
+
+    /// Request installing the dependencies into `staging`
+    ///
+    /// The install carries over where and how the request may run, but
+    /// uses the dependency phase's own limits and timeout.
+    pub(crate) fn install_request(
+        &self,
+        config: &DependencyConfig,
+        request: &ExecutionRequest,
+        staging: &Path,
+    ) -> ExecutionRequest {
+        let target = format!("'{}'", staging.display().to_string().replace('\'', r"'\''"));
+        let copied = self.files.keys().cloned().collect::<Vec<_>>().join(" ");
+        let code = format!(
+            "cp {copied} {target}/ || exit 1\ncd {target} || exit 1\nexec {}\n",
+            self.command
+        );
+
+        let mut install = ExecutionRequest::new(code, "bash")
+            .with_limits(config.limits.clone())
+            .with_timeout(config.timeout)
+            .with_writable_path(staging);
+        install.files = self.files.clone();
+        install.required_backend = request.required_backend.clone();
+        install.required_isolation = request.required_isolation;
+        install.tenant = request.tenant.clone();
+        install.environment = request.environment.clone();
+        install.dns = request.dns.clone();
+        install
+    }
+
+    /// Move a finished install into the cache
+    ///
+    /// # Returns
+    /// The cached install; when another install of the same dependencies
+    /// finished first, its tree is kept and `staging` is removed
+    pub(crate) fn commit(&self, config: &DependencyConfig, staging: &Path) -> PathBuf {
+        let dir = config.cache_dir.join(&self.key);
+        if fs::rename(staging, &dir).is_err() {
+            let _ = fs::remove_dir_all(staging);
+        }
+        dir
+    }
+}
+
+/// Run a request against an installed node_modules tree
+///
+/// The tree is exposed read-only and put on NODE_PATH, which `require()`
+/// consults; a NODE_PATH the request sets itself wins.
+pub(crate) fn attach(request: &mut ExecutionRequest, installed: &Path) {
+    request.readable_paths.push(installed.to_path_buf());
+    request
+        .env_vars
+        .entry("NODE_PATH".to_string())
+        .or_insert_with(|| installed.join("node_modules").display().to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENV: &str = "Docker(node:20-alpine)";
+
+    fn config() -> DependencyConfig {
+        DependencyConfig {
+            cache_dir: std::env::temp_dir()
+                .join(format!("cylo_deps_test_{}", uuid::Uuid::new_v4().simple())),
+            ..DependencyConfig::default()
+        }
+    }
+
+    #[test]
+    fn lockfile_selects_installer_and_key() {
+        let request = ExecutionRequest::new("require('left-pad')", "javascript")
+            .with_file(MANIFEST, r#"{"dependencies":{"left-pad":"1.3.0"}}"#);
+        let npm = NodeDependencies::of(&request, ENV).expect("package.json declares dependencies");
+        assert_eq!(npm.command(), "npm install --no-audit --no-fund");
+
+        let locked = request.clone().with_file("package-lock.json", "{}");
+        let ci = NodeDependencies::of(&locked, ENV).expect("package.json declares dependencies");
+        assert_eq!(ci.command(), "npm ci --no-audit --no-fund");
+        assert_ne!(ci.key, npm.key);
+        assert_eq!(NodeDependencies::of(&locked.clone(), ENV), Some(ci.clone()));
+
+        // Installs are not shared across environments, node versions or
+        // architectures
+        let elsewhere = NodeDependencies::of(&locked, "LandLock(/tmp/cylo_landlock)");
+        assert_ne!(elsewhere.expect("declared").key, ci.key);
+        let mut pinned = locked.clone();
+        pinned.language = "javascript@22".to_string();
+        assert_ne!(NodeDependencies::of(&pinned, ENV).expect("declared").key, ci.key);
+        let arm = locked.clone().with_arch(Arch::Arm64);
+        let x86 = locked.clone().with_arch(Arch::X86_64);
+        assert_ne!(
+            NodeDependencies::of(&arm, ENV).expect("declared").key,
+            NodeDependencies::of(&x86, ENV).expect("declared").key
+        );
+
+        let pnpm = request.clone().with_file("pnpm-lock.yaml", "lockfileVersion: 9");
+        let pnpm = NodeDependencies::of(&pnpm, ENV).expect("package.json declares dependencies");
+        assert!(pnpm.command().starts_with("pnpm install"));
+
+        let python = ExecutionRequest::new("print(1)", "python").with_file(MANIFEST, "{}");
+        assert_eq!(NodeDependencies::of(&python, ENV), None);
+    }
+
+    #[test]
+    fn finished_installs_are_cached_and_attached() {
+        let config = config();
+        let request = ExecutionRequest::new("x", "js").with_file(MANIFEST, "{}");
+        let deps = NodeDependencies::of(&request, ENV).expect("package.json declares dependencies");
+        assert_eq!(deps.cached(&config), None);
+
+        let staging = deps.staging_dir(&config).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&config.cache_dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        let install = deps.install_request(&config, &request, &staging);
+        assert_eq!(install.language, "bash");
+        assert_eq!(install.writable_paths, [staging.clone()]);
+        assert!(install.files.contains_key(MANIFEST));
+        assert!(install.validate().is_ok());
+
+        fs::create_dir_all(staging.join("node_modules")).unwrap();
+        let installed = deps.commit(&config, &staging);
+        assert_eq!(deps.cached(&config), Some(installed.clone()));
+
+        let mut run = request.clone();
+        attach(&mut run, &installed);
+        assert_eq!(run.readable_paths, [installed.clone()]);
+        assert!(run.env_vars["NODE_PATH"].ends_with("node_modules"));
+        let _ = fs::remove_dir_all(&config.cache_dir);
+    }
+}
@@ -5,43 +5,102 @@
 //! ============================================================================
 
 use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::task::AbortHandle;
+
 use crate::execution_env::{CyloInstance, CyloError, CyloResult};
 use crate::backends::{
     ExecutionRequest, ExecutionResult, BackendConfig, create_backend,
 };
 use crate::instance_manager::global_instance_manager;
-use super::types::OptimizationConfig;
+use super::concurrency;
+use super::deadline::{Phase, PhaseClock};
+use super::reload::ExecutorConfig;
 
 /// Execute with specific backend and instance management
+///
+/// Executions are admitted through a per-backend semaphore sized from
+/// `BackendConfig::max_concurrent_executions`, falling back to the
+/// executor's `max_concurrent` preference when the backend config sets no
+/// limit. A reused instance additionally runs at most
+/// `BackendConfig::max_parallel` executions at once. Progress through
+/// provisioning, queueing and the backend is recorded on `clock`; dropping
+/// the returned future aborts the backend's execution task.
 pub async fn execute_with_backend(
     backend_name: String,
     instance: CyloInstance,
-    request: ExecutionRequest,
-    optimization: OptimizationConfig,
+    request: Arc<ExecutionRequest>,
+    config: Arc<ExecutorConfig>,
+    clock: PhaseClock,
 ) -> CyloResult<ExecutionResult> {
+    let optimization = &config.optimization;
+    let registry_credentials = &config.registry_credentials;
+    let default_concurrency = config.preferences.max_concurrent.get(&backend_name).copied();
+    clock.enter(Phase::Provisioning);
     let manager = global_instance_manager();
+    let with_credentials = |config: BackendConfig| {
+        if registry_credentials.is_empty() {
+            config
+        } else {
+            config.with_registry_credentials(registry_credentials.clone())
+        }
+    };
 
     // Register instance if using instance reuse
-    if optimization.instance_reuse {
-        if let Err(e) = manager.register_instance(instance.clone()).await {
-            // Instance might already exist, try to get it
-            if !matches!(e, CyloError::InstanceConflict { .. }) {
-                return Err(e);
-            }
+    if optimization.instance_reuse
+        && let Err(e) = manager
+            .register_instance_with_config(
+                instance.clone(),
+                with_credentials(manager.default_config.clone()),
+            )
+            .await?
+    {
+        // Instance might already exist, try to get it
+        if !matches!(e, CyloError::InstanceConflict { .. }) {
+            return Err(e);
         }
     }
 
     // Get backend instance
     let backend = if optimization.instance_reuse {
-        manager.get_instance(&instance.id()).await?
+        manager.get_instance(&instance.id()).await??
     } else {
         // Create temporary backend
-        let config = BackendConfig::new(&format!("temp_{}", backend_name));
+        let config = with_credentials(BackendConfig::new(&format!("temp_{}", backend_name)));
         Arc::from(create_backend(&instance.env, config)?)
     };
 
+    // Wait for a free slot on the shared instance, then on this backend
+    let limit = backend
+        .get_config()
+        .max_concurrent_executions
+        .or(default_concurrency);
+    clock.enter(Phase::Queued);
+    let queued_at = Instant::now();
+    let instance_permit = if optimization.instance_reuse {
+        Some(manager.acquire_execution_slot(&instance.id()).await??)
+    } else {
+        None
+    };
+    let permit = concurrency::acquire_slot(&backend_name, limit).await?;
+    let queue_wait = queued_at.elapsed();
+
     // Execute code
-    let result = backend.execute_code(request).await;
+    // Backends take the request by value; it is copied only here, once a
+    // slot is held, and not at all when this was the last reference
+    clock.enter(Phase::Running);
+    let task = backend.execute_code(Arc::unwrap_or_clone(request));
+    let _abort = AbortOnDrop(task.abort_handle());
+    let mut result = task.await?;
+    drop(permit);
+    drop(instance_permit);
+
+    if limit.is_some() || optimization.instance_reuse {
+        result
+            .metadata
+            .insert("queue_wait_ms".to_string(), queue_wait.as_millis().to_string());
+    }
 
     // Clean up if not using instance reuse
     if !optimization.instance_reuse {
@@ -53,3 +112,13 @@ pub async fn execute_with_backend(
 
     Ok(result)
 }
+
+/// Aborts a backend's execution task when its caller stops waiting for it,
+/// e.g. because the execution's total budget ran out
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
@@ -0,0 +1,625 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/federation.rs
+//! ----------------------------------------------------------------------------
+//! Routing of requests to remote cylo workers.
+//!
+//! A fleet mixes hosts that offer different backends: macOS nodes run Apple
+//! containers, Linux nodes run FireCracker VMs. Each worker advertises the
+//! backends it can run and how busy it is; an executor that cannot serve a
+//! request locally forwards it to the least loaded worker whose backends
+//! meet the request's requirements and the executor's own preferences.
+//! Workers are reached through the `RemoteWorker` trait; `HttpWorker`
+//! speaks JSON over HTTPS to a worker serving `ADVERTISEMENT_PATH` and
+//! `EXECUTE_PATH` from `CyloExecutor::advertisement` and `execute`.
+//! Requests carrying secrets, environment variables or git credentials,
+//! only go to workers whose transport is authenticated.
+//! ============================================================================
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::{Method, Request};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::client::legacy::Client;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::rt::TokioExecutor;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::types::BackendPreferences;
+use crate::async_task::{AsyncTask, AsyncTaskBuilder};
+use crate::backends::{
+    Arch, BackendCapabilities, ExecutionRequest, ExecutionResult, IsolationLevel,
+};
+use crate::execution_env::{CyloError, CyloResult};
+use crate::logging::targets;
+
+/// Path a worker serves its `WorkerAdvertisement` on (GET)
+pub const ADVERTISEMENT_PATH: &str = "/v1/advertisement";
+
+/// Path a worker accepts an `ExecutionRequest` on (POST), answering with
+/// its `ExecutionResult`
+pub const EXECUTE_PATH: &str = "/v1/execute";
+
+/// Result metadata key naming the remote worker that ran the execution
+pub const WORKER_METADATA: &str = "worker";
+
+/// `backend_config` key a forwarded request carries, naming the executor
+/// that forwarded it; such requests are never forwarded again
+pub const FEDERATED_FROM_KEY: &str = "cylo.federated_from";
+
+/// Advertisements older than this are fetched again before routing
+const ADVERTISEMENT_TTL: Duration = Duration::from_secs(10);
+
+/// Time a worker has to answer an advertisement request
+const ADVERTISE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time a worker has beyond the request's own budget to return a result
+const EXECUTE_GRACE: Duration = Duration::from_secs(120);
+
+/// What a worker can run and how busy it is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerAdvertisement {
+    /// Worker identity, unique within the fleet
+    pub worker_id: String,
+    /// Operating system of the worker's host, e.g. "macos"
+    pub os: String,
+    /// Native architecture of the worker's host
+    pub arch: Option<Arch>,
+    /// Backends available on the worker with their performance ratings
+    pub backends: Vec<(String, u8)>,
+    /// Executions currently in flight on the worker
+    pub in_flight: usize,
+    /// Executions the worker expects to run at once
+    pub capacity: usize,
+}
+
+impl WorkerAdvertisement {
+    /// Fraction of its capacity in use; may exceed 1.0 when it queues
+    pub fn load(&self) -> f64 {
+        self.in_flight as f64 / self.capacity.max(1) as f64
+    }
+
+    /// Best-rated advertised backend able to serve a request, honoring the
+    /// request's requirements and the forwarding executor's preferences
+    pub fn backend_for(
+        &self,
+        request: &ExecutionRequest,
+        preferences: &BackendPreferences,
+    ) -> Option<String> {
+        if request.arch.is_some_and(|arch| self.arch != Some(arch)) {
+            return None;
+        }
+        self.backends
+            .iter()
+            .filter(|(name, _)| request.required_backend.as_ref().is_none_or(|b| b == name))
+            .filter(|(name, _)| {
+                request.required_isolation.is_none_or(|required| {
+                    IsolationLevel::of_backend(name).is_some_and(|level| level >= required)
+                })
+            })
+            .filter(|(name, _)| {
+                BackendCapabilities::of_backend(name)
+                    .is_none_or(|caps| caps.fits_memory(request.limits.max_memory))
+            })
+            .filter(|(name, _)| preferences.denial_reason(name, &request.language).is_none())
+            // Ties keep the first advertised backend
+            .fold(None, |best: Option<&(String, u8)>, candidate| match best {
+                Some(best) if best.1 >= candidate.1 => Some(best),
+                _ => Some(candidate),
+            })
+            .map(|(name, _)| name.clone())
+    }
+}
+
+/// Whether a request depends on resources of this host and so cannot run
+/// anywhere else; a streamed input is read here and cannot be forwarded
+pub(crate) fn host_bound(request: &ExecutionRequest) -> bool {
+    request.input_stream.is_some()
+        || !request.readable_paths.is_empty()
+        || !request.writable_paths.is_empty()
+        || !request.volumes.is_empty()
+        || request.sandbox_state.is_some()
+        || !request.blob_files.is_empty()
+        || request.go_module_cache.is_some()
+        || request.python_env.is_some()
+        || request.backend_config.contains_key(FEDERATED_FROM_KEY)
+}
+
+/// Whether a request carries secrets that only an authenticated transport
+/// may take off this host
+pub(crate) fn carries_secrets(request: &ExecutionRequest) -> bool {
+    !request.env_vars.is_empty()
+        || request
+            .git_repo
+            .as_ref()
+            .is_some_and(|checkout| checkout.credentials.is_some())
+}
+
+/// A cylo worker requests can be forwarded to
+pub trait RemoteWorker: fmt::Debug + Send + Sync {
+    /// Worker identity, unique within the fleet
+    fn id(&self) -> &str;
+
+    /// Fetch the worker's current advertisement
+    fn advertise(&self) -> AsyncTask<CyloResult<WorkerAdvertisement>>;
+
+    /// Run a request on the worker
+    fn execute(&self, request: ExecutionRequest) -> AsyncTask<CyloResult<ExecutionResult>>;
+
+    /// Whether the worker is verified and verifies this executor, so
+    /// requests carrying secrets may be sent to it
+    fn authenticated(&self) -> bool {
+        false
+    }
+}
+
+type HttpClient = Client<HttpsConnector<HttpConnector>, Full<Bytes>>;
+
+/// Worker reached with JSON over HTTPS
+///
+/// The worker's certificate is verified against the host's trust roots,
+/// or the bundled web PKI roots when the host has none. With a bearer
+/// token the worker can verify this executor in turn; only then do
+/// requests carrying secrets go to it. A plain `http://` URL is accepted
+/// for workers on a trusted network, but the token is never sent over it.
+#[derive(Clone)]
+pub struct HttpWorker {
+    id: String,
+    base_url: String,
+    token: Option<String>,
+    client: HttpClient,
+}
+
+impl fmt::Debug for HttpWorker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpWorker")
+            .field("id", &self.id)
+            .field("base_url", &self.base_url)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl HttpWorker {
+    /// Create a client of a worker
+    ///
+    /// # Arguments
+    /// * `id` - Worker identity, unique within the fleet
+    /// * `base_url` - Where the worker listens, e.g. "https://mac-01:7311"
+    ///
+    /// # Returns
+    /// The client, or an error if no TLS configuration could be built
+    pub fn new<I: Into<String>, U: Into<String>>(id: I, base_url: U) -> CyloResult<Self> {
+        // Explicit provider, so other rustls users in the process cannot
+        // make the default ambiguous
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = match HttpsConnectorBuilder::new()
+            .with_provider_and_native_roots(Arc::clone(&provider))
+        {
+            Ok(builder) => builder,
+            Err(e) => {
+                warn!(target: targets::EXECUTOR, "No native trust roots, using web PKI: {}", e);
+                HttpsConnectorBuilder::new()
+                    .with_provider_and_webpki_roots(provider)
+                    .map_err(|e| CyloError::internal(format!("TLS setup failed: {}", e)))?
+            }
+        };
+        let connector = builder.https_or_http().enable_http1().build();
+        Ok(Self {
+            id: id.into(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+            client: Client::builder(TokioExecutor::new()).build(connector),
+        })
+    }
+
+    /// Present a bearer token the worker checks before serving requests
+    pub fn with_bearer_token<T: Into<String>>(mut self, token: T) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Whether requests go over TLS
+    fn encrypted(&self) -> bool {
+        self.base_url
+            .get(..8)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
+    }
+
+    /// Send a request and decode the JSON answer
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Bytes,
+        limit: Duration,
+    ) -> CyloResult<T> {
+        let unavailable = |reason: String| CyloError::backend_unavailable("Federation", reason);
+        let mut request = Request::builder()
+            .method(method)
+            .uri(format!("{}{}", self.base_url, path))
+            .header(CONTENT_TYPE, "application/json");
+        if let Some(token) = self.token.as_ref().filter(|_| self.encrypted()) {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = request
+            .body(Full::new(body))
+            .map_err(|e| unavailable(format!("Invalid request to worker {}: {}", self.id, e)))?;
+
+        let response = tokio::time::timeout(limit, self.client.request(request))
+            .await
+            .map_err(|_| {
+                unavailable(format!("Worker {} did not answer within {:?}", self.id, limit))
+            })?
+            .map_err(|e| unavailable(format!("Worker {} is unreachable: {}", self.id, e)))?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| unavailable(format!("Failed to read answer of {}: {}", self.id, e)))?
+            .to_bytes();
+
+        if !status.is_success() {
+            return Err(CyloError::execution_failed(
+                "Federation",
+                format!(
+                    "Worker {} answered {}: {}",
+                    self.id,
+                    status,
+                    String::from_utf8_lossy(&body).trim()
+                ),
+            ));
+        }
+        serde_json::from_slice(&body).map_err(|e| {
+            CyloError::internal(format!("Worker {} sent an invalid answer: {}", self.id, e))
+        })
+    }
+}
+
+impl RemoteWorker for HttpWorker {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn advertise(&self) -> AsyncTask<CyloResult<WorkerAdvertisement>> {
+        let worker = self.clone();
+        AsyncTaskBuilder::new(async move {
+            worker
+                .call(Method::GET, ADVERTISEMENT_PATH, Bytes::new(), ADVERTISE_TIMEOUT)
+                .await
+        })
+        .spawn()
+    }
+
+    fn execute(&self, request: ExecutionRequest) -> AsyncTask<CyloResult<ExecutionResult>> {
+        let worker = self.clone();
+        AsyncTaskBuilder::new(async move {
+            if carries_secrets(&request) && !worker.authenticated() {
+                return Err(CyloError::backend_unavailable(
+                    "Federation",
+                    format!(
+                        "Worker {} is not reached over authenticated HTTPS; \
+                         requests with secrets are not sent to it",
+                        worker.id
+                    ),
+                ));
+            }
+            let limit = request.total_timeout.unwrap_or(request.timeout) + EXECUTE_GRACE;
+            let body = serde_json::to_vec(&request)
+                .map_err(|e| CyloError::internal(format!("Failed to encode request: {}", e)))?;
+            worker
+                .call(Method::POST, EXECUTE_PATH, Bytes::from(body), limit)
+                .await
+        })
+        .spawn()
+    }
+
+    fn authenticated(&self) -> bool {
+        self.encrypted() && self.token.is_some()
+    }
+}
+
+/// Registered remote workers with their latest advertisements
+#[derive(Debug, Default)]
+pub(crate) struct Federation {
+    workers: RwLock<Vec<WorkerEntry>>,
+}
+
+#[derive(Debug, Clone)]
+struct WorkerEntry {
+    worker: Arc<dyn RemoteWorker>,
+    /// None until fetched, and after the worker failed to answer
+    advertisement: Option<WorkerAdvertisement>,
+    refreshed: Option<Instant>,
+    /// Requests forwarded to it and not yet answered, counted on top of
+    /// its advertised load until the next advertisement
+    dispatched: Arc<AtomicUsize>,
+}
+
+impl Federation {
+    /// Register a worker, replacing one with the same id
+    pub(crate) fn add(&self, worker: Arc<dyn RemoteWorker>) {
+        let mut workers = self.workers.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        workers.retain(|entry| entry.worker.id() != worker.id());
+        workers.push(WorkerEntry {
+            worker,
+            advertisement: None,
+            refreshed: None,
+            dispatched: Arc::new(AtomicUsize::new(0)),
+        });
+    }
+
+    /// Remove a worker; returns whether it was registered
+    pub(crate) fn remove(&self, worker_id: &str) -> bool {
+        let mut workers = self.workers.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let before = workers.len();
+        workers.retain(|entry| entry.worker.id() != worker_id);
+        workers.len() != before
+    }
+
+    /// Latest advertisements of the workers that answered
+    pub(crate) fn advertisements(&self) -> Vec<WorkerAdvertisement> {
+        self.entries()
+            .into_iter()
+            .filter_map(|entry| entry.advertisement)
+            .collect()
+    }
+
+    /// Fetch advertisements again, of every worker or only stale ones
+    ///
+    /// A worker that fails to answer is left out of routing until it
+    /// answers again.
+    pub(crate) async fn refresh(&self, force: bool) -> Vec<WorkerAdvertisement> {
+        let stale = self.entries().into_iter().filter(|entry| {
+            force || entry.refreshed.is_none_or(|at| at.elapsed() >= ADVERTISEMENT_TTL)
+        });
+        // Spawned up front so workers are asked concurrently
+        let pending: Vec<_> = stale
+            .map(|entry| (entry.worker.id().to_string(), entry.worker.advertise()))
+            .collect();
+
+        for (worker_id, task) in pending {
+            let advertisement = match task.await {
+                Ok(Ok(advertisement)) => Some(advertisement),
+                Ok(Err(e)) => {
+                    warn!(target: targets::EXECUTOR, "Worker {} not advertising: {}", worker_id, e);
+                    None
+                }
+                Err(e) => {
+                    warn!(target: targets::EXECUTOR, "Advertising {} panicked: {}", worker_id, e);
+                    None
+                }
+            };
+            let mut workers =
+                self.workers.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(entry) = workers.iter_mut().find(|entry| entry.worker.id() == worker_id) {
+                entry.advertisement = advertisement;
+                entry.refreshed = Some(Instant::now());
+                entry.dispatched.store(0, Ordering::Relaxed);
+            }
+        }
+        self.advertisements()
+    }
+
+    /// Choose the worker to forward a request to
+    ///
+    /// # Returns
+    /// None when no worker is registered, the request is bound to this
+    /// host, or no worker's backends can serve it
+    pub(crate) async fn dispatch(
+        &self,
+        request: &ExecutionRequest,
+        preferences: &BackendPreferences,
+    ) -> Option<Dispatch> {
+        if host_bound(request) || self.entries().is_empty() {
+            return None;
+        }
+        self.refresh(false).await;
+
+        let entries = self.entries();
+        let (index, backend) = choose(&entries, request, preferences)?;
+        let entry = &entries[index];
+        entry.dispatched.fetch_add(1, Ordering::Relaxed);
+        Some(Dispatch {
+            worker: Arc::clone(&entry.worker),
+            backend,
+            _slot: DispatchSlot(Arc::clone(&entry.dispatched)),
+        })
+    }
+
+    fn entries(&self) -> Vec<WorkerEntry> {
+        self.workers
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+/// Least loaded worker able to serve a request, and the backend it would
+/// use; ties keep the earlier registered worker, and requests carrying
+/// secrets only go to authenticated workers
+fn choose(
+    entries: &[WorkerEntry],
+    request: &ExecutionRequest,
+    preferences: &BackendPreferences,
+) -> Option<(usize, String)> {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| !carries_secrets(request) || entry.worker.authenticated())
+        .filter_map(|(index, entry)| {
+            let advertisement = entry.advertisement.as_ref()?;
+            let backend = advertisement.backend_for(request, preferences)?;
+            let in_flight = advertisement.in_flight + entry.dispatched.load(Ordering::Relaxed);
+            let load = in_flight as f64 / advertisement.capacity.max(1) as f64;
+            Some((index, backend, load))
+        })
+        .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(index, backend, _)| (index, backend))
+}
+
+/// A request's claim on a remote worker, released when dropped
+pub(crate) struct Dispatch {
+    worker: Arc<dyn RemoteWorker>,
+    backend: String,
+    _slot: DispatchSlot,
+}
+
+struct DispatchSlot(Arc<AtomicUsize>);
+
+impl Drop for DispatchSlot {
+    fn drop(&mut self) {
+        // The count may have been reset by an advertisement since
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+}
+
+impl Dispatch {
+    /// Worker the request goes to
+    pub(crate) fn worker_id(&self) -> &str {
+        self.worker.id()
+    }
+
+    /// Backend the worker is expected to run the request on
+    pub(crate) fn backend(&self) -> &str {
+        &self.backend
+    }
+
+    /// Forward the request and wait for its result
+    ///
+    /// # Arguments
+    /// * `request` - Request to forward
+    /// * `origin` - Identity of this executor, recorded on the request
+    pub(crate) async fn execute(
+        &self,
+        request: &ExecutionRequest,
+        origin: &str,
+    ) -> CyloResult<ExecutionResult> {
+        let mut forwarded = request.clone();
+        forwarded
+            .backend_config
+            .insert(FEDERATED_FROM_KEY.to_string(), origin.to_string());
+        let mut result = self.worker.execute(forwarded).await.map_err(|e| {
+            CyloError::internal(format!("Forwarding to {} failed: {}", self.worker_id(), e))
+        })??;
+        result
+            .metadata
+            .insert(WORKER_METADATA.to_string(), self.worker_id().to_string());
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct FakeWorker(WorkerAdvertisement, bool);
+
+    impl RemoteWorker for FakeWorker {
+        fn id(&self) -> &str {
+            &self.0.worker_id
+        }
+
+        fn advertise(&self) -> AsyncTask<CyloResult<WorkerAdvertisement>> {
+            let advertisement = self.0.clone();
+            AsyncTaskBuilder::new(async move { Ok(advertisement) }).spawn()
+        }
+
+        fn execute(&self, request: ExecutionRequest) -> AsyncTask<CyloResult<ExecutionResult>> {
+            AsyncTaskBuilder::new(async move {
+                assert!(request.backend_config.contains_key(FEDERATED_FROM_KEY));
+                Ok(ExecutionResult::success("remote"))
+            })
+            .spawn()
+        }
+
+        fn authenticated(&self) -> bool {
+            self.1
+        }
+    }
+
+    fn worker(id: &str, backends: &[&str], in_flight: usize) -> Arc<dyn RemoteWorker> {
+        Arc::new(FakeWorker(
+            WorkerAdvertisement {
+                worker_id: id.to_string(),
+                os: "linux".to_string(),
+                arch: Arch::host(),
+                backends: backends.iter().map(|name| (name.to_string(), 80)).collect(),
+                in_flight,
+                capacity: 4,
+            },
+            // Only the busy Mac is reached over an authenticated transport
+            id == "mac-busy",
+        ))
+    }
+
+    #[tokio::test]
+    async fn requests_go_to_the_least_loaded_capable_worker() {
+        let federation = Federation::default();
+        let preferences = BackendPreferences::default();
+        let apple = ExecutionRequest::new("print(1)", "python").with_backend("Apple");
+        assert!(federation.dispatch(&apple, &preferences).await.is_none());
+
+        federation.add(worker("mac-busy", &["Apple"], 3));
+        federation.add(worker("mac-idle", &["Apple"], 0));
+        federation.add(worker("linux", &["FireCracker", "LandLock"], 0));
+
+        let dispatch = federation.dispatch(&apple, &preferences).await.unwrap();
+        assert_eq!((dispatch.worker_id(), dispatch.backend()), ("mac-idle", "Apple"));
+        let result = dispatch.execute(&apple, "origin").await.unwrap();
+        assert_eq!(result.metadata[WORKER_METADATA], "mac-idle");
+
+        // Forwarded requests count toward load until answered
+        let second = federation.dispatch(&apple, &preferences).await.unwrap();
+        assert_eq!(second.worker_id(), "mac-idle");
+        drop(dispatch);
+
+        let vm = ExecutionRequest::new("print(1)", "python")
+            .with_required_isolation(IsolationLevel::MicroVM);
+        let dispatch = federation.dispatch(&vm, &preferences).await.unwrap();
+        assert_eq!((dispatch.worker_id(), dispatch.backend()), ("linux", "FireCracker"));
+
+        // Host paths and already forwarded requests stay where they are
+        let local = apple.clone().with_readable_path("/srv/data");
+        assert!(federation.dispatch(&local, &preferences).await.is_none());
+        let forwarded = apple.clone().with_backend_config(FEDERATED_FROM_KEY, "elsewhere");
+        assert!(federation.dispatch(&forwarded, &preferences).await.is_none());
+
+        // Secrets only leave for authenticated workers
+        let secret = apple.clone().with_env("API_KEY", "hunter2");
+        let dispatch = federation.dispatch(&secret, &preferences).await.unwrap();
+        assert_eq!(dispatch.worker_id(), "mac-busy");
+        drop(dispatch);
+
+        assert!(federation.remove("linux"));
+        assert!(federation.dispatch(&vm, &preferences).await.is_none());
+        assert_eq!(federation.advertisements().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn http_workers_authenticate_only_over_tls() {
+        let plain = HttpWorker::new("plain", "http://127.0.0.1:9/")
+            .unwrap()
+            .with_bearer_token("s3cret");
+        assert!(!plain.authenticated());
+        assert!(!format!("{:?}", plain).contains("s3cret"));
+        let request = ExecutionRequest::new("print(1)", "python").with_env("API_KEY", "hunter2");
+        let refused = plain.execute(request).await.unwrap().unwrap_err();
+        assert!(refused.to_string().contains("not sent"), "{}", refused);
+
+        let tls = HttpWorker::new("tls", "https://worker.example:7311").unwrap();
+        assert!(!tls.authenticated());
+        assert!(tls.with_bearer_token("t").authenticated());
+    }
+}
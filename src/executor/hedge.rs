@@ -0,0 +1,283 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/hedge.rs
+//! ----------------------------------------------------------------------------
+//! Speculative execution for the `Hedged` routing strategy.
+//!
+//! The request starts on the fastest backend. If it has not finished after
+//! the hedge delay, a copy starts on the runner-up backend; the first
+//! successful result wins and the other copy is cancelled.
+//! ============================================================================
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinError;
+use tracing::debug;
+
+use super::deadline::PhaseClock;
+use super::execution;
+use super::reload::ExecutorConfig;
+use super::types::OptimizationConfig;
+use crate::async_task::AsyncTask;
+use crate::backends::{ExecutionRequest, ExecutionResult};
+use crate::execution_env::{CyloError, CyloInstance, CyloResult};
+use crate::instance_manager::global_instance_manager;
+use crate::logging::targets;
+use crate::reaper::global_reaper;
+
+/// A backend and instance a hedged request may run on
+#[derive(Debug, Clone)]
+pub(crate) struct HedgeLeg {
+    pub backend_name: String,
+    pub instance: CyloInstance,
+}
+
+/// Which copy of a hedged request produced the returned result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Leg {
+    Primary,
+    Hedge,
+}
+
+impl Leg {
+    fn other(self) -> Self {
+        match self {
+            Leg::Primary => Leg::Hedge,
+            Leg::Hedge => Leg::Primary,
+        }
+    }
+}
+
+/// Result of racing the primary against a delayed hedge
+#[derive(Debug)]
+pub(crate) struct RaceOutcome {
+    /// Result returned to the caller
+    pub result: CyloResult<ExecutionResult>,
+    /// Copy that produced `result`
+    pub winner: Leg,
+    /// Whether the hedge copy was started at all
+    pub hedge_launched: bool,
+    /// Whether the losing copy was still running and had to be aborted
+    pub loser_aborted: bool,
+}
+
+/// Outcome of a hedged execution, with the leg that produced it
+#[derive(Debug)]
+pub(crate) struct HedgedExecution {
+    pub leg: HedgeLeg,
+    pub result: CyloResult<ExecutionResult>,
+    pub hedge_launched: bool,
+    pub hedge_won: bool,
+}
+
+/// Run a request on `primary`, hedging onto `secondary` after `delay`
+///
+/// The hedge copy gets its own execution id (`<id>-hedge`) so the reaper
+/// can kill whichever copy loses without touching the winner.
+pub(crate) async fn execute_hedged(
+    primary: HedgeLeg,
+    secondary: HedgeLeg,
+    request: &Arc<ExecutionRequest>,
+    delay: Duration,
+    config: &Arc<ExecutorConfig>,
+) -> HedgedExecution {
+    let primary_id = request.execution_id.clone().unwrap_or_default();
+    let hedge_id = format!("{}-hedge", primary_id);
+
+    let primary_task = spawn_leg(&primary, Arc::clone(request), config);
+    let launch_hedge = || {
+        debug!(
+            target: targets::EXECUTOR,
+            "hedge: {} still running after {:?}, starting copy on {}",
+            primary.backend_name, delay, secondary.backend_name
+        );
+        let mut hedge_request = ExecutionRequest::clone(request);
+        hedge_request.execution_id = Some(hedge_id.clone());
+        // Only the primary streams output; two copies would interleave
+        hedge_request.output_sink = None;
+        spawn_leg(&secondary, Arc::new(hedge_request), config)
+    };
+
+    let outcome = race(primary_task, launch_hedge, delay).await;
+
+    if outcome.hedge_launched {
+        let (winner, winner_id, loser, loser_id) = match outcome.winner {
+            Leg::Primary => (&primary, &primary_id, &secondary, &hedge_id),
+            Leg::Hedge => (&secondary, &hedge_id, &primary, &primary_id),
+        };
+        debug!(
+            target: targets::EXECUTOR,
+            "hedge: {} won over {}",
+            winner.backend_name,
+            loser.backend_name
+        );
+
+        global_reaper().finish_execution(winner_id);
+        global_reaper().kill_execution(loser_id);
+        if outcome.loser_aborted {
+            release_aborted(loser, &config.optimization).await;
+        }
+    }
+
+    let hedge_won = outcome.winner == Leg::Hedge;
+    HedgedExecution {
+        leg: if hedge_won { secondary } else { primary },
+        result: outcome.result,
+        hedge_launched: outcome.hedge_launched,
+        hedge_won,
+    }
+}
+
+/// Race a running primary against a hedge launched after `delay`
+///
+/// The first successful result wins. If the first copy to finish failed,
+/// the other copy is awaited; when both fail the primary's result is
+/// returned. The losing copy's task is aborted if still running.
+pub(crate) async fn race<F>(
+    mut primary: AsyncTask<CyloResult<ExecutionResult>>,
+    launch_hedge: F,
+    delay: Duration,
+) -> RaceOutcome
+where
+    F: FnOnce() -> AsyncTask<CyloResult<ExecutionResult>>,
+{
+    tokio::select! {
+        joined = &mut primary => {
+            return RaceOutcome {
+                result: flatten(joined),
+                winner: Leg::Primary,
+                hedge_launched: false,
+                loser_aborted: false,
+            };
+        }
+        _ = tokio::time::sleep(delay) => {}
+    }
+
+    let mut hedge = launch_hedge();
+
+    let (first, first_result) = tokio::select! {
+        joined = &mut primary => (Leg::Primary, flatten(joined)),
+        joined = &mut hedge => (Leg::Hedge, flatten(joined)),
+    };
+
+    let (winner, result) = if succeeded(&first_result) {
+        (first, first_result)
+    } else {
+        // The first copy to finish failed; the other may still succeed
+        let second = first.other();
+        let task = match second {
+            Leg::Primary => &mut primary,
+            Leg::Hedge => &mut hedge,
+        };
+        let second_result = flatten(task.await);
+        if succeeded(&second_result) || second == Leg::Primary {
+            (second, second_result)
+        } else {
+            (first, first_result)
+        }
+    };
+
+    let loser = match winner {
+        Leg::Primary => &hedge,
+        Leg::Hedge => &primary,
+    };
+    let loser_aborted = !loser.is_finished();
+    loser.abort();
+
+    RaceOutcome {
+        result,
+        winner,
+        hedge_launched: true,
+        loser_aborted,
+    }
+}
+
+/// Collapse a joined leg task into its execution result
+fn flatten(joined: Result<CyloResult<ExecutionResult>, JoinError>) -> CyloResult<ExecutionResult> {
+    joined.map_err(CyloError::from).and_then(|result| result)
+}
+
+fn succeeded(result: &CyloResult<ExecutionResult>) -> bool {
+    matches!(result, Ok(result) if result.is_success())
+}
+
+/// Start one copy of the request on its own task
+fn spawn_leg(
+    leg: &HedgeLeg,
+    request: Arc<ExecutionRequest>,
+    config: &Arc<ExecutorConfig>,
+) -> AsyncTask<CyloResult<ExecutionResult>> {
+    tokio::spawn(execution::execute_with_backend(
+        leg.backend_name.clone(),
+        leg.instance.clone(),
+        request,
+        Arc::clone(config),
+        PhaseClock::default(),
+    ))
+}
+
+/// Drop the instance reference an aborted copy never got to release
+async fn release_aborted(leg: &HedgeLeg, optimization: &OptimizationConfig) {
+    let manager = global_instance_manager();
+    if optimization.instance_reuse {
+        let _ = manager.release_instance(&leg.instance.id());
+    } else {
+        let _ = manager.remove_instance(&leg.instance.id()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finish_after(
+        delay_ms: u64,
+        result: ExecutionResult,
+    ) -> AsyncTask<CyloResult<ExecutionResult>> {
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            Ok(result)
+        })
+    }
+
+    #[tokio::test]
+    async fn fast_primary_never_launches_hedge() {
+        let outcome = race(
+            finish_after(5, ExecutionResult::success("primary")),
+            || panic!("hedge launched"),
+            Duration::from_millis(200),
+        )
+        .await;
+
+        assert_eq!(outcome.winner, Leg::Primary);
+        assert!(!outcome.hedge_launched);
+    }
+
+    #[tokio::test]
+    async fn faster_hedge_wins_and_primary_is_aborted() {
+        let outcome = race(
+            finish_after(5_000, ExecutionResult::success("primary")),
+            || finish_after(5, ExecutionResult::success("hedge")),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert_eq!(outcome.winner, Leg::Hedge);
+        assert!(outcome.hedge_launched && outcome.loser_aborted);
+        assert_eq!(outcome.result.unwrap().stdout, "hedge");
+    }
+
+    #[tokio::test]
+    async fn failed_hedge_waits_for_primary() {
+        let outcome = race(
+            finish_after(50, ExecutionResult::success("primary")),
+            || finish_after(1, ExecutionResult::failure(1, "boom")),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert_eq!(outcome.winner, Leg::Primary);
+        assert!(!outcome.loser_aborted);
+        assert_eq!(outcome.result.unwrap().stdout, "primary");
+    }
+}
@@ -0,0 +1,359 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/host_guard.rs
+//! ----------------------------------------------------------------------------
+//! Host resource admission control.
+//!
+//! Checks free memory, load average, and workspace disk space before an
+//! execution is admitted, so cylo backs off with a typed HostOverloaded error
+//! instead of letting the OOM killer take out the embedding application.
+//! ============================================================================
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Thresholds for admitting executions on this host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostGuardConfig {
+    /// Whether the guard is active
+    pub enabled: bool,
+    /// Minimum available memory in bytes
+    pub min_free_memory: u64,
+    /// Maximum 1-minute load average per CPU core
+    pub max_load_per_core: f64,
+    /// Minimum free disk space on the workspace filesystem in bytes
+    pub min_free_disk: u64,
+    /// Filesystem path whose free space is checked
+    pub workspace_path: PathBuf,
+    /// Base retry-after hint returned when the host is overloaded
+    pub retry_after: Duration,
+}
+
+impl Default for HostGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_free_memory: 256 * 1024 * 1024, // 256MB
+            max_load_per_core: 4.0,
+            min_free_disk: 512 * 1024 * 1024, // 512MB
+            workspace_path: std::env::temp_dir(),
+            retry_after: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Point-in-time view of host resources
+///
+/// Fields are None when the platform cannot report them; missing values
+/// never block admission.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HostSnapshot {
+    /// Available memory in bytes
+    pub free_memory: Option<u64>,
+    /// 1-minute load average divided by CPU core count
+    pub load_per_core: Option<f64>,
+    /// Free space on the workspace filesystem in bytes
+    pub free_disk: Option<u64>,
+}
+
+impl HostSnapshot {
+    /// Sample host resources
+    ///
+    /// # Arguments
+    /// * `workspace_path` - Path whose filesystem free space is reported
+    pub fn capture(workspace_path: &Path) -> Self {
+        let cores = num_cpus::get().max(1) as f64;
+        Self {
+            free_memory: free_memory(),
+            load_per_core: load_average().map(|load| load / cores),
+            free_disk: free_disk(workspace_path),
+        }
+    }
+}
+
+/// Admit or refuse an execution based on a host snapshot
+///
+/// # Arguments
+/// * `config` - Guard thresholds
+/// * `snapshot` - Current host resources
+///
+/// # Returns
+/// Ok(()) if the execution may proceed, HostOverloaded otherwise
+pub fn check(config: &HostGuardConfig, snapshot: &HostSnapshot) -> CyloResult<()> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    if let Some(free) = snapshot.free_memory
+        && free < config.min_free_memory
+    {
+        return Err(CyloError::host_overloaded(
+            "memory",
+            format!(
+                "{} MB available, {} MB required",
+                free / (1024 * 1024),
+                config.min_free_memory / (1024 * 1024)
+            ),
+            config.retry_after,
+        ));
+    }
+
+    if let Some(load) = snapshot.load_per_core
+        && load > config.max_load_per_core
+    {
+        // Back off proportionally to how far over the threshold the host is
+        let factor = (load / config.max_load_per_core).ceil().min(12.0);
+        return Err(CyloError::host_overloaded(
+            "cpu",
+            format!(
+                "load {load:.2} per core exceeds {:.2}",
+                config.max_load_per_core
+            ),
+            config.retry_after.mul_f64(factor),
+        ));
+    }
+
+    if let Some(free) = snapshot.free_disk
+        && free < config.min_free_disk
+    {
+        return Err(CyloError::host_overloaded(
+            "disk",
+            format!(
+                "{} MB free on {}, {} MB required",
+                free / (1024 * 1024),
+                config.workspace_path.display(),
+                config.min_free_disk / (1024 * 1024)
+            ),
+            // Disk pressure rarely clears quickly
+            config.retry_after * 6,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Sample the host and check it against the guard thresholds
+///
+/// Sampling may shell out on some platforms, so it runs on the blocking pool.
+pub async fn admit(config: &HostGuardConfig) -> CyloResult<()> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    let workspace_path = config.workspace_path.clone();
+    let snapshot =
+        tokio::task::spawn_blocking(move || HostSnapshot::capture(&workspace_path)).await?;
+    check(config, &snapshot)
+}
+
+/// Available memory in bytes
+#[cfg(target_os = "linux")]
+fn free_memory() -> Option<u64> {
+    let content = std::fs::read_to_string("/proc/meminfo").ok()?;
+    content
+        .lines()
+        .find(|line| line.starts_with("MemAvailable:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+/// Available memory in bytes (free + inactive pages)
+#[cfg(target_os = "macos")]
+fn free_memory() -> Option<u64> {
+    let output = std::process::Command::new("vm_stat").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+
+    let page_size = text
+        .lines()
+        .next()
+        .and_then(|line| line.split("page size of ").nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|size| size.parse::<u64>().ok())
+        .unwrap_or(4096);
+
+    let pages = |label: &str| {
+        text.lines()
+            .find(|line| line.starts_with(label))
+            .and_then(|line| line.split(':').nth(1))
+            .and_then(|value| value.trim().trim_end_matches('.').parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+
+    Some((pages("Pages free") + pages("Pages inactive")) * page_size)
+}
+
+/// Available physical memory in bytes
+#[cfg(target_os = "windows")]
+fn free_memory() -> Option<u64> {
+    use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    let mut status = MEMORYSTATUSEX {
+        dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+        ..Default::default()
+    };
+    unsafe { GlobalMemoryStatusEx(&mut status).ok()? };
+    Some(status.ullAvailPhys)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn free_memory() -> Option<u64> {
+    None
+}
+
+/// 1-minute load average
+#[cfg(target_os = "linux")]
+fn load_average() -> Option<f64> {
+    std::fs::read_to_string("/proc/loadavg")
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// 1-minute load average
+#[cfg(target_os = "macos")]
+fn load_average() -> Option<f64> {
+    // Output format: "{ 1.23 1.45 1.67 }"
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "vm.loadavg"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .find(|token| *token != "{")?
+        .parse()
+        .ok()
+}
+
+/// Windows has no load average equivalent
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn load_average() -> Option<f64> {
+    None
+}
+
+/// Free disk space for unprivileged users in bytes
+#[cfg(target_os = "linux")]
+fn free_disk(path: &Path) -> Option<u64> {
+    let stats = nix::sys::statvfs::statvfs(path).ok()?;
+    Some(stats.blocks_available() as u64 * stats.fragment_size() as u64)
+}
+
+/// Free disk space in bytes
+#[cfg(target_os = "macos")]
+fn free_disk(path: &Path) -> Option<u64> {
+    // POSIX output: Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse::<u64>()
+        .ok()
+        .map(|kb| kb * 1024)
+}
+
+/// Free disk space available to the caller in bytes
+#[cfg(target_os = "windows")]
+fn free_disk(path: &Path) -> Option<u64> {
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+    use windows::core::PCWSTR;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(Some(0))
+        .collect();
+    let mut available = 0u64;
+    unsafe {
+        GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut available as *mut u64), None, None).ok()?;
+    }
+    Some(available)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn free_disk(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy() -> HostSnapshot {
+        HostSnapshot {
+            free_memory: Some(8 * 1024 * 1024 * 1024),
+            load_per_core: Some(0.5),
+            free_disk: Some(50 * 1024 * 1024 * 1024),
+        }
+    }
+
+    #[test]
+    fn healthy_host_is_admitted() {
+        assert!(check(&HostGuardConfig::default(), &healthy()).is_ok());
+        assert!(check(&HostGuardConfig::default(), &HostSnapshot::default()).is_ok());
+    }
+
+    #[test]
+    fn memory_pressure_is_refused() {
+        let snapshot = HostSnapshot {
+            free_memory: Some(16 * 1024 * 1024),
+            ..healthy()
+        };
+        match check(&HostGuardConfig::default(), &snapshot) {
+            Err(CyloError::HostOverloaded {
+                resource,
+                retry_after_secs,
+                ..
+            }) => {
+                assert_eq!(resource, "memory");
+                assert_eq!(retry_after_secs, 5);
+            }
+            other => panic!("expected HostOverloaded, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn load_backoff_scales_with_overload() {
+        let snapshot = HostSnapshot {
+            load_per_core: Some(10.0),
+            ..healthy()
+        };
+        match check(&HostGuardConfig::default(), &snapshot) {
+            Err(CyloError::HostOverloaded {
+                resource,
+                retry_after_secs,
+                ..
+            }) => {
+                assert_eq!(resource, "cpu");
+                assert_eq!(retry_after_secs, 15);
+            }
+            other => panic!("expected HostOverloaded, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn disabled_guard_admits_everything() {
+        let config = HostGuardConfig {
+            enabled: false,
+            ..HostGuardConfig::default()
+        };
+        let snapshot = HostSnapshot {
+            free_memory: Some(0),
+            load_per_core: Some(100.0),
+            free_disk: Some(0),
+        };
+        assert!(check(&config, &snapshot).is_ok());
+    }
+}
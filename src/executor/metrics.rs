@@ -8,13 +8,14 @@ use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 use crate::execution_env::CyloResult;
 use crate::backends::{ExecutionRequest, ExecutionResult};
+use super::cost;
 use super::types::{ExecutionMetrics, ResourceStats};
 
 /// Update execution metrics
 pub async fn update_metrics(
     metrics: Arc<RwLock<ExecutionMetrics>>,
     backend_name: &str,
-    _request: &ExecutionRequest,
+    request: &ExecutionRequest,
     result: &CyloResult<ExecutionResult>,
 ) {
     if let Ok(mut metrics) = metrics.write() {
@@ -78,8 +79,39 @@ pub async fn update_metrics(
                 resource_stats.peak_memory = exec_result.resource_usage.peak_memory;
             }
             resource_stats.cumulative_cpu_time += exec_result.resource_usage.cpu_time_ms;
+
+            // Aggregate attributed cost per backend and tenant
+            if let Some(cost) = &exec_result.cost {
+                *metrics
+                    .cost_per_backend
+                    .entry(backend_name.to_string())
+                    .or_insert(0.0) += cost.amount;
+
+                let tenant = metrics
+                    .tenant_usage
+                    .entry(cost::tenant_of(request).to_string())
+                    .or_default();
+                tenant.executions += 1;
+                tenant.cpu_seconds += cost.cpu_seconds;
+                tenant.gb_seconds += cost.gb_seconds;
+                tenant.vm_boots += u64::from(cost.vm_boots);
+                tenant.amount += cost.amount;
+            }
         }
 
         metrics.last_updated = Some(SystemTime::now());
     }
 }
+
+/// Record that a hedged execution started its second copy
+///
+/// # Arguments
+/// * `winner` - Backend of the hedge copy if it won, None if the primary won
+pub fn record_hedge(metrics: &Arc<RwLock<ExecutionMetrics>>, winner: Option<&str>) {
+    if let Ok(mut metrics) = metrics.write() {
+        metrics.hedges_launched += 1;
+        if let Some(backend) = winner {
+            *metrics.hedge_wins.entry(backend.to_string()).or_insert(0) += 1;
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/metrics_store.rs
+//! ----------------------------------------------------------------------------
+//! On-disk history of execution metrics.
+//!
+//! Metrics live in memory, so a restart used to lose every latency and
+//! success-rate figure the executor had gathered. With
+//! `OptimizationConfig::metrics_store` set, a background thread appends a
+//! snapshot to a JSON-lines file on an interval, rotating it to numbered
+//! siblings (`metrics.jsonl.1`, `.2`, ...) once it reaches its size limit
+//! and deleting the oldest, so the history never exceeds its disk quota.
+//! The newest snapshot seeds the metrics of the next executor, and the
+//! whole history is available for trend reports.
+//! ============================================================================
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::types::ExecutionMetrics;
+use crate::execution_env::{CyloError, CyloResult};
+use crate::logging::targets;
+
+/// Where and how often metrics snapshots are written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsStoreConfig {
+    /// Current snapshot file; rotated files get a numeric suffix
+    pub path: PathBuf,
+    /// Time between snapshots
+    pub interval: Duration,
+    /// Size a file may reach before it is rotated
+    pub max_file_bytes: u64,
+    /// Files kept, the current one included; the disk quota is
+    /// `max_file_bytes * max_files`
+    pub max_files: u32,
+}
+
+impl Default for MetricsStoreConfig {
+    fn default() -> Self {
+        Self {
+            path: std::env::temp_dir().join("cylo-metrics").join("metrics.jsonl"),
+            interval: Duration::from_secs(60),
+            max_file_bytes: 1024 * 1024,
+            max_files: 5,
+        }
+    }
+}
+
+/// Metrics as they stood at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// When the snapshot was taken
+    pub taken_at: SystemTime,
+    /// Metrics at that time; background task counts are not kept
+    pub metrics: ExecutionMetrics,
+}
+
+/// Snapshot files of one `MetricsStoreConfig`
+#[derive(Debug, Clone)]
+pub(crate) struct MetricsStore {
+    config: MetricsStoreConfig,
+}
+
+impl MetricsStore {
+    pub(crate) fn new(config: MetricsStoreConfig) -> Self {
+        Self { config }
+    }
+
+    /// Append a snapshot, rotating first when it would overflow the
+    /// current file
+    pub(crate) fn append(&self, snapshot: &MetricsSnapshot) -> io::Result<()> {
+        let mut line = serde_json::to_vec(snapshot).map_err(io::Error::other)?;
+        line.push(b'\n');
+
+        let path = &self.config.path;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let current = fs::metadata(path).map_or(0, |metadata| metadata.len());
+        if current > 0 && current + line.len() as u64 > self.config.max_file_bytes {
+            self.rotate()?;
+        }
+
+        OpenOptions::new().create(true).append(true).open(path)?.write_all(&line)
+    }
+
+    /// Newest readable snapshot, if any was written
+    pub(crate) fn latest(&self) -> Option<MetricsSnapshot> {
+        (0..self.config.max_files.max(1))
+            .find_map(|generation| read_snapshots(&self.file(generation)).pop())
+    }
+
+    /// Every readable snapshot, oldest first
+    pub(crate) fn history(&self) -> Vec<MetricsSnapshot> {
+        (0..self.config.max_files.max(1))
+            .rev()
+            .flat_map(|generation| read_snapshots(&self.file(generation)))
+            .collect()
+    }
+
+    /// Shift every file one generation older, dropping the oldest
+    fn rotate(&self) -> io::Result<()> {
+        let oldest = self.config.max_files.max(1) - 1;
+        remove_if_present(&self.file(oldest))?;
+        for generation in (0..oldest).rev() {
+            let from = self.file(generation);
+            if from.exists() {
+                fs::rename(&from, self.file(generation + 1))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// File of a generation; 0 is the current one
+    fn file(&self, generation: u32) -> PathBuf {
+        if generation == 0 {
+            return self.config.path.clone();
+        }
+        let mut name = self.config.path.clone().into_os_string();
+        name.push(format!(".{generation}"));
+        PathBuf::from(name)
+    }
+}
+
+fn remove_if_present(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Snapshots of one file in write order, skipping lines that do not parse,
+/// such as one cut short by a crash
+fn read_snapshots(path: &Path) -> Vec<MetricsSnapshot> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Background thread writing snapshots of an executor's metrics
+///
+/// A final snapshot is written when the persister is dropped, so a clean
+/// shutdown loses nothing.
+#[derive(Debug)]
+pub(crate) struct MetricsPersister {
+    store: MetricsStore,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MetricsPersister {
+    /// Start snapshotting `metrics` until dropped or the metrics are gone
+    pub(crate) fn spawn(
+        store: MetricsStore,
+        metrics: Weak<RwLock<ExecutionMetrics>>,
+    ) -> CyloResult<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::Builder::new()
+            .name("cylo-metrics-store".to_string())
+            .spawn({
+                let store = store.clone();
+                let stop = Arc::clone(&stop);
+                move || persist_loop(&store, &metrics, &stop)
+            })
+            .map_err(|e| CyloError::internal(format!("Failed to start metrics store: {}", e)))?;
+
+        Ok(Self {
+            store,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Files the snapshots are written to
+    pub(crate) fn store(&self) -> &MetricsStore {
+        &self.store
+    }
+}
+
+impl Drop for MetricsPersister {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Write loop run on the persister thread; snapshots are skipped while the
+/// metrics are unchanged
+fn persist_loop(
+    store: &MetricsStore,
+    metrics: &Weak<RwLock<ExecutionMetrics>>,
+    stop: &AtomicBool,
+) {
+    let mut persisted = metrics.upgrade().and_then(|metrics| {
+        metrics.read().unwrap_or_else(|poisoned| poisoned.into_inner()).last_updated
+    });
+
+    loop {
+        // Parked rather than slept so dropping the persister wakes it at once
+        std::thread::park_timeout(store.config.interval);
+        let stopping = stop.load(Ordering::SeqCst);
+
+        let Some(metrics) = metrics.upgrade() else {
+            break;
+        };
+        let current = metrics.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+        drop(metrics);
+
+        if current.last_updated.is_some() && current.last_updated != persisted {
+            let snapshot = MetricsSnapshot {
+                taken_at: SystemTime::now(),
+                metrics: current,
+            };
+            match store.append(&snapshot) {
+                Ok(()) => persisted = snapshot.metrics.last_updated,
+                Err(e) => warn!(
+                    target: targets::EXECUTOR,
+                    "Failed to persist metrics to {}: {}",
+                    store.config.path.display(),
+                    e
+                ),
+            }
+        }
+        if stopping {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_rotate_within_quota_and_reload() {
+        let dir = std::env::temp_dir().join(format!("cylo-metrics-store-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let store = MetricsStore::new(MetricsStoreConfig {
+            path: dir.join("metrics.jsonl"),
+            max_file_bytes: 1024,
+            max_files: 3,
+            ..MetricsStoreConfig::default()
+        });
+        assert!(store.latest().is_none());
+
+        for executions in 1..=40u64 {
+            let mut metrics = ExecutionMetrics::default();
+            metrics.executions_per_backend.insert("LandLock".to_string(), executions);
+            metrics.last_updated = Some(SystemTime::now());
+            let snapshot = MetricsSnapshot {
+                taken_at: SystemTime::now(),
+                metrics,
+            };
+            store.append(&snapshot).unwrap();
+        }
+
+        assert!(!store.file(3).exists());
+        for generation in 0..3 {
+            assert!(fs::metadata(store.file(generation)).unwrap().len() <= 1024);
+        }
+
+        let latest = store.latest().unwrap();
+        assert_eq!(latest.metrics.executions_per_backend["LandLock"], 40);
+
+        let history: Vec<u64> = store
+            .history()
+            .iter()
+            .map(|snapshot| snapshot.metrics.executions_per_backend["LandLock"])
+            .collect();
+        assert!(history.len() < 40);
+        assert!(history.windows(2).all(|pair| pair[0] + 1 == pair[1]));
+        assert_eq!(history.last(), Some(&40));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,167 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/middleware.rs
+//! ----------------------------------------------------------------------------
+//! Execution hooks registered on the executor.
+//!
+//! Middleware sees every execution the executor runs: it may rewrite the
+//! request before routing (inject env vars, force a backend), veto it
+//! (policy checks), and observe or adjust the result afterwards (logging,
+//! billing, redaction) without touching the routing code.
+//! ============================================================================
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::backends::{ExecutionRequest, ExecutionResult};
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Hooks invoked around every execution
+///
+/// All methods have no-op defaults, so implementations only override the
+/// hooks they need. Hooks run on the execution task and should stay cheap;
+/// hand slow work (network calls, disk writes) off to another task.
+pub trait ExecutionMiddleware: Send + Sync {
+    /// Called before validation and routing
+    ///
+    /// The request may be modified. Returning an error rejects the
+    /// execution; the error is reported to every middleware's `on_error`
+    /// and returned to the caller.
+    fn before_execute(&self, _request: &mut ExecutionRequest) -> CyloResult<()> {
+        Ok(())
+    }
+
+    /// Called after a backend produced a result
+    ///
+    /// # Arguments
+    /// * `request` - Request as executed, including its execution id
+    /// * `backend` - Name of the backend that ran it
+    /// * `result` - Result, which may be modified
+    fn after_execute(
+        &self,
+        _request: &ExecutionRequest,
+        _backend: &str,
+        _result: &mut ExecutionResult,
+    ) {
+    }
+
+    /// Called when the execution was rejected or failed to run
+    fn on_error(&self, _request: &ExecutionRequest, _error: &CyloError) {}
+}
+
+/// Ordered middleware registered on an executor
+///
+/// `before_execute` runs in registration order; `after_execute` and
+/// `on_error` run in reverse, so the first middleware registered wraps all
+/// the others.
+#[derive(Clone, Default)]
+pub(crate) struct MiddlewareChain {
+    layers: Vec<Arc<dyn ExecutionMiddleware>>,
+}
+
+impl MiddlewareChain {
+    /// Append a middleware to the chain
+    pub(crate) fn push(&mut self, middleware: Arc<dyn ExecutionMiddleware>) {
+        self.layers.push(middleware);
+    }
+
+    /// Run every `before_execute` hook, stopping at the first rejection
+    pub(crate) fn before_execute(&self, request: &mut ExecutionRequest) -> CyloResult<()> {
+        self.layers
+            .iter()
+            .try_for_each(|layer| layer.before_execute(request))
+    }
+
+    /// Run every `after_execute` hook
+    pub(crate) fn after_execute(
+        &self,
+        request: &ExecutionRequest,
+        backend: &str,
+        result: &mut ExecutionResult,
+    ) {
+        for layer in self.layers.iter().rev() {
+            layer.after_execute(request, backend, result);
+        }
+    }
+
+    /// Run every `on_error` hook
+    pub(crate) fn on_error(&self, request: &ExecutionRequest, error: &CyloError) {
+        for layer in self.layers.iter().rev() {
+            layer.on_error(request, error);
+        }
+    }
+}
+
+impl fmt::Debug for MiddlewareChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MiddlewareChain")
+            .field("layers", &self.layers.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records the hooks it sees into a shared log
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+        reject: bool,
+    }
+
+    impl ExecutionMiddleware for Recorder {
+        fn before_execute(&self, request: &mut ExecutionRequest) -> CyloResult<()> {
+            self.log.lock().unwrap().push(format!("before:{}", self.name));
+            if self.reject {
+                return Err(CyloError::validation("rejected by policy"));
+            }
+            request.env_vars.insert(self.name.to_string(), "1".to_string());
+            Ok(())
+        }
+
+        fn after_execute(&self, _: &ExecutionRequest, _: &str, _: &mut ExecutionResult) {
+            self.log.lock().unwrap().push(format!("after:{}", self.name));
+        }
+
+        fn on_error(&self, _: &ExecutionRequest, _: &CyloError) {
+            self.log.lock().unwrap().push(format!("error:{}", self.name));
+        }
+    }
+
+    fn chain(log: &Arc<Mutex<Vec<String>>>, reject_second: bool) -> MiddlewareChain {
+        let mut chain = MiddlewareChain::default();
+        for (name, reject) in [("a", false), ("b", reject_second)] {
+            chain.push(Arc::new(Recorder {
+                name,
+                log: Arc::clone(log),
+                reject,
+            }));
+        }
+        chain
+    }
+
+    #[test]
+    fn hooks_wrap_in_onion_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let chain = chain(&log, false);
+        let mut request = ExecutionRequest::new("print(1)", "python");
+
+        chain.before_execute(&mut request).unwrap();
+        chain.after_execute(&request, "LandLock", &mut ExecutionResult::success(""));
+
+        assert!(request.env_vars.contains_key("a") && request.env_vars.contains_key("b"));
+        assert_eq!(*log.lock().unwrap(), ["before:a", "before:b", "after:b", "after:a"]);
+    }
+
+    #[test]
+    fn rejection_stops_later_hooks() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let chain = chain(&log, true);
+        let mut request = ExecutionRequest::new("print(1)", "python");
+
+        assert!(chain.before_execute(&mut request).is_err());
+        assert_eq!(*log.lock().unwrap(), ["before:a", "before:b"]);
+    }
+}
@@ -11,26 +11,93 @@
 //! ============================================================================
 
 mod types;
+mod active;
 mod routing;
 mod execution;
 mod metrics;
+mod metrics_store;
 mod factory;
+mod replay;
+mod concurrency;
+mod host_guard;
+mod crash_loop;
+mod dependencies;
+mod reload;
+mod middleware;
+mod hedge;
+mod cost;
+mod schedule;
+mod pipeline;
+mod warm_up;
+mod deadline;
+mod templates;
+mod federation;
+mod plan;
+mod session;
 
 // Re-export public types and functions
 pub use types::{
-    RoutingStrategy, BackendPreferences, OptimizationConfig, ExecutionMetrics, ResourceStats,
+    RoutingStrategy, BackendPreferences, LanguagePreferences, OptimizationConfig,
+    ExecutionMetrics, ResourceStats, TaskStats, TenantUsage,
+};
+pub use active::{ActiveExecution, ExecutionState, ResourceSnapshot};
+pub use cost::{CostModel, RateCard, DEFAULT_TENANT};
+pub use schedule::{Schedule, ScheduledJob};
+pub use pipeline::{Pipeline, PipelineResult, PipelineStep, StepOutcome};
+pub use replay::{RecordedExecution, ReplayBundle};
+pub use warm_up::{
+    BackendReadiness, LanguageReadiness, ReadinessReport, WARM_UP_TENANT, hello_world,
+};
+pub use reload::{ConfigWatcher, ExecutorConfig, LanguageProfile};
+pub use middleware::ExecutionMiddleware;
+pub use host_guard::{HostGuardConfig, HostSnapshot};
+pub use metrics_store::{MetricsSnapshot, MetricsStoreConfig};
+pub use crash_loop::CrashLoopConfig;
+pub use dependencies::{DEPENDENCIES_METADATA, DependencyConfig};
+pub use deadline::{PHASE_TIMINGS_METADATA, Phase, TIMEOUT_PHASE_METADATA};
+pub use templates::RequestTemplate;
+pub use plan::{ExecutionPlan, PolicyVerdict};
+pub use session::{ExecutionSession, SessionSnapshot};
+pub use federation::{
+    ADVERTISEMENT_PATH, EXECUTE_PATH, FEDERATED_FROM_KEY, HttpWorker, RemoteWorker,
+    WORKER_METADATA, WorkerAdvertisement,
 };
 pub use factory::{
     create_executor, create_performance_executor, create_security_executor,
     execute_with_routing, global_executor, init_global_executor,
 };
 
-use std::sync::{Arc, RwLock};
-use std::time::{Duration, SystemTime};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{info, warn};
 use crate::async_task::{AsyncTask, AsyncTaskBuilder};
 use crate::execution_env::{Cylo, CyloInstance, CyloError, CyloResult};
-use crate::backends::{ExecutionRequest, ExecutionResult};
-use crate::platform::{detect_platform, get_available_backends};
+use crate::backends::{
+    BackendConfig, ExecutionRequest, ExecutionResult, HealthCheckLevel, HealthStatus,
+    VOLUME_USAGE_METADATA, VolumeStore, blob_store, blocking, compile_phase, create_backend,
+    language, parse_result_sets, post_process, volumes,
+};
+use crate::backends::budget::BudgetDraw;
+use crate::backends::sampler::global_sampler;
+use crate::backends::transcript::TranscriptTap;
+use crate::instance_manager::{InstanceSelector, global_instance_manager};
+use crate::logging::targets;
+use crate::platform::{backend_availability, detected_backends};
+use crate::reaper::global_reaper;
+use active::ActiveRegistry;
+use crash_loop::CrashLoopTracker;
+use deadline::{Deadline, PhaseClock};
+use dependencies::NodeDependencies;
+use federation::Federation;
+use hedge::HedgeLeg;
+use metrics_store::{MetricsPersister, MetricsStore};
+use middleware::MiddlewareChain;
+use plan::image_of;
+use schedule::Scheduler;
 use types::PlatformCache;
 
 /// High-performance execution orchestrator for Cylo environments
@@ -39,20 +106,55 @@ use types::PlatformCache;
 /// for code execution across multiple isolation backends.
 #[derive(Debug)]
 pub struct CyloExecutor {
-    /// Execution routing strategy
-    routing_strategy: RoutingStrategy,
+    /// Writer of metrics snapshots; declared first so it is dropped while
+    /// the metrics still exist and records a final snapshot
+    metrics_persister: Option<MetricsPersister>,
 
-    /// Backend selection preferences
-    backend_preferences: BackendPreferences,
+    /// State shared with execution and scheduler tasks
+    shared: SharedState,
 
-    /// Performance optimization settings
-    optimization_config: OptimizationConfig,
+    /// Scheduler for delayed and recurring executions, started on first use
+    scheduler: OnceLock<Arc<Scheduler>>,
+}
+
+/// Executor state that outlives a single call
+///
+/// Cloning shares the underlying state; background tasks hold a clone to
+/// snapshot execution contexts.
+#[derive(Debug, Clone)]
+struct SharedState {
+    /// Routing strategy, preferences, and optimization settings; swapped
+    /// as a whole by `apply_config` and snapshotted per execution
+    config: Arc<reload::SharedConfig>,
 
     /// Cached platform capabilities (with interior mutability)
     platform_cache: Arc<RwLock<PlatformCache>>,
 
     /// Execution statistics and metrics
     metrics: Arc<RwLock<ExecutionMetrics>>,
+
+    /// Hooks run around every execution
+    middleware: Arc<RwLock<MiddlewareChain>>,
+
+    /// Prices metered usage; None attributes usage at zero cost
+    cost_model: Arc<RwLock<Option<Arc<dyn CostModel>>>>,
+
+    /// Chooses among matching registered instances; None uses the
+    /// configured `instance_selection` strategy
+    instance_selector: Arc<RwLock<Option<Arc<dyn InstanceSelector>>>>,
+
+    /// Recent crashes per code fingerprint
+    crash_loops: Arc<Mutex<CrashLoopTracker>>,
+
+    /// Executions currently in flight
+    active: Arc<ActiveRegistry>,
+
+    /// Remote workers requests are forwarded to when no local backend can
+    /// serve them
+    federation: Arc<Federation>,
+
+    /// Report of the latest warm-up
+    readiness: Arc<RwLock<Option<ReadinessReport>>>,
 }
 
 impl CyloExecutor {
@@ -72,34 +174,79 @@ impl CyloExecutor {
     /// # Returns
     /// Configured executor with specified strategy
     pub fn with_strategy(strategy: RoutingStrategy) -> Self {
-        let platform_info = detect_platform();
-        let available_backends = get_available_backends()
-            .into_iter()
-            .map(|name| {
-                let rating = platform_info
-                    .available_backends
-                    .iter()
-                    .find(|b| b.name == name)
-                    .map(|b| b.performance_rating)
-                    .unwrap_or(0);
-                (name.to_string(), rating)
-            })
-            .collect();
+        Self::with_config(ExecutorConfig {
+            routing_strategy: strategy,
+            ..ExecutorConfig::default()
+        })
+    }
+
+    /// Create executor from a complete configuration
+    ///
+    /// # Arguments
+    /// * `config` - Routing, preference, and optimization settings
+    ///
+    /// # Returns
+    /// Configured executor
+    pub fn with_config(config: ExecutorConfig) -> Self {
+        // Only backend availability is needed here, which is detected
+        // without running commands
+        let available_backends = available_with_ratings();
 
         let platform_cache = Arc::new(RwLock::new(PlatformCache {
             available_backends,
-            capabilities_hash: routing::compute_capabilities_hash(&platform_info),
+            capabilities_hash: routing::compute_capabilities_hash(detected_backends()),
             cached_at: SystemTime::now(),
             cache_duration: Duration::from_secs(300), // 5 minutes
         }));
 
-        Self {
-            routing_strategy: strategy,
-            backend_preferences: BackendPreferences::default(),
-            optimization_config: OptimizationConfig::default(),
-            platform_cache,
-            metrics: Arc::new(RwLock::new(ExecutionMetrics::default())),
+        crate::hardening::set_helper_hardening(config.optimization.helper_hardening);
+
+        // Clear out leftovers from crashed runs before taking on new work
+        if let Some(policy) = &config.optimization.startup_recovery {
+            crate::recovery::recover_at_startup(policy.clone());
+        }
+
+        // Pick up the metrics the previous executor persisted
+        let metrics_store = config.optimization.metrics_store.clone().map(MetricsStore::new);
+        let restored = metrics_store
+            .as_ref()
+            .and_then(MetricsStore::latest)
+            .map(|snapshot| snapshot.metrics)
+            .unwrap_or_default();
+        let metrics = Arc::new(RwLock::new(restored));
+        let metrics_persister = metrics_store.and_then(|store| {
+            MetricsPersister::spawn(store, Arc::downgrade(&metrics))
+                .inspect_err(|e| {
+                    warn!(target: targets::EXECUTOR, "Metrics will not be persisted: {}", e)
+                })
+                .ok()
+        });
+
+        let persisted_jobs = config.optimization.schedule_store.is_some();
+        let executor = Self {
+            metrics_persister,
+            shared: SharedState {
+                config: Arc::new(RwLock::new(Arc::new(config))),
+                platform_cache,
+                metrics,
+                middleware: Arc::new(RwLock::new(MiddlewareChain::default())),
+                cost_model: Arc::new(RwLock::new(None)),
+                instance_selector: Arc::new(RwLock::new(None)),
+                crash_loops: Arc::new(Mutex::new(CrashLoopTracker::default())),
+                active: Arc::new(ActiveRegistry::default()),
+                federation: Arc::new(Federation::default()),
+                readiness: Arc::new(RwLock::new(None)),
+            },
+            scheduler: OnceLock::new(),
+        };
+
+        // Jobs persisted by the previous executor run without waiting for
+        // this one to schedule anything; outside a runtime they start on
+        // first use of the scheduler
+        if persisted_jobs && tokio::runtime::Handle::try_current().is_ok() {
+            executor.scheduler();
         }
+        executor
     }
 
     /// Execute code with intelligent backend routing
@@ -115,52 +262,133 @@ impl CyloExecutor {
         request: ExecutionRequest,
         instance_hint: Option<&CyloInstance>,
     ) -> AsyncTask<CyloResult<ExecutionResult>> {
-        let strategy = self.routing_strategy.clone();
-        let preferences = self.backend_preferences.clone();
-        let optimization = self.optimization_config.clone();
-        let platform_cache = self.platform_cache.clone();
-        let metrics = Arc::clone(&self.metrics);
+        let context = self.context();
         let instance_hint = instance_hint.cloned();
 
-        AsyncTaskBuilder::new().spawn(move || async move {
-            // Route to optimal backend
-            let (backend_name, cylo_instance) = match instance_hint {
-                Some(instance) => {
-                    // Use explicitly provided instance
-                    (routing::backend_name_from_cylo(&instance.env), instance)
-                }
-                None => {
-                    // Intelligent backend selection
-                    let backend_name = routing::select_optimal_backend(
-                        &strategy,
-                        &preferences,
-                        &platform_cache,
-                        &request,
-                    )?;
-
-                    // Create or reuse instance
-                    let cylo_env = routing::create_cylo_env(&backend_name, &request)?;
-                    let instance_name = routing::generate_instance_name(&backend_name);
-                    let cylo_instance = cylo_env.instance(instance_name);
-
-                    (backend_name, cylo_instance)
-                }
-            };
+        AsyncTaskBuilder::new(async move {
+            context
+                .run(request, instance_hint)
+                .await
+                .and_then(|routed| routed.result)
+        })
+        .spawn()
+    }
 
-            // Execute with selected backend
-            let result = execution::execute_with_backend(
-                backend_name.clone(),
-                cylo_instance,
-                request.clone(),
-                optimization,
-            )
-            .await;
+    /// Execute code and record a replay bundle for the execution
+    ///
+    /// # Arguments
+    /// * `request` - Execution request with code and requirements
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to the result paired with its replay bundle
+    pub fn execute_recorded(
+        &self,
+        request: ExecutionRequest,
+    ) -> AsyncTask<CyloResult<RecordedExecution>> {
+        let context = self.context();
+
+        AsyncTaskBuilder::new(async move {
+            let routed = context.run(request.clone(), None).await?;
+            let bundle = ReplayBundle::capture(
+                &routed.backend_name,
+                &routed.instance,
+                &request,
+                &routed.result,
+            );
+            Ok(RecordedExecution {
+                result: routed.result,
+                bundle,
+            })
+        })
+        .spawn()
+    }
+
+    /// Re-run a recorded execution on the same backend and environment
+    ///
+    /// # Arguments
+    /// * `bundle` - Replay bundle captured by `execute_recorded`
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to the replayed execution result
+    pub fn replay(&self, bundle: ReplayBundle) -> AsyncTask<CyloResult<ExecutionResult>> {
+        let context = self.context();
+
+        AsyncTaskBuilder::new(async move {
+            bundle.validate()?;
+
+            let instance = bundle
+                .environment
+                .clone()
+                .instance(routing::generate_instance_name(&bundle.request.language));
+
+            context
+                .run(bundle.request, Some(instance))
+                .await
+                .and_then(|routed| routed.result)
+        })
+        .spawn()
+    }
 
-            // Update metrics
-            metrics::update_metrics(metrics, &backend_name, &request, &result).await;
+    /// Execute a pipeline of dependent steps
+    ///
+    /// Each step is routed independently and starts as soon as its
+    /// dependencies have succeeded, so independent steps run in parallel.
+    ///
+    /// # Arguments
+    /// * `pipeline` - Steps and the dependencies between them
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to every step's outcome, or a validation
+    /// error if the pipeline is malformed
+    pub fn execute_pipeline(&self, pipeline: Pipeline) -> AsyncTask<CyloResult<PipelineResult>> {
+        let context = self.context();
 
-            result
+        AsyncTaskBuilder::new(async move {
+            pipeline.validate()?;
+            let result = pipeline::run(pipeline, |request| {
+                let context = context.clone();
+                async move {
+                    context
+                        .run(request, None)
+                        .await
+                        .and_then(|routed| routed.result)
+                }
+            })
+            .await;
+            Ok(result)
         })
+        .spawn()
+    }
+
+    /// Start a session whose executions are recorded in one transcript
+    ///
+    /// # Returns
+    /// Session that runs requests through this executor
+    pub fn session(&self) -> ExecutionSession {
+        ExecutionSession::new(self.shared.clone())
+    }
+
+    /// Start a sticky session, whose state carries from one execution to
+    /// the next and can be snapshotted and restored
+    ///
+    /// Its executions run one at a time on the instance the first one ran
+    /// on, each with the session's workspace volume attached. Docker
+    /// continues from the container the last execution left, committed to
+    /// an image, and FireCracker boots from the session's own copy of the
+    /// root filesystem; other backends carry the workspace only.
+    ///
+    /// # Arguments
+    /// * `store` - Store the session's workspace volume is created in
+    /// * `workspace` - Absolute sandbox path the workspace is attached at
+    ///
+    /// # Returns
+    /// The session, or an error if its workspace cannot be created
+    pub fn sticky_session<P: Into<PathBuf>>(
+        &self,
+        store: &VolumeStore,
+        workspace: P,
+    ) -> CyloResult<ExecutionSession> {
+        ExecutionSession::sticky(self.shared.clone(), store, workspace.into())
     }
 
     /// Execute code with automatic instance management
@@ -193,31 +421,428 @@ impl CyloExecutor {
         self.execute(request, Some(instance))
     }
 
+    /// Decide how a request would be executed without executing it
+    ///
+    /// Takes the request through middleware, configured defaults,
+    /// validation, admission policies and routing as `execute` would, and
+    /// reports the outcome of each step instead of running code.
+    /// Middleware `before_execute` hooks see a copy of the request; no
+    /// other hook runs.
+    ///
+    /// # Arguments
+    /// * `request` - Execution request to plan
+    /// * `instance_hint` - Optional preferred instance, as for `execute`
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to the plan; refusals are reported as
+    /// failed policy verdicts rather than errors
+    pub fn plan(
+        &self,
+        request: ExecutionRequest,
+        instance_hint: Option<&CyloInstance>,
+    ) -> AsyncTask<ExecutionPlan> {
+        let context = self.context();
+        let instance_hint = instance_hint.cloned();
+
+        AsyncTaskBuilder::new(async move { context.plan(request, instance_hint).await }).spawn()
+    }
+
+    /// Register middleware to run around every subsequent execution
+    ///
+    /// `before_execute` hooks run in registration order; `after_execute`
+    /// and `on_error` hooks run in reverse.
+    ///
+    /// # Arguments
+    /// * `middleware` - Hooks for logging, request mutation, policy, or billing
+    pub fn add_middleware<M: ExecutionMiddleware + 'static>(&self, middleware: M) {
+        self.shared
+            .middleware
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(Arc::new(middleware));
+    }
+
+    /// Run a request later, once or on a recurring schedule
+    ///
+    /// Must be called from within a Tokio runtime, which starts the
+    /// executor's scheduler unless it was created in one. Pending jobs are
+    /// persisted to `OptimizationConfig::schedule_store` and reloaded and
+    /// started by the next executor created with the same store.
+    ///
+    /// # Arguments
+    /// * `request` - Request to run on every occurrence
+    /// * `schedule` - `Schedule::At`, `Schedule::after`, or `Schedule::cron`
+    ///
+    /// # Returns
+    /// Job id for `cancel_scheduled`, or an error if the request or cron
+    /// expression is invalid
+    pub fn schedule(&self, request: ExecutionRequest, schedule: Schedule) -> CyloResult<String> {
+        request.validate()?;
+        let job = ScheduledJob::new(request, schedule)?;
+        let id = job.id.clone();
+        self.scheduler().add(job);
+        Ok(id)
+    }
+
+    /// Cancel a pending scheduled job
+    ///
+    /// # Returns
+    /// Whether a job with that id was pending
+    pub fn cancel_scheduled(&self, job_id: &str) -> bool {
+        self.scheduler().cancel(job_id)
+    }
+
+    /// Pending scheduled jobs, soonest first
+    pub fn scheduled_jobs(&self) -> Vec<ScheduledJob> {
+        self.scheduler().jobs()
+    }
+
+    /// Executions of this executor currently in flight, oldest first
+    ///
+    /// An execution is listed from the moment its ID is assigned until its
+    /// result is returned, with the resources it holds at the time of the
+    /// call.
+    pub fn active_executions(&self) -> Vec<ActiveExecution> {
+        self.shared.active.snapshot()
+    }
+
+    /// Stop an in-flight execution
+    ///
+    /// Terminates every process, container and VM tracked for it; the call
+    /// running it returns `ExecutionKilled`.
+    ///
+    /// # Arguments
+    /// * `execution_id` - ID of the execution, as listed by
+    ///   `active_executions`
+    ///
+    /// # Returns
+    /// false if no execution with the ID is in flight
+    pub fn kill(&self, execution_id: &str) -> bool {
+        self.shared.active.kill(execution_id)
+    }
+
+    /// Provision backends ahead of traffic and report their readiness
+    ///
+    /// Runs a hello-world program per language on each backend, pulling
+    /// images, booting pooled VMs and filling compiler caches on the way.
+    /// Backends warm up in parallel. The report is also kept for
+    /// `readiness`, so health endpoints can gate traffic on it.
+    ///
+    /// # Arguments
+    /// * `backends` - Backends to warm up; every available backend if empty
+    /// * `languages` - Languages to warm up; every known language if empty
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to the readiness report
+    pub fn warm_up(
+        &self,
+        backends: &[&str],
+        languages: &[&str],
+    ) -> AsyncTask<CyloResult<ReadinessReport>> {
+        let context = self.context();
+        let readiness = Arc::clone(&self.shared.readiness);
+        let mut backends: Vec<String> = backends.iter().map(|name| name.to_string()).collect();
+        let mut languages: Vec<String> = languages.iter().map(|name| name.to_string()).collect();
+        if languages.is_empty() {
+            languages = language::LANGUAGES.iter().map(|spec| spec.name.to_string()).collect();
+        }
+
+        AsyncTaskBuilder::new(async move {
+            if backends.is_empty() {
+                backends = context
+                    .platform_cache
+                    .read()
+                    .map_err(|e| CyloError::internal(format!("Cache lock poisoned: {}", e)))?
+                    .available_backends
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .collect();
+            }
+
+            let report = warm_up::warm_up(backends, languages, move |request| {
+                let context = context.clone();
+                async move {
+                    context
+                        .run(request, None)
+                        .await
+                        .and_then(|routed| routed.result)
+                }
+            })
+            .await;
+            info!(
+                target: targets::EXECUTOR,
+                "Warm-up finished; ready backends: {:?}",
+                report.ready_backends()
+            );
+            *readiness
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(report.clone());
+            Ok(report)
+        })
+        .spawn()
+    }
+
+    /// Report of the latest warm-up, None before the first one finished
+    pub fn readiness(&self) -> Option<ReadinessReport> {
+        self.shared
+            .readiness
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Price every subsequent execution with a cost model
+    ///
+    /// Usage is metered whether or not a model is set; the model only
+    /// determines the `amount` recorded on results, metrics, and audit logs.
+    ///
+    /// # Arguments
+    /// * `model` - Cost model, e.g. a `RateCard`
+    pub fn set_cost_model<M: CostModel + 'static>(&self, model: M) {
+        *self
+            .shared
+            .cost_model
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(model));
+    }
+
+    /// Choose registered instances with a custom selector
+    ///
+    /// Replaces the configured `instance_selection` strategy for every
+    /// subsequent execution routed without an instance hint.
+    ///
+    /// # Arguments
+    /// * `selector` - Instance selector, e.g. a `SelectionStrategy`
+    pub fn set_instance_selector<S: InstanceSelector + 'static>(&self, selector: S) {
+        *self
+            .shared
+            .instance_selector
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(selector));
+    }
+
+    /// Forward requests no local backend can serve to a remote worker
+    ///
+    /// Among registered workers, the least loaded one advertising a backend
+    /// that meets the request's requirements and this executor's
+    /// preferences runs it. Requests using host paths, volumes or blobs of
+    /// this host are never forwarded, and requests carrying environment
+    /// variables or git credentials only go to authenticated workers. A
+    /// worker with the same id is replaced.
+    ///
+    /// # Arguments
+    /// * `worker` - Remote worker, e.g. an `HttpWorker`
+    pub fn add_worker<W: RemoteWorker + 'static>(&self, worker: W) {
+        self.shared.federation.add(Arc::new(worker));
+    }
+
+    /// Stop forwarding requests to a remote worker
+    ///
+    /// # Returns
+    /// Whether a worker with that id was registered
+    pub fn remove_worker(&self, worker_id: &str) -> bool {
+        self.shared.federation.remove(worker_id)
+    }
+
+    /// Latest advertisements of the registered workers that answered
+    pub fn workers(&self) -> Vec<WorkerAdvertisement> {
+        self.shared.federation.advertisements()
+    }
+
+    /// Fetch every registered worker's advertisement now
+    ///
+    /// Advertisements are otherwise fetched when routing finds them stale.
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to the advertisements of the workers that
+    /// answered
+    pub fn refresh_workers(&self) -> AsyncTask<Vec<WorkerAdvertisement>> {
+        let federation = Arc::clone(&self.shared.federation);
+        AsyncTaskBuilder::new(async move { federation.refresh(true).await }).spawn()
+    }
+
+    /// What this executor offers as a worker of another executor
+    ///
+    /// A worker serves this on `ADVERTISEMENT_PATH` and runs requests
+    /// posted to `EXECUTE_PATH` with `execute`.
+    ///
+    /// # Arguments
+    /// * `worker_id` - Identity of this worker within the fleet
+    pub fn advertisement<I: Into<String>>(&self, worker_id: I) -> CyloResult<WorkerAdvertisement> {
+        let backends = self
+            .shared
+            .platform_cache
+            .read()
+            .map_err(|e| CyloError::internal(format!("Cache lock poisoned: {}", e)))?
+            .available_backends
+            .clone();
+        Ok(WorkerAdvertisement {
+            worker_id: worker_id.into(),
+            os: std::env::consts::OS.to_string(),
+            arch: crate::backends::Arch::host(),
+            backends,
+            in_flight: self.shared.active.snapshot().len(),
+            capacity: num_cpus::get(),
+        })
+    }
+
+    /// Identity embedded in the names of every directory, container, and VM
+    /// this executor's backends create
+    ///
+    /// Backend cleanup only touches resources carrying this identity.
+    pub fn identity(&self) -> &'static str {
+        crate::backends::executor_identity()
+    }
+
     /// Get execution metrics and performance statistics
     ///
     /// # Returns
     /// Current execution metrics
     pub fn get_metrics(&self) -> CyloResult<ExecutionMetrics> {
-        let metrics = self.metrics.read().map_err(|e| {
-            CyloError::internal(format!("Failed to read metrics: {}", e))
-        })?;
-        Ok(metrics.clone())
+        let mut metrics = self
+            .shared
+            .metrics
+            .read()
+            .map_err(|e| CyloError::internal(format!("Failed to read metrics: {}", e)))?
+            .clone();
+        metrics.tasks = TaskStats {
+            active_executions: self.shared.active.snapshot().len(),
+            sampled_processes: global_sampler().watched(),
+            blocking_io: blocking::backend_io().stats(),
+            runtime_tasks: tokio::runtime::Handle::try_current()
+                .ok()
+                .map(|runtime| runtime.metrics().num_alive_tasks()),
+        };
+        Ok(metrics)
+    }
+
+    /// Metrics snapshots persisted by this and earlier executors
+    ///
+    /// # Returns
+    /// Snapshots oldest first, for latency and error-rate trends; empty
+    /// when the executor was created without
+    /// `OptimizationConfig::metrics_store`
+    pub fn metrics_history(&self) -> Vec<MetricsSnapshot> {
+        self.metrics_persister
+            .as_ref()
+            .map(|persister| persister.store().history())
+            .unwrap_or_default()
+    }
+
+    /// Atomically replace the executor configuration
+    ///
+    /// Executions already in flight keep the configuration they started
+    /// with; every execution started afterwards sees the new one.
+    ///
+    /// # Arguments
+    /// * `config` - New routing, preference, and optimization settings
+    ///
+    /// # Returns
+    /// Ok(()) once swapped, or a validation error leaving the old config
+    pub fn apply_config(&self, config: ExecutorConfig) -> CyloResult<()> {
+        config.validate()?;
+        reload::replace(&self.shared.config, config);
+        Ok(())
+    }
+
+    /// Snapshot of the current executor configuration
+    pub fn config(&self) -> ExecutorConfig {
+        self.shared.config()
+    }
+
+    /// Reload the configuration from a JSON file whenever it changes
+    ///
+    /// The file is applied once immediately if it exists and is valid.
+    /// Watching stops when the returned watcher is dropped.
+    ///
+    /// # Arguments
+    /// * `path` - Config file in `ExecutorConfig` JSON format
+    pub fn watch_config_file<P: Into<PathBuf>>(&self, path: P) -> CyloResult<ConfigWatcher> {
+        let path = path.into();
+        if path.exists() {
+            self.apply_config(ExecutorConfig::load(&path)?)?;
+        }
+        ConfigWatcher::spawn(path, Arc::clone(&self.shared.config))
     }
 
     /// Update executor configuration
     ///
     /// # Arguments
     /// * `config` - New optimization configuration
-    pub fn update_config(&mut self, config: OptimizationConfig) {
-        self.optimization_config = config;
+    ///
+    /// # Returns
+    /// Ok(()) once applied, or a validation error leaving the current
+    /// configuration in place
+    pub fn update_config(&self, config: OptimizationConfig) -> CyloResult<()> {
+        reload::update(&self.shared.config, |updated| updated.optimization = config)
     }
 
     /// Update backend preferences
     ///
     /// # Arguments
     /// * `preferences` - New backend preferences
-    pub fn update_preferences(&mut self, preferences: BackendPreferences) {
-        self.backend_preferences = preferences;
+    ///
+    /// # Returns
+    /// Ok(()) once applied, or a validation error leaving the current
+    /// configuration in place
+    pub fn update_preferences(&self, preferences: BackendPreferences) -> CyloResult<()> {
+        reload::update(&self.shared.config, |updated| updated.preferences = preferences)
+    }
+
+    /// Register a request template, replacing one of the same name
+    ///
+    /// # Arguments
+    /// * `name` - Name requests are built from, e.g. "python-data-analysis"
+    /// * `template` - Language and settings every request built from it gets
+    ///
+    /// # Returns
+    /// Ok(()) once registered, or a validation error for an unusable
+    /// template
+    pub fn register_template<N: Into<String>>(
+        &self,
+        name: N,
+        template: RequestTemplate,
+    ) -> CyloResult<()> {
+        let name = name.into();
+        template.validate(&name)?;
+        reload::update(&self.shared.config, |updated| {
+            updated.templates.insert(name, template);
+        })
+    }
+
+    /// Remove a request template
+    ///
+    /// # Returns
+    /// Whether a template with that name was registered
+    pub fn unregister_template(&self, name: &str) -> bool {
+        let mut updated = self.config();
+        let removed = updated.templates.remove(name).is_some();
+        if removed {
+            reload::replace(&self.shared.config, updated);
+        }
+        removed
+    }
+
+    /// Registered request templates, keyed by name
+    pub fn templates(&self) -> HashMap<String, RequestTemplate> {
+        self.shared.snapshot().templates.clone()
+    }
+
+    /// Build a request from a registered template
+    ///
+    /// # Arguments
+    /// * `name` - Template to build from
+    /// * `code` - Source code the request runs
+    ///
+    /// # Returns
+    /// The request, ready to adjust further and pass to `execute`, or an
+    /// invalid request error if no template has that name
+    pub fn from_template<C: Into<String>>(
+        &self,
+        name: &str,
+        code: C,
+    ) -> CyloResult<ExecutionRequest> {
+        templates::instantiate(&self.shared.snapshot().templates, name, code.into())
     }
 
     /// Refresh platform cache if needed
@@ -225,14 +850,14 @@ impl CyloExecutor {
     /// # Returns
     /// AsyncTask that resolves when cache is refreshed
     pub fn refresh_platform_cache(&self) -> AsyncTask<CyloResult<()>> {
-        let platform_cache = Arc::clone(&self.platform_cache);
+        let platform_cache = Arc::clone(&self.shared.platform_cache);
 
-        AsyncTaskBuilder::new().spawn(move || async move {
+        AsyncTaskBuilder::new(async move {
             // Check if cache needs refresh
             let should_refresh = {
                 let cache = platform_cache
                     .read()
-                    .map_err(|e| CyloError::Other(format!("Cache lock poisoned: {}", e)))?;
+                    .map_err(|e| CyloError::internal(format!("Cache lock poisoned: {}", e)))?;
 
                 let current_time = SystemTime::now();
                 let cache_age = current_time
@@ -247,33 +872,13 @@ impl CyloExecutor {
             }
 
             // Detect current platform capabilities
-            let platform_info = detect_platform();
-            let available_backends: Vec<(String, u8)> = get_available_backends()
-                .into_iter()
-                .map(|name| {
-                    let rating = platform_info
-                        .available_backends
-                        .iter()
-                        .find(|b| b.name == name)
-                        .map(|b| b.performance_rating)
-                        .unwrap_or(0);
-                    (name, rating)
-                })
-                .collect();
-
-            let capabilities_hash = {
-                use std::collections::hash_map::DefaultHasher;
-                use std::hash::{Hash, Hasher};
-                let mut hasher = DefaultHasher::new();
-                platform_info.os.hash(&mut hasher);
-                platform_info.arch.hash(&mut hasher);
-                hasher.finish()
-            };
+            let available_backends = available_with_ratings();
+            let capabilities_hash = routing::compute_capabilities_hash(detected_backends());
 
             // Update cache with write lock
             let mut cache = platform_cache
                 .write()
-                .map_err(|e| CyloError::Other(format!("Cache lock poisoned: {}", e)))?;
+                .map_err(|e| CyloError::internal(format!("Cache lock poisoned: {}", e)))?;
 
             cache.available_backends = available_backends;
             cache.capabilities_hash = capabilities_hash;
@@ -281,6 +886,767 @@ impl CyloExecutor {
 
             Ok(())
         })
+        .spawn()
+    }
+
+    /// Health of every available backend
+    ///
+    /// Results come from the global instance manager's health cache, which
+    /// routing also consults to avoid backends whose last check failed.
+    /// Backends are only probed when their cached result has expired.
+    ///
+    /// # Arguments
+    /// * `level` - Liveness for a cheap presence check, Readiness to run a
+    ///   probe program in each backend
+    /// * `force` - Probe every backend even if a fresh result is cached
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to health status per backend name
+    pub fn backend_health(
+        &self,
+        level: HealthCheckLevel,
+        force: bool,
+    ) -> AsyncTask<CyloResult<HashMap<String, HealthStatus>>> {
+        let platform_cache = Arc::clone(&self.shared.platform_cache);
+
+        AsyncTaskBuilder::new(async move {
+            let backends: Vec<String> = platform_cache
+                .read()
+                .map_err(|e| CyloError::internal(format!("Cache lock poisoned: {}", e)))?
+                .available_backends
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect();
+
+            let health = global_instance_manager().health_cache();
+            let probe = ExecutionRequest::new("true", "bash");
+            let mut results = HashMap::new();
+            for name in backends {
+                if !force && let Some(status) = health.get(&name, level) {
+                    results.insert(name, status);
+                    continue;
+                }
+
+                let backend = routing::create_cylo_env(&name, &probe).and_then(|env| {
+                    create_backend(&env, BackendConfig::new(&format!("health_{}", name)))
+                });
+                let status = match backend {
+                    Ok(backend) => health.check(&name, backend.as_ref(), level, true).await,
+                    Err(e) => {
+                        let status = HealthStatus::unhealthy(e.to_string());
+                        health.record(&name, level, status.clone());
+                        status
+                    }
+                };
+                results.insert(name, status);
+            }
+
+            Ok(results)
+        })
+        .spawn()
+    }
+}
+
+impl CyloExecutor {
+    /// Snapshot the state needed by a single execution task
+    fn context(&self) -> ExecutionContext {
+        self.shared.context()
+    }
+
+    /// The executor's scheduler, loading persisted jobs and starting its
+    /// loop on first use
+    fn scheduler(&self) -> &Arc<Scheduler> {
+        self.scheduler.get_or_init(|| {
+            let store = self.config().optimization.schedule_store;
+            let scheduler = Arc::new(Scheduler::open(store));
+
+            let shared = self.shared.clone();
+            let run = move |job_id: String, request: ExecutionRequest| {
+                let context = shared.context();
+                tokio::spawn(async move {
+                    match context.run(request, None).await.and_then(|routed| routed.result) {
+                        Ok(result) => info!(
+                            target: targets::EXECUTOR,
+                            "Scheduled job {} finished with exit code {}",
+                            job_id, result.exit_code
+                        ),
+                        Err(e) => warn!(
+                            target: targets::EXECUTOR,
+                            "Scheduled job {} failed: {}",
+                            job_id,
+                            e
+                        ),
+                    }
+                });
+            };
+            tokio::spawn(schedule::run_loop(Arc::downgrade(&scheduler), run));
+
+            scheduler
+        })
+    }
+}
+
+impl SharedState {
+    /// Copy of the current executor configuration
+    fn config(&self) -> ExecutorConfig {
+        ExecutorConfig::clone(&self.snapshot())
+    }
+
+    /// The current executor configuration, shared rather than copied
+    fn snapshot(&self) -> Arc<ExecutorConfig> {
+        Arc::clone(&self.config.read().unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+
+    /// Snapshot the state needed by a single execution task
+    fn context(&self) -> ExecutionContext {
+        ExecutionContext {
+            config: self.snapshot(),
+            platform_cache: Arc::clone(&self.platform_cache),
+            metrics: Arc::clone(&self.metrics),
+            middleware: self
+                .middleware
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone(),
+            cost_model: self
+                .cost_model
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone(),
+            instance_selector: self
+                .instance_selector
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone(),
+            crash_loops: Arc::clone(&self.crash_loops),
+            active: Arc::clone(&self.active),
+            federation: Arc::clone(&self.federation),
+        }
+    }
+}
+
+/// Executor state moved into each execution task
+#[derive(Debug, Clone)]
+struct ExecutionContext {
+    config: Arc<ExecutorConfig>,
+    platform_cache: Arc<RwLock<PlatformCache>>,
+    metrics: Arc<RwLock<ExecutionMetrics>>,
+    middleware: MiddlewareChain,
+    cost_model: Option<Arc<dyn CostModel>>,
+    instance_selector: Option<Arc<dyn InstanceSelector>>,
+    crash_loops: Arc<Mutex<CrashLoopTracker>>,
+    active: Arc<ActiveRegistry>,
+    federation: Arc<Federation>,
+}
+
+/// Outcome of a routed execution along with where it ran
+struct RoutedExecution {
+    backend_name: String,
+    instance: CyloInstance,
+    result: CyloResult<ExecutionResult>,
+}
+
+/// State carried from admitting a request to executing it
+struct Prepared {
+    clock: PhaseClock,
+    deadline: Option<Deadline>,
+    fingerprint: String,
+    dependencies: Option<&'static str>,
+    budget: Option<BudgetDraw>,
+    transcript: Option<TranscriptTap>,
+    attachment: volumes::Attachment,
+}
+
+impl ExecutionContext {
+    /// Run a request through the middleware chain, routing, and execution
+    ///
+    /// Routing failures are returned as errors; execution failures are
+    /// carried in `RoutedExecution::result` so callers know where they ran.
+    async fn run(
+        self,
+        mut request: ExecutionRequest,
+        instance_hint: Option<CyloInstance>,
+    ) -> CyloResult<RoutedExecution> {
+        let middleware = self.middleware.clone();
+
+        let prepared = match middleware.before_execute(&mut request) {
+            Ok(()) => self.prepare(&mut request).await,
+            Err(e) => Err(e),
+        };
+
+        // From here on the request is only read, so it is shared with the
+        // execution tasks rather than copied into each of them
+        let request = Arc::new(request);
+        let routed = match prepared {
+            Ok(prepared) => self.route_and_execute(&request, prepared, instance_hint).await,
+            Err(e) => Err(e),
+        };
+
+        match routed {
+            Ok(mut routed) => {
+                match &mut routed.result {
+                    Ok(result) => middleware.after_execute(&request, &routed.backend_name, result),
+                    Err(e) => middleware.on_error(&request, e),
+                }
+                Ok(routed)
+            }
+            Err(e) => {
+                middleware.on_error(&request, &e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Route and execute a request without running middleware
+    async fn route(
+        self,
+        mut request: ExecutionRequest,
+        instance_hint: Option<CyloInstance>,
+    ) -> CyloResult<RoutedExecution> {
+        let prepared = self.prepare(&mut request).await?;
+        self.route_and_execute(&Arc::new(request), prepared, instance_hint)
+            .await
+    }
+
+    /// Take a request through admission and routing without executing it
+    ///
+    /// Mirrors `run`, `prepare` and `route_and_execute` up to the point
+    /// where a backend would be called, checking each policy without
+    /// recording anything against it.
+    async fn plan(
+        self,
+        mut request: ExecutionRequest,
+        instance_hint: Option<CyloInstance>,
+    ) -> ExecutionPlan {
+        let mut verdicts = Vec::new();
+        let middleware = self.middleware.before_execute(&mut request);
+        verdicts.push(PolicyVerdict::from_result("middleware", &middleware));
+
+        let profile = reload::profile_for(&self.config.language_profiles, &request.language);
+        reload::apply_defaults(self.config.default_limits.as_ref(), profile, &mut request);
+        verdicts.push(PolicyVerdict::from_result("validation", &request.validate()));
+
+        let fingerprint = crash_loop::fingerprint(&request);
+        let crash_loop = self
+            .crash_loops
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .check(&self.config.optimization.crash_loop, &fingerprint, Instant::now());
+        verdicts.push(PolicyVerdict::from_result("crash_loop", &crash_loop));
+        let quotas = volumes::check_quotas(&request.volumes);
+        verdicts.push(PolicyVerdict::from_result("volume_quota", &quotas));
+        let blobs = blob_store::check(request.blob_files.values());
+        verdicts.push(PolicyVerdict::from_result("blob_store", &blobs));
+        if let Some(budget) = request.budget.clone() {
+            let drawable = budget.check();
+            if drawable.is_ok() {
+                budget.clamp(&mut request);
+            }
+            verdicts.push(PolicyVerdict::from_result("budget", &drawable));
+        }
+
+        let mut plan = ExecutionPlan {
+            backend: None,
+            worker: None,
+            instance: None,
+            image: None,
+            hedge_backend: None,
+            timeout: request.timeout,
+            limits: request.limits.clone(),
+            verdicts,
+        };
+
+        let routed = match instance_hint {
+            Some(instance) => {
+                let backend_name = routing::backend_name_from_cylo(&instance.env);
+                let requirements = routing::check_requirements(&backend_name, &request);
+                plan.verdicts.push(PolicyVerdict::from_result("requirements", &requirements));
+                requirements.ok().map(|()| (backend_name, Some(instance)))
+            }
+            None => {
+                let selected = routing::select_optimal_backend(
+                    &self.config.routing_strategy,
+                    &self.config.preferences,
+                    &self.platform_cache,
+                    &request,
+                );
+                match selected {
+                    Ok(backend_name) => {
+                        let instance = self.select_instance(&backend_name, &request);
+                        plan.verdicts.push(PolicyVerdict::from_result("routing", &instance));
+                        if let (Ok(_), RoutingStrategy::Hedged { .. }, None, None) = (
+                            &instance,
+                            &self.config.routing_strategy,
+                            &request.input_stream,
+                            &request.sandbox_state,
+                        ) {
+                            let hedge = routing::select_hedge_backend(
+                                &self.config.preferences,
+                                &self.platform_cache,
+                                &request,
+                                &backend_name,
+                            );
+                            plan.verdicts.push(PolicyVerdict::from_result("hedge", &hedge));
+                            plan.hedge_backend = hedge.ok().flatten();
+                        }
+                        instance.ok().map(|instance| (backend_name, Some(instance)))
+                    }
+                    Err(e) => {
+                        // A remote worker may serve what nothing here can;
+                        // it picks its own instance, and the claim on it is
+                        // released as the dispatch drops
+                        let preferences = &self.config.preferences;
+                        let remote = self.federation.dispatch(&request, preferences).await;
+                        let routing = remote.as_ref().map(|_| ()).ok_or(e);
+                        plan.verdicts.push(PolicyVerdict::from_result("routing", &routing));
+                        remote.map(|dispatch| {
+                            plan.worker = Some(dispatch.worker_id().to_string());
+                            (dispatch.backend().to_string(), None)
+                        })
+                    }
+                }
+            }
+        };
+        if let Some((backend_name, instance)) = routed {
+            plan.image = instance.as_ref().and_then(|instance| image_of(&instance.env));
+            plan.backend = Some(backend_name);
+            plan.instance = instance;
+        }
+
+        // Forwarded work runs on the worker's resources
+        if plan.worker.is_none() {
+            let admission = host_guard::admit(&self.config.optimization.host_guard).await;
+            plan.verdicts.push(PolicyVerdict::from_result("host_guard", &admission));
+        }
+        plan
+    }
+
+    /// Complete and admit a request: apply configured defaults, validate
+    /// it, and provision its dependencies
+    async fn prepare(&self, request: &mut ExecutionRequest) -> CyloResult<Prepared> {
+        // Fill in configured limits, then reject malformed requests before
+        // any backend resources are allocated
+        let profile = reload::profile_for(&self.config.language_profiles, &request.language);
+        reload::apply_defaults(self.config.default_limits.as_ref(), profile, request);
+        request.validate()?;
+
+        // The total budget runs from here to the backend's answer
+        let clock = PhaseClock::default();
+        let deadline = Deadline::start(request, self.config.optimization.total_timeout);
+
+        // Don't spend a sandbox on code that keeps crashing the same way
+        let fingerprint = crash_loop::fingerprint(request);
+        self.crash_loops
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .check(&self.config.optimization.crash_loop, &fingerprint, Instant::now())?;
+
+        // Volumes over their quota are not attached again, and blobs must
+        // still be stored
+        let attachment = volumes::admit(&request.volumes)?;
+        blob_store::admit(request.blob_files.values())?;
+
+        // Draw on the request's budget last, so requests refused above
+        // spend none of it
+        let budget = match request.budget.clone() {
+            Some(budget) => Some(budget.draw(request)?),
+            None => None,
+        };
+
+        // Install declared dependencies in their own execution, or reuse an
+        // earlier install of the same lockfile
+        let dependencies =
+            deadline::within(deadline.as_ref(), self.provision_dependencies(request)).await?;
+
+        // Tag the execution so the reaper can tie spawned resources to it
+        request
+            .execution_id
+            .get_or_insert_with(|| uuid::Uuid::new_v4().to_string());
+
+        // Record the input now and the output as the backend streams it
+        let transcript = request
+            .transcript
+            .clone()
+            .map(|recorder| TranscriptTap::attach(recorder, request));
+
+        Ok(Prepared {
+            clock,
+            deadline,
+            fingerprint,
+            dependencies,
+            budget,
+            transcript,
+            attachment,
+        })
+    }
+
+    /// Instance a request routed to `backend_name` runs on
+    ///
+    /// With instance reuse and load balancing on, the instance selector
+    /// chooses among the registered instances of the request's
+    /// environment. A new instance is started when it chooses none, or when
+    /// the chosen one has no free slot and the pool still has room.
+    fn select_instance(
+        &self,
+        backend_name: &str,
+        request: &ExecutionRequest,
+    ) -> CyloResult<CyloInstance> {
+        let cylo_env = routing::create_cylo_env(backend_name, request)?;
+        let optimization = &self.config.optimization;
+        if optimization.instance_reuse && optimization.load_balancing {
+            let candidates = global_instance_manager().instance_candidates(&cylo_env)?;
+            let selector: &dyn InstanceSelector = match &self.instance_selector {
+                Some(selector) => selector.as_ref(),
+                None => &optimization.instance_selection,
+            };
+            if let Some(chosen) = selector.select(&candidates).and_then(|i| candidates.get(i)) {
+                let pool_full = candidates.len() >= optimization.instance_pool_size as usize;
+                if chosen.free_slots > 0 || pool_full {
+                    return Ok(chosen.instance.clone());
+                }
+            }
+        }
+        Ok(cylo_env.instance(routing::generate_instance_name(&request.language)))
+    }
+
+    /// Route a prepared request to a backend, execute it, and record metrics
+    async fn route_and_execute(
+        self,
+        shared_request: &Arc<ExecutionRequest>,
+        prepared: Prepared,
+        instance_hint: Option<CyloInstance>,
+    ) -> CyloResult<RoutedExecution> {
+        let request: &ExecutionRequest = shared_request;
+        let Prepared {
+            clock,
+            deadline,
+            fingerprint,
+            dependencies,
+            budget,
+            transcript,
+            attachment,
+        } = prepared;
+        let execution_id = request.execution_id.clone().unwrap_or_default();
+        global_reaper().ensure_sweeper(self.config.optimization.monitoring_interval);
+        let active = self.active.register(&execution_id, &request.language);
+
+        let new_instance = |backend_name: &str| -> CyloResult<CyloInstance> {
+            let cylo_env = routing::create_cylo_env(backend_name, request)?;
+            Ok(cylo_env.instance(routing::generate_instance_name(&request.language)))
+        };
+
+        // Route to optimal backend
+        let (backend_name, cylo_instance, hedge, remote) = match instance_hint {
+            Some(instance) => {
+                // Use explicitly provided instance, provided it meets the
+                // request's backend and isolation requirements
+                let backend_name = routing::backend_name_from_cylo(&instance.env);
+                routing::check_requirements(&backend_name, request)?;
+                (backend_name, instance, None, None)
+            }
+            None => {
+                // Intelligent backend selection
+                let selected = routing::select_optimal_backend(
+                    &self.config.routing_strategy,
+                    &self.config.preferences,
+                    &self.platform_cache,
+                    request,
+                );
+                // Nothing here can serve it; a remote worker may
+                let remote = match &selected {
+                    Ok(_) => None,
+                    Err(_) => self.federation.dispatch(request, &self.config.preferences).await,
+                };
+                if let Some(dispatch) = remote {
+                    // Backends not built in here are named as they are there
+                    let backend_name = dispatch.backend().to_string();
+                    let cylo_env = routing::create_cylo_env(&backend_name, request)
+                        .unwrap_or_else(|_| Cylo::Custom {
+                            backend: backend_name.clone(),
+                            config: dispatch.worker_id().to_string(),
+                        });
+                    let instance =
+                        cylo_env.instance(routing::generate_instance_name(&request.language));
+                    (backend_name, instance, None, Some(dispatch))
+                } else {
+                    let backend_name = selected?;
+                    let cylo_instance = self.select_instance(&backend_name, request)?;
+
+                    // Hedged routing also readies a runner-up to race against
+                    // it, unless the request must run only once
+                    let hedge = match &self.config.routing_strategy {
+                        RoutingStrategy::Hedged { delay } => {
+                            routing::select_hedge_backend(
+                                &self.config.preferences,
+                                &self.platform_cache,
+                                request,
+                                &backend_name,
+                            )?
+                            .map(|hedge_name| -> CyloResult<(HedgeLeg, Duration)> {
+                                let instance = new_instance(&hedge_name)?;
+                                let leg = HedgeLeg {
+                                    backend_name: hedge_name,
+                                    instance,
+                                };
+                                Ok((leg, *delay))
+                            })
+                            .transpose()?
+                        }
+                        _ => None,
+                    };
+
+                    (backend_name, cylo_instance, hedge, None)
+                }
+            }
+        };
+        active.set_backend(&backend_name);
+
+        // Refuse to start work the host cannot absorb; forwarded work runs
+        // on the worker's resources
+        if remote.is_none() {
+            active.set_state(ExecutionState::Admitting);
+            let host_guard = &self.config.optimization.host_guard;
+            let admission = active.unless_killed(host_guard::admit(host_guard));
+            deadline::within(deadline.as_ref(), admission).await?;
+        }
+
+        // Execute with selected backend, or race it against the hedge; a
+        // kill returns at once and leaves the backend to wind down
+        active.set_state(ExecutionState::Running);
+        let execution = async {
+            if let Some(dispatch) = &remote {
+                clock.enter(Phase::Running);
+                let forwarded = dispatch.execute(request, crate::backends::executor_identity());
+                let result = active.unless_killed(forwarded).await;
+                return (backend_name.clone(), cylo_instance.clone(), result);
+            }
+            match hedge {
+                None => {
+                    let execution = execution::execute_with_backend(
+                        backend_name.clone(),
+                        cylo_instance.clone(),
+                        Arc::clone(shared_request),
+                        Arc::clone(&self.config),
+                        clock.clone(),
+                    );
+                    let execution =
+                        volumes::within_quotas(&request.volumes, &execution_id, execution);
+                    let result = active.unless_killed(execution).await;
+                    (backend_name.clone(), cylo_instance.clone(), result)
+                }
+                Some((secondary, delay)) => {
+                    clock.enter(Phase::Running);
+                    let primary = HedgeLeg {
+                        backend_name: backend_name.clone(),
+                        instance: cylo_instance.clone(),
+                    };
+                    let hedged = active
+                        .unless_killed(async {
+                            Ok(hedge::execute_hedged(
+                                primary,
+                                secondary,
+                                shared_request,
+                                delay,
+                                &self.config,
+                            )
+                            .await)
+                        })
+                        .await;
+
+                    match hedged {
+                        Ok(mut hedged) => {
+                            if hedged.hedge_launched {
+                                let winner = hedged.leg.backend_name.as_str();
+                                metrics::record_hedge(
+                                    &self.metrics,
+                                    hedged.hedge_won.then_some(winner),
+                                );
+                                if let Ok(result) = &mut hedged.result {
+                                    let copy = if hedged.hedge_won { "hedge" } else { "primary" };
+                                    result
+                                        .metadata
+                                        .insert("hedge_winner".to_string(), copy.to_string());
+                                }
+                            }
+                            (hedged.leg.backend_name, hedged.leg.instance, hedged.result)
+                        }
+                        Err(e) => (backend_name.clone(), cylo_instance.clone(), Err(e)),
+                    }
+                }
+            }
+        };
+
+        // Past the total budget the execution is abandoned wherever it got
+        // to; dropping it releases its slot and stops the backend's task
+        let (backend_name, cylo_instance, mut result) = match &deadline {
+            Some(deadline) => match deadline.race(execution).await {
+                Some(executed) => executed,
+                None => {
+                    let timed_out = deadline.exceeded(&clock);
+                    (backend_name.clone(), cylo_instance.clone(), Ok(timed_out))
+                }
+            },
+            None => execution.await,
+        };
+        if let Ok(exec_result) = &mut result
+            && !exec_result.metadata.contains_key(TIMEOUT_PHASE_METADATA)
+        {
+            clock.record(&mut exec_result.metadata);
+        }
+        active.set_state(ExecutionState::Finishing);
+
+        // Anything the execution left running is now eligible for reaping,
+        // and its volumes can be restored again
+        global_reaper().finish_execution(&execution_id);
+        drop(attachment);
+
+        if let Some(transcript) = &transcript {
+            transcript.finish(result.as_ref().ok());
+        }
+
+        // Compiler output is reported apart from the program's own
+        if let Ok(exec_result) = &mut result {
+            compile_phase::split_phases(exec_result);
+        }
+
+        // Count crashes toward crash loop detection
+        if let Ok(exec_result) = &result {
+            self.crash_loops
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .record(
+                    &self.config.optimization.crash_loop,
+                    &fingerprint,
+                    exec_result,
+                    Instant::now(),
+                );
+        }
+
+        if let (Ok(exec_result), Some(provisioned)) = (&mut result, dependencies) {
+            exec_result
+                .metadata
+                .insert(DEPENDENCIES_METADATA.to_string(), provisioned.to_string());
+        }
+
+        if let Ok(exec_result) = &mut result
+            && !request.volumes.is_empty()
+        {
+            exec_result.metadata.insert(
+                VOLUME_USAGE_METADATA.to_string(),
+                volumes::usage_metadata(&request.volumes),
+            );
+        }
+
+        // SQL result sets are returned as rows as well as printed
+        if let Ok(exec_result) = &mut result
+            && exec_result.exit_code == 0
+            && language::resolve(&request.language).is_some_and(|spec| spec.name == "sql")
+        {
+            exec_result.result_sets = parse_result_sets(&exec_result.stdout);
+        }
+
+        // Strip escapes and extract structures before the expectations see
+        // the output
+        if let Ok(exec_result) = &mut result {
+            post_process::apply(&request.post_processors, exec_result);
+        }
+
+        // Evaluate exit-policy assertions
+        if let (Ok(exec_result), Some(expectations)) = (&mut result, &request.expectations) {
+            exec_result.verdict = Some(expectations.evaluate(exec_result));
+        }
+
+        // Attribute usage and cost to the request's tenant
+        if let Ok(exec_result) = &mut result {
+            exec_result.cost = Some(cost::attribute(
+                self.cost_model.as_deref(),
+                &backend_name,
+                request,
+                exec_result,
+            ));
+        }
+
+        // Large outputs are stored as blobs once everything above has seen
+        // them; whatever could not be stored is returned inline
+        if let (Ok(exec_result), Some(outputs)) = (&mut result, &request.output_blobs)
+            && let Err(e) = blob_store::offload(exec_result, outputs)
+        {
+            warn!(target: targets::EXECUTOR, "Returning outputs inline: {}", e);
+        }
+
+        // Replace the budget's hold with what the execution used
+        if let Some(budget) = budget {
+            budget.settle(result.as_ref().ok());
+        }
+
+        // Update metrics
+        metrics::update_metrics(self.metrics, &backend_name, request, &result).await;
+
+        Ok(RoutedExecution {
+            backend_name,
+            instance: cylo_instance,
+            result,
+        })
+    }
+
+    /// Make a request's declared dependencies available to it
+    ///
+    /// An install only suits the environment it ran in, so a request that
+    /// names no backend is held to the one chosen for its install.
+    ///
+    /// # Returns
+    /// "cached" or "installed" when the request declares dependencies,
+    /// None otherwise; DependencyInstallFailed when the install did not
+    /// succeed
+    async fn provision_dependencies(
+        &self,
+        request: &mut ExecutionRequest,
+    ) -> CyloResult<Option<&'static str>> {
+        let config = &self.config.optimization.dependencies;
+        if !config.enabled || !NodeDependencies::declared_by(request) {
+            return Ok(None);
+        }
+        let backend_name = match &request.required_backend {
+            Some(backend_name) => backend_name.clone(),
+            None => routing::select_optimal_backend(
+                &self.config.routing_strategy,
+                &self.config.preferences,
+                &self.platform_cache,
+                request,
+            )?,
+        };
+        let environment = routing::create_cylo_env(&backend_name, request)?.to_string();
+        request.required_backend = Some(backend_name);
+        let Some(dependencies) = NodeDependencies::of(request, &environment) else {
+            return Ok(None);
+        };
+        if let Some(installed) = dependencies.cached(config) {
+            dependencies::attach(request, &installed);
+            return Ok(Some("cached"));
+        }
+
+        let staging = dependencies.staging_dir(config)?;
+        let install = dependencies.install_request(config, request, &staging);
+        // Boxed: the install is routed like any other execution
+        let routed: Pin<Box<dyn Future<Output = CyloResult<RoutedExecution>> + Send + '_>> =
+            Box::pin(self.clone().route(install, None));
+        let installed = match routed.await.and_then(|routed| routed.result) {
+            Ok(result) if result.is_success() => dependencies.commit(config, &staging),
+            Ok(result) => {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(CyloError::dependency_install_failed(
+                    dependencies.command(),
+                    result,
+                ));
+            }
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(e);
+            }
+        };
+
+        dependencies::attach(request, &installed);
+        Ok(Some("installed"))
     }
 }
 
@@ -289,3 +1655,13 @@ impl Default for CyloExecutor {
         Self::new()
     }
 }
+
+/// Available backends, built-in and registered, with their performance
+/// ratings
+fn available_with_ratings() -> Vec<(String, u8)> {
+    backend_availability()
+        .into_iter()
+        .filter(|backend| backend.available)
+        .map(|backend| (backend.name, backend.performance_rating))
+        .collect()
+}
@@ -0,0 +1,580 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/pipeline.rs
+//! ----------------------------------------------------------------------------
+//! Pipelines of dependent execution steps.
+//!
+//! A pipeline is a DAG of named steps. Each step starts as soon as every
+//! step it depends on has succeeded, so independent branches run in
+//! parallel and are routed to backends independently. A step can take a
+//! dependency's stdout as its stdin or as an environment variable, and a
+//! workspace directory a dependency declared as its output is unpacked
+//! into the step's own workspace. When a step fails, or its task panics,
+//! everything downstream of it is skipped.
+//! ============================================================================
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::future::Future;
+use std::path::{Component, Path};
+
+use tokio::task::JoinSet;
+
+use crate::backends::archive::{self, ArchiveFormat, OutputArchive, WorkspaceArchive};
+use crate::backends::{ExecutionRequest, ExecutionResult};
+use crate::execution_env::{CyloError, CyloResult};
+use crate::platform_utils::create_private_dir;
+
+/// One named step of a pipeline
+#[derive(Debug, Clone)]
+pub struct PipelineStep {
+    /// Unique step name
+    pub name: String,
+    /// Request the step executes
+    pub request: ExecutionRequest,
+    /// Steps that must succeed before this one starts
+    pub depends_on: Vec<String>,
+    /// Step whose stdout becomes this step's stdin
+    pub input_from: Option<String>,
+    /// Environment variables set to a step's stdout, as (step, variable)
+    pub env_from: Vec<(String, String)>,
+    /// Steps whose declared outputs are unpacked into this step's
+    /// workspace, as (step, workspace-relative directory)
+    pub outputs_from: Vec<(String, String)>,
+}
+
+impl PipelineStep {
+    /// Create a step with no dependencies
+    pub fn new<N: Into<String>>(name: N, request: ExecutionRequest) -> Self {
+        Self {
+            name: name.into(),
+            request,
+            depends_on: Vec::new(),
+            input_from: None,
+            env_from: Vec::new(),
+            outputs_from: Vec::new(),
+        }
+    }
+
+    /// Start only after `step` has succeeded
+    pub fn after<S: Into<String>>(mut self, step: S) -> Self {
+        self.add_dependency(step.into());
+        self
+    }
+
+    /// Feed `step`'s stdout to this step's stdin; implies `after(step)`
+    pub fn input_from<S: Into<String>>(mut self, step: S) -> Self {
+        let step = step.into();
+        self.add_dependency(step.clone());
+        self.input_from = Some(step);
+        self
+    }
+
+    /// Set `variable` to `step`'s stdout (without trailing newlines);
+    /// implies `after(step)`
+    pub fn env_from<S: Into<String>, V: Into<String>>(mut self, step: S, variable: V) -> Self {
+        let step = step.into();
+        self.add_dependency(step.clone());
+        self.env_from.push((step, variable.into()));
+        self
+    }
+
+    /// Declare the workspace directory `dir` as this step's output, which
+    /// later steps can take with `outputs_from`
+    pub fn outputs<D: Into<String>>(mut self, dir: D) -> Self {
+        let output = OutputArchive::new(ArchiveFormat::Tar).with_subtree(dir);
+        self.request.output_archive = Some(output);
+        self
+    }
+
+    /// Unpack `step`'s declared output into the workspace directory `dir`;
+    /// implies `after(step)`
+    pub fn outputs_from<S: Into<String>, D: Into<String>>(mut self, step: S, dir: D) -> Self {
+        let step = step.into();
+        self.add_dependency(step.clone());
+        self.outputs_from.push((step, dir.into()));
+        self
+    }
+
+    fn add_dependency(&mut self, step: String) {
+        if !self.depends_on.contains(&step) {
+            self.depends_on.push(step);
+        }
+    }
+
+    /// Request with dependency outputs wired in
+    ///
+    /// # Returns
+    /// The request, or an error if a dependency's declared output is
+    /// missing or cannot be unpacked
+    fn resolve(&self, outcomes: &HashMap<String, StepOutcome>) -> CyloResult<ExecutionRequest> {
+        let completed = |step: &str| match outcomes.get(step) {
+            Some(StepOutcome::Completed(result)) => Some(result),
+            _ => None,
+        };
+        let stdout = |step: &str| completed(step).map_or("", |result| result.stdout.as_str());
+
+        let mut request = self.request.clone();
+        if let Some(step) = &self.input_from {
+            request.input = Some(stdout(step).to_string());
+        }
+        for (step, variable) in &self.env_from {
+            let value = stdout(step).trim_end_matches(['\r', '\n']);
+            request.env_vars.insert(variable.clone(), value.to_string());
+        }
+        if !self.outputs_from.is_empty() {
+            let mut outputs = Vec::new();
+            for (step, dir) in &self.outputs_from {
+                let output = completed(step).and_then(declared_output).ok_or_else(|| {
+                    CyloError::validation(format!(
+                        "Pipeline step '{}' needs the output of '{}', which returned none",
+                        self.name, step
+                    ))
+                })??;
+                outputs.push((dir.as_str(), output));
+            }
+            request.workspace_archive = Some(merge_outputs(&request, &outputs)?);
+        }
+        Ok(request)
+    }
+}
+
+/// The workspace archive a completed step returned as its output, read
+/// back from the blob store if it was stored there
+fn declared_output(result: &ExecutionResult) -> Option<CyloResult<WorkspaceArchive>> {
+    if let Some(blob) = result.blobs.get("workspace_archive") {
+        let data = fs::read(&blob.path).map_err(|e| {
+            CyloError::internal(format!("Failed to read stored output {}: {e}", blob.digest))
+        });
+        return Some(data.map(WorkspaceArchive::from_bytes));
+    }
+    result.workspace_archive.clone().map(Ok)
+}
+
+/// Build one workspace archive from a step's own and its dependencies'
+/// outputs, each unpacked below its directory
+///
+/// The archives are unpacked into a private scratch directory with the
+/// step's archive limits, so they are checked as the backend would.
+fn merge_outputs(
+    request: &ExecutionRequest,
+    outputs: &[(&str, WorkspaceArchive)],
+) -> CyloResult<WorkspaceArchive> {
+    let scratch = std::env::temp_dir().join(format!("cylo_pipeline_{}", uuid::Uuid::new_v4()));
+    let merged = merge_into(&scratch, request, outputs);
+    let _ = fs::remove_dir_all(&scratch);
+    merged
+}
+
+fn merge_into(
+    scratch: &Path,
+    request: &ExecutionRequest,
+    outputs: &[(&str, WorkspaceArchive)],
+) -> CyloResult<WorkspaceArchive> {
+    let limits = &request.archive_limits;
+    create_private_dir(scratch)
+        .map_err(|e| CyloError::internal(format!("Failed to create pipeline scratch: {e}")))?;
+    if let Some(own) = &request.workspace_archive {
+        archive::unpack(own, scratch, limits).map_err(CyloError::validation)?;
+    }
+    for (dir, output) in outputs {
+        let target = scratch.join(dir);
+        fs::create_dir_all(&target)
+            .map_err(|e| CyloError::validation(format!("Invalid output directory '{dir}': {e}")))?;
+        archive::unpack(output, &target, limits).map_err(CyloError::validation)?;
+    }
+    archive::pack(scratch, &OutputArchive::new(ArchiveFormat::Tar), limits)
+        .map_err(CyloError::validation)
+}
+
+/// A DAG of steps submitted as one unit
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+    steps: Vec<PipelineStep>,
+}
+
+impl Pipeline {
+    /// Create an empty pipeline
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a step
+    pub fn step(mut self, step: PipelineStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Steps in the order they were added
+    pub fn steps(&self) -> &[PipelineStep] {
+        &self.steps
+    }
+
+    /// Check step names, dependencies, and requests before anything runs
+    ///
+    /// # Returns
+    /// Ok(()) for a well-formed DAG; a validation error naming the first
+    /// duplicate, unknown dependency, or cycle otherwise
+    pub fn validate(&self) -> CyloResult<()> {
+        if self.steps.is_empty() {
+            return Err(CyloError::validation("Pipeline has no steps"));
+        }
+
+        let mut names = HashSet::new();
+        for step in &self.steps {
+            if step.name.is_empty() {
+                return Err(CyloError::validation("Pipeline step name cannot be empty"));
+            }
+            if !names.insert(step.name.as_str()) {
+                return Err(CyloError::validation(format!(
+                    "Duplicate pipeline step '{}'",
+                    step.name
+                )));
+            }
+        }
+        for step in &self.steps {
+            if let Some(unknown) = step.depends_on.iter().find(|d| !names.contains(d.as_str())) {
+                return Err(CyloError::validation(format!(
+                    "Pipeline step '{}' depends on unknown step '{}'",
+                    step.name, unknown
+                )));
+            }
+            for (dependency, dir) in &step.outputs_from {
+                let declared = self.steps.iter().any(|other| {
+                    other.name == *dependency && other.request.output_archive.is_some()
+                });
+                if !declared {
+                    return Err(CyloError::validation(format!(
+                        "Pipeline step '{}' takes the output of '{}', which declares none",
+                        step.name, dependency
+                    )));
+                }
+                let relative = Path::new(dir)
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+                if !relative {
+                    return Err(CyloError::validation(format!(
+                        "Pipeline step '{}' unpacks outputs to '{}', outside its workspace",
+                        step.name, dir
+                    )));
+                }
+            }
+            step.request.validate()?;
+        }
+
+        // Kahn's algorithm: anything left unvisited sits on a cycle
+        let mut pending: HashMap<&str, usize> = self
+            .steps
+            .iter()
+            .map(|step| (step.name.as_str(), step.depends_on.len()))
+            .collect();
+        let mut ready: VecDeque<&str> = pending
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(name, _)| *name)
+            .collect();
+        let mut visited = 0;
+        while let Some(done) = ready.pop_front() {
+            visited += 1;
+            for step in &self.steps {
+                if step.depends_on.iter().any(|d| d == done)
+                    && let Some(count) = pending.get_mut(step.name.as_str())
+                {
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push_back(&step.name);
+                    }
+                }
+            }
+        }
+        if visited < self.steps.len() {
+            let mut cyclic: Vec<&str> = pending
+                .into_iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(name, _)| name)
+                .collect();
+            cyclic.sort_unstable();
+            return Err(CyloError::validation(format!(
+                "Pipeline has a dependency cycle through: {}",
+                cyclic.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// How one step of a pipeline ended
+#[derive(Debug)]
+pub enum StepOutcome {
+    /// The step ran; it may still have exited non-zero
+    Completed(ExecutionResult),
+    /// The step could not be routed or executed
+    Failed(CyloError),
+    /// The step never ran because a dependency did not succeed
+    Skipped {
+        /// Dependency that failed or was itself skipped
+        dependency: String,
+    },
+}
+
+impl StepOutcome {
+    /// Whether the step ran and succeeded
+    pub fn is_success(&self) -> bool {
+        matches!(self, StepOutcome::Completed(result) if result.is_success())
+    }
+}
+
+/// Outcome of every step of a pipeline
+#[derive(Debug, Default)]
+pub struct PipelineResult {
+    /// Outcome per step name
+    pub steps: HashMap<String, StepOutcome>,
+    /// Step names in the order their outcomes were decided
+    pub order: Vec<String>,
+}
+
+impl PipelineResult {
+    /// Whether every step succeeded
+    pub fn is_success(&self) -> bool {
+        self.steps.values().all(StepOutcome::is_success)
+    }
+
+    /// Outcome of a step
+    pub fn get(&self, step: &str) -> Option<&StepOutcome> {
+        self.steps.get(step)
+    }
+
+    fn record(&mut self, step: String, outcome: StepOutcome) {
+        self.order.push(step.clone());
+        self.steps.insert(step, outcome);
+    }
+}
+
+/// Run a validated pipeline, launching each step through `launch`
+///
+/// # Arguments
+/// * `pipeline` - Pipeline that passed `validate`
+/// * `launch` - Starts one step's request, e.g. by routing it to a backend
+pub(crate) async fn run<F, Fut>(pipeline: Pipeline, mut launch: F) -> PipelineResult
+where
+    F: FnMut(ExecutionRequest) -> Fut,
+    Fut: Future<Output = CyloResult<ExecutionResult>> + Send + 'static,
+{
+    let mut waiting: Vec<PipelineStep> = pipeline.steps;
+    let mut result = PipelineResult::default();
+    let mut running = JoinSet::new();
+    // Steps started but not yet recorded
+    let mut in_flight: HashSet<String> = HashSet::new();
+
+    loop {
+        // Settle every waiting step whose dependencies are decided; skipping
+        // one step can decide others, so repeat until nothing changes
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let mut index = 0;
+            while index < waiting.len() {
+                let step = &waiting[index];
+                let blocked_by = step
+                    .depends_on
+                    .iter()
+                    .find(|d| result.get(d).is_some_and(|outcome| !outcome.is_success()));
+
+                if let Some(dependency) = blocked_by {
+                    let dependency = dependency.clone();
+                    let step = waiting.swap_remove(index);
+                    result.record(step.name, StepOutcome::Skipped { dependency });
+                    changed = true;
+                } else if step.depends_on.iter().all(|d| result.get(d).is_some()) {
+                    let step = waiting.swap_remove(index);
+                    let request = match step.resolve(&result.steps) {
+                        Ok(request) => request,
+                        Err(e) => {
+                            result.record(step.name, StepOutcome::Failed(e));
+                            changed = true;
+                            continue;
+                        }
+                    };
+                    let execution = launch(request);
+                    in_flight.insert(step.name.clone());
+                    running.spawn(async move {
+                        // Spawned separately so a panicking step surfaces as
+                        // its own failure instead of losing the step name
+                        let outcome = tokio::spawn(execution)
+                            .await
+                            .map_err(CyloError::from)
+                            .and_then(|outcome| outcome);
+                        (step.name, outcome)
+                    });
+                    changed = true;
+                } else {
+                    index += 1;
+                }
+            }
+        }
+
+        match running.join_next().await {
+            Some(Ok((name, outcome))) => {
+                in_flight.remove(&name);
+                let outcome = match outcome {
+                    Ok(execution) => StepOutcome::Completed(execution),
+                    Err(e) => StepOutcome::Failed(e),
+                };
+                result.record(name, outcome);
+            }
+            // The wrapper task itself died, so which step it ran is only
+            // known once every other step has reported
+            Some(Err(_)) => {}
+            None if in_flight.is_empty() => break,
+            None => {
+                let mut lost: Vec<String> = in_flight.drain().collect();
+                lost.sort_unstable();
+                for name in lost {
+                    let error = CyloError::internal(format!(
+                        "Pipeline step '{name}' task ended without reporting"
+                    ));
+                    result.record(name, StepOutcome::Failed(error));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Echo stdin and the DEP variable back as stdout; fail on "fail"
+    async fn fake(request: ExecutionRequest) -> CyloResult<ExecutionResult> {
+        if request.code == "fail" {
+            return Ok(ExecutionResult::failure(1, "failed"));
+        }
+        let env = request.env_vars.get("DEP").cloned().unwrap_or_default();
+        let input = request.input.unwrap_or_default();
+        Ok(ExecutionResult::success(format!("{}{}{}\n", request.code, input, env)))
+    }
+
+    fn step(name: &str, code: &str) -> PipelineStep {
+        PipelineStep::new(name, ExecutionRequest::new(code, "bash"))
+    }
+
+    #[tokio::test]
+    async fn outputs_flow_through_diamond() {
+        let pipeline = Pipeline::new()
+            .step(step("compile", "c"))
+            .step(step("test", "t").input_from("compile"))
+            .step(step("bench", "b").env_from("compile", "DEP"))
+            .step(step("report", "r").after("test").env_from("bench", "DEP"));
+        pipeline.validate().unwrap();
+
+        let result = run(pipeline, fake).await;
+
+        assert!(result.is_success());
+        assert_eq!(result.order.first().map(String::as_str), Some("compile"));
+        assert_eq!(result.order.last().map(String::as_str), Some("report"));
+        match result.get("report") {
+            Some(StepOutcome::Completed(report)) => assert_eq!(report.stdout, "rbc\n"),
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn failure_skips_downstream_steps_only() {
+        let pipeline = Pipeline::new()
+            .step(step("compile", "fail"))
+            .step(step("test", "t").after("compile"))
+            .step(step("report", "r").after("test"))
+            .step(step("lint", "l"));
+
+        let result = run(pipeline, fake).await;
+
+        assert!(!result.is_success());
+        assert!(result.get("lint").unwrap().is_success());
+        for skipped in ["test", "report"] {
+            assert!(matches!(result.get(skipped), Some(StepOutcome::Skipped { .. })));
+        }
+    }
+
+    /// Write `out/<code>.txt` and print the dependency outputs it was given
+    async fn build(request: ExecutionRequest) -> CyloResult<ExecutionResult> {
+        let dir = tempfile::tempdir().unwrap();
+        if let Some(archive) = &request.workspace_archive {
+            archive::unpack(archive, dir.path(), &request.archive_limits).unwrap();
+        }
+        let given: String = ["from_a/a.txt", "from_b/b.txt"]
+            .iter()
+            .filter_map(|path| fs::read_to_string(dir.path().join(path)).ok())
+            .collect();
+        fs::create_dir_all(dir.path().join("out")).unwrap();
+        fs::write(dir.path().join(format!("out/{}.txt", request.code)), &request.code).unwrap();
+
+        let mut result = ExecutionResult::success(given);
+        archive::attach_output(&request, dir.path(), &mut result);
+        Ok(result)
+    }
+
+    #[tokio::test]
+    async fn declared_outputs_reach_dependent_workspaces() {
+        let pipeline = Pipeline::new()
+            .step(step("a", "a").outputs("out"))
+            .step(step("b", "b").outputs("out"))
+            .step(step("c", "c").outputs_from("a", "from_a").outputs_from("b", "from_b"));
+        pipeline.validate().unwrap();
+
+        let result = run(pipeline, build).await;
+
+        assert!(result.is_success());
+        match result.get("c") {
+            Some(StepOutcome::Completed(merged)) => assert_eq!(merged.stdout, "ab"),
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn panicking_step_fails_and_skips_its_dependents() {
+        async fn crashing(request: ExecutionRequest) -> CyloResult<ExecutionResult> {
+            if request.code == "panic" {
+                panic!("backend crashed");
+            }
+            fake(request).await
+        }
+        let pipeline = Pipeline::new()
+            .step(step("compile", "panic"))
+            .step(step("test", "t").after("compile"))
+            .step(step("lint", "l"));
+
+        let result = run(pipeline, crashing).await;
+
+        assert!(matches!(result.get("compile"), Some(StepOutcome::Failed(_))));
+        assert!(matches!(result.get("test"), Some(StepOutcome::Skipped { .. })));
+        assert!(result.get("lint").unwrap().is_success());
+        assert_eq!(result.order.len(), 3);
+    }
+
+    #[test]
+    fn invalid_graphs_are_rejected() {
+        let cycle = Pipeline::new()
+            .step(step("a", "a").after("b"))
+            .step(step("b", "b").after("a"))
+            .step(step("c", "c"));
+        let err = cycle.validate().unwrap_err().to_string();
+        assert!(err.contains("cycle through: a, b"), "{}", err);
+
+        let unknown = Pipeline::new().step(step("a", "a").after("missing"));
+        assert!(unknown.validate().is_err());
+
+        let duplicate = Pipeline::new().step(step("a", "a")).step(step("a", "b"));
+        assert!(duplicate.validate().is_err());
+
+        let undeclared = Pipeline::new()
+            .step(step("a", "a"))
+            .step(step("b", "b").outputs_from("a", "deps"));
+        assert!(undeclared.validate().is_err());
+        let escaping = Pipeline::new()
+            .step(step("a", "a").outputs("out"))
+            .step(step("b", "b").outputs_from("a", "../deps"));
+        assert!(escaping.validate().is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/plan.rs
+//! ----------------------------------------------------------------------------
+//! Dry-run execution plans.
+//!
+//! `CyloExecutor::plan` takes a request through the same admission and
+//! routing steps an execution goes through, and reports each decision
+//! instead of acting on it: no code runs, no sandbox or dependency install
+//! is started, and volumes and crash histories are left as they were. UIs
+//! preview where a request would run, operators see which policy rejects
+//! it, and CI checks that request templates still route.
+//! ============================================================================
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::ResourceLimits;
+use crate::execution_env::{Cylo, CyloInstance, CyloResult};
+
+/// Outcome of one admission policy for a planned request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyVerdict {
+    /// Policy checked, e.g. "middleware", "crash_loop" or "host_guard"
+    pub policy: String,
+    /// Whether the request would pass it
+    pub passed: bool,
+    /// Why the request would be refused
+    pub detail: Option<String>,
+}
+
+impl PolicyVerdict {
+    /// Verdict of a policy from the result of its check
+    pub(crate) fn from_result<T>(policy: &str, result: &CyloResult<T>) -> Self {
+        Self {
+            policy: policy.to_string(),
+            passed: result.is_ok(),
+            detail: result.as_ref().err().map(ToString::to_string),
+        }
+    }
+}
+
+/// What executing a request would do, as decided without executing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionPlan {
+    /// Backend the request would run on; None when routing finds none
+    pub backend: Option<String>,
+    /// Remote worker the request would be forwarded to
+    pub worker: Option<String>,
+    /// Instance the request would run on; a fresh name when a new
+    /// instance would be started
+    pub instance: Option<CyloInstance>,
+    /// Container or VM image the instance would run
+    pub image: Option<String>,
+    /// Backend a hedged execution would race against the chosen one
+    pub hedge_backend: Option<String>,
+    /// Wall-clock timeout after defaults and language profiles apply
+    pub timeout: Duration,
+    /// Resource limits after defaults and language profiles apply
+    pub limits: ResourceLimits,
+    /// Admission policies in the order an execution checks them
+    pub verdicts: Vec<PolicyVerdict>,
+}
+
+impl ExecutionPlan {
+    /// Whether executing the request would get as far as a backend
+    pub fn is_admitted(&self) -> bool {
+        self.backend.is_some() && self.verdicts.iter().all(|verdict| verdict.passed)
+    }
+
+    /// Policies that would refuse the request
+    pub fn rejections(&self) -> Vec<&PolicyVerdict> {
+        self.verdicts.iter().filter(|verdict| !verdict.passed).collect()
+    }
+}
+
+/// Image an environment runs, for the backends that run one
+pub(crate) fn image_of(env: &Cylo) -> Option<String> {
+    match env {
+        Cylo::Apple(image)
+        | Cylo::Docker(image)
+        | Cylo::FireCracker(image)
+        | Cylo::K8sJob(image) => Some(image.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_env::CyloError;
+
+    #[test]
+    fn plan_is_admitted_only_when_routed_and_every_policy_passes() {
+        let mut plan = ExecutionPlan {
+            backend: Some("Docker".to_string()),
+            worker: None,
+            instance: None,
+            image: image_of(&Cylo::Docker("python:3.12-alpine".to_string())),
+            hedge_backend: None,
+            timeout: Duration::from_secs(30),
+            limits: ResourceLimits::default(),
+            verdicts: vec![PolicyVerdict::from_result("validation", &Ok(()))],
+        };
+        assert!(plan.is_admitted());
+        assert_eq!(plan.image.as_deref(), Some("python:3.12-alpine"));
+
+        let refused: CyloResult<()> = Err(CyloError::validation("code is empty"));
+        plan.verdicts.push(PolicyVerdict::from_result("middleware", &refused));
+        assert!(!plan.is_admitted());
+        let rejections = plan.rejections();
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections[0].policy, "middleware");
+        assert!(rejections[0].detail.as_deref().unwrap().contains("code is empty"));
+
+        plan.verdicts.truncate(1);
+        plan.backend = None;
+        assert!(!plan.is_admitted());
+        assert_eq!(image_of(&Cylo::LandLock("/tmp/cylo_landlock".to_string())), None);
+    }
+}
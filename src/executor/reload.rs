@@ -0,0 +1,546 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/reload.rs
+//! ----------------------------------------------------------------------------
+//! Runtime-swappable executor configuration.
+//!
+//! `ExecutorConfig` bundles everything routing reads per execution. The
+//! executor keeps it behind a lock and snapshots it when an execution starts,
+//! so swapping it never affects executions already in flight. `ConfigWatcher`
+//! polls a JSON config file and applies it whenever the file changes.
+//! ============================================================================
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::templates::RequestTemplate;
+use super::types::{canonical_language, BackendPreferences, OptimizationConfig, RoutingStrategy};
+use crate::backends::{ExecutionRequest, RegistryCredentials, ResourceLimits};
+use crate::execution_env::{CyloError, CyloResult};
+use crate::logging::targets;
+
+/// How often a watched config file is checked for changes
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Executor settings that can be replaced while the executor is running
+///
+/// Every field has a default, so a config file only needs the settings it
+/// changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutorConfig {
+    /// Execution routing strategy
+    pub routing_strategy: RoutingStrategy,
+    /// Backend selection preferences
+    pub preferences: BackendPreferences,
+    /// Pool sizes, host guard, and other optimization settings
+    pub optimization: OptimizationConfig,
+    /// Limits applied to requests that do not choose their own
+    pub default_limits: Option<ResourceLimits>,
+    /// Per-language timeout and limit defaults, keyed by language name;
+    /// they take precedence over `default_limits`
+    pub language_profiles: HashMap<String, LanguageProfile>,
+    /// Credentials for image pulls from private registries
+    pub registry_credentials: RegistryCredentials,
+    /// Named request templates for `CyloExecutor::from_template`
+    pub templates: HashMap<String, RequestTemplate>,
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self {
+            routing_strategy: RoutingStrategy::default(),
+            preferences: BackendPreferences::default(),
+            optimization: OptimizationConfig::default(),
+            default_limits: None,
+            language_profiles: LanguageProfile::builtin(),
+            registry_credentials: RegistryCredentials::default(),
+            templates: HashMap::new(),
+        }
+    }
+}
+
+/// Defaults for requests in one language
+///
+/// Unset fields fall back to the global defaults. A profile only fills in
+/// what the request left at its default: a request that sets its own
+/// timeout or limits keeps them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguageProfile {
+    /// Wall-clock timeout
+    pub timeout: Option<Duration>,
+    /// Memory limit in bytes
+    pub max_memory: Option<u64>,
+    /// CPU time limit in seconds
+    pub max_cpu_time: Option<u64>,
+    /// Process/thread limit
+    pub max_processes: Option<u32>,
+}
+
+impl LanguageProfile {
+    /// Profile with a timeout and memory limit; CPU time follows the timeout
+    pub fn new(timeout: Duration, max_memory: u64) -> Self {
+        Self {
+            timeout: Some(timeout),
+            max_memory: Some(max_memory),
+            max_cpu_time: Some(timeout.as_secs().max(1)),
+            max_processes: None,
+        }
+    }
+
+    /// Set the process/thread limit
+    pub fn with_max_processes(mut self, limit: u32) -> Self {
+        self.max_processes = Some(limit);
+        self
+    }
+
+    /// Built-in profiles: compiled languages get room to build, scripting
+    /// languages get short, small defaults
+    pub fn builtin() -> HashMap<String, LanguageProfile> {
+        const MIB: u64 = 1024 * 1024;
+        let secs = Duration::from_secs;
+
+        HashMap::from([
+            // rustc and the linker run as separate processes
+            (
+                "rust".to_string(),
+                Self::new(secs(90), 2048 * MIB).with_max_processes(32),
+            ),
+            ("go".to_string(), Self::new(secs(60), 1024 * MIB).with_max_processes(32)),
+            ("python".to_string(), Self::new(secs(10), 256 * MIB)),
+            ("javascript".to_string(), Self::new(secs(10), 256 * MIB)),
+            ("bash".to_string(), Self::new(secs(10), 128 * MIB)),
+            ("lua".to_string(), Self::new(secs(10), 128 * MIB)),
+            // asc runs on node, then the module runs like any other
+            ("assemblyscript".to_string(), Self::new(secs(60), 1024 * MIB)),
+            // wasmtime compiles the module to native code before running it
+            ("wasm".to_string(), Self::new(secs(30), 512 * MIB)),
+            // Package installs compile C and Fortran sources
+            ("r".to_string(), Self::new(secs(60), 1024 * MIB).with_max_processes(32)),
+            ("zig".to_string(), Self::new(secs(90), 2048 * MIB).with_max_processes(32)),
+            // The SDK's build server and restore run alongside the build
+            (
+                "csharp".to_string(),
+                Self::new(secs(120), 2048 * MIB).with_max_processes(64),
+            ),
+        ])
+    }
+
+    fn apply_limits(&self, limits: &mut ResourceLimits) {
+        limits.max_memory = self.max_memory.or(limits.max_memory);
+        limits.max_cpu_time = self.max_cpu_time.or(limits.max_cpu_time);
+        limits.max_processes = self.max_processes.or(limits.max_processes);
+    }
+}
+
+impl ExecutorConfig {
+    /// Set the defaults for a language (aliases such as "py" are accepted)
+    pub fn with_language_profile<L: AsRef<str>>(
+        mut self,
+        language: L,
+        profile: LanguageProfile,
+    ) -> Self {
+        self.language_profiles
+            .insert(canonical_language(language.as_ref()), profile);
+        self
+    }
+
+    /// Register a named request template
+    pub fn with_template<N: Into<String>>(mut self, name: N, template: RequestTemplate) -> Self {
+        self.templates.insert(name.into(), template);
+        self
+    }
+
+    /// Profile for a language, matching keys by canonical language name
+    pub fn language_profile(&self, language: &str) -> Option<&LanguageProfile> {
+        profile_for(&self.language_profiles, language)
+    }
+
+    /// Parse a configuration from JSON
+    pub fn from_json(json: &str) -> CyloResult<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| CyloError::validation(format!("Invalid executor config: {}", e)))
+    }
+
+    /// Load and validate a configuration file
+    ///
+    /// # Arguments
+    /// * `path` - JSON file to read
+    pub fn load(path: &Path) -> CyloResult<Self> {
+        let json = fs::read_to_string(path).map_err(|e| {
+            CyloError::internal(format!(
+                "Failed to read executor config {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let config = Self::from_json(&json)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reject settings that would break routing or admission
+    pub fn validate(&self) -> CyloResult<()> {
+        let weights = self.preferences.weight_multipliers.iter().chain(
+            self.preferences
+                .language_overrides
+                .values()
+                .flat_map(|overrides| overrides.weight_multipliers.iter()),
+        );
+        for (backend, weight) in weights {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(CyloError::validation(format!(
+                    "Weight for {} must be a non-negative number, got {}",
+                    backend, weight
+                )));
+            }
+        }
+
+        if let Some((backend, _)) = self
+            .preferences
+            .max_concurrent
+            .iter()
+            .find(|(_, limit)| **limit == 0)
+        {
+            return Err(CyloError::validation(format!(
+                "max_concurrent for {} must be at least 1",
+                backend
+            )));
+        }
+
+        for (language, profile) in &self.language_profiles {
+            if profile.timeout.is_some_and(|timeout| timeout.is_zero()) {
+                return Err(CyloError::validation(format!(
+                    "Timeout for {} must be non-zero",
+                    language
+                )));
+            }
+            if profile
+                .max_memory
+                .is_some_and(|memory| memory < ExecutionRequest::MIN_MEMORY_BYTES)
+            {
+                return Err(CyloError::validation(format!(
+                    "Memory limit for {} is below {} bytes",
+                    language,
+                    ExecutionRequest::MIN_MEMORY_BYTES
+                )));
+            }
+        }
+
+        for (name, template) in &self.templates {
+            template.validate(name)?;
+        }
+
+        if self.optimization.instance_pool_size == 0 {
+            return Err(CyloError::validation("instance_pool_size must be at least 1"));
+        }
+
+        let crash_loop = &self.optimization.crash_loop;
+        if crash_loop.enabled && (crash_loop.threshold == 0 || crash_loop.window.is_zero()) {
+            return Err(CyloError::validation(
+                "crash_loop threshold and window must be non-zero when enabled",
+            ));
+        }
+
+        let dependencies = &self.optimization.dependencies;
+        if dependencies.enabled
+            && (dependencies.timeout.is_zero() || !dependencies.cache_dir.is_absolute())
+        {
+            return Err(CyloError::validation(
+                "dependencies need a non-zero timeout and an absolute cache_dir when enabled",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Look up a language's profile, accepting aliases on either side
+pub(crate) fn profile_for<'a>(
+    profiles: &'a HashMap<String, LanguageProfile>,
+    language: &str,
+) -> Option<&'a LanguageProfile> {
+    let language = canonical_language(language);
+    profiles.get(&language).or_else(|| {
+        profiles
+            .iter()
+            .find(|(key, _)| canonical_language(key) == language)
+            .map(|(_, profile)| profile)
+    })
+}
+
+/// Fill in configured defaults on a request that did not choose its own
+///
+/// A request that did not choose its limits gets `default_limits` with its
+/// language profile's limits layered on top; one that did not choose its
+/// timeout gets the profile's timeout.
+pub(crate) fn apply_defaults(
+    default_limits: Option<&ResourceLimits>,
+    profile: Option<&LanguageProfile>,
+    request: &mut ExecutionRequest,
+) {
+    if !request.limits_chosen {
+        if let Some(limits) = default_limits {
+            request.limits = limits.clone();
+        }
+        if let Some(profile) = profile {
+            profile.apply_limits(&mut request.limits);
+        }
+    }
+
+    if !request.timeout_chosen
+        && let Some(timeout) = profile.and_then(|profile| profile.timeout)
+    {
+        request.timeout = timeout;
+    }
+}
+
+/// Background watcher that reloads an executor's config file on change
+///
+/// Invalid files are logged and ignored, leaving the current configuration
+/// in place. Watching stops when the watcher is dropped.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ConfigWatcher {
+    /// Start polling `path`, swapping `target` whenever the file changes
+    pub(crate) fn spawn(path: PathBuf, target: Arc<SharedConfig>) -> CyloResult<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::Builder::new()
+            .name("cylo-config-watcher".to_string())
+            .spawn({
+                let path = path.clone();
+                let stop = Arc::clone(&stop);
+                move || watch(&path, &target, &stop)
+            })
+            .map_err(|e| CyloError::internal(format!("Failed to start config watcher: {}", e)))?;
+
+        Ok(Self {
+            path,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// File being watched
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Poll loop run on the watcher thread
+fn watch(path: &Path, target: &SharedConfig, stop: &AtomicBool) {
+    let mut last_seen = fingerprint(path);
+
+    while !stop.load(Ordering::SeqCst) {
+        // Parked rather than slept so dropping the watcher wakes it at once
+        std::thread::park_timeout(CONFIG_POLL_INTERVAL);
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let current = fingerprint(path);
+        if current.is_none() || current == last_seen {
+            continue;
+        }
+        last_seen = current;
+
+        match ExecutorConfig::load(path) {
+            Ok(config) => {
+                replace(target, config);
+                info!(
+                    target: targets::EXECUTOR,
+                    "Reloaded executor config from {}",
+                    path.display()
+                );
+            }
+            Err(e) => warn!(
+                target: targets::EXECUTOR,
+                "Ignoring changed executor config {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+}
+
+/// Modification time and size, used to detect changes to a file
+fn fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Current executor configuration; executions hold on to the snapshot
+/// they started with rather than copying it
+pub(crate) type SharedConfig = RwLock<Arc<ExecutorConfig>>;
+
+/// Swap in a new configuration
+///
+/// The config is only ever replaced wholesale, so a lock poisoned by a
+/// panicking reader cannot hold a half-written value and is recovered.
+pub(crate) fn replace(target: &SharedConfig, config: ExecutorConfig) {
+    crate::hardening::set_helper_hardening(config.optimization.helper_hardening);
+    let mut current = target.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    *current = Arc::new(config);
+}
+
+/// Change part of the configuration, validating the result as a reloaded
+/// file is
+///
+/// The change is made to the current config under the write lock, so
+/// concurrent updates of different parts cannot undo each other.
+///
+/// # Returns
+/// What `change` returned, or a validation error leaving the current
+/// configuration in place
+pub(crate) fn update<R>(
+    target: &SharedConfig,
+    change: impl FnOnce(&mut ExecutorConfig) -> R,
+) -> CyloResult<R> {
+    let mut current = target.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut updated = ExecutorConfig::clone(&current);
+    let changed = change(&mut updated);
+    updated.validate()?;
+    crate::hardening::set_helper_hardening(updated.optimization.helper_hardening);
+    *current = Arc::new(updated);
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::RegistryAuth;
+
+    #[test]
+    fn partial_json_keeps_defaults() {
+        let json = r#"{
+            "routing_strategy": "Security",
+            "preferences": { "excluded_backends": ["Apple"] }
+        }"#;
+        let config = ExecutorConfig::from_json(json).unwrap();
+
+        assert_eq!(config.routing_strategy, RoutingStrategy::Security);
+        assert_eq!(config.preferences.excluded_backends, vec!["Apple".to_string()]);
+        assert_eq!(
+            config.preferences.max_concurrent.get("FireCracker"),
+            Some(&2)
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn registry_credentials_load_from_json() {
+        let json = r#"{
+            "registry_credentials": {
+                "registries": {
+                    "ghcr.io": { "type": "token", "token": "ghp_x" },
+                    "registry.corp.example": { "type": "helper", "helper": "ecr-login" }
+                },
+                "default_helper": "osxkeychain"
+            }
+        }"#;
+        let config = ExecutorConfig::from_json(json).unwrap();
+
+        let credentials = &config.registry_credentials;
+        assert_eq!(
+            credentials.registries.get("ghcr.io"),
+            Some(&RegistryAuth::Token { token: "ghp_x".to_string() })
+        );
+        assert_eq!(credentials.default_helper.as_deref(), Some("osxkeychain"));
+        assert!(ExecutorConfig::default().registry_credentials.is_empty());
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        let mut config = ExecutorConfig::default();
+        config
+            .preferences
+            .weight_multipliers
+            .insert("LandLock".to_string(), f32::NAN);
+        assert!(config.validate().is_err());
+
+        let mut config = ExecutorConfig::default();
+        config.optimization.instance_pool_size = 0;
+        assert!(config.validate().is_err());
+
+        let shared: SharedConfig = RwLock::new(Arc::new(ExecutorConfig::default()));
+        let rejected = update(&shared, |config| config.optimization.instance_pool_size = 0);
+        assert!(rejected.is_err());
+        update(&shared, |config| config.optimization.instance_pool_size = 3).unwrap();
+        let current = shared.read().unwrap();
+        assert_eq!(current.optimization.instance_pool_size, 3);
+    }
+
+    #[test]
+    fn default_limits_only_replace_untouched_limits() {
+        let config = ExecutorConfig {
+            default_limits: Some(ResourceLimits {
+                max_memory: Some(64 * 1024 * 1024),
+                ..ResourceLimits::default()
+            }),
+            ..ExecutorConfig::default()
+        };
+
+        let mut untouched = ExecutionRequest::new("x", "python");
+        apply_defaults(config.default_limits.as_ref(), None, &mut untouched);
+        assert_eq!(untouched.limits.max_memory, Some(64 * 1024 * 1024));
+
+        let custom = ResourceLimits {
+            max_memory: Some(1024 * 1024 * 1024),
+            ..ResourceLimits::default()
+        };
+        let mut chosen = ExecutionRequest::new("x", "python").with_limits(custom.clone());
+        apply_defaults(config.default_limits.as_ref(), None, &mut chosen);
+        assert_eq!(chosen.limits, custom);
+    }
+
+    #[test]
+    fn language_profiles_fill_in_untouched_settings() {
+        let config = ExecutorConfig::default();
+        let profile = |language: &str| config.language_profile(language);
+
+        let mut rust = ExecutionRequest::new("fn main() {}", "rs");
+        apply_defaults(None, profile(&rust.language), &mut rust);
+        assert_eq!(rust.timeout, Duration::from_secs(90));
+        assert_eq!(rust.limits.max_memory, Some(2048 * 1024 * 1024));
+        assert_eq!(rust.limits.max_file_size, ResourceLimits::default().max_file_size);
+
+        let mut python = ExecutionRequest::new("x", "python").with_timeout(Duration::from_secs(5));
+        apply_defaults(None, profile(&python.language), &mut python);
+        assert_eq!(python.timeout, Duration::from_secs(5));
+        assert_eq!(python.limits.max_memory, Some(256 * 1024 * 1024));
+
+        // Choosing the default values is still a choice
+        let mut pinned = ExecutionRequest::new("fn main() {}", "rust")
+            .with_timeout(ExecutionRequest::DEFAULT_TIMEOUT)
+            .with_limits(ResourceLimits::default());
+        apply_defaults(None, profile(&pinned.language), &mut pinned);
+        assert_eq!(pinned.timeout, ExecutionRequest::DEFAULT_TIMEOUT);
+        assert_eq!(pinned.limits, ResourceLimits::default());
+
+        let custom = config.with_language_profile("py", LanguageProfile::default());
+        assert_eq!(custom.language_profile("python"), Some(&LanguageProfile::default()));
+    }
+}
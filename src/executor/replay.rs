@@ -0,0 +1,214 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/replay.rs
+//! ----------------------------------------------------------------------------
+//! Replayable execution bundles for deterministic re-runs.
+//!
+//! A bundle captures the fully effective request together with the backend
+//! and environment it ran on, so a failure seen in production can be re-run
+//! locally against the same configuration.
+//! ============================================================================
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{ExecutionRequest, ExecutionResult, IMAGE_DIGEST_METADATA};
+use crate::execution_env::{Cylo, CyloError, CyloInstance, CyloResult};
+use crate::results::{self, DiffOptions, ResultDiff};
+
+/// Serialized record of a single execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayBundle {
+    /// Bundle format version
+    pub format_version: u32,
+
+    /// Version of cylo that recorded the bundle
+    pub cylo_version: String,
+
+    /// When the execution was recorded
+    pub recorded_at: DateTime<Utc>,
+
+    /// Backend that executed the request
+    pub backend: String,
+
+    /// Execution environment the request ran in
+    pub environment: Cylo,
+
+    /// Digest of the image or rootfs used, when the backend reported one
+    pub image_digest: Option<String>,
+
+    /// Fully effective request as handed to the backend
+    pub request: ExecutionRequest,
+
+    /// Original result, when execution produced one
+    pub result: Option<ExecutionResult>,
+
+    /// Original error, when execution failed before producing a result
+    pub error: Option<String>,
+}
+
+impl ReplayBundle {
+    /// Current bundle format version
+    pub const FORMAT_VERSION: u32 = 1;
+
+    /// Capture a bundle from a completed execution
+    ///
+    /// # Arguments
+    /// * `backend` - Name of the backend that ran the request
+    /// * `instance` - Instance the request ran on
+    /// * `request` - Effective execution request
+    /// * `result` - Outcome of the execution
+    pub fn capture(
+        backend: &str,
+        instance: &CyloInstance,
+        request: &ExecutionRequest,
+        result: &CyloResult<ExecutionResult>,
+    ) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result.clone()), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        Self {
+            format_version: Self::FORMAT_VERSION,
+            cylo_version: env!("CARGO_PKG_VERSION").to_string(),
+            recorded_at: Utc::now(),
+            backend: backend.to_string(),
+            environment: instance.env.clone(),
+            image_digest: result
+                .as_ref()
+                .and_then(|r| r.metadata.get(IMAGE_DIGEST_METADATA).cloned()),
+            request: request.clone(),
+            result,
+            error,
+        }
+    }
+
+    /// Check that this bundle can be replayed by the running version
+    pub fn validate(&self) -> CyloResult<()> {
+        if self.format_version > Self::FORMAT_VERSION {
+            return Err(CyloError::validation(format!(
+                "Replay bundle format {} is newer than supported format {}",
+                self.format_version,
+                Self::FORMAT_VERSION
+            )));
+        }
+        self.environment.validate()
+    }
+
+    /// Check whether a replayed result reproduces the recorded outcome
+    ///
+    /// Compares exit code and captured output byte-for-byte.
+    pub fn reproduces(&self, replayed: &ExecutionResult) -> bool {
+        match &self.result {
+            Some(original) => {
+                original.exit_code == replayed.exit_code
+                    && original.stdout == replayed.stdout
+                    && original.stderr == replayed.stderr
+            }
+            None => false,
+        }
+    }
+
+    /// Compare a replayed result with the recorded one
+    ///
+    /// Unlike `reproduces`, output is normalized as `options` says and the
+    /// changed lines and resource deltas are reported.
+    ///
+    /// # Returns
+    /// The difference, or None when the bundle recorded no result
+    pub fn diff(&self, replayed: &ExecutionResult, options: &DiffOptions) -> Option<ResultDiff> {
+        self.result
+            .as_ref()
+            .map(|original| results::diff_with(original, replayed, options))
+    }
+
+    /// Serialize the bundle to pretty-printed JSON
+    pub fn to_json(&self) -> CyloResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| CyloError::internal(format!("Failed to serialize replay bundle: {e}")))
+    }
+
+    /// Parse a bundle from JSON
+    pub fn from_json(json: &str) -> CyloResult<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| CyloError::validation(format!("Invalid replay bundle: {e}")))
+    }
+
+    /// Write the bundle to a file
+    pub fn save(&self, path: &Path) -> CyloResult<()> {
+        std::fs::write(path, self.to_json()?).map_err(|e| {
+            CyloError::internal(format!(
+                "Failed to write replay bundle {}: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Load a bundle from a file
+    pub fn load(path: &Path) -> CyloResult<Self> {
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            CyloError::internal(format!(
+                "Failed to read replay bundle {}: {e}",
+                path.display()
+            ))
+        })?;
+        Self::from_json(&json)
+    }
+}
+
+/// Execution outcome paired with its replay bundle
+#[derive(Debug, Clone)]
+pub struct RecordedExecution {
+    /// Outcome of the execution
+    pub result: CyloResult<ExecutionResult>,
+
+    /// Bundle that can re-run the execution
+    pub bundle: ReplayBundle,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::ImageReference;
+
+    fn sample_bundle() -> ReplayBundle {
+        let instance = Cylo::LandLock("/tmp/cylo_landlock".to_string()).instance("replay_test");
+        let request = ExecutionRequest::new("print('hi')", "python").with_env("SEED", "7");
+        let mut result = ExecutionResult::success("hi\n");
+        let image = ImageReference::parse("python:3.12-alpine").unwrap();
+        image.record(&mut result, "sha256:abc".to_string());
+        ReplayBundle::capture("LandLock", &instance, &request, &Ok(result))
+    }
+
+    #[test]
+    fn bundle_round_trips_through_json() {
+        let bundle = sample_bundle();
+        let parsed = ReplayBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+
+        assert_eq!(parsed.backend, "LandLock");
+        assert_eq!(parsed.image_digest.as_deref(), Some("sha256:abc"));
+        assert_eq!(parsed.request.env_vars.get("SEED"), Some(&"7".to_string()));
+        assert!(parsed.validate().is_ok());
+    }
+
+    #[test]
+    fn reproduces_compares_output() {
+        let bundle = sample_bundle();
+        assert!(bundle.reproduces(&ExecutionResult::success("hi\n")));
+        assert!(!bundle.reproduces(&ExecutionResult::success("bye\n")));
+
+        let changed = bundle
+            .diff(&ExecutionResult::success("bye\n"), &DiffOptions::default())
+            .unwrap();
+        assert_eq!(changed.stdout.len(), 2);
+    }
+
+    #[test]
+    fn newer_format_is_rejected() {
+        let mut bundle = sample_bundle();
+        bundle.format_version = ReplayBundle::FORMAT_VERSION + 1;
+        assert!(bundle.validate().is_err());
+    }
+}
@@ -5,72 +5,121 @@
 //! ============================================================================
 
 use std::sync::{Arc, RwLock};
+
+use tracing::{debug, trace};
+
 use crate::execution_env::{Cylo, CyloError, CyloResult};
-use crate::backends::ExecutionRequest;
+use crate::ids;
+use crate::backends::{
+    BackendCapabilities, ExecutionRequest, IsolationLevel, arch, language, registry,
+};
+use crate::instance_manager::global_instance_manager;
+use crate::logging::targets;
 use super::types::{RoutingStrategy, BackendPreferences, PlatformCache};
 
 /// Select optimal backend based on strategy and requirements
+///
+/// A backend named by the request overrides the strategy; an isolation
+/// requirement and the allow/deny preferences narrow the candidates the
+/// strategy chooses from. Each rejection and the final choice are logged at
+/// trace/debug level.
 pub fn select_optimal_backend(
     strategy: &RoutingStrategy,
     preferences: &BackendPreferences,
     platform_cache: &Arc<RwLock<PlatformCache>>,
-    _request: &ExecutionRequest,
+    request: &ExecutionRequest,
 ) -> CyloResult<String> {
-    let cache = platform_cache
-        .read()
-        .map_err(|e| CyloError::Other(format!("Cache lock poisoned: {}", e)))?;
-    let available = &cache.available_backends;
+    let available = {
+        let cache = platform_cache
+            .read()
+            .map_err(|e| CyloError::internal(format!("Cache lock poisoned: {}", e)))?;
 
-    if available.is_empty() {
-        return Err(CyloError::no_backend_available());
-    }
+        if cache.available_backends.is_empty() {
+            return Err(CyloError::no_backend_available());
+        }
 
+        skip_unhealthy(eligible_backends(&cache.available_backends, preferences, request)?)
+    };
+
+    let selected = match &request.required_backend {
+        // eligible_backends only keeps the required backend
+        Some(required) => required.clone(),
+        None => select_from(strategy, preferences, &available, &request.language)?,
+    };
+    debug!(
+        target: targets::EXECUTOR,
+        "routing: selected {} for {} request via {:?} (candidates: {})",
+        selected,
+        request.language,
+        strategy,
+        available
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(selected)
+}
+
+/// Apply the routing strategy to already-eligible backends
+fn select_from(
+    strategy: &RoutingStrategy,
+    preferences: &BackendPreferences,
+    available: &[(String, u8)],
+    language: &str,
+) -> CyloResult<String> {
     match strategy {
         RoutingStrategy::Performance => {
-            // Select backend with highest performance rating
+            // Select backend with highest weighted performance rating
             let best = available
                 .iter()
-                .filter(|(name, _)| !preferences.excluded_backends.contains(name))
-                .max_by_key(|(_, rating)| *rating)
-                .ok_or_else(|| CyloError::no_backend_available())?;
+                .map(|(name, rating)| (name, *rating as f32 * preferences.weight(name, language)))
+                .inspect(|(name, score)| {
+                    trace!(
+                        target: targets::EXECUTOR,
+                        "routing: {} performance score {:.1}",
+                        name,
+                        score
+                    )
+                })
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .ok_or_else(CyloError::no_backend_available)?;
             Ok(best.0.clone())
         }
 
         RoutingStrategy::Security => {
-            // Prefer FireCracker > LandLock > Apple for security
-            let security_order = ["FireCracker", "LandLock", "Apple"];
-            for backend in &security_order {
-                if available.iter().any(|(name, _)| name == backend)
-                    && !preferences
-                        .excluded_backends
-                        .contains(&backend.to_string())
-                {
-                    return Ok(backend.to_string());
-                }
-            }
-            Err(CyloError::no_backend_available())
+            // Prefer FireCracker > LandLock > Apple > Docker > K8sJob > Wasm for security
+            let security_order = ["FireCracker", "LandLock", "Apple", "Docker", "K8sJob", "Wasm"];
+            security_order
+                .iter()
+                .find(|backend| available.iter().any(|(name, _)| name == *backend))
+                .map(|backend| backend.to_string())
+                .ok_or_else(CyloError::no_backend_available)
         }
 
         RoutingStrategy::Balanced => {
             // Weight performance with security considerations
             let mut weighted_scores: Vec<(String, f32)> = available
                 .iter()
-                .filter(|(name, _)| !preferences.excluded_backends.contains(name))
                 .map(|(name, rating)| {
                     let base_score = *rating as f32;
                     let security_bonus = match name.as_str() {
                         "FireCracker" => 20.0,
                         "LandLock" => 15.0,
                         "Apple" => 10.0,
+                        "Docker" => 10.0,
+                        "K8sJob" => 5.0,
+                        "Wasm" => 5.0,
                         _ => 0.0,
                     };
-                    let preference_multiplier = preferences
-                        .weight_multipliers
-                        .get(name)
-                        .copied()
-                        .unwrap_or(1.0);
+                    let preference_multiplier = preferences.weight(name, language);
 
                     let total_score = (base_score + security_bonus) * preference_multiplier;
+                    trace!(
+                        target: targets::EXECUTOR,
+                        "routing: {} balanced score ({} + {}) x {} = {:.1}",
+                        name, base_score, security_bonus, preference_multiplier, total_score
+                    );
                     (name.clone(), total_score)
                 })
                 .collect();
@@ -82,28 +131,357 @@ pub fn select_optimal_backend(
             weighted_scores
                 .first()
                 .map(|(name, _)| name.clone())
-                .ok_or_else(|| CyloError::no_backend_available())
+                .ok_or_else(CyloError::no_backend_available)
         }
 
         RoutingStrategy::PreferBackend(preferred) => {
-            // Use preferred backend if available, otherwise balanced
-            if available.iter().any(|(name, _)| name == preferred)
-                && !preferences.excluded_backends.contains(preferred)
-            {
+            // Use preferred backend if eligible, otherwise balanced
+            if available.iter().any(|(name, _)| name == preferred) {
                 Ok(preferred.clone())
             } else {
-                select_optimal_backend(
-                    &RoutingStrategy::Balanced,
-                    preferences,
-                    platform_cache,
-                    _request,
-                )
+                trace!(
+                    target: targets::EXECUTOR,
+                    "routing: preferred backend {} not eligible, using balanced",
+                    preferred
+                );
+                select_from(&RoutingStrategy::Balanced, preferences, available, language)
             }
         }
 
         RoutingStrategy::ExplicitOnly => Err(CyloError::invalid_configuration(
             "ExplicitOnly strategy requires instance_hint",
         )),
+
+        // The primary copy of a hedged request runs on the fastest backend
+        RoutingStrategy::Hedged { .. } => {
+            select_from(&RoutingStrategy::Performance, preferences, available, language)
+        }
+    }
+}
+
+/// Select the backend a hedged request races against `primary`
+///
+/// # Returns
+/// The fastest eligible backend other than `primary`, or None when the
+/// request names its backend, must not run twice, or nothing else is
+/// eligible
+pub fn select_hedge_backend(
+    preferences: &BackendPreferences,
+    platform_cache: &Arc<RwLock<PlatformCache>>,
+    request: &ExecutionRequest,
+    primary: &str,
+) -> CyloResult<Option<String>> {
+    if request.required_backend.is_some() || !runs_twice_safely(request) {
+        return Ok(None);
+    }
+
+    let runners_up: Vec<(String, u8)> = {
+        let cache = platform_cache
+            .read()
+            .map_err(|e| CyloError::internal(format!("Cache lock poisoned: {}", e)))?;
+        skip_unhealthy(eligible_backends(&cache.available_backends, preferences, request)?)
+            .into_iter()
+            .filter(|(name, _)| name != primary)
+            .collect()
+    };
+
+    if runners_up.is_empty() {
+        trace!(target: targets::EXECUTOR, "routing: no backend besides {} to hedge onto", primary);
+        return Ok(None);
+    }
+    select_from(
+        &RoutingStrategy::Performance,
+        preferences,
+        &runners_up,
+        &request.language,
+    )
+    .map(Some)
+}
+
+/// Whether two copies of a request may run at once
+///
+/// Only one copy can read a streamed input or save a sandbox, and copies
+/// would race on host state the request writes: writable paths, volumes,
+/// the host-side git checkout and the output archive.
+fn runs_twice_safely(request: &ExecutionRequest) -> bool {
+    request.input_stream.is_none()
+        && request.sandbox_state.is_none()
+        && request.writable_paths.is_empty()
+        && request.volumes.is_empty()
+        && request.git_repo.is_none()
+        && request.output_archive.is_none()
+}
+
+/// Drop backends whose cached health check failed and is backing off
+///
+/// Health is read from the global instance manager's cache without probing
+/// anything. If every candidate is backing off they are all kept, so a
+/// request is still attempted rather than rejected outright.
+fn skip_unhealthy(candidates: Vec<(String, u8)>) -> Vec<(String, u8)> {
+    let health = global_instance_manager().health_cache();
+    let (healthy, backing_off): (Vec<_>, Vec<_>) = candidates
+        .iter()
+        .cloned()
+        .partition(|(name, _)| !health.is_backing_off(name));
+
+    if healthy.is_empty() {
+        return candidates;
+    }
+    for (name, _) in &backing_off {
+        trace!(
+            target: targets::EXECUTOR,
+            "routing: {} rejected: health check failed recently",
+            name
+        );
+    }
+    healthy
+}
+
+/// Narrow available backends to those meeting the request's requirements
+/// and permitted by preferences
+///
+/// # Returns
+/// Matching backends, RequirementsUnsatisfiable listing what the request
+/// needs but cannot get, or BackendUnavailable if preferences reject every
+/// remaining backend
+fn eligible_backends(
+    available: &[(String, u8)],
+    preferences: &BackendPreferences,
+    request: &ExecutionRequest,
+) -> CyloResult<Vec<(String, u8)>> {
+    let language = request.language.as_str();
+    let mut missing = Vec::new();
+
+    if let Some(required) = &request.required_backend {
+        if !available.iter().any(|(name, _)| name == required) {
+            let names: Vec<&str> = available.iter().map(|(name, _)| name.as_str()).collect();
+            missing.push(format!(
+                "backend {} is not available (available: {})",
+                required,
+                names.join(", ")
+            ));
+        } else if let Some(reason) = preferences.denial_reason(required, language) {
+            missing.push(format!("backend {} is {} by preferences", required, reason));
+        }
+    }
+
+    let requested: Vec<&(String, u8)> = available
+        .iter()
+        .filter(|(name, _)| {
+            request
+                .required_backend
+                .as_ref()
+                .is_none_or(|required| required == name)
+        })
+        .collect();
+
+    let meeting_isolation: Vec<&(String, u8)> = requested
+        .iter()
+        .copied()
+        .filter(|(name, _)| {
+            let meets = meets_isolation(name, request);
+            if !meets {
+                trace!(
+                    target: targets::EXECUTOR,
+                    "routing: {} rejected: below required isolation",
+                    name
+                );
+            }
+            meets
+        })
+        .collect();
+
+    if let Some(level) = request.required_isolation
+        && meeting_isolation.is_empty()
+    {
+        let offered: Vec<String> = requested
+            .iter()
+            .map(|(name, _)| match IsolationLevel::of_backend(name) {
+                Some(provided) => format!("{} provides {}", name, provided),
+                None => format!("{} has unknown isolation", name),
+            })
+            .collect();
+        missing.push(format!(
+            "isolation level {} ({})",
+            level,
+            offered.join(", ")
+        ));
+    }
+
+    let fitting_memory: Vec<&(String, u8)> = meeting_isolation
+        .iter()
+        .copied()
+        .filter(|(name, _)| {
+            let fits = fits_memory(name, request);
+            if !fits {
+                trace!(
+                    target: targets::EXECUTOR,
+                    "routing: {} rejected: memory limit above backend maximum",
+                    name
+                );
+            }
+            fits
+        })
+        .collect();
+
+    if let Some(bytes) = request.limits.max_memory
+        && !meeting_isolation.is_empty()
+        && fitting_memory.is_empty()
+    {
+        let offered: Vec<String> = meeting_isolation
+            .iter()
+            .filter_map(|(name, _)| {
+                let max = BackendCapabilities::of_backend(name)?.max_memory_bytes?;
+                Some(format!("{} allows {}", name, max))
+            })
+            .collect();
+        missing.push(format!(
+            "memory limit of {} bytes ({})",
+            bytes,
+            offered.join(", ")
+        ));
+    }
+
+    let running_arch: Vec<&(String, u8)> = fitting_memory
+        .iter()
+        .copied()
+        .filter(|(name, _)| {
+            let runs = runs_arch(name, request);
+            if !runs {
+                trace!(
+                    target: targets::EXECUTOR,
+                    "routing: {} rejected: cannot run the requested architecture",
+                    name
+                );
+            }
+            runs
+        })
+        .collect();
+
+    if let Some(requested_arch) = request.arch
+        && !fitting_memory.is_empty()
+        && running_arch.is_empty()
+    {
+        let reasons: Vec<String> = fitting_memory
+            .iter()
+            .map(|(name, _)| {
+                format!("{}: {}", name, arch::unsupported_reason(name, requested_arch))
+            })
+            .collect();
+        missing.push(format!(
+            "architecture {} ({})",
+            requested_arch,
+            reasons.join(", ")
+        ));
+    }
+
+    if !missing.is_empty() {
+        return Err(CyloError::requirements_unsatisfiable(missing));
+    }
+
+    let mut denied = Vec::new();
+    let candidates: Vec<(String, u8)> = running_arch
+        .into_iter()
+        .filter(|(name, _)| match preferences.denial_reason(name, language) {
+            Some(reason) => {
+                trace!(
+                    target: targets::EXECUTOR,
+                    "routing: {} rejected for {}: {}",
+                    name,
+                    language,
+                    reason
+                );
+                denied.push(format!("{} ({})", name, reason));
+                false
+            }
+            None => true,
+        })
+        .cloned()
+        .collect();
+
+    if candidates.is_empty() {
+        return Err(CyloError::backend_unavailable(
+            "executor",
+            format!(
+                "preferences reject every available backend for {}: {}",
+                language,
+                denied.join(", ")
+            ),
+        ));
+    }
+
+    Ok(candidates)
+}
+
+/// Whether a backend provides the isolation a request requires
+fn meets_isolation(backend_name: &str, request: &ExecutionRequest) -> bool {
+    match request.required_isolation {
+        Some(required) => {
+            IsolationLevel::of_backend(backend_name).is_some_and(|level| level >= required)
+        }
+        None => true,
+    }
+}
+
+/// Whether a backend can give the request the memory limit it asks for
+fn fits_memory(backend_name: &str, request: &ExecutionRequest) -> bool {
+    BackendCapabilities::of_backend(backend_name)
+        .is_none_or(|capabilities| capabilities.fits_memory(request.limits.max_memory))
+}
+
+/// Whether a backend can run the request's architecture on this host
+fn runs_arch(backend_name: &str, request: &ExecutionRequest) -> bool {
+    request
+        .arch
+        .is_none_or(|requested| arch::supports(backend_name, requested, &request.language))
+}
+
+/// Check that an explicitly chosen backend satisfies the request
+///
+/// Used when an instance hint bypasses routing; the request's requirements
+/// still apply to it.
+pub fn check_requirements(backend_name: &str, request: &ExecutionRequest) -> CyloResult<()> {
+    let mut missing = Vec::new();
+
+    if let Some(required) = &request.required_backend
+        && required != backend_name
+    {
+        missing.push(format!(
+            "backend {} (instance runs on {})",
+            required, backend_name
+        ));
+    }
+    if let Some(level) = request.required_isolation
+        && !meets_isolation(backend_name, request)
+    {
+        missing.push(format!(
+            "isolation level {} (instance runs on {})",
+            level, backend_name
+        ));
+    }
+    if let Some(bytes) = request.limits.max_memory
+        && !fits_memory(backend_name, request)
+    {
+        missing.push(format!(
+            "memory limit of {} bytes (instance runs on {})",
+            bytes, backend_name
+        ));
+    }
+
+    if let Some(requested_arch) = request.arch
+        && !runs_arch(backend_name, request)
+    {
+        missing.push(format!(
+            "architecture {} ({})",
+            requested_arch,
+            arch::unsupported_reason(backend_name, requested_arch)
+        ));
+    }
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(CyloError::requirements_unsatisfiable(missing))
     }
 }
 
@@ -115,54 +493,245 @@ pub fn create_cylo_env(backend_name: &str, request: &ExecutionRequest) -> CyloRe
             Ok(Cylo::Apple(image))
         }
         "LandLock" => Ok(Cylo::LandLock("/tmp/cylo_landlock".to_string())),
+        "Docker" => {
+            let image = select_image_for_language(&request.language);
+            Ok(Cylo::Docker(image))
+        }
+        "Wasm" => {
+            let wasmtime = crate::backends::wasm::installed_runtime().unwrap_or("wasmtime");
+            Ok(Cylo::Wasm(wasmtime.to_string()))
+        }
+        "K8sJob" => {
+            let image = select_image_for_language(&request.language);
+            Ok(Cylo::K8sJob(image))
+        }
         "FireCracker" => {
             let image = select_image_for_language(&request.language);
             Ok(Cylo::FireCracker(image))
         }
-        _ => Err(CyloError::unsupported_backend(backend_name)),
+        _ => match registry::factory(backend_name) {
+            Some((_, factory)) => Ok(Cylo::Custom {
+                backend: backend_name.to_string(),
+                config: factory.env_config_for(request),
+            }),
+            None => Err(CyloError::unsupported_backend(backend_name)),
+        },
     }
 }
 
 /// Select appropriate container image for programming language
+///
+/// A pinned version (`python@3.12`, `node@20`) becomes the image tag;
+/// unpinned languages get the default image.
 pub fn select_image_for_language(language: &str) -> String {
-    match language.to_lowercase().as_str() {
-        "python" | "python3" => "python:3.11-alpine".to_string(),
-        "javascript" | "js" | "node" => "node:18-alpine".to_string(),
-        "rust" => "rust:1.75-alpine".to_string(),
-        "go" => "golang:1.21-alpine".to_string(),
-        _ => "alpine:3.18".to_string(), // Default for bash/sh
+    let (name, version) = language::split_version(language);
+    let image = |repository: &str, default: &str| {
+        format!("{}:{}-alpine", repository, version.unwrap_or(default))
+    };
+
+    match name.to_lowercase().as_str() {
+        "python" | "python3" | "py" => image("python", "3.11"),
+        "javascript" | "js" | "node" => image("node", "18"),
+        "rust" | "rs" => image("rust", "1.75"),
+        "go" | "golang" => image("golang", "1.21"),
+        // The R project publishes no Alpine images
+        "r" | "rscript" => format!("rocker/r-ver:{}", version.unwrap_or("4.3")),
+        "bash" | "sh" => match version {
+            Some(version) => format!("bash:{}", version),
+            None => "alpine:3.18".to_string(),
+        },
+        _ => "alpine:3.18".to_string(),
     }
 }
 
 /// Get backend name from Cylo environment
 pub fn backend_name_from_cylo(cylo: &Cylo) -> String {
-    match cylo {
-        Cylo::Apple(_) => "Apple".to_string(),
-        Cylo::LandLock(_) => "LandLock".to_string(),
-        Cylo::FireCracker(_) => "FireCracker".to_string(),
-    }
+    cylo.backend_type().to_string()
 }
 
-/// Generate unique instance name
-pub fn generate_instance_name(backend_name: &str) -> String {
-    format!(
-        "{}_{}",
-        backend_name.to_lowercase(),
-        uuid::Uuid::new_v4().simple()
-    )
+/// Generate a unique instance name tagged with the request's language,
+/// e.g. `cylo-py-7f3a2b9c01de`
+pub fn generate_instance_name(language: &str) -> String {
+    ids::generate(&ids::language_tag(language))
 }
 
 /// Compute platform capabilities hash for cache invalidation
-pub fn compute_capabilities_hash(platform_info: &crate::platform::PlatformInfo) -> u64 {
+pub fn compute_capabilities_hash(backends: &[crate::platform::BackendAvailability]) -> u64 {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
     let mut hasher = DefaultHasher::new();
-    platform_info.available_backends.len().hash(&mut hasher);
-    for backend in &platform_info.available_backends {
+    backends.len().hash(&mut hasher);
+    for backend in backends {
         backend.name.hash(&mut hasher);
         backend.available.hash(&mut hasher);
         backend.performance_rating.hash(&mut hasher);
     }
     hasher.finish()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::{Arch, ResourceLimits};
+    use std::time::{Duration, SystemTime};
+
+    fn cache(backends: &[(&str, u8)]) -> Arc<RwLock<PlatformCache>> {
+        Arc::new(RwLock::new(PlatformCache {
+            available_backends: backends
+                .iter()
+                .map(|(name, rating)| (name.to_string(), *rating))
+                .collect(),
+            capabilities_hash: 0,
+            cached_at: SystemTime::now(),
+            cache_duration: Duration::from_secs(60),
+        }))
+    }
+
+    fn select(backends: &[(&str, u8)], request: &ExecutionRequest) -> CyloResult<String> {
+        select_optimal_backend(
+            &RoutingStrategy::Performance,
+            &BackendPreferences::default(),
+            &cache(backends),
+            request,
+        )
+    }
+
+    #[test]
+    fn required_backend_overrides_strategy() {
+        let request = ExecutionRequest::new("x", "python").with_backend("FireCracker");
+        let backends = [("LandLock", 90), ("FireCracker", 60)];
+        assert_eq!(select(&backends, &request).unwrap(), "FireCracker");
+    }
+
+    #[test]
+    fn isolation_requirement_narrows_candidates() {
+        let request = ExecutionRequest::new("x", "python")
+            .with_required_isolation(IsolationLevel::MicroVM);
+        let backends = [("LandLock", 90), ("FireCracker", 60)];
+        assert_eq!(select(&backends, &request).unwrap(), "FireCracker");
+
+        let err = select(&[("LandLock", 90)], &request).unwrap_err();
+        match err {
+            CyloError::RequirementsUnsatisfiable { missing } => {
+                assert_eq!(missing.len(), 1);
+                assert!(missing[0].contains("microVM"));
+                assert!(missing[0].contains("LandLock provides namespace"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn preferences_deny_allow_and_weight() {
+        let backends = [("LandLock", 90), ("FireCracker", 60), ("Apple", 70)];
+        let select_with = |preferences: BackendPreferences, language: &str| {
+            select_optimal_backend(
+                &RoutingStrategy::Performance,
+                &preferences,
+                &cache(&backends),
+                &ExecutionRequest::new("x", language),
+            )
+        };
+
+        let go_off_landlock = BackendPreferences::default().deny_for_language("golang", "LandLock");
+        assert_eq!(select_with(go_off_landlock.clone(), "go").unwrap(), "Apple");
+        assert_eq!(select_with(go_off_landlock, "python").unwrap(), "LandLock");
+
+        let allowed = BackendPreferences::default().allow("FireCracker");
+        assert_eq!(select_with(allowed, "python").unwrap(), "FireCracker");
+
+        let weighted = BackendPreferences::default().with_weight("LandLock", 0.5);
+        assert_eq!(select_with(weighted, "python").unwrap(), "Apple");
+
+        let none_left = BackendPreferences::default()
+            .deny("LandLock")
+            .deny("FireCracker")
+            .deny("Apple");
+        assert!(matches!(
+            select_with(none_left, "python"),
+            Err(CyloError::BackendUnavailable { .. })
+        ));
+    }
+
+    #[test]
+    fn unavailable_backend_is_reported() {
+        let request = ExecutionRequest::new("x", "python").with_backend("FireCracker");
+        assert!(matches!(
+            select(&[("LandLock", 90)], &request),
+            Err(CyloError::RequirementsUnsatisfiable { .. })
+        ));
+        assert!(check_requirements("LandLock", &request).is_err());
+        assert!(check_requirements("FireCracker", &request).is_ok());
+    }
+
+    #[test]
+    fn hedge_races_fastest_against_runner_up() {
+        let hedged = RoutingStrategy::Hedged {
+            delay: Duration::from_millis(250),
+        };
+        let backends = cache(&[("LandLock", 90), ("Apple", 70), ("FireCracker", 60)]);
+        let preferences = BackendPreferences::default();
+        let request = ExecutionRequest::new("x", "python");
+
+        let primary = select_optimal_backend(&hedged, &preferences, &backends, &request).unwrap();
+        assert_eq!(primary, "LandLock");
+        let hedge = select_hedge_backend(&preferences, &backends, &request, &primary).unwrap();
+        assert_eq!(hedge.as_deref(), Some("Apple"));
+
+        let alone = cache(&[("LandLock", 90)]);
+        let hedge = select_hedge_backend(&preferences, &alone, &request, "LandLock").unwrap();
+        assert_eq!(hedge, None);
+
+        // Requests writing host state run once
+        let writing = request.clone().with_writable_path("/tmp/cylo_hedge_out");
+        let hedge = select_hedge_backend(&preferences, &backends, &writing, "LandLock").unwrap();
+        assert_eq!(hedge, None);
+
+        let pinned = request.with_backend("LandLock");
+        let hedge = select_hedge_backend(&preferences, &backends, &pinned, "LandLock").unwrap();
+        assert_eq!(hedge, None);
+    }
+
+    #[test]
+    fn memory_limit_excludes_small_backends() {
+        let limits = ResourceLimits {
+            max_memory: Some(2 * 1024 * 1024 * 1024),
+            ..ResourceLimits::default()
+        };
+        let request = ExecutionRequest::new("x", "python").with_limits(limits);
+        let backends = [("FireCracker", 90), ("LandLock", 60)];
+        assert_eq!(select(&backends, &request).unwrap(), "LandLock");
+
+        let err = select(&[("FireCracker", 90)], &request).unwrap_err();
+        assert!(err.to_string().contains("FireCracker allows 536870912"), "{err}");
+        assert!(check_requirements("FireCracker", &request).is_err());
+        assert!(check_requirements("LandLock", &request).is_ok());
+    }
+
+    #[test]
+    fn foreign_architecture_needs_a_capable_backend() {
+        let Some(host) = Arch::host() else {
+            return;
+        };
+        let foreign = if host == Arch::X86_64 { Arch::Arm64 } else { Arch::X86_64 };
+        let backends = [("FireCracker", 90)];
+        let native = ExecutionRequest::new("x", "python").with_arch(host);
+        assert_eq!(select(&backends, &native).unwrap(), "FireCracker");
+
+        let request = ExecutionRequest::new("x", "python").with_arch(foreign);
+        let err = select(&backends, &request).unwrap_err();
+        assert!(err.to_string().contains(&format!("architecture {foreign}")), "{err}");
+        assert!(check_requirements("FireCracker", &request).is_err());
+    }
+
+    #[test]
+    fn pinned_versions_select_image_tags() {
+        assert_eq!(select_image_for_language("python"), "python:3.11-alpine");
+        assert_eq!(select_image_for_language("python@3.12"), "python:3.12-alpine");
+        assert_eq!(select_image_for_language("node@20"), "node:20-alpine");
+        assert_eq!(select_image_for_language("rust@1.78"), "rust:1.78-alpine");
+        assert_eq!(select_image_for_language("bash"), "alpine:3.18");
+        assert_eq!(select_image_for_language("R@4.4"), "rocker/r-ver:4.4");
+    }
+}
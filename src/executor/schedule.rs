@@ -0,0 +1,578 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/schedule.rs
+//! ----------------------------------------------------------------------------
+//! In-process scheduler for delayed and recurring executions.
+//!
+//! Jobs run a stored request at a fixed time or on a cron schedule. Pending
+//! jobs are mirrored to a JSON store so they survive a restart; recurring
+//! jobs that missed runs while the process was down resume at their next
+//! occurrence rather than replaying every missed run.
+//!
+//! Stored requests carry environment variables, git credentials and host
+//! paths, so the store lives in a directory private to the user and is
+//! written 0600. One executor at a time owns a store, holding a lock on it
+//! for as long as it lives; others keep their jobs in memory rather than
+//! overwrite the owner's.
+//! ============================================================================
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Weak};
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+use crate::backends::ExecutionRequest;
+use crate::execution_env::{CyloError, CyloResult};
+use crate::logging::targets;
+use crate::platform_utils;
+
+/// Longest the scheduler loop sleeps before re-checking its jobs
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// When a scheduled execution runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Schedule {
+    /// Run once at a point in time; times in the past run immediately
+    At(DateTime<Utc>),
+    /// Run on a five-field cron expression (minute hour day month weekday),
+    /// evaluated in UTC
+    Cron(String),
+}
+
+impl Schedule {
+    /// Run once after a delay from now
+    pub fn after(delay: Duration) -> Self {
+        let delay = chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::MAX);
+        Schedule::At(Utc::now().checked_add_signed(delay).unwrap_or(DateTime::<Utc>::MAX_UTC))
+    }
+
+    /// Run on a cron expression, e.g. `"*/15 * * * *"` or `"@daily"`
+    pub fn cron<E: Into<String>>(expression: E) -> Self {
+        Schedule::Cron(expression.into())
+    }
+
+    /// First run time for a new job
+    fn first_run(&self, now: DateTime<Utc>) -> CyloResult<DateTime<Utc>> {
+        match self {
+            Schedule::At(at) => Ok(*at),
+            Schedule::Cron(expression) => CronSchedule::parse(expression)?
+                .next_after(now)
+                .ok_or_else(|| {
+                    CyloError::validation(format!("Cron '{}' never fires", expression))
+                }),
+        }
+    }
+
+    /// Next run after a run at `now`, or None for one-shot schedules
+    fn next_run(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::At(_) => None,
+            Schedule::Cron(expression) => CronSchedule::parse(expression).ok()?.next_after(now),
+        }
+    }
+}
+
+/// A pending scheduled execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    /// Job identifier returned by `CyloExecutor::schedule`
+    pub id: String,
+    /// Request run on every occurrence; each run gets a fresh execution id
+    pub request: ExecutionRequest,
+    /// When the job runs
+    pub schedule: Schedule,
+    /// Next time the job is due
+    pub next_run: DateTime<Utc>,
+    /// Last time the job was started, if ever
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+impl ScheduledJob {
+    /// Create a job due at its schedule's first occurrence after now
+    pub(crate) fn new(request: ExecutionRequest, schedule: Schedule) -> CyloResult<Self> {
+        let next_run = schedule.first_run(Utc::now())?;
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            request,
+            schedule,
+            next_run,
+            last_run: None,
+        })
+    }
+}
+
+/// Pending jobs and the store they are mirrored to
+#[derive(Debug)]
+pub(crate) struct Scheduler {
+    jobs: Mutex<HashMap<String, ScheduledJob>>,
+    store: Option<PathBuf>,
+    /// Lock on the store, held while the scheduler owns it
+    _store_lock: Option<File>,
+    wake: Notify,
+}
+
+impl Scheduler {
+    /// Open a scheduler, loading any jobs persisted in `store`
+    ///
+    /// Recurring jobs whose next run passed while nothing was running are
+    /// moved to their next future occurrence; overdue one-shot jobs are
+    /// kept and run as soon as the scheduler loop starts. A store another
+    /// executor owns, or one that is not private to the user, is left
+    /// alone and the jobs are kept in memory only.
+    pub(crate) fn open(store: Option<PathBuf>) -> Self {
+        let (store, store_lock) = match store.map(|path| (lock_store(&path), path)) {
+            Some((Ok(lock), path)) => (Some(path), Some(lock)),
+            Some((Err(e), path)) => {
+                warn!(
+                    target: targets::EXECUTOR,
+                    "Scheduled jobs are kept in memory only; store {} is unusable: {}",
+                    path.display(),
+                    e
+                );
+                (None, None)
+            }
+            None => (None, None),
+        };
+
+        let now = Utc::now();
+        let jobs = store
+            .as_deref()
+            .map(load_jobs)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|mut job| {
+                if job.next_run < now && matches!(job.schedule, Schedule::Cron(_)) {
+                    job.next_run = job.schedule.next_run(now)?;
+                }
+                Some((job.id.clone(), job))
+            })
+            .collect();
+
+        Self {
+            jobs: Mutex::new(jobs),
+            store,
+            _store_lock: store_lock,
+            wake: Notify::new(),
+        }
+    }
+
+    /// Add a job and wake the loop so it sees the new due time
+    pub(crate) fn add(&self, job: ScheduledJob) {
+        let mut jobs = self.lock();
+        jobs.insert(job.id.clone(), job);
+        self.persist(&jobs);
+        drop(jobs);
+        self.wake.notify_one();
+    }
+
+    /// Remove a pending job
+    ///
+    /// # Returns
+    /// Whether a job with that id was pending
+    pub(crate) fn cancel(&self, id: &str) -> bool {
+        let mut jobs = self.lock();
+        let removed = jobs.remove(id).is_some();
+        if removed {
+            self.persist(&jobs);
+        }
+        removed
+    }
+
+    /// Snapshot of pending jobs ordered by next run
+    pub(crate) fn jobs(&self) -> Vec<ScheduledJob> {
+        let mut jobs: Vec<ScheduledJob> = self.lock().values().cloned().collect();
+        jobs.sort_by_key(|job| job.next_run);
+        jobs
+    }
+
+    /// Remove one-shot jobs and advance recurring jobs that are due
+    ///
+    /// # Returns
+    /// Requests to run now, each paired with its job id
+    fn take_due(&self, now: DateTime<Utc>) -> Vec<(String, ExecutionRequest)> {
+        let mut jobs = self.lock();
+        let due: Vec<String> = jobs
+            .values()
+            .filter(|job| job.next_run <= now)
+            .map(|job| job.id.clone())
+            .collect();
+        if due.is_empty() {
+            return Vec::new();
+        }
+
+        let mut runs = Vec::with_capacity(due.len());
+        for id in due {
+            let Some(job) = jobs.get_mut(&id) else {
+                continue;
+            };
+            let mut request = job.request.clone();
+            request.execution_id = None;
+            runs.push((id.clone(), request));
+
+            job.last_run = Some(now);
+            match job.schedule.next_run(now) {
+                Some(next) => job.next_run = next,
+                None => {
+                    jobs.remove(&id);
+                }
+            }
+        }
+        self.persist(&jobs);
+        runs
+    }
+
+    /// Time until the earliest pending job is due
+    fn time_until_next(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.lock()
+            .values()
+            .map(|job| job.next_run)
+            .min()
+            .map(|next| (next - now).to_std().unwrap_or(Duration::ZERO))
+    }
+
+    /// Jobs are only ever inserted or removed whole, so a poisoned lock is
+    /// recovered rather than losing the schedule
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, ScheduledJob>> {
+        self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn persist(&self, jobs: &HashMap<String, ScheduledJob>) {
+        let Some(path) = &self.store else {
+            return;
+        };
+        let entries: Vec<&ScheduledJob> = jobs.values().collect();
+        let result = serde_json::to_string(&entries)
+            .map_err(std::io::Error::other)
+            .and_then(|json| platform_utils::write_private_file(path, json.as_bytes()));
+        if let Err(e) = result {
+            warn!(
+                target: targets::EXECUTOR,
+                "Failed to persist scheduled jobs to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Take ownership of a store, creating its private directory
+///
+/// # Returns
+/// The lock file, held open for as long as the store is owned, or why
+/// the store cannot be owned
+fn lock_store(path: &Path) -> std::io::Result<File> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        platform_utils::create_private_dir(dir)?;
+    }
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let lock = File::create(path.with_file_name(format!("{name}.lock")))?;
+    lock.try_lock().map_err(|e| match e {
+        std::fs::TryLockError::WouldBlock => {
+            std::io::Error::other("another executor owns it")
+        }
+        std::fs::TryLockError::Error(e) => e,
+    })?;
+    Ok(lock)
+}
+
+/// Read persisted jobs, treating a missing or unreadable store as empty
+fn load_jobs(path: &Path) -> Vec<ScheduledJob> {
+    let Ok(json) = platform_utils::read_private_file(path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        warn!(
+            target: targets::EXECUTOR,
+            "Ignoring unreadable schedule store {}: {}",
+            path.display(),
+            e
+        );
+        Vec::new()
+    })
+}
+
+/// Run due jobs until the scheduler is dropped
+///
+/// The loop only holds the scheduler while checking it and sleeping, so it
+/// exits within `MAX_SLEEP` of its owner dropping the scheduler.
+pub(crate) async fn run_loop<F>(scheduler: Weak<Scheduler>, run: F)
+where
+    F: Fn(String, ExecutionRequest) + Send + 'static,
+{
+    loop {
+        let Some(scheduler) = scheduler.upgrade() else {
+            break;
+        };
+
+        for (id, request) in scheduler.take_due(Utc::now()) {
+            debug!(target: targets::EXECUTOR, "scheduler: starting job {}", id);
+            run(id, request);
+        }
+
+        let sleep = scheduler
+            .time_until_next(Utc::now())
+            .map_or(MAX_SLEEP, |until| until.min(MAX_SLEEP));
+        tokio::select! {
+            _ = scheduler.wake.notified() => {}
+            _ = tokio::time::sleep(sleep) => {}
+        }
+    }
+}
+
+/// Parsed five-field cron expression
+///
+/// Supports `*`, values, ranges (`1-5`), lists (`1,15`), and steps (`*/10`,
+/// `0-30/5`) in every field, plus the `@hourly`, `@daily`, `@weekly`,
+/// `@monthly`, and `@yearly` shorthands. Weekdays run 0-7 with both 0 and 7
+/// meaning Sunday. As in standard cron, when both day-of-month and
+/// day-of-week are restricted a day matching either one fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression
+    pub(crate) fn parse(expression: &str) -> CyloResult<Self> {
+        let expanded = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let &[minute, hour, day, month, weekday] = fields.as_slice() else {
+            return Err(invalid_cron(
+                expression,
+                format!("expected 5 fields, found {}", fields.len()),
+            ));
+        };
+
+        let field = |text: &str, min: u32, max: u32| {
+            parse_field(text, min, max).map_err(|reason| invalid_cron(expression, reason))
+        };
+
+        let mut days_of_week = field(weekday, 0, 7)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: field(minute, 0, 59)?,
+            hours: field(hour, 0, 23)?,
+            days_of_month: field(day, 1, 31)?,
+            months: field(month, 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: day != "*",
+            day_of_week_restricted: weekday != "*",
+        })
+    }
+
+    /// First matching minute strictly after `after`
+    ///
+    /// # Returns
+    /// The next fire time, or None if nothing matches within five years
+    /// (e.g. `0 0 31 2 *`)
+    pub(crate) fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        let limit = start + chrono::Duration::days(5 * 366);
+
+        let mut date = start.date_naive();
+        let (mut hour, mut minute) = (start.hour(), start.minute());
+
+        while date <= limit.date_naive() {
+            if !bit(self.months, date.month()) {
+                date = first_of_next_month(date)?;
+                (hour, minute) = (0, 0);
+                continue;
+            }
+            if !self.matches_day(date) {
+                date = date.succ_opt()?;
+                (hour, minute) = (0, 0);
+                continue;
+            }
+
+            let found = (hour..24).filter(|h| bit(self.hours, *h)).find_map(|h| {
+                let from = if h == hour { minute } else { 0 };
+                (from..60).find(|m| bit(self.minutes, *m)).map(|m| (h, m))
+            });
+            if let Some((h, m)) = found {
+                return Some(Utc.from_utc_datetime(&date.and_hms_opt(h, m, 0)?));
+            }
+
+            date = date.succ_opt()?;
+            (hour, minute) = (0, 0);
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let by_month_day = bit(self.days_of_month, date.day());
+        let by_weekday = bit(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => by_month_day || by_weekday,
+            (true, false) => by_month_day,
+            (false, true) => by_weekday,
+            (false, false) => true,
+        }
+    }
+}
+
+fn invalid_cron(expression: &str, reason: impl std::fmt::Display) -> CyloError {
+    CyloError::validation(format!("Invalid cron expression '{}': {}", expression, reason))
+}
+
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDate> {
+    match date.month() {
+        12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+        month => NaiveDate::from_ymd_opt(date.year(), month + 1, 1),
+    }
+}
+
+/// Parse one cron field into a bitmask of the values it matches
+fn parse_field(text: &str, min: u32, max: u32) -> Result<u64, String> {
+    let number = |value: &str| -> Result<u32, String> {
+        let parsed: u32 = value
+            .parse()
+            .map_err(|_| format!("'{}' is not a number", value))?;
+        if parsed < min || parsed > max {
+            return Err(format!("{} is outside {}-{}", parsed, min, max));
+        }
+        Ok(parsed)
+    };
+
+    let mut mask = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a valid step", step))?;
+                if step == 0 {
+                    return Err("step must be at least 1".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (low, high) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((low, high)) => (number(low)?, number(high)?),
+                // `5/10` means every 10th value starting at 5
+                None if step > 1 => (number(range)?, max),
+                None => {
+                    let value = number(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if low > high {
+            return Err(format!("range {}-{} is reversed", low, high));
+        }
+
+        for value in (low..=high).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn cron_finds_next_occurrence() {
+        let every_quarter_hour = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            every_quarter_hour.next_after(utc("2025-03-10T10:07:30Z")),
+            Some(utc("2025-03-10T10:15:00Z"))
+        );
+
+        // Fridays at 17:30; 2025-03-10 is a Monday
+        let friday_evening = CronSchedule::parse("30 17 * * 5").unwrap();
+        assert_eq!(
+            friday_evening.next_after(utc("2025-03-10T18:00:00Z")),
+            Some(utc("2025-03-14T17:30:00Z"))
+        );
+
+        let new_year = CronSchedule::parse("@yearly").unwrap();
+        assert_eq!(
+            new_year.next_after(utc("2025-12-31T23:59:00Z")),
+            Some(utc("2026-01-01T00:00:00Z"))
+        );
+
+        let sunday = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(sunday, CronSchedule::parse("0 0 * * 0").unwrap());
+
+        let never = CronSchedule::parse("0 0 31 2 *").unwrap();
+        assert_eq!(never.next_after(utc("2025-01-01T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn invalid_cron_is_rejected() {
+        let invalid = ["* * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "a * * * *"];
+        for expression in invalid {
+            assert!(CronSchedule::parse(expression).is_err(), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn due_jobs_are_taken_and_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("jobs.json");
+        let request = ExecutionRequest::new("print(1)", "python");
+
+        let scheduler = Scheduler::open(Some(store.clone()));
+        let once = ScheduledJob::new(request.clone(), Schedule::At(utc("2020-01-01T00:00:00Z")));
+        let hourly = ScheduledJob::new(request, Schedule::cron("@hourly")).unwrap();
+        scheduler.add(once.unwrap());
+        scheduler.add(hourly.clone());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&store).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let due = scheduler.take_due(Utc::now());
+        assert_eq!(due.len(), 1);
+        assert!(due[0].1.execution_id.is_none());
+
+        // Owned by the first scheduler, so a second one keeps to memory
+        let contender = Scheduler::open(Some(store.clone()));
+        assert!(contender.store.is_none() && contender.jobs().is_empty());
+        drop((contender, scheduler));
+
+        let reopened = Scheduler::open(Some(store));
+        let pending = reopened.jobs();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, hourly.id);
+        assert!(reopened.cancel(&hourly.id));
+        assert!(reopened.jobs().is_empty());
+    }
+}
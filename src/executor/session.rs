@@ -0,0 +1,420 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/session.rs
+//! ----------------------------------------------------------------------------
+//! Sessions of related executions.
+//!
+//! An agent working interactively sends a series of executions that belong
+//! together. A session runs each of them through the executor, with the
+//! same routing, admission and middleware as `execute`, and records what
+//! went in and came out of every one in a single timestamped transcript.
+//! The transcript can be exported at any point for audit and debugging.
+//!
+//! A sticky session also carries state from one execution to the next: a
+//! workspace volume attached to every execution, and on backends that keep
+//! it the sandbox's own filesystem. Its executions run one at a time on the
+//! instance the first one ran on, so each continues from where the last
+//! left off. Snapshots capture the workspace and the sandbox together, so
+//! an agent can branch from a known-good state and roll back a failed
+//! experiment.
+//! ============================================================================
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::async_task::{AsyncTask, AsyncTaskBuilder};
+use crate::backends::{
+    ExecutionRequest, ExecutionResult, SandboxState, Transcript, TranscriptRecorder, Volume,
+    VolumeStore, blocking,
+};
+use crate::execution_env::{CyloError, CyloInstance, CyloResult};
+
+use super::SharedState;
+
+/// Point-in-time state of a sticky session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// ID the snapshot is restored by
+    pub id: String,
+    /// ID of the session it was taken of
+    pub session: String,
+    /// When it was taken
+    pub created_at: SystemTime,
+    /// Bytes of workspace it holds
+    pub workspace_bytes: u64,
+}
+
+/// Series of executions recorded in one transcript
+///
+/// Created by `CyloExecutor::session` or `CyloExecutor::sticky_session`.
+/// Executions of a plain session share the executor's state and may run
+/// concurrently; those of a sticky session run one at a time.
+#[derive(Debug)]
+pub struct ExecutionSession {
+    /// Unique session ID, also the transcript's title
+    id: String,
+    /// Executor state executions are run with
+    shared: SharedState,
+    /// Transcript every execution of the session records into
+    transcript: TranscriptRecorder,
+    /// State carried between executions, for a sticky session
+    sticky: Option<Arc<Sticky>>,
+}
+
+/// Workspace and sandbox a sticky session carries between executions
+#[derive(Debug)]
+struct Sticky {
+    /// Store holding the workspace volume
+    store: VolumeStore,
+    /// Volume attached to every execution
+    workspace: Volume,
+    /// Absolute sandbox path the workspace is attached at
+    target: PathBuf,
+    /// Sandbox filesystem backends continue from
+    sandbox: SandboxState,
+    /// Instance the first execution ran on, which later ones are pinned
+    /// to; locked for the whole of each execution, snapshot and restore
+    instance: Mutex<Option<CyloInstance>>,
+}
+
+impl ExecutionSession {
+    pub(super) fn new(shared: SharedState) -> Self {
+        let id = uuid::Uuid::new_v4().to_string();
+        let transcript = TranscriptRecorder::new(Transcript::new().with_title(id.clone()));
+        Self {
+            id,
+            shared,
+            transcript,
+            sticky: None,
+        }
+    }
+
+    /// Session with a new workspace volume in `store`, attached at `target`
+    pub(super) fn sticky(
+        shared: SharedState,
+        store: &VolumeStore,
+        target: PathBuf,
+    ) -> CyloResult<Self> {
+        let mut session = Self::new(shared);
+        let name = format!("session-{}", session.id);
+        let workspace = store.create(&name, None)?;
+        let sandbox = SandboxState::new(session.id.clone(), store.sandbox_dir(&name)?);
+        session.sticky = Some(Arc::new(Sticky {
+            store: store.clone(),
+            workspace,
+            target,
+            sandbox,
+            instance: Mutex::new(None),
+        }));
+        Ok(session)
+    }
+
+    /// Unique session ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Workspace volume of a sticky session
+    pub fn workspace(&self) -> Option<&Volume> {
+        self.sticky.as_ref().map(|sticky| &sticky.workspace)
+    }
+
+    /// Execute code as part of the session
+    ///
+    /// The request's input, and its output as the backend produces it,
+    /// are recorded in the session transcript in place of any transcript
+    /// the request carried. In a sticky session the execution waits for
+    /// the previous one to finish, has the workspace attached, and runs on
+    /// the instance the first execution ran on rather than `instance_hint`.
+    ///
+    /// # Arguments
+    /// * `request` - Execution request with code and requirements
+    /// * `instance_hint` - Optional preferred instance for execution
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to execution result
+    pub fn execute(
+        &self,
+        request: ExecutionRequest,
+        instance_hint: Option<&CyloInstance>,
+    ) -> AsyncTask<CyloResult<ExecutionResult>> {
+        let context = self.shared.context();
+        let mut request = request.with_transcript(self.transcript.clone());
+        let sticky = self.sticky.clone();
+        let instance_hint = instance_hint.cloned();
+
+        AsyncTaskBuilder::new(async move {
+            let Some(sticky) = sticky else {
+                return context
+                    .run(request, instance_hint)
+                    .await
+                    .and_then(|routed| routed.result);
+            };
+
+            let mut pinned = sticky.instance.lock().await;
+            request = request.with_volume(&sticky.workspace, sticky.target.clone());
+            request.sandbox_state = Some(sticky.sandbox.clone());
+            let routed = context.run(request, pinned.clone().or(instance_hint)).await?;
+            pinned.get_or_insert(routed.instance);
+            routed.result
+        })
+        .spawn()
+    }
+
+    /// Everything exchanged with the session's executions so far
+    ///
+    /// Export the copy with `Transcript::to_asciicast` for a terminal
+    /// player or `Transcript::to_text` for logs.
+    pub fn export_transcript(&self) -> Transcript {
+        self.transcript.transcript()
+    }
+
+    /// Capture the workspace and sandbox of a sticky session
+    ///
+    /// Waits for a running execution to finish first.
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to the snapshot, or a validation error for
+    /// a session that is not sticky
+    pub fn snapshot(&self) -> AsyncTask<CyloResult<SessionSnapshot>> {
+        let sticky = self.sticky();
+        let session = self.id.clone();
+
+        AsyncTaskBuilder::new(async move {
+            let sticky = sticky?;
+            let _turn = sticky.instance.lock().await;
+            let job = Arc::clone(&sticky);
+            blocking::backend_io()
+                .run(move || {
+                    let workspace = job.store.snapshot(&job.workspace.name)?;
+                    if let Err(e) = job.sandbox.snapshot(&workspace.id) {
+                        let _ = job.store.remove_snapshot(&job.workspace.name, &workspace.id);
+                        return Err(e);
+                    }
+                    Ok(SessionSnapshot {
+                        id: workspace.id,
+                        session,
+                        created_at: workspace.created_at,
+                        workspace_bytes: workspace.size_bytes,
+                    })
+                })
+                .await?
+        })
+        .spawn()
+    }
+
+    /// Snapshots of a sticky session, oldest first
+    pub fn snapshots(&self) -> CyloResult<Vec<SessionSnapshot>> {
+        let sticky = self.sticky()?;
+        let snapshots = sticky.store.snapshots(&sticky.workspace.name)?;
+        Ok(snapshots
+            .into_iter()
+            .map(|workspace| SessionSnapshot {
+                id: workspace.id,
+                session: self.id.clone(),
+                created_at: workspace.created_at,
+                workspace_bytes: workspace.size_bytes,
+            })
+            .collect())
+    }
+
+    /// Roll a sticky session's workspace and sandbox back to a snapshot
+    ///
+    /// Waits for a running execution to finish first. Both are copied
+    /// before either is swapped in, and the workspace is put back if the
+    /// sandbox cannot be, so a failed restore leaves the session as it was.
+    /// The snapshot is kept, so the session can be rolled back to it again.
+    ///
+    /// # Returns
+    /// AsyncTask that resolves once restored, or to a validation error for
+    /// an unknown snapshot, a session that is not sticky, or a workspace
+    /// another execution has attached
+    pub fn restore(&self, snapshot_id: &str) -> AsyncTask<CyloResult<()>> {
+        let sticky = self.sticky();
+        let snapshot_id = snapshot_id.to_string();
+
+        AsyncTaskBuilder::new(async move {
+            let sticky = sticky?;
+            let _turn = sticky.instance.lock().await;
+            let job = Arc::clone(&sticky);
+            blocking::backend_io()
+                .run(move || {
+                    let mut workspace = job.store.stage_restore(&job.workspace.name, &snapshot_id)?;
+                    let mut sandbox = job.sandbox.stage_restore(&snapshot_id)?;
+                    workspace.swap()?;
+                    if let Err(e) = sandbox.swap() {
+                        workspace.undo();
+                        return Err(e);
+                    }
+                    sandbox.finish();
+                    workspace.finish();
+                    Ok(())
+                })
+                .await?
+        })
+        .spawn()
+    }
+
+    /// End the session, removing its workspace, sandbox state and
+    /// snapshots; a plain session has nothing to remove
+    ///
+    /// Waits for a running execution to finish first. The transcript is
+    /// exported beforehand if it is still needed.
+    pub fn close(self) -> AsyncTask<CyloResult<()>> {
+        let sticky = self.sticky;
+
+        AsyncTaskBuilder::new(async move {
+            let Some(sticky) = sticky else {
+                return Ok(());
+            };
+            let _turn = sticky.instance.lock().await;
+            let job = Arc::clone(&sticky);
+            blocking::backend_io()
+                .run(move || {
+                    job.sandbox.discard()?;
+                    job.store.remove(&job.workspace.name).map(|_| ())
+                })
+                .await?
+        })
+        .spawn()
+    }
+
+    fn sticky(&self) -> CyloResult<Arc<Sticky>> {
+        self.sticky.clone().ok_or_else(|| {
+            CyloError::validation(format!(
+                "Session {} is not sticky; start it with CyloExecutor::sticky_session",
+                self.id
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::super::{CyloExecutor, ExecutorConfig, OptimizationConfig};
+    use crate::backends::{
+        AsyncTask, BackendConfig, BackendFactory, ExecutionBackend, ExecutionRequest,
+        ExecutionResult, HealthCheckLevel, HealthStatus, TranscriptStream, VolumeStore,
+        register_backend,
+    };
+    use crate::execution_env::{Cylo, CyloResult};
+
+    /// Backend echoing a request's input, so sessions run real executions
+    /// without a sandbox on the host
+    #[derive(Debug)]
+    struct EchoBackend(BackendConfig);
+
+    impl ExecutionBackend for EchoBackend {
+        fn execute_code(&self, request: ExecutionRequest) -> AsyncTask<ExecutionResult> {
+            let input = request.input.unwrap_or_default();
+            tokio::spawn(async move { ExecutionResult::success(input) })
+        }
+
+        fn health_check(&self, _level: HealthCheckLevel) -> AsyncTask<HealthStatus> {
+            tokio::spawn(async { HealthStatus::healthy("echo") })
+        }
+
+        fn cleanup(&self) -> AsyncTask<CyloResult<()>> {
+            tokio::spawn(async { Ok(()) })
+        }
+
+        fn get_config(&self) -> &BackendConfig {
+            &self.0
+        }
+
+        fn backend_type(&self) -> &'static str {
+            "SessionTestEcho"
+        }
+
+        fn supports_language(&self, language: &str) -> bool {
+            language == "python"
+        }
+
+        fn supported_languages(&self) -> &[&'static str] {
+            &["python"]
+        }
+    }
+
+    struct EchoFactory;
+
+    impl BackendFactory for EchoFactory {
+        fn create(&self, _: &str, config: BackendConfig) -> CyloResult<Box<dyn ExecutionBackend>> {
+            Ok(Box::new(EchoBackend(config)))
+        }
+    }
+
+    fn executor() -> CyloExecutor {
+        CyloExecutor::with_config(ExecutorConfig {
+            optimization: OptimizationConfig {
+                startup_recovery: None,
+                ..OptimizationConfig::default()
+            },
+            ..ExecutorConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn sessions_export_what_their_executions_exchanged() {
+        let executor = executor();
+        let session = executor.session();
+        assert_ne!(session.id(), executor.session().id());
+
+        let transcript = session.export_transcript();
+        assert_eq!(transcript.title.as_deref(), Some(session.id()));
+        assert!(transcript.events.is_empty());
+
+        // Input is recorded once the request is admitted; the unknown
+        // language is refused before that
+        let refused = ExecutionRequest::new("x", "no-such-language").with_input("ignored\n");
+        assert!(session.execute(refused, None).await.unwrap().is_err());
+        assert!(session.export_transcript().events.is_empty());
+
+        register_backend("SessionTestEcho", Box::new(EchoFactory)).unwrap();
+        let echo = Cylo::Custom {
+            backend: "SessionTestEcho".to_string(),
+            config: String::new(),
+        }
+        .instance("session_echo");
+        let request = ExecutionRequest::new("print(input())", "python").with_input("4\n");
+        let result = session.execute(request, Some(&echo)).await.unwrap().unwrap();
+        assert_eq!(result.stdout, "4\n");
+
+        let transcript = session.export_transcript();
+        let streams: Vec<_> = transcript.events.iter().map(|e| e.stream).collect();
+        assert_eq!(streams, [TranscriptStream::Input, TranscriptStream::Stdout]);
+        let cast = transcript.to_asciicast();
+        assert!(cast.contains(r#""i","4\n""#) && cast.contains(r#""o","4\n""#), "{cast}");
+
+        assert!(session.snapshot().await.unwrap().is_err());
+        assert!(session.close().await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn sticky_sessions_roll_back_to_snapshots() {
+        let root = std::env::temp_dir().join(format!("cylo_sessions_{}", uuid::Uuid::new_v4()));
+        let store = VolumeStore::new(&root);
+        let executor = executor();
+        let session = executor.sticky_session(&store, "/workspace").unwrap();
+        let workspace = session.workspace().unwrap().path.clone();
+
+        fs::write(workspace.join("model.txt"), "good").unwrap();
+        let snapshot = session.snapshot().await.unwrap().unwrap();
+        assert_eq!(snapshot.session, session.id());
+        assert_eq!(snapshot.workspace_bytes, 4);
+
+        fs::write(workspace.join("model.txt"), "broken").unwrap();
+        session.restore(&snapshot.id).await.unwrap().unwrap();
+        assert_eq!(fs::read_to_string(workspace.join("model.txt")).unwrap(), "good");
+        assert_eq!(session.snapshots().unwrap(), [snapshot]);
+        assert!(session.restore("missing").await.unwrap().is_err());
+
+        session.close().await.unwrap().unwrap();
+        assert!(store.list().unwrap().is_empty());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
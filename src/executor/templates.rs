@@ -0,0 +1,264 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/templates.rs
+//! ----------------------------------------------------------------------------
+//! Named execution request templates.
+//!
+//! Applications that submit the same kind of execution from many places
+//! register its language, limits, mounts, environment and network policy
+//! once under a name, then build requests with `from_template(name, code)`.
+//! Templates live in `ExecutorConfig`, so they can ship in the executor's
+//! config file and change with it.
+//! ============================================================================
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{DnsPolicy, ExecutionRequest, IsolationLevel, ResourceLimits, VolumeMount};
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Settings shared by every request built from a template
+///
+/// Only the language is required; unset settings leave the request's
+/// defaults in place, so language profiles still apply to them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestTemplate {
+    /// Programming language
+    pub language: String,
+    /// What the template is for, shown when listing templates
+    pub description: Option<String>,
+    /// Wall-clock timeout
+    pub timeout: Option<Duration>,
+    /// Resource limits; `max_network_bandwidth` of zero cuts off the network
+    pub limits: Option<ResourceLimits>,
+    /// Environment variables
+    pub env_vars: HashMap<String, String>,
+    /// Files written into the working directory, keyed by relative path
+    pub files: BTreeMap<String, String>,
+    /// Host paths the code may read
+    pub readable_paths: Vec<PathBuf>,
+    /// Host paths the code may write
+    pub writable_paths: Vec<PathBuf>,
+    /// Volumes attached to the execution
+    pub volumes: Vec<VolumeMount>,
+    /// DNS policy applied when network access is allowed
+    pub dns: Option<DnsPolicy>,
+    /// Minimum isolation level the backend must provide
+    pub required_isolation: Option<IsolationLevel>,
+    /// Backend the request must run on
+    pub required_backend: Option<String>,
+    /// Tenant usage is attributed to
+    pub tenant: Option<String>,
+    /// Backend-specific settings
+    pub backend_config: HashMap<String, String>,
+}
+
+impl RequestTemplate {
+    /// Template for a language with nothing else set
+    pub fn new<L: Into<String>>(language: L) -> Self {
+        Self {
+            language: language.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the description
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the wall-clock timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the resource limits
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Add an environment variable
+    pub fn with_env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.env_vars.insert(key.into(), value.into());
+        self
+    }
+
+    /// Add a file to the working directory
+    pub fn with_file<P: Into<String>, C: Into<String>>(mut self, path: P, contents: C) -> Self {
+        self.files.insert(path.into(), contents.into());
+        self
+    }
+
+    /// Let the code read a host path
+    pub fn with_readable_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.readable_paths.push(path.into());
+        self
+    }
+
+    /// Let the code write a host path
+    pub fn with_writable_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.writable_paths.push(path.into());
+        self
+    }
+
+    /// Attach a volume
+    pub fn with_volume(mut self, mount: VolumeMount) -> Self {
+        self.volumes.push(mount);
+        self
+    }
+
+    /// Set the DNS policy
+    pub fn with_dns(mut self, policy: DnsPolicy) -> Self {
+        self.dns = Some(policy);
+        self
+    }
+
+    /// Require a minimum isolation level
+    pub fn with_required_isolation(mut self, level: IsolationLevel) -> Self {
+        self.required_isolation = Some(level);
+        self
+    }
+
+    /// Require a specific backend
+    pub fn with_backend<B: Into<String>>(mut self, backend: B) -> Self {
+        self.required_backend = Some(backend.into());
+        self
+    }
+
+    /// Attribute usage to a tenant
+    pub fn with_tenant<T: Into<String>>(mut self, tenant: T) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    /// Add a backend-specific setting
+    pub fn with_backend_config<K: Into<String>, V: Into<String>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Self {
+        self.backend_config.insert(key.into(), value.into());
+        self
+    }
+
+    /// Build a request running `code` with the template's settings
+    pub fn instantiate<C: Into<String>>(&self, code: C) -> ExecutionRequest {
+        let mut request = ExecutionRequest::new(code, self.language.clone());
+        if let Some(timeout) = self.timeout {
+            request = request.with_timeout(timeout);
+        }
+        if let Some(limits) = &self.limits {
+            request = request.with_limits(limits.clone());
+        }
+        request.env_vars = self.env_vars.clone();
+        request.files = self.files.clone();
+        request.readable_paths = self.readable_paths.clone();
+        request.writable_paths = self.writable_paths.clone();
+        request.volumes = self.volumes.clone();
+        request.dns = self.dns.clone();
+        request.required_isolation = self.required_isolation;
+        request.required_backend = self.required_backend.clone();
+        request.tenant = self.tenant.clone();
+        request.backend_config = self.backend_config.clone();
+        request
+    }
+
+    /// Reject a template no request built from it could pass validation
+    pub(crate) fn validate(&self, name: &str) -> CyloResult<()> {
+        if name.trim().is_empty() {
+            return Err(CyloError::validation("Template names must not be empty"));
+        }
+        if self.language.trim().is_empty() {
+            return Err(CyloError::validation(format!(
+                "Template {} must name a language",
+                name
+            )));
+        }
+        if self.timeout.is_some_and(|timeout| timeout.is_zero()) {
+            return Err(CyloError::validation(format!(
+                "Timeout of template {} must be non-zero",
+                name
+            )));
+        }
+        if self
+            .limits
+            .as_ref()
+            .and_then(|limits| limits.max_memory)
+            .is_some_and(|memory| memory < ExecutionRequest::MIN_MEMORY_BYTES)
+        {
+            return Err(CyloError::validation(format!(
+                "Memory limit of template {} is below {} bytes",
+                name,
+                ExecutionRequest::MIN_MEMORY_BYTES
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Look up a template by name and build a request from it
+pub(crate) fn instantiate(
+    templates: &HashMap<String, RequestTemplate>,
+    name: &str,
+    code: String,
+) -> CyloResult<ExecutionRequest> {
+    templates
+        .get(name)
+        .map(|template| template.instantiate(code))
+        .ok_or_else(|| {
+            CyloError::invalid_request("template", format!("No request template named {}", name))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_inherit_template_settings() {
+        let limits = ResourceLimits {
+            max_memory: Some(512 * 1024 * 1024),
+            max_network_bandwidth: Some(0),
+            ..ResourceLimits::default()
+        };
+        let template = RequestTemplate::new("python")
+            .with_description("pandas over the shared datasets")
+            .with_timeout(Duration::from_secs(120))
+            .with_limits(limits.clone())
+            .with_env("MPLBACKEND", "Agg")
+            .with_readable_path("/srv/datasets")
+            .with_required_isolation(IsolationLevel::Container)
+            .with_tenant("analytics");
+        template.validate("python-data-analysis").unwrap();
+
+        let templates = HashMap::from([("python-data-analysis".to_string(), template)]);
+        let request = instantiate(&templates, "python-data-analysis", "print(1)".into()).unwrap();
+        assert_eq!(request.code, "print(1)");
+        assert_eq!(request.language, "python");
+        assert_eq!(request.timeout, Duration::from_secs(120));
+        assert_eq!(request.limits, limits);
+        assert!(request.timeout_chosen && request.limits_chosen);
+        assert!(!request.network_allowed());
+        assert_eq!(request.env_vars["MPLBACKEND"], "Agg");
+        assert_eq!(request.readable_paths, [PathBuf::from("/srv/datasets")]);
+        assert_eq!(request.required_isolation, Some(IsolationLevel::Container));
+        assert_eq!(request.tenant.as_deref(), Some("analytics"));
+
+        // Unset settings keep the request defaults
+        let bare = RequestTemplate::new("bash").instantiate("true");
+        assert_eq!(bare.timeout, ExecutionRequest::DEFAULT_TIMEOUT);
+        assert_eq!(bare.limits, ResourceLimits::default());
+        assert!(!bare.timeout_chosen && !bare.limits_chosen);
+
+        assert!(instantiate(&templates, "missing", String::new()).is_err());
+        assert!(RequestTemplate::default().validate("empty").is_err());
+        let instant = RequestTemplate::new("bash").with_timeout(Duration::ZERO);
+        assert!(instant.validate("instant").is_err());
+    }
+}
@@ -5,25 +5,45 @@
 //! ============================================================================
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
 
+use serde::{Deserialize, Serialize};
+
+use super::crash_loop::CrashLoopConfig;
+use super::dependencies::DependencyConfig;
+use super::host_guard::HostGuardConfig;
+use super::metrics_store::MetricsStoreConfig;
+use crate::backends::{BlockingPoolStats, language};
+use crate::hardening::HardeningLevel;
+use crate::instance_manager::SelectionStrategy;
+use crate::recovery::RecoveryPolicy;
+
 /// Routing strategy for execution requests
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RoutingStrategy {
     /// Always use the fastest available backend
     Performance,
     /// Prioritize maximum security isolation
     Security,
     /// Balance performance and security
+    #[default]
     Balanced,
     /// Use specific backend if available, fallback to balanced
     PreferBackend(String),
     /// Only use explicitly specified backends
     ExplicitOnly,
+    /// Start on the fastest backend and, if it has not finished after
+    /// `delay`, race a copy on the runner-up; the first success wins
+    Hedged {
+        /// How long the primary runs alone before the hedge starts
+        delay: Duration,
+    },
 }
 
 /// Backend selection preferences and weights
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct BackendPreferences {
     /// Preferred backends in order of preference
     pub preferred_order: Vec<String>,
@@ -33,6 +53,142 @@ pub struct BackendPreferences {
     pub max_concurrent: HashMap<String, u32>,
     /// Backend exclusion list
     pub excluded_backends: Vec<String>,
+    /// Backends routing may choose from; empty allows every backend
+    pub allowed_backends: Vec<String>,
+    /// Overrides applied to requests for a specific language, keyed by
+    /// canonical language name
+    pub language_overrides: HashMap<String, LanguagePreferences>,
+}
+
+/// Preferences that apply only to requests for one language
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguagePreferences {
+    /// Backends this language may use; empty defers to the global allow-list
+    pub allowed_backends: Vec<String>,
+    /// Backends this language must never use
+    pub excluded_backends: Vec<String>,
+    /// Weight multipliers replacing the global ones for this language
+    pub weight_multipliers: HashMap<String, f32>,
+}
+
+impl LanguagePreferences {
+    /// Allow a backend for this language
+    pub fn allow<B: Into<String>>(mut self, backend: B) -> Self {
+        self.allowed_backends.push(backend.into());
+        self
+    }
+
+    /// Never use a backend for this language
+    pub fn deny<B: Into<String>>(mut self, backend: B) -> Self {
+        self.excluded_backends.push(backend.into());
+        self
+    }
+
+    /// Set a backend's weight multiplier for this language
+    pub fn with_weight<B: Into<String>>(mut self, backend: B, weight: f32) -> Self {
+        self.weight_multipliers.insert(backend.into(), weight);
+        self
+    }
+}
+
+impl BackendPreferences {
+    /// Restrict routing to the given backend (may be called repeatedly)
+    pub fn allow<B: Into<String>>(mut self, backend: B) -> Self {
+        self.allowed_backends.push(backend.into());
+        self
+    }
+
+    /// Never route to the given backend
+    pub fn deny<B: Into<String>>(mut self, backend: B) -> Self {
+        self.excluded_backends.push(backend.into());
+        self
+    }
+
+    /// Set a backend's weight multiplier
+    pub fn with_weight<B: Into<String>>(mut self, backend: B, weight: f32) -> Self {
+        self.weight_multipliers.insert(backend.into(), weight);
+        self
+    }
+
+    /// Set the fallback concurrency limit for a backend
+    pub fn with_max_concurrent<B: Into<String>>(mut self, backend: B, limit: u32) -> Self {
+        self.max_concurrent.insert(backend.into(), limit);
+        self
+    }
+
+    /// Set overrides for one language (aliases such as "golang" are accepted)
+    pub fn with_language_override<L: AsRef<str>>(
+        mut self,
+        language: L,
+        overrides: LanguagePreferences,
+    ) -> Self {
+        self.language_overrides
+            .insert(canonical_language(language.as_ref()), overrides);
+        self
+    }
+
+    /// Never route requests for `language` to `backend`
+    ///
+    /// Shorthand for the common "go → never LandLock on this host" case.
+    pub fn deny_for_language<L: AsRef<str>, B: Into<String>>(
+        mut self,
+        language: L,
+        backend: B,
+    ) -> Self {
+        self.language_overrides
+            .entry(canonical_language(language.as_ref()))
+            .or_default()
+            .excluded_backends
+            .push(backend.into());
+        self
+    }
+
+    /// Why a backend may not serve a language, or None if it may
+    pub fn denial_reason(&self, backend: &str, language: &str) -> Option<String> {
+        let listed = |list: &[String]| list.iter().any(|name| name == backend);
+
+        if listed(&self.excluded_backends) {
+            return Some("denied".to_string());
+        }
+        if !self.allowed_backends.is_empty() && !listed(&self.allowed_backends) {
+            return Some("not in allow-list".to_string());
+        }
+        if let Some(overrides) = self.language_override(language) {
+            if listed(&overrides.excluded_backends) {
+                return Some(format!("denied for {}", language));
+            }
+            if !overrides.allowed_backends.is_empty() && !listed(&overrides.allowed_backends) {
+                return Some(format!("not in allow-list for {}", language));
+            }
+        }
+        None
+    }
+
+    /// Whether a backend may serve requests for a language
+    pub fn is_permitted(&self, backend: &str, language: &str) -> bool {
+        self.denial_reason(backend, language).is_none()
+    }
+
+    /// Weight multiplier for a backend, honoring language overrides
+    pub fn weight(&self, backend: &str, language: &str) -> f32 {
+        self.language_override(language)
+            .and_then(|overrides| overrides.weight_multipliers.get(backend))
+            .or_else(|| self.weight_multipliers.get(backend))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    fn language_override(&self, language: &str) -> Option<&LanguagePreferences> {
+        self.language_overrides.get(&canonical_language(language))
+    }
+}
+
+/// Canonical name used to key language overrides and profiles
+pub(crate) fn canonical_language(language: &str) -> String {
+    language::resolve(language)
+        .map(|spec| spec.name.to_string())
+        .unwrap_or_else(|| language.to_lowercase())
 }
 
 impl Default for BackendPreferences {
@@ -41,27 +197,41 @@ impl Default for BackendPreferences {
         weight_multipliers.insert("Apple".to_string(), 1.0);
         weight_multipliers.insert("LandLock".to_string(), 1.0);
         weight_multipliers.insert("FireCracker".to_string(), 1.0);
+        weight_multipliers.insert("Docker".to_string(), 1.0);
+        weight_multipliers.insert("Wasm".to_string(), 1.0);
+        weight_multipliers.insert("K8sJob".to_string(), 1.0);
 
+        // Fallback limits used when a backend config sets no
+        // max_concurrent_executions; VM boots are far heavier than sandboxes
         let mut max_concurrent = HashMap::new();
         max_concurrent.insert("Apple".to_string(), 10);
-        max_concurrent.insert("LandLock".to_string(), 20);
-        max_concurrent.insert("FireCracker".to_string(), 50);
+        max_concurrent.insert("LandLock".to_string(), 32);
+        max_concurrent.insert("FireCracker".to_string(), 2);
+        max_concurrent.insert("Docker".to_string(), 10);
+        max_concurrent.insert("Wasm".to_string(), 32);
+        max_concurrent.insert("K8sJob".to_string(), 10);
 
         Self {
             preferred_order: vec![
                 "FireCracker".to_string(),
                 "LandLock".to_string(),
                 "Apple".to_string(),
+                "Docker".to_string(),
+                "Wasm".to_string(),
+                "K8sJob".to_string(),
             ],
             weight_multipliers,
             max_concurrent,
             excluded_backends: Vec::new(),
+            allowed_backends: Vec::new(),
+            language_overrides: HashMap::new(),
         }
     }
 }
 
 /// Performance optimization configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct OptimizationConfig {
     /// Enable instance reuse for repeated executions
     pub instance_reuse: bool,
@@ -71,8 +241,30 @@ pub struct OptimizationConfig {
     pub max_idle_time: Duration,
     /// Enable load balancing across instances
     pub load_balancing: bool,
+    /// How a registered instance is chosen when several match a request
+    pub instance_selection: SelectionStrategy,
     /// Resource usage monitoring interval
     pub monitoring_interval: Duration,
+    /// Host resource admission thresholds
+    pub host_guard: HostGuardConfig,
+    /// Refusal of code that keeps crashing the same way
+    pub crash_loop: CrashLoopConfig,
+    /// Installation of dependencies declared by request files
+    pub dependencies: DependencyConfig,
+    /// Cleanup of leftovers from crashed runs when the executor is created
+    pub startup_recovery: Option<RecoveryPolicy>,
+    /// File pending scheduled executions are persisted to, in a directory
+    /// private to the user; None keeps them in memory only
+    pub schedule_store: Option<PathBuf>,
+    /// Periodic on-disk snapshots of the execution metrics, reloaded when
+    /// the executor is created; None keeps them in memory only
+    pub metrics_store: Option<MetricsStoreConfig>,
+    /// Confinement of the helper processes cylo starts itself; applies
+    /// process-wide
+    pub helper_hardening: HardeningLevel,
+    /// Total budget of requests that set none, provisioning and queueing
+    /// included; None leaves them bounded only by their timeout
+    pub total_timeout: Option<Duration>,
 }
 
 impl Default for OptimizationConfig {
@@ -82,7 +274,18 @@ impl Default for OptimizationConfig {
             instance_pool_size: 5,
             max_idle_time: Duration::from_secs(300),
             load_balancing: true,
+            instance_selection: SelectionStrategy::default(),
             monitoring_interval: Duration::from_secs(60),
+            host_guard: HostGuardConfig::default(),
+            crash_loop: CrashLoopConfig::default(),
+            dependencies: DependencyConfig::default(),
+            startup_recovery: Some(RecoveryPolicy::default()),
+            schedule_store: Some(
+                crate::platform_utils::user_state_dir().join("schedule").join("jobs.json"),
+            ),
+            metrics_store: None,
+            helper_hardening: HardeningLevel::default(),
+            total_timeout: None,
         }
     }
 }
@@ -101,7 +304,8 @@ pub struct PlatformCache {
 }
 
 /// Execution metrics and performance statistics
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ExecutionMetrics {
     /// Total executions per backend
     pub executions_per_backend: HashMap<String, u64>,
@@ -111,12 +315,51 @@ pub struct ExecutionMetrics {
     pub success_rate: HashMap<String, f32>,
     /// Resource usage statistics
     pub resource_usage: HashMap<String, ResourceStats>,
+    /// Hedged executions that started a second copy
+    pub hedges_launched: u64,
+    /// Hedged executions won by the second copy, per winning backend
+    pub hedge_wins: HashMap<String, u64>,
+    /// Total attributed cost per backend
+    pub cost_per_backend: HashMap<String, f64>,
+    /// Aggregated usage and cost per tenant
+    pub tenant_usage: HashMap<String, TenantUsage>,
+    /// Background work in flight, sampled when the metrics are read
+    #[serde(skip)]
+    pub tasks: TaskStats,
     /// Last update timestamp
     pub last_updated: Option<SystemTime>,
 }
 
+/// Background work the executor and its backends have in flight
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskStats {
+    /// Executions in flight
+    pub active_executions: usize,
+    /// Processes the shared resource sampler is watching
+    pub sampled_processes: usize,
+    /// Counters of the pool backends run blocking IO on
+    pub blocking_io: BlockingPoolStats,
+    /// Tasks alive on the Tokio runtime the metrics were read from
+    pub runtime_tasks: Option<usize>,
+}
+
+/// Usage and cost aggregated for one tenant
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TenantUsage {
+    /// Executions attributed to the tenant
+    pub executions: u64,
+    /// Total CPU-seconds consumed
+    pub cpu_seconds: f64,
+    /// Total GiB-seconds of reserved memory
+    pub gb_seconds: f64,
+    /// Total microVM boots
+    pub vm_boots: u64,
+    /// Total attributed cost
+    pub amount: f64,
+}
+
 /// Resource usage statistics for a backend
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ResourceStats {
     /// Average memory usage in bytes
     pub avg_memory: u64,
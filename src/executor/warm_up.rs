@@ -0,0 +1,243 @@
+//! ============================================================================
+//! File: packages/cylo/src/executor/warm_up.rs
+//! ----------------------------------------------------------------------------
+//! Backend warm-up and readiness reporting.
+//!
+//! Warming up runs a hello-world program per language on every requested
+//! backend, through the same routing and execution path as user requests.
+//! That pulls images, boots and pools VMs, and fills compiler caches ahead
+//! of time, so a service can hold traffic back until its sandboxes are
+//! ready instead of charging the cold start to its first user.
+//! ============================================================================
+
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+
+use crate::backends::{ExecutionRequest, ExecutionResult, language};
+use crate::execution_env::CyloResult;
+
+/// Tenant warm-up executions are attributed to
+pub const WARM_UP_TENANT: &str = "cylo-warm-up";
+
+/// Smallest program that exercises a language's whole toolchain
+///
+/// # Returns
+/// None for languages cylo does not know
+pub fn hello_world(language: &str) -> Option<&'static str> {
+    let program = match language::resolve(language)?.name {
+        "python" => "print('ready')",
+        "javascript" => "console.log('ready');",
+        "rust" => "fn main() { println!(\"ready\"); }",
+        "bash" => "echo ready",
+        "go" => "package main\n\nimport \"fmt\"\n\nfunc main() { fmt.Println(\"ready\") }\n",
+        "r" => "cat('ready\\n')",
+        "sql" => "SELECT 'ready';",
+        "zig" => {
+            "const std = @import(\"std\");\n\n\
+             pub fn main() void {\n    std.debug.print(\"ready\\n\", .{});\n}\n"
+        }
+        "csharp" => "Console.WriteLine(\"ready\");",
+        "lua" => "print('ready')",
+        "assemblyscript" => "console.log(\"ready\");",
+        "wasm" => "(module (func (export \"_start\")))",
+        _ => return None,
+    };
+    Some(program)
+}
+
+/// Outcome of warming up one language on one backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageReadiness {
+    /// Language, as requested
+    pub language: String,
+    /// Whether the hello-world program ran successfully
+    pub ready: bool,
+    /// How long the run took, provisioning included
+    pub duration: Duration,
+    /// Why the language is not ready
+    pub error: Option<String>,
+}
+
+/// Outcome of warming up one backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackendReadiness {
+    /// Backend name
+    pub backend: String,
+    /// Outcome per language, in the requested order
+    pub languages: Vec<LanguageReadiness>,
+}
+
+impl BackendReadiness {
+    /// Whether every language is ready on the backend
+    pub fn is_ready(&self) -> bool {
+        self.languages.iter().all(|language| language.ready)
+    }
+}
+
+/// Result of `CyloExecutor::warm_up`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    /// Outcome per backend, in the requested order
+    pub backends: Vec<BackendReadiness>,
+    /// When the warm-up finished
+    pub completed_at: SystemTime,
+}
+
+impl ReadinessReport {
+    /// Whether every language is ready on every backend
+    ///
+    /// A warm-up of no backends is not ready: nothing could serve traffic.
+    pub fn is_ready(&self) -> bool {
+        !self.backends.is_empty() && self.backends.iter().all(BackendReadiness::is_ready)
+    }
+
+    /// Backends on which every language is ready
+    pub fn ready_backends(&self) -> Vec<&str> {
+        self.backends
+            .iter()
+            .filter(|backend| backend.is_ready())
+            .map(|backend| backend.backend.as_str())
+            .collect()
+    }
+
+    /// Every (backend, language, error) that is not ready
+    pub fn failures(&self) -> Vec<(&str, &str, &str)> {
+        self.backends
+            .iter()
+            .flat_map(|backend| {
+                backend.languages.iter().filter(|language| !language.ready).map(|language| {
+                    (
+                        backend.backend.as_str(),
+                        language.language.as_str(),
+                        language.error.as_deref().unwrap_or("not ready"),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Warm up backends in parallel, languages one after another per backend
+///
+/// Languages share a backend's instance, so running them in turn lets the
+/// first one pay for provisioning and the rest reuse it.
+///
+/// # Arguments
+/// * `backends` - Backend names
+/// * `languages` - Language names
+/// * `run` - Routes and executes one request
+pub(super) async fn warm_up<F, Fut>(
+    backends: Vec<String>,
+    languages: Vec<String>,
+    run: F,
+) -> ReadinessReport
+where
+    F: Fn(ExecutionRequest) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = CyloResult<ExecutionResult>> + Send + 'static,
+{
+    let mut running = JoinSet::new();
+    for (index, backend) in backends.into_iter().enumerate() {
+        let languages = languages.clone();
+        let run = run.clone();
+        running.spawn(async move {
+            let mut readiness = Vec::with_capacity(languages.len());
+            for language in languages {
+                readiness.push(warm_up_language(&backend, language, &run).await);
+            }
+            (
+                index,
+                BackendReadiness {
+                    backend,
+                    languages: readiness,
+                },
+            )
+        });
+    }
+
+    let mut backends = Vec::new();
+    while let Some(joined) = running.join_next().await {
+        if let Ok(backend) = joined {
+            backends.push(backend);
+        }
+    }
+    backends.sort_by_key(|(index, _)| *index);
+    ReadinessReport {
+        backends: backends.into_iter().map(|(_, backend)| backend).collect(),
+        completed_at: SystemTime::now(),
+    }
+}
+
+async fn warm_up_language<F, Fut>(backend: &str, language: String, run: &F) -> LanguageReadiness
+where
+    F: Fn(ExecutionRequest) -> Fut,
+    Fut: Future<Output = CyloResult<ExecutionResult>>,
+{
+    let started = Instant::now();
+    let Some(program) = hello_world(&language) else {
+        return LanguageReadiness {
+            error: Some(format!("no hello-world program for '{language}'")),
+            language,
+            ready: false,
+            duration: Duration::ZERO,
+        };
+    };
+
+    let request = ExecutionRequest::new(program, language.as_str())
+        .with_backend(backend)
+        .with_tenant(WARM_UP_TENANT);
+    let error = match run(request).await {
+        Ok(result) if result.is_success() => None,
+        Ok(result) => Some(format!(
+            "hello world exited with {}: {}",
+            result.exit_code,
+            result.stderr.trim()
+        )),
+        Err(e) => Some(e.to_string()),
+    };
+    LanguageReadiness {
+        language,
+        ready: error.is_none(),
+        duration: started.elapsed(),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_env::CyloError;
+
+    #[tokio::test]
+    async fn report_gates_on_every_backend_and_language() {
+        assert!(
+            language::LANGUAGES
+                .iter()
+                .all(|spec| hello_world(spec.name).is_some())
+        );
+
+        let run = |request: ExecutionRequest| async move {
+            match (request.required_backend.as_deref(), request.language.as_str()) {
+                (Some("Apple"), _) => Err(CyloError::internal("image pull failed")),
+                (_, "rust") => Ok(ExecutionResult::failure(1, "rustc missing")),
+                _ => Ok(ExecutionResult::success("ready")),
+            }
+        };
+        let backends = vec!["LandLock".to_string(), "Apple".to_string()];
+
+        let report = warm_up(backends.clone(), vec!["python".to_string()], run).await;
+        assert_eq!(report.ready_backends(), vec!["LandLock"]);
+        assert!(!report.is_ready());
+        assert_eq!(report.failures().len(), 1);
+
+        let languages = vec!["python".to_string(), "rust".to_string(), "cobol".to_string()];
+        let report = warm_up(backends[..1].to_vec(), languages, run).await;
+        let failures = report.failures();
+        assert_eq!(failures.len(), 2);
+        assert!(failures[1].2.contains("no hello-world program"));
+
+        assert!(!warm_up(Vec::new(), Vec::new(), run).await.is_ready());
+    }
+}
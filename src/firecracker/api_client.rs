@@ -7,8 +7,10 @@ use bytes::Bytes;
 use http::{Method, Request, StatusCode};
 use http_body_util::{BodyExt, Full};
 use hyper_client_sockets::{Backend, tokio::TokioBackend};
-use log::error;
 use serde::Serialize;
+use tracing::error;
+
+use crate::logging::targets;
 
 use super::api_types::FirecrackerError;
 
@@ -30,7 +32,7 @@ pub async fn api_put<T: Serialize>(socket_path: &Path, path: &str, body: &T) ->
     // Spawn connection handler
     tokio::spawn(async move {
         if let Err(e) = conn.await {
-            error!("Firecracker API connection error: {}", e);
+            error!(target: targets::FIRECRACKER, "Firecracker API connection error: {}", e);
         }
     });
 
@@ -4,10 +4,12 @@ use std::path::PathBuf;
 use std::process::Command;
 
 use anyhow::Result;
-use log::{error, info};
+use tracing::{error, info};
 
 use crate::config::RamdiskConfig;
 use crate::error::StorageError;
+use crate::ids;
+use crate::logging::targets;
 
 // Internal modules
 mod api_client;
@@ -39,16 +41,16 @@ pub async fn create_firecracker_environment(
         ssh_config: None,
     };
 
-    let vm_id = format!("cylo-{}", uuid::Uuid::new_v4());
+    let vm_id = ids::generate("ramdisk");
     let mut vm = FirecrackerVM::new(fc_config, vm_id);
 
     match vm.start().await {
         Ok(_) => {
-            info!("Firecracker VM started successfully");
+            info!(target: targets::FIRECRACKER, "Firecracker VM started successfully");
             Ok(vm)
         }
         Err(e) => {
-            error!("Failed to start Firecracker VM: {}", e);
+            error!(target: targets::FIRECRACKER, "Failed to start Firecracker VM: {}", e);
             Err(StorageError::Other(e))
         }
     }
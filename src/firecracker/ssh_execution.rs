@@ -6,8 +6,10 @@ use std::net::TcpStream;
 use std::path::Path;
 
 use anyhow::{Context, Result};
-use log::info;
 use ssh2::Session;
+use tracing::info;
+
+use crate::logging::targets;
 
 use super::config::{SshAuth, SshConfig};
 
@@ -18,7 +20,7 @@ pub fn execute_code(
     language: &str,
     code: &str,
 ) -> Result<String> {
-    info!("Executing {} code in Firecracker VM", language);
+    info!(target: targets::FIRECRACKER, "Executing {} code in Firecracker VM", language);
 
     // Create a temporary file with the code
     let tmp_dir = "/tmp";
@@ -48,7 +50,7 @@ pub fn execute_code(
 
 /// Copy a file to the VM using SSH/SCP
 pub fn copy_to_vm(ssh_config: &SshConfig, host_path: &str, guest_path: &str) -> Result<()> {
-    info!("Copying {} to VM at {}", host_path, guest_path);
+    info!(target: targets::FIRECRACKER, "Copying {} to VM at {}", host_path, guest_path);
 
     let session = create_ssh_session(ssh_config)?;
 
@@ -75,13 +77,13 @@ pub fn copy_to_vm(ssh_config: &SshConfig, host_path: &str, guest_path: &str) ->
         .wait_close()
         .context("Failed to wait for close")?;
 
-    info!("Successfully copied file to VM");
+    info!(target: targets::FIRECRACKER, "Successfully copied file to VM");
     Ok(())
 }
 
 /// Execute a command in the VM via SSH
 pub fn execute_command(ssh_config: &SshConfig, command: &str) -> Result<String> {
-    info!("Executing command in VM: {}", command);
+    info!(target: targets::FIRECRACKER, "Executing command in VM: {}", command);
 
     let session = create_ssh_session(ssh_config)?;
 
@@ -115,7 +117,7 @@ pub fn execute_command(ssh_config: &SshConfig, command: &str) -> Result<String>
         ));
     }
 
-    info!("Command executed successfully");
+    info!(target: targets::FIRECRACKER, "Command executed successfully");
     Ok(output)
 }
 
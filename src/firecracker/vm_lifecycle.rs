@@ -6,9 +6,10 @@ use std::process::{Command, Stdio};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use log::{info, warn};
+use tracing::{info, warn};
 
 use crate::error::StorageError;
+use crate::logging::targets;
 
 use super::api_client;
 use super::api_types::{BootSource, Drive, InstanceActionInfo, MachineConfiguration, NetworkInterface};
@@ -39,7 +40,7 @@ impl FirecrackerVM {
 
     /// Start the Firecracker VM
     pub async fn start(&mut self) -> Result<()> {
-        info!("Starting Firecracker VM with ID: {}", self.vm_id);
+        info!(target: targets::FIRECRACKER, "Starting Firecracker VM with ID: {}", self.vm_id);
 
         // Check if Firecracker binary exists
         if !self.config.binary_path.exists() {
@@ -82,13 +83,13 @@ impl FirecrackerVM {
         // Configure the VM (now async)
         self.configure_vm().await?;
 
-        info!("Firecracker VM started successfully");
+        info!(target: targets::FIRECRACKER, "Firecracker VM started successfully");
         Ok(())
     }
 
     /// Configure the VM using the Firecracker API
     async fn configure_vm(&self) -> Result<()> {
-        info!("Configuring Firecracker VM via API");
+        info!(target: targets::FIRECRACKER, "Configuring Firecracker VM via API");
 
         let api_socket = self
             .api_socket
@@ -106,7 +107,7 @@ impl FirecrackerVM {
             .await
             .context("Failed to configure boot source")?;
 
-        info!("Boot source configured");
+        info!(target: targets::FIRECRACKER, "Boot source configured");
 
         // Configure machine config (vCPU and memory)
         let machine_config = MachineConfiguration {
@@ -120,6 +121,7 @@ impl FirecrackerVM {
             .context("Failed to configure machine")?;
 
         info!(
+            target: targets::FIRECRACKER,
             "Machine config set: {} vCPUs, {} MiB memory",
             self.config.vcpu_count, self.config.mem_size_mib
         );
@@ -136,7 +138,7 @@ impl FirecrackerVM {
             .await
             .context("Failed to configure root filesystem")?;
 
-        info!("Root filesystem configured");
+        info!(target: targets::FIRECRACKER, "Root filesystem configured");
 
         // Configure network if provided
         if let Some(net_config) = &self.config.network_config {
@@ -150,7 +152,7 @@ impl FirecrackerVM {
                 .await
                 .context("Failed to configure network interface")?;
 
-            info!("Network interface configured");
+            info!(target: targets::FIRECRACKER, "Network interface configured");
         }
 
         // Start the VM instance
@@ -162,14 +164,14 @@ impl FirecrackerVM {
             .await
             .context("Failed to start VM instance")?;
 
-        info!("VM instance started successfully");
+        info!(target: targets::FIRECRACKER, "VM instance started successfully");
 
         Ok(())
     }
 
     /// Stop the Firecracker VM
     pub async fn stop(&self) -> Result<(), StorageError> {
-        info!("Stopping Firecracker VM with ID: {}", self.vm_id);
+        info!(target: targets::FIRECRACKER, "Stopping Firecracker VM with ID: {}", self.vm_id);
 
         if let Some(socket) = &self.api_socket {
             // Send shutdown request
@@ -178,7 +180,7 @@ impl FirecrackerVM {
             };
 
             if let Err(e) = api_client::api_put(socket, "actions", &shutdown_action).await {
-                warn!("Failed to send shutdown request: {}", e);
+                warn!(target: targets::FIRECRACKER, "Failed to send shutdown request: {}", e);
                 // Continue with cleanup even if shutdown request fails
             }
 
@@ -189,12 +191,12 @@ impl FirecrackerVM {
             if socket.exists()
                 && let Err(e) = fs::remove_file(socket)
             {
-                warn!("Failed to remove socket file: {}", e);
+                warn!(target: targets::FIRECRACKER, "Failed to remove socket file: {}", e);
                 // Continue anyway
             }
         }
 
-        info!("Firecracker VM stopped successfully");
+        info!(target: targets::FIRECRACKER, "Firecracker VM stopped successfully");
         Ok(())
     }
 
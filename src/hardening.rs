@@ -0,0 +1,541 @@
+// ============================================================================
+// File: packages/cylo/src/hardening.rs
+// ----------------------------------------------------------------------------
+// Confinement of the helper programs cylo itself runs.
+//
+// Besides executed code, cylo starts host tools on its own behalf: tar and
+// mkfs.ext4 for images and VM shares, container runtime CLIs and kubectl
+// for running and cleaning up containers, git (and the ssh it starts) for
+// checkouts, and diskpart, imdisk and PowerShell for Windows ramdisks (SSH
+// sessions into VMs run in-process and start no helper). They run with
+// cylo's privileges, so a compromised or exploited helper binary
+// could reach far beyond its task. Helpers are started through
+// `HelperCommand`, which confines them according to the process-wide
+// `HardeningLevel`:
+//
+// - Linux: a seccomp filter refusing syscalls no helper needs (ptrace,
+//   module and kexec loading, bpf, keyrings, reboot, clock changes); at
+//   `Strict`, file tools and git are also refused mounts and namespace
+//   changes.
+// - Windows: a kill-on-close Job Object cut off from other processes'
+//   windows and the clipboard; at `Strict` helpers other than disk tools,
+//   which format volumes through a child, and git may not start processes.
+// - macOS offers no per-process syscall filter for third-party binaries,
+//   so helpers run unconfined there.
+// ============================================================================
+
+use std::future::Future;
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// How tightly cylo's helper processes are confined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HardeningLevel {
+    /// Helpers run unconfined
+    Off,
+    /// File tools and git are filtered on Linux and every helper runs in a
+    /// restricted job on Windows
+    #[default]
+    Standard,
+    /// As `Standard`, plus container and disk tools on Linux, no mounts or
+    /// namespace changes for file tools, and no child processes for file
+    /// and container tools on Windows
+    Strict,
+}
+
+impl FromStr for HardeningLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "standard" => Ok(Self::Standard),
+            "strict" => Ok(Self::Strict),
+            other => Err(format!(
+                "unknown hardening level '{other}'; use off, standard or strict"
+            )),
+        }
+    }
+}
+
+/// Process-wide level, stored as the enum's discriminant
+static LEVEL: AtomicU8 = AtomicU8::new(HardeningLevel::Standard as u8);
+
+/// Set how helper processes started from now on are confined
+///
+/// Executors apply `OptimizationConfig::helper_hardening` here whenever
+/// they are created or reconfigured.
+pub fn set_helper_hardening(level: HardeningLevel) {
+    LEVEL.store(level as u8, Ordering::SeqCst);
+}
+
+/// How helper processes are currently confined
+pub fn helper_hardening() -> HardeningLevel {
+    match LEVEL.load(Ordering::SeqCst) {
+        0 => HardeningLevel::Off,
+        1 => HardeningLevel::Standard,
+        _ => HardeningLevel::Strict,
+    }
+}
+
+/// Kind of helper, deciding what it is allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Helper {
+    /// Reads and writes files only: tar, mkfs.ext4
+    FileTool,
+    /// Container runtime CLI or kubectl; rootless runtimes start setuid ID mappers
+    /// and enter namespaces, so it is only filtered at `Strict`
+    ContainerCli,
+    /// Disk and volume management, run with administrator rights
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    DiskTool,
+    /// git, which fetches over the network, and the ssh and remote helpers
+    /// it starts, which inherit its confinement
+    NetworkClient,
+}
+
+impl Helper {
+    /// Whether `level` confines this kind of helper
+    fn confined_at(self, level: HardeningLevel) -> bool {
+        match level {
+            HardeningLevel::Off => false,
+            HardeningLevel::Standard => {
+                matches!(self, Self::FileTool | Self::NetworkClient) || cfg!(target_os = "windows")
+            }
+            HardeningLevel::Strict => true,
+        }
+    }
+}
+
+/// Run a helper under the current `HardeningLevel`
+///
+/// Implemented for `std::process::Command`, whose runs complete in place,
+/// and `tokio::process::Command`, whose runs are futures.
+pub(crate) trait HelperCommand {
+    /// Exit status of a run, or a future of it
+    type Status<'a>
+    where
+        Self: 'a;
+    /// Captured output of a run, or a future of it
+    type Captured<'a>
+    where
+        Self: 'a;
+    /// Child process type the command spawns
+    type Child;
+
+    /// Like `Command::status`, confined as `helper`
+    fn helper_status(&mut self, helper: Helper) -> Self::Status<'_>;
+
+    /// Like `Command::output`, confined as `helper`; stdin is closed and
+    /// stdout and stderr are captured whatever the command set
+    fn helper_output(&mut self, helper: Helper) -> Self::Captured<'_>;
+
+    /// Like `Command::spawn`, confined as `helper` for as long as the
+    /// returned child is kept
+    fn helper_spawn(&mut self, helper: Helper) -> io::Result<HelperChild<Self::Child>>;
+}
+
+/// Helper started by `HelperCommand::helper_spawn`
+///
+/// Dereferences to the child process. On Windows it also holds the
+/// helper's job, which kills whatever is left of the helper when dropped.
+#[derive(Debug)]
+pub(crate) struct HelperChild<C> {
+    child: C,
+    #[cfg(target_os = "windows")]
+    _job: Option<crate::backends::windows::JobManager>,
+}
+
+impl<C> Deref for HelperChild<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.child
+    }
+}
+
+impl<C> DerefMut for HelperChild<C> {
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.child
+    }
+}
+
+impl HelperChild<Child> {
+    /// Like `Child::wait_with_output`, keeping the helper confined until
+    /// it exits
+    pub(crate) fn wait_with_output(self) -> io::Result<Output> {
+        self.child.wait_with_output()
+    }
+}
+
+impl HelperChild<tokio::process::Child> {
+    /// Like `tokio::process::Child::wait_with_output`, keeping the helper
+    /// confined until it exits
+    pub(crate) async fn wait_with_output(self) -> io::Result<Output> {
+        self.child.wait_with_output().await
+    }
+}
+
+/// Hook run in a helper's child process before it executes
+type PreExecHook = Box<dyn FnMut() -> io::Result<()> + Send + Sync>;
+
+/// Confine a command before it is spawned, returning whether its child
+/// must also be put in a job once started
+fn confine_before_spawn(
+    helper: Helper,
+    pre_exec: impl FnOnce(PreExecHook),
+) -> bool {
+    let level = helper_hardening();
+    if !helper.confined_at(level) {
+        return false;
+    }
+    #[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    if let Some(hook) = seccomp::hook(helper, level) {
+        pre_exec(hook);
+    }
+    #[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+    let _ = pre_exec;
+    true
+}
+
+/// Wrap a started helper, putting it in a restricted job on Windows
+#[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+fn started<C>(child: C, pid: Option<u32>, helper: Helper, confined: bool) -> HelperChild<C> {
+    HelperChild {
+        child,
+        #[cfg(target_os = "windows")]
+        _job: pid
+            .filter(|_| confined)
+            .and_then(|pid| windows_job::confine(pid, helper, helper_hardening())),
+    }
+}
+
+impl HelperCommand for Command {
+    type Status<'a> = io::Result<ExitStatus>;
+    type Captured<'a> = io::Result<Output>;
+    type Child = Child;
+
+    fn helper_status(&mut self, helper: Helper) -> io::Result<ExitStatus> {
+        self.helper_spawn(helper)?.wait()
+    }
+
+    fn helper_output(&mut self, helper: Helper) -> io::Result<Output> {
+        self.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        self.helper_spawn(helper)?.wait_with_output()
+    }
+
+    fn helper_spawn(&mut self, helper: Helper) -> io::Result<HelperChild<Child>> {
+        let confined = confine_before_spawn(helper, |hook| {
+            #[cfg(unix)]
+            {
+                use std::os::unix::process::CommandExt;
+                // SAFETY: the hook only calls prctl(2), which is
+                // async-signal-safe, on a program built before the fork
+                unsafe {
+                    self.pre_exec(hook);
+                }
+            }
+            #[cfg(not(unix))]
+            let _ = hook;
+        });
+        let child = self.spawn()?;
+        let pid = child.id();
+        Ok(started(child, Some(pid), helper, confined))
+    }
+}
+
+impl HelperCommand for tokio::process::Command {
+    type Status<'a> = Pin<Box<dyn Future<Output = io::Result<ExitStatus>> + Send + 'a>>;
+    type Captured<'a> = Pin<Box<dyn Future<Output = io::Result<Output>> + Send + 'a>>;
+    type Child = tokio::process::Child;
+
+    fn helper_status(&mut self, helper: Helper) -> Self::Status<'_> {
+        Box::pin(async move { self.helper_spawn(helper)?.wait().await })
+    }
+
+    fn helper_output(&mut self, helper: Helper) -> Self::Captured<'_> {
+        self.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        Box::pin(async move {
+            self.helper_spawn(helper)?.wait_with_output().await
+        })
+    }
+
+    fn helper_spawn(&mut self, helper: Helper) -> io::Result<HelperChild<tokio::process::Child>> {
+        let confined = confine_before_spawn(helper, |hook| {
+            // SAFETY: as for `std::process::Command`
+            #[cfg(unix)]
+            unsafe {
+                self.pre_exec(hook);
+            }
+            #[cfg(not(unix))]
+            let _ = hook;
+        });
+        let child = self.spawn()?;
+        let pid = child.id();
+        Ok(started(child, pid, helper, confined))
+    }
+}
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod seccomp {
+    use std::io;
+    use std::sync::OnceLock;
+
+    use tracing::warn;
+
+    use super::{HardeningLevel, Helper, PreExecHook};
+    use crate::logging::targets;
+
+    /// Syscalls no helper has a reason to make
+    const DENIED: &[libc::c_long] = &[
+        libc::SYS_ptrace,
+        libc::SYS_process_vm_readv,
+        libc::SYS_process_vm_writev,
+        libc::SYS_kexec_load,
+        libc::SYS_kexec_file_load,
+        libc::SYS_init_module,
+        libc::SYS_finit_module,
+        libc::SYS_delete_module,
+        libc::SYS_bpf,
+        libc::SYS_perf_event_open,
+        libc::SYS_userfaultfd,
+        libc::SYS_keyctl,
+        libc::SYS_add_key,
+        libc::SYS_request_key,
+        libc::SYS_reboot,
+        libc::SYS_swapon,
+        libc::SYS_swapoff,
+        libc::SYS_acct,
+        libc::SYS_open_by_handle_at,
+        libc::SYS_settimeofday,
+        libc::SYS_clock_settime,
+        libc::SYS_clock_adjtime,
+        libc::SYS_adjtimex,
+        #[cfg(target_arch = "x86_64")]
+        libc::SYS_iopl,
+        #[cfg(target_arch = "x86_64")]
+        libc::SYS_ioperm,
+    ];
+
+    /// Syscalls file tools and network clients are also refused at `Strict`
+    const DENIED_STRICT: &[libc::c_long] = &[
+        libc::SYS_mount,
+        libc::SYS_umount2,
+        libc::SYS_pivot_root,
+        libc::SYS_chroot,
+        libc::SYS_unshare,
+        libc::SYS_setns,
+    ];
+
+    const BPF_LD_W_ABS: u16 = 0x20;
+    const BPF_JEQ_K: u16 = 0x15;
+    #[cfg(target_arch = "x86_64")]
+    const BPF_JGE_K: u16 = 0x35;
+    const BPF_RET_K: u16 = 0x06;
+
+    const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+    const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+
+    /// Offsets of the syscall number and audit architecture in
+    /// `struct seccomp_data`
+    const NR_OFFSET: u32 = 0;
+    const ARCH_OFFSET: u32 = 4;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xc000_003e;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+    /// Syscall numbers at and above this use the x32 ABI, which would
+    /// otherwise slip past the filter
+    #[cfg(target_arch = "x86_64")]
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+    /// Pre-exec hook loading the helper's filter in the child just before
+    /// it executes, if the kernel supports filters
+    ///
+    /// Filtering also sets no_new_privs, so the helper cannot regain
+    /// privileges through setuid binaries. The hook only calls prctl(2),
+    /// which is async-signal-safe, on a program built before the fork.
+    pub(super) fn hook(helper: Helper, level: HardeningLevel) -> Option<PreExecHook> {
+        if !available() {
+            return None;
+        }
+        let mut denied = DENIED.to_vec();
+        if matches!(helper, Helper::FileTool | Helper::NetworkClient)
+            && level == HardeningLevel::Strict
+        {
+            denied.extend_from_slice(DENIED_STRICT);
+        }
+        let program = filter(&denied);
+
+        Some(Box::new(move || {
+            let prog = libc::sock_fprog {
+                len: program.len() as u16,
+                filter: program.as_ptr().cast_mut(),
+            };
+            // SAFETY: prctl(2) reads `prog`, which outlives the call
+            let failed = unsafe {
+                libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1 as libc::c_ulong, 0, 0, 0) != 0
+                    || libc::prctl(
+                        libc::PR_SET_SECCOMP,
+                        libc::SECCOMP_MODE_FILTER as libc::c_ulong,
+                        &prog as *const libc::sock_fprog,
+                    ) != 0
+            };
+            if failed {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }))
+    }
+
+    /// BPF program failing `denied` syscalls and foreign-ABI syscalls with
+    /// EPERM and allowing everything else
+    pub(super) fn filter(denied: &[libc::c_long]) -> Vec<libc::sock_filter> {
+        let deny = SECCOMP_RET_ERRNO | libc::EPERM as u32;
+        let mut program = vec![
+            stmt(BPF_LD_W_ABS, ARCH_OFFSET),
+            jump(BPF_JEQ_K, AUDIT_ARCH, 1, 0),
+            stmt(BPF_RET_K, deny),
+            stmt(BPF_LD_W_ABS, NR_OFFSET),
+        ];
+        #[cfg(target_arch = "x86_64")]
+        program.extend([jump(BPF_JGE_K, X32_SYSCALL_BIT, 0, 1), stmt(BPF_RET_K, deny)]);
+        for nr in denied {
+            program.push(jump(BPF_JEQ_K, *nr as u32, 0, 1));
+            program.push(stmt(BPF_RET_K, deny));
+        }
+        program.push(stmt(BPF_RET_K, SECCOMP_RET_ALLOW));
+        program
+    }
+
+    fn stmt(code: u16, k: u32) -> libc::sock_filter {
+        jump(code, k, 0, 0)
+    }
+
+    fn jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter { code, jt, jf, k }
+    }
+
+    /// Whether the kernel supports seccomp filters; probed once per process
+    fn available() -> bool {
+        static AVAILABLE: OnceLock<bool> = OnceLock::new();
+        *AVAILABLE.get_or_init(|| {
+            // SAFETY: PR_GET_SECCOMP only reads the calling thread's mode
+            let available = unsafe { libc::prctl(libc::PR_GET_SECCOMP) } >= 0;
+            if !available {
+                warn!(
+                    target: targets::HARDENING,
+                    "Kernel lacks seccomp; cylo's helper processes run unfiltered"
+                );
+            }
+            available
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_job {
+    use tracing::warn;
+
+    use super::{HardeningLevel, Helper};
+    use crate::backends::windows::{JobManager, WindowsLimits};
+    use crate::logging::targets;
+
+    /// Put a freshly started helper in a restricted job, kept alive until
+    /// the helper is waited for; dropping it kills whatever is left
+    pub(super) fn confine(pid: u32, helper: Helper, level: HardeningLevel) -> Option<JobManager> {
+        // Disk tools format through a child and git starts ssh and its
+        // remote helpers
+        let single = level == HardeningLevel::Strict
+            && helper != Helper::DiskTool
+            && helper != Helper::NetworkClient;
+        let limits = WindowsLimits {
+            memory_bytes: None,
+            cpu_time_ms: None,
+            max_processes: single.then_some(1),
+            affinity_mask: None,
+            io_bandwidth: None,
+        };
+        let confined = JobManager::create_with_limits(&limits).and_then(|job| {
+            job.restrict_ui()?;
+            job.assign_process(pid)?;
+            Ok(job)
+        });
+        confined
+            .inspect_err(|e| {
+                warn!(target: targets::HARDENING, "Helper {} runs unconfined: {}", pid, e)
+            })
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_decide_which_helpers_are_confined() {
+        assert_eq!("STRICT".parse::<HardeningLevel>(), Ok(HardeningLevel::Strict));
+        assert!("paranoid".parse::<HardeningLevel>().is_err());
+        assert_eq!(serde_json::to_string(&HardeningLevel::Off).unwrap(), "\"off\"");
+
+        assert!(!Helper::FileTool.confined_at(HardeningLevel::Off));
+        assert!(Helper::FileTool.confined_at(HardeningLevel::Standard));
+        assert!(Helper::NetworkClient.confined_at(HardeningLevel::Standard));
+        assert!(Helper::ContainerCli.confined_at(HardeningLevel::Strict));
+        assert_eq!(
+            Helper::ContainerCli.confined_at(HardeningLevel::Standard),
+            cfg!(target_os = "windows")
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn async_helpers_capture_output() {
+        let output = tokio::process::Command::new("echo")
+            .arg("fetched")
+            .helper_output(Helper::NetworkClient)
+            .await
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"fetched\n");
+    }
+
+    #[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    #[test]
+    fn strict_file_tools_cannot_enter_namespaces() {
+        use std::process::Stdio;
+
+        let program = seccomp::filter(&[libc::SYS_ptrace]);
+        assert_eq!(program.last().map(|insn| insn.k), Some(0x7fff_0000));
+
+        let run = |cmd: &mut Command| {
+            use std::os::unix::process::CommandExt;
+
+            if let Some(hook) = seccomp::hook(Helper::FileTool, HardeningLevel::Strict) {
+                // SAFETY: the hook only calls prctl(2)
+                unsafe {
+                    cmd.pre_exec(hook);
+                }
+            }
+            cmd.stdout(Stdio::null()).stderr(Stdio::null()).status()
+        };
+        assert!(run(&mut Command::new("true")).unwrap().success());
+        if let Ok(status) = run(Command::new("unshare").args(["--user", "true"])) {
+            assert!(!status.success());
+        }
+    }
+}
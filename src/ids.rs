@@ -0,0 +1,285 @@
+// ============================================================================
+// File: packages/cylo/src/ids.rs
+// ----------------------------------------------------------------------------
+// Generated names and validation of caller-supplied ones.
+//
+// Names end up as instance keys, container names, FireCracker VM ids, jail
+// and workspace directories, and Windows object names, each with its own
+// character and length rules. Every name cylo makes up is generated here:
+// a `cylo` prefix, a short readable tag such as the language, and 48
+// random bits (`cylo-py-7f3a2b9c01de`), using only lowercase ASCII letters,
+// digits and hyphens so the same name is valid wherever it is used. Names
+// supplied by callers are checked against the rules of the place they are
+// used in.
+// ============================================================================
+
+use std::fmt;
+
+use crate::backends::language;
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Prefix of every name cylo generates
+pub const ID_PREFIX: &str = "cylo";
+
+/// Hex digits of randomness in a generated name (48 bits)
+const SUFFIX_LEN: usize = 12;
+
+/// Longest tag kept in a generated name
+const MAX_TAG_LEN: usize = 12;
+
+/// Tag used when a requested tag has no usable characters
+const FALLBACK_TAG: &str = "x";
+
+/// Device names Windows reserves in every directory
+const WINDOWS_RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Generate a readable, collision-resistant name
+///
+/// # Arguments
+/// * `tag` - Readable part, e.g. a language or backend; lowercased and
+///   reduced to ASCII letters and digits
+///
+/// # Returns
+/// `cylo-<tag>-<12 hex digits>`, valid for every `NameTarget`
+pub fn generate(tag: &str) -> String {
+    format!("{ID_PREFIX}-{}-{}", sanitize_tag(tag), suffix())
+}
+
+/// Short tag for a language: its source extension when known
+///
+/// `python@3.12` yields `py`; an unknown language is reduced to its
+/// letters and digits.
+pub fn language_tag(language: &str) -> String {
+    match language::resolve(language) {
+        Some(spec) => spec.extension.to_string(),
+        None => sanitize_tag(language::split_version(language).0),
+    }
+}
+
+/// Name of a host artifact owned by this process
+///
+/// `<kind>-<owner>-<tag>-<random>-<pid>`. Startup recovery finds leftovers
+/// by the `kind` prefix and decides whether they are abandoned from the
+/// trailing pid, so both must stay where they are.
+pub(crate) fn artifact_name(kind: &str, owner_id: &str, tag: &str) -> String {
+    format!(
+        "{kind}-{owner_id}-{}-{}-{}",
+        sanitize_tag(tag),
+        suffix(),
+        std::process::id()
+    )
+}
+
+/// Name of a temporary workspace directory: `cylo_<owner>_<label>_<random>`
+pub(crate) fn workspace_dir_name(owner_id: &str, label: &str) -> String {
+    format!("{}{label}_{}", workspace_dir_prefix(owner_id), suffix())
+}
+
+/// Prefix shared by every workspace directory name of one owner
+pub(crate) fn workspace_dir_prefix(owner_id: &str) -> String {
+    format!("{ID_PREFIX}_{owner_id}_")
+}
+
+/// Whether a directory name has the workspace layout, including the
+/// UUID-suffixed names of earlier versions
+pub(crate) fn is_workspace_dir_name(name: &str) -> bool {
+    name.strip_prefix("cylo_")
+        .and_then(|rest| rest.rsplit_once('_'))
+        .is_some_and(|(_, id)| is_suffix(id) || uuid::Uuid::parse_str(id).is_ok())
+}
+
+/// Random part of a generated name
+fn suffix() -> String {
+    // The low 48 bits of a v4 UUID are all random
+    let random = uuid::Uuid::new_v4().as_u128() as u64 & 0xffff_ffff_ffff;
+    format!("{random:0width$x}", width = SUFFIX_LEN)
+}
+
+fn is_suffix(id: &str) -> bool {
+    id.len() == SUFFIX_LEN && id.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn sanitize_tag(tag: &str) -> String {
+    let tag: String = tag
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .take(MAX_TAG_LEN)
+        .collect();
+    if tag.is_empty() {
+        FALLBACK_TAG.to_string()
+    } else {
+        tag
+    }
+}
+
+/// Where a name is used, which decides the rules it must follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NameTarget {
+    /// Instance names tracked by the instance manager
+    Instance,
+    /// Container names: a letter or digit, then letters, digits, `_`, `.`
+    /// and `-`
+    Container,
+    /// FireCracker VM ids: letters, digits and `-`
+    VmId,
+    /// A single file or directory name on the host
+    PathComponent,
+    /// Windows workspace names, used for directories and Job objects
+    WindowsObject,
+}
+
+impl NameTarget {
+    fn max_len(self) -> usize {
+        match self {
+            NameTarget::Instance | NameTarget::Container | NameTarget::WindowsObject => 63,
+            NameTarget::VmId => 64,
+            NameTarget::PathComponent => 255,
+        }
+    }
+
+    fn allows(self, c: char) -> bool {
+        c.is_ascii_alphanumeric()
+            || match self {
+                NameTarget::Instance | NameTarget::WindowsObject => c == '-' || c == '_',
+                NameTarget::Container | NameTarget::PathComponent => {
+                    c == '-' || c == '_' || c == '.'
+                }
+                NameTarget::VmId => c == '-',
+            }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            NameTarget::Instance => "Instance name",
+            NameTarget::Container => "Container name",
+            NameTarget::VmId => "VM id",
+            NameTarget::PathComponent => "File name",
+            NameTarget::WindowsObject => "Workspace name",
+        }
+    }
+}
+
+impl fmt::Display for NameTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Check a name against the rules of where it is used
+///
+/// # Returns
+/// Ok(()), or why the name is rejected, e.g. "cannot be empty"
+pub(crate) fn check(target: NameTarget, name: &str) -> Result<(), &'static str> {
+    let (Some(first), Some(last)) = (name.chars().next(), name.chars().last()) else {
+        return Err("cannot be empty");
+    };
+    if name.len() > target.max_len() {
+        return Err(match target {
+            NameTarget::VmId => "cannot exceed 64 characters",
+            NameTarget::PathComponent => "cannot exceed 255 characters",
+            _ => "cannot exceed 63 characters",
+        });
+    }
+    if !name.chars().all(|c| target.allows(c)) {
+        return Err(match target {
+            NameTarget::Instance | NameTarget::WindowsObject => {
+                "can only contain ASCII letters, digits, hyphens, and underscores"
+            }
+            NameTarget::Container | NameTarget::PathComponent => {
+                "can only contain ASCII letters, digits, dots, hyphens, and underscores"
+            }
+            NameTarget::VmId => "can only contain ASCII letters, digits, and hyphens",
+        });
+    }
+
+    match target {
+        NameTarget::Instance if !first.is_ascii_alphanumeric() => {
+            Err("cannot start with hyphen or underscore")
+        }
+        NameTarget::Instance if !last.is_ascii_alphanumeric() => {
+            Err("cannot end with hyphen or underscore")
+        }
+        NameTarget::Container if !first.is_ascii_alphanumeric() => {
+            Err("must start with a letter or digit")
+        }
+        NameTarget::PathComponent if first == '.' || first == '-' => {
+            Err("cannot start with a dot or hyphen")
+        }
+        NameTarget::WindowsObject
+            if WINDOWS_RESERVED.iter().any(|reserved| reserved.eq_ignore_ascii_case(name)) =>
+        {
+            Err("is reserved by Windows")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Validate a caller-supplied name for where it will be used
+///
+/// # Arguments
+/// * `target` - Where the name is used
+/// * `name` - The name to validate
+///
+/// # Returns
+/// Ok(()), or a validation error naming the broken rule
+pub fn validate_name(target: NameTarget, name: &str) -> CyloResult<()> {
+    check(target, name).map_err(|reason| CyloError::validation(format!("{target} {reason}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGETS: [NameTarget; 5] = [
+        NameTarget::Instance,
+        NameTarget::Container,
+        NameTarget::VmId,
+        NameTarget::PathComponent,
+        NameTarget::WindowsObject,
+    ];
+
+    #[test]
+    fn generated_names_are_readable_and_valid_everywhere() {
+        let name = generate(&language_tag("python@3.12"));
+        assert!(name.starts_with("cylo-py-"), "{name}");
+        assert_eq!(name.len(), "cylo-py-".len() + SUFFIX_LEN);
+        assert_ne!(name, generate("py"));
+        assert!(generate("C++ / Ünïcode").starts_with("cylo-cncode-"));
+        assert!(generate("--").starts_with("cylo-x-"));
+
+        let artifact = artifact_name("exec", "0123456789ab", "rust");
+        assert!(artifact.ends_with(&format!("-{}", std::process::id())));
+        for target in TARGETS {
+            assert_eq!(check(target, &name), Ok(()), "{target}");
+            assert_eq!(check(target, &artifact), Ok(()), "{target}");
+        }
+
+        assert!(is_workspace_dir_name(&workspace_dir_name("owner", "apple")));
+        assert!(workspace_dir_name("owner", "apple").starts_with(&workspace_dir_prefix("owner")));
+        assert!(is_workspace_dir_name(&format!("cylo_rust_{}", uuid::Uuid::new_v4())));
+        assert!(!is_workspace_dir_name("cylo_rust_notanid"));
+    }
+
+    #[test]
+    fn names_are_checked_against_each_target() {
+        assert_eq!(check(NameTarget::Instance, ""), Err("cannot be empty"));
+        assert!(check(NameTarget::Instance, "-name").is_err());
+        assert!(check(NameTarget::Instance, "naïve").is_err());
+        assert!(check(NameTarget::VmId, "vm_1").is_err());
+        assert!(check(NameTarget::Container, "web.1").is_ok());
+        assert!(check(NameTarget::Container, ".web").is_err());
+        assert!(check(NameTarget::PathComponent, "..").is_err());
+        assert!(check(NameTarget::PathComponent, "a/b").is_err());
+        assert!(check(NameTarget::WindowsObject, "com1").is_err());
+        assert!(check(NameTarget::WindowsObject, "console").is_ok());
+        assert!(check(NameTarget::VmId, &"a".repeat(64)).is_ok());
+        assert!(check(NameTarget::Container, &"a".repeat(64)).is_err());
+
+        let error = validate_name(NameTarget::WindowsObject, "NUL").unwrap_err();
+        assert!(error.to_string().contains("Workspace name is reserved by Windows"));
+    }
+}
@@ -11,9 +11,12 @@
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
 use crate::async_task::{AsyncTask, AsyncTaskBuilder};
-use crate::backends::{ExecutionBackend, create_backend};
+use crate::backends::{BackendConfig, ExecutionBackend, HealthCheckLevel, create_backend};
 use crate::execution_env::{CyloError, CyloInstance, CyloResult};
+use crate::logging::targets;
 
 use super::{InstanceManager, ManagedInstance};
 
@@ -29,8 +32,25 @@ impl InstanceManager {
     /// # Returns
     /// AsyncTask that resolves when instance is registered
     pub fn register_instance(&self, instance: CyloInstance) -> AsyncTask<CyloResult<()>> {
+        self.register_instance_with_config(instance, self.default_config.clone())
+    }
+
+    /// Register a new named instance whose backend uses `config` instead of
+    /// the manager's default configuration
+    ///
+    /// # Arguments
+    /// * `instance` - Cylo instance configuration
+    /// * `config` - Configuration the backend is created with
+    ///
+    /// # Returns
+    /// AsyncTask that resolves when instance is registered
+    pub fn register_instance_with_config(
+        &self,
+        instance: CyloInstance,
+        config: BackendConfig,
+    ) -> AsyncTask<CyloResult<()>> {
         let instances_lock = Arc::clone(&self.instances);
-        let default_config = self.default_config.clone();
+        let health_cache = Arc::clone(&self.health_cache);
 
         AsyncTaskBuilder::new(async move {
             // Validate instance configuration
@@ -50,17 +70,25 @@ impl InstanceManager {
             }
 
             // Create backend instance
-            let backend = create_backend(&instance.env, default_config)?;
+            let backend: Arc<dyn ExecutionBackend> =
+                Arc::from(create_backend(&instance.env, config)?);
 
-            // Perform initial health check
-            let health_result = (backend.health_check().await).ok();
+            // Perform initial health check, replacing anything cached under
+            // a previous instance of the same name
+            let health_result = health_cache
+                .check(&instance.id(), backend.as_ref(), HealthCheckLevel::Readiness, true)
+                .await;
 
+            let parallelism = backend.get_config().parallelism();
             let managed_instance = ManagedInstance {
-                backend: Arc::from(backend),
+                instance: instance.clone(),
+                backend,
                 last_accessed: SystemTime::now(),
-                last_health: health_result,
-                last_health_check: Some(SystemTime::now()),
+                last_health_check: Some(health_result.last_check),
+                last_health: Some(health_result),
+                last_metrics: None,
                 ref_count: 0,
+                execution_slots: Arc::new(Semaphore::new(parallelism as usize)),
             };
 
             // Register the instance
@@ -81,7 +109,8 @@ impl InstanceManager {
     ///
     /// Returns a reference to the backend instance if it exists
     /// and is healthy. Updates access timestamp and increments
-    /// reference count.
+    /// reference count. Health comes from the manager's health cache, so
+    /// the backend is only probed once the cached result has expired.
     ///
     /// # Arguments
     /// * `instance_id` - Unique instance identifier
@@ -93,11 +122,10 @@ impl InstanceManager {
         instance_id: &str,
     ) -> AsyncTask<CyloResult<Arc<dyn ExecutionBackend>>> {
         let instances_lock = Arc::clone(&self.instances);
+        let health_cache = Arc::clone(&self.health_cache);
         let instance_id = instance_id.to_string();
-        let health_check_interval = self.health_check_interval;
 
         AsyncTaskBuilder::new(async move {
-            // First, try to get the instance with read lock
             let backend = {
                 let instances = instances_lock.read().map_err(|e| {
                     CyloError::internal(format!("Failed to acquire read lock: {e}"))
@@ -111,70 +139,38 @@ impl InstanceManager {
                 }
             };
 
-            // Check if health check is needed
-            let needs_health_check = {
-                let instances = instances_lock.read().map_err(|e| {
-                    CyloError::internal(format!("Failed to acquire read lock: {e}"))
-                })?;
-
-                if let Some(managed) = instances.get(&instance_id) {
-                    managed
-                        .last_health_check
-                        .map(|last| {
-                            last.elapsed().unwrap_or(Duration::from_secs(0)) > health_check_interval
-                        })
-                        .unwrap_or(true)
-                } else {
-                    false
-                }
-            };
+            // Only the cheap liveness probe runs on the hot path; a stale
+            // readiness result is refreshed without delaying the caller
+            let health_result = health_cache
+                .check(&instance_id, backend.as_ref(), HealthCheckLevel::Liveness, false)
+                .await;
+            health_cache.refresh_in_background(
+                &instance_id,
+                backend.clone(),
+                HealthCheckLevel::Readiness,
+            );
+
+            if !health_result.is_healthy {
+                return Err(CyloError::backend_unavailable(
+                    backend.backend_type(),
+                    format!(
+                        "Instance {} is unhealthy: {}",
+                        instance_id, health_result.message
+                    ),
+                ));
+            }
 
-            // Perform health check if needed
-            if needs_health_check {
-                let health_result = match backend.health_check().await {
-                    Ok(health) => health,
-                    Err(e) => {
-                        return Err(CyloError::backend_unavailable(
-                            backend.backend_type(),
-                            format!("Health check failed for instance {instance_id}: {e}"),
-                        ));
-                    }
-                };
-
-                if !health_result.is_healthy {
-                    return Err(CyloError::backend_unavailable(
-                        backend.backend_type(),
-                        format!(
-                            "Instance {} is unhealthy: {}",
-                            instance_id, health_result.message
-                        ),
-                    ));
-                }
+            // Update health status, access timestamp and ref count
+            {
+                let mut instances = instances_lock.write().map_err(|e| {
+                    CyloError::internal(format!("Failed to acquire write lock: {e}"))
+                })?;
 
-                // Update health status
-                {
-                    let mut instances = instances_lock.write().map_err(|e| {
-                        CyloError::internal(format!("Failed to acquire write lock: {e}"))
-                    })?;
-
-                    if let Some(managed) = instances.get_mut(&instance_id) {
-                        managed.last_health = Some(health_result);
-                        managed.last_health_check = Some(SystemTime::now());
-                        managed.last_accessed = SystemTime::now();
-                        managed.ref_count += 1;
-                    }
-                }
-            } else {
-                // Just update access timestamp and ref count
-                {
-                    let mut instances = instances_lock.write().map_err(|e| {
-                        CyloError::internal(format!("Failed to acquire write lock: {e}"))
-                    })?;
-
-                    if let Some(managed) = instances.get_mut(&instance_id) {
-                        managed.last_accessed = SystemTime::now();
-                        managed.ref_count += 1;
-                    }
+                if let Some(managed) = instances.get_mut(&instance_id) {
+                    managed.last_health_check = Some(health_result.last_check);
+                    managed.last_health = Some(health_result);
+                    managed.last_accessed = SystemTime::now();
+                    managed.ref_count += 1;
                 }
             }
 
@@ -209,6 +205,40 @@ impl InstanceManager {
         Ok(())
     }
 
+    /// Wait for one of an instance's execution slots
+    ///
+    /// An instance runs at most `max_parallel` executions at once; each
+    /// execution holds a slot for its whole duration.
+    ///
+    /// # Arguments
+    /// * `instance_id` - Unique instance identifier
+    ///
+    /// # Returns
+    /// AsyncTask that resolves to a permit released when dropped
+    pub fn acquire_execution_slot(
+        &self,
+        instance_id: &str,
+    ) -> AsyncTask<CyloResult<OwnedSemaphorePermit>> {
+        let slots = self
+            .instances
+            .read()
+            .map_err(|e| CyloError::internal(format!("Failed to acquire read lock: {e}")))
+            .and_then(|instances| match instances.get(instance_id) {
+                Some(managed) => Ok(Arc::clone(&managed.execution_slots)),
+                None => Err(CyloError::InstanceNotFound {
+                    name: instance_id.to_string(),
+                }),
+            });
+
+        AsyncTaskBuilder::new(async move {
+            slots?
+                .acquire_owned()
+                .await
+                .map_err(|e| CyloError::internal(format!("Execution slots closed: {e}")))
+        })
+        .spawn()
+    }
+
     /// Remove an instance from the registry
     ///
     /// Cleanly shuts down and removes the specified instance.
@@ -222,6 +252,7 @@ impl InstanceManager {
     pub fn remove_instance(&self, instance_id: &str) -> AsyncTask<CyloResult<()>> {
         let instances_lock = Arc::clone(&self.instances);
         let instance_id = instance_id.to_string();
+        self.health_cache.invalidate(&instance_id);
 
         AsyncTaskBuilder::new(async move {
             // Remove the instance from registry
@@ -244,7 +275,12 @@ impl InstanceManager {
                 // Perform cleanup
                 if let Err(e) = managed.backend.cleanup().await {
                     // Log cleanup error but don't fail the removal
-                    log::warn!("Failed to cleanup instance {}: {}", instance_id, e);
+                    tracing::warn!(
+                        target: targets::INSTANCES,
+                        "Failed to cleanup instance {}: {}",
+                        instance_id,
+                        e
+                    );
                 }
             }
 
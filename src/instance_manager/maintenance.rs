@@ -3,29 +3,93 @@
 // ----------------------------------------------------------------------------
 // Instance maintenance operations:
 // - Health check all instances
+// - Sample live instance metrics
 // - Cleanup idle instances
 // - Shutdown all instances
 // ============================================================================
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 
 use crate::async_task::{AsyncTask, AsyncTaskBuilder};
-use crate::backends::HealthStatus;
+use crate::backends::{ExecutionBackend, HealthCheckLevel, HealthStatus, InstanceMetrics};
 use crate::execution_env::{CyloError, CyloResult};
+use crate::logging::targets;
 
-use super::InstanceManager;
+use super::{InstanceManager, ManagedInstance};
+
+/// An instance as seen by idle cleanup
+#[derive(Debug, Clone)]
+pub(crate) struct EvictionCandidate {
+    pub(crate) id: String,
+    /// Time since last access, or None while references are held
+    pub(crate) idle: Option<Duration>,
+    /// Live memory held, in bytes
+    pub(crate) memory_bytes: u64,
+}
+
+/// Instances to evict: every idle one past `max_idle`, then, while all
+/// instances together hold more memory than `budget`, the remaining idle
+/// ones holding the most memory
+pub(crate) fn select_evictions(
+    candidates: &[EvictionCandidate],
+    max_idle: Duration,
+    budget: Option<u64>,
+) -> Vec<String> {
+    let mut held: u64 = candidates.iter().map(|c| c.memory_bytes).sum();
+    let mut evict = Vec::new();
+    let mut idle_within_limit = Vec::new();
+    for candidate in candidates {
+        match candidate.idle {
+            Some(idle) if idle > max_idle => {
+                held -= candidate.memory_bytes;
+                evict.push(candidate.id.clone());
+            }
+            Some(_) => idle_within_limit.push(candidate),
+            None => {}
+        }
+    }
+
+    if let Some(budget) = budget {
+        idle_within_limit.sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes));
+        for candidate in idle_within_limit {
+            if held <= budget {
+                break;
+            }
+            held -= candidate.memory_bytes;
+            evict.push(candidate.id.clone());
+        }
+    }
+    evict
+}
+
+/// Sample an instance's metrics and store them on its registry entry
+async fn sample_metrics(
+    instances_lock: &RwLock<HashMap<String, ManagedInstance>>,
+    instance_id: &str,
+    backend: &dyn ExecutionBackend,
+) -> Option<InstanceMetrics> {
+    let metrics = backend.instance_metrics().await.ok().flatten()?;
+    if let Ok(mut instances) = instances_lock.write()
+        && let Some(managed) = instances.get_mut(instance_id)
+    {
+        managed.last_metrics = Some(metrics.clone());
+    }
+    Some(metrics)
+}
 
 impl InstanceManager {
     /// Perform health checks on all instances
     ///
-    /// Updates health status for all registered instances.
+    /// Probes every registered instance regardless of cached results and
+    /// refreshes the health cache with the outcome.
     ///
     /// # Returns
     /// AsyncTask that resolves when all health checks complete
     pub fn health_check_all(&self) -> AsyncTask<CyloResult<HashMap<String, HealthStatus>>> {
         let instances_lock = Arc::clone(&self.instances);
+        let health_cache = Arc::clone(&self.health_cache);
 
         AsyncTaskBuilder::new(async move {
             let mut results = HashMap::new();
@@ -47,38 +111,23 @@ impl InstanceManager {
 
             for (instance_id, backend) in instance_list {
                 let id = instance_id.clone();
+                let health_cache = Arc::clone(&health_cache);
                 let health_task = AsyncTaskBuilder::new(async move {
-                    let health = backend.health_check().await;
+                    let health = health_cache
+                        .check(&id, backend.as_ref(), HealthCheckLevel::Readiness, true)
+                        .await;
                     (id, health)
                 })
                 .spawn();
                 health_tasks.push(health_task);
             }
 
-            // Collect results
+            // Collect results; failed probes are already reported as
+            // unhealthy by the cache
             for task in health_tasks {
-                match task.await {
-                    Ok((instance_id, health)) => {
-                        match health {
-                            Ok(health_status) => {
-                                results.insert(instance_id, health_status);
-                            }
-                            Err(_) => {
-                                // Health check failed, insert unhealthy status
-                                results.insert(
-                                    instance_id,
-                                    HealthStatus::unhealthy("Health check failed"),
-                                );
-                            }
-                        }
-                    }
-                    Err(_) => {
-                        // Task failed, skip this instance
-                    }
+                if let Ok((instance_id, health_status)) = task.await {
+                    results.insert(instance_id, health_status);
                 }
-
-                // Note: Health status is already stored in results HashMap
-                // The stored health status in instances is updated when instances are accessed
             }
 
             Ok(results)
@@ -86,39 +135,93 @@ impl InstanceManager {
         .spawn()
     }
 
+    /// Sample live memory and CPU of every instance
+    ///
+    /// Instances whose backends run no VMs or containers are omitted. The
+    /// samples are also kept for `get_instance_metrics`.
+    ///
+    /// # Returns
+    /// AsyncTask that resolves with metrics per instance id
+    pub fn collect_instance_metrics(
+        &self,
+    ) -> AsyncTask<CyloResult<HashMap<String, InstanceMetrics>>> {
+        let instances_lock = Arc::clone(&self.instances);
+
+        AsyncTaskBuilder::new(async move {
+            let instance_list = {
+                let instances = instances_lock.read().map_err(|e| {
+                    CyloError::internal(format!("Failed to acquire read lock: {e}"))
+                })?;
+                instances
+                    .iter()
+                    .map(|(id, managed)| (id.clone(), managed.backend.clone()))
+                    .collect::<Vec<_>>()
+            };
+
+            let mut results = HashMap::new();
+            for (instance_id, backend) in instance_list {
+                if let Some(metrics) =
+                    sample_metrics(&instances_lock, &instance_id, backend.as_ref()).await
+                {
+                    results.insert(instance_id, metrics);
+                }
+            }
+            Ok(results)
+        })
+        .spawn()
+    }
+
     /// Clean up idle instances
     ///
-    /// Removes instances that have been idle longer than the
-    /// configured maximum idle time and have no active references.
+    /// Removes instances that have been idle longer than the configured
+    /// maximum idle time and have no active references. With an idle memory
+    /// budget, live metrics are sampled first and further unreferenced
+    /// instances are removed, largest first, until the budget is met.
     ///
     /// # Returns
     /// AsyncTask that resolves with count of cleaned up instances
     pub fn cleanup_idle_instances(&self) -> AsyncTask<CyloResult<u32>> {
         let instances_lock = Arc::clone(&self.instances);
         let max_idle_time = self.max_idle_time;
+        let budget = self.idle_memory_budget;
 
         AsyncTaskBuilder::new(async move {
             let now = SystemTime::now();
-            let mut to_remove = Vec::new();
 
-            // Identify idle instances
-            {
+            let snapshot = {
                 let instances = instances_lock.read().map_err(|e| {
                     CyloError::internal(format!("Failed to acquire read lock: {e}"))
                 })?;
 
-                for (instance_id, managed) in instances.iter() {
-                    let idle_time = now
-                        .duration_since(managed.last_accessed)
-                        .unwrap_or(Duration::from_secs(0));
+                instances
+                    .iter()
+                    .map(|(instance_id, managed)| {
+                        let idle = (managed.ref_count == 0).then(|| {
+                            now.duration_since(managed.last_accessed)
+                                .unwrap_or(Duration::from_secs(0))
+                        });
+                        (instance_id.clone(), idle, managed.backend.clone())
+                    })
+                    .collect::<Vec<_>>()
+            };
 
-                    if idle_time > max_idle_time && managed.ref_count == 0 {
-                        to_remove.push(instance_id.clone());
-                    }
-                }
+            let mut candidates = Vec::with_capacity(snapshot.len());
+            for (id, idle, backend) in snapshot {
+                let memory_bytes = match budget {
+                    Some(_) => sample_metrics(&instances_lock, &id, backend.as_ref())
+                        .await
+                        .map_or(0, |metrics| metrics.memory_bytes),
+                    None => 0,
+                };
+                candidates.push(EvictionCandidate {
+                    id,
+                    idle,
+                    memory_bytes,
+                });
             }
+            let to_remove = select_evictions(&candidates, max_idle_time, budget);
 
-            // Remove idle instances
+            // Remove idle instances, skipping any referenced since the snapshot
             let mut removed_count = 0;
             for instance_id in to_remove {
                 let managed_instance = {
@@ -126,13 +229,21 @@ impl InstanceManager {
                         CyloError::internal(format!("Failed to acquire write lock: {e}"))
                     })?;
 
-                    instances.remove(&instance_id)
+                    match instances.get(&instance_id) {
+                        Some(managed) if managed.ref_count == 0 => instances.remove(&instance_id),
+                        _ => None,
+                    }
                 };
 
                 if let Some(managed) = managed_instance {
                     // Perform cleanup
                     if let Err(e) = managed.backend.cleanup().await {
-                        log::warn!("Failed to cleanup idle instance {}: {}", instance_id, e);
+                        tracing::warn!(
+                            target: targets::INSTANCES,
+                            "Failed to cleanup idle instance {}: {}",
+                            instance_id,
+                            e
+                        );
                     } else {
                         removed_count += 1;
                     }
@@ -171,7 +282,12 @@ impl InstanceManager {
                 let id = instance_id.clone();
                 let cleanup_task = AsyncTaskBuilder::new(async move {
                     if let Err(e) = managed.backend.cleanup().await {
-                        log::warn!("Failed to cleanup instance {} during shutdown: {}", id, e);
+                        tracing::warn!(
+                            target: targets::INSTANCES,
+                            "Failed to cleanup instance {} during shutdown: {}",
+                            id,
+                            e
+                        );
                     }
                 })
                 .spawn();
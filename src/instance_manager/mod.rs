@@ -14,12 +14,19 @@ use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 
-use crate::backends::{BackendConfig, ExecutionBackend, HealthStatus};
+use tokio::sync::Semaphore;
+
+use crate::backends::{
+    BackendConfig, ExecutionBackend, HealthCache, HealthCacheConfig, HealthStatus,
+    InstanceMetrics,
+};
+use crate::execution_env::CyloInstance;
 
 // Submodules
 mod lifecycle;
 mod queries;
 mod maintenance;
+mod selection;
 mod global;
 
 #[cfg(test)]
@@ -27,6 +34,7 @@ mod tests;
 
 // Re-exports
 pub use global::{global_instance_manager, init_global_instance_manager};
+pub use selection::{InstanceCandidate, InstanceSelector, SelectionStrategy};
 
 /// Thread-safe instance manager for Cylo execution environments
 ///
@@ -41,16 +49,23 @@ pub struct InstanceManager {
     /// Default configuration for new instances
     pub(crate) default_config: BackendConfig,
 
-    /// Health check interval for monitoring
-    pub(crate) health_check_interval: Duration,
+    /// Cached health results, keyed by instance id; its TTL is the
+    /// health check interval
+    pub(crate) health_cache: Arc<HealthCache>,
 
     /// Maximum idle time before cleanup
     pub(crate) max_idle_time: Duration,
+
+    /// Live memory all instances may hold before idle ones are evicted
+    pub(crate) idle_memory_budget: Option<u64>,
 }
 
 /// Managed instance wrapper with metadata
 #[derive(Debug)]
 pub(crate) struct ManagedInstance {
+    /// Environment and name the instance was registered with
+    pub(crate) instance: CyloInstance,
+
     /// The backend instance
     pub(crate) backend: Arc<dyn ExecutionBackend>,
 
@@ -63,8 +78,14 @@ pub(crate) struct ManagedInstance {
     /// Last health check timestamp
     pub(crate) last_health_check: Option<SystemTime>,
 
+    /// Last sample of the instance's live VMs or containers
+    pub(crate) last_metrics: Option<InstanceMetrics>,
+
     /// Reference count for active operations
     pub(crate) ref_count: u32,
+
+    /// Execution slots, sized from the backend's `max_parallel`
+    pub(crate) execution_slots: Arc<Semaphore>,
 }
 
 impl InstanceManager {
@@ -76,8 +97,9 @@ impl InstanceManager {
         Self {
             instances: Arc::new(RwLock::new(HashMap::new())),
             default_config: BackendConfig::new("default"),
-            health_check_interval: Duration::from_secs(60),
+            health_cache: Arc::new(HealthCache::new(HealthCacheConfig::default())),
             max_idle_time: Duration::from_secs(300), // 5 minutes
+            idle_memory_budget: None,
         }
     }
 
@@ -98,10 +120,44 @@ impl InstanceManager {
         Self {
             instances: Arc::new(RwLock::new(HashMap::new())),
             default_config: config,
-            health_check_interval,
+            health_cache: Arc::new(HealthCache::new(HealthCacheConfig {
+                ttl: health_check_interval,
+                ..HealthCacheConfig::default()
+            })),
             max_idle_time,
+            idle_memory_budget: None,
         }
     }
+
+    /// Evict idle instances while instances hold more live memory than
+    /// `bytes`, largest first
+    ///
+    /// # Arguments
+    /// * `bytes` - Memory budget checked by `cleanup_idle_instances`
+    ///
+    /// # Returns
+    /// Manager enforcing the budget
+    pub fn with_idle_memory_budget(mut self, bytes: u64) -> Self {
+        self.idle_memory_budget = Some(bytes);
+        self
+    }
+
+    /// Share a health cache with other components
+    ///
+    /// # Arguments
+    /// * `cache` - Cache consulted before probing instances
+    ///
+    /// # Returns
+    /// Manager using `cache` for instance health
+    pub fn with_health_cache(mut self, cache: Arc<HealthCache>) -> Self {
+        self.health_cache = cache;
+        self
+    }
+
+    /// Health cache consulted before probing instances
+    pub fn health_cache(&self) -> &Arc<HealthCache> {
+        &self.health_cache
+    }
 }
 
 impl Default for InstanceManager {
@@ -4,9 +4,10 @@
 // Instance query operations:
 // - List all instances
 // - Get instance health status
+// - Get last sampled instance metrics
 // ============================================================================
 
-use crate::backends::HealthStatus;
+use crate::backends::{HealthStatus, InstanceMetrics};
 use crate::execution_env::{CyloError, CyloResult};
 
 use super::InstanceManager;
@@ -42,4 +43,25 @@ impl InstanceManager {
             .get(instance_id)
             .and_then(|managed| managed.last_health.clone()))
     }
+
+    /// Get the last metrics sample of an instance
+    ///
+    /// Samples are taken by `collect_instance_metrics` and during idle
+    /// cleanup.
+    ///
+    /// # Arguments
+    /// * `instance_id` - Unique instance identifier
+    ///
+    /// # Returns
+    /// Metrics if the instance exists and its backend reports them
+    pub fn get_instance_metrics(&self, instance_id: &str) -> CyloResult<Option<InstanceMetrics>> {
+        let instances = self
+            .instances
+            .read()
+            .map_err(|e| CyloError::internal(format!("Failed to acquire read lock: {e}")))?;
+
+        Ok(instances
+            .get(instance_id)
+            .and_then(|managed| managed.last_metrics.clone()))
+    }
 }
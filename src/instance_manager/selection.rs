@@ -0,0 +1,148 @@
+// ============================================================================
+// File: packages/cylo/src/instance_manager/selection.rs
+// ----------------------------------------------------------------------------
+// Choice among registered instances that could serve the same request:
+// - Candidate snapshots of every instance of an environment
+// - Pluggable selection strategies
+// - Built-in health-, load- and verification-based strategies
+// ============================================================================
+
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::execution_env::{Cylo, CyloError, CyloInstance, CyloResult};
+
+use super::InstanceManager;
+
+/// A registered instance as seen by instance selection
+#[derive(Debug, Clone)]
+pub struct InstanceCandidate {
+    /// The instance
+    pub instance: CyloInstance,
+    /// Whether its last health check passed
+    pub healthy: bool,
+    /// Time since its health was last checked; None if it never was
+    pub since_verified: Option<Duration>,
+    /// Executions currently holding a reference to it
+    pub in_flight: u32,
+    /// Execution slots free right now
+    pub free_slots: usize,
+    /// Execution slots in total, the backend's `max_parallel`
+    pub capacity: usize,
+}
+
+impl InstanceCandidate {
+    /// Fraction of its execution slots in use, from 0.0 to 1.0
+    pub fn load(&self) -> f64 {
+        if self.capacity == 0 {
+            return 1.0;
+        }
+        1.0 - self.free_slots.min(self.capacity) as f64 / self.capacity as f64
+    }
+}
+
+/// Picks the instance a request runs on
+///
+/// Candidates are sorted by instance id, so equal candidates resolve the
+/// same way every time.
+pub trait InstanceSelector: fmt::Debug + Send + Sync {
+    /// Index of the candidate to run on, or None if none should be used
+    fn select(&self, candidates: &[InstanceCandidate]) -> Option<usize>;
+}
+
+/// Built-in instance selection strategies
+///
+/// Every strategy passes over instances whose last health check failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionStrategy {
+    /// The healthy instance with the lowest id
+    FirstMatch,
+    /// The healthy instance using the smallest share of its execution
+    /// slots
+    LeastLoaded,
+    /// The healthy instance whose health was checked most recently
+    MostRecentlyVerified,
+    /// Free slots first, then load, weighted by how recently health was
+    /// verified
+    #[default]
+    HealthWeighted,
+}
+
+/// Verification age at which a health check counts half as much as a fresh
+/// one in `HealthWeighted`
+const VERIFICATION_HALF_LIFE: Duration = Duration::from_secs(60);
+
+impl SelectionStrategy {
+    /// Score of a healthy candidate; higher is better
+    fn score(self, candidate: &InstanceCandidate) -> f64 {
+        match self {
+            Self::FirstMatch => 0.0,
+            Self::LeastLoaded => -candidate.load(),
+            Self::MostRecentlyVerified => {
+                candidate.since_verified.map_or(f64::MIN, |age| -age.as_secs_f64())
+            }
+            Self::HealthWeighted => {
+                let saturated = if candidate.free_slots == 0 { -2.0 } else { 0.0 };
+                let freshness = candidate.since_verified.map_or(0.0, |age| {
+                    let half_lives = age.as_secs_f64() / VERIFICATION_HALF_LIFE.as_secs_f64();
+                    0.5f64.powf(half_lives)
+                });
+                saturated + (1.0 - candidate.load()) + freshness * 0.5
+            }
+        }
+    }
+}
+
+impl InstanceSelector for SelectionStrategy {
+    fn select(&self, candidates: &[InstanceCandidate]) -> Option<usize> {
+        let mut best: Option<(usize, f64)> = None;
+        for (index, candidate) in candidates.iter().enumerate() {
+            if !candidate.healthy {
+                continue;
+            }
+            let score = self.score(candidate);
+            // Strictly better only, so ties keep the earlier candidate
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((index, score));
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+}
+
+impl InstanceManager {
+    /// Snapshot every registered instance of an environment
+    ///
+    /// # Arguments
+    /// * `env` - Environment the instances must have been created for
+    ///
+    /// # Returns
+    /// Candidates sorted by instance id
+    pub fn instance_candidates(&self, env: &Cylo) -> CyloResult<Vec<InstanceCandidate>> {
+        let instances = self
+            .instances
+            .read()
+            .map_err(|e| CyloError::internal(format!("Failed to acquire read lock: {e}")))?;
+
+        let now = SystemTime::now();
+        let mut candidates: Vec<InstanceCandidate> = instances
+            .values()
+            .filter(|managed| managed.instance.env == *env)
+            .map(|managed| InstanceCandidate {
+                instance: managed.instance.clone(),
+                healthy: managed.last_health.as_ref().is_some_and(|health| health.is_healthy),
+                since_verified: managed
+                    .last_health_check
+                    .map(|checked| now.duration_since(checked).unwrap_or_default()),
+                in_flight: managed.ref_count,
+                free_slots: managed.execution_slots.available_permits(),
+                capacity: managed.backend.get_config().parallelism() as usize,
+            })
+            .collect();
+        candidates.sort_by_key(|candidate| candidate.instance.id());
+        Ok(candidates)
+    }
+}
@@ -9,7 +9,11 @@ use std::time::Duration;
 use crate::backends::BackendConfig;
 use crate::execution_env::{Cylo, CyloError};
 
-use super::{global_instance_manager, InstanceManager};
+use super::maintenance::{EvictionCandidate, select_evictions};
+use super::{
+    global_instance_manager, InstanceCandidate, InstanceManager, InstanceSelector,
+    SelectionStrategy,
+};
 
 #[tokio::test]
 async fn instance_manager_creation() {
@@ -145,6 +149,71 @@ fn custom_configuration() {
     let manager =
         InstanceManager::with_config(config, Duration::from_secs(30), Duration::from_secs(600));
 
-    assert_eq!(manager.health_check_interval, Duration::from_secs(30));
+    assert_eq!(manager.health_cache().config().ttl, Duration::from_secs(30));
     assert_eq!(manager.max_idle_time, Duration::from_secs(600));
 }
+
+#[test]
+fn eviction_honours_idle_time_and_memory_budget() {
+    let candidate = |id: &str, idle: Option<u64>, memory_bytes: u64| EvictionCandidate {
+        id: id.to_string(),
+        idle: idle.map(Duration::from_secs),
+        memory_bytes,
+    };
+    let candidates = [
+        candidate("stale", Some(900), 100),
+        candidate("warm_small", Some(10), 200),
+        candidate("warm_large", Some(10), 500),
+        candidate("busy", None, 1000),
+    ];
+    let max_idle = Duration::from_secs(600);
+
+    assert_eq!(select_evictions(&candidates, max_idle, None), ["stale"]);
+    assert_eq!(
+        select_evictions(&candidates, max_idle, Some(1500)),
+        ["stale", "warm_large"]
+    );
+    // Busy instances are never evicted, even when the budget stays exceeded
+    assert_eq!(
+        select_evictions(&candidates, max_idle, Some(0)),
+        ["stale", "warm_large", "warm_small"]
+    );
+}
+
+#[test]
+fn selection_strategies_skip_unhealthy_instances() {
+    let candidate = |name: &str, healthy: bool, verified_secs: u64, free_slots: usize| {
+        InstanceCandidate {
+            instance: Cylo::LandLock("/tmp/test".to_string()).instance(name),
+            healthy,
+            since_verified: Some(Duration::from_secs(verified_secs)),
+            in_flight: (4 - free_slots) as u32,
+            free_slots,
+            capacity: 4,
+        }
+    };
+    let candidates = [
+        candidate("a_busy", true, 5, 0),
+        candidate("b_stale", true, 600, 3),
+        candidate("c_down", false, 0, 4),
+        candidate("d_fresh", true, 1, 2),
+    ];
+
+    assert_eq!(SelectionStrategy::FirstMatch.select(&candidates), Some(0));
+    assert_eq!(SelectionStrategy::LeastLoaded.select(&candidates), Some(1));
+    assert_eq!(SelectionStrategy::MostRecentlyVerified.select(&candidates), Some(3));
+    // A fresh health check outweighs one extra free slot, a full instance
+    // loses to any with room
+    assert_eq!(SelectionStrategy::HealthWeighted.select(&candidates), Some(3));
+    assert_eq!(SelectionStrategy::HealthWeighted.select(&candidates[..2]), Some(1));
+
+    let down = [candidate("c_down", false, 0, 4)];
+    for strategy in [
+        SelectionStrategy::FirstMatch,
+        SelectionStrategy::LeastLoaded,
+        SelectionStrategy::MostRecentlyVerified,
+        SelectionStrategy::HealthWeighted,
+    ] {
+        assert_eq!(strategy.select(&down), None);
+    }
+}
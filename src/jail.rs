@@ -7,9 +7,10 @@ use std::thread;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use log::{info, warn};
+use tracing::{info, warn};
 
 use crate::error::StorageError;
+use crate::logging::targets;
 use crate::state::PipelineEvent;
 
 /// Configuration for the file system jail
@@ -69,32 +70,45 @@ fn is_apparmor_enabled() -> bool {
 pub fn init_jail(config: &JailConfig) -> Result<(), StorageError> {
     // Landlock is preferred but not mandatory
     if !config.enable_landlock {
-        warn!("Landlock restrictions disabled - security will be reduced");
+        warn!(
+            target: targets::SANDBOX,
+            "Landlock restrictions disabled - security will be reduced"
+        );
     }
 
     // Check for AppArmor if enabled
     if config.check_apparmor && is_apparmor_enabled() {
-        warn!("AppArmor is active on this system. This may affect execution permissions.");
-        warn!("If execution fails, you may need to run: sudo aa-complain /usr/bin/cargo");
+        warn!(
+            target: targets::SANDBOX,
+            "AppArmor is active on this system. This may affect execution permissions."
+        );
+        warn!(
+            target: targets::SANDBOX,
+            "If execution fails, you may need to run: sudo aa-complain /usr/bin/cargo"
+        );
     }
 
     // Create the allowed directory if it doesn't exist
     let allowed_dir = config.allowed_dir.to_str().unwrap_or("/tmp/cylo");
     create_dir_all(allowed_dir)
         .with_context(|| format!("Failed to create allowed directory at {allowed_dir}"))?;
-    info!("Allowed directory ensured at {}", allowed_dir);
+    info!(target: targets::SANDBOX, "Allowed directory ensured at {}", allowed_dir);
 
     // Try to set up Landlock rules if enabled
     if config.enable_landlock {
         match apply_landlock_restrictions(allowed_dir) {
-            Ok(_) => info!("Landlock restrictions applied successfully"),
+            Ok(_) => info!(target: targets::SANDBOX, "Landlock restrictions applied successfully"),
             Err(e) => warn!(
+                target: targets::SANDBOX,
                 "Failed to apply Landlock restrictions: {}. Continuing with reduced security.",
                 e
             ),
         }
     } else {
-        warn!("Landlock restrictions not applied - running with reduced security");
+        warn!(
+            target: targets::SANDBOX,
+            "Landlock restrictions not applied - running with reduced security"
+        );
     }
 
     Ok(())
@@ -135,15 +149,21 @@ fn apply_landlock_restrictions(allowed_dir: &str) -> Result<(), StorageError> {
         // Verify that Landlock is fully enforced
         match status.ruleset {
             RulesetStatus::FullyEnforced => {
-                info!("Landlock is fully on—locked to {}", allowed_dir);
+                info!(target: targets::SANDBOX, "Landlock is fully on—locked to {}", allowed_dir);
                 Ok(())
             }
             RulesetStatus::PartiallyEnforced => {
-                warn!("Landlock is only partly enforced - security may be reduced");
+                warn!(
+                    target: targets::SANDBOX,
+                    "Landlock is only partly enforced - security may be reduced"
+                );
                 Ok(()) // Continue anyway
             }
             RulesetStatus::NotEnforced => {
-                warn!("Landlock failed to enforce restrictions - continuing with reduced security");
+                warn!(
+                    target: targets::SANDBOX,
+                    "Landlock failed to enforce restrictions - continuing with reduced security"
+                );
                 Ok(()) // Continue anyway
             }
         }
@@ -152,6 +172,7 @@ fn apply_landlock_restrictions(allowed_dir: &str) -> Result<(), StorageError> {
     #[cfg(not(all(target_os = "linux", feature = "landlock")))]
     {
         warn!(
+            target: targets::SANDBOX,
             "Landlock is not available. Running with reduced security on {}",
             allowed_dir
         );
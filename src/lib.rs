@@ -6,6 +6,9 @@
 //! - Apple containerization for macOS with Apple Silicon
 //! - `LandLock` sandboxing for Linux with kernel-level security
 //! - `FireCracker` microVMs for ultra-lightweight virtualization
+//! - Docker/Podman containers wherever a container engine is installed
+//! - WebAssembly modules under wasmtime, with CPU time metered as fuel
+//! - Kubernetes Jobs, for running inside a cluster without a node-local sandbox
 //!
 //! Features:
 //! - Zero allocation in hot paths
@@ -42,21 +45,106 @@ pub use execution_env::{
     Cylo, CyloError, CyloInstance, CyloResult, validate_environment_spec, validate_instance_name,
 };
 
+pub mod ids;
+pub use ids::{NameTarget, validate_name};
+
+pub mod logging;
+pub use logging::{LogConfig, LogLevel};
+
 // ============================================================================
 // Backend implementations and traits
 // ============================================================================
 
 pub mod backends;
 pub use backends::{
+    Arch,
+    ArchiveFormat,
+    ArchiveLimits,
+    BackendCapabilities,
     // Backend implementations
     BackendConfig,
+    BackendFactory,
+    Blob,
+    BlobFile,
+    BlobStore,
+    BlockingPoolStats,
+    BudgetLimits,
+    BudgetUsage,
+    CompilationPhase,
+    CompilerOptions,
+    CpuSet,
+    CrashReport,
+    DesktopAccess,
+    DockerBackend,
+    DnsPolicy,
+    ErrorFrame,
+    EnvironmentProfile,
     // Trait
     ExecutionBackend,
+    ExecutionBudget,
+    ExecutionCost,
     ExecutionRequest,
     ExecutionResult,
+    ExpectationVerdict,
+    Expectations,
+    GitCheckout,
+    GitCredentials,
+    HealthCache,
+    HealthCacheConfig,
+    HealthCheckLevel,
     HealthStatus,
+    HostOffer,
+    ImageReference,
+    ImageStore,
+    InputStream,
+    InstanceMetrics,
+    IsolationLevel,
+    K8sJobBackend,
+    OptLevel,
+    OutputArchive,
+    OutputBlobs,
+    OutputChunk,
+    OutputSink,
+    OutputStream,
+    PluginManifest,
+    PluginPath,
+    PluginRequest,
+    PluginResponse,
+    PostProcessor,
+    ProcessedOutput,
+    ProgressReporter,
+    ProvisioningEvent,
+    ProvisioningLimits,
+    ProvisioningStage,
+    RawOutput,
+    RegistryAuth,
+    RegistryCredentials,
+    ResultSet,
+    RustEdition,
+    SandboxState,
+    SecurityReport,
+    SqlEngine,
+    SqlOptions,
+    Traceback,
+    TracebackLanguage,
+    Transcript,
+    TranscriptEvent,
+    TranscriptRecorder,
+    TranscriptStream,
+    VirtualClock,
+    Volume,
+    VolumeMount,
+    VolumeSnapshot,
+    VolumeStore,
+    WasmBackend,
+    WorkspaceArchive,
     // Factory function
     create_backend,
+    executor_identity,
+    load_backend_plugin,
+    register_backend,
+    registered_backends,
+    unregister_backend,
 };
 // Platform-specific backends
 #[cfg(target_os = "macos")]
@@ -64,6 +152,9 @@ pub use backends::AppleBackend;
 #[cfg(target_os = "linux")]
 pub use backends::{FireCrackerBackend, LandLockBackend};
 
+// Conformance suite any ExecutionBackend implementation can be run against
+pub mod backend_conformance;
+
 // ============================================================================
 // Platform detection and capabilities
 // ============================================================================
@@ -76,6 +167,8 @@ pub use platform::{
     OperatingSystem,
     PerformanceHints,
     PlatformInfo,
+    backend_availability,
+    detected_backends,
     get_available_backends,
     get_recommended_backend,
     has_kvm,
@@ -89,14 +182,17 @@ pub use platform::{
 // ============================================================================
 
 pub mod error;
-pub use error::{ExecError, StorageError};
+pub use error::{Error, ErrorCategory, ExecError, StorageError};
 
 // ============================================================================
 // Configuration and utilities
 // ============================================================================
 
 pub mod config;
-pub use config::{FileSystem, RamdiskConfig};
+pub use config::{
+    FileSystem, LinuxRamdiskStrategy, RamdiskConfig, RamdiskProviderStatus,
+    WindowsRamdiskProvider,
+};
 
 pub mod platform_utils;
 pub use platform_utils::set_executable;
@@ -105,7 +201,10 @@ pub mod exec;
 pub use exec::{exec_bash, exec_go, exec_js, exec_python, exec_rust};
 
 pub mod ramdisk;
-pub use ramdisk::{create_ramdisk, create_secure_ramdisk, get_watched_dir, remove_ramdisk};
+pub use ramdisk::{
+    create_ramdisk, create_secure_ramdisk, get_watched_dir, remove_ramdisk,
+    windows_ramdisk_providers,
+};
 
 pub mod metadata;
 pub use metadata::MetadataManager;
@@ -125,6 +224,23 @@ pub use firecracker::{FirecrackerVM, create_firecracker_environment, is_firecrac
 pub mod task;
 pub use task::{ExecutionPool, ExecutionTask};
 
+pub mod reaper;
+pub use reaper::{Reaper, global_reaper};
+
+pub mod results;
+pub use results::{DiffOptions, ResultDiff};
+
+pub mod recovery;
+pub use recovery::{RecoveryPolicy, RecoveryReport};
+
+pub mod hardening;
+pub use hardening::{HardeningLevel, helper_hardening, set_helper_hardening};
+
+#[cfg(feature = "toolchains")]
+pub mod toolchains;
+#[cfg(feature = "toolchains")]
+pub use toolchains::{Remediation, ToolchainManager, remediation_for};
+
 // Platform-specific modules
 #[cfg(target_os = "macos")]
 pub mod macos;
@@ -141,13 +257,32 @@ pub mod windows;
 #[cfg(target_os = "windows")]
 pub use windows::WindowsRamdisk;
 
+// ============================================================================
+// Execution routing and orchestration
+// ============================================================================
+
+pub mod executor;
+pub use executor::{
+    BackendPreferences, BackendReadiness, ConfigWatcher, CostModel, CrashLoopConfig, CyloExecutor,
+    DependencyConfig, ExecutionMetrics, ExecutionMiddleware, ExecutionPlan, ExecutionSession,
+    ExecutorConfig,
+    HostGuardConfig, HttpWorker, LanguagePreferences, LanguageProfile, LanguageReadiness,
+    MetricsSnapshot, MetricsStoreConfig, OptimizationConfig, Phase, Pipeline, PipelineResult,
+    PipelineStep, PolicyVerdict, RateCard, ReadinessReport, RecordedExecution, RemoteWorker,
+    ReplayBundle, RequestTemplate,
+    RoutingStrategy, Schedule, ScheduledJob, SessionSnapshot, StepOutcome, TaskStats, TenantUsage,
+    WorkerAdvertisement,
+    create_executor, global_executor, init_global_executor,
+};
+
 // ============================================================================
 // Global instance manager
 // ============================================================================
 
 pub mod instance_manager;
 pub use instance_manager::{
-    InstanceManager, global_instance_manager, init_global_instance_manager,
+    InstanceCandidate, InstanceManager, InstanceSelector, SelectionStrategy,
+    global_instance_manager, init_global_instance_manager,
 };
 // ============================================================================
 // Asynchronous task utilities
@@ -155,7 +290,9 @@ pub use instance_manager::{
 use serde::{Deserialize, Serialize};
 
 // Use our own async task implementations
-pub use crate::async_task::{AsyncTask, AsyncTaskBuilder};
+pub use crate::async_task::{
+    AsyncTask, AsyncTaskBuilder, EventSender, EventStream, RecvError, StreamStats, event_stream,
+};
 
 // ============================================================================
 // Performance monitoring and diagnostics
@@ -164,7 +301,10 @@ pub use crate::async_task::{AsyncTask, AsyncTaskBuilder};
 /// Get comprehensive platform and backend diagnostics
 pub fn get_diagnostics() -> AsyncTask<DiagnosticsReport> {
     AsyncTaskBuilder::new(async move {
-        let platform_info = platform::detect_platform();
+        // Detection shells out on first use, so it runs off the runtime
+        let platform_info = platform::detect_platform_async()
+            .await
+            .unwrap_or_else(|_| platform::detect_platform());
         let available_backends = get_available_backends();
         let manager = global_instance_manager();
 
@@ -175,12 +315,17 @@ pub fn get_diagnostics() -> AsyncTask<DiagnosticsReport> {
         };
 
         let instance_list = manager.list_instances().unwrap_or_default();
+        let instance_metrics = match manager.collect_instance_metrics().await {
+            Ok(Ok(metrics)) => metrics,
+            _ => std::collections::HashMap::new(),
+        };
 
         DiagnosticsReport {
             platform: platform_info.clone(),
             available_backends,
             backend_health: health_results,
             active_instances: instance_list,
+            instance_metrics,
             performance_hints: platform_info.performance.clone(),
         }
     })
@@ -198,6 +343,8 @@ pub struct DiagnosticsReport {
     pub backend_health: std::collections::HashMap<String, HealthStatus>,
     /// Currently active instances
     pub active_instances: Vec<String>,
+    /// Live memory and CPU of instances running VMs or containers
+    pub instance_metrics: std::collections::HashMap<String, InstanceMetrics>,
     /// Performance optimization hints
     pub performance_hints: PerformanceHints,
 }
@@ -206,37 +353,4 @@ pub struct DiagnosticsReport {
 // AsyncTask module - simple wrapper around tokio for backend compatibility
 // ============================================================================
 
-pub mod async_task {
-    /// AsyncTask is a type alias for tokio::task::JoinHandle
-    pub type AsyncTask<T> = tokio::task::JoinHandle<T>;
-
-    /// Simple AsyncTaskBuilder for fluent construction
-    pub struct AsyncTaskBuilder<F> {
-        future: F,
-    }
-
-    impl<F, T> AsyncTaskBuilder<F>
-    where
-        F: std::future::Future<Output = T> + Send + 'static,
-        T: Send + 'static,
-    {
-        /// Create a new AsyncTaskBuilder
-        pub fn new(future: F) -> Self {
-            Self { future }
-        }
-
-        /// Spawn the task and return the AsyncTask handle
-        pub fn spawn(self) -> AsyncTask<T> {
-            tokio::spawn(self.future)
-        }
-    }
-
-    /// Convenience function to spawn an async task
-    pub fn spawn_async<F, T>(future: F) -> AsyncTask<T>
-    where
-        F: std::future::Future<Output = T> + Send + 'static,
-        T: Send + 'static,
-    {
-        tokio::spawn(future)
-    }
-}
+pub mod async_task;
@@ -1,5 +1,6 @@
 use crate::error::StorageError;
-use log::{error, info};
+use crate::logging::targets;
+use tracing::{error, info};
 use std::fs;
 use std::os::unix::fs::{MetadataExt, PermissionsExt};
 use std::path::{Path, PathBuf};
@@ -65,24 +66,35 @@ impl DirectoryManager {
         if !parent_exists || !parent_writable {
             let (user, group) = Self::get_current_user_group();
 
-            info!("\nSecure code execution requires creating an isolated ramdisk environment.");
-            info!("The following directory needs to be created:");
             info!(
+                target: targets::RAMDISK,
+                "\nSecure code execution requires creating an isolated ramdisk environment."
+            );
+            info!(target: targets::RAMDISK, "The following directory needs to be created:");
+            info!(
+                target: targets::RAMDISK,
                 "- {}: [exists: {}] [writable: {}]",
                 parent_dir.display(),
                 if parent_exists { "yes" } else { "no" },
                 if parent_writable { "yes" } else { "no" }
             );
 
-            info!("This requires elevated privileges to execute the following command:");
             info!(
+                target: targets::RAMDISK,
+                "This requires elevated privileges to execute the following command:"
+            );
+            info!(
+                target: targets::RAMDISK,
                 "    sudo mkdir -p {} && sudo chown {}:{} {}",
                 mount_point.display(),
                 user,
                 group,
                 parent_dir.display()
             );
-            info!("This operation provides secure isolation for the code you're about to run.");
+            info!(
+                target: targets::RAMDISK,
+                "This operation provides secure isolation for the code you're about to run."
+            );
 
             // Try to execute the command with sudo
             let mkdir_result = Command::new("sudo")
@@ -91,7 +103,7 @@ impl DirectoryManager {
 
             if let Ok(status) = mkdir_result {
                 if status.success() {
-                    info!("Successfully created directory with sudo");
+                    info!(target: targets::RAMDISK, "Successfully created directory with sudo");
 
                     // Now set permissions
                     let chown_cmd = format!("{}:{}", user, group);
@@ -101,7 +113,10 @@ impl DirectoryManager {
 
                     if let Ok(status) = chown_result {
                         if status.success() {
-                            info!("Successfully set permissions with sudo");
+                            info!(
+                                target: targets::RAMDISK,
+                                "Successfully set permissions with sudo"
+                            );
                             return Ok(());
                         }
                     }
@@ -139,7 +154,7 @@ impl DirectoryManager {
                 "The application will then securely mount a ramdisk for code execution.\n",
             );
 
-            error!("{}", error_msg);
+            error!(target: targets::RAMDISK, "{}", error_msg);
             return Err(StorageError::InsufficientPrivileges(error_msg));
         }
 
@@ -153,21 +168,24 @@ impl DirectoryManager {
 
     /// Create the mount point directory, trying with sudo if necessary.
     fn create_mount_point(mount_point: &Path) -> Result<(), StorageError> {
-        info!("Creating mount point at {}", mount_point.display());
+        info!(target: targets::RAMDISK, "Creating mount point at {}", mount_point.display());
 
         match fs::create_dir_all(mount_point) {
             Ok(_) => {
-                info!("Mount point directory created successfully");
+                info!(target: targets::RAMDISK, "Mount point directory created successfully");
                 Ok(())
             }
             Err(e) => {
-                error!("Failed to create mount point directory: {}", e);
+                error!(target: targets::RAMDISK, "Failed to create mount point directory: {}", e);
 
                 let (user, group) = Self::get_current_user_group();
 
-                info!("\nSecure code execution requires creating an isolated ramdisk environment.");
-                info!("Failed to create mount point directory: {}", e);
-                info!("Trying with elevated privileges...");
+                info!(
+                    target: targets::RAMDISK,
+                    "\nSecure code execution requires creating an isolated ramdisk environment."
+                );
+                info!(target: targets::RAMDISK, "Failed to create mount point directory: {}", e);
+                info!(target: targets::RAMDISK, "Trying with elevated privileges...");
 
                 // Try with sudo
                 let mkdir_result = Command::new("sudo")
@@ -176,7 +194,7 @@ impl DirectoryManager {
 
                 if let Ok(status) = mkdir_result {
                     if status.success() {
-                        info!("Successfully created directory with sudo");
+                        info!(target: targets::RAMDISK, "Successfully created directory with sudo");
 
                         // Set permissions
                         let chown_cmd = format!("{}:{}", user, group);
@@ -186,8 +204,14 @@ impl DirectoryManager {
 
                         if let Ok(status) = chown_result {
                             if status.success() {
-                                info!("Successfully set permissions with sudo");
-                                info!("Mount point directory created successfully with sudo");
+                                info!(
+                                    target: targets::RAMDISK,
+                                    "Successfully set permissions with sudo"
+                                );
+                                info!(
+                                    target: targets::RAMDISK,
+                                    "Mount point directory created successfully with sudo"
+                                );
                                 return Ok(());
                             }
                         }
@@ -223,23 +247,32 @@ impl DirectoryManager {
         if !parent_exists || !parent_writable || !mount_exists || !mount_writable {
             let (user, group) = Self::get_current_user_group();
 
-            info!("\nSecure code execution requires creating an isolated ramdisk environment.");
-            info!("The following directories need to be created:");
             info!(
+                target: targets::RAMDISK,
+                "\nSecure code execution requires creating an isolated ramdisk environment."
+            );
+            info!(target: targets::RAMDISK, "The following directories need to be created:");
+            info!(
+                target: targets::RAMDISK,
                 "- {}: [exists: {}] [writable: {}]",
                 parent_dir.display(),
                 if parent_exists { "yes" } else { "no" },
                 if parent_writable { "yes" } else { "no" }
             );
             info!(
+                target: targets::RAMDISK,
                 "- {}: [exists: {}] [writable: {}]",
                 mount_point.display(),
                 if mount_exists { "yes" } else { "no" },
                 if mount_writable { "yes" } else { "no" }
             );
 
-            info!("This requires elevated privileges to execute the following command:");
             info!(
+                target: targets::RAMDISK,
+                "This requires elevated privileges to execute the following command:"
+            );
+            info!(
+                target: targets::RAMDISK,
                 "    sudo mkdir -p {} && sudo chown {}:{} {} {}",
                 mount_point.display(),
                 user,
@@ -247,7 +280,10 @@ impl DirectoryManager {
                 parent_dir.display(),
                 mount_point.display()
             );
-            info!("This operation provides secure isolation for the code you're about to run.");
+            info!(
+                target: targets::RAMDISK,
+                "This operation provides secure isolation for the code you're about to run."
+            );
 
             // Try to execute the command with sudo
             let mkdir_result = Command::new("sudo")
@@ -256,7 +292,7 @@ impl DirectoryManager {
 
             if let Ok(status) = mkdir_result {
                 if status.success() {
-                    info!("Successfully created directory with sudo");
+                    info!(target: targets::RAMDISK, "Successfully created directory with sudo");
 
                     // Set permissions on both directories
                     let chown_cmd = format!("{}:{}", user, group);
@@ -271,7 +307,10 @@ impl DirectoryManager {
 
                     if let Ok(status) = chown_result {
                         if status.success() {
-                            info!("Successfully set permissions with sudo");
+                            info!(
+                                target: targets::RAMDISK,
+                                "Successfully set permissions with sudo"
+                            );
                             return Ok(());
                         }
                     }
@@ -313,7 +352,7 @@ impl DirectoryManager {
             error_msg
                 .push_str("After running this command, try executing this application again.\n");
 
-            error!("{}", error_msg);
+            error!(target: targets::RAMDISK, "{}", error_msg);
             return Err(StorageError::InsufficientPrivileges(error_msg));
         }
 
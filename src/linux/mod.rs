@@ -1,7 +1,9 @@
 use crate::error::StorageError;
+use crate::logging::targets;
 use crate::sandbox::safe_path_to_string;
-use log::{error, info};
-use std::path::Path;
+use crate::config::LinuxRamdiskStrategy;
+use tracing::{error, info, warn};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::{fs};
 
@@ -11,6 +13,7 @@ mod mount;
 mod namespace_create;
 mod privilege;
 mod sudo_create;
+mod visibility;
 
 pub use detection::EnvironmentDetector;
 pub use directory::DirectoryManager;
@@ -20,8 +23,10 @@ pub use privilege::PrivilegeManager;
 /// Linux-specific ramdisk implementation using tmpfs and Linux namespaces.
 ///
 /// This implementation provides secure, isolated ramdisk storage for code execution
-/// on Linux systems. It attempts to use unprivileged user namespaces first for
-/// maximum security and portability, falling back to sudo-based creation when necessary.
+/// on Linux systems. How the tmpfs is made visible to other processes is chosen per
+/// host (see `LinuxRamdiskStrategy`): a shared host mount when privileges allow it,
+/// otherwise a namespace kept alive by a holder process and entered with `nsenter`,
+/// and as a last resort a namespace only the creating process sees.
 pub struct LinuxRamdisk;
 
 impl LinuxRamdisk {
@@ -36,7 +41,46 @@ impl LinuxRamdisk {
     /// namespace_create::create_with_namespaces which handles all the logic
     /// including fallbacks to sudo-based creation if needed.
     pub fn create(config: &crate::config::RamdiskConfig) -> Result<(), StorageError> {
-        namespace_create::create_with_namespaces(config)
+        let strategy = Self::strategy();
+        info!(
+            target: targets::RAMDISK,
+            "Creating ramdisk with {} strategy: {}",
+            strategy,
+            strategy.visibility()
+        );
+
+        let created = match strategy {
+            LinuxRamdiskStrategy::SharedMount => visibility::create_shared(config),
+            LinuxRamdiskStrategy::NamespaceHolder => visibility::create_with_holder(config),
+            LinuxRamdiskStrategy::ProcessNamespace => {
+                return namespace_create::create_with_namespaces(config);
+            }
+        };
+        created.or_else(|e| {
+            warn!(
+                target: targets::RAMDISK,
+                "{} strategy failed: {}; falling back to a process-local namespace",
+                strategy,
+                e
+            );
+            namespace_create::create_with_namespaces(config)
+        })
+    }
+
+    /// Strategy `create` uses on this host.
+    pub fn strategy() -> LinuxRamdiskStrategy {
+        visibility::select_strategy()
+    }
+
+    /// Command that runs `program` where the ramdisk at `mount_point` is visible,
+    /// entering the holder's namespaces with `nsenter` when there is a holder.
+    pub fn command(mount_point: &Path, program: &str) -> Command {
+        visibility::command(mount_point, program)
+    }
+
+    /// Path this process reaches the ramdisk at `mount_point` under.
+    pub fn host_path(mount_point: &Path) -> PathBuf {
+        visibility::host_path(mount_point)
     }
 }
 
@@ -46,6 +90,9 @@ impl crate::platform::RamdiskPlatform for LinuxRamdisk {
     }
 
     fn is_mounted(&self, mount_point: &Path) -> Result<bool, StorageError> {
+        if visibility::holder_pid(mount_point).is_some() {
+            return Ok(true);
+        }
         if !mount_point.exists() {
             return Ok(false);
         }
@@ -63,9 +110,14 @@ impl crate::platform::RamdiskPlatform for LinuxRamdisk {
     }
 
     fn remove(&self, mount_point: &Path) -> Result<(), StorageError> {
+        if visibility::remove_holder(mount_point)? {
+            info!(target: targets::RAMDISK, "Ramdisk removal completed successfully");
+            return Ok(());
+        }
+
         let mount_point_str = safe_path_to_string(mount_point)
             .map_err(|e| StorageError::PathInvalid(e.to_string()))?;
-        info!("Attempting to unmount {}", mount_point_str);
+        info!(target: targets::RAMDISK, "Attempting to unmount {}", mount_point_str);
 
         // First try without sudo
         let status = Command::new("umount").arg(mount_point).status();
@@ -77,11 +129,11 @@ impl crate::platform::RamdiskPlatform for LinuxRamdisk {
 
         // If that fails, try with sudo
         if !unmount_success {
-            info!("Regular unmount failed, trying with sudo");
+            info!(target: targets::RAMDISK, "Regular unmount failed, trying with sudo");
             let sudo_result = PrivilegeManager::run_with_sudo("umount", &[&mount_point_str])?;
 
             if !sudo_result {
-                error!("Unmount command failed even with sudo");
+                error!(target: targets::RAMDISK, "Unmount command failed even with sudo");
                 return Err(StorageError::CommandFailed(
                     "Failed to unmount ramdisk".to_string(),
                 ));
@@ -89,15 +141,16 @@ impl crate::platform::RamdiskPlatform for LinuxRamdisk {
         }
 
         info!(
+            target: targets::RAMDISK,
             "Successfully unmounted {}, cleaning up directory",
             mount_point.display()
         );
         fs::remove_dir_all(mount_point).map_err(|e| {
-            error!("Failed to remove ramdisk directory: {}", e);
+            error!(target: targets::RAMDISK, "Failed to remove ramdisk directory: {}", e);
             StorageError::Io(e)
         })?;
 
-        info!("Ramdisk removal completed successfully");
+        info!(target: targets::RAMDISK, "Ramdisk removal completed successfully");
         Ok(())
     }
 }
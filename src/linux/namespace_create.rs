@@ -1,6 +1,7 @@
 use crate::config::RamdiskConfig;
 use crate::error::StorageError;
-use log::{error, info, warn};
+use crate::logging::targets;
+use tracing::{error, info, warn};
 use nix::errno::Errno;
 use nix::libc::{chdir, mount, CLONE_NEWNS, CLONE_NEWUSER};
 use std::ffi::CString;
@@ -30,18 +31,24 @@ use super::sudo_create;
 pub fn create_with_namespaces(config: &RamdiskConfig) -> Result<(), StorageError> {
     // Check for container/security restrictions
     if EnvironmentDetector::is_in_container() {
-        warn!("Detected container environment - namespace operations may be restricted");
+        warn!(
+            target: targets::RAMDISK,
+            "Detected container environment - namespace operations may be restricted"
+        );
     }
 
     if EnvironmentDetector::is_apparmor_active() {
-        warn!("AppArmor is active and may restrict mount operations");
+        warn!(target: targets::RAMDISK, "AppArmor is active and may restrict mount operations");
     }
 
     // Step 1: Try to create a sandbox (user namespace + mount namespace) without privileges
-    info!("Attempting to create user and mount namespaces with unshare()");
+    info!(
+        target: targets::RAMDISK,
+        "Attempting to create user and mount namespaces with unshare()"
+    );
     if unsafe { nix::libc::unshare(CLONE_NEWUSER | CLONE_NEWNS) } != 0 {
         let errno_val = Errno::last();
-        error!("Failed to create namespaces: errno: {:?}", errno_val);
+        error!(target: targets::RAMDISK, "Failed to create namespaces: errno: {:?}", errno_val);
 
         // Handle specific error conditions
         return handle_namespace_error(errno_val, config);
@@ -60,6 +67,7 @@ pub fn create_with_namespaces(config: &RamdiskConfig) -> Result<(), StorageError
     setup_watched_dir(&config.mount_point)?;
 
     info!(
+        target: targets::RAMDISK,
         "Ramdisk created and configured successfully at {}",
         config.mount_point.display()
     );
@@ -70,17 +78,29 @@ pub fn create_with_namespaces(config: &RamdiskConfig) -> Result<(), StorageError
 fn handle_namespace_error(errno_val: Errno, config: &RamdiskConfig) -> Result<(), StorageError> {
     match errno_val {
         Errno::EPERM => {
-            info!("Operation not permitted - user namespaces are disabled");
-            info!("Attempting to use sudo for ramdisk creation...");
+            info!(
+                target: targets::RAMDISK,
+                "Operation not permitted - user namespaces are disabled"
+            );
+            info!(target: targets::RAMDISK, "Attempting to use sudo for ramdisk creation...");
 
             // Try to enable user namespaces with sudo
             if PrivilegeManager::run_with_sudo("sysctl", &["-w", "kernel.unprivileged_userns_clone=1"])? {
-                info!("Successfully enabled unprivileged user namespaces");
+                info!(
+                    target: targets::RAMDISK,
+                    "Successfully enabled unprivileged user namespaces"
+                );
                 // Try again with the newly enabled setting
                 if unsafe { nix::libc::unshare(CLONE_NEWUSER | CLONE_NEWNS) } != 0 {
-                    error!("Still failed to create namespaces after enabling unprivileged user namespaces");
+                    error!(
+                        target: targets::RAMDISK,
+                        "Still failed to create namespaces after enabling unprivileged user namespaces"
+                    );
                 } else {
-                    info!("Successfully created namespaces after enabling unprivileged user namespaces");
+                    info!(
+                        target: targets::RAMDISK,
+                        "Successfully created namespaces after enabling unprivileged user namespaces"
+                    );
                     // Continue with setup
                     setup_namespace_mappings()?;
                     DirectoryManager::ensure_mount_directories(&config.mount_point)?;
@@ -91,29 +111,38 @@ fn handle_namespace_error(errno_val: Errno, config: &RamdiskConfig) -> Result<()
             }
 
             // Fall back to sudo-based creation
-            info!("Attempting to create ramdisk directly with sudo");
+            info!(target: targets::RAMDISK, "Attempting to create ramdisk directly with sudo");
             if sudo_create::create_with_sudo(config)? {
-                info!("Successfully created ramdisk with sudo");
+                info!(target: targets::RAMDISK, "Successfully created ramdisk with sudo");
                 return Ok(());
             }
 
-            error!("Could not create ramdisk even with sudo");
+            error!(target: targets::RAMDISK, "Could not create ramdisk even with sudo");
             Err(StorageError::InsufficientPrivileges(
                 "Could not create ramdisk even with sudo. Secure execution requires ramdisk isolation.".into()
             ))
         }
 
         Errno::EACCES => {
-            error!("Permission denied - AppArmor or seccomp is blocking namespace creation");
-            info!("Attempting to configure AppArmor with sudo...");
+            error!(
+                target: targets::RAMDISK,
+                "Permission denied - AppArmor or seccomp is blocking namespace creation"
+            );
+            info!(target: targets::RAMDISK, "Attempting to configure AppArmor with sudo...");
 
             if PrivilegeManager::run_with_sudo("aa-complain", &["/usr/bin/cargo"])? {
-                info!("Successfully set AppArmor to complain mode");
+                info!(target: targets::RAMDISK, "Successfully set AppArmor to complain mode");
                 // Try again with the new AppArmor setting
                 if unsafe { nix::libc::unshare(CLONE_NEWUSER | CLONE_NEWNS) } != 0 {
-                    error!("Still failed to create namespaces after configuring AppArmor");
+                    error!(
+                        target: targets::RAMDISK,
+                        "Still failed to create namespaces after configuring AppArmor"
+                    );
                 } else {
-                    info!("Successfully created namespaces after configuring AppArmor");
+                    info!(
+                        target: targets::RAMDISK,
+                        "Successfully created namespaces after configuring AppArmor"
+                    );
                     // Continue with setup
                     setup_namespace_mappings()?;
                     DirectoryManager::ensure_mount_directories(&config.mount_point)?;
@@ -124,7 +153,7 @@ fn handle_namespace_error(errno_val: Errno, config: &RamdiskConfig) -> Result<()
             }
 
             // Fall back to sudo-based creation
-            info!("Attempting to create ramdisk directly with sudo");
+            info!(target: targets::RAMDISK, "Attempting to create ramdisk directly with sudo");
             if sudo_create::create_with_sudo(config)? {
                 return Ok(());
             }
@@ -135,11 +164,17 @@ fn handle_namespace_error(errno_val: Errno, config: &RamdiskConfig) -> Result<()
         }
 
         Errno::EINVAL => {
-            error!("Invalid argument - this could be due to kernel configuration or nested container");
-            error!("Linux kernel 5.11+ is recommended for full namespace support");
+            error!(
+                target: targets::RAMDISK,
+                "Invalid argument - this could be due to kernel configuration or nested container"
+            );
+            error!(
+                target: targets::RAMDISK,
+                "Linux kernel 5.11+ is recommended for full namespace support"
+            );
 
             // Try direct mount with sudo
-            info!("Attempting to create ramdisk directly with sudo");
+            info!(target: targets::RAMDISK, "Attempting to create ramdisk directly with sudo");
             if sudo_create::create_with_sudo(config)? {
                 return Ok(());
             }
@@ -151,7 +186,7 @@ fn handle_namespace_error(errno_val: Errno, config: &RamdiskConfig) -> Result<()
 
         _ => {
             // Try direct mount with sudo as a last resort
-            info!("Attempting to create ramdisk directly with sudo");
+            info!(target: targets::RAMDISK, "Attempting to create ramdisk directly with sudo");
             if sudo_create::create_with_sudo(config)? {
                 return Ok(());
             }
@@ -169,16 +204,16 @@ fn handle_namespace_error(errno_val: Errno, config: &RamdiskConfig) -> Result<()
 /// This tells the kernel to map the current user's UID/GID to root (0)
 /// within the namespace, allowing unprivileged mount operations.
 fn setup_namespace_mappings() -> Result<(), StorageError> {
-    info!("Setting up UID/GID mappings for user namespace");
+    info!(target: targets::RAMDISK, "Setting up UID/GID mappings for user namespace");
     let uid = nix::unistd::geteuid().as_raw();
     let gid = nix::unistd::getegid().as_raw();
     let uid_map = format!("0 {} 1", uid);
     let gid_map = format!("0 {} 1", gid);
 
     match File::create("/proc/self/uid_map").and_then(|mut f| f.write_all(uid_map.as_bytes())) {
-        Ok(_) => info!("UID mapping successful: {}", uid_map),
+        Ok(_) => info!(target: targets::RAMDISK, "UID mapping successful: {}", uid_map),
         Err(e) => {
-            error!("Failed to set UID mapping: {}", e);
+            error!(target: targets::RAMDISK, "Failed to set UID mapping: {}", e);
             return Err(StorageError::Other(anyhow::anyhow!(
                 "UID map failed: {}",
                 e
@@ -187,9 +222,9 @@ fn setup_namespace_mappings() -> Result<(), StorageError> {
     }
 
     match File::create("/proc/self/setgroups").and_then(|mut f| f.write_all(b"deny")) {
-        Ok(_) => info!("Setgroups deny successful"),
+        Ok(_) => info!(target: targets::RAMDISK, "Setgroups deny successful"),
         Err(e) => {
-            error!("Failed to deny setgroups: {}", e);
+            error!(target: targets::RAMDISK, "Failed to deny setgroups: {}", e);
             return Err(StorageError::Other(anyhow::anyhow!(
                 "Setgroups failed: {}",
                 e
@@ -198,9 +233,9 @@ fn setup_namespace_mappings() -> Result<(), StorageError> {
     }
 
     match File::create("/proc/self/gid_map").and_then(|mut f| f.write_all(gid_map.as_bytes())) {
-        Ok(_) => info!("GID mapping successful: {}", gid_map),
+        Ok(_) => info!(target: targets::RAMDISK, "GID mapping successful: {}", gid_map),
         Err(e) => {
-            error!("Failed to set GID mapping: {}", e);
+            error!(target: targets::RAMDISK, "Failed to set GID mapping: {}", e);
             return Err(StorageError::Other(anyhow::anyhow!(
                 "GID map failed: {}",
                 e
@@ -226,6 +261,7 @@ fn mount_tmpfs(config: &RamdiskConfig) -> Result<(), StorageError> {
         .map_err(|e| StorageError::PathInvalid(format!("Size parameter contains null byte: {}", e)))?;
 
     info!(
+        target: targets::RAMDISK,
         "Mounting tmpfs with size {} at {}",
         size,
         mount_point.display()
@@ -242,7 +278,7 @@ fn mount_tmpfs(config: &RamdiskConfig) -> Result<(), StorageError> {
         {
             let err = io::Error::last_os_error();
             let errno_val = Errno::last();
-            error!("Mount failed: {} (errno: {})", err, errno_val);
+            error!(target: targets::RAMDISK, "Mount failed: {} (errno: {})", err, errno_val);
             return Err(StorageError::CommandFailed(format!(
                 "Couldn't mount ramdisk: {}",
                 err
@@ -258,11 +294,11 @@ fn setup_watched_dir(mount_point: &std::path::Path) -> Result<(), StorageError>
     let mp_cstr = CString::new(mount_point.to_str().unwrap_or(""))
         .map_err(|e| StorageError::PathInvalid(format!("Mount point path contains null byte: {}", e)))?;
 
-    info!("Changing directory to {}", mount_point.display());
+    info!(target: targets::RAMDISK, "Changing directory to {}", mount_point.display());
     unsafe {
         if chdir(mp_cstr.as_ptr()) != 0 {
             let err = io::Error::last_os_error();
-            error!("Failed to chdir to ramdisk: {}", err);
+            error!(target: targets::RAMDISK, "Failed to chdir to ramdisk: {}", err);
             return Err(StorageError::Other(anyhow::anyhow!(
                 "Failed to move into ramdisk: {}",
                 err
@@ -270,20 +306,20 @@ fn setup_watched_dir(mount_point: &std::path::Path) -> Result<(), StorageError>
         }
     }
 
-    info!("Creating watched_dir inside ramdisk");
+    info!(target: targets::RAMDISK, "Creating watched_dir inside ramdisk");
     match fs::create_dir("watched_dir") {
-        Ok(_) => info!("Created watched_dir successfully"),
+        Ok(_) => info!(target: targets::RAMDISK, "Created watched_dir successfully"),
         Err(e) => {
-            error!("Failed to create watched_dir in ramdisk: {}", e);
+            error!(target: targets::RAMDISK, "Failed to create watched_dir in ramdisk: {}", e);
             return Err(StorageError::Io(e));
         }
     }
 
-    info!("Setting watched_dir permissions to 0700");
+    info!(target: targets::RAMDISK, "Setting watched_dir permissions to 0700");
     match fs::set_permissions("watched_dir", fs::Permissions::from_mode(0o700)) {
-        Ok(_) => info!("Set watched_dir permissions successfully"),
+        Ok(_) => info!(target: targets::RAMDISK, "Set watched_dir permissions successfully"),
         Err(e) => {
-            error!("Failed to set watched_dir permissions: {}", e);
+            error!(target: targets::RAMDISK, "Failed to set watched_dir permissions: {}", e);
             return Err(StorageError::Io(e));
         }
     }
@@ -1,5 +1,6 @@
 use crate::error::StorageError;
-use log::{info, warn};
+use crate::logging::targets;
+use tracing::{info, warn};
 use std::process::Command;
 
 /// Utilities for running commands with privilege escalation (sudo).
@@ -18,12 +19,12 @@ impl PrivilegeManager {
     /// or Err if there was an error that should be propagated.
     pub fn run_with_sudo(cmd: &str, args: &[&str]) -> Result<bool, StorageError> {
         // First try running without sudo
-        info!("Attempting to run '{}' without sudo first", cmd);
+        info!(target: targets::RAMDISK, "Attempting to run '{}' without sudo first", cmd);
         let result = Command::new(cmd).args(args).output();
 
         if let Ok(output) = result {
             if output.status.success() {
-                info!("Command succeeded without sudo");
+                info!(target: targets::RAMDISK, "Command succeeded without sudo");
                 return Ok(true);
             }
         }
@@ -32,7 +33,7 @@ impl PrivilegeManager {
         let full_cmd = format!("{} {}", cmd, args.join(" "));
 
         // Check if we can use sudo non-interactively
-        info!("Checking if sudo is available non-interactively");
+        info!(target: targets::RAMDISK, "Checking if sudo is available non-interactively");
         let sudo_check = Command::new("sudo")
             .arg("-n") // Non-interactive check
             .arg("true")
@@ -42,7 +43,7 @@ impl PrivilegeManager {
 
         if sudo_available {
             // Try the command with sudo non-interactively
-            info!("Sudo is available, trying command with sudo");
+            info!(target: targets::RAMDISK, "Sudo is available, trying command with sudo");
             let sudo_result = Command::new("sudo")
                 .arg("-n") // Non-interactive mode
                 .arg(cmd)
@@ -52,23 +53,36 @@ impl PrivilegeManager {
             match sudo_result {
                 Ok(output) => {
                     if output.status.success() {
-                        info!("Successfully executed command with sudo: {}", full_cmd);
+                        info!(
+                            target: targets::RAMDISK,
+                            "Successfully executed command with sudo: {}",
+                            full_cmd
+                        );
                         return Ok(true);
                     } else {
                         let stderr = String::from_utf8_lossy(&output.stderr);
-                        warn!("Command failed with sudo: {}", stderr);
+                        warn!(target: targets::RAMDISK, "Command failed with sudo: {}", stderr);
                     }
                 }
                 Err(e) => {
-                    warn!("Failed to execute command with sudo: {}", e);
+                    warn!(target: targets::RAMDISK, "Failed to execute command with sudo: {}", e);
                 }
             }
         } else {
             // Going to need an interactive sudo prompt
-            info!("\nSecure code execution requires creating an isolated ramdisk environment.");
-            info!("This requires elevated privileges to execute the following command:");
-            info!("    sudo {}", full_cmd);
-            info!("This operation provides secure isolation for the code you're about to run.");
+            info!(
+                target: targets::RAMDISK,
+                "\nSecure code execution requires creating an isolated ramdisk environment."
+            );
+            info!(
+                target: targets::RAMDISK,
+                "This requires elevated privileges to execute the following command:"
+            );
+            info!(target: targets::RAMDISK, "    sudo {}", full_cmd);
+            info!(
+                target: targets::RAMDISK,
+                "This operation provides secure isolation for the code you're about to run."
+            );
 
             // Try with interactive sudo
             let sudo_interactive = Command::new("sudo").arg(cmd).args(args).status();
@@ -76,35 +90,39 @@ impl PrivilegeManager {
             match sudo_interactive {
                 Ok(status) => {
                     if status.success() {
-                        info!("Successfully executed command with sudo");
+                        info!(target: targets::RAMDISK, "Successfully executed command with sudo");
                         return Ok(true);
                     } else {
-                        warn!("Command failed with interactive sudo");
+                        warn!(target: targets::RAMDISK, "Command failed with interactive sudo");
                     }
                 }
                 Err(e) => {
-                    warn!("Failed to execute command with interactive sudo: {}", e);
+                    warn!(
+                        target: targets::RAMDISK,
+                        "Failed to execute command with interactive sudo: {}",
+                        e
+                    );
                 }
             }
         }
 
         // If everything failed, try one more time as the current user
-        info!("Trying alternative approach without elevated privileges");
+        info!(target: targets::RAMDISK, "Trying alternative approach without elevated privileges");
         let fallback_result = Command::new(cmd).args(args).output();
 
         match fallback_result {
             Ok(output) => {
                 if output.status.success() {
-                    info!("Command succeeded in fallback mode");
+                    info!(target: targets::RAMDISK, "Command succeeded in fallback mode");
                     Ok(true)
                 } else {
                     let stderr = String::from_utf8_lossy(&output.stderr);
-                    warn!("Fallback command also failed: {}", stderr);
+                    warn!(target: targets::RAMDISK, "Fallback command also failed: {}", stderr);
                     Ok(false)
                 }
             }
             Err(e) => {
-                warn!("Fallback command error: {}", e);
+                warn!(target: targets::RAMDISK, "Fallback command error: {}", e);
                 Ok(false) // Return false instead of error to allow graceful fallback
             }
         }
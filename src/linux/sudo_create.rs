@@ -1,6 +1,7 @@
 use crate::config::RamdiskConfig;
 use crate::error::StorageError;
-use log::{error, info};
+use crate::logging::targets;
+use tracing::{error, info};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 
@@ -23,7 +24,7 @@ pub fn create_with_sudo(config: &RamdiskConfig) -> Result<bool, StorageError> {
     // Ensure directories exist with proper permissions
     DirectoryManager::ensure_sudo_mount_directories(mount_point)?;
 
-    info!("Creating mount point at {}", mount_point.display());
+    info!(target: targets::RAMDISK, "Creating mount point at {}", mount_point.display());
 
     // Mount the tmpfs with sudo
     let size_arg = format!("size={}G", config.size_gb);
@@ -40,7 +41,7 @@ pub fn create_with_sudo(config: &RamdiskConfig) -> Result<bool, StorageError> {
     )?;
 
     if !mount_result {
-        error!("Failed to mount tmpfs with sudo");
+        error!(target: targets::RAMDISK, "Failed to mount tmpfs with sudo");
         return Ok(false);
     }
 
@@ -48,6 +49,7 @@ pub fn create_with_sudo(config: &RamdiskConfig) -> Result<bool, StorageError> {
     setup_watched_dir_with_sudo(mount_point)?;
 
     info!(
+        target: targets::RAMDISK,
         "Ramdisk created and configured successfully with sudo at {}",
         config.mount_point.display()
     );
@@ -60,11 +62,11 @@ pub fn create_with_sudo(config: &RamdiskConfig) -> Result<bool, StorageError> {
 fn setup_watched_dir_with_sudo(mount_point: &std::path::Path) -> Result<(), StorageError> {
     let watched_dir = mount_point.join("watched_dir");
 
-    info!("Creating watched_dir inside ramdisk");
+    info!(target: targets::RAMDISK, "Creating watched_dir inside ramdisk");
     match fs::create_dir(&watched_dir) {
-        Ok(_) => info!("Created watched_dir successfully"),
+        Ok(_) => info!(target: targets::RAMDISK, "Created watched_dir successfully"),
         Err(e) => {
-            error!("Failed to create watched_dir in ramdisk: {}", e);
+            error!(target: targets::RAMDISK, "Failed to create watched_dir in ramdisk: {}", e);
             // Try to unmount since we failed
             let _ = PrivilegeManager::run_with_sudo("umount", &[mount_point.to_str().unwrap_or("")]);
             return Err(StorageError::Io(e));
@@ -72,11 +74,11 @@ fn setup_watched_dir_with_sudo(mount_point: &std::path::Path) -> Result<(), Stor
     }
 
     // Set permissions on watched_dir
-    info!("Setting watched_dir permissions to 0700");
+    info!(target: targets::RAMDISK, "Setting watched_dir permissions to 0700");
     match fs::set_permissions(&watched_dir, fs::Permissions::from_mode(0o700)) {
-        Ok(_) => info!("Set watched_dir permissions successfully"),
+        Ok(_) => info!(target: targets::RAMDISK, "Set watched_dir permissions successfully"),
         Err(e) => {
-            error!("Failed to set watched_dir permissions: {}", e);
+            error!(target: targets::RAMDISK, "Failed to set watched_dir permissions: {}", e);
             // Continue anyway, this is not critical
         }
     }
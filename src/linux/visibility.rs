@@ -0,0 +1,291 @@
+use crate::config::{LinuxRamdiskStrategy, RamdiskConfig};
+use crate::error::StorageError;
+use crate::logging::targets;
+use crate::platform_utils::{
+    create_private_dir, read_private_file, user_state_dir, write_private_file,
+};
+use crate::reaper::process_alive;
+use tracing::{info, warn};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::directory::DirectoryManager;
+use super::privilege::PrivilegeManager;
+use super::sudo_create;
+
+/// How long a namespace holder may take to mount its tmpfs
+const HOLDER_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Script the holder runs inside its namespaces: mount the tmpfs, report
+/// that it is ready, then sleep for as long as the ramdisk should live
+const HOLDER_SCRIPT: &str = "mount -t tmpfs -o \"$1\" none \"$2\" \
+    && mkdir -m 700 \"$2/watched_dir\" && echo ready && exec sleep infinity";
+
+/// What the host allows, as far as choosing a strategy goes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Probes {
+    root: bool,
+    passwordless_sudo: bool,
+    user_namespaces: bool,
+}
+
+impl Probes {
+    fn detect() -> Self {
+        let succeeds = |program: &str, args: &[&str]| {
+            Command::new(program)
+                .args(args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        };
+
+        let root = nix::unistd::geteuid().is_root();
+        Self {
+            root,
+            passwordless_sudo: !root && succeeds("sudo", &["-n", "true"]),
+            // Also proves unshare is installed; nsenter ships with it
+            user_namespaces: succeeds("unshare", &["--user", "--map-root-user", "--mount", "true"]),
+        }
+    }
+
+    fn strategy(self) -> LinuxRamdiskStrategy {
+        if self.root || self.passwordless_sudo {
+            LinuxRamdiskStrategy::SharedMount
+        } else if self.user_namespaces {
+            LinuxRamdiskStrategy::NamespaceHolder
+        } else {
+            LinuxRamdiskStrategy::ProcessNamespace
+        }
+    }
+}
+
+/// Select how ramdisks are created on this host.
+///
+/// A shared host mount is preferred when privileges allow it, then a
+/// namespace holder process, and only then a namespace that just the
+/// creating process sees.
+pub fn select_strategy() -> LinuxRamdiskStrategy {
+    Probes::detect().strategy()
+}
+
+/// Mount the tmpfs in the host namespace and mark it shared, so later
+/// mount namespaces (bwrap sandboxes included) receive it and its changes.
+pub fn create_shared(config: &RamdiskConfig) -> Result<(), StorageError> {
+    if !sudo_create::create_with_sudo(config)? {
+        return Err(StorageError::CommandFailed(
+            "Failed to mount tmpfs in the host namespace".into(),
+        ));
+    }
+
+    let mount_point = config.mount_point.to_str().unwrap_or("");
+    if !PrivilegeManager::run_with_sudo("mount", &["--make-shared", mount_point])? {
+        warn!(
+            target: targets::RAMDISK,
+            "Could not mark {} as a shared mount; namespaces created later may not see changes",
+            mount_point
+        );
+    }
+    Ok(())
+}
+
+/// Mount the tmpfs inside a new user and mount namespace kept alive by a
+/// detached holder process.
+///
+/// The holder outlives this process, so the ramdisk persists until it is
+/// removed; its PID is recorded under the temp directory.
+pub fn create_with_holder(config: &RamdiskConfig) -> Result<(), StorageError> {
+    let mount_point = &config.mount_point;
+    if let Some(pid) = holder_pid(mount_point) {
+        info!(
+            target: targets::RAMDISK,
+            "Namespace holder {} already serves {}",
+            pid,
+            mount_point.display()
+        );
+        return Ok(());
+    }
+
+    DirectoryManager::ensure_mount_directories(mount_point)?;
+
+    let mut child = Command::new("unshare")
+        .args(["--user", "--map-root-user", "--mount", "--propagation", "private", "--"])
+        .args(["sh", "-c", HOLDER_SCRIPT, "cylo-ramdisk-holder"])
+        .arg(format!("size={}G", config.size_gb))
+        .arg(mount_point)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Its own process group, so terminal signals aimed at us spare it
+        .process_group(0)
+        .spawn()
+        .map_err(|e| {
+            StorageError::CommandFailed(format!("Failed to start namespace holder: {}", e))
+        })?;
+
+    let (ready_tx, ready_rx) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        std::thread::spawn(move || {
+            let mut line = String::new();
+            let read = BufReader::new(stdout).read_line(&mut line);
+            let _ = ready_tx.send(read.is_ok() && line.trim() == "ready");
+        });
+    }
+
+    if !ready_rx.recv_timeout(HOLDER_READY_TIMEOUT).unwrap_or(false) {
+        let _ = child.kill();
+        let stderr = child
+            .wait_with_output()
+            .map(|output| String::from_utf8_lossy(&output.stderr).trim().to_string())
+            .unwrap_or_default();
+        return Err(StorageError::CommandFailed(format!(
+            "Namespace holder could not mount the ramdisk: {}",
+            stderr
+        )));
+    }
+
+    let pid = child.id();
+    let pid_file = holder_file(mount_point);
+    if let Some(dir) = pid_file.parent() {
+        create_private_dir(dir).map_err(StorageError::Io)?;
+    }
+    write_private_file(&pid_file, pid.to_string().as_bytes()).map_err(StorageError::Io)?;
+
+    info!(
+        target: targets::RAMDISK,
+        "Namespace holder {} serves {}; enter it with nsenter --target {} --user --mount",
+        pid,
+        mount_point.display(),
+        pid
+    );
+    Ok(())
+}
+
+/// PID of the live holder process serving a mount point, if any
+///
+/// The holder must be a process of the current user; a recorded PID
+/// reused by someone else's process is not entered.
+pub fn holder_pid(mount_point: &Path) -> Option<u32> {
+    let pid: u32 = read_private_file(&holder_file(mount_point)).ok()?.trim().parse().ok()?;
+    let owner = fs::metadata(format!("/proc/{}", pid)).ok()?.uid();
+    if owner != nix::unistd::geteuid().as_raw() {
+        return None;
+    }
+    let mounts = fs::read_to_string(format!("/proc/{}/mounts", pid)).ok()?;
+    (process_alive(pid) && mounts_contain(&mounts, mount_point)).then_some(pid)
+}
+
+/// Command that runs `program` where the ramdisk at `mount_point` is
+/// visible: inside the holder's namespaces when there is a holder.
+pub fn command(mount_point: &Path, program: &str) -> Command {
+    match holder_pid(mount_point) {
+        Some(pid) => {
+            let mut command = Command::new("nsenter");
+            command
+                .args(["--target", &pid.to_string(), "--user", "--mount", "--"])
+                .arg(program);
+            command
+        }
+        None => Command::new(program),
+    }
+}
+
+/// Path this process reaches `mount_point` under: through the holder's
+/// root when there is a holder, otherwise the mount point itself.
+pub fn host_path(mount_point: &Path) -> PathBuf {
+    match holder_pid(mount_point) {
+        Some(pid) => PathBuf::from(format!("/proc/{}/root", pid))
+            .join(mount_point.strip_prefix("/").unwrap_or(mount_point)),
+        None => mount_point.to_path_buf(),
+    }
+}
+
+/// Stop the holder serving a mount point, which unmounts its tmpfs.
+///
+/// Returns Ok(true) if there was a holder to stop.
+pub fn remove_holder(mount_point: &Path) -> Result<bool, StorageError> {
+    let Some(pid) = holder_pid(mount_point) else {
+        return Ok(false);
+    };
+
+    info!(target: targets::RAMDISK, "Stopping namespace holder {}", pid);
+    nix::sys::signal::kill(
+        nix::unistd::Pid::from_raw(pid as i32),
+        nix::sys::signal::Signal::SIGTERM,
+    )
+    .map_err(|e| StorageError::CommandFailed(format!("Failed to stop namespace holder: {}", e)))?;
+
+    let _ = fs::remove_file(holder_file(mount_point));
+    // Only the empty mount point directory is left on the host
+    let _ = fs::remove_dir(mount_point);
+    Ok(true)
+}
+
+fn holder_file(mount_point: &Path) -> PathBuf {
+    let name: String = mount_point
+        .to_string_lossy()
+        .trim_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    user_state_dir()
+        .join("ramdisk")
+        .join(format!("holder-{}.pid", name))
+}
+
+/// Whether a `/proc/<pid>/mounts` listing has a mount at `mount_point`
+fn mounts_contain(mounts: &str, mount_point: &Path) -> bool {
+    let target = mount_point.to_string_lossy();
+    mounts
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .any(|field| unescape_mount_field(field) == target)
+}
+
+/// Undo the octal escapes the kernel writes for whitespace and backslashes
+fn unescape_mount_field(field: &str) -> String {
+    field
+        .replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\012", "\n")
+        .replace("\\134", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategy_prefers_shared_mounts_then_holders() {
+        let probes = |root, passwordless_sudo, user_namespaces| Probes {
+            root,
+            passwordless_sudo,
+            user_namespaces,
+        };
+        assert_eq!(probes(true, false, true).strategy(), LinuxRamdiskStrategy::SharedMount);
+        assert_eq!(probes(false, true, false).strategy(), LinuxRamdiskStrategy::SharedMount);
+        assert_eq!(
+            probes(false, false, true).strategy(),
+            LinuxRamdiskStrategy::NamespaceHolder
+        );
+        assert_eq!(
+            probes(false, false, false).strategy(),
+            LinuxRamdiskStrategy::ProcessNamespace
+        );
+
+        let mounts = "proc /proc proc rw 0 0\nnone /ephemeral/my\\040cylo tmpfs rw,size=1G 0 0\n";
+        assert!(mounts_contain(mounts, Path::new("/ephemeral/my cylo")));
+        assert!(!mounts_contain(mounts, Path::new("/ephemeral")));
+        let pid_file = holder_file(Path::new("/ephemeral/cylo"));
+        assert!(pid_file.ends_with("holder-ephemeral_cylo.pid"));
+        assert!(pid_file.starts_with(user_state_dir()));
+        assert_eq!(host_path(Path::new("/nonexistent/cylo")), Path::new("/nonexistent/cylo"));
+    }
+}
@@ -0,0 +1,283 @@
+// ============================================================================
+// File: packages/cylo/src/logging.rs
+// ----------------------------------------------------------------------------
+// Tracing targets and runtime log configuration.
+//
+// Every subsystem logs under its own target (`cylo::executor`,
+// `cylo::backend::firecracker`, `cylo::ramdisk`, ...) so its verbosity can
+// be tuned on its own. `init` installs a subscriber whose per-target levels
+// can be changed later without a restart. Without it, events are forwarded
+// to the `log` crate as before.
+//
+// User code, program output and secrets are only logged through
+// `Sensitive`, which prints their size rather than their content unless
+// sensitive logging was switched on explicitly.
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry, reload};
+
+use crate::execution_env::{CyloError, CyloResult};
+
+/// Environment variable with filter directives applied on top of the
+/// configured levels, e.g. `cylo::backend::firecracker=trace`
+pub const LOG_FILTER_ENV: &str = "CYLO_LOG";
+
+/// Targets cylo logs under, one per subsystem
+pub mod targets {
+    /// Routing, scheduling and execution orchestration
+    pub const EXECUTOR: &str = "cylo::executor";
+    /// One usage and cost record per execution
+    pub const AUDIT: &str = "cylo::audit";
+    /// Instance registration, health and cleanup
+    pub const INSTANCES: &str = "cylo::instances";
+    /// Backend code shared by every backend
+    pub const BACKEND: &str = "cylo::backend";
+    /// Apple containerization backend
+    pub const BACKEND_APPLE: &str = "cylo::backend::apple";
+    /// FireCracker microVM backend
+    pub const BACKEND_FIRECRACKER: &str = "cylo::backend::firecracker";
+    /// LandLock sandbox backend
+    pub const BACKEND_LANDLOCK: &str = "cylo::backend::landlock";
+    /// Windows Job Object backend
+    pub const BACKEND_WINDOWS: &str = "cylo::backend::windows";
+    /// FireCracker VMs backing ramdisk environments
+    pub const FIRECRACKER: &str = "cylo::firecracker";
+    /// Ramdisk creation and removal
+    pub const RAMDISK: &str = "cylo::ramdisk";
+    /// Sandboxed language environments and the filesystem jail
+    pub const SANDBOX: &str = "cylo::sandbox";
+    /// Direct language execution helpers
+    pub const EXEC: &str = "cylo::exec";
+    /// File watching and the execution pipeline
+    pub const PIPELINE: &str = "cylo::pipeline";
+    /// Reaping of spawned processes, containers and VMs
+    pub const REAPER: &str = "cylo::reaper";
+    /// Startup crash recovery
+    pub const RECOVERY: &str = "cylo::recovery";
+    /// Confinement of cylo's own helper processes
+    pub const HARDENING: &str = "cylo::hardening";
+    /// Toolchain installation
+    pub const TOOLCHAINS: &str = "cylo::toolchains";
+    /// Command line front end
+    pub const CLI: &str = "cylo::cli";
+}
+
+/// Verbosity of a log target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        })
+    }
+}
+
+/// Log levels and redaction settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// Level of targets without their own entry
+    pub default_level: LogLevel,
+    /// Levels by target; a target also covers the targets below it, so
+    /// `cylo::backend` covers `cylo::backend::firecracker`
+    pub targets: BTreeMap<String, LogLevel>,
+    /// Log user code, program output and secrets verbatim instead of
+    /// their sizes; for debugging only
+    pub log_sensitive: bool,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            default_level: LogLevel::Info,
+            targets: BTreeMap::new(),
+            log_sensitive: false,
+        }
+    }
+}
+
+impl LogConfig {
+    /// Set the level of one target
+    pub fn with_target(mut self, target: impl Into<String>, level: LogLevel) -> Self {
+        self.targets.insert(target.into(), level);
+        self
+    }
+
+    /// Filter directives for this configuration, e.g.
+    /// `info,cylo::backend::firecracker=debug`
+    pub fn directives(&self) -> String {
+        let mut directives = vec![self.default_level.to_string()];
+        directives.extend(
+            self.targets
+                .iter()
+                .map(|(target, level)| format!("{target}={level}")),
+        );
+        directives.join(",")
+    }
+
+    fn filter(&self) -> CyloResult<EnvFilter> {
+        let mut directives = self.directives();
+        if let Ok(extra) = std::env::var(LOG_FILTER_ENV)
+            && !extra.trim().is_empty()
+        {
+            directives.push(',');
+            directives.push_str(extra.trim());
+        }
+        EnvFilter::try_new(&directives).map_err(|e| {
+            CyloError::invalid_request("log_filter", format!("{directives}: {e}"))
+        })
+    }
+}
+
+static SENSITIVE: AtomicBool = AtomicBool::new(false);
+
+/// Reload handle of the installed filter and the config it was built from
+struct Installed {
+    handle: reload::Handle<EnvFilter, Registry>,
+    config: Mutex<LogConfig>,
+}
+
+static INSTALLED: OnceLock<Installed> = OnceLock::new();
+
+/// Install cylo's subscriber, writing formatted events to stderr
+///
+/// # Arguments
+/// * `config` - Initial levels; `CYLO_LOG` directives are applied on top
+///
+/// # Returns
+/// Ok(()) once installed; an error if the directives do not parse or
+/// another global subscriber is already set
+pub fn init(config: LogConfig) -> CyloResult<()> {
+    let (filter, handle) = reload::Layer::new(config.filter()?);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .try_init()
+        .map_err(|e| CyloError::internal(format!("Failed to install log subscriber: {e}")))?;
+
+    SENSITIVE.store(config.log_sensitive, Ordering::Relaxed);
+    let _ = INSTALLED.set(Installed {
+        handle,
+        config: Mutex::new(config),
+    });
+    Ok(())
+}
+
+/// Replace the levels and redaction settings at runtime
+///
+/// Takes effect for events logged after it returns. Redaction applies
+/// even when `init` was not called and another subscriber is in use.
+pub fn reconfigure(config: LogConfig) -> CyloResult<()> {
+    SENSITIVE.store(config.log_sensitive, Ordering::Relaxed);
+    let Some(installed) = INSTALLED.get() else {
+        return Ok(());
+    };
+    installed
+        .handle
+        .reload(config.filter()?)
+        .map_err(|e| CyloError::internal(format!("Failed to reload log filter: {e}")))?;
+    *installed
+        .config
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = config;
+    Ok(())
+}
+
+/// Change the level of one target at runtime
+///
+/// # Arguments
+/// * `target` - Target such as `targets::BACKEND_FIRECRACKER`
+/// * `level` - New level for it and the targets below it
+pub fn set_target_level(target: &str, level: LogLevel) -> CyloResult<()> {
+    reconfigure(current_config().with_target(target, level))
+}
+
+/// Configuration currently in effect
+pub fn current_config() -> LogConfig {
+    match INSTALLED.get() {
+        Some(installed) => installed
+            .config
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone(),
+        None => LogConfig {
+            log_sensitive: SENSITIVE.load(Ordering::Relaxed),
+            ..LogConfig::default()
+        },
+    }
+}
+
+/// User code, program output or a secret, logged by size unless
+/// sensitive logging is on
+pub struct Sensitive<'a>(pub &'a str);
+
+impl fmt::Display for Sensitive<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if SENSITIVE.load(Ordering::Relaxed) {
+            f.write_str(self.0)
+        } else {
+            write!(f, "<{} bytes redacted>", self.0.len())
+        }
+    }
+}
+
+impl fmt::Debug for Sensitive<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directives_and_redaction_follow_the_config() {
+        let config = LogConfig::default()
+            .with_target(targets::BACKEND_FIRECRACKER, LogLevel::Debug)
+            .with_target(targets::RAMDISK, LogLevel::Off);
+        assert_eq!(
+            config.directives(),
+            "info,cylo::backend::firecracker=debug,cylo::ramdisk=off"
+        );
+        assert!(config.filter().is_ok());
+
+        let parsed: LogConfig =
+            serde_json::from_str(r#"{"targets": {"cylo::executor": "trace"}}"#).unwrap();
+        assert_eq!(parsed.default_level, LogLevel::Info);
+        assert_eq!(parsed.targets["cylo::executor"], LogLevel::Trace);
+
+        assert_eq!(Sensitive("print('hi')").to_string(), "<11 bytes redacted>");
+        reconfigure(LogConfig {
+            log_sensitive: true,
+            ..LogConfig::default()
+        })
+        .unwrap();
+        assert_eq!(Sensitive("print('hi')").to_string(), "print('hi')");
+        reconfigure(LogConfig::default()).unwrap();
+        assert!(!current_config().log_sensitive);
+    }
+}
@@ -3,7 +3,10 @@ use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 
 #[cfg(target_os = "linux")]
-use log::{error, info};
+use tracing::{error, info};
+
+#[cfg(target_os = "linux")]
+use crate::logging::targets;
 
 /// Metadata keys used for source code files
 pub const XATTR_NAMESPACE: &str = "user.ironexec";
@@ -148,13 +151,13 @@ fn main() -> io::Result<()> {{
         match File::open(path) {
             Ok(file) => {
                 if let Err(e) = file.set_xattr(XATTR_LANGUAGE, language.as_bytes()) {
-                    error!("Failed to set language xattr: {}", e);
+                    error!(target: targets::PIPELINE, "Failed to set language xattr: {}", e);
                     return Ok(());
                 }
 
                 let timestamp = chrono::Utc::now().to_rfc3339();
                 if let Err(e) = file.set_xattr(XATTR_LAST_EXECUTED, timestamp.as_bytes()) {
-                    error!("Failed to set timestamp xattr: {}", e);
+                    error!(target: targets::PIPELINE, "Failed to set timestamp xattr: {}", e);
                     return Ok(());
                 }
 
@@ -167,11 +170,12 @@ fn main() -> io::Result<()> {{
 
                 if let Err(e) = file.set_xattr(XATTR_EXECUTION_COUNT, count.to_string().as_bytes())
                 {
-                    error!("Failed to set execution count xattr: {}", e);
+                    error!(target: targets::PIPELINE, "Failed to set execution count xattr: {}", e);
                     return Ok(());
                 }
 
                 info!(
+                    target: targets::PIPELINE,
                     "Updated metadata for {}: lang={} count={} last={}",
                     path.display(),
                     language,
@@ -180,7 +184,7 @@ fn main() -> io::Result<()> {{
                 );
             }
             Err(e) => {
-                error!("Failed to open file for metadata update: {}", e);
+                error!(target: targets::PIPELINE, "Failed to open file for metadata update: {}", e);
             }
         }
 
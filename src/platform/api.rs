@@ -11,31 +11,52 @@
 // ============================================================================
 
 use super::types::*;
+use crate::async_task::{AsyncTask, AsyncTaskBuilder};
+use crate::backends::registry;
 
 /// Get current platform information
+///
+/// Detection runs once and is cached; the first call blocks while it shells
+/// out for versions and runtimes, so async code uses
+/// `detect_platform_async` instead.
 pub fn detect_platform() -> &'static PlatformInfo {
     PlatformInfo::get()
 }
 
+/// Get current platform information without blocking the async runtime
+///
+/// Returns the cached information at once when detection already ran, and
+/// otherwise runs detection on a blocking thread.
+pub fn detect_platform_async() -> AsyncTask<&'static PlatformInfo> {
+    AsyncTaskBuilder::new(async move {
+        if let Some(info) = PlatformInfo::cached() {
+            return info;
+        }
+        tokio::task::spawn_blocking(PlatformInfo::get)
+            .await
+            .unwrap_or_else(|_| PlatformInfo::get())
+    })
+    .spawn()
+}
+
 /// Check if running on Apple Silicon
 pub fn is_apple_silicon() -> bool {
-    let info = detect_platform();
-    matches!(info.os, OperatingSystem::MacOS { .. }) && matches!(info.arch, Architecture::Arm64)
+    cfg!(target_os = "macos") && std::env::consts::ARCH == "aarch64"
 }
 
 /// Check if running on Linux
 pub fn is_linux() -> bool {
-    matches!(detect_platform().os, OperatingSystem::Linux { .. })
+    cfg!(target_os = "linux")
 }
 
 /// Check if LandLock is available
 pub fn has_landlock() -> bool {
-    detect_platform().capabilities.security.landlock
+    super::capabilities::has_landlock_support()
 }
 
 /// Check if KVM is available
 pub fn has_kvm() -> bool {
-    detect_platform().capabilities.virtualization.kvm_available
+    super::capabilities::has_kvm_support()
 }
 
 /// Get recommended backend for current platform
@@ -45,10 +66,28 @@ pub fn get_recommended_backend() -> Option<String> {
 
 /// Get available backends for current platform
 pub fn get_available_backends() -> Vec<String> {
-    detect_platform()
-        .available_backends
-        .iter()
+    backend_availability()
+        .into_iter()
         .filter(|b| b.available)
-        .map(|b| b.name.clone())
+        .map(|b| b.name)
         .collect()
 }
+
+/// Built-in backends available on this host
+///
+/// Cheap and cached on its own: it checks files rather than running
+/// commands, and does not wait for the full `detect_platform`.
+pub fn detected_backends() -> &'static [BackendAvailability] {
+    PlatformInfo::backends()
+}
+
+/// Availability of the detected built-in backends followed by every
+/// registered third-party backend
+///
+/// Unlike `detected_backends`, this is not cached: registered backends are
+/// probed on every call.
+pub fn backend_availability() -> Vec<BackendAvailability> {
+    let mut backends = detected_backends().to_vec();
+    backends.extend(registry::availability());
+    backends
+}
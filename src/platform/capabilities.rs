@@ -26,12 +26,22 @@ pub(crate) fn detect_virtualization_support(_os: &OperatingSystem) -> Virtualiza
 
 /// Detect container runtime support for the given OS
 pub(crate) fn detect_container_support(os: &OperatingSystem) -> ContainerSupport {
+    let [docker, podman, container, python, node] =
+        commands_available(["docker", "podman", "container", "python3", "node"]);
+
+    let mut native_runtimes = Vec::new();
+    if python {
+        native_runtimes.push("python".to_string());
+    }
+    if node {
+        native_runtimes.push("javascript".to_string());
+    }
+
     ContainerSupport {
-        docker_available: is_command_available("docker"),
-        podman_available: is_command_available("podman"),
-        apple_containers: is_command_available("container")
-            && matches!(os, OperatingSystem::MacOS { .. }),
-        native_runtimes: detect_native_runtimes(),
+        docker_available: docker,
+        podman_available: podman,
+        apple_containers: container && matches!(os, OperatingSystem::MacOS { .. }),
+        native_runtimes,
     }
 }
 
@@ -70,9 +80,20 @@ pub(crate) fn detect_filesystem_features() -> FilesystemFeatures {
         journaling_enabled: true,
         copy_on_write: false,
         encryption_enabled: false,
+        ramdisk_strategy: ramdisk_strategy(),
     }
 }
 
+#[cfg(target_os = "linux")]
+fn ramdisk_strategy() -> Option<crate::config::LinuxRamdiskStrategy> {
+    Some(crate::linux::LinuxRamdisk::strategy())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn ramdisk_strategy() -> Option<crate::config::LinuxRamdiskStrategy> {
+    None
+}
+
 /// Measure actual DNS resolution time by testing against reliable domains
 ///
 /// Tests DNS lookup against multiple domains with timeout protection.
@@ -136,7 +157,7 @@ fn has_hardware_virtualization() -> bool {
     false
 }
 
-fn has_kvm_support() -> bool {
+pub(crate) fn has_kvm_support() -> bool {
     std::path::Path::new("/dev/kvm").exists()
 }
 
@@ -150,7 +171,7 @@ fn has_hypervisor_framework() -> bool {
     cfg!(target_os = "macos")
 }
 
-fn has_landlock_support() -> bool {
+pub(crate) fn has_landlock_support() -> bool {
     // Linux-specific detection using syscalls
     false
 }
@@ -177,13 +198,10 @@ fn is_command_available(command: &str) -> bool {
         .is_ok()
 }
 
-fn detect_native_runtimes() -> Vec<String> {
-    let mut runtimes = Vec::new();
-    if is_command_available("python3") {
-        runtimes.push("python".to_string());
-    }
-    if is_command_available("node") {
-        runtimes.push("javascript".to_string());
-    }
-    runtimes
+/// Probe several commands at once; each probe spawns a process
+fn commands_available<const N: usize>(commands: [&str; N]) -> [bool; N] {
+    std::thread::scope(|scope| {
+        let probes = commands.map(|command| scope.spawn(move || is_command_available(command)));
+        probes.map(|probe| probe.join().unwrap_or(false))
+    })
 }
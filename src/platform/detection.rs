@@ -8,23 +8,32 @@
 // - Available backends and their capabilities
 // - Platform capabilities (virtualization, containers, security)
 // - Performance characteristics
+//
+// Backend availability only needs file checks and compile-time facts, so it
+// is detected and cached on its own; routing and executor construction use
+// it without waiting for the full detection, whose version and runtime
+// probes shell out and run in parallel.
 // ============================================================================
 
-use std::collections::HashMap;
 use std::sync::OnceLock;
 use std::time::SystemTime;
 
 use super::capabilities::*;
 use super::performance::*;
 use super::types::*;
+use crate::backends::BackendCapabilities;
 
 /// Global platform information cache
 static PLATFORM_INFO: OnceLock<PlatformInfo> = OnceLock::new();
 
+/// Built-in backend availability cache, filled without running commands
+static BACKENDS: OnceLock<Vec<BackendAvailability>> = OnceLock::new();
+
 impl PlatformInfo {
     /// Get or detect platform information
     ///
-    /// Uses cached detection results for performance.
+    /// Uses cached detection results for performance. The first call
+    /// blocks while probes run; async callers use `detect_platform_async`.
     ///
     /// # Returns
     /// Platform information
@@ -32,26 +41,64 @@ impl PlatformInfo {
         PLATFORM_INFO.get_or_init(Self::detect)
     }
 
+    /// Cached platform information, if detection already ran
+    pub(crate) fn cached() -> Option<&'static PlatformInfo> {
+        PLATFORM_INFO.get()
+    }
+
+    /// Built-in backends available on this host
+    ///
+    /// Detected from file checks and compile-time facts only, and cached
+    /// separately from the full platform information.
+    pub(crate) fn backends() -> &'static [BackendAvailability] {
+        BACKENDS.get_or_init(|| {
+            Self::detect_available_backends(&Self::os_family(), &Self::detect_architecture())
+        })
+    }
+
     /// Force re-detection of platform information
     ///
     /// # Returns
     /// Newly detected platform information
     pub fn detect() -> PlatformInfo {
-        let os = Self::detect_operating_system();
         let arch = Self::detect_architecture();
-        let capabilities = Self::detect_capabilities(&os);
-        let available_backends = Self::detect_available_backends(&os, &arch, &capabilities);
+
+        // Version probes shell out, so they run alongside capability probes
+        let family = Self::os_family();
+        let (os, capabilities) = std::thread::scope(|scope| {
+            let os = scope.spawn(Self::detect_operating_system);
+            let capabilities = Self::detect_capabilities(&family);
+            (os.join().unwrap_or_else(|_| family.clone()), capabilities)
+        });
 
         PlatformInfo {
             os,
             arch,
             capabilities,
-            available_backends,
+            available_backends: Self::backends().to_vec(),
             performance: detect_performance_hints(),
             detected_at: SystemTime::now(),
         }
     }
 
+    /// Operating system without its version, known at compile time
+    fn os_family() -> OperatingSystem {
+        if cfg!(target_os = "linux") {
+            OperatingSystem::Linux {
+                distribution: None,
+                kernel_version: None,
+            }
+        } else if cfg!(target_os = "macos") {
+            OperatingSystem::MacOS { version: None }
+        } else if cfg!(target_os = "windows") {
+            OperatingSystem::Windows { version: None }
+        } else {
+            OperatingSystem::Unknown {
+                name: std::env::consts::OS.to_string(),
+            }
+        }
+    }
+
     /// Detect operating system
     fn detect_operating_system() -> OperatingSystem {
         #[cfg(target_os = "linux")]
@@ -110,7 +157,6 @@ impl PlatformInfo {
     fn detect_available_backends(
         os: &OperatingSystem,
         arch: &Architecture,
-        capabilities: &PlatformCapabilities,
     ) -> Vec<BackendAvailability> {
         let mut backends = Vec::new();
 
@@ -120,33 +166,66 @@ impl PlatformInfo {
                 name: "Apple".to_string(),
                 available: true,
                 reason: "Running on macOS with Apple Silicon".to_string(),
-                capabilities: HashMap::new(),
+                capabilities: BackendCapabilities::of_backend("Apple"),
                 performance_rating: 95,
             });
         }
 
         // LandLock backend
-        if capabilities.security.landlock {
+        if has_landlock_support() {
             backends.push(BackendAvailability {
                 name: "LandLock".to_string(),
                 available: true,
                 reason: "LandLock is supported by the kernel".to_string(),
-                capabilities: HashMap::new(),
+                capabilities: BackendCapabilities::of_backend("LandLock"),
                 performance_rating: 85,
             });
         }
 
         // FireCracker backend
-        if capabilities.virtualization.kvm_available {
+        if has_kvm_support() {
             backends.push(BackendAvailability {
                 name: "FireCracker".to_string(),
                 available: true,
                 reason: "KVM is available for hardware virtualization".to_string(),
-                capabilities: HashMap::new(),
+                capabilities: BackendCapabilities::of_backend("FireCracker"),
                 performance_rating: 90,
             });
         }
 
+        // Docker backend
+        if let Some(runtime) = crate::backends::docker::installed_runtime() {
+            backends.push(BackendAvailability {
+                name: "Docker".to_string(),
+                available: true,
+                reason: format!("{runtime} is installed"),
+                capabilities: BackendCapabilities::of_backend("Docker"),
+                performance_rating: 70,
+            });
+        }
+
+        // WebAssembly backend
+        if let Some(wasmtime) = crate::backends::wasm::installed_runtime() {
+            backends.push(BackendAvailability {
+                name: "Wasm".to_string(),
+                available: true,
+                reason: format!("{wasmtime} is installed"),
+                capabilities: BackendCapabilities::of_backend("Wasm"),
+                performance_rating: 50,
+            });
+        }
+
+        // Kubernetes Job backend
+        if let Some(kubectl) = crate::backends::kubernetes::installed_runtime() {
+            backends.push(BackendAvailability {
+                name: "K8sJob".to_string(),
+                available: true,
+                reason: format!("{kubectl} is installed"),
+                capabilities: BackendCapabilities::of_backend("K8sJob"),
+                performance_rating: 40,
+            });
+        }
+
         backends
     }
 
@@ -60,6 +60,13 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn async_detection_shares_the_cache() {
+        let info = detect_platform_async().await.unwrap();
+        assert!(std::ptr::eq(info, detect_platform()));
+        assert_eq!(info.available_backends.len(), detected_backends().len());
+    }
+
     #[test]
     fn utility_functions() {
         // These should not panic
@@ -10,11 +10,13 @@
 // - Backend availability information
 // ============================================================================
 
-use std::collections::HashMap;
 use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 
+use crate::backends::BackendCapabilities;
+use crate::config::LinuxRamdiskStrategy;
+
 /// Comprehensive platform information
 ///
 /// Contains detected platform capabilities, available backends,
@@ -94,8 +96,8 @@ pub struct BackendAvailability {
     /// Availability reason (why available/unavailable)
     pub reason: String,
 
-    /// Backend-specific capabilities
-    pub capabilities: HashMap<String, String>,
+    /// What the backend offers executions
+    pub capabilities: Option<BackendCapabilities>,
 
     /// Performance rating (0-100)
     pub performance_rating: u8,
@@ -207,6 +209,10 @@ pub struct FilesystemFeatures {
 
     /// Encryption support (e.g., FileVault)
     pub encryption_enabled: bool,
+
+    /// How ramdisks are made visible to other processes (Linux)
+    #[serde(default)]
+    pub ramdisk_strategy: Option<LinuxRamdiskStrategy>,
 }
 
 /// Performance optimization hints
@@ -2,12 +2,17 @@
 // File: packages/cylo/src/platform_utils.rs
 // ----------------------------------------------------------------------------
 // Cross-platform utilities for file operations
+//
+// Includes the per-user directory cylo keeps its own state in. It sits in
+// the shared temp directory, so it is created owner-only and checked on
+// every use: another local user who made it first, or who can swap one of
+// its ancestors, must not be able to read what cylo stores or plant what it
+// loads.
 // ============================================================================
 
-use std::path::Path;
-use std::io;
-#[cfg(unix)]
 use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 
 /// Set executable permissions on a file
 ///
@@ -31,3 +36,144 @@ pub fn set_executable(_path: &Path) -> io::Result<()> {
     // No permissions need to be set
     Ok(())
 }
+
+/// Directory private to the current user for cylo's own state
+///
+/// Only the path; `create_private_dir` creates and checks it.
+pub fn user_state_dir() -> PathBuf {
+    #[cfg(unix)]
+    // SAFETY: geteuid(2) cannot fail and touches no memory
+    let user = unsafe { libc::geteuid() }.to_string();
+    // %TEMP% is per-user on Windows already
+    #[cfg(not(unix))]
+    let user = "user".to_string();
+    std::env::temp_dir().join(format!("cylo-{user}"))
+}
+
+/// Create a directory only the current user can use, or check an existing
+/// one is
+///
+/// Missing directories are created 0700. The directory must be owned by
+/// the current user, who it is closed to everyone but, and every ancestor
+/// must be owned by the current user or root, with a directory others can
+/// write to allowed only if it is root's and sticky, like /tmp.
+///
+/// # Returns
+/// Ok(()), or PermissionDenied naming the path another user could control
+#[cfg(unix)]
+pub fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    // SAFETY: geteuid(2) cannot fail and touches no memory
+    let uid = unsafe { libc::geteuid() };
+    let refused = |path: &Path, why: &str| {
+        io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} {why}", path.display()),
+        )
+    };
+
+    let metadata = fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid {
+        return Err(refused(dir, "is not a directory of this user"));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    for ancestor in dir.ancestors().skip(1).filter(|path| !path.as_os_str().is_empty()) {
+        let metadata = fs::symlink_metadata(ancestor)?;
+        let owner_trusted = metadata.uid() == uid || metadata.uid() == 0;
+        let shared = metadata.is_dir() && metadata.mode() & 0o022 != 0;
+        let sticky_root = metadata.uid() == 0 && metadata.mode() & 0o1000 != 0;
+        if !owner_trusted || (shared && !sticky_root) {
+            return Err(refused(ancestor, "could be replaced by another user"));
+        }
+    }
+    Ok(())
+}
+
+/// Create a directory; %TEMP% and profiles are per-user already
+#[cfg(not(unix))]
+pub fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+/// Write a file only the current user can read, replacing it whole
+///
+/// The contents go to a new file created 0600 next to `path`, never
+/// through a link, which is then renamed over `path`.
+pub fn write_private_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let staged = path.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+    let _ = fs::remove_file(&staged);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600).custom_flags(libc::O_NOFOLLOW);
+    }
+    let written = options
+        .open(&staged)
+        .and_then(|mut file| file.write_all(contents).and_then(|()| file.sync_all()))
+        .and_then(|()| fs::rename(&staged, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&staged);
+    }
+    written
+}
+
+/// Read a file, refusing a link in its place
+pub fn read_private_file(path: &Path) -> io::Result<String> {
+    use std::io::Read;
+
+    let mut options = fs::OpenOptions::new();
+    options.read(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_NOFOLLOW);
+    }
+    let mut contents = String::new();
+    options.open(path)?.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn private_dirs_and_files_stay_closed_to_others() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let base = tempfile::tempdir().unwrap();
+        let dir = base.path().join("state").join("jobs");
+        create_private_dir(&dir).unwrap();
+        assert_eq!(fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+
+        let file = dir.join("jobs.json");
+        write_private_file(&file, b"[]").unwrap();
+        write_private_file(&file, b"[1]").unwrap();
+        assert_eq!(fs::metadata(&file).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(read_private_file(&file).unwrap(), "[1]");
+
+        let link = dir.join("link.json");
+        std::os::unix::fs::symlink(&file, &link).unwrap();
+        assert!(read_private_file(&link).is_err());
+
+        // Opened up to others: closed again
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        create_private_dir(&dir).unwrap();
+        assert_eq!(fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+
+        // Below a directory anyone can swap it out of: refused
+        fs::set_permissions(base.path().join("state"), fs::Permissions::from_mode(0o777)).unwrap();
+        let err = create_private_dir(&dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
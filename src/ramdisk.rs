@@ -1,20 +1,35 @@
 use std::path::{Path, PathBuf};
 
-use log::{info, warn};
+use tracing::{info, warn};
 
 #[cfg(target_os = "linux")]
 use crate::linux::LinuxRamdisk;
+use crate::logging::targets;
 #[cfg(target_os = "macos")]
 use crate::macos::MacosRamdisk;
 #[cfg(target_os = "windows")]
 use crate::windows::WindowsRamdisk;
-use crate::{config::RamdiskConfig, error::StorageError};
+use crate::{
+    config::{RamdiskConfig, RamdiskProviderStatus},
+    error::{Error, StorageError},
+};
 #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
 use crate::platform::RamdiskPlatform;
 
 /// Returns the path to the watched directory within the ramdisk
+///
+/// On Linux a ramdisk served by a namespace holder is reached through the
+/// holder's root, since it is not mounted in this process's namespace.
 pub fn get_watched_dir(config: &RamdiskConfig) -> PathBuf {
-    PathBuf::from(&config.mount_point).join("watched_dir")
+    #[cfg(target_os = "linux")]
+    {
+        LinuxRamdisk::host_path(&config.mount_point).join("watched_dir")
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        PathBuf::from(&config.mount_point).join("watched_dir")
+    }
 }
 
 /// Creates a ramdisk with the specified configuration
@@ -24,16 +39,16 @@ pub fn get_watched_dir(config: &RamdiskConfig) -> PathBuf {
 ///
 /// # Returns
 /// * `Ok(())` if successful
-/// * `Err(StorageError)` if creation fails
-pub fn create_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError> {
+/// * `Err(Error::Storage)` if creation fails
+pub fn create_ramdisk(config: &RamdiskConfig) -> Result<(), Error> {
     #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
     {
         let mut platform = get_platform_impl()?;
-        platform.create(config)
+        Ok(platform.create(config)?)
     }
 
     #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
-    Err(StorageError::UnsupportedOs(std::env::consts::OS.to_string()))
+    Err(StorageError::UnsupportedOs(std::env::consts::OS.to_string()).into())
 }
 
 /// Removes a ramdisk at the specified mount point
@@ -43,16 +58,16 @@ pub fn create_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError> {
 ///
 /// # Returns
 /// * `Ok(())` if successful
-/// * `Err(StorageError)` if removal fails
-pub fn remove_ramdisk(mount_point: &Path) -> Result<(), StorageError> {
+/// * `Err(Error::Storage)` if removal fails
+pub fn remove_ramdisk(mount_point: &Path) -> Result<(), Error> {
     #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
     {
         let platform = get_platform_impl()?;
-        platform.remove(mount_point)
+        Ok(platform.remove(mount_point)?)
     }
 
     #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
-    Err(StorageError::UnsupportedOs(std::env::consts::OS.to_string()))
+    Err(StorageError::UnsupportedOs(std::env::consts::OS.to_string()).into())
 }
 
 /// Checks if a path is mounted as a ramdisk
@@ -63,16 +78,47 @@ pub fn remove_ramdisk(mount_point: &Path) -> Result<(), StorageError> {
 /// # Returns
 /// * `Ok(true)` if path is mounted as a ramdisk
 /// * `Ok(false)` if not
-/// * `Err(StorageError)` if there's an error checking
-pub fn is_mounted(mount_point: &Path) -> Result<bool, StorageError> {
+/// * `Err(Error::Storage)` if there's an error checking
+pub fn is_mounted(mount_point: &Path) -> Result<bool, Error> {
     #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
     {
         let platform = get_platform_impl()?;
-        platform.is_mounted(mount_point)
+        Ok(platform.is_mounted(mount_point)?)
     }
 
     #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
-    Err(StorageError::UnsupportedOs(std::env::consts::OS.to_string()))
+    Err(StorageError::UnsupportedOs(std::env::consts::OS.to_string()).into())
+}
+
+/// Reports which Windows ramdisk providers can be used on this host
+///
+/// # Returns
+/// One status per provider, in the order automatic selection tries them;
+/// every provider is unavailable on other platforms
+pub fn windows_ramdisk_providers() -> Vec<RamdiskProviderStatus> {
+    #[cfg(target_os = "windows")]
+    {
+        crate::windows::provider_statuses()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use crate::config::WindowsRamdiskProvider;
+
+        [
+            WindowsRamdiskProvider::ImDisk,
+            WindowsRamdiskProvider::DevDrive,
+            WindowsRamdiskProvider::Vhd,
+            WindowsRamdiskProvider::TempDir,
+        ]
+        .into_iter()
+        .map(|provider| RamdiskProviderStatus {
+            provider,
+            available: false,
+            reason: "Only available on Windows".into(),
+        })
+        .collect()
+    }
 }
 
 /// Creates a secure execution environment
@@ -91,19 +137,26 @@ pub fn is_mounted(mount_point: &Path) -> Result<bool, StorageError> {
 ///
 /// # Returns
 /// * `Ok(())` if successful
-/// * `Err(StorageError)` if creation fails
-pub fn create_secure_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError> {
+/// * `Err(Error::Storage)` if creation fails
+pub fn create_secure_ramdisk(config: &RamdiskConfig) -> Result<(), Error> {
     let watched_dir = get_watched_dir(config);
     let ramdisk_created: bool;
 
     #[cfg(target_os = "linux")]
     {
-        info!("Attempting to create secure ramdisk with Linux-specific implementation");
-        info!("This may prompt for sudo access if needed for optimal security");
+        info!(
+            target: targets::RAMDISK,
+            "Attempting to create secure ramdisk with Linux-specific implementation"
+        );
+        info!(
+            target: targets::RAMDISK,
+            "This may prompt for sudo access if needed for optimal security"
+        );
 
         match crate::linux::LinuxRamdisk::create(config) {
             Ok(_) => {
                 info!(
+                    target: targets::RAMDISK,
                     "Successfully created ramdisk at {}",
                     config.mount_point.display()
                 );
@@ -111,6 +164,7 @@ pub fn create_secure_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError>
             }
             Err(e) => {
                 warn!(
+                    target: targets::RAMDISK,
                     "Could not create ramdisk: {}. Falling back to local dir.",
                     e
                 );
@@ -125,6 +179,7 @@ pub fn create_secure_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError>
         match platform.create(config) {
             Ok(_) => {
                 info!(
+                    target: targets::RAMDISK,
                     "Successfully created ramdisk at {}",
                     config.mount_point.display()
                 );
@@ -132,6 +187,7 @@ pub fn create_secure_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError>
             }
             Err(e) => {
                 warn!(
+                    target: targets::RAMDISK,
                     "Could not create ramdisk: {}. Falling back to local dir.",
                     e
                 );
@@ -146,6 +202,7 @@ pub fn create_secure_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError>
         match platform.create(config) {
             Ok(_) => {
                 info!(
+                    target: targets::RAMDISK,
                     "Successfully created ramdisk at {}",
                     config.mount_point.display()
                 );
@@ -153,6 +210,7 @@ pub fn create_secure_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError>
             }
             Err(e) => {
                 warn!(
+                    target: targets::RAMDISK,
                     "Could not create ramdisk: {}. Falling back to local dir.",
                     e
                 );
@@ -163,19 +221,25 @@ pub fn create_secure_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError>
 
     #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
     {
-        warn!("Ramdisk not supported on this OS: {}", std::env::consts::OS);
-        warn!("Using local directory instead");
+        warn!(
+            target: targets::RAMDISK,
+            "Ramdisk not supported on this OS: {}",
+            std::env::consts::OS
+        );
+        warn!(target: targets::RAMDISK, "Using local directory instead");
         ramdisk_created = false;
     }
 
     if !ramdisk_created {
         std::fs::create_dir_all(&watched_dir).map_err(StorageError::Io)?;
         info!(
+            target: targets::RAMDISK,
             "Created fallback watched directory at {}",
             watched_dir.display()
         );
     } else {
         info!(
+            target: targets::RAMDISK,
             "Using ramdisk watched directory at {}",
             watched_dir.display()
         );
@@ -188,7 +252,7 @@ pub fn create_secure_ramdisk(config: &RamdiskConfig) -> Result<(), StorageError>
     };
 
     if let Err(e) = crate::jail::init_jail(&jail_config) {
-        warn!("Landlock failed: {}. Security may be reduced.", e);
+        warn!(target: targets::RAMDISK, "Landlock failed: {}. Security may be reduced.", e);
     }
 
     Ok(())
@@ -0,0 +1,688 @@
+// ============================================================================
+// File: packages/cylo/src/reaper.rs
+// ----------------------------------------------------------------------------
+// Global reaper for processes, containers, and VMs spawned by cylo.
+//
+// Every backend registers what it spawns; a periodic sweep kills anything
+// whose owning execution has finished, whose deadline has passed, or whose
+// owner process died. Workspaces of failed executions kept for inspection
+// are registered the same way and removed once their deadline passes. The
+// registry is mirrored to a per-process state file so a later process can
+// reap what a crashed one left behind.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::hardening::{Helper, HelperCommand};
+use crate::logging::targets;
+
+/// Extra time past an execution's timeout before its resources are reaped
+pub const DEADLINE_GRACE: Duration = Duration::from_secs(60);
+
+/// Kind of resource tracked by the reaper
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResourceKind {
+    /// Host process (sandbox helper, compiled binary, ...)
+    Process { pid: u32 },
+
+    /// Container managed through a runtime CLI
+    Container { runtime: String, name: String },
+
+    /// MicroVM process and the host files backing it
+    Vm {
+        pid: u32,
+        socket_path: PathBuf,
+        artifacts: Vec<PathBuf>,
+    },
+
+    /// Files and directories of a failed execution kept for inspection
+    Workspace { paths: Vec<PathBuf> },
+}
+
+/// Resource registered with the reaper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedResource {
+    /// Registry identifier
+    pub id: u64,
+    /// What was spawned
+    pub kind: ResourceKind,
+    /// Backend that spawned it
+    pub backend: String,
+    /// Execution that owns it, if known
+    pub execution_id: Option<String>,
+    /// PID of the cylo process that spawned it
+    pub owner_pid: u32,
+    /// When it was registered
+    pub registered_at: SystemTime,
+    /// Time after which it is reaped even if still owned
+    pub deadline: Option<SystemTime>,
+    /// Whether the owning execution has finished
+    pub finished: bool,
+    /// Start time of a tracked process as the OS reported it at
+    /// registration; a process is only signalled while its PID still
+    /// refers to a process that started then
+    #[serde(default)]
+    pub started: Option<String>,
+}
+
+impl TrackedResource {
+    /// Whether the PID still refers to the process that was registered
+    ///
+    /// A reused PID belongs to a process with another start time, and a
+    /// resource whose start time could not be read is never signalled.
+    fn is_same_process(&self, pid: u32) -> bool {
+        self.started.is_some() && process_start_time(pid) == self.started
+    }
+
+    /// Whether the underlying resource still exists
+    fn is_alive(&self) -> bool {
+        match &self.kind {
+            ResourceKind::Process { pid } | ResourceKind::Vm { pid, .. } => {
+                self.is_same_process(*pid)
+            }
+            ResourceKind::Container { runtime, name } => Command::new(runtime)
+                .args(["inspect", name])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .helper_status(Helper::ContainerCli)
+                .map(|status| status.success())
+                .unwrap_or(false),
+            ResourceKind::Workspace { paths } => paths.iter().any(|path| path.exists()),
+        }
+    }
+
+    /// Kill the resource and remove its host artifacts
+    fn terminate(&self) {
+        match &self.kind {
+            ResourceKind::Process { pid } => {
+                if self.is_same_process(*pid) {
+                    kill_process(*pid);
+                }
+            }
+            ResourceKind::Container { runtime, name } => {
+                let _ = Command::new(runtime)
+                    .args(["rm", "-f", name])
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .helper_status(Helper::ContainerCli);
+            }
+            ResourceKind::Vm {
+                pid,
+                socket_path,
+                artifacts,
+            } => {
+                if self.is_same_process(*pid) {
+                    kill_process(*pid);
+                }
+                let _ = std::fs::remove_file(socket_path);
+                for artifact in artifacts {
+                    let _ = std::fs::remove_file(artifact);
+                }
+            }
+            ResourceKind::Workspace { paths } => {
+                for path in paths {
+                    let _ = if path.is_dir() {
+                        std::fs::remove_dir_all(path)
+                    } else {
+                        std::fs::remove_file(path)
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Summary of a sweep
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReapReport {
+    /// Resources that were still alive and got killed
+    pub reaped: u32,
+    /// Resources that had already exited and were dropped from the registry
+    pub pruned: u32,
+    /// Resources left alone because their owner is still using them
+    pub retained: u32,
+}
+
+/// Registry of everything cylo has spawned in this process
+#[derive(Debug)]
+pub struct Reaper {
+    resources: Mutex<HashMap<u64, TrackedResource>>,
+    next_id: AtomicU64,
+    state_dir: PathBuf,
+    sweeper_started: AtomicBool,
+}
+
+static GLOBAL_REAPER: OnceLock<Reaper> = OnceLock::new();
+
+/// Get the process-wide reaper
+pub fn global_reaper() -> &'static Reaper {
+    GLOBAL_REAPER.get_or_init(|| Reaper::new(std::env::temp_dir().join("cylo-reaper")))
+}
+
+impl Reaper {
+    /// Create a reaper that mirrors its registry under `state_dir`
+    pub fn new(state_dir: PathBuf) -> Self {
+        Self {
+            resources: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            state_dir,
+            sweeper_started: AtomicBool::new(false),
+        }
+    }
+
+    /// Register a spawned resource
+    ///
+    /// # Arguments
+    /// * `kind` - What was spawned
+    /// * `backend` - Backend that spawned it
+    /// * `execution_id` - Owning execution, if known
+    /// * `deadline` - Time after which it is reaped regardless of ownership
+    ///
+    /// # Returns
+    /// Guard that marks the resource finished when dropped
+    pub fn track(
+        &'static self,
+        kind: ResourceKind,
+        backend: &str,
+        execution_id: Option<&str>,
+        deadline: Option<Duration>,
+    ) -> ReaperGuard {
+        let id = self.register(kind, backend, execution_id, deadline);
+        ReaperGuard { reaper: self, id }
+    }
+
+    /// Register a spawned resource and return its raw identifier
+    ///
+    /// Use this when the resource outlives a single scope; call `release`
+    /// once it has been cleaned up.
+    pub fn register(
+        &self,
+        kind: ResourceKind,
+        backend: &str,
+        execution_id: Option<&str>,
+        deadline: Option<Duration>,
+    ) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let now = SystemTime::now();
+        let started = match &kind {
+            ResourceKind::Process { pid } | ResourceKind::Vm { pid, .. } => {
+                process_start_time(*pid)
+            }
+            ResourceKind::Container { .. } | ResourceKind::Workspace { .. } => None,
+        };
+        let resource = TrackedResource {
+            id,
+            kind,
+            backend: backend.to_string(),
+            execution_id: execution_id.map(str::to_string),
+            owner_pid: std::process::id(),
+            registered_at: now,
+            deadline: deadline.map(|d| now + d),
+            finished: false,
+            started,
+        };
+        debug!(target: targets::REAPER, "Reaper tracking {:?} for {}", resource.kind, backend);
+
+        if let Ok(mut resources) = self.resources.lock() {
+            resources.insert(id, resource);
+            self.persist(&resources);
+        }
+        id
+    }
+
+    /// Forget a resource its owner already cleaned up
+    pub fn release(&self, id: u64) {
+        if let Ok(mut resources) = self.resources.lock()
+            && resources.remove(&id).is_some()
+        {
+            self.persist(&resources);
+        }
+    }
+
+    /// Mark a resource's owning execution as finished
+    pub fn mark_finished(&self, id: u64) {
+        if let Ok(mut resources) = self.resources.lock()
+            && let Some(resource) = resources.get_mut(&id)
+        {
+            resource.finished = true;
+            self.persist(&resources);
+        }
+    }
+
+    /// Mark every resource of an execution as finished
+    pub fn finish_execution(&self, execution_id: &str) {
+        if let Ok(mut resources) = self.resources.lock() {
+            for resource in resources.values_mut() {
+                if resource.execution_id.as_deref() == Some(execution_id) {
+                    resource.finished = true;
+                }
+            }
+            self.persist(&resources);
+        }
+    }
+
+    /// Snapshot of currently tracked resources
+    pub fn tracked(&self) -> Vec<TrackedResource> {
+        self.resources
+            .lock()
+            .map(|resources| resources.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Kill every tracked resource belonging to an execution
+    ///
+    /// # Returns
+    /// Number of resources killed
+    pub fn kill_execution(&self, execution_id: &str) -> u32 {
+        self.reap_matching(|r| r.execution_id.as_deref() == Some(execution_id))
+    }
+
+    /// Kill every tracked resource spawned by a backend
+    ///
+    /// # Returns
+    /// Number of resources killed
+    pub fn reap_backend(&self, backend: &str) -> u32 {
+        self.reap_matching(|r| r.backend == backend)
+    }
+
+    fn reap_matching(&self, predicate: impl Fn(&TrackedResource) -> bool) -> u32 {
+        let targets: Vec<TrackedResource> = match self.resources.lock() {
+            Ok(mut resources) => {
+                let ids: Vec<u64> = resources
+                    .values()
+                    .filter(|r| predicate(r))
+                    .map(|r| r.id)
+                    .collect();
+                let targets = ids.iter().filter_map(|id| resources.remove(id)).collect();
+                self.persist(&resources);
+                targets
+            }
+            Err(_) => return 0,
+        };
+
+        for resource in &targets {
+            resource.terminate();
+        }
+        targets.len() as u32
+    }
+
+    /// Run one sweep over this process's registry and orphaned state files
+    pub fn sweep(&self) -> ReapReport {
+        let mut report = ReapReport::default();
+        let now = SystemTime::now();
+
+        let candidates: Vec<TrackedResource> = match self.resources.lock() {
+            Ok(resources) => resources.values().cloned().collect(),
+            Err(_) => return report,
+        };
+
+        let mut remove = Vec::new();
+        for resource in candidates {
+            let expired = resource.deadline.is_some_and(|deadline| now > deadline);
+            if !resource.is_alive() {
+                report.pruned += 1;
+                remove.push(resource.id);
+            } else if resource.finished || expired {
+                info!(
+                    target: targets::REAPER,
+                    "Reaping {:?} from {} (finished: {}, expired: {})",
+                    resource.kind, resource.backend, resource.finished, expired
+                );
+                resource.terminate();
+                report.reaped += 1;
+                remove.push(resource.id);
+            } else {
+                report.retained += 1;
+            }
+        }
+
+        if let Ok(mut resources) = self.resources.lock() {
+            for id in remove {
+                resources.remove(&id);
+            }
+            self.persist(&resources);
+        }
+
+        let orphaned = self.reap_dead_owners();
+        report.reaped += orphaned;
+        report
+    }
+
+    /// Reap resources recorded by cylo processes that are no longer running
+    pub(crate) fn reap_dead_owners(&self) -> u32 {
+        let Ok(entries) = std::fs::read_dir(&self.state_dir) else {
+            return 0;
+        };
+
+        let own_pid = std::process::id();
+        let mut reaped = 0;
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            let Some(owner_pid) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u32>().ok())
+            else {
+                continue;
+            };
+
+            if owner_pid == own_pid || process_alive(owner_pid) {
+                continue;
+            }
+
+            let resources: Vec<TrackedResource> = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+
+            // Only processes whose PID still refers to the recorded process
+            // are alive, so those in files left from before a reboot, or
+            // written without start times, are dropped unsignalled
+            for resource in resources.iter().filter(|r| r.is_alive()) {
+                warn!(
+                    target: targets::REAPER,
+                    "Reaping {:?} left behind by dead cylo process {}",
+                    resource.kind, owner_pid
+                );
+                resource.terminate();
+                reaped += 1;
+            }
+            let _ = std::fs::remove_file(&path);
+        }
+        reaped
+    }
+
+    /// Start the periodic sweeper on the current tokio runtime
+    ///
+    /// Idempotent; only the first call spawns a task. Does nothing when
+    /// called outside a runtime.
+    pub fn ensure_sweeper(&'static self, interval: Duration) {
+        if tokio::runtime::Handle::try_current().is_err()
+            || self.sweeper_started.swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let report = tokio::task::spawn_blocking(move || self.sweep())
+                    .await
+                    .unwrap_or_default();
+                if report.reaped > 0 {
+                    info!(target: targets::REAPER, "Reaper sweep: {:?}", report);
+                }
+            }
+        });
+    }
+
+    /// Mirror the registry to this process's state file
+    fn persist(&self, resources: &HashMap<u64, TrackedResource>) {
+        let path = self.state_dir.join(format!("{}.json", std::process::id()));
+        if resources.is_empty() {
+            let _ = std::fs::remove_file(&path);
+            return;
+        }
+
+        let entries: Vec<&TrackedResource> = resources.values().collect();
+        let result = std::fs::create_dir_all(&self.state_dir).and_then(|_| {
+            let json = serde_json::to_string(&entries).map_err(std::io::Error::other)?;
+            std::fs::write(&path, json)
+        });
+        if let Err(e) = result {
+            debug!(target: targets::REAPER, "Failed to persist reaper state: {}", e);
+        }
+    }
+}
+
+/// RAII registration that marks its resource finished when dropped
+///
+/// A finished resource that is still alive gets killed by the next sweep,
+/// so owners that waited on a process themselves call `release` instead.
+#[derive(Debug)]
+pub struct ReaperGuard {
+    reaper: &'static Reaper,
+    id: u64,
+}
+
+impl ReaperGuard {
+    /// Registry identifier of the tracked resource
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The owner cleaned the resource up itself; stop tracking it
+    pub fn release(self) {
+        self.reaper.release(self.id);
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for ReaperGuard {
+    fn drop(&mut self) {
+        self.reaper.mark_finished(self.id);
+    }
+}
+
+/// Start time of a process, unique to it across PID reuse and reboots
+///
+/// # Returns
+/// The boot ID and the start time in clock ticks since boot, or `None`
+/// when the process does not exist
+#[cfg(target_os = "linux")]
+pub(crate) fn process_start_time(pid: u32) -> Option<String> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The command name may contain spaces and parentheses; the fields after
+    // it start with the third (state), so the 22nd (starttime) is the 20th
+    let ticks = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .nth(19)?;
+    let boot_id = std::fs::read_to_string("/proc/sys/kernel/random/boot_id").ok()?;
+    Some(format!("{}/{ticks}", boot_id.trim()))
+}
+
+/// Start time of a process, unique to it across PID reuse and reboots
+///
+/// # Returns
+/// The wall-clock start time `ps` reports, or `None` when the process does
+/// not exist
+#[cfg(all(unix, not(target_os = "linux")))]
+pub(crate) fn process_start_time(pid: u32) -> Option<String> {
+    let output = Command::new("ps")
+        .args(["-o", "lstart=", "-p", &pid.to_string()])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let started = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !started.is_empty()).then_some(started)
+}
+
+/// Start time of a process, unique to it across PID reuse and reboots
+///
+/// # Returns
+/// The process creation time in 100ns intervals since 1601, or `None` when
+/// the process does not exist
+#[cfg(target_os = "windows")]
+pub(crate) fn process_start_time(pid: u32) -> Option<String> {
+    use windows::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut created = FILETIME::default();
+        let mut exited = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        let queried =
+            GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user).is_ok();
+        let _ = CloseHandle(handle);
+        queried.then(|| {
+            ((u64::from(created.dwHighDateTime) << 32) | u64::from(created.dwLowDateTime))
+                .to_string()
+        })
+    }
+}
+
+/// Check whether a process exists
+#[cfg(target_os = "linux")]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    use nix::errno::Errno;
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    !matches!(kill(Pid::from_raw(pid as i32), None), Err(Errno::ESRCH))
+}
+
+/// Check whether a process exists
+#[cfg(all(unix, not(target_os = "linux")))]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Check whether a process exists and has not exited
+#[cfg(target_os = "windows")]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    use windows::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        match OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
+            Ok(handle) => {
+                let mut code = 0u32;
+                let queried = GetExitCodeProcess(handle, &mut code).is_ok();
+                let _ = CloseHandle(handle);
+                queried && code == STILL_ACTIVE.0 as u32
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Forcefully kill a process
+#[cfg(target_os = "linux")]
+pub(crate) fn kill_process(pid: u32) {
+    use nix::sys::signal::{Signal, kill};
+    use nix::unistd::Pid;
+
+    let _ = kill(Pid::from_raw(pid as i32), Signal::SIGKILL);
+}
+
+/// Forcefully kill a process
+#[cfg(all(unix, not(target_os = "linux")))]
+pub(crate) fn kill_process(pid: u32) {
+    let _ = Command::new("kill")
+        .args(["-KILL", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Forcefully kill a process and its children
+#[cfg(target_os = "windows")]
+pub(crate) fn kill_process(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_reaper() -> Reaper {
+        Reaper::new(std::env::temp_dir().join(format!("cylo-reaper-test-{}", uuid::Uuid::new_v4())))
+    }
+
+    #[test]
+    fn exited_processes_are_pruned() {
+        let reaper = test_reaper();
+        // PIDs this large are never allocated
+        reaper.register(ResourceKind::Process { pid: 4_000_000 }, "Test", None, None);
+
+        let report = reaper.sweep();
+        assert_eq!(report.pruned, 1);
+        assert!(reaper.tracked().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn finished_live_processes_are_reaped() {
+        let reaper = test_reaper();
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+
+        let id = reaper.register(
+            ResourceKind::Process { pid: child.id() },
+            "Test",
+            Some("exec-1"),
+            None,
+        );
+        assert_eq!(reaper.sweep().retained, 1);
+
+        reaper.finish_execution("exec-1");
+        let report = reaper.sweep();
+        assert_eq!(report.reaped, 1);
+        assert!(reaper.tracked().iter().all(|r| r.id != id));
+
+        let _ = child.wait();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reused_pids_are_never_signalled() {
+        let reaper = test_reaper();
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+
+        let id = reaper.register(
+            ResourceKind::Process { pid: child.id() },
+            "Test",
+            Some("exec-2"),
+            None,
+        );
+        assert!(reaper.tracked()[0].started.is_some());
+
+        // Pretend the PID now belongs to a process other than the tracked one
+        reaper
+            .resources
+            .lock()
+            .unwrap()
+            .get_mut(&id)
+            .unwrap()
+            .started = Some("another process".to_string());
+        reaper.finish_execution("exec-2");
+        let report = reaper.sweep();
+        assert_eq!((report.pruned, report.reaped), (1, 0));
+        assert!(child.try_wait().unwrap().is_none());
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn release_forgets_resource() {
+        let reaper = test_reaper();
+        let id = reaper.register(ResourceKind::Process { pid: 4_000_001 }, "Test", None, None);
+        reaper.release(id);
+        assert!(reaper.tracked().is_empty());
+    }
+}
@@ -0,0 +1,573 @@
+// ============================================================================
+// File: packages/cylo/src/recovery.rs
+// ----------------------------------------------------------------------------
+// Startup crash-recovery for artifacts left behind by earlier cylo runs.
+//
+// Scans for workspace directories, jail execution directories, dangling
+// containers, stale FireCracker sockets and VM files, and orphaned ramdisk
+// VHDs, then removes them according to an age/ownership policy. A dry-run
+// mode reports what would be removed without touching anything.
+// ============================================================================
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::hardening::{Helper, HelperCommand};
+use crate::ids;
+use crate::logging::targets;
+use crate::reaper::{global_reaper, process_alive};
+
+/// Policy controlling which leftovers are cleaned up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecoveryPolicy {
+    /// Minimum age before an artifact is considered abandoned
+    ///
+    /// Artifacts whose owning process is known to be dead are removed
+    /// regardless of age; this only applies when ownership is unknown.
+    pub min_age: Duration,
+    /// Report what would be removed without removing anything
+    pub dry_run: bool,
+    /// Directory scanned for workspaces, sockets, VM files and VHDs
+    pub temp_dir: PathBuf,
+    /// LandLock jail roots scanned for `exec-*` directories
+    pub jail_roots: Vec<PathBuf>,
+    /// Container runtime CLI used to list `cylo-` containers, if any; by
+    /// default Apple's `container` on macOS and docker or podman elsewhere
+    pub container_runtime: Option<String>,
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        Self {
+            min_age: Duration::from_secs(3600), // 1 hour
+            dry_run: false,
+            temp_dir: std::env::temp_dir(),
+            jail_roots: vec![PathBuf::from("/tmp/cylo_landlock")],
+            container_runtime: if cfg!(target_os = "macos") {
+                Some("container".to_string())
+            } else {
+                crate::backends::docker::installed_runtime().map(String::from)
+            },
+        }
+    }
+}
+
+impl RecoveryPolicy {
+    /// Set the minimum age for artifacts of unknown ownership
+    pub fn with_min_age(mut self, min_age: Duration) -> Self {
+        self.min_age = min_age;
+        self
+    }
+
+    /// Only report leftovers, do not remove them
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Set the directory scanned for temporary artifacts
+    pub fn with_temp_dir<P: Into<PathBuf>>(mut self, temp_dir: P) -> Self {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+
+    /// Add a LandLock jail root to scan
+    pub fn with_jail_root<P: Into<PathBuf>>(mut self, jail_root: P) -> Self {
+        self.jail_roots.push(jail_root.into());
+        self
+    }
+
+    /// Set the container runtime CLI, or None to skip container scanning
+    pub fn with_container_runtime(mut self, runtime: Option<String>) -> Self {
+        self.container_runtime = runtime;
+        self
+    }
+}
+
+/// Kind of artifact left behind by a previous run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeftoverKind {
+    /// `cylo_<name>_<uuid>` workspace directory
+    WorkspaceDir,
+    /// `exec-<id>-<pid>` directory inside a LandLock jail
+    JailDir,
+    /// `cylo-<id>-<pid>` container
+    Container,
+    /// FireCracker API socket with no listener
+    VmSocket,
+    /// FireCracker config or log file
+    VmArtifact,
+    /// Ramdisk VHD image
+    Vhd,
+}
+
+/// Artifact found during a recovery scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leftover {
+    /// What the artifact is
+    pub kind: LeftoverKind,
+    /// Path or container name
+    pub location: String,
+    /// PID of the cylo process that created it, when encoded in its name
+    pub owner_pid: Option<u32>,
+    /// Time since the artifact was last modified, when known
+    pub age: Option<Duration>,
+}
+
+/// What recovery did with a leftover
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// Artifact was removed
+    Removed,
+    /// Artifact would be removed outside of dry-run mode
+    WouldRemove,
+    /// Artifact was left alone
+    Retained { reason: String },
+    /// Removal was attempted and failed
+    Failed { error: String },
+}
+
+/// Leftover paired with the action taken on it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryEntry {
+    /// Artifact that was found
+    pub leftover: Leftover,
+    /// Action taken
+    pub action: RecoveryAction,
+}
+
+/// Result of a recovery pass
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Whether the pass ran in dry-run mode
+    pub dry_run: bool,
+    /// Every leftover found and what happened to it
+    pub entries: Vec<RecoveryEntry>,
+    /// Resources reaped from registries of dead cylo processes
+    pub orphans_reaped: u32,
+    /// Ramdisks unmounted because the cylo process that created them exited
+    pub ramdisks_unmounted: u32,
+}
+
+impl RecoveryReport {
+    /// Number of artifacts removed (or that would be, in dry-run mode)
+    pub fn removed(&self) -> usize {
+        self.count(|action| {
+            matches!(action, RecoveryAction::Removed | RecoveryAction::WouldRemove)
+        })
+    }
+
+    /// Number of artifacts left in place by policy
+    pub fn retained(&self) -> usize {
+        self.count(|action| matches!(action, RecoveryAction::Retained { .. }))
+    }
+
+    /// Number of artifacts that could not be removed
+    pub fn failed(&self) -> usize {
+        self.count(|action| matches!(action, RecoveryAction::Failed { .. }))
+    }
+
+    fn count(&self, predicate: impl Fn(&RecoveryAction) -> bool) -> usize {
+        self.entries.iter().filter(|e| predicate(&e.action)).count()
+    }
+}
+
+impl fmt::Display for RecoveryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.dry_run { "would remove" } else { "removed" };
+        writeln!(
+            f,
+            "cylo recovery: {} {}, {} retained, {} failed, {} orphaned resources reaped",
+            verb,
+            self.removed(),
+            self.retained(),
+            self.failed(),
+            self.orphans_reaped
+        )?;
+        if self.ramdisks_unmounted > 0 {
+            writeln!(f, "  {} stale ramdisks unmounted", self.ramdisks_unmounted)?;
+        }
+        for entry in &self.entries {
+            let action = match &entry.action {
+                RecoveryAction::Removed => "removed".to_string(),
+                RecoveryAction::WouldRemove => "would remove".to_string(),
+                RecoveryAction::Retained { reason } => format!("retained ({reason})"),
+                RecoveryAction::Failed { error } => format!("failed ({error})"),
+            };
+            writeln!(
+                f,
+                "  {:?} {}: {}",
+                entry.leftover.kind, entry.leftover.location, action
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Find leftovers from previous runs without acting on them
+///
+/// # Arguments
+/// * `policy` - Locations to scan
+pub fn scan(policy: &RecoveryPolicy) -> Vec<Leftover> {
+    let mut leftovers = Vec::new();
+    let now = SystemTime::now();
+
+    for entry in read_dir_entries(&policy.temp_dir) {
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let age = age_of(&path, now);
+        let location = path.display().to_string();
+
+        let kind = if path.is_dir() && is_workspace_dir(name) {
+            Some((LeftoverKind::WorkspaceDir, None))
+        } else if name.starts_with("cylo_ramdisk_") && name.ends_with(".vhd") {
+            Some((LeftoverKind::Vhd, vhd_owner(&path)))
+        } else if let Some(stem) = name.strip_suffix(".sock")
+            && (stem.starts_with("cylo-") || stem.starts_with("firecracker-cylo-"))
+        {
+            Some((LeftoverKind::VmSocket, trailing_pid(stem)))
+        } else if let Some(stem) = name
+            .strip_suffix(".json")
+            .or_else(|| name.strip_suffix(".log"))
+            && stem.starts_with("cylo-")
+            && let Some(pid) = trailing_pid(stem)
+        {
+            // Require the owner PID suffix so unrelated cylo-* files are ignored
+            Some((LeftoverKind::VmArtifact, Some(pid)))
+        } else {
+            None
+        };
+
+        if let Some((kind, owner_pid)) = kind {
+            leftovers.push(Leftover {
+                kind,
+                location,
+                owner_pid,
+                age,
+            });
+        }
+    }
+
+    for jail_root in &policy.jail_roots {
+        for entry in read_dir_entries(jail_root) {
+            let path = entry.path();
+            if let Some(name) = path.file_name().and_then(|n| n.to_str())
+                && name.starts_with("exec-")
+                && path.is_dir()
+            {
+                leftovers.push(Leftover {
+                    kind: LeftoverKind::JailDir,
+                    location: path.display().to_string(),
+                    owner_pid: trailing_pid(name),
+                    age: age_of(&path, now),
+                });
+            }
+        }
+    }
+
+    if let Some(runtime) = &policy.container_runtime {
+        for name in list_containers(runtime) {
+            leftovers.push(Leftover {
+                kind: LeftoverKind::Container,
+                owner_pid: trailing_pid(&name),
+                location: name,
+                age: None,
+            });
+        }
+    }
+
+    leftovers
+}
+
+/// Scan for leftovers and clean them up according to the policy
+///
+/// # Arguments
+/// * `policy` - Scan locations, age threshold, and dry-run flag
+///
+/// # Returns
+/// Report of every leftover found and the action taken
+pub fn recover(policy: &RecoveryPolicy) -> RecoveryReport {
+    let own_pid = std::process::id();
+    let mut report = RecoveryReport {
+        dry_run: policy.dry_run,
+        ..RecoveryReport::default()
+    };
+
+    // Unmount ramdisks of exited processes first so their VHDs are no
+    // longer attached when the scan finds them
+    #[cfg(target_os = "windows")]
+    if !policy.dry_run {
+        report.ramdisks_unmounted = crate::windows::reconcile_mounts();
+    }
+
+    for leftover in scan(policy) {
+        let action = match retain_reason(&leftover, policy, own_pid) {
+            Some(reason) => RecoveryAction::Retained { reason },
+            None if policy.dry_run => RecoveryAction::WouldRemove,
+            None => match remove(&leftover, policy) {
+                Ok(()) => RecoveryAction::Removed,
+                Err(error) => RecoveryAction::Failed { error },
+            },
+        };
+        report.entries.push(RecoveryEntry { leftover, action });
+    }
+
+    if !policy.dry_run {
+        report.orphans_reaped = global_reaper().reap_dead_owners();
+    }
+
+    report
+}
+
+/// Run recovery once per process, off the calling thread when possible
+///
+/// Subsequent calls are no-ops. Uses the tokio blocking pool when a runtime
+/// is available, otherwise runs inline.
+pub fn recover_at_startup(policy: RecoveryPolicy) {
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let run = move || {
+        let report = recover(&policy);
+        if report.entries.is_empty()
+            && report.orphans_reaped == 0
+            && report.ramdisks_unmounted == 0
+        {
+            return;
+        }
+        if report.failed() > 0 {
+            warn!(target: targets::RECOVERY, "{report}");
+        } else {
+            info!(target: targets::RECOVERY, "{report}");
+        }
+    };
+
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            handle.spawn_blocking(run);
+        }
+        Err(_) => run(),
+    }
+}
+
+/// Why a leftover must be kept, or None if it may be removed
+fn retain_reason(leftover: &Leftover, policy: &RecoveryPolicy, own_pid: u32) -> Option<String> {
+    if let Some(pid) = leftover.owner_pid {
+        if pid == own_pid {
+            return Some("owned by this process".to_string());
+        }
+        if process_alive(pid) {
+            return Some(format!("owner process {pid} is still running"));
+        }
+        // Owner is gone; the artifact is abandoned regardless of age
+        return None;
+    }
+
+    if leftover.kind == LeftoverKind::VmSocket && socket_has_listener(&leftover.location) {
+        return Some("socket has an active listener".to_string());
+    }
+
+    match leftover.age {
+        Some(age) if age < policy.min_age => Some(format!(
+            "younger than {}s (age {}s)",
+            policy.min_age.as_secs(),
+            age.as_secs()
+        )),
+        Some(_) => None,
+        None => Some("age unknown and owner not recorded".to_string()),
+    }
+}
+
+/// Remove a leftover artifact
+fn remove(leftover: &Leftover, policy: &RecoveryPolicy) -> Result<(), String> {
+    match leftover.kind {
+        LeftoverKind::WorkspaceDir | LeftoverKind::JailDir => {
+            fs::remove_dir_all(&leftover.location).map_err(|e| e.to_string())
+        }
+        LeftoverKind::VmSocket | LeftoverKind::VmArtifact | LeftoverKind::Vhd => {
+            // An attached VHD cannot be deleted; that failure is reported
+            fs::remove_file(&leftover.location).map_err(|e| e.to_string())
+        }
+        LeftoverKind::Container => {
+            let runtime = policy
+                .container_runtime
+                .as_deref()
+                .ok_or_else(|| "no container runtime configured".to_string())?;
+            let status = Command::new(runtime)
+                .args(["rm", "-f", &leftover.location])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .helper_status(Helper::ContainerCli)
+                .map_err(|e| e.to_string())?;
+            if status.success() {
+                Ok(())
+            } else {
+                Err(format!("{runtime} rm exited with {status}"))
+            }
+        }
+    }
+}
+
+/// Directory entries, or nothing if the directory cannot be read
+fn read_dir_entries(dir: &Path) -> Vec<fs::DirEntry> {
+    fs::read_dir(dir)
+        .map(|entries| entries.filter_map(Result::ok).collect())
+        .unwrap_or_default()
+}
+
+/// Time since a path was last modified
+fn age_of(path: &Path, now: SystemTime) -> Option<Duration> {
+    let modified = fs::symlink_metadata(path).ok()?.modified().ok()?;
+    Some(now.duration_since(modified).unwrap_or_default())
+}
+
+/// Whether a name matches the `cylo_<name>_<random>` workspace layout
+fn is_workspace_dir(name: &str) -> bool {
+    ids::is_workspace_dir_name(name)
+}
+
+/// Process that owns a ramdisk VHD, from the Windows ramdisk state file
+#[cfg(target_os = "windows")]
+fn vhd_owner(path: &Path) -> Option<u32> {
+    crate::windows::vhd_owner(path)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn vhd_owner(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// PID encoded as the final `-<pid>` segment of a name
+fn trailing_pid(name: &str) -> Option<u32> {
+    name.rsplit_once('-')
+        .and_then(|(_, pid)| pid.parse::<u32>().ok())
+}
+
+/// Whether something is still accepting connections on a Unix socket
+#[cfg(unix)]
+fn socket_has_listener(path: &str) -> bool {
+    std::os::unix::net::UnixStream::connect(path).is_ok()
+}
+
+#[cfg(not(unix))]
+fn socket_has_listener(_path: &str) -> bool {
+    false
+}
+
+/// Names of all `cylo-` containers known to a runtime
+fn list_containers(runtime: &str) -> Vec<String> {
+    let Ok(output) = Command::new(runtime)
+        .args(["list", "--all", "--quiet"])
+        .stderr(Stdio::null())
+        .helper_output(Helper::ContainerCli)
+    else {
+        return Vec::new();
+    };
+
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|name| name.starts_with("cylo-"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PID above any real pid_max
+    const DEAD_PID: u32 = i32::MAX as u32;
+
+    fn test_policy() -> (RecoveryPolicy, PathBuf) {
+        let root = std::env::temp_dir()
+            .join(format!("cylo-recovery-test-{}", uuid::Uuid::new_v4()));
+        let temp_dir = root.join("tmp");
+        let jail = root.join("jail");
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::create_dir_all(&jail).unwrap();
+
+        let policy = RecoveryPolicy {
+            min_age: Duration::ZERO,
+            dry_run: false,
+            temp_dir,
+            jail_roots: vec![jail],
+            container_runtime: None,
+        };
+        (policy, root)
+    }
+
+    #[test]
+    fn dead_owner_jail_dirs_are_removed() {
+        let (policy, root) = test_policy();
+        let dead = policy.jail_roots[0].join(format!("exec-abc-{DEAD_PID}"));
+        let live = policy.jail_roots[0].join(format!("exec-def-{}", std::process::id()));
+        fs::create_dir_all(&dead).unwrap();
+        fs::create_dir_all(&live).unwrap();
+
+        let report = recover(&policy);
+
+        assert!(!dead.exists());
+        assert!(live.exists());
+        assert_eq!(report.removed(), 1);
+        assert_eq!(report.retained(), 1);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn dry_run_reports_without_removing() {
+        let (policy, root) = test_policy();
+        let workspace = policy
+            .temp_dir
+            .join(format!("cylo_python_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&workspace).unwrap();
+
+        let report = recover(&policy.clone().dry_run());
+
+        assert!(workspace.exists());
+        assert!(report.dry_run);
+        assert_eq!(report.removed(), 1);
+        assert_eq!(report.entries[0].action, RecoveryAction::WouldRemove);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn young_unowned_artifacts_are_retained() {
+        let (policy, root) = test_policy();
+        let policy = policy.with_min_age(Duration::from_secs(3600));
+        let vhd = policy
+            .temp_dir
+            .join(format!("cylo_ramdisk_{}.vhd", uuid::Uuid::new_v4()));
+        fs::write(&vhd, b"").unwrap();
+
+        let report = recover(&policy);
+
+        assert!(vhd.exists());
+        assert_eq!(report.retained(), 1);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn name_patterns() {
+        assert!(is_workspace_dir(&format!("cylo_rust_{}", uuid::Uuid::new_v4())));
+        assert!(!is_workspace_dir("cylo_landlock"));
+        assert_eq!(trailing_pid("cylo-0123abcd-4242"), Some(4242));
+        assert_eq!(trailing_pid("cylo-reaper"), None);
+    }
+}
@@ -3,7 +3,10 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{anyhow, Result};
-use log::info;
+use tracing::info;
+
+use crate::hardening::{Helper, HelperCommand};
+use crate::logging::targets;
 
 /// Configuration for repository initialization
 #[derive(Debug, Clone)]
@@ -26,7 +29,7 @@ impl Default for RepoConfig {
 
 /// Initializes a repository with metadata and git filters
 pub fn init_repository(config: &RepoConfig) -> Result<()> {
-    info!("Initializing repository at {:?}", config.path);
+    info!(target: targets::PIPELINE, "Initializing repository at {:?}", config.path);
 
     // Create .parallm directory if it doesn't exist
     let parallm_dir = config.path.join(".parallm");
@@ -47,7 +50,7 @@ pub fn init_repository(config: &RepoConfig) -> Result<()> {
         setup_git_filters(&config.path)?;
     }
 
-    info!("Repository initialization complete");
+    info!(target: targets::PIPELINE, "Repository initialization complete");
     Ok(())
 }
 
@@ -79,11 +82,11 @@ fn copy_filter_scripts(git_dir: &Path) -> Result<()> {
 /// Initializes git repository if not already initialized
 fn init_git(repo_path: &Path) -> Result<()> {
     if !repo_path.join(".git").exists() {
-        info!("Initializing git repository");
+        info!(target: targets::PIPELINE, "Initializing git repository");
         let output = Command::new("git")
             .arg("init")
             .current_dir(repo_path)
-            .output()?;
+            .helper_output(Helper::NetworkClient)?;
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
@@ -95,7 +98,7 @@ fn init_git(repo_path: &Path) -> Result<()> {
 
 /// Sets up git filters for the repository
 fn setup_git_filters(repo_path: &Path) -> Result<()> {
-    info!("Setting up git filters");
+    info!(target: targets::PIPELINE, "Setting up git filters");
 
     // Create .gitattributes if it doesn't exist
     let gitattributes = repo_path.join(".gitattributes");
@@ -121,7 +124,7 @@ fn setup_git_filters(repo_path: &Path) -> Result<()> {
         let output = Command::new("git")
             .args(args)
             .current_dir(repo_path)
-            .output()?;
+            .helper_output(Helper::NetworkClient)?;
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
@@ -0,0 +1,377 @@
+// ============================================================================
+// File: packages/cylo/src/results.rs
+// ----------------------------------------------------------------------------
+// Structured comparison of two execution results.
+//
+// Regression runs and replays need more than "same or not": which lines of
+// output changed, whether the exit status or outcome moved, and how much
+// more CPU or memory the second run used. Output is normalized before it is
+// compared, so line endings, timestamps and float noise the caller chose to
+// ignore do not show up as differences.
+// ============================================================================
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::backends::{ExecutionOutcome, ExecutionResult, ResourceUsage};
+
+/// Text timestamps are replaced with when `strip_timestamps` is set
+pub const TIMESTAMP_PLACEHOLDER: &str = "<timestamp>";
+
+/// Largest line-pair table the line diff builds; larger outputs have only
+/// their common prefix and suffix matched
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// How output is normalized before it is compared
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffOptions {
+    /// Treat `\r\n` as `\n`
+    pub normalize_line_endings: bool,
+    /// Ignore whitespace at the end of lines
+    pub trim_trailing_whitespace: bool,
+    /// Replace dates and times of day with a placeholder, so logs that
+    /// print when they ran compare equal
+    pub strip_timestamps: bool,
+    /// Numbers within this absolute distance of each other compare equal
+    pub float_tolerance: Option<f64>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            normalize_line_endings: true,
+            trim_trailing_whitespace: false,
+            strip_timestamps: false,
+            float_tolerance: None,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// Ignore whitespace at the end of lines
+    pub fn with_trim_trailing_whitespace(mut self, trim: bool) -> Self {
+        self.trim_trailing_whitespace = trim;
+        self
+    }
+
+    /// Replace dates and times of day before comparing
+    pub fn with_strip_timestamps(mut self, strip: bool) -> Self {
+        self.strip_timestamps = strip;
+        self
+    }
+
+    /// Compare numbers within `tolerance` of each other as equal
+    pub fn with_float_tolerance(mut self, tolerance: f64) -> Self {
+        self.float_tolerance = Some(tolerance.abs());
+        self
+    }
+
+    /// Apply every text normalization to `text`
+    pub fn normalize(&self, text: &str) -> String {
+        let mut text = if self.normalize_line_endings {
+            text.replace("\r\n", "\n")
+        } else {
+            text.to_string()
+        };
+        if self.strip_timestamps {
+            text = timestamp_pattern()
+                .replace_all(&text, TIMESTAMP_PLACEHOLDER)
+                .into_owned();
+        }
+        if self.trim_trailing_whitespace {
+            text = text.lines().map(str::trim_end).collect::<Vec<_>>().join("\n");
+        }
+        text
+    }
+
+    /// Whether two normalized lines compare equal
+    fn lines_match(&self, a: &str, b: &str) -> bool {
+        if a == b {
+            return true;
+        }
+        let Some(tolerance) = self.float_tolerance else {
+            return false;
+        };
+
+        // Equal text between the numbers and numbers within tolerance
+        let numbers = number_pattern();
+        let (mut rest_a, mut rest_b) = (a, b);
+        loop {
+            match (numbers.find(rest_a), numbers.find(rest_b)) {
+                (Some(num_a), Some(num_b)) => {
+                    if rest_a[..num_a.start()] != rest_b[..num_b.start()] {
+                        return false;
+                    }
+                    let close = match (num_a.as_str().parse::<f64>(), num_b.as_str().parse()) {
+                        (Ok(x), Ok(y)) => (x - y).abs() <= tolerance,
+                        _ => num_a.as_str() == num_b.as_str(),
+                    };
+                    if !close {
+                        return false;
+                    }
+                    rest_a = &rest_a[num_a.end()..];
+                    rest_b = &rest_b[num_b.end()..];
+                }
+                (None, None) => return rest_a == rest_b,
+                _ => return false,
+            }
+        }
+    }
+}
+
+fn timestamp_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        // ISO 8601 dates with an optional time and offset, or a bare
+        // time of day
+        Regex::new(concat!(
+            r"\d{4}-\d{2}-\d{2}",
+            r"(?:[T ]\d{2}:\d{2}(?::\d{2}(?:[.,]\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?)?",
+            r"|\b\d{2}:\d{2}:\d{2}(?:[.,]\d+)?\b",
+        ))
+        .expect("timestamp pattern is valid")
+    })
+}
+
+fn number_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"-?(?:\d+\.?\d*|\.\d+)(?:[eE][+-]?\d+)?").expect("number pattern is valid")
+    })
+}
+
+/// One changed line of output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineChange {
+    /// Line only in the first result, numbered from 1 in its output
+    Removed { line: usize, text: String },
+    /// Line only in the second result, numbered from 1 in its output
+    Added { line: usize, text: String },
+}
+
+/// Difference of two resource usages; positive when the second used more
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceDelta {
+    /// Peak memory usage in bytes
+    pub peak_memory: i64,
+    /// CPU time in milliseconds
+    pub cpu_time_ms: i64,
+    /// Processes created
+    pub process_count: i64,
+    /// Bytes written to disk
+    pub disk_bytes_written: i64,
+    /// Bytes read from disk
+    pub disk_bytes_read: i64,
+    /// Network bytes sent
+    pub network_bytes_sent: i64,
+    /// Network bytes received
+    pub network_bytes_received: i64,
+}
+
+impl ResourceDelta {
+    /// `b - a` for every counter
+    pub fn between(a: &ResourceUsage, b: &ResourceUsage) -> Self {
+        let delta = |x: u64, y: u64| {
+            (i128::from(y) - i128::from(x)).clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+        };
+        Self {
+            peak_memory: delta(a.peak_memory, b.peak_memory),
+            cpu_time_ms: delta(a.cpu_time_ms, b.cpu_time_ms),
+            process_count: i64::from(b.process_count) - i64::from(a.process_count),
+            disk_bytes_written: delta(a.disk_bytes_written, b.disk_bytes_written),
+            disk_bytes_read: delta(a.disk_bytes_read, b.disk_bytes_read),
+            network_bytes_sent: delta(a.network_bytes_sent, b.network_bytes_sent),
+            network_bytes_received: delta(a.network_bytes_received, b.network_bytes_received),
+        }
+    }
+}
+
+/// Structured difference between two execution results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultDiff {
+    /// Exit codes of both results, when they differ
+    pub exit_code: Option<(i32, i32)>,
+    /// Outcomes of both results, when they differ
+    pub outcome: Option<(ExecutionOutcome, ExecutionOutcome)>,
+    /// Changed stdout lines, after normalization
+    pub stdout: Vec<LineChange>,
+    /// Changed stderr lines, after normalization
+    pub stderr: Vec<LineChange>,
+    /// Wall time of the second result minus the first, in milliseconds
+    pub duration_delta_ms: i64,
+    /// Resource usage of the second result minus the first
+    pub resources: ResourceDelta,
+}
+
+impl ResultDiff {
+    /// Whether both results ended the same way with the same output;
+    /// duration and resource usage are not considered
+    pub fn is_equivalent(&self) -> bool {
+        self.exit_code.is_none()
+            && self.outcome.is_none()
+            && self.stdout.is_empty()
+            && self.stderr.is_empty()
+    }
+}
+
+impl fmt::Display for ResultDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((a, b)) = self.exit_code {
+            writeln!(f, "exit code: {a} -> {b}")?;
+        }
+        if let Some((a, b)) = self.outcome {
+            writeln!(f, "outcome: {a:?} -> {b:?}")?;
+        }
+        for (stream, changes) in [("stdout", &self.stdout), ("stderr", &self.stderr)] {
+            if changes.is_empty() {
+                continue;
+            }
+            writeln!(f, "{stream}:")?;
+            for change in changes {
+                match change {
+                    LineChange::Removed { line, text } => writeln!(f, "-{line}: {text}")?,
+                    LineChange::Added { line, text } => writeln!(f, "+{line}: {text}")?,
+                }
+            }
+        }
+        write!(
+            f,
+            "duration: {:+}ms, cpu: {:+}ms, peak memory: {:+} bytes",
+            self.duration_delta_ms, self.resources.cpu_time_ms, self.resources.peak_memory
+        )
+    }
+}
+
+/// Compare two results with the default normalization
+pub fn diff(a: &ExecutionResult, b: &ExecutionResult) -> ResultDiff {
+    diff_with(a, b, &DiffOptions::default())
+}
+
+/// Compare two results, normalizing their output as `options` says
+///
+/// # Returns
+/// What changed from `a` to `b`
+pub fn diff_with(a: &ExecutionResult, b: &ExecutionResult, options: &DiffOptions) -> ResultDiff {
+    let duration = |result: &ExecutionResult| {
+        i64::try_from(result.duration.as_millis()).unwrap_or(i64::MAX)
+    };
+    ResultDiff {
+        exit_code: (a.exit_code != b.exit_code).then_some((a.exit_code, b.exit_code)),
+        outcome: (a.outcome != b.outcome).then_some((a.outcome, b.outcome)),
+        stdout: diff_lines(&options.normalize(&a.stdout), &options.normalize(&b.stdout), options),
+        stderr: diff_lines(&options.normalize(&a.stderr), &options.normalize(&b.stderr), options),
+        duration_delta_ms: duration(b) - duration(a),
+        resources: ResourceDelta::between(&a.resource_usage, &b.resource_usage),
+    }
+}
+
+/// Lines removed from `a` and added in `b`, from a longest common
+/// subsequence of matching lines
+fn diff_lines(a: &str, b: &str, options: &DiffOptions) -> Vec<LineChange> {
+    let a: Vec<&str> = a.lines().collect();
+    let b: Vec<&str> = b.lines().collect();
+
+    let prefix = a
+        .iter()
+        .zip(&b)
+        .take_while(|(x, y)| options.lines_match(x, y))
+        .count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| options.lines_match(x, y))
+        .count();
+    let middle_a = &a[prefix..a.len() - suffix];
+    let middle_b = &b[prefix..b.len() - suffix];
+
+    let removed = |i: usize| LineChange::Removed {
+        line: prefix + i + 1,
+        text: middle_a[i].to_string(),
+    };
+    let added = |j: usize| LineChange::Added {
+        line: prefix + j + 1,
+        text: middle_b[j].to_string(),
+    };
+
+    let (n, m) = (middle_a.len(), middle_b.len());
+    if n == 0 || m == 0 || n.saturating_mul(m) > MAX_DIFF_CELLS {
+        return (0..n).map(removed).chain((0..m).map(added)).collect();
+    }
+
+    // lcs[i][j]: longest common subsequence of middle_a[i..] and middle_b[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if options.lines_match(middle_a[i], middle_b[j]) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if options.lines_match(middle_a[i], middle_b[j]) {
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            changes.push(removed(i));
+            i += 1;
+        } else {
+            changes.push(added(j));
+            j += 1;
+        }
+    }
+    changes.extend((i..n).map(removed));
+    changes.extend((j..m).map(added));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn diff_reports_changed_lines_and_deltas() {
+        let mut a =
+            ExecutionResult::success("start 2024-05-01T10:00:00Z\nx = 0.30000000000000004\nsame\n");
+        a.duration = Duration::from_millis(100);
+        a.resource_usage.cpu_time_ms = 40;
+        let mut b = ExecutionResult::failure(1, "boom");
+        b.stdout = "start 2024-05-02T11:30:00Z\r\nx = 0.3\nsame\nextra\n".to_string();
+        b.duration = Duration::from_millis(150);
+        b.resource_usage.cpu_time_ms = 30;
+
+        let strict = diff(&a, &b);
+        assert_eq!(strict.exit_code, Some((0, 1)));
+        assert_eq!(strict.duration_delta_ms, 50);
+        assert_eq!(strict.resources.cpu_time_ms, -10);
+        assert_eq!(strict.stdout.len(), 5);
+        assert!(!strict.is_equivalent());
+
+        let lenient = DiffOptions::default()
+            .with_strip_timestamps(true)
+            .with_float_tolerance(1e-9);
+        let relaxed = diff_with(&a, &b, &lenient);
+        assert_eq!(
+            relaxed.stdout,
+            vec![LineChange::Added {
+                line: 4,
+                text: "extra".to_string()
+            }]
+        );
+        assert_eq!(relaxed.stderr.len(), 1);
+        assert!(relaxed.to_string().contains("+4: extra"));
+
+        let same = diff_with(&a, &a.clone(), &lenient);
+        assert!(same.is_equivalent());
+    }
+}
@@ -15,8 +15,9 @@ use crate::error::{SandboxError, SandboxResult};
 /// - Language runtimes can't access system libraries or user directories outside the sandbox
 /// - Dependencies are localized to the sandboxed environment
 /// - Runtime behavior is predictable and repeatable
+#[derive(Debug, Clone)]
 pub struct SandboxedEnvironment {
-    /// Type of environment (python, node, rust, go, etc.)
+    /// Type of environment (python, node, rust, go, r, etc.)
     pub env_type: String,
     /// Path to the environment directory
     pub path: PathBuf,
@@ -53,6 +54,7 @@ impl SandboxedEnvironment {
             "node" => self.path.join("bin").join(binary_name),
             "rust" => self.path.join("bin").join(binary_name),
             "go" => self.path.join("bin").join(binary_name),
+            "r" => self.path.join("bin").join(binary_name),
             _ => PathBuf::from(binary_name),
         }
     }
@@ -1,7 +1,8 @@
 use std::fs;
 
-use log::{info, warn};
+use tracing::{info, warn};
 
+use crate::logging::targets;
 use crate::{
     error::{ExecError, Result},
     exec::find_command,
@@ -20,7 +21,7 @@ pub fn create_go_environment_impl<'a>(
     let mut env = SandboxedEnvironment::new("go", env_path.clone());
 
     if env_path.exists() {
-        info!("Go environment already exists at {:?}", env_path);
+        info!(target: targets::SANDBOX, "Go environment already exists at {:?}", env_path);
         env.is_valid = true;
         manager.add_environment(env);
         return manager.get_environment("go").ok_or_else(|| {
@@ -30,7 +31,7 @@ pub fn create_go_environment_impl<'a>(
         });
     }
 
-    info!("Creating Go environment at {:?}", env_path);
+    info!(target: targets::SANDBOX, "Creating Go environment at {:?}", env_path);
 
     // Create directory structure for a proper Go workspace
     let go_paths = [
@@ -43,6 +44,7 @@ pub fn create_go_environment_impl<'a>(
     for path in &go_paths {
         if let Err(e) = fs::create_dir_all(path) {
             warn!(
+                target: targets::SANDBOX,
                 "Failed to create Go env directory structure at {:?}: {}",
                 path, e
             );
@@ -85,7 +87,7 @@ pub fn create_go_environment_impl<'a>(
 
     let go_bin_path = env_path.join("bin").join("go");
     if let Err(e) = fs::write(&go_bin_path, go_wrapper) {
-        warn!("Failed to create Go wrapper script: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create Go wrapper script: {}", e);
         return Err(ExecError::RuntimeError(format!(
             "Failed to create Go wrapper script: {e}"
         )));
@@ -93,7 +95,7 @@ pub fn create_go_environment_impl<'a>(
 
     // Make it executable
     if let Err(e) = set_executable(&go_bin_path) {
-        warn!("Failed to make Go wrapper executable: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to make Go wrapper executable: {}", e);
         return Err(ExecError::RuntimeError(format!(
             "Failed to set permissions on Go wrapper: {e}"
         )));
@@ -102,7 +104,7 @@ pub fn create_go_environment_impl<'a>(
     // Create a simple hello world program to verify the environment
     let hello_dir = env_path.join("src").join("hello");
     if let Err(e) = fs::create_dir_all(&hello_dir) {
-        warn!("Failed to create hello directory: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create hello directory: {}", e);
     }
 
     if let Err(e) = fs::write(
@@ -116,10 +118,10 @@ func main() {
 }
 "#,
     ) {
-        warn!("Failed to create hello world Go program: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create hello world Go program: {}", e);
     }
 
-    info!("Created Go environment with workspace structure");
+    info!(target: targets::SANDBOX, "Created Go environment with workspace structure");
     env.is_valid = true;
 
     // Add environment variables
@@ -3,13 +3,15 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use log::{debug, error, warn};
+use tracing::{debug, error, warn};
 
+use crate::logging::targets;
 use crate::{error::Result, sandbox::environment::SandboxedEnvironment};
 
 mod go;
 mod node;
 mod python;
+mod r;
 mod rust;
 
 /// Manages sandboxed environments for different language runtimes
@@ -29,7 +31,7 @@ impl SandboxManager {
         if !base_dir.exists()
             && let Err(e) = fs::create_dir_all(&base_dir)
         {
-            error!("Failed to create sandbox base directory: {}", e);
+            error!(target: targets::SANDBOX, "Failed to create sandbox base directory: {}", e);
         }
 
         Self {
@@ -75,14 +77,24 @@ impl SandboxManager {
         go::create_go_environment_impl(self, name)
     }
 
+    /// Create an R environment with its own package library
+    pub fn create_r_environment(&mut self, name: &str) -> Result<&SandboxedEnvironment> {
+        r::create_r_environment_impl(self, name)
+    }
+
     /// Clean up all environments
     pub fn cleanup(&self) -> Result<()> {
         for env in &self.environments {
-            debug!("Cleaning up environment at {:?}", env.path);
+            debug!(target: targets::SANDBOX, "Cleaning up environment at {:?}", env.path);
             if env.path.exists()
                 && let Err(e) = fs::remove_dir_all(&env.path)
             {
-                warn!("Failed to clean up environment at {:?}: {}", env.path, e);
+                warn!(
+                    target: targets::SANDBOX,
+                    "Failed to clean up environment at {:?}: {}",
+                    env.path,
+                    e
+                );
             }
         }
         Ok(())
@@ -1,7 +1,8 @@
 use std::{fs, process::Command};
 
-use log::{info, warn};
+use tracing::{info, warn};
 
+use crate::logging::targets;
 use crate::{
     error::{ExecError, Result},
     exec::find_command,
@@ -20,7 +21,7 @@ pub fn create_node_environment_impl<'a>(
     let mut env = SandboxedEnvironment::new("node", env_path.clone());
 
     if env_path.exists() {
-        info!("Node.js environment already exists at {:?}", env_path);
+        info!(target: targets::SANDBOX, "Node.js environment already exists at {:?}", env_path);
         env.is_valid = true;
         manager.add_environment(env);
         return manager.get_environment("node").ok_or_else(|| {
@@ -30,11 +31,11 @@ pub fn create_node_environment_impl<'a>(
         });
     }
 
-    info!("Creating Node.js environment at {:?}", env_path);
+    info!(target: targets::SANDBOX, "Creating Node.js environment at {:?}", env_path);
 
     // Check if fnm is available
     if find_command(&["fnm"]).is_some() {
-        info!("Using fnm to create Node.js environment");
+        info!(target: targets::SANDBOX, "Using fnm to create Node.js environment");
 
         let env_path_str = safe_path_to_str(&env_path)?;
         let output = Command::new("fnm")
@@ -44,7 +45,10 @@ pub fn create_node_environment_impl<'a>(
         match output {
             Ok(output) => {
                 if output.status.success() {
-                    info!("Node.js environment created successfully with fnm");
+                    info!(
+                        target: targets::SANDBOX,
+                        "Node.js environment created successfully with fnm"
+                    );
                     env.is_valid = true;
 
                     // Add environment variables
@@ -69,17 +73,21 @@ pub fn create_node_environment_impl<'a>(
                     });
                 }
                 let stderr = String::from_utf8_lossy(&output.stderr);
-                warn!("Failed to create Node.js environment with fnm: {}", stderr);
+                warn!(
+                    target: targets::SANDBOX,
+                    "Failed to create Node.js environment with fnm: {}",
+                    stderr
+                );
             }
             Err(e) => {
-                warn!("Failed to execute fnm: {}", e);
+                warn!(target: targets::SANDBOX, "Failed to execute fnm: {}", e);
             }
         }
     }
 
     // Fall back to creating a simple directory structure with Node wrapper
     if let Err(e) = fs::create_dir_all(env_path.join("bin")) {
-        warn!("Failed to create Node.js env directory structure: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create Node.js env directory structure: {}", e);
         return Err(ExecError::RuntimeError(format!(
             "Failed to create Node.js environment directory: {e}"
         )));
@@ -120,7 +128,7 @@ pub fn create_node_environment_impl<'a>(
 
     let node_bin_path = env_path.join("bin").join("node");
     if let Err(e) = fs::write(&node_bin_path, node_wrapper) {
-        warn!("Failed to create Node.js wrapper script: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create Node.js wrapper script: {}", e);
         return Err(ExecError::RuntimeError(format!(
             "Failed to create Node.js wrapper script: {e}"
         )));
@@ -128,7 +136,7 @@ pub fn create_node_environment_impl<'a>(
 
     // Make it executable
     if let Err(e) = set_executable(&node_bin_path) {
-        warn!("Failed to make Node.js wrapper executable: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to make Node.js wrapper executable: {}", e);
         return Err(ExecError::RuntimeError(format!(
             "Failed to set permissions on Node.js wrapper: {e}"
         )));
@@ -136,10 +144,10 @@ pub fn create_node_environment_impl<'a>(
 
     // Create npm directory
     if let Err(e) = fs::create_dir_all(env_path.join("node_modules")) {
-        warn!("Failed to create node_modules directory: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create node_modules directory: {}", e);
     }
 
-    info!("Created minimal Node.js environment with wrapper script");
+    info!(target: targets::SANDBOX, "Created minimal Node.js environment with wrapper script");
     env.is_valid = true;
 
     // Add environment variables
@@ -1,7 +1,8 @@
 use std::{fs, process::Command};
 
-use log::{info, warn};
+use tracing::{info, warn};
 
+use crate::logging::targets;
 use crate::{
     error::{ExecError, Result},
     exec::find_command,
@@ -20,7 +21,7 @@ pub fn create_python_environment_impl<'a>(
     let mut env = SandboxedEnvironment::new("python", env_path.clone());
 
     if env_path.exists() {
-        info!("Python environment already exists at {:?}", env_path);
+        info!(target: targets::SANDBOX, "Python environment already exists at {:?}", env_path);
         env.is_valid = true;
         manager.add_environment(env);
         return manager.get_environment("python").ok_or_else(|| {
@@ -30,7 +31,7 @@ pub fn create_python_environment_impl<'a>(
         });
     }
 
-    info!("Creating Python virtual environment at {:?}", env_path);
+    info!(target: targets::SANDBOX, "Creating Python virtual environment at {:?}", env_path);
     // Check for Python interpreter using absolute paths first, which is more reliable in containers
     let python_candidates = &[
         "/usr/bin/python3",
@@ -70,7 +71,7 @@ pub fn create_python_environment_impl<'a>(
     match output {
         Ok(output) => {
             if output.status.success() {
-                info!("Python virtual environment created successfully");
+                info!(target: targets::SANDBOX, "Python virtual environment created successfully");
                 env.is_valid = true;
 
                 // Add environment variables
@@ -100,18 +101,26 @@ pub fn create_python_environment_impl<'a>(
                 })
             } else {
                 let stderr = String::from_utf8_lossy(&output.stderr);
-                warn!("Failed to create Python virtual environment: {}", stderr);
+                warn!(
+                    target: targets::SANDBOX,
+                    "Failed to create Python virtual environment: {}",
+                    stderr
+                );
                 Err(ExecError::CommandFailed(format!(
                     "Failed to create Python virtual environment: {stderr}"
                 )))
             }
         }
         Err(e) => {
-            warn!("Failed to execute Python venv command: {}", e);
+            warn!(target: targets::SANDBOX, "Failed to execute Python venv command: {}", e);
 
             // Try a simpler approach - create directory structure manually
             if let Err(e) = fs::create_dir_all(env_path.join("bin")) {
-                warn!("Failed to create Python env directory structure: {}", e);
+                warn!(
+                    target: targets::SANDBOX,
+                    "Failed to create Python env directory structure: {}",
+                    e
+                );
             }
 
             // Create an activation script that sets PATH
@@ -123,7 +132,7 @@ pub fn create_python_environment_impl<'a>(
             );
 
             if let Err(e) = fs::write(env_path.join("bin").join("activate"), activate_script) {
-                warn!("Failed to create activation script: {}", e);
+                warn!(target: targets::SANDBOX, "Failed to create activation script: {}", e);
             }
 
             // Create a simple wrapper script for python
@@ -134,14 +143,17 @@ pub fn create_python_environment_impl<'a>(
 
             let python_bin_path = env_path.join("bin").join("python");
             if let Err(e) = fs::write(&python_bin_path, python_wrapper) {
-                warn!("Failed to create Python wrapper script: {}", e);
+                warn!(target: targets::SANDBOX, "Failed to create Python wrapper script: {}", e);
             }
 
             // Make it executable
             if let Err(e) = set_executable(&python_bin_path) {
-                warn!("Failed to make Python wrapper executable: {}", e);
+                warn!(target: targets::SANDBOX, "Failed to make Python wrapper executable: {}", e);
             } else {
-                info!("Created minimal Python environment with wrapper script");
+                info!(
+                    target: targets::SANDBOX,
+                    "Created minimal Python environment with wrapper script"
+                );
                 env.is_valid = true;
 
                 // Add environment variables
@@ -0,0 +1,114 @@
+use std::fs;
+
+use tracing::{info, warn};
+
+use crate::logging::targets;
+use crate::{
+    error::{ExecError, Result},
+    exec::find_command,
+    platform_utils::set_executable,
+    sandbox::{environment::SandboxedEnvironment, path_utils::safe_path_to_str},
+};
+
+use super::SandboxManager;
+
+/// Create an R environment with its own package library
+pub fn create_r_environment_impl<'a>(
+    manager: &'a mut SandboxManager,
+    name: &str,
+) -> Result<&'a SandboxedEnvironment> {
+    let env_path = manager.base_dir().join(name);
+    let mut env = SandboxedEnvironment::new("r", env_path.clone());
+
+    if env_path.exists() {
+        info!(target: targets::SANDBOX, "R environment already exists at {:?}", env_path);
+        env.is_valid = true;
+        manager.add_environment(env);
+        return manager.get_environment("r").ok_or_else(|| {
+            ExecError::RuntimeError(
+                "Failed to retrieve R environment after adding it to sandbox".to_string(),
+            )
+        });
+    }
+
+    info!(target: targets::SANDBOX, "Creating R environment at {:?}", env_path);
+
+    // R ignores a user library that does not exist, so create it up front
+    let r_paths = [env_path.join("bin"), env_path.join("library")];
+    for path in &r_paths {
+        if let Err(e) = fs::create_dir_all(path) {
+            warn!(
+                target: targets::SANDBOX,
+                "Failed to create R env directory structure at {:?}: {}",
+                path, e
+            );
+            return Err(ExecError::RuntimeError(format!(
+                "Failed to create R environment directory structure: {e}"
+            )));
+        }
+    }
+
+    // Find Rscript - check for absolute paths first
+    let rscript_candidates = &[
+        "/usr/bin/Rscript",
+        "/usr/local/bin/Rscript",
+        "/usr/lib/R/bin/Rscript",
+        "/opt/R/current/bin/Rscript",
+        "Rscript",
+    ];
+
+    let rscript = find_command(rscript_candidates).ok_or_else(|| {
+        ExecError::RuntimeError(format!(
+            "No R runtime found. Tried: {rscript_candidates:?}"
+        ))
+    })?;
+
+    // Create a wrapper script that pins the library and skips site and
+    // user startup files, so host configuration cannot leak in
+    let library_path = env_path.join("library");
+    let library_path_str = safe_path_to_str(&library_path)?;
+    let rscript_wrapper = format!(
+        "#!/bin/sh\n\
+         export R_LIBS_USER=\"{library_path_str}\"\n\
+         export R_LIBS_SITE=\"\"\n\
+         {rscript} --no-site-file --no-init-file \"$@\"\n"
+    );
+
+    let rscript_bin_path = env_path.join("bin").join("Rscript");
+    if let Err(e) = fs::write(&rscript_bin_path, rscript_wrapper) {
+        warn!(target: targets::SANDBOX, "Failed to create Rscript wrapper script: {}", e);
+        return Err(ExecError::RuntimeError(format!(
+            "Failed to create Rscript wrapper script: {e}"
+        )));
+    }
+
+    // Make it executable
+    if let Err(e) = set_executable(&rscript_bin_path) {
+        warn!(target: targets::SANDBOX, "Failed to make Rscript wrapper executable: {}", e);
+        return Err(ExecError::RuntimeError(format!(
+            "Failed to set permissions on Rscript wrapper: {e}"
+        )));
+    }
+
+    info!(target: targets::SANDBOX, "Created R environment with private package library");
+    env.is_valid = true;
+
+    // Add environment variables
+    let bin_path = env_path.join("bin");
+    let bin_path_str = safe_path_to_str(&bin_path)?;
+    env.add_env_var("R_LIBS_USER", library_path_str);
+    env.add_env_var("R_LIBS_SITE", "");
+    env.add_env_var(
+        "PATH",
+        &format!(
+            "{}:{}",
+            bin_path_str,
+            std::env::var("PATH").unwrap_or_else(|_| String::new())
+        ),
+    );
+
+    manager.add_environment(env);
+    manager.get_environment("r").ok_or_else(|| {
+        ExecError::RuntimeError("Failed to retrieve R environment after creation".to_string())
+    })
+}
@@ -1,7 +1,8 @@
 use std::fs;
 
-use log::{info, warn};
+use tracing::{info, warn};
 
+use crate::logging::targets;
 use crate::{
     error::{ExecError, Result},
     exec::find_command,
@@ -20,7 +21,7 @@ pub fn create_rust_environment_impl<'a>(
     let mut env = SandboxedEnvironment::new("rust", env_path.clone());
 
     if env_path.exists() {
-        info!("Rust environment already exists at {:?}", env_path);
+        info!(target: targets::SANDBOX, "Rust environment already exists at {:?}", env_path);
         env.is_valid = true;
         manager.add_environment(env);
         return manager.get_environment("rust").ok_or_else(|| {
@@ -30,11 +31,11 @@ pub fn create_rust_environment_impl<'a>(
         });
     }
 
-    info!("Creating Rust environment at {:?}", env_path);
+    info!(target: targets::SANDBOX, "Creating Rust environment at {:?}", env_path);
 
     // Create directory structure
     if let Err(e) = fs::create_dir_all(env_path.join("bin")) {
-        warn!("Failed to create Rust env directory structure: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create Rust env directory structure: {}", e);
         return Err(ExecError::RuntimeError(format!(
             "Failed to create Rust environment directory: {e}"
         )));
@@ -51,12 +52,12 @@ edition = "2021"
 [dependencies]
 "#,
     ) {
-        warn!("Failed to create Cargo.toml: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create Cargo.toml: {}", e);
     }
 
     // Create src directory with main.rs
     if let Err(e) = fs::create_dir_all(env_path.join("src")) {
-        warn!("Failed to create src directory: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create src directory: {}", e);
     }
 
     if let Err(e) = fs::write(
@@ -66,7 +67,7 @@ edition = "2021"
 }
 "#,
     ) {
-        warn!("Failed to create main.rs: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create main.rs: {}", e);
     }
 
     // Find rustc and cargo - check absolute paths first
@@ -118,7 +119,7 @@ edition = "2021"
 
     let rustc_bin_path = env_path.join("bin").join("rustc");
     if let Err(e) = fs::write(&rustc_bin_path, rustc_wrapper) {
-        warn!("Failed to create rustc wrapper script: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create rustc wrapper script: {}", e);
         return Err(ExecError::RuntimeError(format!(
             "Failed to create rustc wrapper script: {e}"
         )));
@@ -126,7 +127,7 @@ edition = "2021"
 
     let cargo_bin_path = env_path.join("bin").join("cargo");
     if let Err(e) = fs::write(&cargo_bin_path, cargo_wrapper) {
-        warn!("Failed to create cargo wrapper script: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to create cargo wrapper script: {}", e);
         return Err(ExecError::RuntimeError(format!(
             "Failed to create cargo wrapper script: {e}"
         )));
@@ -134,14 +135,14 @@ edition = "2021"
 
     // Make them executable
     if let Err(e) = set_executable(&rustc_bin_path) {
-        warn!("Failed to make rustc wrapper executable: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to make rustc wrapper executable: {}", e);
     }
 
     if let Err(e) = set_executable(&cargo_bin_path) {
-        warn!("Failed to make cargo wrapper executable: {}", e);
+        warn!(target: targets::SANDBOX, "Failed to make cargo wrapper executable: {}", e);
     }
 
-    info!("Created minimal Rust environment with wrapper scripts");
+    info!(target: targets::SANDBOX, "Created minimal Rust environment with wrapper scripts");
     env.is_valid = true;
 
     // Add environment variables
@@ -1,13 +1,16 @@
 mod environment;
 mod manager;
 mod path_utils;
+mod registry;
 
 pub use environment::SandboxedEnvironment;
 pub use manager::SandboxManager;
 pub use path_utils::{safe_path_to_str, safe_path_to_string};
+pub use registry::{register_environment, registered_environment, unregister_environment};
 
-use log::info;
+use tracing::info;
 
+use crate::logging::targets;
 use crate::{config::RamdiskConfig, error::Result};
 
 /// Helper function to create a Python virtual environment
@@ -26,6 +29,7 @@ pub fn create_python_venv(config: &RamdiskConfig) -> Result<SandboxedEnvironment
     let ramdisk_path = config.mount_point.clone();
 
     info!(
+        target: targets::SANDBOX,
         "Creating Python virtual environment inside ramdisk at: {}",
         ramdisk_path.display()
     );
@@ -58,6 +62,7 @@ pub fn create_node_environment(config: &RamdiskConfig) -> Result<SandboxedEnviro
     let ramdisk_path = config.mount_point.clone();
 
     info!(
+        target: targets::SANDBOX,
         "Creating Node.js environment inside ramdisk at: {}",
         ramdisk_path.display()
     );
@@ -90,6 +95,7 @@ pub fn create_rust_environment(config: &RamdiskConfig) -> Result<SandboxedEnviro
     let ramdisk_path = config.mount_point.clone();
 
     info!(
+        target: targets::SANDBOX,
         "Creating Rust environment inside ramdisk at: {}",
         ramdisk_path.display()
     );
@@ -122,6 +128,7 @@ pub fn create_go_environment(config: &RamdiskConfig) -> Result<SandboxedEnvironm
     let ramdisk_path = config.mount_point.clone();
 
     info!(
+        target: targets::SANDBOX,
         "Creating Go environment inside ramdisk at: {}",
         ramdisk_path.display()
     );
@@ -137,3 +144,36 @@ pub fn create_go_environment(config: &RamdiskConfig) -> Result<SandboxedEnvironm
         Err(e) => Err(e),
     }
 }
+
+/// Helper function to create an R environment
+///
+/// Creates an isolated R environment with its own package library, which
+/// `install.packages()` writes to, within the secure ramdisk.
+///
+/// # Arguments
+/// * `config` - Ramdisk configuration with mount point
+///
+/// # Returns
+/// * A configured SandboxedEnvironment with R-specific environment variables
+/// * Error if environment creation fails
+pub fn create_r_environment(config: &RamdiskConfig) -> Result<SandboxedEnvironment> {
+    // Always use the ramdisk path for security
+    let ramdisk_path = config.mount_point.clone();
+
+    info!(
+        target: targets::SANDBOX,
+        "Creating R environment inside ramdisk at: {}",
+        ramdisk_path.display()
+    );
+
+    let mut sandbox_manager = SandboxManager::new(ramdisk_path);
+    match sandbox_manager.create_r_environment("r_env") {
+        Ok(env) => {
+            let mut env_copy = SandboxedEnvironment::new("r", env.path.clone());
+            env_copy.is_valid = env.is_valid;
+            env_copy.env_vars = env.env_vars.clone();
+            Ok(env_copy)
+        }
+        Err(e) => Err(e),
+    }
+}
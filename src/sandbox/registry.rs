@@ -0,0 +1,49 @@
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+
+use super::SandboxedEnvironment;
+
+/// Environments execution requests can refer to by name
+static ENVIRONMENTS: OnceLock<RwLock<HashMap<String, SandboxedEnvironment>>> = OnceLock::new();
+
+fn environments() -> &'static RwLock<HashMap<String, SandboxedEnvironment>> {
+    ENVIRONMENTS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Make an environment available to execution requests under a name
+///
+/// A request naming the environment (e.g. through
+/// `ExecutionRequest::with_python_env`) runs with it bound into the sandbox
+/// and activated. Registering a name again replaces the earlier environment.
+///
+/// # Arguments
+/// * `name` - Name requests refer to the environment by
+/// * `env` - Environment, usually created by a `SandboxManager`
+pub fn register_environment(name: &str, env: SandboxedEnvironment) {
+    environments()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(name.to_string(), env);
+}
+
+/// Remove a named environment; later requests naming it are rejected
+///
+/// # Returns
+/// The environment that was registered under the name, if any
+pub fn unregister_environment(name: &str) -> Option<SandboxedEnvironment> {
+    environments()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .remove(name)
+}
+
+/// Look up a named environment
+pub fn registered_environment(name: &str) -> Option<SandboxedEnvironment> {
+    environments()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(name)
+        .cloned()
+}
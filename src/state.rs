@@ -1,10 +1,11 @@
 use std::path::PathBuf;
 
-use log::{error, info, warn};
+use tracing::{error, info, warn};
 
 use crate::config::RamdiskConfig;
 #[cfg(target_os = "linux")]
 use crate::firecracker::FirecrackerVM;
+use crate::logging::targets;
 use crate::ramdisk::create_secure_ramdisk;
 use crate::task::{ExecutionPool, ExecutionTask};
 
@@ -103,28 +104,39 @@ impl ExecutionFlow {
 
     /// Handles incoming events and updates the state accordingly
     pub fn handle(&mut self, event: &PipelineEvent) {
-        info!("Handling event {:?} in state {:?}", event, self.state);
+        info!(target: targets::PIPELINE, "Handling event {:?} in state {:?}", event, self.state);
         match (&self.state, event) {
             (State::Init, PipelineEvent::ExecuteCode { language, code }) => {
-                info!("Received code execution request for {}", language);
+                info!(
+                    target: targets::PIPELINE,
+                    "Received code execution request for {}",
+                    language
+                );
 
                 // Check if Firecracker is available (Linux only)
                 #[cfg(target_os = "linux")]
                 {
                     if crate::firecracker::is_firecracker_available() {
-                        info!("Firecracker is available, using VM isolation");
+                        info!(
+                            target: targets::PIPELINE,
+                            "Firecracker is available, using VM isolation"
+                        );
                         // Block on async function from sync context
                         let handle = tokio::runtime::Handle::current();
                         match handle.block_on(crate::firecracker::create_firecracker_environment(
                             &self.config,
                         )) {
                             Ok(vm) => {
-                                info!("Firecracker VM created successfully");
+                                info!(
+                                    target: targets::PIPELINE,
+                                    "Firecracker VM created successfully"
+                                );
                                 self.firecracker_vm = Some(vm);
                                 self.state = State::PrepareExecution;
                             }
                             Err(e) => {
                                 warn!(
+                                    target: targets::PIPELINE,
                                     "Failed to create Firecracker VM: {}, falling back to ramdisk",
                                     e
                                 );
@@ -132,14 +144,14 @@ impl ExecutionFlow {
                             }
                         }
                     } else {
-                        info!("Using Linux native ramdisk isolation");
+                        info!(target: targets::PIPELINE, "Using Linux native ramdisk isolation");
                         self.state = State::MountRamdisk;
                     }
                 }
 
                 #[cfg(not(target_os = "linux"))]
                 {
-                    info!("Using platform-specific isolation");
+                    info!(target: targets::PIPELINE, "Using platform-specific isolation");
                     self.state = State::MountRamdisk;
                 }
 
@@ -151,12 +163,14 @@ impl ExecutionFlow {
             (State::Init, PipelineEvent::StepSuccess) => {
                 self.step_count += 1;
                 info!(
+                    target: targets::PIPELINE,
                     "Mounting secure ramdisk with Landlock protection... (Step {})",
                     self.step_count
                 );
                 match create_secure_ramdisk(&self.config) {
                     Ok(_) => {
                         info!(
+                            target: targets::PIPELINE,
                             "Secure ramdisk mounted successfully at {}",
                             self.config.mount_point.display()
                         );
@@ -165,33 +179,35 @@ impl ExecutionFlow {
                         self.state = State::PrepareExecution;
                     }
                     Err(e) => {
-                        error!("Failed to mount secure ramdisk: {}", e);
+                        error!(target: targets::PIPELINE, "Failed to mount secure ramdisk: {}", e);
                         self.state = State::Failed;
                     }
                 }
             }
             (State::Init, PipelineEvent::StepError(msg)) => {
-                error!("Error in init: {}", msg);
+                error!(target: targets::PIPELINE, "Error in init: {}", msg);
                 self.state = State::Failed;
             }
             (State::Init, PipelineEvent::FileChanged(path)) => {
-                info!("File changed in init state: {}", path.display());
+                info!(target: targets::PIPELINE, "File changed in init state: {}", path.display());
             }
             (State::MountRamdisk, PipelineEvent::StepSuccess) => {
                 // Linux ramdisk setup
                 info!(
+                    target: targets::PIPELINE,
                     "Trying to set up the ramdisk at {}",
                     self.config.mount_point.display()
                 );
                 match create_secure_ramdisk(&self.config) {
                     Ok(_) => {
-                        info!("Secure ramdisk mounted successfully");
+                        info!(target: targets::PIPELINE, "Secure ramdisk mounted successfully");
                         self.execution_pool = Some(ExecutionPool::new(4, self.config.clone()));
                         self.state = State::PrepareExecution;
                     }
                     Err(e) => {
                         // The error message from linux.rs should already be clear and helpful
                         error!(
+                            target: targets::PIPELINE,
                             "Failed to create ramdisk: {}. Sandboxed environments require ramdisk.",
                             e
                         );
@@ -209,7 +225,7 @@ impl ExecutionFlow {
                 }
             }
             (State::PrepareExecution, PipelineEvent::ExecuteCode { language, code }) => {
-                info!("Preparing to execute {} code", language);
+                info!(target: targets::PIPELINE, "Preparing to execute {} code", language);
 
                 if let Some(pool) = &self.execution_pool {
                     let task = ExecutionTask {
@@ -220,12 +236,16 @@ impl ExecutionFlow {
 
                     match pool.submit_task(task) {
                         Ok(_) => {
-                            info!("Task {} submitted for execution", self.tasks_submitted);
+                            info!(
+                                target: targets::PIPELINE,
+                                "Task {} submitted for execution",
+                                self.tasks_submitted
+                            );
                             self.tasks_submitted += 1;
                             self.state = State::Processing;
                         }
                         Err(e) => {
-                            error!("Failed to submit task: {}", e);
+                            error!(target: targets::PIPELINE, "Failed to submit task: {}", e);
                             self.cleanup_ramdisk();
                             self.state = State::Failed;
                         }
@@ -240,9 +260,14 @@ impl ExecutionFlow {
                         Ok(outcome) => {
                             self.tasks_completed += 1;
                             if outcome.success {
-                                info!("Task {} completed successfully", outcome.task_id);
+                                info!(
+                                    target: targets::PIPELINE,
+                                    "Task {} completed successfully",
+                                    outcome.task_id
+                                );
                             } else {
                                 error!(
+                                    target: targets::PIPELINE,
                                     "Task {} failed: {}",
                                     outcome.task_id,
                                     outcome.error.unwrap_or_else(|| "Unknown error".to_string())
@@ -251,7 +276,7 @@ impl ExecutionFlow {
 
                             // If all tasks are complete, move to Done state
                             if self.tasks_completed == self.tasks_submitted {
-                                info!("All tasks completed");
+                                info!(target: targets::PIPELINE, "All tasks completed");
                                 self.cleanup_ramdisk();
                                 self.state = State::Done;
                             }
@@ -259,7 +284,11 @@ impl ExecutionFlow {
                         Err(e) => {
                             // Don't treat TryRecvError as fatal - only log it if it's a real error
                             if e.to_string().contains("disconnected") {
-                                error!("Task channel disconnected: {}", e);
+                                error!(
+                                    target: targets::PIPELINE,
+                                    "Task channel disconnected: {}",
+                                    e
+                                );
                                 self.cleanup_ramdisk();
                                 self.state = State::Failed;
                             } else {
@@ -271,10 +300,14 @@ impl ExecutionFlow {
                 }
             }
             (State::Done, PipelineEvent::FileChanged(path)) => {
-                info!("File changed while in done state: {}", path.display());
+                info!(
+                    target: targets::PIPELINE,
+                    "File changed while in done state: {}",
+                    path.display()
+                );
             }
             (State::Failed, PipelineEvent::FileChanged(path)) => {
-                error!("File changed while failed: {}", path.display());
+                error!(target: targets::PIPELINE, "File changed while failed: {}", path.display());
             }
             _ => {}
         }
@@ -289,9 +322,9 @@ impl ExecutionFlow {
                 // Block on async function from sync context
                 let handle = tokio::runtime::Handle::current();
                 if let Err(e) = handle.block_on(vm.stop()) {
-                    error!("Failed to stop Firecracker VM: {}", e);
+                    error!(target: targets::PIPELINE, "Failed to stop Firecracker VM: {}", e);
                 } else {
-                    info!("Firecracker VM stopped successfully");
+                    info!(target: targets::PIPELINE, "Firecracker VM stopped successfully");
                 }
             }
         }
@@ -302,24 +335,30 @@ impl ExecutionFlow {
         // First check if the ramdisk is actually mounted
         match crate::ramdisk::is_mounted(mount_point) {
             Ok(true) => {
-                info!("Attempting to unmount ramdisk at {}", mount_point.display());
+                info!(
+                    target: targets::PIPELINE,
+                    "Attempting to unmount ramdisk at {}",
+                    mount_point.display()
+                );
                 if let Err(e) = crate::ramdisk::remove_ramdisk(mount_point) {
                     warn!(
+                        target: targets::PIPELINE,
                         "Failed to cleanup ramdisk: {} - this is expected if ramdisk wasn't created",
                         e
                     );
                 } else {
-                    info!("Ramdisk cleanup successful");
+                    info!(target: targets::PIPELINE, "Ramdisk cleanup successful");
                 }
             }
             Ok(false) => {
                 info!(
+                    target: targets::PIPELINE,
                     "No mounted ramdisk found at {}, skipping cleanup",
                     mount_point.display()
                 );
             }
             Err(e) => {
-                warn!("Failed to check if ramdisk is mounted: {}", e);
+                warn!(target: targets::PIPELINE, "Failed to check if ramdisk is mounted: {}", e);
             }
         }
 
@@ -332,37 +371,41 @@ impl ExecutionFlow {
 
         // Clean up Python environment
         if python_env_path.exists() {
-            info!("Cleaning up Python environment at {:?}", python_env_path);
+            info!(
+                target: targets::PIPELINE,
+                "Cleaning up Python environment at {:?}",
+                python_env_path
+            );
             if let Err(e) = std::fs::remove_dir_all(&python_env_path) {
-                warn!("Failed to clean up Python environment: {}", e);
+                warn!(target: targets::PIPELINE, "Failed to clean up Python environment: {}", e);
             }
         }
 
         // Clean up Node environment
         if node_env_path.exists() {
-            info!("Cleaning up Node environment at {:?}", node_env_path);
+            info!(target: targets::PIPELINE, "Cleaning up Node environment at {:?}", node_env_path);
             if let Err(e) = std::fs::remove_dir_all(&node_env_path) {
-                warn!("Failed to clean up Node environment: {}", e);
+                warn!(target: targets::PIPELINE, "Failed to clean up Node environment: {}", e);
             }
         }
 
         // Clean up Rust environment
         if rust_env_path.exists() {
-            info!("Cleaning up Rust environment at {:?}", rust_env_path);
+            info!(target: targets::PIPELINE, "Cleaning up Rust environment at {:?}", rust_env_path);
             if let Err(e) = std::fs::remove_dir_all(&rust_env_path) {
-                warn!("Failed to clean up Rust environment: {}", e);
+                warn!(target: targets::PIPELINE, "Failed to clean up Rust environment: {}", e);
             }
         }
 
         // Clean up Go environment
         if go_env_path.exists() {
-            info!("Cleaning up Go environment at {:?}", go_env_path);
+            info!(target: targets::PIPELINE, "Cleaning up Go environment at {:?}", go_env_path);
             if let Err(e) = std::fs::remove_dir_all(&go_env_path) {
-                warn!("Failed to clean up Go environment: {}", e);
+                warn!(target: targets::PIPELINE, "Failed to clean up Go environment: {}", e);
             }
         }
 
         // Ensure we're not leaving any security holes
-        info!("Verifying all security restrictions are removed");
+        info!(target: targets::PIPELINE, "Verifying all security restrictions are removed");
     }
 }
@@ -2,12 +2,13 @@ use std::fmt::Formatter;
 use std::sync::{Arc, Mutex, mpsc};
 use std::thread;
 
-use log::{error, info};
+use tracing::{error, info};
 
 use crate::config::RamdiskConfig;
 use crate::error::ExecError;
 use crate::exec::{exec_go, exec_js, exec_python, exec_rust};
 use crate::exec_bash;
+use crate::logging::targets;
 
 /// Represents a code execution task
 #[derive(Debug)]
@@ -94,21 +95,26 @@ impl ExecutionPool {
         result_tx: mpsc::Sender<ExecutionOutcome>,
         config: RamdiskConfig,
     ) {
-        info!("Worker {} started", worker_id);
+        info!(target: targets::PIPELINE, "Worker {} started", worker_id);
 
         loop {
-            info!("Worker {} waiting for next task", worker_id);
+            info!(target: targets::PIPELINE, "Worker {} waiting for next task", worker_id);
             let next_task = {
                 let lock = match rx.lock() {
                     Ok(l) => l,
                     Err(poisoned) => {
-                        error!("Worker {} mutex poisoned, recovering", worker_id);
+                        error!(
+                            target: targets::PIPELINE,
+                            "Worker {} mutex poisoned, recovering",
+                            worker_id
+                        );
                         poisoned.into_inner()
                     }
                 };
-                info!("Worker {} acquired lock", worker_id);
+                info!(target: targets::PIPELINE, "Worker {} acquired lock", worker_id);
                 let task = lock.recv();
                 info!(
+                    target: targets::PIPELINE,
                     "Worker {} received task result: {:?}",
                     worker_id,
                     task.is_ok()
@@ -119,12 +125,17 @@ impl ExecutionPool {
             let task = match next_task {
                 Ok(t) => t,
                 Err(_) => {
-                    info!("Worker {} channel closed, exiting", worker_id);
+                    info!(
+                        target: targets::PIPELINE,
+                        "Worker {} channel closed, exiting",
+                        worker_id
+                    );
                     break;
                 }
             };
 
             info!(
+                target: targets::PIPELINE,
                 "Worker {} processing task {} with language {}",
                 worker_id, task.id, task.language
             );
@@ -133,9 +144,10 @@ impl ExecutionPool {
                 "go" => exec_go(&task.code, &config),
                 "rust" => exec_rust(&task.code, &config),
                 "python" => {
-                    info!("Worker {} executing Python code", worker_id);
+                    info!(target: targets::PIPELINE, "Worker {} executing Python code", worker_id);
                     let result = exec_python(&task.code, &config);
                     info!(
+                        target: targets::PIPELINE,
                         "Worker {} Python execution result: {:?}",
                         worker_id,
                         result.is_ok()
@@ -144,10 +156,15 @@ impl ExecutionPool {
                 }
                 "js" => exec_js(&task.code, &config),
                 "bash" => exec_bash(&task.code, &config),
-                _ => Err(ExecError::UnsupportedLanguage(task.language.clone())),
+                _ => Err(ExecError::UnsupportedLanguage(task.language.clone()).into()),
             };
 
-            info!("Worker {} sending outcome for task {}", worker_id, task.id);
+            info!(
+                target: targets::PIPELINE,
+                "Worker {} sending outcome for task {}",
+                worker_id,
+                task.id
+            );
             let execution_outcome = ExecutionOutcome {
                 task_id: task.id,
                 success: outcome.is_ok(),
@@ -155,12 +172,17 @@ impl ExecutionPool {
             };
 
             if let Err(e) = result_tx.send(execution_outcome) {
-                error!("Worker {} failed to send outcome: {}", worker_id, e);
+                error!(
+                    target: targets::PIPELINE,
+                    "Worker {} failed to send outcome: {}",
+                    worker_id,
+                    e
+                );
                 break;
             }
-            info!("Worker {} successfully sent outcome", worker_id);
+            info!(target: targets::PIPELINE, "Worker {} successfully sent outcome", worker_id);
         }
 
-        info!("Worker {} finished", worker_id);
+        info!(target: targets::PIPELINE, "Worker {} finished", worker_id);
     }
 }
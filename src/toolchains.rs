@@ -0,0 +1,528 @@
+// ============================================================================
+// File: packages/cylo/src/toolchains.rs
+// ----------------------------------------------------------------------------
+// Self-service installation of missing language toolchains.
+//
+// When a request needs an interpreter or compiler the host does not have,
+// `remediation_for` turns the backend error into an installable offer and
+// `ToolchainManager::install` performs it inside a toolchain root owned by
+// the workspace: standalone Python builds (SHA-256 verified here), Node via
+// fnm, and Rust via rustup with a local RUSTUP_HOME. fnm and rustup verify
+// the checksums of what they download themselves. Installing activates the
+// root: host backends then resolve pinned versions against its toolchains
+// too, and LandLock binds them into its sandbox.
+// ============================================================================
+
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use sha2::{Digest, Sha256};
+
+use crate::async_task::AsyncTaskBuilder;
+use crate::backends::runtime::version_matches;
+use crate::backends::{AsyncTask, BackendError, BackendResult, language};
+use crate::logging::targets;
+
+/// Where a toolchain is installed from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToolchainSource {
+    /// A standalone build archive verified against a pinned SHA-256
+    Archive {
+        /// Download URL of the `.tar.gz` archive
+        url: String,
+        /// Expected lowercase hex SHA-256 of the archive
+        sha256: String,
+    },
+    /// Node.js installed by fnm into the toolchain root
+    Fnm,
+    /// A Rust toolchain installed by rustup into the toolchain root
+    Rustup,
+}
+
+/// An installable fix for a missing interpreter or compiler
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Remediation {
+    /// Canonical language name
+    pub language: String,
+    /// Version to install
+    pub version: String,
+    /// How the toolchain is obtained
+    pub source: ToolchainSource,
+}
+
+impl Remediation {
+    /// Install a standalone Python build from python-build-standalone
+    ///
+    /// # Arguments
+    /// * `version` - Full Python version, e.g. `3.12.4`
+    /// * `release` - python-build-standalone release tag, e.g. `20240726`
+    /// * `sha256` - SHA-256 of the `install_only` archive for this host
+    pub fn python_standalone(version: &str, release: &str, sha256: &str) -> Self {
+        Self {
+            language: "python".to_string(),
+            version: version.to_string(),
+            source: ToolchainSource::Archive {
+                url: format!(
+                    "https://github.com/astral-sh/python-build-standalone/releases/download/\
+                     {release}/cpython-{version}+{release}-{}-install_only.tar.gz",
+                    host_triple()
+                ),
+                sha256: sha256.to_ascii_lowercase(),
+            },
+        }
+    }
+
+    /// Whether installing requires a checksum the caller has not supplied
+    pub fn needs_checksum(&self) -> bool {
+        matches!(&self.source, ToolchainSource::Archive { sha256, .. } if sha256.is_empty())
+    }
+}
+
+impl fmt::Display for Remediation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            ToolchainSource::Archive { url, .. } => {
+                write!(f, "install {} {} from {}", self.language, self.version, url)
+            }
+            ToolchainSource::Fnm => write!(f, "install node {} with fnm", self.version),
+            ToolchainSource::Rustup => write!(f, "install rust {} with rustup", self.version),
+        }
+    }
+}
+
+/// Offer a toolchain installation that would fix a backend error
+///
+/// Recognises missing pinned versions and unsupported-language errors for
+/// Node and Rust, whose installers verify what they download. Python
+/// archives have no published checksum to trust automatically, so no
+/// offer is made for them; build one with a known checksum with
+/// `Remediation::python_standalone`.
+///
+/// # Arguments
+/// * `error` - Error returned by a backend
+/// * `default_version` - Version to offer when the error names none
+pub fn remediation_for(error: &BackendError, default_version: &str) -> Option<Remediation> {
+    let (language, version) = match error {
+        BackendError::RuntimeVersionUnavailable {
+            language,
+            requested,
+            ..
+        } => (language.as_str(), requested.as_str()),
+        BackendError::UnsupportedLanguage { language, .. } => {
+            let (name, version) = language::split_version(language);
+            (name, version.unwrap_or(default_version))
+        }
+        _ => return None,
+    };
+
+    let spec = language::resolve(language)?;
+    let source = match spec.name {
+        "javascript" => ToolchainSource::Fnm,
+        "rust" => ToolchainSource::Rustup,
+        _ => return None,
+    };
+
+    Some(Remediation {
+        language: spec.name.to_string(),
+        version: version.to_string(),
+        source,
+    })
+}
+
+/// A toolchain installed under a `ToolchainManager` root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledToolchain {
+    /// Canonical language name
+    pub language: String,
+    /// Installed version
+    pub version: String,
+    /// Directory holding the toolchain's executables
+    pub bin_dir: PathBuf,
+}
+
+impl InstalledToolchain {
+    /// Directory the toolchain is installed in, holding `bin_dir` and the
+    /// libraries its executables load
+    pub fn install_dir(&self) -> &Path {
+        self.bin_dir.parent().unwrap_or(&self.bin_dir)
+    }
+}
+
+/// Roots whose toolchains the backends run
+fn activated_roots() -> &'static Mutex<Vec<PathBuf>> {
+    static ROOTS: OnceLock<Mutex<Vec<PathBuf>>> = OnceLock::new();
+    ROOTS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Toolchains installed under every activated root
+pub(crate) fn activated_toolchains() -> Vec<InstalledToolchain> {
+    let roots = activated_roots()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    roots
+        .into_iter()
+        .flat_map(|root| ToolchainManager::new(root).installed())
+        .collect()
+}
+
+/// Installs toolchains into a directory owned by a workspace
+#[derive(Debug, Clone)]
+pub struct ToolchainManager {
+    root: PathBuf,
+}
+
+impl ToolchainManager {
+    /// Create a manager installing under `root`
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Toolchain root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Toolchains already installed under the root
+    pub fn installed(&self) -> Vec<InstalledToolchain> {
+        let mut installed = Vec::new();
+
+        for version in subdirectories(&self.root.join("python")) {
+            let bin_dir = self.root.join("python").join(&version).join("python/bin");
+            installed.push(InstalledToolchain {
+                language: "python".to_string(),
+                version,
+                bin_dir,
+            });
+        }
+        for version in subdirectories(&self.fnm_dir().join("node-versions")) {
+            let bin_dir = self
+                .fnm_dir()
+                .join("node-versions")
+                .join(&version)
+                .join("installation/bin");
+            installed.push(InstalledToolchain {
+                language: "javascript".to_string(),
+                version: version.trim_start_matches('v').to_string(),
+                bin_dir,
+            });
+        }
+        for toolchain in subdirectories(&self.rustup_home().join("toolchains")) {
+            let bin_dir = self.rustup_home().join("toolchains").join(&toolchain).join("bin");
+            let version = toolchain
+                .strip_suffix(&format!("-{}", host_triple()))
+                .unwrap_or(&toolchain)
+                .to_string();
+            installed.push(InstalledToolchain {
+                language: "rust".to_string(),
+                version,
+                bin_dir,
+            });
+        }
+
+        installed
+    }
+
+    /// Let the backends run the toolchains installed under this root
+    ///
+    /// Host backends search the toolchains after PATH when resolving a
+    /// pinned version, and LandLock binds them into its sandbox. `install`
+    /// activates the root itself.
+    pub fn activate(&self) {
+        let mut roots = activated_roots()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !roots.contains(&self.root) {
+            roots.push(self.root.clone());
+        }
+    }
+
+    /// Executable directories of every installed toolchain
+    pub fn search_path(&self) -> Vec<PathBuf> {
+        self.installed()
+            .into_iter()
+            .map(|toolchain| toolchain.bin_dir)
+            .collect()
+    }
+
+    /// Perform a remediation
+    ///
+    /// # Returns
+    /// AsyncTask resolving to the installed toolchain, or an error if the
+    /// download, checksum verification, or installer failed
+    pub fn install(
+        &self,
+        remediation: Remediation,
+    ) -> AsyncTask<BackendResult<InstalledToolchain>> {
+        let manager = self.clone();
+        AsyncTaskBuilder::new(async move {
+            tokio::task::spawn_blocking(move || manager.install_blocking(&remediation))
+                .await
+                .map_err(|e| BackendError::Internal {
+                    message: format!("Toolchain installation task failed: {}", e),
+                })?
+        })
+        .spawn()
+    }
+
+    fn install_blocking(&self, remediation: &Remediation) -> BackendResult<InstalledToolchain> {
+        if !language::is_valid_version(&remediation.version) {
+            return Err(BackendError::InvalidConfig {
+                backend: "toolchains",
+                details: format!("invalid toolchain version '{}'", remediation.version),
+            });
+        }
+        info!(target: targets::TOOLCHAINS, "toolchains: {}", remediation);
+        let before = self.installed();
+        self.activate();
+
+        match &remediation.source {
+            ToolchainSource::Archive { url, sha256 } => {
+                self.install_archive(remediation, url, sha256)?
+            }
+            ToolchainSource::Fnm => self.install_fnm(&remediation.version)?,
+            ToolchainSource::Rustup => self.install_rustup(&remediation.version)?,
+        }
+
+        // Channels like `lts` or `stable` resolve to a concrete version, so
+        // prefer whatever toolchain the installer just added
+        let after = self.installed();
+        let language = remediation.language.as_str();
+        after
+            .iter()
+            .find(|toolchain| toolchain.language == language && !before.contains(toolchain))
+            .or_else(|| {
+                after.iter().find(|toolchain| {
+                    toolchain.language == language
+                        && version_matches(&toolchain.version, &remediation.version)
+                })
+            })
+            .cloned()
+            .ok_or_else(|| BackendError::Internal {
+                message: format!("{} finished but no toolchain was found", remediation),
+            })
+    }
+
+    fn install_archive(
+        &self,
+        remediation: &Remediation,
+        url: &str,
+        sha256: &str,
+    ) -> BackendResult<()> {
+        if url.is_empty() || sha256.is_empty() {
+            return Err(BackendError::InvalidConfig {
+                backend: "toolchains",
+                details: format!(
+                    "{} {} needs an archive URL and SHA-256 before it can be installed",
+                    remediation.language, remediation.version
+                ),
+            });
+        }
+
+        let target = self.root.join(&remediation.language).join(&remediation.version);
+        fs::create_dir_all(&target).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to create {}: {}", target.display(), e),
+        })?;
+        let archive = self.root.join(format!(
+            "{}-{}.tar.gz.partial",
+            remediation.language, remediation.version
+        ));
+
+        let result = download(url, &archive)
+            .and_then(|()| verify_sha256(&archive, sha256))
+            .and_then(|()| {
+                run(Command::new("tar").arg("-xzf").arg(&archive).arg("-C").arg(&target))
+            });
+        let _ = fs::remove_file(&archive);
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&target);
+        }
+        result
+    }
+
+    fn install_fnm(&self, version: &str) -> BackendResult<()> {
+        run(Command::new("fnm")
+            .arg("install")
+            .arg(version)
+            .env("FNM_DIR", self.fnm_dir()))
+    }
+
+    fn install_rustup(&self, version: &str) -> BackendResult<()> {
+        run(Command::new("rustup")
+            .args(["toolchain", "install", version, "--profile", "minimal"])
+            .env("RUSTUP_HOME", self.rustup_home())
+            .env("CARGO_HOME", self.root.join("cargo")))
+    }
+
+    fn fnm_dir(&self) -> PathBuf {
+        self.root.join("fnm")
+    }
+
+    fn rustup_home(&self) -> PathBuf {
+        self.root.join("rustup")
+    }
+}
+
+/// Target triple used in toolchain archive and directory names
+fn host_triple() -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "apple-darwin",
+        "windows" => "pc-windows-msvc",
+        _ => "unknown-linux-gnu",
+    };
+    format!("{}-{}", std::env::consts::ARCH, os)
+}
+
+fn subdirectories(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn download(url: &str, destination: &Path) -> BackendResult<()> {
+    run(Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location", "--output"])
+        .arg(destination)
+        .arg(url))
+    .map_err(|e| BackendError::NetworkFailed {
+        details: format!("Failed to download {}: {}", url, e),
+    })
+}
+
+/// Check a file against an expected lowercase hex SHA-256
+fn verify_sha256(path: &Path, expected: &str) -> BackendResult<()> {
+    let actual = sha256_file(path)?;
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(BackendError::ChecksumMismatch {
+            artifact: path.display().to_string(),
+            expected: expected.to_string(),
+            actual,
+        })
+    }
+}
+
+fn sha256_file(path: &Path) -> BackendResult<String> {
+    let mut file = fs::File::open(path).map_err(|e| BackendError::FileSystemFailed {
+        details: format!("Failed to open {}: {}", path.display(), e),
+    })?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| BackendError::FileSystemFailed {
+            details: format!("Failed to read {}: {}", path.display(), e),
+        })?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn run(command: &mut Command) -> BackendResult<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| BackendError::ProcessFailed {
+            details: format!("Failed to run {} (is it installed?): {}", program, e),
+        })?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(BackendError::ProcessFailed {
+            details: format!(
+                "{} exited with {}: {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_errors_map_to_remediations() {
+        let missing = BackendError::RuntimeVersionUnavailable {
+            backend: "LandLock",
+            language: "rust".to_string(),
+            requested: "1.78".to_string(),
+            installed: vec!["1.75.0".to_string()],
+        };
+        let offer = remediation_for(&missing, "stable").unwrap();
+        assert_eq!((offer.version.as_str(), &offer.source), ("1.78", &ToolchainSource::Rustup));
+
+        let unsupported = BackendError::UnsupportedLanguage {
+            backend: "LandLock",
+            language: "node@20".to_string(),
+        };
+        let offer = remediation_for(&unsupported, "lts").unwrap();
+        assert_eq!((offer.language.as_str(), offer.version.as_str()), ("javascript", "20"));
+
+        let python = BackendError::UnsupportedLanguage {
+            backend: "LandLock",
+            language: "python".to_string(),
+        };
+        assert!(remediation_for(&python, "3.12.4").is_none());
+        assert!(Remediation::python_standalone("3.12.4", "20240726", "").needs_checksum());
+        assert!(remediation_for(&BackendError::ExecutionTimeout { seconds: 1 }, "x").is_none());
+    }
+
+    #[test]
+    fn archive_checksum_is_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("archive.tar.gz");
+        fs::write(&archive, b"abc").unwrap();
+
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert!(verify_sha256(&archive, abc).is_ok());
+        assert!(matches!(
+            verify_sha256(&archive, &"0".repeat(64)),
+            Err(BackendError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn installed_toolchains_are_discovered() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = ToolchainManager::new(dir.path());
+        let node_bin = dir.path().join("fnm/node-versions/v20.11.0/installation/bin");
+        fs::create_dir_all(node_bin).unwrap();
+        fs::create_dir_all(dir.path().join("python/3.12.4/python/bin")).unwrap();
+
+        let installed = manager.installed();
+        assert_eq!(installed.len(), 2);
+        assert!(installed
+            .iter()
+            .any(|t| t.language == "javascript" && t.version == "20.11.0"));
+        assert_eq!(manager.search_path().len(), 2);
+        assert!(installed.iter().all(|t| t.bin_dir.starts_with(t.install_dir())));
+
+        manager.activate();
+        assert!(activated_toolchains().iter().any(|t| t.language == "python"));
+    }
+}
@@ -2,11 +2,12 @@ use std::path::PathBuf;
 use std::sync::mpsc;
 use std::thread;
 use tokio::runtime::Runtime;
-use log::{error, info};
+use tracing::{error, info};
 use watchexec::Watchexec;
 use watchexec_events::{Event, Tag, Source};
 
 use crate::error::StorageError;
+use crate::logging::targets;
 use crate::state::PipelineEvent;
 
 /// Process a watchexec event and send file change notifications
@@ -28,11 +29,16 @@ fn process_event(event: &Event, tx: &mpsc::Sender<PipelineEvent>, _base_path: &P
                 .map(|ft| format!("{:?}", ft))
                 .unwrap_or_else(|| "unknown".to_string());
             
-            info!("File changed: {} (type: {})", path.display(), type_str);
+            info!(
+                target: targets::PIPELINE,
+                "File changed: {} (type: {})",
+                path.display(),
+                type_str
+            );
             
             // Send specific file path that changed
             if let Err(e) = tx.send(PipelineEvent::FileChanged(path.clone())) {
-                error!("Failed to send file change event: {}", e);
+                error!(target: targets::PIPELINE, "Failed to send file change event: {}", e);
             }
         }
     }
@@ -40,7 +46,7 @@ fn process_event(event: &Event, tx: &mpsc::Sender<PipelineEvent>, _base_path: &P
 
 // Event-based file watcher using watchexec
 pub fn watch_directory(path: PathBuf, tx: mpsc::Sender<PipelineEvent>) -> Result<(), StorageError> {
-    info!("Starting file watcher for directory: {}", path.display());
+    info!(target: targets::PIPELINE, "Starting file watcher for directory: {}", path.display());
 
     // Clone the path and sender for the handler
     let path_to_watch = path.clone();
@@ -49,6 +55,7 @@ pub fn watch_directory(path: PathBuf, tx: mpsc::Sender<PipelineEvent>) -> Result
     // Start the watcher in a background thread
     thread::spawn(move || {
         info!(
+            target: targets::PIPELINE,
             "File watcher thread started for {}",
             path_to_watch.display()
         );
@@ -66,7 +73,10 @@ pub fn watch_directory(path: PathBuf, tx: mpsc::Sender<PipelineEvent>) -> Result
                         
                         // Handle shutdown signals
                         if action.signals().next().is_some() {
-                            info!("Received shutdown signal, stopping file watcher");
+                            info!(
+                                target: targets::PIPELINE,
+                                "Received shutdown signal, stopping file watcher"
+                            );
                             action.quit();
                         }
                         
@@ -77,22 +87,37 @@ pub fn watch_directory(path: PathBuf, tx: mpsc::Sender<PipelineEvent>) -> Result
                             wx.config.pathset([path_to_watch.clone()]);
                             
                             // Start watchexec main loop
-                            info!("Event-based file watcher started for {}", path_to_watch.display());
+                            info!(
+                                target: targets::PIPELINE,
+                                "Event-based file watcher started for {}",
+                                path_to_watch.display()
+                            );
                             match wx.main().await {
-                                Ok(_) => info!("File watcher completed successfully"),
-                                Err(e) => error!("File watcher error: {}", e),
+                                Ok(_) => info!(
+                                    target: targets::PIPELINE,
+                                    "File watcher completed successfully"
+                                ),
+                                Err(e) => error!(
+                                    target: targets::PIPELINE,
+                                    "File watcher error: {}",
+                                    e
+                                ),
                             }
                         }
-                        Err(e) => error!("Failed to initialize watchexec: {}", e),
+                        Err(e) => error!(
+                            target: targets::PIPELINE,
+                            "Failed to initialize watchexec: {}",
+                            e
+                        ),
                     }
                 });
             }
-            Err(e) => error!("Failed to create runtime: {}", e),
+            Err(e) => error!(target: targets::PIPELINE, "Failed to create runtime: {}", e),
         }
 
-        info!("File watcher thread exited");
+        info!(target: targets::PIPELINE, "File watcher thread exited");
     });
 
-    info!("File watcher initialized successfully");
+    info!(target: targets::PIPELINE, "File watcher initialized successfully");
     Ok(())
 }
@@ -1,24 +1,59 @@
-//! Native Windows ramdisk implementation using VHD and diskpart
+//! Native Windows ramdisk implementation
 //!
-//! This module provides production-quality ramdisk support for Windows using:
-//! - Virtual Hard Disk (VHD) files backed by system page file
-//! - diskpart.exe for disk management operations
-//! - Native Windows APIs for privilege detection and drive management
+//! This module provides ramdisk support for Windows through several providers:
+//! - ImDisk virtual disks, when the ImDisk driver is installed
+//! - ReFS Dev Drives on an attached VHD
+//! - Virtual Hard Disk (VHD) files attached and formatted with diskpart.exe
+//! - A temp directory mapped to the drive letter with `subst`, whose size
+//!   quota cylo enforces itself; the only provider that needs no
+//!   administrator privileges
+//!
+//! `RamdiskConfig::windows_provider` picks one provider or, with `Auto`, the
+//! first of them that is available and succeeds.
+//!
+//! Every mounted drive is recorded in a state file together with its
+//! provider, backing VHD or directory, and owning process, so it can be
+//! removed by a process other than the one that created it. Drives whose
+//! owner has exited are unmounted the next time a ramdisk is created or
+//! startup recovery runs.
 
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::io::Write;
+use std::time::{Duration, SystemTime};
 
-use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
 
-use crate::config::RamdiskConfig;
+use crate::config::{RamdiskConfig, RamdiskProviderStatus, WindowsRamdiskProvider};
 use crate::error::StorageError;
+use crate::hardening::{Helper, HelperCommand};
+use crate::logging::targets;
 use crate::platform::RamdiskPlatform;
+use crate::reaper::process_alive;
+
+/// Smallest Dev Drive Windows will format
+const DEV_DRIVE_MIN_GB: u64 = 50;
+
+/// How often the quota of a quota-enforced drive is checked
+const QUOTA_INTERVAL: Duration = Duration::from_secs(2);
 
-/// Windows ramdisk implementation using VHD files
+/// Concrete providers, in the order `Auto` tries them
+const PROVIDERS: [WindowsRamdiskProvider; 4] = [
+    WindowsRamdiskProvider::ImDisk,
+    WindowsRamdiskProvider::DevDrive,
+    WindowsRamdiskProvider::Vhd,
+    WindowsRamdiskProvider::TempDir,
+];
+
+/// Windows ramdisk implementation
 pub struct WindowsRamdisk {
+    /// Provider backing the ramdisk, once created
+    provider: Option<WindowsRamdiskProvider>,
     /// Path to the VHD file (stored in temp directory)
     vhd_path: Option<PathBuf>,
+    /// Directory mapped to the drive letter by the temp-directory provider
+    backing_dir: Option<PathBuf>,
     /// Assigned drive letter (e.g., 'Z')
     drive_letter: Option<char>,
 }
@@ -26,20 +61,22 @@ pub struct WindowsRamdisk {
 impl RamdiskPlatform for WindowsRamdisk {
     fn new() -> Self {
         Self {
+            provider: None,
             vhd_path: None,
+            backing_dir: None,
             drive_letter: None,
         }
     }
 
     fn create(&mut self, config: &RamdiskConfig) -> Result<(), StorageError> {
-        info!("Creating Windows ramdisk with VHD backend");
+        info!(
+            target: targets::RAMDISK,
+            "Creating Windows ramdisk with {} provider",
+            config.windows_provider
+        );
 
-        // Check for administrator privileges
-        if !check_admin_privileges()? {
-            return Err(StorageError::InsufficientPrivileges(
-                "Creating ramdisk requires administrator privileges. Please run as administrator.".into()
-            ));
-        }
+        // Free drive letters held by ramdisks of crashed processes first
+        reconcile_mounts();
 
         // Determine drive letter to use
         let drive_letter = extract_drive_letter(&config.mount_point)
@@ -48,49 +85,71 @@ impl RamdiskPlatform for WindowsRamdisk {
                 "No available drive letters and none specified in mount_point".into()
             ))?;
 
-        info!("Using drive letter: {}", drive_letter);
-
-        // Create VHD file in temp directory
-        let temp_vhd = std::env::temp_dir()
-            .join(format!("cylo_ramdisk_{}.vhd", uuid::Uuid::new_v4()));
-
-        info!("Creating VHD at: {}", temp_vhd.display());
-
-        // Calculate size in MB
-        let size_mb = config.size_gb * 1024;
-
-        // Create and execute diskpart script
-        let diskpart_commands = format!(
-            "create vdisk file=\"{}\" maximum={} type=expandable\n\
-             attach vdisk\n\
-             convert mbr\n\
-             create partition primary\n\
-             format fs=NTFS quick label=\"{}\"\n\
-             assign letter={}\n\
-             exit",
-            temp_vhd.display(),
-            size_mb,
-            config.volume_name,
-            drive_letter
-        );
+        info!(target: targets::RAMDISK, "Using drive letter: {}", drive_letter);
 
-        run_diskpart_script(&diskpart_commands)
-            .map_err(|e| StorageError::Other(anyhow::anyhow!(
-                "Failed to create VHD ramdisk: {}", e
-            )))?;
+        let candidates = config.windows_provider.candidates();
+        let mut failures = Vec::new();
+        for &provider in candidates {
+            let status = provider_status(provider);
+            if !status.available {
+                info!(
+                    target: targets::RAMDISK,
+                    "Skipping {} ramdisk provider: {}",
+                    provider,
+                    status.reason
+                );
+                failures.push(format!("{provider}: {}", status.reason));
+                continue;
+            }
 
-        // Store state
-        self.vhd_path = Some(temp_vhd);
-        self.drive_letter = Some(drive_letter);
+            match self.create_with(provider, config, drive_letter) {
+                Ok(()) => {
+                    self.provider = Some(provider);
+                    self.drive_letter = Some(drive_letter);
+                    MountState::default_location().record(MountRecord {
+                        drive_letter,
+                        provider,
+                        backing: self.backing(),
+                        owner_pid: std::process::id(),
+                    });
 
-        // Create cylo subdirectory in the new drive
-        let cylo_dir = PathBuf::from(format!("{}:\\cylo", drive_letter));
-        std::fs::create_dir_all(&cylo_dir)
-            .map_err(|e| StorageError::Io(e))?;
+                    // Create cylo subdirectory in the new drive
+                    let cylo_dir = PathBuf::from(format!("{}:\\cylo", drive_letter));
+                    std::fs::create_dir_all(&cylo_dir).map_err(StorageError::Io)?;
 
-        info!("Windows ramdisk created successfully on drive {}:", drive_letter);
+                    info!(
+                        target: targets::RAMDISK,
+                        "Windows ramdisk created successfully on drive {}: using {}",
+                        drive_letter,
+                        provider
+                    );
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!(
+                        target: targets::RAMDISK,
+                        "{} ramdisk provider failed: {}",
+                        provider,
+                        e
+                    );
+                    failures.push(format!("{provider}: {e}"));
+                }
+            }
+        }
 
-        Ok(())
+        let failures = failures.join("; ");
+        let admin = check_admin_privileges().unwrap_or(false);
+        if !admin && candidates.iter().all(|provider| provider.requires_admin()) {
+            Err(StorageError::InsufficientPrivileges(format!(
+                "Creating ramdisk requires administrator privileges. \
+                 Please run as administrator or use the TempDir provider ({failures})"
+            )))
+        } else {
+            Err(StorageError::Other(anyhow::anyhow!(
+                "No Windows ramdisk provider succeeded: {}",
+                failures
+            )))
+        }
     }
 
     fn is_mounted(&self, mount_point: &Path) -> Result<bool, StorageError> {
@@ -119,40 +178,674 @@ impl RamdiskPlatform for WindowsRamdisk {
     fn remove(&self, mount_point: &Path) -> Result<(), StorageError> {
         // Verify this is the correct mount point before removing
         if !self.is_mounted(mount_point)? {
-            warn!("Mount point {} is not mounted, skipping removal", mount_point.display());
+            warn!(
+                target: targets::RAMDISK,
+                "Mount point {} is not mounted, skipping removal",
+                mount_point.display()
+            );
+            return Ok(());
+        }
+
+        let Some(drive_letter) = self.drive_letter.or_else(|| extract_drive_letter(mount_point))
+        else {
             return Ok(());
+        };
+
+        // A fresh instance knows the drive from the state file; without a
+        // record, subst and ImDisk drives can be recognised and anything
+        // else is treated as a VHD
+        let state = MountState::default_location();
+        let record = state.find(drive_letter);
+        let provider = self
+            .provider
+            .or(record.as_ref().map(|record| record.provider))
+            .unwrap_or_else(|| {
+                if subst_target(drive_letter).is_some() {
+                    WindowsRamdiskProvider::TempDir
+                } else if is_imdisk_drive(drive_letter) {
+                    WindowsRamdiskProvider::ImDisk
+                } else {
+                    WindowsRamdiskProvider::Vhd
+                }
+            });
+        let backing = self
+            .backing()
+            .or(record.and_then(|record| record.backing))
+            .or_else(|| match provider {
+                WindowsRamdiskProvider::TempDir => subst_target(drive_letter),
+                _ => None,
+            });
+
+        teardown(provider, drive_letter, backing.as_deref())?;
+        state.forget(drive_letter);
+        Ok(())
+    }
+}
+
+impl WindowsRamdisk {
+    fn create_with(
+        &mut self,
+        provider: WindowsRamdiskProvider,
+        config: &RamdiskConfig,
+        drive_letter: char,
+    ) -> Result<(), StorageError> {
+        match provider {
+            WindowsRamdiskProvider::ImDisk => create_imdisk(config, drive_letter),
+            WindowsRamdiskProvider::DevDrive => {
+                let vhd_path = create_dev_drive(config, drive_letter)?;
+                self.vhd_path = Some(vhd_path);
+                Ok(())
+            }
+            WindowsRamdiskProvider::TempDir => {
+                let backing_dir = create_subst(config, drive_letter)?;
+                self.backing_dir = Some(backing_dir);
+                Ok(())
+            }
+            WindowsRamdiskProvider::Vhd | WindowsRamdiskProvider::Auto => {
+                let vhd_path = create_vhd(config, drive_letter)?;
+                self.vhd_path = Some(vhd_path);
+                Ok(())
+            }
+        }
+    }
+
+    /// VHD file or directory backing the created drive
+    fn backing(&self) -> Option<PathBuf> {
+        self.backing_dir.clone().or_else(|| self.vhd_path.clone())
+    }
+}
+
+/// Unmount a drive and delete what backed it
+///
+/// # Arguments
+/// * `provider` - Provider that created the drive
+/// * `drive_letter` - Drive to unmount
+/// * `backing` - VHD file or mapped directory, when known
+fn teardown(
+    provider: WindowsRamdiskProvider,
+    drive_letter: char,
+    backing: Option<&Path>,
+) -> Result<(), StorageError> {
+    match provider {
+        WindowsRamdiskProvider::ImDisk => remove_imdisk(drive_letter),
+        WindowsRamdiskProvider::TempDir => remove_subst(drive_letter, backing),
+        _ => {
+            match backing {
+                Some(vhd_path) => remove_vhd(vhd_path),
+                None => {
+                    warn!(target: targets::RAMDISK, "No VHD path stored - cannot remove ramdisk")
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn remove_vhd(vhd_path: &Path) {
+    info!(target: targets::RAMDISK, "Removing Windows ramdisk: {}", vhd_path.display());
+
+    // Detach VHD via diskpart
+    let diskpart_commands = format!(
+        "select vdisk file=\"{}\"\n\
+         detach vdisk\n\
+         exit",
+        vhd_path.display()
+    );
+
+    if let Err(e) = run_diskpart_script(&diskpart_commands) {
+        warn!(target: targets::RAMDISK, "Failed to detach VHD via diskpart: {}", e);
+        // Continue to file deletion attempt
+    }
+
+    // Delete the VHD file
+    if let Err(e) = std::fs::remove_file(vhd_path) {
+        warn!(target: targets::RAMDISK, "Failed to delete VHD file: {}", e);
+        // Non-fatal - file may be locked or already deleted
+    } else {
+        info!(target: targets::RAMDISK, "VHD file deleted successfully");
+    }
+}
+
+/// A mounted drive as recorded in the state file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct MountRecord {
+    drive_letter: char,
+    provider: WindowsRamdiskProvider,
+    /// VHD file or mapped directory; ImDisk drives have neither
+    #[serde(default)]
+    backing: Option<PathBuf>,
+    /// Process that created the drive
+    owner_pid: u32,
+}
+
+/// Drives mounted by cylo processes, persisted across restarts
+struct MountState {
+    path: PathBuf,
+}
+
+impl MountState {
+    fn default_location() -> Self {
+        Self {
+            path: std::env::temp_dir().join("cylo-ramdisk").join("mounts.json"),
         }
+    }
 
-        if let Some(ref vhd_path) = self.vhd_path {
-            info!("Removing Windows ramdisk: {}", vhd_path.display());
+    fn load(&self) -> Vec<MountRecord> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, records: &[MountRecord]) {
+        if records.is_empty() {
+            let _ = std::fs::remove_file(&self.path);
+            return;
+        }
 
-            // Detach VHD via diskpart
-            let diskpart_commands = format!(
-                "select vdisk file=\"{}\"\n\
-                 detach vdisk\n\
-                 exit",
-                vhd_path.display()
+        // Written aside and renamed so a crash never leaves half a file
+        let staging = self.path.with_extension("json.tmp");
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| {
+                let json = serde_json::to_string_pretty(records).map_err(std::io::Error::other)?;
+                std::fs::write(&staging, json)
+            })
+            .and_then(|_| std::fs::rename(&staging, &self.path));
+        if let Err(e) = result {
+            warn!(
+                target: targets::RAMDISK,
+                "Failed to write ramdisk state {}: {}",
+                self.path.display(),
+                e
             );
+        }
+    }
+
+    fn find(&self, drive_letter: char) -> Option<MountRecord> {
+        self.load()
+            .into_iter()
+            .find(|record| record.drive_letter.eq_ignore_ascii_case(&drive_letter))
+    }
+
+    /// Record a drive, replacing any earlier record of its letter
+    fn record(&self, record: MountRecord) {
+        let mut records = self.load();
+        records.retain(|r| !r.drive_letter.eq_ignore_ascii_case(&record.drive_letter));
+        records.push(record);
+        self.save(&records);
+    }
 
-            if let Err(e) = run_diskpart_script(&diskpart_commands) {
-                warn!("Failed to detach VHD via diskpart: {}", e);
-                // Continue to file deletion attempt
+    fn forget(&self, drive_letter: char) {
+        let mut records = self.load();
+        let before = records.len();
+        records.retain(|r| !r.drive_letter.eq_ignore_ascii_case(&drive_letter));
+        if records.len() != before {
+            self.save(&records);
+        }
+    }
+
+    /// Tear down drives whose owner has exited
+    ///
+    /// Records whose teardown fails are kept and retried next time.
+    ///
+    /// # Returns
+    /// Drives torn down
+    fn reconcile(
+        &self,
+        owner_alive: impl Fn(u32) -> bool,
+        teardown: impl Fn(&MountRecord) -> Result<(), StorageError>,
+    ) -> u32 {
+        let records = self.load();
+        if records.is_empty() {
+            return 0;
+        }
+
+        let mut reconciled = 0;
+        let mut kept = Vec::with_capacity(records.len());
+        for record in records {
+            if owner_alive(record.owner_pid) {
+                kept.push(record);
+                continue;
+            }
+            match teardown(&record) {
+                Ok(()) => {
+                    info!(
+                        target: targets::RAMDISK,
+                        "Removed {} ramdisk {}: left behind by exited process {}",
+                        record.provider,
+                        record.drive_letter,
+                        record.owner_pid
+                    );
+                    reconciled += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        target: targets::RAMDISK,
+                        "Failed to remove stale ramdisk {}: {}",
+                        record.drive_letter,
+                        e
+                    );
+                    kept.push(record);
+                }
+            }
+        }
+        self.save(&kept);
+        reconciled
+    }
+}
+
+/// Unmount ramdisks recorded by cylo processes that are no longer running
+///
+/// # Returns
+/// Number of drives removed
+pub fn reconcile_mounts() -> u32 {
+    MountState::default_location().reconcile(process_alive, |record| {
+        let still_mapped = match record.provider {
+            // The letter may have been reused since; only unmap it if it
+            // still points at the recorded drive
+            WindowsRamdiskProvider::TempDir => {
+                subst_target(record.drive_letter) == record.backing
+            }
+            WindowsRamdiskProvider::ImDisk => is_imdisk_drive(record.drive_letter),
+            _ => true,
+        };
+        if still_mapped {
+            teardown(record.provider, record.drive_letter, record.backing.as_deref())
+        } else {
+            if record.provider == WindowsRamdiskProvider::TempDir
+                && let Some(dir) = &record.backing
+            {
+                let _ = std::fs::remove_dir_all(dir);
             }
+            Ok(())
+        }
+    })
+}
 
-            // Delete the VHD file
-            if let Err(e) = std::fs::remove_file(vhd_path) {
-                warn!("Failed to delete VHD file: {}", e);
-                // Non-fatal - file may be locked or already deleted
+/// Process that owns the ramdisk backed by a VHD file, per the state file
+pub(crate) fn vhd_owner(vhd_path: &Path) -> Option<u32> {
+    MountState::default_location()
+        .load()
+        .into_iter()
+        .find(|record| record.backing.as_deref() == Some(vhd_path))
+        .map(|record| record.owner_pid)
+}
+
+impl WindowsRamdiskProvider {
+    fn requires_admin(self) -> bool {
+        !matches!(self, WindowsRamdiskProvider::TempDir)
+    }
+}
+
+/// Report which Windows ramdisk providers can be used on this host
+///
+/// # Returns
+/// One status per concrete provider, in the order `Auto` tries them
+pub fn provider_statuses() -> Vec<RamdiskProviderStatus> {
+    PROVIDERS.into_iter().map(provider_status).collect()
+}
+
+fn provider_status(provider: WindowsRamdiskProvider) -> RamdiskProviderStatus {
+    let status = |available: bool, reason: String| RamdiskProviderStatus {
+        provider,
+        available,
+        reason,
+    };
+    let admin = check_admin_privileges().unwrap_or(false);
+    let admin_required = || status(false, "requires administrator privileges".into());
+
+    match provider {
+        WindowsRamdiskProvider::ImDisk => match find_on_path("imdisk.exe") {
+            None => status(false, "imdisk.exe not found on PATH".into()),
+            Some(_) if !admin => admin_required(),
+            Some(path) => status(true, format!("ImDisk found at {}", path.display())),
+        },
+        WindowsRamdiskProvider::DevDrive if !admin => admin_required(),
+        WindowsRamdiskProvider::DevDrive => {
+            let supported = Command::new("fsutil")
+                .args(["devdrv", "query"])
+                .output()
+                .is_ok_and(|output| output.status.success());
+            if supported {
+                status(true, "Dev Drive is supported".into())
             } else {
-                info!("VHD file deleted successfully");
+                status(false, "Dev Drive is not supported or not enabled on this system".into())
             }
+        }
+        WindowsRamdiskProvider::Vhd if !admin => admin_required(),
+        WindowsRamdiskProvider::Vhd => status(true, "VHD attached with diskpart".into()),
+        WindowsRamdiskProvider::TempDir => {
+            status(true, "temp directory mapped with subst, quota enforced by cylo".into())
+        }
+        WindowsRamdiskProvider::Auto => match provider_statuses().into_iter().find(|s| s.available)
+        {
+            Some(first) => status(true, format!("{} would be used", first.provider)),
+            None => status(false, "no provider is available".into()),
+        },
+    }
+}
 
-            Ok(())
+/// Create a VHD-backed NTFS drive with diskpart
+///
+/// # Returns
+/// Path of the VHD file
+fn create_vhd(config: &RamdiskConfig, drive_letter: char) -> Result<PathBuf, StorageError> {
+    // Create VHD file in temp directory
+    let temp_vhd = std::env::temp_dir()
+        .join(format!("cylo_ramdisk_{}.vhd", uuid::Uuid::new_v4()));
+
+    info!(target: targets::RAMDISK, "Creating VHD at: {}", temp_vhd.display());
+
+    // Calculate size in MB
+    let size_mb = config.size_gb * 1024;
+
+    // Create and execute diskpart script
+    let diskpart_commands = format!(
+        "create vdisk file=\"{}\" maximum={} type=expandable\n\
+         attach vdisk\n\
+         convert mbr\n\
+         create partition primary\n\
+         format fs=NTFS quick label=\"{}\"\n\
+         assign letter={}\n\
+         exit",
+        temp_vhd.display(),
+        size_mb,
+        config.volume_name,
+        drive_letter
+    );
+
+    run_diskpart_script(&diskpart_commands)
+        .map_err(|e| StorageError::Other(anyhow::anyhow!(
+            "Failed to create VHD ramdisk: {}", e
+        )))?;
+
+    Ok(temp_vhd)
+}
+
+/// Create a ReFS Dev Drive on an expandable VHD
+///
+/// Dev Drives are at least 50 GB; a smaller configured size is enforced
+/// as a quota instead.
+///
+/// # Returns
+/// Path of the VHD file
+fn create_dev_drive(config: &RamdiskConfig, drive_letter: char) -> Result<PathBuf, StorageError> {
+    let temp_vhd = std::env::temp_dir()
+        .join(format!("cylo_ramdisk_{}.vhd", uuid::Uuid::new_v4()));
+    let size_mb = config.size_gb.max(DEV_DRIVE_MIN_GB) * 1024;
+
+    info!(target: targets::RAMDISK, "Creating Dev Drive VHD at: {}", temp_vhd.display());
+
+    let diskpart_commands = format!(
+        "create vdisk file=\"{}\" maximum={} type=expandable\n\
+         attach vdisk\n\
+         convert gpt\n\
+         create partition primary\n\
+         assign letter={}\n\
+         exit",
+        temp_vhd.display(),
+        size_mb,
+        drive_letter
+    );
+    run_diskpart_script(&diskpart_commands)
+        .map_err(|e| StorageError::Other(anyhow::anyhow!(
+            "Failed to create Dev Drive VHD: {}", e
+        )))?;
+
+    let format = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(format!(
+            "Format-Volume -DriveLetter {} -DevDrive -NewFileSystemLabel '{}' \
+             -Confirm:$false -Force",
+            drive_letter,
+            config.volume_name.replace('\'', "''")
+        ))
+        .helper_output(Helper::DiskTool)
+        .map_err(|e| StorageError::CommandFailed(format!("Failed to execute powershell: {}", e)));
+
+    let failure = match format {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => Some(StorageError::CommandFailed(format!(
+            "Format-Volume -DevDrive failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ))),
+        Err(e) => Some(e),
+    };
+    if let Some(e) = failure {
+        // Do not leave the unformatted VHD attached
+        let detach = format!(
+            "select vdisk file=\"{}\"\ndetach vdisk\nexit",
+            temp_vhd.display()
+        );
+        let _ = run_diskpart_script(&detach);
+        let _ = std::fs::remove_file(&temp_vhd);
+        return Err(e);
+    }
+
+    if config.size_gb < DEV_DRIVE_MIN_GB {
+        spawn_quota_enforcer(
+            PathBuf::from(format!("{}:\\", drive_letter)),
+            config.size_gb * 1024 * 1024 * 1024,
+        );
+    }
+    Ok(temp_vhd)
+}
+
+/// Create an ImDisk virtual disk formatted as NTFS
+fn create_imdisk(config: &RamdiskConfig, drive_letter: char) -> Result<(), StorageError> {
+    let output = Command::new("imdisk")
+        .args(["-a", "-s"])
+        .arg(format!("{}M", config.size_gb * 1024))
+        .arg("-m")
+        .arg(format!("{}:", drive_letter))
+        .arg("-p")
+        .arg(format!("/fs:ntfs /q /y /v:{}", config.volume_name))
+        .helper_output(Helper::DiskTool)
+        .map_err(|e| StorageError::CommandFailed(format!("Failed to execute imdisk: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(StorageError::CommandFailed(format!(
+            "imdisk failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    Ok(())
+}
+
+fn remove_imdisk(drive_letter: char) -> Result<(), StorageError> {
+    info!(target: targets::RAMDISK, "Removing ImDisk ramdisk on drive {}:", drive_letter);
+    let output = Command::new("imdisk")
+        .args(["-D", "-m"])
+        .arg(format!("{}:", drive_letter))
+        .helper_output(Helper::DiskTool)
+        .map_err(|e| StorageError::CommandFailed(format!("Failed to execute imdisk: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(StorageError::CommandFailed(format!(
+            "imdisk failed to remove drive {}: {}",
+            drive_letter,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    Ok(())
+}
+
+fn is_imdisk_drive(drive_letter: char) -> bool {
+    find_on_path("imdisk.exe").is_some()
+        && Command::new("imdisk")
+            .args(["-l", "-m"])
+            .arg(format!("{}:", drive_letter))
+            .output()
+            .is_ok_and(|output| output.status.success())
+}
+
+/// Map a fresh temp directory to the drive letter and enforce its quota
+///
+/// # Returns
+/// The mapped directory
+fn create_subst(config: &RamdiskConfig, drive_letter: char) -> Result<PathBuf, StorageError> {
+    let backing_dir = std::env::temp_dir()
+        .join(format!("cylo_ramdisk_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&backing_dir).map_err(StorageError::Io)?;
+
+    let output = Command::new("subst")
+        .arg(format!("{}:", drive_letter))
+        .arg(&backing_dir)
+        .output()
+        .map_err(|e| StorageError::CommandFailed(format!("Failed to execute subst: {}", e)));
+
+    match output {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => {
+            let _ = std::fs::remove_dir_all(&backing_dir);
+            return Err(StorageError::CommandFailed(format!(
+                "subst failed: {}",
+                String::from_utf8_lossy(&output.stdout)
+            )));
+        }
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&backing_dir);
+            return Err(e);
+        }
+    }
+
+    spawn_quota_enforcer(backing_dir.clone(), config.size_gb * 1024 * 1024 * 1024);
+    Ok(backing_dir)
+}
+
+fn remove_subst(drive_letter: char, backing_dir: Option<&Path>) -> Result<(), StorageError> {
+    info!(target: targets::RAMDISK, "Removing subst drive {}:", drive_letter);
+    let output = Command::new("subst")
+        .arg(format!("{}:", drive_letter))
+        .arg("/D")
+        .output()
+        .map_err(|e| StorageError::CommandFailed(format!("Failed to execute subst: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(StorageError::CommandFailed(format!(
+            "subst failed to remove drive {}: {}",
+            drive_letter,
+            String::from_utf8_lossy(&output.stdout)
+        )));
+    }
+
+    // Removing the directory also ends its quota enforcer
+    if let Some(dir) = backing_dir
+        && let Err(e) = std::fs::remove_dir_all(dir)
+    {
+        warn!(
+            target: targets::RAMDISK,
+            "Failed to delete ramdisk directory {}: {}",
+            dir.display(),
+            e
+        );
+    }
+    Ok(())
+}
+
+/// Directory a drive letter is mapped to with `subst`, if any
+fn subst_target(drive_letter: char) -> Option<PathBuf> {
+    let output = Command::new("subst").output().ok()?;
+    parse_subst_listing(&String::from_utf8_lossy(&output.stdout), drive_letter)
+}
+
+/// Find a drive's target in `subst` output, e.g. `Z:\: => C:\Temp\dir`
+fn parse_subst_listing(listing: &str, drive_letter: char) -> Option<PathBuf> {
+    listing.lines().find_map(|line| {
+        let (drive, target) = line.split_once("=>")?;
+        let drive = drive.trim();
+        (drive.len() >= 2
+            && drive.starts_with(drive_letter.to_ascii_uppercase())
+            && drive[1..].starts_with(':'))
+        .then(|| PathBuf::from(target.trim()))
+    })
+}
+
+/// Keep a directory within its size quota until it is removed
+///
+/// Files written last are deleted first when the directory grows past
+/// the quota, so older work in progress survives a runaway writer.
+fn spawn_quota_enforcer(dir: PathBuf, limit_bytes: u64) {
+    let spawned = std::thread::Builder::new()
+        .name("cylo-ramdisk-quota".into())
+        .spawn(move || {
+            while dir.exists() {
+                match enforce_quota(&dir, limit_bytes) {
+                    Ok(0) => {}
+                    Ok(freed) => warn!(
+                        target: targets::RAMDISK,
+                        "Ramdisk {} exceeded its {} byte quota; deleted {} bytes",
+                        dir.display(),
+                        limit_bytes,
+                        freed
+                    ),
+                    Err(e) => warn!(
+                        target: targets::RAMDISK,
+                        "Failed to enforce quota on {}: {}",
+                        dir.display(),
+                        e
+                    ),
+                }
+                std::thread::sleep(QUOTA_INTERVAL);
+            }
+        });
+    if let Err(e) = spawned {
+        warn!(target: targets::RAMDISK, "Failed to start ramdisk quota enforcer: {}", e);
+    }
+}
+
+/// Delete the newest files under `dir` until it fits within `limit_bytes`
+///
+/// # Returns
+/// Bytes freed
+fn enforce_quota(dir: &Path, limit_bytes: u64) -> std::io::Result<u64> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+    let mut used: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if used <= limit_bytes {
+        return Ok(0);
+    }
+
+    files.sort_by(|a, b| b.2.cmp(&a.2));
+    let mut freed = 0;
+    for (path, size, _) in files {
+        if used <= limit_bytes {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            used -= size;
+            freed += size;
+        }
+    }
+    Ok(freed)
+}
+
+fn collect_files(
+    dir: &Path,
+    files: &mut Vec<(PathBuf, u64, SystemTime)>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            collect_files(&entry.path(), files)?;
         } else {
-            warn!("No VHD path stored - cannot remove ramdisk");
-            Ok(())
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((entry.path(), metadata.len(), modified));
         }
     }
+    Ok(())
+}
+
+/// Find an executable in the directories on PATH
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
 }
 
 /// Execute a diskpart script with the given commands
@@ -175,13 +868,13 @@ fn run_diskpart_script(script: &str) -> Result<(), StorageError> {
             .map_err(StorageError::Io)?;
     }
 
-    info!("Running diskpart script: {}", script_path.display());
+    info!(target: targets::RAMDISK, "Running diskpart script: {}", script_path.display());
 
     // Execute diskpart
     let output = Command::new("diskpart")
         .arg("/s")
         .arg(&script_path)
-        .output()
+        .helper_output(Helper::DiskTool)
         .map_err(|e| StorageError::CommandFailed(
             format!("Failed to execute diskpart: {}", e)
         ))?;
@@ -198,7 +891,7 @@ fn run_diskpart_script(script: &str) -> Result<(), StorageError> {
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    info!("diskpart output: {}", stdout);
+    info!(target: targets::RAMDISK, "diskpart output: {}", stdout);
 
     Ok(())
 }
@@ -325,4 +1018,83 @@ mod tests {
         let path = Path::new("relative/path");
         assert_eq!(extract_drive_letter(path), None);
     }
+
+    #[test]
+    fn test_providers_fall_back_and_report_status() {
+        assert_eq!(WindowsRamdiskProvider::Auto.candidates(), &PROVIDERS);
+        assert_eq!(
+            WindowsRamdiskProvider::ImDisk.candidates(),
+            &[WindowsRamdiskProvider::ImDisk]
+        );
+        assert_eq!("devdrive".parse::<WindowsRamdiskProvider>().ok(), Some(
+            WindowsRamdiskProvider::DevDrive
+        ));
+
+        let statuses = provider_statuses();
+        assert_eq!(statuses.len(), PROVIDERS.len());
+        let temp_dir = statuses.last().unwrap();
+        assert_eq!(temp_dir.provider, WindowsRamdiskProvider::TempDir);
+        assert!(temp_dir.available);
+    }
+
+    #[test]
+    fn test_parse_subst_listing() {
+        let listing = "Y:\\: => C:\\other\r\nZ:\\: => C:\\Temp\\cylo_ramdisk_1\r\n";
+        assert_eq!(
+            parse_subst_listing(listing, 'z'),
+            Some(PathBuf::from("C:\\Temp\\cylo_ramdisk_1"))
+        );
+        assert_eq!(parse_subst_listing(listing, 'X'), None);
+    }
+
+    #[test]
+    fn test_mount_state_reconciles_dead_owners() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = MountState {
+            path: dir.path().join("mounts.json"),
+        };
+        let record = |drive_letter, owner_pid| MountRecord {
+            drive_letter,
+            provider: WindowsRamdiskProvider::Vhd,
+            backing: Some(dir.path().join(format!("{drive_letter}.vhd"))),
+            owner_pid,
+        };
+        state.record(record('X', 1));
+        state.record(record('Y', 2));
+        state.record(record('Z', 3));
+        state.record(record('Z', 4));
+        assert_eq!(state.find('z').map(|r| r.owner_pid), Some(4));
+
+        // Owner 1 is alive; 2 exited and tears down; 4 exited but fails
+        let reconciled = state.reconcile(
+            |pid| pid == 1,
+            |record| match record.owner_pid {
+                4 => Err(StorageError::CommandFailed("busy".into())),
+                _ => Ok(()),
+            },
+        );
+        assert_eq!(reconciled, 1);
+        let remaining: Vec<char> = state.load().iter().map(|r| r.drive_letter).collect();
+        assert_eq!(remaining, ['X', 'Z']);
+
+        state.forget('X');
+        state.forget('Z');
+        assert!(!state.path.exists());
+    }
+
+    #[test]
+    fn test_quota_deletes_newest_files_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.bin");
+        std::fs::write(&old, vec![0u8; 600]).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        let new = dir.path().join("nested").join("new.bin");
+        std::fs::write(&new, vec![0u8; 600]).unwrap();
+
+        assert_eq!(enforce_quota(dir.path(), 2000).unwrap(), 0);
+        assert_eq!(enforce_quota(dir.path(), 1000).unwrap(), 600);
+        assert!(old.exists());
+        assert!(!new.exists());
+    }
 }
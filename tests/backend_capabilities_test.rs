@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use cylo::{BackendCapabilities, IsolationLevel, platform::detect_platform};
+
+    #[test]
+    fn detected_backends_report_capabilities() {
+        for backend in &detect_platform().available_backends {
+            let capabilities = backend
+                .capabilities
+                .as_ref()
+                .unwrap_or_else(|| panic!("{} reports no capabilities", backend.name));
+            assert_eq!(
+                Some(capabilities.isolation),
+                IsolationLevel::of_backend(&backend.name),
+                "{} isolation",
+                backend.name
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn backend_instances_match_routing_descriptors() {
+        use cylo::{BackendConfig, ExecutionBackend, create_backend, execution_env::Cylo};
+
+        let jail = std::env::temp_dir().join(format!("cylo-caps-{}", std::process::id()));
+        let env = Cylo::LandLock(jail.display().to_string());
+        let backend = match create_backend(&env, BackendConfig::new("capabilities")) {
+            Ok(backend) => backend,
+            Err(e) => {
+                println!("Skipping: LandLock backend unavailable: {}", e);
+                return;
+            }
+        };
+
+        let capabilities = backend.capabilities();
+        assert_eq!(
+            Some(capabilities.clone()),
+            BackendCapabilities::of_backend("LandLock")
+        );
+        assert_eq!(capabilities.isolation, IsolationLevel::Namespace);
+        assert!(!capabilities.network_control);
+        let _ = std::fs::remove_dir_all(&jail);
+    }
+}
@@ -169,6 +169,8 @@ mod tests {
             volume_name: "exec_test".into(),
             #[cfg(target_os = "macos")]
             filesystem: FileSystem::APFS,
+            #[cfg(target_os = "windows")]
+            windows_provider: Default::default(),
         };
 
         // Create the watched directory